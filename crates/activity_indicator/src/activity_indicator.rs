@@ -21,6 +21,7 @@ actions!(activity_indicator, [ShowErrorMessage]);
 
 pub enum Event {
     ShowError { lsp_name: Arc<str>, error: String },
+    ShowGitError { title: String, error: String },
 }
 
 pub struct ActivityIndicator {
@@ -83,38 +84,33 @@ impl ActivityIndicator {
             }
         });
 
-        cx.subscribe(&this, move |_, _, event, cx| match event {
-            Event::ShowError { lsp_name, error } => {
-                let create_buffer = project.update(cx, |project, cx| project.create_buffer(cx));
-                let project = project.clone();
-                let error = error.clone();
-                let lsp_name = lsp_name.clone();
-                cx.spawn(|workspace, mut cx| async move {
-                    let buffer = create_buffer.await?;
-                    buffer.update(&mut cx, |buffer, cx| {
-                        buffer.edit(
-                            [(
-                                0..0,
-                                format!("Language server error: {}\n\n{}", lsp_name, error),
-                            )],
-                            None,
-                            cx,
-                        );
-                    })?;
-                    workspace.update(&mut cx, |workspace, cx| {
-                        workspace.add_item_to_active_pane(
-                            Box::new(cx.new_view(|cx| {
-                                Editor::for_buffer(buffer, Some(project.clone()), cx)
-                            })),
-                            None,
-                            cx,
-                        );
-                    })?;
+        cx.subscribe(&this, move |_, _, event, cx| {
+            let text = match event {
+                Event::ShowError { lsp_name, error } => {
+                    format!("Language server error: {}\n\n{}", lsp_name, error)
+                }
+                Event::ShowGitError { title, error } => format!("{} failed\n\n{}", title, error),
+            };
+            let create_buffer = project.update(cx, |project, cx| project.create_buffer(cx));
+            let project = project.clone();
+            cx.spawn(|workspace, mut cx| async move {
+                let buffer = create_buffer.await?;
+                buffer.update(&mut cx, |buffer, cx| {
+                    buffer.edit([(0..0, text)], None, cx);
+                })?;
+                workspace.update(&mut cx, |workspace, cx| {
+                    workspace.add_item_to_active_pane(
+                        Box::new(
+                            cx.new_view(|cx| Editor::for_buffer(buffer, Some(project.clone()), cx)),
+                        ),
+                        None,
+                        cx,
+                    );
+                })?;
 
-                    anyhow::Ok(())
-                })
-                .detach();
-            }
+                anyhow::Ok(())
+            })
+            .detach();
         })
         .detach();
         this
@@ -136,6 +132,20 @@ impl ActivityIndicator {
         cx.notify();
     }
 
+    fn show_git_error(&mut self, cx: &mut ViewContext<Self>) {
+        let failure = self.project.update(cx, |project, cx| {
+            let failure = project.last_git_operation_failure().cloned();
+            project.dismiss_git_operation_failure(cx);
+            failure
+        });
+        if let Some(failure) = failure {
+            cx.emit(Event::ShowGitError {
+                title: failure.title,
+                error: failure.error,
+            });
+        }
+    }
+
     fn dismiss_error_message(&mut self, _: &DismissErrorMessage, cx: &mut ViewContext<Self>) {
         if let Some(updater) = &self.auto_updater {
             updater.update(cx, |updater, cx| {
@@ -218,6 +228,34 @@ impl ActivityIndicator {
             };
         }
 
+        // Show any git operation running in the background.
+        if let Some(operation) = self.project.read(cx).git_operation() {
+            let mut message = format!("{}…", operation.title);
+            if let Some(progress) = &operation.progress {
+                write!(
+                    &mut message,
+                    " {} ({}%)",
+                    progress.stage, progress.percentage
+                )
+                .unwrap();
+            }
+
+            return Content {
+                icon: Some(
+                    Icon::new(IconName::ArrowCircle)
+                        .size(IconSize::Small)
+                        .with_animation(
+                            "arrow-circle",
+                            Animation::new(Duration::from_secs(2)).repeat(),
+                            |icon, delta| icon.transform(Transformation::rotate(percentage(delta))),
+                        )
+                        .into_any_element(),
+                ),
+                message,
+                on_click: None,
+            };
+        }
+
         // Show any language server installation info.
         let mut downloading = SmallVec::<[_; 3]>::new();
         let mut checking_for_update = SmallVec::<[_; 3]>::new();
@@ -292,6 +330,19 @@ impl ActivityIndicator {
             };
         }
 
+        // Show any git operation failure
+        if let Some(failure) = self.project.read(cx).last_git_operation_failure() {
+            return Content {
+                icon: Some(
+                    Icon::new(IconName::ExclamationTriangle)
+                        .size(IconSize::Small)
+                        .into_any_element(),
+                ),
+                message: format!("{} failed. Click to show error.", failure.title),
+                on_click: Some(Arc::new(|this, cx| this.show_git_error(cx))),
+            };
+        }
+
         // Show any application auto-update info.
         if let Some(updater) = &self.auto_updater {
             return match &updater.read(cx).status() {
@@ -75,17 +75,27 @@ impl SlashCommand for TermSlashCommand {
             return Task::ready(Err(anyhow::anyhow!("no active terminal")));
         };
 
-        let line_count = argument.and_then(|a| parse_argument(a)).unwrap_or(20);
-
-        let lines = active_terminal
-            .read(cx)
-            .model()
-            .read(cx)
-            .last_n_non_empty_lines(line_count);
+        let terminal = active_terminal.read(cx).model().read(cx);
+        let line_count = argument.and_then(|a| parse_argument(a));
 
+        // Without an explicit line count, prefer the output of the last command,
+        // which is known when the shell reports its commands through shell integration.
         let mut text = String::new();
-        text.push_str("Terminal output:\n");
-        text.push_str(&lines.join("\n"));
+        match line_count
+            .is_none()
+            .then(|| terminal.last_command_output())
+            .flatten()
+        {
+            Some(output) => {
+                text.push_str("Output of the last terminal command:\n");
+                text.push_str(&output);
+            }
+            None => {
+                let lines = terminal.last_n_non_empty_lines(line_count.unwrap_or(20));
+                text.push_str("Terminal output:\n");
+                text.push_str(&lines.join("\n"));
+            }
+        }
         let range = 0..text.len();
 
         Task::ready(Ok(SlashCommandOutput {
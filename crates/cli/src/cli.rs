@@ -15,6 +15,9 @@ pub enum CliRequest {
         open_new_workspace: Option<bool>,
         dev_server_token: Option<String>,
     },
+    /// Installs the already built extension at the given path as a dev extension,
+    /// or reloads it if it is installed.
+    ReloadDevExtension { path: String },
 }
 
 #[derive(Debug, Serialize, Deserialize)]
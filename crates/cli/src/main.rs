@@ -51,6 +51,10 @@ struct Args {
     /// Run zed in dev-server mode
     #[arg(long)]
     dev_server_token: Option<String>,
+    /// Install the already built extension at the given path as a dev extension,
+    /// or reload it if it is already installed.
+    #[arg(long, value_name = "PATH")]
+    reload_dev_extension: Option<PathBuf>,
 }
 
 fn parse_path_with_position(
@@ -125,6 +129,21 @@ fn main() -> Result<()> {
         None
     };
 
+    let request = if let Some(extension_path) = args.reload_dev_extension {
+        let extension_path = fs::canonicalize(&extension_path)
+            .with_context(|| format!("extension not found at {}", extension_path.display()))?;
+        CliRequest::ReloadDevExtension {
+            path: extension_path.display().to_string(),
+        }
+    } else {
+        CliRequest::Open {
+            paths,
+            wait: args.wait,
+            open_new_workspace,
+            dev_server_token: args.dev_server_token,
+        }
+    };
+
     let exit_status = Arc::new(Mutex::new(None));
 
     let sender: JoinHandle<anyhow::Result<()>> = thread::spawn({
@@ -132,12 +151,7 @@ fn main() -> Result<()> {
         move || {
             let (_, handshake) = server.accept().context("Handshake after Zed spawn")?;
             let (tx, rx) = (handshake.requests, handshake.responses);
-            tx.send(CliRequest::Open {
-                paths,
-                wait: args.wait,
-                open_new_workspace,
-                dev_server_token: args.dev_server_token,
-            })?;
+            tx.send(request)?;
 
             while let Ok(response) = rx.recv() {
                 match response {
@@ -376,6 +376,7 @@ CREATE TABLE extension_versions (
     description TEXT NOT NULL,
     schema_version INTEGER NOT NULL DEFAULT 0,
     wasm_api_version TEXT,
+    changelog TEXT,
     download_count INTEGER NOT NULL DEFAULT 0,
     PRIMARY KEY (extension_id, version)
 );
@@ -366,6 +366,7 @@ async fn fetch_extension_manifest(
         repository: manifest.repository,
         schema_version: manifest.schema_version.unwrap_or(0),
         wasm_api_version: manifest.wasm_api_version,
+        changelog: manifest.changelog,
         published_at,
     })
 }
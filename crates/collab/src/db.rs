@@ -816,6 +816,7 @@ pub struct NewExtensionVersion {
     pub repository: String,
     pub schema_version: i32,
     pub wasm_api_version: Option<String>,
+    pub changelog: Option<String>,
     pub published_at: PrimitiveDateTime,
 }
 
@@ -1,7 +1,7 @@
+use std::cmp::Reverse;
 use std::str::FromStr;
 
 use chrono::Utc;
-use sea_orm::sea_query::IntoCondition;
 use util::ResultExt;
 
 use super::*;
@@ -112,17 +112,32 @@ impl Database {
         Ok(max_versions)
     }
 
-    /// Returns all of the versions for the extension with the given ID.
+    /// Returns all of the versions for the extension with the given ID, newest first.
     pub async fn get_extension_versions(
         &self,
         extension_id: &str,
     ) -> Result<Vec<ExtensionMetadata>> {
         self.transaction(|tx| async move {
-            let condition = extension::Column::ExternalId
-                .eq(extension_id)
-                .into_condition();
+            let Some(extension) = extension::Entity::find()
+                .filter(extension::Column::ExternalId.eq(extension_id))
+                .one(&*tx)
+                .await?
+            else {
+                return Ok(Vec::new());
+            };
 
-            self.get_extensions_where(condition, None, &tx).await
+            let mut versions = extension_version::Entity::find()
+                .filter(extension_version::Column::ExtensionId.eq(extension.id))
+                .all(&*tx)
+                .await?;
+            versions.sort_unstable_by_key(|version| {
+                Reverse(SemanticVersion::from_str(&version.version).ok())
+            });
+
+            Ok(versions
+                .into_iter()
+                .map(|version| metadata_from_extension_and_version(extension.clone(), version))
+                .collect())
         })
         .await
     }
@@ -282,6 +297,7 @@ impl Database {
                         description: ActiveValue::Set(version.description.clone()),
                         schema_version: ActiveValue::Set(version.schema_version),
                         wasm_api_version: ActiveValue::Set(version.wasm_api_version.clone()),
+                        changelog: ActiveValue::Set(version.changelog.clone()),
                         download_count: ActiveValue::NotSet,
                     }
                 }))
@@ -370,6 +386,7 @@ fn metadata_from_extension_and_version(
             repository: version.repository,
             schema_version: Some(version.schema_version),
             wasm_api_version: version.wasm_api_version,
+            changelog: version.changelog,
         },
 
         published_at: convert_time_to_chrono(version.published_at),
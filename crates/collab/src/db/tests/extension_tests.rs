@@ -37,6 +37,7 @@ async fn test_extensions(db: &Arc<Database>) {
                         repository: "ext1/repo".into(),
                         schema_version: 1,
                         wasm_api_version: None,
+                        changelog: None,
                         published_at: t0,
                     },
                     NewExtensionVersion {
@@ -47,6 +48,7 @@ async fn test_extensions(db: &Arc<Database>) {
                         repository: "ext1/repo".into(),
                         schema_version: 1,
                         wasm_api_version: None,
+                        changelog: None,
                         published_at: t0,
                     },
                 ],
@@ -61,6 +63,7 @@ async fn test_extensions(db: &Arc<Database>) {
                     repository: "ext2/repo".into(),
                     schema_version: 0,
                     wasm_api_version: None,
+                    changelog: None,
                     published_at: t0,
                 }],
             ),
@@ -97,6 +100,7 @@ async fn test_extensions(db: &Arc<Database>) {
                     repository: "ext1/repo".into(),
                     schema_version: Some(1),
                     wasm_api_version: None,
+                    changelog: None,
                 },
                 published_at: t0_chrono,
                 download_count: 0,
@@ -111,6 +115,7 @@ async fn test_extensions(db: &Arc<Database>) {
                     repository: "ext2/repo".into(),
                     schema_version: Some(0),
                     wasm_api_version: None,
+                    changelog: None,
                 },
                 published_at: t0_chrono,
                 download_count: 0
@@ -132,6 +137,7 @@ async fn test_extensions(db: &Arc<Database>) {
                 repository: "ext2/repo".into(),
                 schema_version: Some(0),
                 wasm_api_version: None,
+                changelog: None,
             },
             published_at: t0_chrono,
             download_count: 0
@@ -172,6 +178,7 @@ async fn test_extensions(db: &Arc<Database>) {
                     repository: "ext2/repo".into(),
                     schema_version: Some(0),
                     wasm_api_version: None,
+                    changelog: None,
                 },
                 published_at: t0_chrono,
                 download_count: 7
@@ -186,6 +193,7 @@ async fn test_extensions(db: &Arc<Database>) {
                     repository: "ext1/repo".into(),
                     schema_version: Some(1),
                     wasm_api_version: None,
+                    changelog: None,
                 },
                 published_at: t0_chrono,
                 download_count: 5,
@@ -207,6 +215,7 @@ async fn test_extensions(db: &Arc<Database>) {
                     repository: "ext1/repo".into(),
                     schema_version: 1,
                     wasm_api_version: None,
+                    changelog: None,
                     published_at: t0,
                 }],
             ),
@@ -220,6 +229,7 @@ async fn test_extensions(db: &Arc<Database>) {
                     repository: "ext2/repo".into(),
                     schema_version: 0,
                     wasm_api_version: None,
+                    changelog: None,
                     published_at: t0,
                 }],
             ),
@@ -258,6 +268,7 @@ async fn test_extensions(db: &Arc<Database>) {
                     repository: "ext2/repo".into(),
                     schema_version: Some(0),
                     wasm_api_version: None,
+                    changelog: None,
                 },
                 published_at: t0_chrono,
                 download_count: 7
@@ -272,6 +283,7 @@ async fn test_extensions(db: &Arc<Database>) {
                     repository: "ext1/repo".into(),
                     schema_version: Some(1),
                     wasm_api_version: None,
+                    changelog: None,
                 },
                 published_at: t0_chrono,
                 download_count: 5,
@@ -311,6 +323,7 @@ async fn test_extensions_by_id(db: &Arc<Database>) {
                         repository: "ext1/repo".into(),
                         schema_version: 1,
                         wasm_api_version: Some("0.0.4".into()),
+                        changelog: None,
                         published_at: t0,
                     },
                     NewExtensionVersion {
@@ -321,6 +334,7 @@ async fn test_extensions_by_id(db: &Arc<Database>) {
                         repository: "ext1/repo".into(),
                         schema_version: 1,
                         wasm_api_version: Some("0.0.4".into()),
+                        changelog: Some("## 0.0.2\n\n- Fixes\n".into()),
                         published_at: t0,
                     },
                     NewExtensionVersion {
@@ -331,6 +345,7 @@ async fn test_extensions_by_id(db: &Arc<Database>) {
                         repository: "ext1/repo".into(),
                         schema_version: 1,
                         wasm_api_version: Some("0.0.5".into()),
+                        changelog: Some("## 0.0.3\n\n- Features\n\n## 0.0.2\n\n- Fixes\n".into()),
                         published_at: t0,
                     },
                 ],
@@ -345,6 +360,7 @@ async fn test_extensions_by_id(db: &Arc<Database>) {
                     repository: "ext2/repo".into(),
                     schema_version: 0,
                     wasm_api_version: None,
+                    changelog: None,
                     published_at: t0,
                 }],
             ),
@@ -378,9 +394,30 @@ async fn test_extensions_by_id(db: &Arc<Database>) {
                 repository: "ext1/repo".into(),
                 schema_version: Some(1),
                 wasm_api_version: Some("0.0.4".into()),
+                changelog: Some("## 0.0.2\n\n- Fixes\n".into()),
             },
             published_at: t0_chrono,
             download_count: 0,
         }]
     );
+
+    // All versions of an extension are returned, newest first.
+    let versions = db.get_extension_versions("ext1").await.unwrap();
+    assert_eq!(
+        versions
+            .iter()
+            .map(|extension| (
+                extension.manifest.version.as_ref(),
+                extension.manifest.changelog.as_deref()
+            ))
+            .collect::<Vec<_>>(),
+        &[
+            (
+                "0.0.3",
+                Some("## 0.0.3\n\n- Features\n\n## 0.0.2\n\n- Fixes\n")
+            ),
+            ("0.0.2", Some("## 0.0.2\n\n- Fixes\n")),
+            ("0.0.1", None),
+        ]
+    );
 }
@@ -485,6 +485,33 @@ impl Server {
             .add_request_handler(user_handler(
                 forward_read_only_project_request::<proto::InlayHints>,
             ))
+            .add_request_handler(user_handler(
+                forward_read_only_project_request::<proto::GetSemanticTokens>,
+            ))
+            .add_request_handler(user_handler(
+                forward_read_only_project_request::<proto::PrepareCallHierarchy>,
+            ))
+            .add_request_handler(user_handler(
+                forward_read_only_project_request::<proto::CallHierarchyIncomingCalls>,
+            ))
+            .add_request_handler(user_handler(
+                forward_read_only_project_request::<proto::CallHierarchyOutgoingCalls>,
+            ))
+            .add_request_handler(user_handler(
+                forward_read_only_project_request::<proto::PrepareTypeHierarchy>,
+            ))
+            .add_request_handler(user_handler(
+                forward_read_only_project_request::<proto::TypeHierarchySupertypes>,
+            ))
+            .add_request_handler(user_handler(
+                forward_read_only_project_request::<proto::TypeHierarchySubtypes>,
+            ))
+            .add_request_handler(user_handler(
+                forward_read_only_project_request::<proto::GetCodeLens>,
+            ))
+            .add_request_handler(user_handler(
+                forward_mutating_project_request::<proto::ExecuteCodeLens>,
+            ))
             .add_request_handler(user_handler(
                 forward_read_only_project_request::<proto::OpenBufferByPath>,
             ))
@@ -551,9 +578,40 @@ impl Server {
             .add_request_handler(user_handler(
                 forward_mutating_project_request::<proto::LinkedEditingRange>,
             ))
+            .add_request_handler(user_handler(
+                forward_read_only_project_request::<proto::GetGitStatus>,
+            ))
+            .add_request_handler(user_handler(
+                forward_mutating_project_request::<proto::StageGitPaths>,
+            ))
+            .add_request_handler(user_handler(
+                forward_mutating_project_request::<proto::UnstageGitPaths>,
+            ))
+            .add_request_handler(user_handler(
+                forward_mutating_project_request::<proto::StageGitHunk>,
+            ))
+            .add_request_handler(user_handler(
+                forward_mutating_project_request::<proto::UnstageGitHunk>,
+            ))
+            .add_request_handler(user_handler(
+                forward_mutating_project_request::<proto::GitCommit>,
+            ))
+            .add_request_handler(user_handler(
+                forward_read_only_project_request::<proto::GetGitChangedPaths>,
+            ))
+            .add_request_handler(user_handler(
+                forward_read_only_project_request::<proto::GetGitLog>,
+            ))
+            .add_request_handler(user_handler(
+                forward_read_only_project_request::<proto::GetGitCommitChanges>,
+            ))
             .add_message_handler(create_buffer_for_peer)
             .add_request_handler(update_buffer)
             .add_message_handler(broadcast_project_message_from_host::<proto::RefreshInlayHints>)
+            .add_message_handler(
+                broadcast_project_message_from_host::<proto::RefreshSemanticTokens>,
+            )
+            .add_message_handler(broadcast_project_message_from_host::<proto::RefreshCodeLens>)
             .add_message_handler(broadcast_project_message_from_host::<proto::UpdateBufferFile>)
             .add_message_handler(broadcast_project_message_from_host::<proto::BufferReloaded>)
             .add_message_handler(broadcast_project_message_from_host::<proto::BufferSaved>)
@@ -0,0 +1,210 @@
+use std::{collections::BTreeMap, mem, time::Duration};
+
+use collections::{HashMap, HashSet};
+use gpui::{
+    div, InteractiveElement, IntoElement, Model, ParentElement, StatefulInteractiveElement, Styled,
+    ViewContext, WeakView,
+};
+use language::{language_settings::language_settings, Buffer, ToPoint as _};
+use project::CodeLens;
+use ui::{h_flex, ActiveTheme, Color, Label, LabelCommon, LabelSize};
+
+use crate::{
+    display_map::{
+        BlockContext, BlockDisposition, BlockId, BlockProperties, BlockStyle, RenderBlock,
+    },
+    lsp_buffer_cache::{refresh_lsp_buffer_cache, LspBufferCache, LspBufferRequest},
+    Anchor, Editor, ExcerptId,
+};
+
+/// Code lenses queried from the language servers for every buffer shown in the editor,
+/// along with the blocks displaying them.
+#[derive(Default)]
+pub(super) struct CodeLensCache {
+    lenses: LspBufferCache<CodeLens>,
+    /// The blocks showing the lenses, along with the positions they were inserted at.
+    blocks: HashMap<BlockId, Anchor>,
+}
+
+impl CodeLensCache {
+    /// Marks all cached lenses as stale, so that the next refresh queries them again.
+    pub(super) fn invalidate(&mut self) {
+        self.lenses.invalidate();
+    }
+}
+
+const CODE_LENS_REQUEST: LspBufferRequest<CodeLens> = LspBufferRequest {
+    cache: |editor| &mut editor.code_lens_cache.lenses,
+    enabled: |buffer, cx| language_settings(buffer.language(), buffer.file(), cx).code_lens,
+    query: |project, buffer, cx| project.code_lens(buffer, cx),
+    update: update_code_lens_blocks,
+    debounce_timeout: Duration::from_millis(250),
+};
+
+/// Queries code lenses for all buffers whose cached lenses are missing or stale, and updates the blocks afterwards.
+pub(super) fn refresh_code_lens(editor: &mut Editor, debounce: bool, cx: &mut ViewContext<Editor>) {
+    refresh_lsp_buffer_cache(editor, &CODE_LENS_REQUEST, debounce, cx);
+}
+
+/// Updates the code lens blocks to show the cached lenses of every excerpt.
+///
+/// Lenses starting on the same line are shown together, in a single block above that line.
+/// The blocks of lines that still have lenses are only re-rendered, so refreshes don't shift the text.
+pub(super) fn update_code_lens_blocks(editor: &mut Editor, cx: &mut ViewContext<Editor>) {
+    let snapshot = editor.buffer.read(cx).snapshot(cx);
+    let mut lenses_by_row =
+        BTreeMap::<(ExcerptId, u32), (Anchor, Model<Buffer>, Vec<CodeLens>)>::new();
+    for (excerpt_id, buffer_snapshot, excerpt_range) in snapshot.excerpts() {
+        let buffer_id = buffer_snapshot.remote_id();
+        let Some(lenses) = editor.code_lens_cache.lenses.get(buffer_id) else {
+            continue;
+        };
+        let Some(buffer) = editor.buffer.read(cx).buffer(buffer_id) else {
+            continue;
+        };
+
+        let excerpt_range = excerpt_range.context;
+        for lens in lenses.iter() {
+            let start = lens.range.start;
+            if start.cmp(&excerpt_range.start, buffer_snapshot).is_lt()
+                || start.cmp(&excerpt_range.end, buffer_snapshot).is_gt()
+            {
+                continue;
+            }
+            let Some(position) = snapshot.anchor_in_excerpt(excerpt_id, start) else {
+                continue;
+            };
+            lenses_by_row
+                .entry((excerpt_id, start.to_point(buffer_snapshot).row))
+                .or_insert_with(|| (position, buffer.clone(), Vec::new()))
+                .2
+                .push(lens.clone());
+        }
+    }
+
+    let editor_handle = cx.view().downgrade();
+    let mut renderers = HashMap::default();
+    let mut removed_block_ids = HashSet::default();
+    for (block_id, position) in mem::take(&mut editor.code_lens_cache.blocks) {
+        let Some(buffer_position) = snapshot
+            .buffer_for_excerpt(position.excerpt_id)
+            .map(|buffer_snapshot| position.text_anchor.to_point(buffer_snapshot))
+        else {
+            removed_block_ids.insert(block_id);
+            continue;
+        };
+        match lenses_by_row.remove(&(position.excerpt_id, buffer_position.row)) {
+            Some((_, buffer, lenses)) => {
+                let render = render_code_lens_block(editor_handle.clone(), buffer, lenses);
+                renderers.insert(block_id, (None, render));
+                editor.code_lens_cache.blocks.insert(block_id, position);
+            }
+            None => {
+                removed_block_ids.insert(block_id);
+            }
+        }
+    }
+    if !removed_block_ids.is_empty() {
+        editor.remove_blocks(removed_block_ids, None, cx);
+    }
+    if !renderers.is_empty() {
+        editor.replace_blocks(renderers, None, cx);
+    }
+
+    let (positions, blocks): (Vec<_>, Vec<_>) = lenses_by_row
+        .into_values()
+        .map(|(position, buffer, lenses)| {
+            let block = BlockProperties {
+                position,
+                height: 1,
+                style: BlockStyle::Flex,
+                disposition: BlockDisposition::Above,
+                render: render_code_lens_block(editor_handle.clone(), buffer, lenses),
+            };
+            (position, block)
+        })
+        .unzip();
+    if !blocks.is_empty() {
+        let block_ids = editor.insert_blocks(blocks, None, cx);
+        editor
+            .code_lens_cache
+            .blocks
+            .extend(block_ids.into_iter().zip(positions));
+    }
+}
+
+fn render_code_lens_block(
+    editor: WeakView<Editor>,
+    buffer: Model<Buffer>,
+    lenses: Vec<CodeLens>,
+) -> RenderBlock {
+    Box::new(move |cx| render_code_lenses(&editor, &buffer, &lenses, cx).into_any_element())
+}
+
+fn render_code_lenses(
+    editor: &WeakView<Editor>,
+    buffer: &Model<Buffer>,
+    lenses: &[CodeLens],
+    cx: &mut BlockContext,
+) -> impl IntoElement {
+    h_flex()
+        .id(cx.block_id)
+        .h(cx.line_height)
+        .pl(cx.anchor_x)
+        .gap_2()
+        .children(lenses.iter().enumerate().map(|(ix, lens)| {
+            let title = lens
+                .lsp_lens
+                .command
+                .as_ref()
+                .map(|command| command.title.clone())
+                .unwrap_or_default();
+            let hover_color = cx.theme().colors().text;
+            div()
+                .id(ix)
+                .cursor_pointer()
+                .hover(move |style| style.text_color(hover_color))
+                .child(Label::new(title).size(LabelSize::Small).color(Color::Muted))
+                .on_click({
+                    let editor = editor.clone();
+                    let buffer = buffer.clone();
+                    let lens = lens.clone();
+                    move |_, cx| {
+                        editor
+                            .update(cx, |editor, cx| {
+                                editor.execute_code_lens(buffer.clone(), lens.clone(), cx)
+                            })
+                            .ok();
+                    }
+                })
+        }))
+}
+
+impl Editor {
+    /// Runs the command of the code lens, opening the edits it produced, if any.
+    pub fn execute_code_lens(
+        &mut self,
+        buffer: Model<Buffer>,
+        lens: CodeLens,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let (Some(project), Some(workspace)) = (self.project.clone(), self.workspace()) else {
+            return;
+        };
+        let title = lens
+            .lsp_lens
+            .command
+            .as_ref()
+            .map(|command| command.title.clone())
+            .unwrap_or_default();
+        let execute_code_lens = project.update(cx, |project, cx| {
+            project.execute_code_lens(buffer, lens, cx)
+        });
+        let workspace = workspace.downgrade();
+        cx.spawn(|editor, cx| async move {
+            let project_transaction = execute_code_lens.await?;
+            Self::open_project_transaction(&editor, workspace, project_transaction, title, cx).await
+        })
+        .detach_and_log_err(cx);
+    }
+}
@@ -0,0 +1,369 @@
+use std::{sync::Arc, time::Duration};
+
+use clock::Global;
+use collections::{HashMap, HashSet};
+use git::{
+    conflict::{parse_conflicts, Conflict, ConflictResolution},
+    repository::GitFileStatus,
+};
+use gpui::{
+    AppContext, InteractiveElement, IntoElement, ParentElement, Styled, Task, ViewContext, WeakView,
+};
+use language::Buffer;
+use multi_buffer::{ExcerptId, MultiBufferSnapshot, ToPoint as _};
+use text::{BufferId, Point};
+use ui::{
+    h_flex, ActiveTheme, Button, ButtonCommon, ButtonStyle, Clickable, FluentBuilder, LabelSize,
+};
+use util::ResultExt;
+
+use crate::{
+    display_map::{BlockContext, BlockDisposition, BlockId, BlockProperties, BlockStyle},
+    scroll::Autoscroll,
+    Editor, EditorMode, GoToConflict, GoToPrevConflict,
+};
+
+const CONFLICTS_DEBOUNCE_TIMEOUT: Duration = Duration::from_millis(250);
+
+/// Merge conflicts parsed from every buffer shown in the editor whose file git reports as
+/// conflicted, along with the blocks offering to resolve them.
+#[derive(Default)]
+pub(super) struct ConflictCache {
+    buffers: HashMap<BufferId, CachedBufferConflicts>,
+    block_ids: HashSet<BlockId>,
+    refresh_task: Option<Task<Option<()>>>,
+}
+
+struct CachedBufferConflicts {
+    /// Version of the buffer the conflicts were parsed from.
+    version: Global,
+    conflicts: Arc<[Conflict]>,
+}
+
+/// Row highlight of our side of a conflict, including its `<<<<<<<` line.
+enum ConflictOursHighlight {}
+/// Row highlight of their side of a conflict, including its `=======` and `>>>>>>>` lines.
+enum ConflictTheirsHighlight {}
+
+fn is_conflicted(buffer: &Buffer, cx: &AppContext) -> bool {
+    project::File::from_dyn(buffer.file()).map_or(false, |file| {
+        file.worktree
+            .read(cx)
+            .entry_for_path(&file.path)
+            .map_or(false, |entry| {
+                entry.git_status == Some(GitFileStatus::Conflict)
+            })
+    })
+}
+
+/// Parses the conflicts of all conflicted buffers whose cached conflicts are missing or stale,
+/// and updates the highlights and blocks afterwards.
+pub(super) fn refresh_conflicts(editor: &mut Editor, debounce: bool, cx: &mut ViewContext<Editor>) {
+    if editor.mode != EditorMode::Full {
+        return;
+    }
+
+    let buffers = editor.buffer.read(cx).all_buffers();
+    let conflicted_buffers = buffers
+        .into_iter()
+        .filter(|buffer| is_conflicted(buffer.read(cx), cx))
+        .collect::<Vec<_>>();
+    let conflicted_buffer_ids = conflicted_buffers
+        .iter()
+        .map(|buffer| buffer.read(cx).remote_id())
+        .collect::<Vec<_>>();
+    editor
+        .conflict_cache
+        .buffers
+        .retain(|buffer_id, _| conflicted_buffer_ids.contains(buffer_id));
+
+    let snapshots_to_parse = conflicted_buffers
+        .into_iter()
+        .filter_map(|buffer| {
+            let buffer = buffer.read(cx);
+            let needs_refresh = editor
+                .conflict_cache
+                .buffers
+                .get(&buffer.remote_id())
+                .map_or(true, |cached| {
+                    buffer.version().changed_since(&cached.version)
+                });
+            needs_refresh.then(|| buffer.text_snapshot())
+        })
+        .collect::<Vec<_>>();
+    if snapshots_to_parse.is_empty() {
+        editor.conflict_cache.refresh_task = None;
+        update_conflict_blocks(editor, cx);
+        return;
+    }
+
+    editor.conflict_cache.refresh_task = Some(cx.spawn(|editor, mut cx| async move {
+        if debounce {
+            cx.background_executor()
+                .timer(CONFLICTS_DEBOUNCE_TIMEOUT)
+                .await;
+        }
+
+        let parsed = cx
+            .background_executor()
+            .spawn(async move {
+                snapshots_to_parse
+                    .into_iter()
+                    .map(|snapshot| {
+                        let conflicts = parse_conflicts(&snapshot);
+                        (snapshot.remote_id(), snapshot.version().clone(), conflicts)
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .await;
+
+        editor
+            .update(&mut cx, |editor, cx| {
+                for (buffer_id, version, conflicts) in parsed {
+                    editor.conflict_cache.buffers.insert(
+                        buffer_id,
+                        CachedBufferConflicts {
+                            version,
+                            conflicts: conflicts.into(),
+                        },
+                    );
+                }
+                update_conflict_blocks(editor, cx);
+            })
+            .log_err()
+    }));
+}
+
+/// The cached conflicts that are shown in an excerpt, in the order of the multibuffer, along
+/// with the excerpt showing them.
+pub(super) fn visible_conflicts(
+    editor: &Editor,
+    snapshot: &MultiBufferSnapshot,
+) -> Vec<(ExcerptId, Conflict)> {
+    let mut conflicts = Vec::new();
+    for (excerpt_id, buffer_snapshot, excerpt_range) in snapshot.excerpts() {
+        let Some(cached) = editor
+            .conflict_cache
+            .buffers
+            .get(&buffer_snapshot.remote_id())
+        else {
+            continue;
+        };
+        let excerpt_range = excerpt_range.context;
+        conflicts.extend(
+            cached
+                .conflicts
+                .iter()
+                .filter(|conflict| {
+                    conflict
+                        .range
+                        .start
+                        .cmp(&excerpt_range.start, buffer_snapshot)
+                        .is_ge()
+                        && conflict
+                            .range
+                            .start
+                            .cmp(&excerpt_range.end, buffer_snapshot)
+                            .is_lt()
+                })
+                .map(|conflict| (excerpt_id, conflict.clone())),
+        );
+    }
+    conflicts
+}
+
+/// Replaces the conflict highlights and blocks with ones for the cached conflicts of every
+/// excerpt.
+fn update_conflict_blocks(editor: &mut Editor, cx: &mut ViewContext<Editor>) {
+    let old_block_ids = std::mem::take(&mut editor.conflict_cache.block_ids);
+    if !old_block_ids.is_empty() {
+        editor.remove_blocks(old_block_ids, None, cx);
+    }
+    editor.clear_row_highlights::<ConflictOursHighlight>();
+    editor.clear_row_highlights::<ConflictTheirsHighlight>();
+
+    let snapshot = editor.buffer.read(cx).snapshot(cx);
+    let ours_color = cx.theme().status().created_background;
+    let theirs_color = cx.theme().status().info_background;
+    let editor_handle = cx.view().downgrade();
+    let mut blocks = Vec::new();
+    for (excerpt_id, conflict) in visible_conflicts(editor, &snapshot) {
+        let anchor = |text_anchor| snapshot.anchor_in_excerpt(excerpt_id, text_anchor);
+        let (Some(start), Some(separator), Some(theirs_end)) = (
+            anchor(conflict.range.start),
+            anchor(conflict.separator),
+            anchor(conflict.theirs.end),
+        ) else {
+            continue;
+        };
+        let last_ours_row = separator.to_point(&snapshot).row.saturating_sub(1);
+        let last_ours_row = snapshot.anchor_before(Point::new(last_ours_row, 0));
+        editor.highlight_rows::<ConflictOursHighlight>(
+            start..=last_ours_row,
+            Some(ours_color),
+            false,
+            cx,
+        );
+        editor.highlight_rows::<ConflictTheirsHighlight>(
+            separator..=theirs_end,
+            Some(theirs_color),
+            false,
+            cx,
+        );
+
+        let editor = editor_handle.clone();
+        blocks.push(BlockProperties {
+            position: start,
+            height: 1,
+            style: BlockStyle::Flex,
+            disposition: BlockDisposition::Above,
+            render: Box::new(move |cx| {
+                render_conflict_block(&editor, excerpt_id, &conflict, cx).into_any_element()
+            }),
+        });
+    }
+
+    if !blocks.is_empty() {
+        editor.conflict_cache.block_ids =
+            editor.insert_blocks(blocks, None, cx).into_iter().collect();
+    }
+    cx.notify();
+}
+
+fn render_conflict_block(
+    editor: &WeakView<Editor>,
+    excerpt_id: ExcerptId,
+    conflict: &Conflict,
+    cx: &mut BlockContext,
+) -> impl IntoElement {
+    let read_only = editor
+        .upgrade()
+        .map_or(true, |editor| editor.read(cx).read_only(cx));
+    let resolve_button = |id: &'static str, label: String, resolution: ConflictResolution| {
+        let editor = editor.clone();
+        let conflict = conflict.clone();
+        Button::new(id, label)
+            .label_size(LabelSize::Small)
+            .style(ButtonStyle::Subtle)
+            .on_click(move |_, cx| {
+                editor
+                    .update(cx, |editor, cx| {
+                        editor.resolve_conflict(excerpt_id, &conflict, resolution, cx)
+                    })
+                    .ok();
+            })
+    };
+    let side_label = |side: &str, label: &str| {
+        if label.is_empty() {
+            format!("Accept {side}")
+        } else {
+            format!("Accept {side} ({label})")
+        }
+    };
+
+    h_flex()
+        .id(cx.block_id)
+        .h(cx.line_height)
+        .pl(cx.gutter_dimensions.full_width())
+        .gap_1()
+        .when(!read_only, |this| {
+            this.child(resolve_button(
+                "accept-ours",
+                side_label("Ours", &conflict.ours_label),
+                ConflictResolution::Ours,
+            ))
+            .child(resolve_button(
+                "accept-theirs",
+                side_label("Theirs", &conflict.theirs_label),
+                ConflictResolution::Theirs,
+            ))
+            .child(resolve_button(
+                "accept-both",
+                "Accept Both".into(),
+                ConflictResolution::Both,
+            ))
+        })
+}
+
+impl Editor {
+    /// Replaces a conflict, including its markers, with the text of the chosen side or sides.
+    pub fn resolve_conflict(
+        &mut self,
+        excerpt_id: ExcerptId,
+        conflict: &Conflict,
+        resolution: ConflictResolution,
+        cx: &mut ViewContext<Self>,
+    ) {
+        if self.read_only(cx) {
+            return;
+        }
+        let snapshot = self.buffer.read(cx).snapshot(cx);
+        let Some(range) = snapshot
+            .anchor_in_excerpt(excerpt_id, conflict.range.start)
+            .zip(snapshot.anchor_in_excerpt(excerpt_id, conflict.range.end))
+        else {
+            return;
+        };
+        let Some(buffer_snapshot) = snapshot.buffer_for_excerpt(excerpt_id) else {
+            return;
+        };
+        let text = conflict.resolved_text(resolution, buffer_snapshot);
+        self.transact(cx, |editor, cx| {
+            editor.buffer.update(cx, |buffer, cx| {
+                buffer.edit([(range.0..range.1, text)], None, cx)
+            });
+        });
+    }
+
+    pub fn go_to_conflict(&mut self, _: &GoToConflict, cx: &mut ViewContext<Self>) {
+        self.go_to_conflict_in_direction(true, cx);
+    }
+
+    pub fn go_to_prev_conflict(&mut self, _: &GoToPrevConflict, cx: &mut ViewContext<Self>) {
+        self.go_to_conflict_in_direction(false, cx);
+    }
+
+    /// Moves the cursor to the start of the next or previous conflict, wrapping around.
+    fn go_to_conflict_in_direction(&mut self, forward: bool, cx: &mut ViewContext<Self>) {
+        let snapshot = self.buffer.read(cx).snapshot(cx);
+        let cursor_row = self.selections.newest::<Point>(cx).head().row;
+        let conflict_starts = visible_conflicts(self, &snapshot)
+            .into_iter()
+            .filter_map(|(excerpt_id, conflict)| {
+                snapshot.anchor_in_excerpt(excerpt_id, conflict.range.start)
+            })
+            .map(|anchor| anchor.to_point(&snapshot))
+            .collect::<Vec<_>>();
+        let target = if forward {
+            conflict_starts
+                .iter()
+                .find(|start| start.row > cursor_row)
+                .or(conflict_starts.first())
+        } else {
+            conflict_starts
+                .iter()
+                .rev()
+                .find(|start| start.row < cursor_row)
+                .or(conflict_starts.last())
+        };
+        if let Some(&target) = target {
+            self.change_selections(Some(Autoscroll::fit()), cx, |s| {
+                s.select_ranges([target..target]);
+            });
+        }
+    }
+}
+
+/// Returns the ranges of the conflicts shown in the editor, in the order of the multibuffer.
+#[cfg(test)]
+pub(super) fn editor_conflicts(editor: &Editor, cx: &AppContext) -> Vec<std::ops::Range<Point>> {
+    let snapshot = editor.buffer.read(cx).snapshot(cx);
+    visible_conflicts(editor, &snapshot)
+        .into_iter()
+        .filter_map(|(excerpt_id, conflict)| {
+            let start = snapshot.anchor_in_excerpt(excerpt_id, conflict.range.start)?;
+            let end = snapshot.anchor_in_excerpt(excerpt_id, conflict.range.end)?;
+            Some(start.to_point(&snapshot)..end.to_point(&snapshot))
+        })
+        .collect()
+}
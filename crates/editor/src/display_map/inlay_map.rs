@@ -1,7 +1,7 @@
 use crate::{HighlightStyles, InlayId};
 use collections::{BTreeMap, BTreeSet};
 use gpui::HighlightStyle;
-use language::{Chunk, Edit, Point, TextSummary};
+use language::{Chunk, Edit, HighlightId, Point, TextSummary};
 use multi_buffer::{
     Anchor, MultiBufferChunks, MultiBufferRow, MultiBufferRows, MultiBufferSnapshot, ToOffset,
 };
@@ -209,6 +209,12 @@ impl Ord for HighlightEndpoint {
     }
 }
 
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+struct SemanticTokenEndpoint {
+    offset: InlayOffset,
+    highlight_id: Option<HighlightId>,
+}
+
 pub struct InlayChunks<'a> {
     transforms: Cursor<'a, Transform, (InlayOffset, usize)>,
     buffer_chunks: MultiBufferChunks<'a>,
@@ -220,6 +226,8 @@ pub struct InlayChunks<'a> {
     highlight_styles: HighlightStyles,
     highlight_endpoints: Peekable<vec::IntoIter<HighlightEndpoint>>,
     active_highlights: BTreeMap<Option<TypeId>, HighlightStyle>,
+    semantic_token_endpoints: Peekable<vec::IntoIter<SemanticTokenEndpoint>>,
+    active_semantic_token: Option<HighlightId>,
     highlights: Highlights<'a>,
     snapshot: &'a InlaySnapshot,
 }
@@ -262,6 +270,15 @@ impl<'a> Iterator for InlayChunks<'a> {
                 break;
             }
         }
+        while let Some(endpoint) = self.semantic_token_endpoints.peek().copied() {
+            if endpoint.offset <= self.output_offset {
+                self.active_semantic_token = endpoint.highlight_id;
+                self.semantic_token_endpoints.next();
+            } else {
+                next_highlight_endpoint = next_highlight_endpoint.min(endpoint.offset);
+                break;
+            }
+        }
 
         let chunk = match self.transforms.item()? {
             Transform::Isomorphic(_) => {
@@ -286,6 +303,9 @@ impl<'a> Iterator for InlayChunks<'a> {
                     text: prefix,
                     ..chunk.clone()
                 };
+                if let Some(highlight_id) = self.active_semantic_token {
+                    prefix.syntax_highlight_id = Some(highlight_id);
+                }
                 if !self.active_highlights.is_empty() {
                     let mut highlight_style = HighlightStyle::default();
                     for active_highlight in self.active_highlights.values() {
@@ -1039,6 +1059,18 @@ impl InlaySnapshot {
             }
         }
         highlight_endpoints.sort();
+
+        let mut semantic_token_endpoints = Vec::new();
+        if language_aware {
+            if let Some(semantic_token_highlights) = highlights.semantic_token_highlights {
+                self.apply_semantic_token_highlights(
+                    &range,
+                    semantic_token_highlights,
+                    &mut semantic_token_endpoints,
+                );
+            }
+        }
+
         let buffer_range = self.to_buffer_offset(range.start)..self.to_buffer_offset(range.end);
         let buffer_chunks = self.buffer.chunks(buffer_range, language_aware);
 
@@ -1053,6 +1085,8 @@ impl InlaySnapshot {
             highlight_styles: highlights.styles,
             highlight_endpoints: highlight_endpoints.into_iter().peekable(),
             active_highlights: Default::default(),
+            semantic_token_endpoints: semantic_token_endpoints.into_iter().peekable(),
+            active_semantic_token: None,
             highlights,
             snapshot: self,
         }
@@ -1116,6 +1150,37 @@ impl InlaySnapshot {
         }
     }
 
+    fn apply_semantic_token_highlights(
+        &self,
+        range: &Range<InlayOffset>,
+        semantic_token_highlights: &[(Range<Anchor>, HighlightId)],
+        endpoints: &mut Vec<SemanticTokenEndpoint>,
+    ) {
+        if semantic_token_highlights.is_empty() {
+            return;
+        }
+
+        let range_start = self.buffer.anchor_after(self.to_buffer_offset(range.start));
+        let range_end = self.buffer.anchor_before(self.to_buffer_offset(range.end));
+        let start_ix = semantic_token_highlights
+            .partition_point(|(probe, _)| probe.end.cmp(&range_start, &self.buffer).is_le());
+        for (token_range, highlight_id) in &semantic_token_highlights[start_ix..] {
+            if token_range.start.cmp(&range_end, &self.buffer).is_ge() {
+                break;
+            }
+
+            endpoints.push(SemanticTokenEndpoint {
+                offset: self.to_inlay_offset(token_range.start.to_offset(&self.buffer)),
+                highlight_id: Some(*highlight_id),
+            });
+            endpoints.push(SemanticTokenEndpoint {
+                offset: self.to_inlay_offset(token_range.end.to_offset(&self.buffer)),
+                highlight_id: None,
+            });
+        }
+        endpoints.sort_by_key(|endpoint| (endpoint.offset, endpoint.highlight_id.is_some()));
+    }
+
     #[cfg(test)]
     pub fn text(&self) -> String {
         self.chunks(Default::default()..self.len(), false, Highlights::default())
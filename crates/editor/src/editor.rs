@@ -15,6 +15,8 @@
 pub mod actions;
 mod blame_entry_tooltip;
 mod blink_manager;
+mod code_lens;
+mod conflicts;
 mod debounced_delay;
 pub mod display_map;
 mod editor_settings;
@@ -29,12 +31,15 @@ mod inlay_hint_cache;
 mod inline_completion_provider;
 pub mod items;
 mod linked_editing_ranges;
+mod lsp_buffer_cache;
 mod mouse_context_menu;
 pub mod movement;
 mod persistence;
 mod rust_analyzer_ext;
 pub mod scroll;
 mod selections_collection;
+mod semantic_tokens;
+mod snippet_variables;
 pub mod tasks;
 
 #[cfg(test)]
@@ -91,7 +96,7 @@ use language::{
     CursorShape, Diagnostic, Documentation, IndentKind, IndentSize, Language, OffsetRangeExt,
     Point, Selection, SelectionGoal, TransactionId,
 };
-use language::{point_to_lsp, BufferRow, Runnable, RunnableRange};
+use language::{point_to_lsp, BufferRow, BufferSnapshot, Runnable, RunnableRange};
 use linked_editing_ranges::refresh_linked_ranges;
 use task::{ResolvedTask, TaskTemplate, TaskVariables};
 
@@ -123,6 +128,7 @@ use serde::{Deserialize, Serialize};
 use settings::{update_settings_file, Settings, SettingsStore};
 use smallvec::SmallVec;
 use snippet::Snippet;
+use snippet_variables::SnippetVariables;
 use std::{
     any::TypeId,
     borrow::Cow,
@@ -514,6 +520,9 @@ pub struct Editor {
     document_highlights_task: Option<Task<()>>,
     linked_editing_range_task: Option<Task<Option<()>>>,
     linked_edit_ranges: linked_editing_ranges::LinkedEditingRanges,
+    semantic_tokens_cache: semantic_tokens::SemanticTokensCache,
+    code_lens_cache: code_lens::CodeLensCache,
+    conflict_cache: conflicts::ConflictCache,
     pending_rename: Option<RenameState>,
     searchable: bool,
     cursor_shape: CursorShape,
@@ -525,6 +534,7 @@ pub struct Editor {
     input_enabled: bool,
     use_modal_editing: bool,
     read_only: bool,
+    masked: bool,
     leader_peer_id: Option<PeerId>,
     remote_id: Option<ViewId>,
     hover_state: HoverState,
@@ -775,9 +785,20 @@ struct AutocloseRegion {
 #[derive(Debug)]
 struct SnippetState {
     ranges: Vec<Vec<Range<Anchor>>>,
+    choices: Vec<Option<Vec<String>>>,
+    transforms: Vec<SnippetTransform>,
     active_index: usize,
 }
 
+/// A transformed mirror of a snippet tabstop, updated when leaving that tabstop.
+#[derive(Debug)]
+struct SnippetTransform {
+    tabstop_index: usize,
+    source: Range<Anchor>,
+    target: Range<Anchor>,
+    transform: snippet::Transform,
+}
+
 #[doc(hidden)]
 pub struct RenameState {
     pub range: Range<Anchor>,
@@ -904,6 +925,9 @@ struct CompletionsMenu {
     selected_item: usize,
     scroll_handle: UniformListScrollHandle,
     selected_completion_documentation_resolve_debounce: Arc<Mutex<DebouncedDelay>>,
+    /// Whether the menu offers the choices of a snippet tabstop rather than
+    /// completions from the completion provider.
+    is_snippet_choice: bool,
 }
 
 impl CompletionsMenu {
@@ -1740,6 +1764,19 @@ impl Editor {
                 project_subscriptions.push(cx.subscribe(project, |editor, _, event, cx| {
                     if let project::Event::RefreshInlayHints = event {
                         editor.refresh_inlay_hints(InlayHintRefreshReason::RefreshRequested, cx);
+                    } else if let project::Event::RefreshSemanticTokens = event {
+                        editor.semantic_tokens_cache.invalidate();
+                        semantic_tokens::refresh_semantic_tokens(editor, false, cx);
+                    } else if let project::Event::RefreshCodeLens = event {
+                        editor.code_lens_cache.invalidate();
+                        code_lens::refresh_code_lens(editor, false, cx);
+                    } else if let project::Event::LanguageServerAdded(_) = event {
+                        editor.semantic_tokens_cache.invalidate();
+                        semantic_tokens::refresh_semantic_tokens(editor, false, cx);
+                        editor.code_lens_cache.invalidate();
+                        code_lens::refresh_code_lens(editor, false, cx);
+                    } else if let project::Event::WorktreeUpdatedGitRepositories = event {
+                        conflicts::refresh_conflicts(editor, false, cx);
                     } else if let project::Event::SnippetEdit(id, snippet_edits) = event {
                         if let Some(buffer) = editor.buffer.read(cx).buffer(*id) {
                             let focus_handle = editor.focus_handle(cx);
@@ -1845,6 +1882,7 @@ impl Editor {
             input_enabled: true,
             use_modal_editing: mode == EditorMode::Full,
             read_only: false,
+            masked: false,
             use_autoclose: true,
             use_auto_surround: true,
             auto_replace_emoji_shortcode: false,
@@ -1899,6 +1937,9 @@ impl Editor {
             ],
             tasks_update_task: None,
             linked_edit_ranges: Default::default(),
+            semantic_tokens_cache: Default::default(),
+            code_lens_cache: Default::default(),
+            conflict_cache: Default::default(),
             previous_search_ranges: None,
             breadcrumb_header: None,
         };
@@ -1916,6 +1957,10 @@ impl Editor {
                 this.git_blame_inline_enabled = true;
                 this.start_git_blame_inline(false, cx);
             }
+
+            semantic_tokens::refresh_semantic_tokens(&mut this, false, cx);
+            code_lens::refresh_code_lens(&mut this, false, cx);
+            conflicts::refresh_conflicts(&mut this, false, cx);
         }
 
         this.report_editor_event("open", None, cx);
@@ -2230,6 +2275,12 @@ impl Editor {
         self.read_only = read_only;
     }
 
+    /// Hides the whole text behind redactions and disables copying it, as for passwords.
+    pub fn set_masked(&mut self, masked: bool, cx: &mut ViewContext<Self>) {
+        self.masked = masked;
+        cx.notify();
+    }
+
     pub fn set_use_autoclose(&mut self, autoclose: bool) {
         self.use_autoclose = autoclose;
     }
@@ -4102,6 +4153,7 @@ impl Editor {
                         selected_completion_documentation_resolve_debounce: Arc::new(Mutex::new(
                             DebouncedDelay::new(),
                         )),
+                        is_snippet_choice: false,
                     };
                     menu.filter(query.as_deref(), cx.background_executor().clone())
                         .await;
@@ -4191,6 +4243,7 @@ impl Editor {
             .matches
             .get(action.item_ix.unwrap_or(completions_menu.selected_item))?;
         let buffer_handle = completions_menu.buffer;
+        let is_snippet_choice = completions_menu.is_snippet_choice;
         let completions = completions_menu.completions.read();
         let completion = completions.get(mat.candidate_id)?;
         cx.stop_propagation();
@@ -4199,7 +4252,13 @@ impl Editor {
         let text;
 
         if completion.is_snippet() {
-            snippet = Some(Snippet::parse(&completion.new_text).log_err()?);
+            let variables = SnippetVariables::new(self, cx);
+            snippet = Some(
+                Snippet::parse_with_variables(&completion.new_text, &|name| {
+                    variables.resolve(name)
+                })
+                .log_err()?,
+            );
             text = snippet.as_ref().unwrap().text.clone();
         } else {
             snippet = None;
@@ -4331,6 +4390,10 @@ impl Editor {
             self.show_completions(&ShowCompletions { trigger: None }, cx);
         }
 
+        if is_snippet_choice {
+            return Some(Task::ready(Ok(())));
+        }
+
         let provider = self.completion_provider.as_ref()?;
         let apply_edits = provider.apply_additional_edits_for_completion(
             buffer_handle,
@@ -5154,7 +5217,7 @@ impl Editor {
             ranges: Vec<Range<T>>,
         }
 
-        let tabstops = self.buffer.update(cx, |buffer, cx| {
+        let (tabstops, transforms) = self.buffer.update(cx, |buffer, cx| {
             let snippet_text: Arc<str> = snippet.text.clone().into();
             buffer.edit(
                 insertion_ranges
@@ -5167,7 +5230,22 @@ impl Editor {
 
             let snapshot = &*buffer.read(cx);
             let snippet = &snippet;
-            snippet
+            let mut delta = 0_isize;
+            let insertion_starts = insertion_ranges
+                .iter()
+                .map(|insertion_range| {
+                    let insertion_start = insertion_range.start as isize + delta;
+                    delta += snippet.text.len() as isize - insertion_range.len() as isize;
+                    insertion_start
+                })
+                .collect::<Vec<_>>();
+            let anchor_range = |insertion_start: isize, range: &Range<isize>| {
+                let start = ((insertion_start + range.start) as usize).min(snapshot.len());
+                let end = ((insertion_start + range.end) as usize).min(snapshot.len());
+                snapshot.anchor_before(start)..snapshot.anchor_after(end)
+            };
+
+            let tabstops = snippet
                 .tabstops
                 .iter()
                 .map(|tabstop| {
@@ -5177,17 +5255,8 @@ impl Editor {
                     let mut tabstop_ranges = tabstop
                         .iter()
                         .flat_map(|tabstop_range| {
-                            let mut delta = 0_isize;
-                            insertion_ranges.iter().map(move |insertion_range| {
-                                let insertion_start = insertion_range.start as isize + delta;
-                                delta +=
-                                    snippet.text.len() as isize - insertion_range.len() as isize;
-
-                                let start = ((insertion_start + tabstop_range.start) as usize)
-                                    .min(snapshot.len());
-                                let end = ((insertion_start + tabstop_range.end) as usize)
-                                    .min(snapshot.len());
-                                snapshot.anchor_before(start)..snapshot.anchor_after(end)
+                            insertion_starts.iter().map(move |insertion_start| {
+                                anchor_range(*insertion_start, tabstop_range)
                             })
                         })
                         .collect::<Vec<_>>();
@@ -5198,7 +5267,24 @@ impl Editor {
                         ranges: tabstop_ranges,
                     }
                 })
-                .collect::<Vec<_>>()
+                .collect::<Vec<_>>();
+
+            let mut transforms = Vec::new();
+            for transform in &snippet.transforms {
+                let Some(source) = snippet.tabstops[transform.tabstop_index].first() else {
+                    continue;
+                };
+                for insertion_start in &insertion_starts {
+                    transforms.push(SnippetTransform {
+                        tabstop_index: transform.tabstop_index,
+                        source: anchor_range(*insertion_start, source),
+                        target: anchor_range(*insertion_start, &transform.range),
+                        transform: transform.transform.clone(),
+                    });
+                }
+            }
+
+            (tabstops, transforms)
         });
         if let Some(tabstop) = tabstops.first() {
             self.change_selections(Some(Autoscroll::fit()), cx, |s| {
@@ -5212,10 +5298,16 @@ impl Editor {
                     .into_iter()
                     .map(|tabstop| tabstop.ranges)
                     .collect::<Vec<_>>();
+                let choices = snippet.choices.first().cloned().flatten();
                 self.snippet_stack.push(SnippetState {
                     active_index: 0,
                     ranges,
+                    choices: snippet.choices,
+                    transforms,
                 });
+                if let Some(choices) = choices {
+                    self.show_snippet_choices(&choices, cx);
+                }
             }
 
             // Check whether the just-entered snippet ends with an auto-closable bracket.
@@ -5267,6 +5359,7 @@ impl Editor {
 
     pub fn move_to_snippet_tabstop(&mut self, bias: Bias, cx: &mut ViewContext<Self>) -> bool {
         if let Some(mut snippet) = self.snippet_stack.pop() {
+            let previous_index = snippet.active_index;
             match bias {
                 Bias::Left => {
                     if snippet.active_index > 0 {
@@ -5285,14 +5378,19 @@ impl Editor {
                     }
                 }
             }
+            self.apply_snippet_transforms(&snippet, previous_index, cx);
             if let Some(current_ranges) = snippet.ranges.get(snippet.active_index) {
                 self.change_selections(Some(Autoscroll::fit()), cx, |s| {
                     s.select_anchor_ranges(current_ranges.iter().cloned())
                 });
+                let choices = snippet.choices.get(snippet.active_index).cloned().flatten();
                 // If snippet state is not at the last tabstop, push it back on the stack
                 if snippet.active_index + 1 < snippet.ranges.len() {
                     self.snippet_stack.push(snippet);
                 }
+                if let Some(choices) = choices {
+                    self.show_snippet_choices(&choices, cx);
+                }
                 return true;
             }
         }
@@ -5300,6 +5398,89 @@ impl Editor {
         false
     }
 
+    /// Updates the transformed mirrors of the tabstop from its current text.
+    fn apply_snippet_transforms(
+        &mut self,
+        snippet: &SnippetState,
+        tabstop_index: usize,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let snapshot = self.buffer.read(cx).snapshot(cx);
+        let edits = snippet
+            .transforms
+            .iter()
+            .filter(|transform| transform.tabstop_index == tabstop_index)
+            .map(|transform| {
+                let text = snapshot
+                    .text_for_range(transform.source.clone())
+                    .collect::<String>();
+                (transform.target.clone(), transform.transform.apply(&text))
+            })
+            .collect::<Vec<_>>();
+        if !edits.is_empty() {
+            self.buffer
+                .update(cx, |buffer, cx| buffer.edit(edits, None, cx));
+        }
+    }
+
+    /// Offers the choices of the active snippet tabstop in a completions menu.
+    fn show_snippet_choices(&mut self, choices: &[String], cx: &mut ViewContext<Self>) {
+        let selection = self.selections.newest_anchor();
+        let (selection_start, selection_end) = (selection.start, selection.end);
+        let multi_buffer = self.buffer.read(cx);
+        let (Some((buffer, start)), Some((_, end))) = (
+            multi_buffer.text_anchor_for_position(selection_start, cx),
+            multi_buffer.text_anchor_for_position(selection_end, cx),
+        ) else {
+            return;
+        };
+
+        let completions = choices
+            .iter()
+            .map(|choice| Completion {
+                old_range: start..end,
+                new_text: choice.clone(),
+                label: CodeLabel::plain(choice.clone(), None),
+                server_id: LanguageServerId(0),
+                documentation: Some(Documentation::Undocumented),
+                lsp_completion: Default::default(),
+                confirm: None,
+                show_new_completions_on_confirm: false,
+            })
+            .collect::<Vec<_>>();
+        let menu = CompletionsMenu {
+            id: post_inc(&mut self.next_completion_id),
+            initial_position: selection_start,
+            match_candidates: choices
+                .iter()
+                .enumerate()
+                .map(|(id, choice)| StringMatchCandidate::new(id, choice.clone()))
+                .collect(),
+            buffer,
+            completions: Arc::new(RwLock::new(completions.into())),
+            matches: choices
+                .iter()
+                .enumerate()
+                .map(|(candidate_id, choice)| StringMatch {
+                    candidate_id,
+                    score: 0.,
+                    positions: Vec::new(),
+                    string: choice.clone(),
+                })
+                .collect(),
+            selected_item: 0,
+            scroll_handle: UniformListScrollHandle::new(),
+            selected_completion_documentation_resolve_debounce: Arc::new(Mutex::new(
+                DebouncedDelay::new(),
+            )),
+            is_snippet_choice: true,
+        };
+
+        *self.context_menu.write() = Some(ContextMenu::Completions(menu));
+        self.discard_inline_completion(false, cx);
+        cx.notify();
+    }
+
     pub fn clear(&mut self, cx: &mut ViewContext<Self>) {
         self.transact(cx, |this, cx| {
             this.select_all(&SelectAll, cx);
@@ -5882,6 +6063,82 @@ impl Editor {
         }
     }
 
+    pub fn stage_selected_hunks(&mut self, _: &StageSelectedHunks, cx: &mut ViewContext<Self>) {
+        let multi_buffer = self.buffer.read(cx);
+        let multi_buffer_snapshot = multi_buffer.snapshot(cx);
+        let hunks =
+            hunks_for_selections(&multi_buffer_snapshot, &self.selections.disjoint_anchors())
+                .into_iter()
+                .filter_map(|hunk| {
+                    let buffer = multi_buffer.buffer(hunk.buffer_id)?;
+                    let new_text = buffer
+                        .read(cx)
+                        .text_for_range(hunk.buffer_range)
+                        .collect::<String>();
+                    Some((buffer, hunk.diff_base_byte_range, Some(new_text)))
+                })
+                .collect();
+        self.update_staged_hunks(hunks, cx);
+    }
+
+    /// Unstages the staged changes on the selected lines. Those are not diff hunks in the
+    /// editor, whose diff base is the index, so the lines are mapped to the index text instead.
+    pub fn unstage_selected_hunks(&mut self, _: &UnstageSelectedHunks, cx: &mut ViewContext<Self>) {
+        let multi_buffer = self.buffer.read(cx);
+        let mut hunks = Vec::new();
+        for selection in self.selections.all::<Point>(cx) {
+            for (buffer, range, _) in multi_buffer.range_to_buffer_ranges(selection.range(), cx) {
+                let buffer_snapshot = buffer.read(cx).snapshot();
+                let Some(diff_base) = buffer.read(cx).diff_base() else {
+                    continue;
+                };
+                let rows = buffer_snapshot.offset_to_point(range.start).row
+                    ..buffer_snapshot.offset_to_point(range.end).row + 1;
+                let diff_base_range = diff_base_range_for_rows(&buffer_snapshot, diff_base, rows);
+                hunks.push((buffer, diff_base_range, None));
+            }
+        }
+        self.update_staged_hunks(hunks, cx);
+    }
+
+    /// Stages the given diff base ranges of the buffers when they have a new text,
+    /// and unstages them otherwise.
+    pub(crate) fn update_staged_hunks(
+        &mut self,
+        mut hunks: Vec<(Model<Buffer>, Range<usize>, Option<String>)>,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let Some(project) = self.project.clone() else {
+            return;
+        };
+        // Editing the index text moves the diff base ranges after the edit,
+        // so the hunks of each buffer are applied from last to first.
+        hunks.sort_by_key(|(buffer, diff_base_range, _)| {
+            (buffer.entity_id(), Reverse(diff_base_range.start))
+        });
+        hunks.dedup_by(|(buffer_a, range_a, _), (buffer_b, range_b, _)| {
+            buffer_a == buffer_b && range_a == range_b
+        });
+        if hunks.is_empty() {
+            return;
+        }
+
+        cx.spawn(|_, mut cx| async move {
+            for (buffer, diff_base_range, new_text) in hunks {
+                project
+                    .update(&mut cx, |project, cx| match new_text {
+                        Some(new_text) => {
+                            project.stage_hunk(&buffer, diff_base_range, new_text, cx)
+                        }
+                        None => project.unstage_hunk(&buffer, diff_base_range, cx),
+                    })?
+                    .await?;
+            }
+            anyhow::Ok(())
+        })
+        .detach_and_log_err(cx);
+    }
+
     pub fn open_active_item_in_terminal(&mut self, _: &OpenInTerminal, cx: &mut ViewContext<Self>) {
         if let Some(working_directory) = self.active_excerpt(cx).and_then(|(_, buffer, _)| {
             let project_path = buffer.read(cx).project_path(cx)?;
@@ -6472,6 +6729,9 @@ impl Editor {
     }
 
     pub fn cut(&mut self, _: &Cut, cx: &mut ViewContext<Self>) {
+        if self.masked {
+            return;
+        }
         let mut text = String::new();
         let buffer = self.buffer.read(cx).snapshot(cx);
         let mut selections = self.selections.all::<Point>(cx);
@@ -6516,6 +6776,9 @@ impl Editor {
     }
 
     pub fn copy(&mut self, _: &Copy, cx: &mut ViewContext<Self>) {
+        if self.masked {
+            return;
+        }
         let selections = self.selections.all::<Point>(cx);
         let buffer = self.buffer.read(cx).read(cx);
         let mut text = String::new();
@@ -11042,6 +11305,15 @@ impl Editor {
         display_snapshot: &DisplaySnapshot,
         cx: &WindowContext,
     ) -> Vec<Range<DisplayPoint>> {
+        if self.masked {
+            let range = search_range.start.to_display_point(display_snapshot)
+                ..search_range.end.to_display_point(display_snapshot);
+            return if range.start == range.end {
+                Vec::new()
+            } else {
+                vec![range]
+            };
+        }
         display_snapshot
             .buffer_snapshot
             .redacted_ranges(search_range, |file| {
@@ -11162,6 +11434,10 @@ impl Editor {
                     }
                 }
 
+                semantic_tokens::refresh_semantic_tokens(self, true, cx);
+                code_lens::refresh_code_lens(self, true, cx);
+                conflicts::refresh_conflicts(self, true, cx);
+
                 let Some(project) = &self.project else { return };
                 let telemetry = project.read(cx).client().telemetry().clone();
                 refresh_linked_ranges(self, cx);
@@ -11179,9 +11455,15 @@ impl Editor {
                     excerpts: excerpts.clone(),
                 });
                 self.refresh_inlay_hints(InlayHintRefreshReason::NewLinesShown, cx);
+                semantic_tokens::refresh_semantic_tokens(self, false, cx);
+                code_lens::refresh_code_lens(self, false, cx);
+                conflicts::refresh_conflicts(self, false, cx);
             }
             multi_buffer::Event::ExcerptsRemoved { ids } => {
                 self.refresh_inlay_hints(InlayHintRefreshReason::ExcerptsRemoved(ids.clone()), cx);
+                semantic_tokens::refresh_semantic_tokens(self, false, cx);
+                code_lens::refresh_code_lens(self, false, cx);
+                conflicts::refresh_conflicts(self, false, cx);
                 cx.emit(EditorEvent::ExcerptsRemoved { ids: ids.clone() })
             }
             multi_buffer::Event::ExcerptsEdited { ids } => {
@@ -11197,6 +11479,9 @@ impl Editor {
             }
             multi_buffer::Event::LanguageChanged(buffer_id) => {
                 linked_editing_ranges::refresh_linked_ranges(self, cx);
+                semantic_tokens::refresh_semantic_tokens(self, false, cx);
+                code_lens::refresh_code_lens(self, false, cx);
+                conflicts::refresh_conflicts(self, false, cx);
                 cx.emit(EditorEvent::Reparsed(*buffer_id));
                 cx.notify();
             }
@@ -11239,6 +11524,8 @@ impl Editor {
             )),
             cx,
         );
+        semantic_tokens::refresh_semantic_tokens(self, false, cx);
+        code_lens::refresh_code_lens(self, false, cx);
         let editor_settings = EditorSettings::get_global(cx);
         self.scroll_manager.vertical_scroll_margin = editor_settings.vertical_scroll_margin;
         self.show_breadcrumbs = editor_settings.toolbar.breadcrumbs;
@@ -11687,6 +11974,46 @@ impl Editor {
     }
 }
 
+/// Maps the buffer rows to the byte range of the corresponding lines of the buffer's diff base.
+/// Rows in a diff hunk map to the hunk's whole diff base range.
+fn diff_base_range_for_rows(
+    buffer: &BufferSnapshot,
+    diff_base: &Rope,
+    rows: Range<u32>,
+) -> Range<usize> {
+    let row_offset = |row: i64| {
+        diff_base.point_to_offset(Point::new(row.max(0) as u32, 0).min(diff_base.max_point()))
+    };
+
+    let mut start = None;
+    let mut end = None;
+    // The difference between the rows of the diff base and of the buffer after the last hunk.
+    let mut row_delta = 0;
+    for hunk in buffer.git_diff_hunks_in_row_range(0..rows.end) {
+        let hunk_rows = &hunk.associated_range;
+        if start.is_none() {
+            if rows.start < hunk_rows.start {
+                start = Some(row_offset(rows.start as i64 + row_delta));
+            } else if rows.start < hunk_rows.end {
+                start = Some(hunk.diff_base_byte_range.start);
+            }
+        }
+        if end.is_none() {
+            if rows.end <= hunk_rows.start {
+                end = Some(row_offset(rows.end as i64 + row_delta));
+            } else if rows.end < hunk_rows.end {
+                end = Some(hunk.diff_base_byte_range.end);
+            }
+        }
+        let diff_base_end_row = diff_base.offset_to_point(hunk.diff_base_byte_range.end).row;
+        row_delta = diff_base_end_row as i64 - hunk_rows.end as i64;
+    }
+
+    let start = start.unwrap_or_else(|| row_offset(rows.start as i64 + row_delta));
+    let end = end.unwrap_or_else(|| row_offset(rows.end as i64 + row_delta));
+    start..end.max(start)
+}
+
 fn hunks_for_selections(
     multi_buffer_snapshot: &MultiBufferSnapshot,
     selections: &[Selection<Anchor>],
@@ -8,6 +8,7 @@ use crate::{
     },
     JoinLines,
 };
+use ::git::conflict::ConflictResolution;
 use futures::StreamExt;
 use gpui::{
     div, SemanticVersion, TestAppContext, UpdateGlobal, VisualTestContext, WindowBounds,
@@ -6075,6 +6076,79 @@ async fn test_snippets(cx: &mut gpui::TestAppContext) {
     });
 }
 
+#[gpui::test]
+async fn test_snippet_choices_and_transforms(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+
+    let (text, insertion_ranges) = marked_text_ranges(
+        indoc! {"
+            ˇ
+            ˇ
+        "},
+        false,
+    );
+
+    let buffer = cx.update(|cx| MultiBuffer::build_simple(&text, cx));
+    let (editor, cx) = cx.add_window_view(|cx| build_editor(buffer, cx));
+
+    _ = editor.update(cx, |editor, cx| {
+        let snippet =
+            Snippet::parse("let ${1|foo,bar|}: ${1/(.*)/${1:/capitalize}/} = $2;$0").unwrap();
+
+        editor
+            .insert_snippet(&insertion_ranges, snippet, cx)
+            .unwrap();
+
+        fn assert(editor: &mut Editor, cx: &mut ViewContext<Editor>, marked_text: &str) {
+            let (expected_text, selection_ranges) = marked_text_ranges(marked_text, false);
+            assert_eq!(editor.text(cx), expected_text);
+            assert_eq!(editor.selections.ranges::<usize>(cx), selection_ranges);
+        }
+
+        assert(
+            editor,
+            cx,
+            indoc! {"
+                let «foo»: Foo = ;
+                let «foo»: Foo = ;
+            "},
+        );
+        if let Some(ContextMenu::Completions(menu)) = editor.context_menu.read().as_ref() {
+            assert_eq!(
+                menu.matches.iter().map(|m| &m.string).collect::<Vec<_>>(),
+                &["foo", "bar"]
+            );
+        } else {
+            panic!("expected the choices of the tabstop to be offered");
+        }
+
+        editor.context_menu_next(&Default::default(), cx);
+        editor
+            .confirm_completion(&ConfirmCompletion::default(), cx)
+            .unwrap()
+            .detach();
+        assert(
+            editor,
+            cx,
+            indoc! {"
+                let barˇ: Foo = ;
+                let barˇ: Foo = ;
+            "},
+        );
+
+        // Mirrors are transformed when leaving the tabstop
+        assert!(editor.move_to_next_snippet_tabstop(cx));
+        assert(
+            editor,
+            cx,
+            indoc! {"
+                let bar: Bar = ˇ;
+                let bar: Bar = ˇ;
+            "},
+        );
+    });
+}
+
 #[gpui::test]
 async fn test_document_format_during_save(cx: &mut gpui::TestAppContext) {
     init_test(cx, |_| {});
@@ -9195,6 +9269,38 @@ async fn go_to_hunk(executor: BackgroundExecutor, cx: &mut gpui::TestAppContext)
     );
 }
 
+#[gpui::test]
+async fn test_diff_base_range_for_rows(
+    executor: BackgroundExecutor,
+    cx: &mut gpui::TestAppContext,
+) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorTestContext::new(cx).await;
+    let diff_base = "a\nb\nc\nd\ne\n";
+    // A modified row and two added rows.
+    cx.set_state("ˇa\nB\nc\nx\ny\nd\ne\n");
+    cx.set_diff_base(Some(diff_base));
+    executor.run_until_parked();
+
+    let buffer = cx.buffer_snapshot();
+    let diff_base = Rope::from(diff_base);
+    for (rows, expected_range) in [
+        (0..1, 0..2),
+        (1..2, 2..4),
+        (2..3, 4..6),
+        (3..4, 6..6),
+        (5..6, 6..8),
+        (0..7, 0..10),
+    ] {
+        assert_eq!(
+            diff_base_range_for_rows(&buffer, &diff_base, rows.clone()),
+            expected_range,
+            "rows {rows:?}"
+        );
+    }
+}
+
 #[test]
 fn test_split_words() {
     fn split(text: &str) -> Vec<&str> {
@@ -13057,6 +13163,119 @@ fn test_crease_insertion_and_rendering(cx: &mut TestAppContext) {
     assert!(!snapshot.is_line_folded(MultiBufferRow(1)));
 }
 
+#[gpui::test]
+async fn test_merge_conflicts(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/dir",
+        json!({
+            ".git": {},
+            "file.txt": "
+                one
+                <<<<<<< HEAD
+                two
+                =======
+                TWO
+                >>>>>>> feature
+                three
+                <<<<<<< HEAD
+                four
+                =======
+                FOUR
+                >>>>>>> feature
+            "
+            .unindent(),
+        }),
+    )
+    .await;
+    fs.set_status_for_repo_via_working_copy_change(
+        "/dir/.git".as_ref(),
+        &[(
+            "file.txt".as_ref(),
+            ::git::repository::GitFileStatus::Conflict,
+        )],
+    );
+
+    let project = Project::test(fs, ["/dir".as_ref()], cx).await;
+    cx.executor().run_until_parked();
+    let buffer = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer("/dir/file.txt", cx)
+        })
+        .await
+        .unwrap();
+    let buffer = cx.new_model(|cx| MultiBuffer::singleton(buffer, cx));
+    let (editor, cx) = cx.add_window_view(|cx| build_editor(buffer, cx));
+    cx.executor().run_until_parked();
+
+    editor.update(cx, |editor, cx| {
+        assert_eq!(
+            conflicts::editor_conflicts(editor, cx),
+            [
+                Point::new(1, 0)..Point::new(6, 0),
+                Point::new(7, 0)..Point::new(12, 0)
+            ]
+        );
+
+        editor.go_to_conflict(&GoToConflict, cx);
+        assert_eq!(
+            editor.selections.newest::<Point>(cx).head(),
+            Point::new(1, 0)
+        );
+        editor.go_to_conflict(&GoToConflict, cx);
+        assert_eq!(
+            editor.selections.newest::<Point>(cx).head(),
+            Point::new(7, 0)
+        );
+        editor.go_to_conflict(&GoToConflict, cx);
+        assert_eq!(
+            editor.selections.newest::<Point>(cx).head(),
+            Point::new(1, 0)
+        );
+        editor.go_to_prev_conflict(&GoToPrevConflict, cx);
+        assert_eq!(
+            editor.selections.newest::<Point>(cx).head(),
+            Point::new(7, 0)
+        );
+
+        let snapshot = editor.buffer().read(cx).snapshot(cx);
+        let (excerpt_id, conflict) = conflicts::visible_conflicts(editor, &snapshot)
+            .into_iter()
+            .next()
+            .unwrap();
+        editor.resolve_conflict(excerpt_id, &conflict, ConflictResolution::Ours, cx);
+        assert_eq!(
+            editor.text(cx),
+            "one\ntwo\nthree\n<<<<<<< HEAD\nfour\n=======\nFOUR\n>>>>>>> feature\n"
+        );
+    });
+    cx.executor().advance_clock(Duration::from_secs(1));
+    cx.executor().run_until_parked();
+
+    editor.update(cx, |editor, cx| {
+        assert_eq!(
+            conflicts::editor_conflicts(editor, cx),
+            [Point::new(3, 0)..Point::new(8, 0)]
+        );
+
+        let snapshot = editor.buffer().read(cx).snapshot(cx);
+        let (excerpt_id, conflict) = conflicts::visible_conflicts(editor, &snapshot)
+            .into_iter()
+            .next()
+            .unwrap();
+        editor.resolve_conflict(excerpt_id, &conflict, ConflictResolution::Both, cx);
+        assert_eq!(editor.text(cx), "one\ntwo\nthree\nfour\nFOUR\n");
+    });
+    cx.executor().advance_clock(Duration::from_secs(1));
+    cx.executor().run_until_parked();
+
+    editor.update(cx, |editor, cx| {
+        assert!(conflicts::editor_conflicts(editor, cx).is_empty());
+    });
+}
+
 fn empty_range(row: usize, column: usize) -> Range<DisplayPoint> {
     let point = DisplayPoint::new(DisplayRow(row as u32), column as u32);
     point..point
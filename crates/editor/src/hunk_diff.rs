@@ -4,7 +4,10 @@ use std::{
 };
 
 use collections::{hash_map, HashMap, HashSet};
-use git::diff::{DiffHunk, DiffHunkStatus};
+use git::{
+    diff::{DiffHunk, DiffHunkStatus},
+    repository::DiffBase,
+};
 use gpui::{AppContext, Hsla, Model, Task, View};
 use language::Buffer;
 use multi_buffer::{
@@ -13,7 +16,9 @@ use multi_buffer::{
 use settings::SettingsStore;
 use text::{BufferId, Point};
 use ui::{
-    div, ActiveTheme, Context as _, IntoElement, ParentElement, Styled, ViewContext, VisualContext,
+    div, h_flex, ActiveTheme, Button, ButtonCommon, ButtonStyle, Clickable, Context as _,
+    FluentBuilder, IntoElement, LabelSize, ParentElement, Styled, ViewContext, VisualContext,
+    WindowContext,
 };
 use util::{debug_panic, RangeExt};
 
@@ -22,7 +27,7 @@ use crate::{
     git::{diff_hunk_to_display, DisplayDiffHunk},
     hunk_status, hunks_for_selections, BlockDisposition, BlockId, BlockProperties, BlockStyle,
     DiffRowHighlight, Editor, EditorSnapshot, ExpandAllHunkDiffs, RangeToAnchorExt,
-    RevertSelectedHunks, ToDisplayPoint, ToggleHunkDiff,
+    RevertSelectedHunks, StageSelectedHunks, ToDisplayPoint, ToggleHunkDiff, UnstageSelectedHunks,
 };
 
 #[derive(Debug, Clone)]
@@ -310,6 +315,9 @@ impl Editor {
         let (editor_height, editor_with_deleted_text) =
             editor_with_deleted_text(diff_base_buffer, deleted_hunk_color, hunk, cx);
         let editor_model = cx.model().clone();
+        let editor = cx.view().downgrade();
+        let hunk_range = hunk.multi_buffer_range.clone();
+        let diff_base_range = hunk.diff_base_byte_range.clone();
         let mut new_block_ids = self.insert_blocks(
             Some(BlockProperties {
                 position: hunk.multi_buffer_range.start,
@@ -317,11 +325,44 @@ impl Editor {
                 style: BlockStyle::Flex,
                 render: Box::new(move |cx| {
                     let gutter_dimensions = editor_model.read(cx).gutter_dimensions;
+                    let can_stage_hunks = editor_model.read(cx).can_stage_hunks(cx);
+                    let staging_button = |id: &'static str, label: &'static str, stage: bool| {
+                        let editor = editor.clone();
+                        let hunk_range = hunk_range.clone();
+                        let diff_base_range = diff_base_range.clone();
+                        Button::new(id, label)
+                            .label_size(LabelSize::Small)
+                            .style(ButtonStyle::Filled)
+                            .on_click(move |_, cx| {
+                                editor
+                                    .update(cx, |editor, cx| {
+                                        editor.update_staged_expanded_hunk(
+                                            &hunk_range,
+                                            diff_base_range.clone(),
+                                            stage,
+                                            cx,
+                                        )
+                                    })
+                                    .ok();
+                            })
+                    };
                     div()
+                        .relative()
                         .bg(deleted_hunk_color)
                         .size_full()
                         .pl(gutter_dimensions.full_width())
                         .child(editor_with_deleted_text.clone())
+                        .when(can_stage_hunks, |div| {
+                            div.child(
+                                h_flex()
+                                    .absolute()
+                                    .top_0()
+                                    .right_4()
+                                    .gap_1()
+                                    .child(staging_button("stage-hunk", "Stage", true))
+                                    .child(staging_button("unstage-hunk", "Unstage", false)),
+                            )
+                        })
                         .into_any_element()
                 }),
                 disposition: BlockDisposition::Above,
@@ -339,6 +380,40 @@ impl Editor {
         }
     }
 
+    /// Whether the diff hunks are relative to the index, which is required to stage them.
+    /// Read-only editors, like those showing past revisions, can't stage hunks.
+    pub(crate) fn can_stage_hunks(&self, cx: &AppContext) -> bool {
+        !self.read_only(cx)
+            && self.project.as_ref().map_or(false, |project| {
+                *project.read(cx).git_diff_base() == DiffBase::Index
+            })
+    }
+
+    /// Stages the changes of the expanded hunk at the given range, or unstages the staged
+    /// changes on its diff base lines.
+    fn update_staged_expanded_hunk(
+        &mut self,
+        hunk_range: &Range<Anchor>,
+        diff_base_range: Range<usize>,
+        stage: bool,
+        cx: &mut ViewContext<'_, Editor>,
+    ) {
+        let Some((_, buffer, _)) = self
+            .buffer()
+            .read(cx)
+            .excerpt_containing(hunk_range.start, cx)
+        else {
+            return;
+        };
+        let new_text = stage.then(|| {
+            buffer
+                .read(cx)
+                .text_for_range(hunk_range.start.text_anchor..hunk_range.end.text_anchor)
+                .collect::<String>()
+        });
+        self.update_staged_hunks(vec![(buffer, diff_base_range, new_text)], cx);
+    }
+
     pub(super) fn clear_expanded_diff_hunks(&mut self, cx: &mut ViewContext<'_, Editor>) {
         self.expanded_hunks.hunk_update_tasks.clear();
         let to_remove = self
@@ -613,6 +688,31 @@ fn editor_with_deleted_text(
                 }
             }),
         ]);
+        let update_staged_hunk = |stage: bool| {
+            let parent_editor = parent_editor.clone();
+            let hunk_range = hunk.multi_buffer_range.clone();
+            let diff_base_range = hunk.diff_base_byte_range.clone();
+            move |cx: &mut WindowContext| {
+                parent_editor
+                    .update(cx, |editor, cx| {
+                        editor.update_staged_expanded_hunk(
+                            &hunk_range,
+                            diff_base_range.clone(),
+                            stage,
+                            cx,
+                        )
+                    })
+                    .ok();
+            }
+        };
+        let stage_hunk = update_staged_hunk(true);
+        editor
+            .register_action::<StageSelectedHunks>(move |_, cx| stage_hunk(cx))
+            .detach();
+        let unstage_hunk = update_staged_hunk(false);
+        editor
+            .register_action::<UnstageSelectedHunks>(move |_, cx| unstage_hunk(cx))
+            .detach();
         let original_multi_buffer_range = hunk.multi_buffer_range.clone();
         let diff_base_range = hunk.diff_base_byte_range.clone();
         editor
@@ -0,0 +1,152 @@
+use std::{sync::Arc, time::Duration};
+
+use anyhow::Result;
+use clock::Global;
+use collections::HashMap;
+use gpui::{AppContext, Model, ModelContext, Task, ViewContext};
+use language::Buffer;
+use project::Project;
+use text::BufferId;
+use util::ResultExt;
+
+use crate::{Editor, EditorMode};
+
+/// The results of an LSP request, such as semantic tokens or code lenses, queried for every
+/// buffer shown in the editor.
+pub(super) struct LspBufferCache<T> {
+    buffers: HashMap<BufferId, CachedBufferResults<T>>,
+    refresh_task: Option<Task<Option<()>>>,
+}
+
+struct CachedBufferResults<T> {
+    /// Version of the buffer the results were queried for, `None` if the server asked for a refresh.
+    /// Any edit after that version makes the results stale, but they are still shown until the new ones arrive.
+    version: Option<Global>,
+    results: Arc<[T]>,
+}
+
+impl<T> Default for LspBufferCache<T> {
+    fn default() -> Self {
+        Self {
+            buffers: HashMap::default(),
+            refresh_task: None,
+        }
+    }
+}
+
+impl<T> LspBufferCache<T> {
+    /// Marks all cached results as stale, so that the next refresh queries them again.
+    pub(super) fn invalidate(&mut self) {
+        for cached in self.buffers.values_mut() {
+            cached.version = None;
+        }
+    }
+
+    /// The latest results queried for the buffer, even if they are stale.
+    pub(super) fn get(&self, buffer_id: BufferId) -> Option<&Arc<[T]>> {
+        self.buffers.get(&buffer_id).map(|cached| &cached.results)
+    }
+
+    fn needs_refresh(&self, buffer: &Buffer) -> bool {
+        self.buffers
+            .get(&buffer.remote_id())
+            .and_then(|cached| cached.version.as_ref())
+            .map_or(true, |version| buffer.version().changed_since(version))
+    }
+}
+
+/// How [`refresh_lsp_buffer_cache`] queries the results of one kind of LSP request, and shows them.
+pub(super) struct LspBufferRequest<T> {
+    /// The cache of the results in the editor.
+    pub cache: fn(&mut Editor) -> &mut LspBufferCache<T>,
+    /// Whether the results are enabled for the buffer by the settings.
+    pub enabled: fn(&Buffer, &AppContext) -> bool,
+    /// Queries the results for the buffer from the language servers.
+    pub query: fn(&Project, &Model<Buffer>, &mut ModelContext<Project>) -> Task<Result<Vec<T>>>,
+    /// Updates the editor to show the cached results.
+    pub update: fn(&mut Editor, &mut ViewContext<Editor>),
+    /// How long to wait for further edits before querying, when debouncing.
+    pub debounce_timeout: Duration,
+}
+
+/// Queries the results for all buffers whose cached results are missing or stale, and updates
+/// the editor afterwards.
+pub(super) fn refresh_lsp_buffer_cache<T: 'static>(
+    editor: &mut Editor,
+    request: &LspBufferRequest<T>,
+    debounce: bool,
+    cx: &mut ViewContext<Editor>,
+) {
+    if editor.mode != EditorMode::Full {
+        return;
+    }
+    let Some(project) = editor.project.clone() else {
+        return;
+    };
+
+    let buffers = editor.buffer.read(cx).all_buffers();
+    let enabled_buffer_ids = buffers
+        .iter()
+        .filter(|buffer| (request.enabled)(buffer.read(cx), cx))
+        .map(|buffer| buffer.read(cx).remote_id())
+        .collect::<Vec<_>>();
+    let cache = (request.cache)(editor);
+    cache
+        .buffers
+        .retain(|buffer_id, _| enabled_buffer_ids.contains(buffer_id));
+
+    let buffers_to_query = buffers
+        .into_iter()
+        .filter(|buffer| {
+            let buffer = buffer.read(cx);
+            enabled_buffer_ids.contains(&buffer.remote_id()) && cache.needs_refresh(buffer)
+        })
+        .collect::<Vec<_>>();
+    if buffers_to_query.is_empty() {
+        (request.update)(editor, cx);
+        return;
+    }
+
+    let cache = request.cache;
+    let query = request.query;
+    let update = request.update;
+    let debounce_timeout = debounce.then_some(request.debounce_timeout);
+    cache(editor).refresh_task = Some(cx.spawn(|editor, mut cx| async move {
+        if let Some(debounce_timeout) = debounce_timeout {
+            cx.background_executor().timer(debounce_timeout).await;
+        }
+
+        let queries = project
+            .update(&mut cx, |project, cx| {
+                buffers_to_query
+                    .into_iter()
+                    .map(|buffer| {
+                        let buffer_id = buffer.read(cx).remote_id();
+                        let version = buffer.read(cx).version();
+                        let results = query(project, &buffer, cx);
+                        async move { (buffer_id, version, results.await) }
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .log_err()?;
+        let responses = futures::future::join_all(queries).await;
+
+        editor
+            .update(&mut cx, |editor, cx| {
+                for (buffer_id, version, results) in responses {
+                    let Some(results) = results.log_err() else {
+                        continue;
+                    };
+                    cache(editor).buffers.insert(
+                        buffer_id,
+                        CachedBufferResults {
+                            version: Some(version),
+                            results: results.into(),
+                        },
+                    );
+                }
+                update(editor, cx);
+            })
+            .log_err()
+    }));
+}
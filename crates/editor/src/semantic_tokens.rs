@@ -0,0 +1,192 @@
+use std::time::Duration;
+
+use collections::HashMap;
+use gpui::ViewContext;
+use language::{language_settings::language_settings, HighlightId};
+use project::lsp_command::SemanticToken;
+use theme::{ActiveTheme, SyntaxTheme};
+
+use crate::{
+    lsp_buffer_cache::{refresh_lsp_buffer_cache, LspBufferCache, LspBufferRequest},
+    Editor,
+};
+
+/// Semantic tokens queried from the language servers for every buffer shown in the editor.
+pub(super) type SemanticTokensCache = LspBufferCache<SemanticToken>;
+
+const SEMANTIC_TOKENS_REQUEST: LspBufferRequest<SemanticToken> = LspBufferRequest {
+    cache: |editor| &mut editor.semantic_tokens_cache,
+    enabled: |buffer, cx| language_settings(buffer.language(), buffer.file(), cx).semantic_tokens,
+    query: |project, buffer, cx| project.semantic_tokens(buffer, cx),
+    update: update_semantic_token_highlights,
+    debounce_timeout: Duration::from_millis(150),
+};
+
+/// Queries semantic tokens for all buffers whose cached tokens are missing or stale, and updates the display map afterwards.
+pub(super) fn refresh_semantic_tokens(
+    editor: &mut Editor,
+    debounce: bool,
+    cx: &mut ViewContext<Editor>,
+) {
+    refresh_lsp_buffer_cache(editor, &SEMANTIC_TOKENS_REQUEST, debounce, cx);
+}
+
+/// Converts the cached tokens of every excerpt into syntax highlights of the display map.
+pub(super) fn update_semantic_token_highlights(editor: &mut Editor, cx: &mut ViewContext<Editor>) {
+    let syntax_theme = cx.theme().syntax().clone();
+    let snapshot = editor.buffer.read(cx).snapshot(cx);
+    let mut highlight_ids = HashMap::default();
+    let mut highlights = Vec::new();
+    for (excerpt_id, buffer_snapshot, excerpt_range) in snapshot.excerpts() {
+        let Some(tokens) = editor
+            .semantic_tokens_cache
+            .get(buffer_snapshot.remote_id())
+        else {
+            continue;
+        };
+
+        let excerpt_range = excerpt_range.context;
+        for token in tokens.iter() {
+            if token
+                .range
+                .end
+                .cmp(&excerpt_range.start, buffer_snapshot)
+                .is_le()
+            {
+                continue;
+            }
+            if token
+                .range
+                .start
+                .cmp(&excerpt_range.end, buffer_snapshot)
+                .is_ge()
+            {
+                break;
+            }
+
+            let highlight_id = *highlight_ids
+                .entry((token.token_type.as_str(), token.token_modifiers.as_slice()))
+                .or_insert_with(|| {
+                    semantic_token_highlight_name(&token.token_type, &token.token_modifiers)
+                        .and_then(|name| highlight_id_for_name(&syntax_theme, name))
+                });
+            let Some(highlight_id) = highlight_id else {
+                continue;
+            };
+            let (Some(start), Some(end)) = (
+                snapshot.anchor_in_excerpt(excerpt_id, token.range.start),
+                snapshot.anchor_in_excerpt(excerpt_id, token.range.end),
+            ) else {
+                continue;
+            };
+            highlights.push((start..end, highlight_id));
+        }
+    }
+
+    let changed = editor.display_map.update(cx, |display_map, _| {
+        if highlights.is_empty() {
+            display_map.clear_semantic_token_highlights()
+        } else {
+            display_map.set_semantic_token_highlights(highlights);
+            true
+        }
+    });
+    if changed {
+        cx.notify();
+    }
+}
+
+/// Maps an LSP semantic token type and its modifiers to the name of a [`SyntaxTheme`] highlight.
+///
+/// Both the standard LSP token types and the common extensions used by servers (e.g. rust-analyzer)
+/// are recognized; `None` is returned for tokens that should keep their tree-sitter highlight.
+fn semantic_token_highlight_name(
+    token_type: &str,
+    token_modifiers: &[String],
+) -> Option<&'static str> {
+    let has_modifier = |modifier: &str| token_modifiers.iter().any(|m| m == modifier);
+    let name = match token_type {
+        "namespace" => "namespace",
+        "type" | "class" | "struct" | "interface" | "union" | "typeAlias" | "typeParameter" => {
+            "type"
+        }
+        "builtinType" => "type.builtin",
+        "enum" => "enum",
+        "enumMember" => "variant",
+        "variable" | "parameter" if has_modifier("readonly") || has_modifier("constant") => {
+            "constant"
+        }
+        "variable" | "parameter" => "variable",
+        "selfKeyword" | "selfTypeKeyword" => "variable.special",
+        "property" | "event" => "property",
+        "function" if has_modifier("defaultLibrary") => "function.builtin",
+        "function" => "function",
+        "method" => "function.method",
+        "macro" | "derive" => "function.special",
+        "decorator" | "attribute" => "attribute",
+        "keyword" | "modifier" => "keyword",
+        "comment" if has_modifier("documentation") => "comment.doc",
+        "comment" => "comment",
+        "string" | "character" => "string",
+        "escapeSequence" => "string.escape",
+        "regexp" => "string.regex",
+        "number" => "number",
+        "boolean" => "boolean",
+        "operator" => "operator",
+        "label" | "lifetime" => "label",
+        _ => return None,
+    };
+    Some(name)
+}
+
+/// Looks the highlight up in the theme, falling back to less specific names (`function.method` -> `function`).
+fn highlight_id_for_name(syntax_theme: &SyntaxTheme, mut name: &str) -> Option<HighlightId> {
+    loop {
+        if let Some(id) = syntax_theme.highlight_id(name) {
+            return Some(HighlightId(id));
+        }
+        name = &name[..name.rfind('.')?];
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::Hsla;
+
+    #[test]
+    fn test_semantic_token_highlight_ids() {
+        let syntax_theme = SyntaxTheme::new_test([
+            ("comment", Hsla::default()),
+            ("comment.doc", Hsla::default()),
+            ("constant", Hsla::default()),
+            ("function", Hsla::default()),
+            ("type", Hsla::default()),
+            ("variable", Hsla::default()),
+        ]);
+        let highlight_name = |token_type: &str, modifiers: &[&str]| {
+            let modifiers = modifiers
+                .iter()
+                .map(|modifier| modifier.to_string())
+                .collect::<Vec<_>>();
+            semantic_token_highlight_name(token_type, &modifiers)
+                .and_then(|name| highlight_id_for_name(&syntax_theme, name))
+                .and_then(|id| id.name(&syntax_theme))
+        };
+
+        assert_eq!(highlight_name("method", &[]), Some("function"));
+        assert_eq!(highlight_name("macro", &[]), Some("function"));
+        assert_eq!(highlight_name("struct", &["declaration"]), Some("type"));
+        assert_eq!(highlight_name("builtinType", &[]), Some("type"));
+        assert_eq!(highlight_name("variable", &[]), Some("variable"));
+        assert_eq!(highlight_name("variable", &["readonly"]), Some("constant"));
+        assert_eq!(
+            highlight_name("comment", &["documentation"]),
+            Some("comment.doc")
+        );
+        assert_eq!(highlight_name("comment", &[]), Some("comment"));
+        // Known token types without a matching theme highlight keep the tree-sitter colors.
+        assert_eq!(highlight_name("enumMember", &[]), None);
+        assert_eq!(highlight_name("unresolvedReference", &[]), None);
+    }
+}
@@ -0,0 +1,122 @@
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use gpui::AppContext;
+use language::{CharKind, Point};
+use multi_buffer::MultiBufferRow;
+use project::WorktreeId;
+use time::OffsetDateTime;
+
+use crate::Editor;
+
+/// The values of the variables that can be used in snippets, such as `$TM_FILENAME`,
+/// captured from the editor's newest selection when inserting a snippet.
+pub(crate) struct SnippetVariables {
+    selected_text: String,
+    current_line: String,
+    current_word: Option<String>,
+    row: u32,
+    file_path: Option<PathBuf>,
+    relative_file_path: Option<Arc<Path>>,
+    worktree: Option<(String, Arc<Path>)>,
+    clipboard: Option<String>,
+    line_comment: Option<Arc<str>>,
+    block_comment: Option<(Arc<str>, Arc<str>)>,
+    now: OffsetDateTime,
+}
+
+impl SnippetVariables {
+    pub(crate) fn new(editor: &Editor, cx: &AppContext) -> Self {
+        let snapshot = editor.buffer.read(cx).snapshot(cx);
+        let selection = editor.selections.newest::<Point>(cx);
+        let head = selection.head();
+
+        let (word_range, word_kind) = snapshot.surrounding_word(head);
+        let current_word = (word_kind == Some(CharKind::Word))
+            .then(|| snapshot.text_for_range(word_range).collect::<String>());
+        let line_end = Point::new(head.row, snapshot.line_len(MultiBufferRow(head.row)));
+        let language_scope = snapshot.language_scope_at(head);
+
+        let file = editor
+            .buffer
+            .read(cx)
+            .text_anchor_for_position(selection.head(), cx)
+            .and_then(|(buffer, _)| buffer.read(cx).file().cloned());
+        let worktree = file.as_ref().and_then(|file| {
+            let worktree_id = WorktreeId::from_usize(file.worktree_id());
+            let worktree = editor
+                .project
+                .as_ref()?
+                .read(cx)
+                .worktree_for_id(worktree_id, cx)?;
+            let worktree = worktree.read(cx);
+            Some((worktree.root_name().to_string(), worktree.abs_path()))
+        });
+
+        Self {
+            selected_text: snapshot.text_for_range(selection.range()).collect(),
+            current_line: snapshot
+                .text_for_range(Point::new(head.row, 0)..line_end)
+                .collect(),
+            current_word,
+            row: head.row,
+            file_path: file.as_ref().map(|file| match file.as_local() {
+                Some(file) => file.abs_path(cx),
+                None => file.full_path(cx),
+            }),
+            relative_file_path: file.as_ref().map(|file| file.path().clone()),
+            worktree,
+            clipboard: cx.read_from_clipboard().map(|item| item.text().clone()),
+            line_comment: language_scope
+                .as_ref()
+                .and_then(|scope| scope.line_comment_prefixes().first().cloned()),
+            block_comment: language_scope
+                .as_ref()
+                .and_then(|scope| scope.block_comment_delimiters())
+                .map(|(start, end)| (start.clone(), end.clone())),
+            now: OffsetDateTime::now_utc().to_offset(cx.local_timezone()),
+        }
+    }
+
+    /// Returns the value of the variable, or `None` if it is unknown or has no value.
+    pub(crate) fn resolve(&self, name: &str) -> Option<String> {
+        let path_to_string = |path: &Path| path.to_string_lossy().into_owned();
+        let file_path = || self.file_path.as_deref();
+        let value = match name {
+            "TM_SELECTED_TEXT" => self.selected_text.clone(),
+            "TM_CURRENT_LINE" => self.current_line.clone(),
+            "TM_CURRENT_WORD" => self.current_word.clone()?,
+            "TM_LINE_INDEX" => self.row.to_string(),
+            "TM_LINE_NUMBER" => (self.row + 1).to_string(),
+            "TM_FILENAME" => path_to_string(file_path()?.file_name()?.as_ref()),
+            "TM_FILENAME_BASE" => path_to_string(file_path()?.file_stem()?.as_ref()),
+            "TM_DIRECTORY" => path_to_string(file_path()?.parent()?),
+            "TM_FILEPATH" => path_to_string(file_path()?),
+            "RELATIVE_FILEPATH" => path_to_string(self.relative_file_path.as_deref()?),
+            "WORKSPACE_NAME" => self.worktree.as_ref()?.0.clone(),
+            "WORKSPACE_FOLDER" => path_to_string(&self.worktree.as_ref()?.1),
+            "CLIPBOARD" => self.clipboard.clone()?,
+            "CURRENT_YEAR" => self.now.year().to_string(),
+            "CURRENT_YEAR_SHORT" => format!("{:02}", self.now.year() % 100),
+            "CURRENT_MONTH" => format!("{:02}", u8::from(self.now.month())),
+            "CURRENT_MONTH_NAME" => self.now.month().to_string(),
+            "CURRENT_MONTH_NAME_SHORT" => self.now.month().to_string()[..3].to_string(),
+            "CURRENT_DATE" => format!("{:02}", self.now.day()),
+            "CURRENT_DAY_NAME" => self.now.weekday().to_string(),
+            "CURRENT_DAY_NAME_SHORT" => self.now.weekday().to_string()[..3].to_string(),
+            "CURRENT_HOUR" => format!("{:02}", self.now.hour()),
+            "CURRENT_MINUTE" => format!("{:02}", self.now.minute()),
+            "CURRENT_SECOND" => format!("{:02}", self.now.second()),
+            "CURRENT_SECONDS_UNIX" => self.now.unix_timestamp().to_string(),
+            "RANDOM" => format!("{:06}", rand::random::<u32>() % 1_000_000),
+            "RANDOM_HEX" => format!("{:06x}", rand::random::<u32>() & 0xff_ffff),
+            "LINE_COMMENT" => self.line_comment.as_deref()?.trim_end().to_string(),
+            "BLOCK_COMMENT_START" => self.block_comment.as_ref()?.0.trim_end().to_string(),
+            "BLOCK_COMMENT_END" => self.block_comment.as_ref()?.1.trim_start().to_string(),
+            _ => return None,
+        };
+        Some(value)
+    }
+}
@@ -0,0 +1,60 @@
+use std::{ops::Range, path::Path, sync::Arc};
+
+use anyhow::{anyhow, Result};
+use futures::FutureExt;
+use gpui::{AppContext, Task};
+use language::{BufferFormatter, FormattingOptions};
+
+use crate::wasm_host::{wit, WasmExtension};
+
+pub struct ExtensionFormatter {
+    pub(crate) extension: WasmExtension,
+    pub(crate) name: Arc<str>,
+}
+
+impl BufferFormatter for ExtensionFormatter {
+    fn name(&self) -> Arc<str> {
+        self.name.clone()
+    }
+
+    fn format(
+        &self,
+        text: String,
+        path: &Path,
+        options: FormattingOptions,
+        cx: &AppContext,
+    ) -> Task<Result<Vec<(Range<usize>, String)>>> {
+        let extension = self.extension.clone();
+        let name = self.name.clone();
+        let path = path.to_string_lossy().to_string();
+        let options = wit::FormattingOptions {
+            tab_size: options.tab_size,
+            hard_tabs: options.hard_tabs,
+        };
+        cx.background_executor().spawn(async move {
+            let edits = extension
+                .call(|extension, store| {
+                    async move {
+                        let edits = extension
+                            .call_format_buffer(store, &name, &text, &path, options)
+                            .await?
+                            .map_err(|e| anyhow!("{}", e))?;
+
+                        anyhow::Ok(edits)
+                    }
+                    .boxed()
+                })
+                .await?;
+
+            Ok(edits
+                .into_iter()
+                .map(|edit| {
+                    (
+                        edit.range.start as usize..edit.range.end as usize,
+                        edit.new_text,
+                    )
+                })
+                .collect())
+        })
+    }
+}
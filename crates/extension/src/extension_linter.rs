@@ -0,0 +1,62 @@
+use std::{path::Path, sync::Arc};
+
+use anyhow::{anyhow, Result};
+use futures::FutureExt;
+use gpui::{AppContext, Task};
+use language::{BufferLinter, LintDiagnostic};
+
+use crate::wasm_host::{wit, WasmExtension};
+
+pub struct ExtensionLinter {
+    pub(crate) extension: WasmExtension,
+    pub(crate) name: Arc<str>,
+}
+
+impl BufferLinter for ExtensionLinter {
+    fn name(&self) -> Arc<str> {
+        self.name.clone()
+    }
+
+    fn lint(
+        &self,
+        text: String,
+        path: &Path,
+        cx: &AppContext,
+    ) -> Task<Result<Vec<LintDiagnostic>>> {
+        let extension = self.extension.clone();
+        let name = self.name.clone();
+        let path = path.to_string_lossy().to_string();
+        cx.background_executor().spawn(async move {
+            let diagnostics = extension
+                .call(|extension, store| {
+                    async move {
+                        let diagnostics = extension
+                            .call_lint(store, &name, &text, &path)
+                            .await?
+                            .map_err(|e| anyhow!("{}", e))?;
+
+                        anyhow::Ok(diagnostics)
+                    }
+                    .boxed()
+                })
+                .await?;
+
+            Ok(diagnostics
+                .into_iter()
+                .map(|diagnostic| LintDiagnostic {
+                    range: diagnostic.range.start as usize..diagnostic.range.end as usize,
+                    severity: match diagnostic.severity {
+                        wit::DiagnosticSeverity::Error => lsp::DiagnosticSeverity::ERROR,
+                        wit::DiagnosticSeverity::Warning => lsp::DiagnosticSeverity::WARNING,
+                        wit::DiagnosticSeverity::Information => {
+                            lsp::DiagnosticSeverity::INFORMATION
+                        }
+                        wit::DiagnosticSeverity::Hint => lsp::DiagnosticSeverity::HINT,
+                    },
+                    message: diagnostic.message,
+                    code: diagnostic.code,
+                })
+                .collect())
+        })
+    }
+}
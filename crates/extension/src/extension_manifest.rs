@@ -2,6 +2,7 @@ use anyhow::{anyhow, Context, Result};
 use collections::{BTreeMap, HashMap};
 use fs::Fs;
 use language::LanguageServerName;
+use schemars::JsonSchema;
 use semantic_version::SemanticVersion;
 use serde::{Deserialize, Serialize};
 use std::{
@@ -80,6 +81,14 @@ pub struct ExtensionManifest {
     pub indexed_docs_providers: BTreeMap<Arc<str>, IndexedDocsProviderEntry>,
     #[serde(default)]
     pub snippets: Option<PathBuf>,
+    #[serde(default)]
+    pub tasks: Option<TasksManifestEntry>,
+    #[serde(default)]
+    pub formatters: BTreeMap<Arc<str>, FormatterManifestEntry>,
+    #[serde(default)]
+    pub linters: BTreeMap<Arc<str>, LinterManifestEntry>,
+    #[serde(default)]
+    pub capabilities: Vec<ExtensionCapability>,
 }
 
 #[derive(Clone, Default, PartialEq, Eq, Debug, Deserialize, Serialize)]
@@ -144,6 +153,82 @@ pub struct SlashCommandManifestEntry {
 #[derive(Clone, PartialEq, Eq, Debug, Deserialize, Serialize)]
 pub struct IndexedDocsProviderEntry {}
 
+/// Declares that the extension provides tasks for worktrees, and which task variables it provides
+/// for running them.
+#[derive(Clone, Default, PartialEq, Eq, Debug, Deserialize, Serialize)]
+pub struct TasksManifestEntry {
+    /// The names of the custom task variables the extension provides, without their `ZED_CUSTOM_` prefix.
+    /// Other variables the extension returns are ignored.
+    #[serde(default)]
+    pub variables: Vec<Arc<str>>,
+}
+
+/// Declares a formatter that the extension provides for the buffers of the given languages.
+#[derive(Clone, Default, PartialEq, Eq, Debug, Deserialize, Serialize)]
+pub struct FormatterManifestEntry {
+    pub languages: Vec<Arc<str>>,
+}
+
+/// Declares a linter that the extension provides for the buffers of the given languages.
+#[derive(Clone, Default, PartialEq, Eq, Debug, Deserialize, Serialize)]
+pub struct LinterManifestEntry {
+    pub languages: Vec<Arc<str>>,
+}
+
+/// A capability that an extension declares it needs, and that the user has to grant it.
+#[derive(Clone, PartialEq, Eq, Debug, Deserialize, Serialize, JsonSchema)]
+#[serde(tag = "kind")]
+pub enum ExtensionCapability {
+    /// Running a command as a subprocess.
+    #[serde(rename = "process:exec")]
+    ProcessExec(ProcessExecCapability),
+}
+
+impl fmt::Display for ExtensionCapability {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ProcessExec(capability) => {
+                write!(f, "run `{}", capability.command)?;
+                for arg in &capability.args {
+                    write!(f, " {arg}")?;
+                }
+                write!(f, "`")
+            }
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Debug, Deserialize, Serialize, JsonSchema)]
+pub struct ProcessExecCapability {
+    /// The command that may be run, either the name of a binary on the `$PATH` or an absolute path.
+    pub command: String,
+    /// The patterns that the arguments must match, one per argument.
+    ///
+    /// A `*` pattern matches any single argument, and a `**` pattern matches all of the remaining ones.
+    #[serde(default)]
+    pub args: Vec<String>,
+}
+
+impl ProcessExecCapability {
+    /// Returns whether the capability allows running the given command with the given arguments.
+    pub fn allows(&self, command: &str, args: &[String]) -> bool {
+        if self.command != command {
+            return false;
+        }
+
+        let mut args = args.iter();
+        for pattern in &self.args {
+            match (pattern.as_str(), args.next()) {
+                ("**", _) => return true,
+                ("*", Some(_)) => {}
+                (pattern, Some(arg)) if pattern == arg => {}
+                _ => return false,
+            }
+        }
+        args.next().is_none()
+    }
+}
+
 impl ExtensionManifest {
     pub async fn load(fs: Arc<dyn Fs>, extension_dir: &Path) -> Result<Self> {
         let extension_name = extension_dir
@@ -209,5 +294,9 @@ fn manifest_from_old_manifest(
         slash_commands: BTreeMap::default(),
         indexed_docs_providers: BTreeMap::default(),
         snippets: None,
+        tasks: None,
+        formatters: BTreeMap::default(),
+        linters: BTreeMap::default(),
+        capabilities: Vec::new(),
     }
 }
@@ -1,3 +1,4 @@
+use crate::extension_manifest::ExtensionCapability;
 use anyhow::Result;
 use collections::HashMap;
 use gpui::AppContext;
@@ -14,8 +15,52 @@ pub struct ExtensionSettings {
     /// available out-of-the-box.
     #[serde(default)]
     pub auto_install_extensions: HashMap<Arc<str>, bool>,
+    /// How new versions of each extension should be handled, keyed by extension ID.
+    ///
+    /// Either a boolean or one of `"off"`, `"notify"` or `"on"`.
+    #[serde(default)]
+    pub auto_update_extensions: HashMap<Arc<str>, ExtensionAutoUpdateSetting>,
+    /// The versions that extensions are pinned to, keyed by extension ID.
+    ///
+    /// A pinned extension is kept at the given version and is never updated automatically.
     #[serde(default)]
-    pub auto_update_extensions: HashMap<Arc<str>, bool>,
+    pub pinned_extension_versions: HashMap<Arc<str>, Arc<str>>,
+    /// The capabilities that the user granted to extensions, keyed by extension ID.
+    ///
+    /// An extension can only use the capabilities that it declares in its manifest and that are granted here.
+    #[serde(default)]
+    pub granted_extension_capabilities: HashMap<Arc<str>, Vec<ExtensionCapability>>,
+}
+
+/// How Zed handles new versions of an installed extension.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ExtensionAutoUpdatePolicy {
+    /// Don't check for new versions of the extension.
+    Off,
+    /// Check for new versions of the extension, and notify the user when one is available.
+    Notify,
+    /// Install new versions of the extension automatically.
+    #[default]
+    On,
+}
+
+/// An entry of `auto_update_extensions`, which is either a boolean or an [`ExtensionAutoUpdatePolicy`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(untagged)]
+pub enum ExtensionAutoUpdateSetting {
+    Enabled(bool),
+    Policy(ExtensionAutoUpdatePolicy),
+}
+
+impl From<ExtensionAutoUpdateSetting> for ExtensionAutoUpdatePolicy {
+    fn from(setting: ExtensionAutoUpdateSetting) -> Self {
+        match setting {
+            ExtensionAutoUpdateSetting::Enabled(true) => Self::On,
+            ExtensionAutoUpdateSetting::Enabled(false) => Self::Off,
+            ExtensionAutoUpdateSetting::Policy(policy) => policy,
+        }
+    }
 }
 
 impl ExtensionSettings {
@@ -27,11 +72,30 @@ impl ExtensionSettings {
             .unwrap_or(true)
     }
 
-    pub fn should_auto_update(&self, extension_id: &str) -> bool {
+    /// Returns how new versions of the given extension should be handled.
+    pub fn auto_update_policy(&self, extension_id: &str) -> ExtensionAutoUpdatePolicy {
         self.auto_update_extensions
             .get(extension_id)
             .copied()
-            .unwrap_or(true)
+            .map_or(ExtensionAutoUpdatePolicy::On, Into::into)
+    }
+
+    /// Returns whether the given extension should be checked for new versions.
+    pub fn should_auto_update(&self, extension_id: &str) -> bool {
+        self.pinned_version(extension_id).is_none()
+            && self.auto_update_policy(extension_id) != ExtensionAutoUpdatePolicy::Off
+    }
+
+    /// Returns the version that the given extension is pinned to, if any.
+    pub fn pinned_version(&self, extension_id: &str) -> Option<&Arc<str>> {
+        self.pinned_extension_versions.get(extension_id)
+    }
+
+    /// Returns the capabilities that the user granted to the given extension.
+    pub fn granted_capabilities(&self, extension_id: &str) -> &[ExtensionCapability] {
+        self.granted_extension_capabilities
+            .get(extension_id)
+            .map_or(&[], Vec::as_slice)
     }
 }
 
@@ -1,17 +1,24 @@
 pub mod extension_builder;
+mod extension_formatter;
 mod extension_indexed_docs_provider;
+mod extension_linter;
 mod extension_lsp_adapter;
 mod extension_manifest;
 mod extension_settings;
 mod extension_slash_command;
+mod extension_task_provider;
+pub mod extension_test_runner;
 mod wasm_host;
 
 #[cfg(test)]
 mod extension_store_test;
 
+use crate::extension_formatter::ExtensionFormatter;
 use crate::extension_indexed_docs_provider::ExtensionIndexedDocsProvider;
+use crate::extension_linter::ExtensionLinter;
 use crate::extension_manifest::SchemaVersion;
 use crate::extension_slash_command::ExtensionSlashCommand;
+use crate::extension_task_provider::ExtensionTaskProvider;
 use crate::{extension_lsp_adapter::ExtensionLspAdapter, wasm_host::wit};
 use anyhow::{anyhow, bail, Context as _, Result};
 use assistant_slash_command::SlashCommandRegistry;
@@ -20,7 +27,7 @@ use async_tar::Archive;
 use client::{telemetry::Telemetry, Client, ExtensionMetadata, GetExtensionsResponse};
 use collections::{btree_map, BTreeMap, HashSet};
 use extension_builder::{CompileExtensionOptions, ExtensionBuilder};
-use fs::{Fs, RemoveOptions};
+use fs::{Fs, RemoveOptions, RenameOptions};
 use futures::{
     channel::{
         mpsc::{unbounded, UnboundedSender},
@@ -39,11 +46,12 @@ use language::{
     LanguageConfig, LanguageMatcher, LanguageQueries, LanguageRegistry, QUERY_FILENAME_PREFIXES,
 };
 use node_runtime::NodeRuntime;
-use project::ContextProviderWithTasks;
+use project::{ContextProviderWithTasks, TaskProviderRegistry};
 use release_channel::ReleaseChannel;
 use semantic_version::SemanticVersion;
 use serde::{Deserialize, Serialize};
 use settings::Settings;
+use similar::{ChangeTag, TextDiff};
 use snippet_provider::SnippetRegistry;
 use std::ops::RangeInclusive;
 use std::str::FromStr;
@@ -62,9 +70,12 @@ use wasm_host::{
 };
 
 pub use extension_manifest::{
-    ExtensionLibraryKind, ExtensionManifest, GrammarManifestEntry, OldExtensionManifest,
+    ExtensionCapability, ExtensionLibraryKind, ExtensionManifest, GrammarManifestEntry,
+    OldExtensionManifest, ProcessExecCapability,
+};
+pub use extension_settings::{
+    ExtensionAutoUpdatePolicy, ExtensionAutoUpdateSetting, ExtensionSettings,
 };
-pub use extension_settings::ExtensionSettings;
 
 const RELOAD_DEBOUNCE_DURATION: Duration = Duration::from_millis(200);
 const FS_WATCH_LATENCY: Duration = Duration::from_millis(100);
@@ -101,6 +112,19 @@ pub fn is_version_compatible(
     true
 }
 
+/// Returns the lines of the `new` changelog that aren't present in the `old` one.
+pub fn changelog_diff(old: Option<&str>, new: &str) -> String {
+    let Some(old) = old else {
+        return new.to_string();
+    };
+
+    TextDiff::from_lines(old, new)
+        .iter_all_changes()
+        .filter(|change| change.tag() == ChangeTag::Insert)
+        .map(|change| change.value())
+        .collect()
+}
+
 pub struct ExtensionStore {
     builder: Arc<ExtensionBuilder>,
     extension_index: ExtensionIndex,
@@ -110,6 +134,9 @@ pub struct ExtensionStore {
     reload_tx: UnboundedSender<Option<Arc<str>>>,
     reload_complete_senders: Vec<oneshot::Sender<()>>,
     installed_dir: PathBuf,
+    previous_dir: PathBuf,
+    previous_versions: BTreeMap<Arc<str>, Arc<str>>,
+    available_updates: BTreeMap<Arc<str>, ExtensionMetadata>,
     outstanding_operations: BTreeMap<Arc<str>, ExtensionOperation>,
     index_path: PathBuf,
     language_registry: Arc<LanguageRegistry>,
@@ -117,6 +144,7 @@ pub struct ExtensionStore {
     slash_command_registry: Arc<SlashCommandRegistry>,
     indexed_docs_registry: Arc<IndexedDocsRegistry>,
     snippet_registry: Arc<SnippetRegistry>,
+    task_provider_registry: Arc<TaskProviderRegistry>,
     modified_extensions: HashSet<Arc<str>>,
     wasm_host: Arc<WasmHost>,
     wasm_extensions: Vec<(Arc<ExtensionManifest>, WasmExtension)>,
@@ -128,6 +156,7 @@ pub enum ExtensionOperation {
     Upgrade,
     Install,
     Remove,
+    Rollback,
 }
 
 #[derive(Clone)]
@@ -136,6 +165,7 @@ pub enum Event {
     StartedReloading,
     ExtensionInstalled(Arc<str>),
     ExtensionFailedToLoad(Arc<str>),
+    ExtensionUpdateAvailable(Arc<str>),
 }
 
 impl EventEmitter<Event> for ExtensionStore {}
@@ -196,6 +226,7 @@ pub fn init(
             SlashCommandRegistry::global(cx),
             IndexedDocsRegistry::global(cx),
             SnippetRegistry::global(cx),
+            TaskProviderRegistry::default_global(cx),
             cx,
         )
     });
@@ -231,17 +262,22 @@ impl ExtensionStore {
         slash_command_registry: Arc<SlashCommandRegistry>,
         indexed_docs_registry: Arc<IndexedDocsRegistry>,
         snippet_registry: Arc<SnippetRegistry>,
+        task_provider_registry: Arc<TaskProviderRegistry>,
         cx: &mut ModelContext<Self>,
     ) -> Self {
         let work_dir = extensions_dir.join("work");
         let build_dir = build_dir.unwrap_or_else(|| extensions_dir.join("build"));
         let installed_dir = extensions_dir.join("installed");
+        let previous_dir = extensions_dir.join("previous");
         let index_path = extensions_dir.join("index.json");
 
         let (reload_tx, mut reload_rx) = unbounded();
         let mut this = Self {
             extension_index: Default::default(),
             installed_dir,
+            previous_dir,
+            previous_versions: Default::default(),
+            available_updates: Default::default(),
             index_path,
             builder: Arc::new(ExtensionBuilder::new(build_dir)),
             outstanding_operations: Default::default(),
@@ -264,6 +300,7 @@ impl ExtensionStore {
             slash_command_registry,
             indexed_docs_registry,
             snippet_registry,
+            task_provider_registry,
             reload_tx,
             tasks: Vec::new(),
         };
@@ -306,10 +343,12 @@ impl ExtensionStore {
             reload_future = Some(this.reload(None, cx));
         }
 
+        let load_previous_versions = this.load_previous_versions(cx);
         cx.spawn(|this, mut cx| async move {
             if let Some(future) = reload_future {
                 future.await;
             }
+            load_previous_versions.await;
             this.update(&mut cx, |this, cx| this.auto_install_extensions(cx))
                 .ok();
             this.update(&mut cx, |this, cx| this.check_for_updates(cx))
@@ -414,6 +453,39 @@ impl ExtensionStore {
         &self.extension_index.extensions
     }
 
+    /// Returns the version of the given extension that was installed before its last update,
+    /// if it is still available for a rollback.
+    pub fn previous_version(&self, extension_id: &str) -> Option<&Arc<str>> {
+        self.previous_versions.get(extension_id)
+    }
+
+    /// Returns the newer version of the given extension that was found while checking for
+    /// updates, if its auto-update policy is to only notify the user.
+    pub fn available_update(&self, extension_id: &str) -> Option<&ExtensionMetadata> {
+        self.available_updates.get(extension_id)
+    }
+
+    /// Returns the capabilities that the given installed extension declares in its manifest,
+    /// but that the user has not granted it.
+    pub fn ungranted_capabilities(
+        &self,
+        extension_id: &str,
+        cx: &AppContext,
+    ) -> Vec<ExtensionCapability> {
+        let Some(extension) = self.extension_index.extensions.get(extension_id) else {
+            return Vec::new();
+        };
+        let granted_capabilities =
+            ExtensionSettings::get_global(cx).granted_capabilities(extension_id);
+        extension
+            .manifest
+            .capabilities
+            .iter()
+            .filter(|capability| !granted_capabilities.contains(capability))
+            .cloned()
+            .collect()
+    }
+
     pub fn dev_extensions(&self) -> impl Iterator<Item = &Arc<ExtensionManifest>> {
         self.extension_index
             .extensions
@@ -501,6 +573,39 @@ impl ExtensionStore {
         self.fetch_extensions_from_api(&format!("/extensions/{extension_id}"), &[], cx)
     }
 
+    /// Fetches the changelog entries that were added between the installed version of the given
+    /// extension and the given version.
+    ///
+    /// Returns `None` if the given version doesn't provide a changelog.
+    pub fn fetch_changelog_diff(
+        &self,
+        extension_id: &str,
+        version: Arc<str>,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<Option<String>>> {
+        let installed_version = self
+            .extension_index
+            .extensions
+            .get(extension_id)
+            .map(|extension| extension.manifest.version.clone());
+        let versions = self.fetch_extension_versions(extension_id, cx);
+        cx.background_executor().spawn(async move {
+            let versions = versions.await?;
+            let changelog = |version: &str| {
+                versions
+                    .iter()
+                    .find(|extension| extension.manifest.version.as_ref() == version)
+                    .and_then(|extension| extension.manifest.changelog.as_deref())
+            };
+
+            let Some(new_changelog) = changelog(&version) else {
+                return Ok(None);
+            };
+            let old_changelog = installed_version.as_deref().and_then(changelog);
+            Ok(Some(changelog_diff(old_changelog, new_changelog)))
+        })
+    }
+
     /// Installs any extensions that should be included with Zed by default.
     ///
     /// This can be used to make certain functionality provided by extensions
@@ -534,6 +639,8 @@ impl ExtensionStore {
     }
 
     pub fn check_for_updates(&mut self, cx: &mut ModelContext<Self>) {
+        self.install_pinned_versions(cx);
+
         let task = self.fetch_extensions_with_update_available(cx);
         cx.spawn(move |this, mut cx| async move {
             Self::upgrade_extensions(this, task.await?, &mut cx).await
@@ -561,7 +668,25 @@ impl ExtensionStore {
                     }
                 }
 
-                Some(this.upgrade_extension(extension.id, extension.manifest.version, cx))
+                let extension_settings = ExtensionSettings::get_global(cx);
+                if extension_settings.pinned_version(&extension.id).is_some() {
+                    return None;
+                }
+
+                match extension_settings.auto_update_policy(&extension.id) {
+                    ExtensionAutoUpdatePolicy::Off => None,
+                    ExtensionAutoUpdatePolicy::Notify => {
+                        let extension_id = extension.id.clone();
+                        this.available_updates
+                            .insert(extension_id.clone(), extension);
+                        cx.emit(Event::ExtensionUpdateAvailable(extension_id));
+                        cx.notify();
+                        None
+                    }
+                    ExtensionAutoUpdatePolicy::On => {
+                        Some(this.upgrade_extension(extension.id, extension.manifest.version, cx))
+                    }
+                }
             })?;
 
             if let Some(task) = task {
@@ -571,6 +696,33 @@ impl ExtensionStore {
         anyhow::Ok(())
     }
 
+    /// Installs the pinned version of every installed extension that is currently at a
+    /// different version.
+    fn install_pinned_versions(&mut self, cx: &mut ModelContext<Self>) {
+        let extension_settings = ExtensionSettings::get_global(cx);
+        let extensions_to_install = self
+            .extension_index
+            .extensions
+            .iter()
+            .filter(|(_, extension)| !extension.dev)
+            .filter_map(|(extension_id, extension)| {
+                let pinned_version = extension_settings.pinned_version(extension_id)?;
+                (*pinned_version != extension.manifest.version)
+                    .then(|| (extension_id.clone(), pinned_version.clone()))
+            })
+            .collect::<Vec<_>>();
+
+        for (extension_id, version) in extensions_to_install {
+            if self.previous_versions.get(&extension_id) == Some(&version) {
+                self.rollback_extension(extension_id, cx)
+                    .detach_and_log_err(cx);
+            } else {
+                self.upgrade_extension(extension_id, version, cx)
+                    .detach_and_log_err(cx);
+            }
+        }
+    }
+
     fn fetch_extensions_from_api(
         &self,
         path: &str,
@@ -622,6 +774,15 @@ impl ExtensionStore {
         cx: &mut ModelContext<Self>,
     ) -> Task<Result<()>> {
         let extension_dir = self.installed_dir.join(extension_id.as_ref());
+        let previous_dir = self.previous_dir.clone();
+        let previous_extension_dir = previous_dir.join(extension_id.as_ref());
+        let download_dir = previous_dir.join(format!(".{extension_id}.download"));
+        let previous_version = self
+            .extension_index
+            .extensions
+            .get(&extension_id)
+            .filter(|extension| !extension.dev)
+            .map(|extension| extension.manifest.version.clone());
         let http_client = self.http_client.clone();
         let fs = self.fs.clone();
 
@@ -650,15 +811,6 @@ impl ExtensionStore {
                 .await
                 .map_err(|err| anyhow!("error downloading extension: {}", err))?;
 
-            fs.remove_dir(
-                &extension_dir,
-                RemoveOptions {
-                    recursive: true,
-                    ignore_if_not_exists: true,
-                },
-            )
-            .await?;
-
             let content_length = response
                 .headers()
                 .get(isahc::http::header::CONTENT_LENGTH)
@@ -674,10 +826,69 @@ impl ExtensionStore {
                     bail!("downloaded extension size {actual_len} does not match content length {content_length}");
                 }
             }
+
+            // Unpack the new version next to the installed one first, so that a broken
+            // download leaves the installed version in place.
+            fs.remove_dir(
+                &download_dir,
+                RemoveOptions {
+                    recursive: true,
+                    ignore_if_not_exists: true,
+                },
+            )
+            .await?;
+            fs.create_dir(&previous_dir).await?;
             let decompressed_bytes = GzipDecoder::new(BufReader::new(tar_gz_bytes.as_slice()));
             let archive = Archive::new(decompressed_bytes);
-            archive.unpack(extension_dir).await?;
+            let unpack_result = archive.unpack(&download_dir).await;
+            if unpack_result.is_err() {
+                fs.remove_dir(
+                    &download_dir,
+                    RemoveOptions {
+                        recursive: true,
+                        ignore_if_not_exists: true,
+                    },
+                )
+                .await
+                .log_err();
+            }
+            unpack_result?;
+
+            // Keep the currently installed version around, so that it can be rolled back to.
+            if let Some(previous_version) = previous_version {
+                fs.remove_dir(
+                    &previous_extension_dir,
+                    RemoveOptions {
+                        recursive: true,
+                        ignore_if_not_exists: true,
+                    },
+                )
+                .await?;
+                fs.rename(
+                    &extension_dir,
+                    &previous_extension_dir,
+                    RenameOptions::default(),
+                )
+                .await?;
+                this.update(&mut cx, |this, _| {
+                    this.previous_versions
+                        .insert(extension_id.clone(), previous_version);
+                })?;
+            } else {
+                fs.remove_dir(
+                    &extension_dir,
+                    RemoveOptions {
+                        recursive: true,
+                        ignore_if_not_exists: true,
+                    },
+                )
+                .await?;
+            }
+            fs.rename(&download_dir, &extension_dir, RenameOptions::default())
+                .await?;
+
             this.update(&mut cx, |this, cx| {
+                this.available_updates.remove(&extension_id);
                 this.reload(Some(extension_id.clone()), cx)
             })?
             .await;
@@ -767,6 +978,7 @@ impl ExtensionStore {
 
     pub fn uninstall_extension(&mut self, extension_id: Arc<str>, cx: &mut ModelContext<Self>) {
         let extension_dir = self.installed_dir.join(extension_id.as_ref());
+        let previous_extension_dir = self.previous_dir.join(extension_id.as_ref());
         let fs = self.fs.clone();
 
         match self.outstanding_operations.entry(extension_id.clone()) {
@@ -796,14 +1008,141 @@ impl ExtensionStore {
                 },
             )
             .await?;
+            fs.remove_dir(
+                &previous_extension_dir,
+                RemoveOptions {
+                    recursive: true,
+                    ignore_if_not_exists: true,
+                },
+            )
+            .await?;
 
-            this.update(&mut cx, |this, cx| this.reload(None, cx))?
-                .await;
+            this.update(&mut cx, |this, cx| {
+                this.previous_versions.remove(&extension_id);
+                this.available_updates.remove(&extension_id);
+                this.reload(None, cx)
+            })?
+            .await;
             anyhow::Ok(())
         })
         .detach_and_log_err(cx)
     }
 
+    /// Swaps the installed version of the given extension with the version that was installed
+    /// before its last update.
+    pub fn rollback_extension(
+        &mut self,
+        extension_id: Arc<str>,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<()>> {
+        let Some(previous_version) = self.previous_versions.get(&extension_id).cloned() else {
+            return Task::ready(Err(anyhow!(
+                "no previous version of extension {extension_id} to roll back to"
+            )));
+        };
+        let installed_version = match self.extension_index.extensions.get(&extension_id) {
+            Some(extension) if extension.dev => {
+                return Task::ready(Err(anyhow!(
+                    "cannot roll back dev extension {extension_id}"
+                )));
+            }
+            Some(extension) => Some(extension.manifest.version.clone()),
+            None => None,
+        };
+
+        let extension_dir = self.installed_dir.join(extension_id.as_ref());
+        let previous_extension_dir = self.previous_dir.join(extension_id.as_ref());
+        let swap_dir = self.previous_dir.join(format!(".{extension_id}"));
+        let fs = self.fs.clone();
+
+        match self.outstanding_operations.entry(extension_id.clone()) {
+            btree_map::Entry::Occupied(_) => return Task::ready(Ok(())),
+            btree_map::Entry::Vacant(e) => e.insert(ExtensionOperation::Rollback),
+        };
+        cx.notify();
+
+        log::info!("rolling back extension {extension_id} to {previous_version}");
+        cx.spawn(move |this, mut cx| async move {
+            let _finish = util::defer({
+                let this = this.clone();
+                let mut cx = cx.clone();
+                let extension_id = extension_id.clone();
+                move || {
+                    this.update(&mut cx, |this, cx| {
+                        this.outstanding_operations.remove(extension_id.as_ref());
+                        cx.notify();
+                    })
+                    .ok();
+                }
+            });
+
+            if installed_version.is_some() {
+                fs.rename(&extension_dir, &swap_dir, RenameOptions::default())
+                    .await?;
+            }
+            fs.rename(
+                &previous_extension_dir,
+                &extension_dir,
+                RenameOptions::default(),
+            )
+            .await?;
+            if installed_version.is_some() {
+                fs.rename(&swap_dir, &previous_extension_dir, RenameOptions::default())
+                    .await?;
+            }
+
+            this.update(&mut cx, |this, cx| {
+                match installed_version {
+                    Some(installed_version) => {
+                        this.previous_versions
+                            .insert(extension_id.clone(), installed_version);
+                    }
+                    None => {
+                        this.previous_versions.remove(&extension_id);
+                    }
+                }
+                this.reload(Some(extension_id), cx)
+            })?
+            .await;
+            anyhow::Ok(())
+        })
+    }
+
+    /// Loads the versions of the extensions that were installed before their last update.
+    fn load_previous_versions(&mut self, cx: &mut ModelContext<Self>) -> Task<()> {
+        let fs = self.fs.clone();
+        let previous_dir = self.previous_dir.clone();
+        cx.spawn(|this, mut cx| async move {
+            let mut previous_versions = BTreeMap::default();
+            if let Ok(mut extension_paths) = fs.read_dir(&previous_dir).await {
+                while let Some(extension_path) = extension_paths.next().await {
+                    let Ok(extension_path) = extension_path else {
+                        continue;
+                    };
+                    let Some(extension_id) = extension_path
+                        .file_name()
+                        .and_then(|file_name| file_name.to_str())
+                        .filter(|file_name| !file_name.starts_with('.'))
+                    else {
+                        continue;
+                    };
+                    if let Some(manifest) = ExtensionManifest::load(fs.clone(), &extension_path)
+                        .await
+                        .log_err()
+                    {
+                        previous_versions.insert(Arc::from(extension_id), manifest.version);
+                    }
+                }
+            }
+
+            this.update(&mut cx, |this, cx| {
+                this.previous_versions = previous_versions;
+                cx.notify();
+            })
+            .ok();
+        })
+    }
+
     pub fn install_dev_extension(
         &mut self,
         extension_source_path: PathBuf,
@@ -857,26 +1196,50 @@ impl ExtensionStore {
                 })
                 .await?;
 
-            let output_path = &extensions_dir.join(extension_id.as_ref());
-            if let Some(metadata) = fs.metadata(&output_path).await? {
-                if metadata.is_symlink {
-                    fs.remove_file(
-                        &output_path,
-                        RemoveOptions {
-                            recursive: false,
-                            ignore_if_not_exists: true,
-                        },
-                    )
-                    .await?;
-                } else {
-                    bail!("extension {extension_id} is already installed");
-                }
+            link_dev_extension(
+                fs.as_ref(),
+                &extensions_dir,
+                &extension_id,
+                extension_source_path,
+            )
+            .await?;
+
+            this.update(&mut cx, |this, cx| this.reload(None, cx))?
+                .await;
+            Ok(())
+        })
+    }
+
+    /// Installs the already built extension at the given path as a dev extension,
+    /// or reloads it if it is installed, like after `zed-extension dev` rebuilt it.
+    pub fn reload_dev_extension(
+        &mut self,
+        extension_source_path: PathBuf,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<()>> {
+        let extensions_dir = self.extensions_dir();
+        let fs = self.fs.clone();
+
+        cx.spawn(move |this, mut cx| async move {
+            let extension_manifest =
+                ExtensionManifest::load(fs.clone(), &extension_source_path).await?;
+            let extension_id = extension_manifest.id.clone();
+
+            if this.update(&mut cx, |this, _| {
+                this.outstanding_operations.contains_key(&extension_id)
+            })? {
+                bail!("extension {extension_id} is already being installed or updated");
             }
 
-            fs.create_symlink(output_path, extension_source_path)
-                .await?;
+            link_dev_extension(
+                fs.as_ref(),
+                &extensions_dir,
+                &extension_id,
+                extension_source_path,
+            )
+            .await?;
 
-            this.update(&mut cx, |this, cx| this.reload(None, cx))?
+            this.update(&mut cx, |this, cx| this.reload(Some(extension_id), cx))?
                 .await;
             Ok(())
         })
@@ -1035,6 +1398,22 @@ impl ExtensionStore {
                         .remove_lsp_adapter(&language, language_server_name);
                 }
             }
+            if extension.manifest.tasks.is_some() {
+                self.task_provider_registry
+                    .unregister_provider(extension_id);
+            }
+            for (formatter_name, formatter) in extension.manifest.formatters.iter() {
+                for language in &formatter.languages {
+                    self.language_registry
+                        .remove_formatter(language, extension_id, formatter_name);
+                }
+            }
+            for (linter_name, linter) in extension.manifest.linters.iter() {
+                for language in &linter.languages {
+                    self.language_registry
+                        .remove_linter(language, extension_id, linter_name);
+                }
+            }
         }
 
         self.wasm_extensions
@@ -1230,6 +1609,41 @@ impl ExtensionStore {
                             },
                         ));
                     }
+
+                    if let Some(tasks) = &manifest.tasks {
+                        this.task_provider_registry.register_provider(Arc::new(
+                            ExtensionTaskProvider {
+                                extension: wasm_extension.clone(),
+                                variables: tasks.variables.clone(),
+                            },
+                        ));
+                    }
+
+                    for (formatter_name, formatter) in &manifest.formatters {
+                        for language in &formatter.languages {
+                            this.language_registry.register_formatter(
+                                language.clone(),
+                                manifest.id.clone(),
+                                Arc::new(ExtensionFormatter {
+                                    extension: wasm_extension.clone(),
+                                    name: formatter_name.clone(),
+                                }),
+                            );
+                        }
+                    }
+
+                    for (linter_name, linter) in &manifest.linters {
+                        for language in &linter.languages {
+                            this.language_registry.register_linter(
+                                language.clone(),
+                                manifest.id.clone(),
+                                Arc::new(ExtensionLinter {
+                                    extension: wasm_extension.clone(),
+                                    name: linter_name.clone(),
+                                }),
+                            );
+                        }
+                    }
                 }
 
                 this.wasm_extensions.extend(wasm_extensions);
@@ -1382,6 +1796,33 @@ impl ExtensionStore {
     }
 }
 
+/// Links the extension at the given source path into the extensions directory as a dev
+/// extension, replacing the link of a dev extension with the same ID.
+async fn link_dev_extension(
+    fs: &dyn Fs,
+    extensions_dir: &Path,
+    extension_id: &str,
+    extension_source_path: PathBuf,
+) -> Result<()> {
+    let output_path = &extensions_dir.join(extension_id);
+    if let Some(metadata) = fs.metadata(&output_path).await? {
+        if metadata.is_symlink {
+            fs.remove_file(
+                &output_path,
+                RemoveOptions {
+                    recursive: false,
+                    ignore_if_not_exists: true,
+                },
+            )
+            .await?;
+        } else {
+            bail!("extension {extension_id} is already installed");
+        }
+    }
+
+    fs.create_symlink(output_path, extension_source_path).await
+}
+
 fn load_plugin_queries(root_path: &Path) -> LanguageQueries {
     let mut result = LanguageQueries::default();
     if let Some(entries) = std::fs::read_dir(root_path).log_err() {
@@ -1,9 +1,9 @@
 use crate::extension_manifest::SchemaVersion;
 use crate::extension_settings::ExtensionSettings;
 use crate::{
-    Event, ExtensionIndex, ExtensionIndexEntry, ExtensionIndexLanguageEntry,
-    ExtensionIndexThemeEntry, ExtensionManifest, ExtensionStore, GrammarManifestEntry,
-    RELOAD_DEBOUNCE_DURATION,
+    changelog_diff, Event, ExtensionAutoUpdatePolicy, ExtensionAutoUpdateSetting, ExtensionIndex,
+    ExtensionIndexEntry, ExtensionIndexLanguageEntry, ExtensionIndexThemeEntry, ExtensionManifest,
+    ExtensionStore, GrammarManifestEntry, RELOAD_DEBOUNCE_DURATION,
 };
 use assistant_slash_command::SlashCommandRegistry;
 use async_compression::futures::bufread::GzipEncoder;
@@ -16,7 +16,7 @@ use indexed_docs::IndexedDocsRegistry;
 use language::{LanguageMatcher, LanguageRegistry, LanguageServerBinaryStatus, LanguageServerName};
 use node_runtime::FakeNodeRuntime;
 use parking_lot::Mutex;
-use project::{Project, DEFAULT_COMPLETION_CONTEXT};
+use project::{Project, TaskProviderRegistry, DEFAULT_COMPLETION_CONTEXT};
 use serde_json::json;
 use settings::{Settings as _, SettingsStore};
 use snippet_provider::SnippetRegistry;
@@ -162,6 +162,10 @@ async fn test_extension_store(cx: &mut TestAppContext) {
                         slash_commands: BTreeMap::default(),
                         indexed_docs_providers: BTreeMap::default(),
                         snippets: None,
+                        tasks: None,
+                        formatters: BTreeMap::default(),
+                        linters: BTreeMap::default(),
+                        capabilities: Vec::new(),
                     }),
                     dev: false,
                 },
@@ -188,6 +192,10 @@ async fn test_extension_store(cx: &mut TestAppContext) {
                         slash_commands: BTreeMap::default(),
                         indexed_docs_providers: BTreeMap::default(),
                         snippets: None,
+                        tasks: None,
+                        formatters: BTreeMap::default(),
+                        linters: BTreeMap::default(),
+                        capabilities: Vec::new(),
                     }),
                     dev: false,
                 },
@@ -262,6 +270,7 @@ async fn test_extension_store(cx: &mut TestAppContext) {
     let slash_command_registry = SlashCommandRegistry::new();
     let indexed_docs_registry = Arc::new(IndexedDocsRegistry::new(cx.executor()));
     let snippet_registry = Arc::new(SnippetRegistry::new());
+    let task_provider_registry = Arc::new(TaskProviderRegistry::default());
     let node_runtime = FakeNodeRuntime::new();
 
     let store = cx.new_model(|cx| {
@@ -277,6 +286,7 @@ async fn test_extension_store(cx: &mut TestAppContext) {
             slash_command_registry.clone(),
             indexed_docs_registry.clone(),
             snippet_registry.clone(),
+            task_provider_registry.clone(),
             cx,
         )
     });
@@ -351,6 +361,10 @@ async fn test_extension_store(cx: &mut TestAppContext) {
                 slash_commands: BTreeMap::default(),
                 indexed_docs_providers: BTreeMap::default(),
                 snippets: None,
+                tasks: None,
+                formatters: BTreeMap::default(),
+                linters: BTreeMap::default(),
+                capabilities: Vec::new(),
             }),
             dev: false,
         },
@@ -403,6 +417,7 @@ async fn test_extension_store(cx: &mut TestAppContext) {
             slash_command_registry,
             indexed_docs_registry,
             snippet_registry,
+            task_provider_registry,
             cx,
         )
     });
@@ -452,6 +467,286 @@ async fn test_extension_store(cx: &mut TestAppContext) {
     });
 }
 
+#[gpui::test]
+async fn test_reload_dev_extension(cx: &mut TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    let http_client = FakeHttpClient::with_200_response();
+
+    fs.insert_tree(
+        "/the-extension-dir",
+        json!({
+            "installed": {}
+        }),
+    )
+    .await;
+    fs.insert_tree(
+        "/the-source-dir",
+        json!({
+            "extension.toml": r#"
+                id = "my-extension"
+                name = "My Extension"
+                version = "1.0.0"
+                schema_version = 1
+            "#,
+        }),
+    )
+    .await;
+
+    let language_registry = Arc::new(LanguageRegistry::test(cx.executor()));
+    let theme_registry = Arc::new(ThemeRegistry::new(Box::new(())));
+    let slash_command_registry = SlashCommandRegistry::new();
+    let indexed_docs_registry = Arc::new(IndexedDocsRegistry::new(cx.executor()));
+    let snippet_registry = Arc::new(SnippetRegistry::new());
+    let task_provider_registry = Arc::new(TaskProviderRegistry::default());
+    let node_runtime = FakeNodeRuntime::new();
+
+    let store = cx.new_model(|cx| {
+        ExtensionStore::new(
+            PathBuf::from("/the-extension-dir"),
+            None,
+            fs.clone(),
+            http_client.clone(),
+            None,
+            node_runtime,
+            language_registry,
+            theme_registry,
+            slash_command_registry,
+            indexed_docs_registry,
+            snippet_registry,
+            task_provider_registry,
+            cx,
+        )
+    });
+    cx.executor().advance_clock(RELOAD_DEBOUNCE_DURATION);
+
+    // An extension that is not installed yet is installed as a dev extension.
+    let reload = store.update(cx, |store, cx| {
+        store.reload_dev_extension(PathBuf::from("/the-source-dir"), cx)
+    });
+    cx.executor().advance_clock(RELOAD_DEBOUNCE_DURATION);
+    reload.await.unwrap();
+    store.read_with(cx, |store, _| {
+        let extension = &store.installed_extensions()["my-extension"];
+        assert!(extension.dev);
+        assert_eq!(extension.manifest.version.as_ref(), "1.0.0");
+    });
+
+    // An installed dev extension is reloaded.
+    fs.insert_file(
+        "/the-source-dir/extension.toml",
+        r#"
+            id = "my-extension"
+            name = "My Extension"
+            version = "1.1.0"
+            schema_version = 1
+        "#
+        .as_bytes()
+        .to_vec(),
+    )
+    .await;
+    let reload = store.update(cx, |store, cx| {
+        store.reload_dev_extension(PathBuf::from("/the-source-dir"), cx)
+    });
+    cx.executor().advance_clock(RELOAD_DEBOUNCE_DURATION);
+    reload.await.unwrap();
+    store.read_with(cx, |store, _| {
+        let extension = &store.installed_extensions()["my-extension"];
+        assert!(extension.dev);
+        assert_eq!(extension.manifest.version.as_ref(), "1.1.0");
+    });
+}
+
+#[gpui::test]
+async fn test_extension_update_policies_and_rollback(cx: &mut TestAppContext) {
+    init_test(cx);
+    cx.update(|cx| {
+        SettingsStore::update_global(cx, |store, cx| {
+            store.update_user_settings::<ExtensionSettings>(cx, |settings| {
+                settings.auto_update_extensions.insert(
+                    "zed-gleam".into(),
+                    ExtensionAutoUpdateSetting::Policy(ExtensionAutoUpdatePolicy::Notify),
+                );
+                settings
+                    .pinned_extension_versions
+                    .insert("zed-ruby".into(), "0.2.0".into());
+            });
+        });
+    });
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/the-extension-dir",
+        json!({
+            "installed": {
+                "zed-gleam": {
+                    "extension.toml": r#"
+                        id = "zed-gleam"
+                        name = "Gleam"
+                        version = "1.0.0"
+                        schema_version = 1
+                    "#,
+                },
+                "zed-ruby": {
+                    "extension.toml": r#"
+                        id = "zed-ruby"
+                        name = "Ruby"
+                        version = "0.2.0"
+                        schema_version = 1
+                    "#,
+                },
+            },
+            "previous": {
+                "zed-ruby": {
+                    "extension.toml": r#"
+                        id = "zed-ruby"
+                        name = "Ruby"
+                        version = "0.1.0"
+                        schema_version = 1
+                    "#,
+                },
+            },
+        }),
+    )
+    .await;
+
+    let http_client = FakeHttpClient::create(|request| async move {
+        if request.uri().path() != "/extensions/updates" {
+            return Ok(Response::builder().status(404).body("".into()).unwrap());
+        }
+
+        let extension = |id: &str, name: &str, version: &str| {
+            json!({
+                "id": id,
+                "name": name,
+                "version": version,
+                "description": null,
+                "authors": [],
+                "repository": "",
+                "schema_version": 1,
+                "wasm_api_version": null,
+                "changelog": null,
+                "published_at": "2024-05-21T00:00:00Z",
+                "download_count": 0,
+            })
+        };
+        Ok(Response::new(
+            json!({
+                "data": [
+                    extension("zed-gleam", "Gleam", "1.1.0"),
+                    extension("zed-ruby", "Ruby", "0.3.0"),
+                ]
+            })
+            .to_string()
+            .into(),
+        ))
+    });
+
+    let store = cx.new_model(|cx| {
+        ExtensionStore::new(
+            PathBuf::from("/the-extension-dir"),
+            None,
+            fs.clone(),
+            http_client,
+            None,
+            FakeNodeRuntime::new(),
+            Arc::new(LanguageRegistry::test(cx.background_executor().clone())),
+            Arc::new(ThemeRegistry::new(Box::new(()))),
+            SlashCommandRegistry::new(),
+            Arc::new(IndexedDocsRegistry::new(cx.background_executor().clone())),
+            Arc::new(SnippetRegistry::new()),
+            Arc::new(TaskProviderRegistry::default()),
+            cx,
+        )
+    });
+    cx.executor().advance_clock(RELOAD_DEBOUNCE_DURATION);
+    cx.executor().run_until_parked();
+
+    // Extensions with a "notify" policy aren't updated, and pinned extensions are left alone.
+    store.read_with(cx, |store, _| {
+        let installed_extensions = store.installed_extensions();
+        assert_eq!(
+            installed_extensions["zed-gleam"].manifest.version.as_ref(),
+            "1.0.0"
+        );
+        assert_eq!(
+            installed_extensions["zed-ruby"].manifest.version.as_ref(),
+            "0.2.0"
+        );
+        assert_eq!(
+            store
+                .available_update("zed-gleam")
+                .map(|extension| extension.manifest.version.as_ref()),
+            Some("1.1.0")
+        );
+        assert!(store.available_update("zed-ruby").is_none());
+        assert_eq!(
+            store
+                .previous_version("zed-ruby")
+                .map(|version| version.as_ref()),
+            Some("0.1.0")
+        );
+        assert!(store.previous_version("zed-gleam").is_none());
+    });
+
+    // Rolling back swaps the installed version with the previous one.
+    let rollback = store.update(cx, |store, cx| {
+        store.rollback_extension("zed-ruby".into(), cx)
+    });
+    cx.executor().advance_clock(RELOAD_DEBOUNCE_DURATION);
+    rollback.await.unwrap();
+    store.read_with(cx, |store, _| {
+        assert_eq!(
+            store.installed_extensions()["zed-ruby"]
+                .manifest
+                .version
+                .as_ref(),
+            "0.1.0"
+        );
+        assert_eq!(
+            store
+                .previous_version("zed-ruby")
+                .map(|version| version.as_ref()),
+            Some("0.2.0")
+        );
+    });
+    assert!(fs
+        .load(Path::new(
+            "/the-extension-dir/previous/zed-ruby/extension.toml"
+        ))
+        .await
+        .unwrap()
+        .contains(r#"version = "0.2.0""#));
+
+    // Uninstalling an extension also removes its previous version.
+    store.update(cx, |store, cx| {
+        store.uninstall_extension("zed-ruby".into(), cx)
+    });
+    cx.executor().advance_clock(RELOAD_DEBOUNCE_DURATION);
+    cx.executor().run_until_parked();
+    store.read_with(cx, |store, _| {
+        assert!(!store.installed_extensions().contains_key("zed-ruby"));
+        assert!(store.previous_version("zed-ruby").is_none());
+    });
+    assert!(
+        !fs.is_dir(Path::new("/the-extension-dir/previous/zed-ruby"))
+            .await
+    );
+}
+
+#[test]
+fn test_changelog_diff() {
+    let old_changelog = "## 0.1.0\n\n- Initial release\n";
+    let new_changelog = "## 0.2.0\n\n- Add snippets\n\n## 0.1.0\n\n- Initial release\n";
+
+    assert_eq!(
+        changelog_diff(Some(old_changelog), new_changelog),
+        "## 0.2.0\n\n- Add snippets\n\n"
+    );
+    assert_eq!(changelog_diff(None, old_changelog), old_changelog);
+}
+
 #[gpui::test]
 async fn test_extension_store_with_test_extension(cx: &mut TestAppContext) {
     init_test(cx);
@@ -485,6 +780,7 @@ async fn test_extension_store_with_test_extension(cx: &mut TestAppContext) {
     let slash_command_registry = SlashCommandRegistry::new();
     let indexed_docs_registry = Arc::new(IndexedDocsRegistry::new(cx.executor()));
     let snippet_registry = Arc::new(SnippetRegistry::new());
+    let task_provider_registry = Arc::new(TaskProviderRegistry::default());
     let node_runtime = FakeNodeRuntime::new();
 
     let mut status_updates = language_registry.language_server_binary_statuses();
@@ -577,6 +873,7 @@ async fn test_extension_store_with_test_extension(cx: &mut TestAppContext) {
             slash_command_registry,
             indexed_docs_registry,
             snippet_registry,
+            task_provider_registry,
             cx,
         )
     });
@@ -750,6 +1047,265 @@ async fn test_extension_store_with_test_extension(cx: &mut TestAppContext) {
     assert!(fs.metadata(&expected_server_path).await.unwrap().is_none());
 }
 
+#[cfg(unix)]
+#[gpui::test]
+async fn test_extension_process_capabilities(cx: &mut TestAppContext) {
+    use crate::{wasm_host::ProcessRequest, ExtensionCapability, ProcessExecCapability};
+    use std::{os::unix::fs::PermissionsExt as _, time::Duration};
+
+    init_test(cx);
+    cx.executor().allow_parking();
+
+    let root_dir = temp_tree(json!({
+        "bin": {
+            "fake-linter": r#"#!/bin/sh
+if [ "$1" = "--hang" ]; then
+    sleep 10
+fi
+echo "linting $* in $(pwd) with $FAKE_LINTER_MODE"
+echo "warning: unused variable" >&2
+exit 3
+"#,
+        },
+        "extensions": {
+            "installed": {},
+            "work": {
+                "linter-extension": {
+                    "src": {}
+                }
+            }
+        }
+    }));
+    let root_dir = root_dir.path().canonicalize().unwrap();
+    let extensions_dir = root_dir.join("extensions");
+    let work_dir = extensions_dir.join("work/linter-extension");
+    let fake_linter_path = root_dir.join("bin/fake-linter");
+    std::fs::set_permissions(&fake_linter_path, std::fs::Permissions::from_mode(0o755)).unwrap();
+    let fake_linter = fake_linter_path.to_string_lossy().to_string();
+
+    let manifest: ExtensionManifest = toml::from_str(&format!(
+        r#"
+        id = "linter-extension"
+        name = "Linter"
+        version = "0.1.0"
+        schema_version = 1
+
+        [[capabilities]]
+        kind = "process:exec"
+        command = "{fake_linter}"
+        args = ["--check", "*"]
+
+        [[capabilities]]
+        kind = "process:exec"
+        command = "{fake_linter}"
+        args = ["--hang", "**"]
+
+        [[capabilities]]
+        kind = "process:exec"
+        command = "fake-linter"
+        args = ["**"]
+
+        [[capabilities]]
+        kind = "process:exec"
+        command = "./fake-linter"
+        args = ["**"]
+        "#
+    ))
+    .unwrap();
+    let manifest = Arc::new(manifest);
+
+    let fs = Arc::new(RealFs::default());
+    let language_registry = Arc::new(LanguageRegistry::test(cx.executor()));
+    let store = cx.new_model(|cx| {
+        ExtensionStore::new(
+            extensions_dir.clone(),
+            None,
+            fs,
+            FakeHttpClient::with_404_response(),
+            None,
+            FakeNodeRuntime::new(),
+            language_registry,
+            Arc::new(ThemeRegistry::new(Box::new(()))),
+            SlashCommandRegistry::new(),
+            Arc::new(IndexedDocsRegistry::new(cx.background_executor().clone())),
+            Arc::new(SnippetRegistry::new()),
+            Arc::new(TaskProviderRegistry::default()),
+            cx,
+        )
+    });
+    let wasm_host = store.read_with(cx, |store, _| store.wasm_host.clone());
+
+    let request = |args: &[&str]| ProcessRequest {
+        command: fake_linter.clone(),
+        args: args.iter().map(|arg| arg.to_string()).collect(),
+        env: vec![("FAKE_LINTER_MODE".into(), "strict".into())],
+        cwd: None,
+        timeout: Some(Duration::from_secs(5)),
+    };
+    let grant_capabilities = |capabilities: Vec<ExtensionCapability>, cx: &mut TestAppContext| {
+        cx.update(|cx| {
+            SettingsStore::update_global(cx, |store, cx| {
+                store.update_user_settings::<ExtensionSettings>(cx, |settings| {
+                    settings
+                        .granted_extension_capabilities
+                        .insert("linter-extension".into(), capabilities);
+                });
+            });
+        });
+    };
+
+    // The extension declares the capabilities, but the user has not granted them yet.
+    let error = wasm_host
+        .run_process(&manifest, request(&["--check", "main.rs"]))
+        .await
+        .unwrap_err();
+    assert!(error.to_string().contains("is not allowed"), "{error}");
+
+    grant_capabilities(vec![manifest.capabilities[0].clone()], cx);
+    let output = wasm_host
+        .run_process(&manifest, request(&["--check", "main.rs"]))
+        .await
+        .unwrap();
+    assert_eq!(output.status, Some(3));
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        format!(
+            "linting --check main.rs in {} with strict\n",
+            work_dir.display()
+        )
+    );
+    assert_eq!(
+        String::from_utf8(output.stderr).unwrap(),
+        "warning: unused variable\n"
+    );
+
+    // The working directory can be changed within the extension's work directory.
+    let output = wasm_host
+        .run_process(
+            &manifest,
+            ProcessRequest {
+                cwd: Some("src".into()),
+                ..request(&["--check", "lib.rs"])
+            },
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        format!(
+            "linting --check lib.rs in {} with strict\n",
+            work_dir.join("src").display()
+        )
+    );
+
+    // The working directory cannot be outside of the extension's work directory.
+    for cwd in [root_dir.join("bin"), PathBuf::from("../..")] {
+        let error = wasm_host
+            .run_process(
+                &manifest,
+                ProcessRequest {
+                    cwd: Some(cwd),
+                    ..request(&["--check", "lib.rs"])
+                },
+            )
+            .await
+            .unwrap_err();
+        assert!(
+            error
+                .to_string()
+                .contains("working directory must be inside"),
+            "{error}"
+        );
+    }
+
+    // The arguments must match the patterns of a granted capability.
+    for args in [
+        &["--fix", "main.rs"][..],
+        &["--check"],
+        &["--check", "main.rs", "lib.rs"],
+        &["--hang"],
+    ] {
+        let error = wasm_host
+            .run_process(&manifest, request(args))
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("is not allowed"), "{error}");
+    }
+
+    // Granting a capability that the extension does not declare has no effect.
+    let undeclared_capability = ExtensionCapability::ProcessExec(ProcessExecCapability {
+        command: fake_linter.clone(),
+        args: vec!["**".into()],
+    });
+    grant_capabilities(vec![undeclared_capability], cx);
+    let error = wasm_host
+        .run_process(&manifest, request(&["--fix", "main.rs"]))
+        .await
+        .unwrap_err();
+    assert!(error.to_string().contains("is not allowed"), "{error}");
+
+    // Subprocesses that outlive their timeout are killed.
+    grant_capabilities(manifest.capabilities.clone(), cx);
+    let error = wasm_host
+        .run_process(
+            &manifest,
+            ProcessRequest {
+                timeout: Some(Duration::from_millis(100)),
+                ..request(&["--hang", "main.rs"])
+            },
+        )
+        .await
+        .unwrap_err();
+    assert!(
+        error.to_string().contains("did not exit within 100ms"),
+        "{error}"
+    );
+
+    // Extensions cannot run executables of their own under the name of a granted command,
+    // neither by changing where it is looked up nor by running it from their work directory.
+    let extension_linter_path = work_dir.join("fake-linter");
+    std::fs::write(&extension_linter_path, "#!/bin/sh\necho pwned\n").unwrap();
+    std::fs::set_permissions(
+        &extension_linter_path,
+        std::fs::Permissions::from_mode(0o755),
+    )
+    .unwrap();
+    let work_dir_string = work_dir.to_string_lossy().to_string();
+    for env_var_name in ["PATH", "LD_PRELOAD", "DYLD_INSERT_LIBRARIES"] {
+        let error = wasm_host
+            .run_process(
+                &manifest,
+                ProcessRequest {
+                    command: "fake-linter".into(),
+                    env: vec![(env_var_name.into(), work_dir_string.clone())],
+                    ..request(&[])
+                },
+            )
+            .await
+            .unwrap_err();
+        assert!(
+            error
+                .to_string()
+                .contains(&format!("not allowed to set the `{env_var_name}`")),
+            "{error}"
+        );
+    }
+    let error = wasm_host
+        .run_process(
+            &manifest,
+            ProcessRequest {
+                command: "./fake-linter".into(),
+                ..request(&[])
+            },
+        )
+        .await
+        .unwrap_err();
+    assert!(
+        error.to_string().contains("from the extensions directory"),
+        "{error}"
+    );
+}
+
 fn init_test(cx: &mut TestAppContext) {
     cx.update(|cx| {
         let store = SettingsStore::test(cx);
@@ -0,0 +1,107 @@
+use std::sync::Arc;
+
+use anyhow::{anyhow, Result};
+use futures::FutureExt;
+use gpui::{AppContext, Task};
+use language::LspAdapterDelegate;
+use project::TaskProvider;
+use task::{TaskTemplate, TaskTemplates, TaskVariables, VariableName};
+use wasmtime_wasi::WasiView;
+
+use crate::wasm_host::{wit, WasmExtension};
+
+pub struct ExtensionTaskProvider {
+    pub(crate) extension: WasmExtension,
+    /// The custom task variables that the extension declares in its manifest.
+    pub(crate) variables: Vec<Arc<str>>,
+}
+
+impl TaskProvider for ExtensionTaskProvider {
+    fn id(&self) -> Arc<str> {
+        self.extension.manifest.id.clone()
+    }
+
+    fn tasks_for_worktree(
+        &self,
+        worktree: Arc<dyn LspAdapterDelegate>,
+        cx: &AppContext,
+    ) -> Task<Result<TaskTemplates>> {
+        let extension = self.extension.clone();
+        cx.background_executor().spawn(async move {
+            let templates = extension
+                .call(|extension, store| {
+                    async move {
+                        let resource = store.data_mut().table().push(worktree)?;
+                        let templates = extension
+                            .call_tasks_for_worktree(store, resource)
+                            .await?
+                            .map_err(|e| anyhow!("{}", e))?;
+
+                        anyhow::Ok(templates)
+                    }
+                    .boxed()
+                })
+                .await?;
+
+            Ok(TaskTemplates(
+                templates.into_iter().map(task_template_from_wit).collect(),
+            ))
+        })
+    }
+
+    fn task_context(
+        &self,
+        variables: TaskVariables,
+        worktree: Arc<dyn LspAdapterDelegate>,
+        cx: &AppContext,
+    ) -> Task<Result<TaskVariables>> {
+        if self.variables.is_empty() {
+            return Task::ready(Ok(TaskVariables::default()));
+        }
+
+        let extension = self.extension.clone();
+        let declared_variables = self.variables.clone();
+        let variables = variables
+            .into_iter()
+            .map(|(name, value)| (name.to_string(), value))
+            .collect::<wit::TaskVariables>();
+        cx.background_executor().spawn(async move {
+            let provided_variables = extension
+                .call(|extension, store| {
+                    async move {
+                        let resource = store.data_mut().table().push(worktree)?;
+                        let provided_variables = extension
+                            .call_task_context(store, &variables, resource)
+                            .await?
+                            .map_err(|e| anyhow!("{}", e))?;
+
+                        anyhow::Ok(provided_variables)
+                    }
+                    .boxed()
+                })
+                .await?;
+
+            Ok(provided_variables
+                .into_iter()
+                .filter_map(|(name, value)| {
+                    let name = declared_variables
+                        .iter()
+                        .find(|declared_name| declared_name.as_ref() == name)?;
+                    Some((VariableName::Custom(name.to_string().into()), value))
+                })
+                .collect())
+        })
+    }
+}
+
+fn task_template_from_wit(template: wit::TaskTemplate) -> TaskTemplate {
+    TaskTemplate {
+        label: template.label,
+        command: template.command,
+        args: template.args,
+        env: template.env.into_iter().collect(),
+        cwd: template.cwd,
+        tags: template.tags,
+        ..TaskTemplate::default()
+    }
+}
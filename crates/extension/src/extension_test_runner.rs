@@ -0,0 +1,431 @@
+use crate::wasm_host::{wit, WasmExtension, WasmHost};
+use crate::ExtensionManifest;
+use anyhow::{anyhow, Context as _, Result};
+use async_trait::async_trait;
+use collections::HashMap;
+use fs::Fs;
+use futures::FutureExt;
+use gpui::{AppContext, BackgroundExecutor, Task};
+use http::HttpClient;
+use language::{
+    LanguageRegistry, LanguageServerBinaryStatus, LanguageServerName, LspAdapterDelegate,
+};
+use node_runtime::NodeRuntime;
+use serde::Deserialize;
+use serde_json::json;
+use std::{
+    ffi::OsStr,
+    ops::Range,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+use wasmtime_wasi::WasiView;
+
+/// The directory of an extension containing its test fixtures.
+pub const TESTS_DIR_NAME: &str = "tests";
+
+/// The directory of the tests directory containing the snapshots of the test results.
+pub const SNAPSHOTS_DIR_NAME: &str = "snapshots";
+
+/// Runs the tests of an extension against its WASM module, without the rest of Zed.
+///
+/// Each test is a JSON fixture in the `tests` directory of the extension, naming the export to
+/// call and its inputs. The result of the call is compared against the snapshot with the same
+/// file name in the `tests/snapshots` directory, which is recorded when it does not exist yet.
+///
+/// For example, `tests/rust-completions.json` could contain:
+///
+/// ```json
+/// {
+///   "labels_for_completions": {
+///     "language_server_id": "rust-analyzer",
+///     "completions": [{ "label": "len", "kind": 2, "detail": "fn(&self) -> usize" }]
+///   }
+/// }
+/// ```
+pub struct ExtensionTestRunner {
+    wasm_host: Arc<WasmHost>,
+    http_client: Arc<dyn HttpClient>,
+}
+
+/// The inputs of a test, keyed by the export that the test calls.
+#[derive(Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum ExtensionTestFixture {
+    LabelsForCompletions {
+        language_server_id: LanguageServerName,
+        /// The completions, as sent by the language server.
+        completions: Vec<lsp::CompletionItem>,
+    },
+    LabelsForSymbols {
+        language_server_id: LanguageServerName,
+        symbols: Vec<SymbolFixture>,
+    },
+    CompleteSlashCommandArgument {
+        command: String,
+        #[serde(default)]
+        query: String,
+    },
+    RunSlashCommand {
+        command: String,
+        #[serde(default)]
+        argument: Option<String>,
+    },
+}
+
+#[derive(Deserialize)]
+struct SymbolFixture {
+    name: String,
+    kind: lsp::SymbolKind,
+}
+
+#[derive(Debug)]
+pub struct ExtensionTestOutcome {
+    /// The name of the test, which is the file stem of its fixture.
+    pub name: String,
+    pub result: ExtensionTestResult,
+}
+
+#[derive(Debug)]
+pub enum ExtensionTestResult {
+    Passed,
+    /// The test had no snapshot yet, or snapshots were being updated, so its result was recorded.
+    Recorded,
+    Failed {
+        expected: String,
+        actual: String,
+    },
+    Errored(anyhow::Error),
+}
+
+impl ExtensionTestRunner {
+    pub fn new(
+        fs: Arc<dyn Fs>,
+        http_client: Arc<dyn HttpClient>,
+        node_runtime: Arc<dyn NodeRuntime>,
+        work_dir: PathBuf,
+        cx: &mut AppContext,
+    ) -> Self {
+        let language_registry = Arc::new(LanguageRegistry::new(
+            Task::ready(()),
+            cx.background_executor().clone(),
+        ));
+        let wasm_host = WasmHost::new(
+            fs,
+            http_client.clone(),
+            node_runtime,
+            language_registry,
+            work_dir,
+            cx,
+        );
+        Self {
+            wasm_host,
+            http_client,
+        }
+    }
+
+    /// Loads the built extension in the given directory and runs each of its tests,
+    /// recording the snapshots of all of them if `update_snapshots` is set.
+    pub async fn run(
+        &self,
+        extension_dir: &Path,
+        manifest: Arc<ExtensionManifest>,
+        update_snapshots: bool,
+        executor: BackgroundExecutor,
+    ) -> Result<Vec<ExtensionTestOutcome>> {
+        let tests_dir = extension_dir.join(TESTS_DIR_NAME);
+        let mut fixture_paths = std::fs::read_dir(&tests_dir)
+            .with_context(|| format!("failed to read {}", tests_dir.display()))?
+            .map(|entry| Ok(entry?.path()))
+            .collect::<Result<Vec<_>>>()?;
+        fixture_paths.retain(|path| path.extension() == Some("json".as_ref()));
+        fixture_paths.sort();
+
+        let wasm_bytes = std::fs::read(extension_dir.join("extension.wasm"))
+            .context("failed to read extension.wasm")?;
+        let extension = self
+            .wasm_host
+            .load_extension(wasm_bytes, manifest.clone(), executor)
+            .await
+            .with_context(|| format!("failed to load wasm extension {}", manifest.id))?;
+
+        let worktree: Arc<dyn LspAdapterDelegate> = Arc::new(TestWorktree {
+            root_path: tests_dir.clone(),
+            http_client: self.http_client.clone(),
+        });
+
+        let mut outcomes = Vec::new();
+        for fixture_path in fixture_paths {
+            let Some(name) = fixture_path.file_stem() else {
+                continue;
+            };
+            let name = name.to_string_lossy().to_string();
+            let snapshot_path = tests_dir
+                .join(SNAPSHOTS_DIR_NAME)
+                .join(format!("{name}.json"));
+
+            let result = match run_test(&extension, &manifest, &fixture_path, worktree.clone())
+                .await
+                .with_context(|| format!("failed to run {}", fixture_path.display()))
+            {
+                Ok(actual) => compare_snapshot(&snapshot_path, actual, update_snapshots)
+                    .unwrap_or_else(ExtensionTestResult::Errored),
+                Err(error) => ExtensionTestResult::Errored(error),
+            };
+            outcomes.push(ExtensionTestOutcome { name, result });
+        }
+
+        Ok(outcomes)
+    }
+}
+
+/// Calls the export named by the fixture at the given path, returning its result as pretty JSON.
+async fn run_test(
+    extension: &WasmExtension,
+    manifest: &ExtensionManifest,
+    fixture_path: &Path,
+    worktree: Arc<dyn LspAdapterDelegate>,
+) -> Result<String> {
+    let fixture = std::fs::read_to_string(fixture_path)?;
+    let fixture: ExtensionTestFixture = serde_json::from_str(&fixture)?;
+
+    let result: serde_json::Value = match fixture {
+        ExtensionTestFixture::LabelsForCompletions {
+            language_server_id,
+            completions,
+        } => {
+            let completions = completions
+                .into_iter()
+                .map(wit::Completion::from)
+                .collect::<Vec<_>>();
+            let labels = extension
+                .call(move |extension, store| {
+                    async move {
+                        extension
+                            .call_labels_for_completions(store, &language_server_id, completions)
+                            .await?
+                            .map_err(|e| anyhow!("{}", e))
+                    }
+                    .boxed()
+                })
+                .await?;
+            labels.into_iter().map(code_label_to_json).collect()
+        }
+        ExtensionTestFixture::LabelsForSymbols {
+            language_server_id,
+            symbols,
+        } => {
+            let symbols = symbols
+                .into_iter()
+                .map(|symbol| wit::Symbol {
+                    name: symbol.name,
+                    kind: symbol.kind.into(),
+                })
+                .collect::<Vec<_>>();
+            let labels = extension
+                .call(move |extension, store| {
+                    async move {
+                        extension
+                            .call_labels_for_symbols(store, &language_server_id, symbols)
+                            .await?
+                            .map_err(|e| anyhow!("{}", e))
+                    }
+                    .boxed()
+                })
+                .await?;
+            labels.into_iter().map(code_label_to_json).collect()
+        }
+        ExtensionTestFixture::CompleteSlashCommandArgument { command, query } => {
+            let command = slash_command(manifest, &command)?;
+            let completions = extension
+                .call(move |extension, store| {
+                    async move {
+                        extension
+                            .call_complete_slash_command_argument(store, &command, &query)
+                            .await?
+                            .map_err(|e| anyhow!("{}", e))
+                    }
+                    .boxed()
+                })
+                .await?;
+            completions
+                .into_iter()
+                .map(|completion| {
+                    json!({
+                        "label": completion.label,
+                        "new_text": completion.new_text,
+                        "run_command": completion.run_command,
+                    })
+                })
+                .collect()
+        }
+        ExtensionTestFixture::RunSlashCommand { command, argument } => {
+            let command = slash_command(manifest, &command)?;
+            let output = extension
+                .call(move |extension, store| {
+                    async move {
+                        let resource = store.data_mut().table().push(worktree)?;
+                        extension
+                            .call_run_slash_command(store, &command, argument.as_deref(), resource)
+                            .await?
+                            .map_err(|e| anyhow!("{}", e))
+                    }
+                    .boxed()
+                })
+                .await?;
+            json!({
+                "text": output.text,
+                "sections": output
+                    .sections
+                    .into_iter()
+                    .map(|section| json!({
+                        "range": Range::<usize>::from(section.range),
+                        "label": section.label,
+                    }))
+                    .collect::<Vec<_>>(),
+            })
+        }
+    };
+
+    let mut result = serde_json::to_string_pretty(&result)?;
+    result.push('\n');
+    Ok(result)
+}
+
+fn slash_command(manifest: &ExtensionManifest, name: &str) -> Result<wit::SlashCommand> {
+    let command = manifest
+        .slash_commands
+        .get(name)
+        .ok_or_else(|| anyhow!("slash command {name} is not declared in the manifest"))?;
+    Ok(wit::SlashCommand {
+        name: name.to_string(),
+        description: command.description.clone(),
+        tooltip_text: command.tooltip_text.clone(),
+        requires_argument: command.requires_argument,
+    })
+}
+
+fn code_label_to_json(label: Option<wit::CodeLabel>) -> serde_json::Value {
+    let Some(label) = label else {
+        return serde_json::Value::Null;
+    };
+    json!({
+        "code": label.code,
+        "spans": label
+            .spans
+            .into_iter()
+            .map(|span| match span {
+                wit::CodeLabelSpan::CodeRange(range) => {
+                    json!({ "code_range": Range::<usize>::from(range) })
+                }
+                wit::CodeLabelSpan::Literal(literal) => json!({
+                    "literal": {
+                        "text": literal.text,
+                        "highlight_name": literal.highlight_name,
+                    }
+                }),
+            })
+            .collect::<Vec<_>>(),
+        "filter_range": Range::<usize>::from(label.filter_range),
+    })
+}
+
+fn compare_snapshot(
+    snapshot_path: &Path,
+    actual: String,
+    update_snapshots: bool,
+) -> Result<ExtensionTestResult> {
+    let expected = match std::fs::read_to_string(snapshot_path) {
+        Ok(expected) => Some(expected),
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => None,
+        Err(error) => return Err(error.into()),
+    };
+
+    match expected {
+        Some(expected) if expected == actual => Ok(ExtensionTestResult::Passed),
+        Some(expected) if !update_snapshots => Ok(ExtensionTestResult::Failed { expected, actual }),
+        _ => {
+            if let Some(snapshots_dir) = snapshot_path.parent() {
+                std::fs::create_dir_all(snapshots_dir)?;
+            }
+            std::fs::write(snapshot_path, actual)
+                .with_context(|| format!("failed to write {}", snapshot_path.display()))?;
+            Ok(ExtensionTestResult::Recorded)
+        }
+    }
+}
+
+/// The worktree that tests calling exports with a worktree run against,
+/// which is the `tests` directory of the extension.
+struct TestWorktree {
+    root_path: PathBuf,
+    http_client: Arc<dyn HttpClient>,
+}
+
+#[async_trait]
+impl LspAdapterDelegate for TestWorktree {
+    fn show_notification(&self, message: &str, _: &mut AppContext) {
+        log::info!("{message}");
+    }
+
+    fn http_client(&self) -> Arc<dyn HttpClient> {
+        self.http_client.clone()
+    }
+
+    fn worktree_id(&self) -> u64 {
+        0
+    }
+
+    fn worktree_root_path(&self) -> &Path {
+        &self.root_path
+    }
+
+    fn update_status(&self, _: LanguageServerName, _: LanguageServerBinaryStatus) {}
+
+    async fn which(&self, _: &OsStr) -> Option<PathBuf> {
+        None
+    }
+
+    async fn shell_env(&self) -> HashMap<String, String> {
+        HashMap::default()
+    }
+
+    async fn read_text_file(&self, path: PathBuf) -> Result<String> {
+        Ok(std::fs::read_to_string(self.root_path.join(path))?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compare_snapshot() {
+        let dir = util::test::temp_tree(serde_json::json!({}));
+        let snapshot_path = dir.path().join(SNAPSHOTS_DIR_NAME).join("a.json");
+
+        // A missing snapshot is recorded.
+        assert!(matches!(
+            compare_snapshot(&snapshot_path, "[1]\n".into(), false).unwrap(),
+            ExtensionTestResult::Recorded
+        ));
+        assert!(matches!(
+            compare_snapshot(&snapshot_path, "[1]\n".into(), false).unwrap(),
+            ExtensionTestResult::Passed
+        ));
+
+        // A differing result fails, unless snapshots are being updated.
+        match compare_snapshot(&snapshot_path, "[2]\n".into(), false).unwrap() {
+            ExtensionTestResult::Failed { expected, actual } => {
+                assert_eq!(expected, "[1]\n");
+                assert_eq!(actual, "[2]\n");
+            }
+            result => panic!("unexpected result {result:?}"),
+        }
+        assert!(matches!(
+            compare_snapshot(&snapshot_path, "[2]\n".into(), true).unwrap(),
+            ExtensionTestResult::Recorded
+        ));
+        assert_eq!(std::fs::read_to_string(&snapshot_path).unwrap(), "[2]\n");
+    }
+}
@@ -1,6 +1,6 @@
 pub(crate) mod wit;
 
-use crate::ExtensionManifest;
+use crate::{ExtensionCapability, ExtensionManifest, ExtensionSettings};
 use anyhow::{anyhow, bail, Context as _, Result};
 use fs::{normalize_path, Fs};
 use futures::future::LocalBoxFuture;
@@ -18,9 +18,13 @@ use language::LanguageRegistry;
 use node_runtime::NodeRuntime;
 use release_channel::ReleaseChannel;
 use semantic_version::SemanticVersion;
+use settings::Settings;
+use smol::process::Stdio;
 use std::{
+    env,
     path::{Path, PathBuf},
     sync::{Arc, OnceLock},
+    time::Duration,
 };
 use wasmtime::{
     component::{Component, ResourceTable},
@@ -56,6 +60,32 @@ pub(crate) struct WasmState {
     pub(crate) host: Arc<WasmHost>,
 }
 
+/// Whether setting the environment variable would change which program, or which
+/// libraries, a subprocess runs, bypassing the command check of its capability.
+fn changes_executed_program(env_var_name: &str) -> bool {
+    let env_var_name = env_var_name.to_ascii_uppercase();
+    env_var_name == "PATH" || env_var_name.starts_with("LD_") || env_var_name.starts_with("DYLD_")
+}
+
+/// A subprocess that an extension asks to run.
+pub(crate) struct ProcessRequest {
+    pub command: String,
+    pub args: Vec<String>,
+    pub env: Vec<(String, String)>,
+    /// The directory to run the subprocess in, relative to the extension's work directory.
+    pub cwd: Option<PathBuf>,
+    pub timeout: Option<Duration>,
+}
+
+/// The output of a subprocess that an extension ran.
+#[derive(Debug)]
+pub(crate) struct ProcessOutput {
+    /// The exit code, or `None` if the subprocess was terminated by a signal.
+    pub status: Option<i32>,
+    pub stdout: Vec<u8>,
+    pub stderr: Vec<u8>,
+}
+
 type MainThreadCall =
     Box<dyn Send + for<'a> FnOnce(&'a mut AsyncAppContext) -> LocalBoxFuture<'a, ()>>;
 
@@ -186,6 +216,145 @@ impl WasmHost {
             .build())
     }
 
+    /// Runs a subprocess on behalf of the extension with the given manifest.
+    ///
+    /// The extension must declare a capability allowing the command in its manifest,
+    /// and the user must have granted it that capability.
+    pub(crate) async fn run_process(
+        &self,
+        manifest: &Arc<ExtensionManifest>,
+        request: ProcessRequest,
+    ) -> Result<ProcessOutput> {
+        let granted_capabilities = self
+            .on_main_thread({
+                let extension_id = manifest.id.clone();
+                move |cx| {
+                    async move {
+                        cx.update(|cx| {
+                            ExtensionSettings::get_global(cx)
+                                .granted_capabilities(&extension_id)
+                                .to_vec()
+                        })
+                    }
+                    .boxed_local()
+                }
+            })
+            .await?;
+        let is_allowed = manifest
+            .capabilities
+            .iter()
+            .filter(|capability| granted_capabilities.contains(capability))
+            .any(|capability| match capability {
+                ExtensionCapability::ProcessExec(capability) => {
+                    capability.allows(&request.command, &request.args)
+                }
+            });
+        if !is_allowed {
+            bail!(
+                "extension {} is not allowed to run `{}` with arguments {:?}",
+                manifest.id,
+                request.command,
+                request.args
+            );
+        }
+
+        let cwd = match &request.cwd {
+            Some(cwd) => self
+                .writeable_path_from_extension(&manifest.id, cwd)
+                .context("the working directory must be inside the extension's work directory")?,
+            None => self.work_dir.join(manifest.id.as_ref()),
+        };
+
+        if let Some((name, _)) = request
+            .env
+            .iter()
+            .find(|(name, _)| changes_executed_program(name))
+        {
+            bail!(
+                "extension {} is not allowed to set the `{name}` environment variable",
+                manifest.id
+            );
+        }
+
+        // Resolve the command against the `PATH` of Zed, and make sure it is not
+        // an executable the extension put into place itself.
+        let search_path = env::var_os("PATH");
+        let program = which::which_in(&request.command, search_path.as_ref(), &cwd)
+            .with_context(|| format!("failed to find `{}`", request.command))?;
+        let program = self.fs.canonicalize(&program).await.unwrap_or(program);
+        let extensions_dir = self.work_dir.parent().unwrap_or(&self.work_dir);
+        let extensions_dir = self
+            .fs
+            .canonicalize(extensions_dir)
+            .await
+            .unwrap_or_else(|_| extensions_dir.to_path_buf());
+        if program.starts_with(&extensions_dir) {
+            bail!(
+                "extension {} is not allowed to run `{}` from the extensions directory",
+                manifest.id,
+                request.command
+            );
+        }
+
+        // The subprocess only sees the environment variables the extension passes,
+        // so that it cannot read secrets from the environment of Zed.
+        let mut command = smol::process::Command::new(&program);
+        command
+            .args(&request.args)
+            .env_clear()
+            .envs(search_path.map(|path| ("PATH", path)))
+            .envs(request.env)
+            .current_dir(&cwd)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true);
+
+        // Dropping the output future kills the subprocess.
+        let output = command.output();
+        let output = match request.timeout {
+            Some(timeout) => smol::future::or(async { Some(output.await) }, async {
+                smol::Timer::after(timeout).await;
+                None
+            })
+            .await
+            .ok_or_else(|| {
+                anyhow!(
+                    "`{}` did not exit within {}ms",
+                    request.command,
+                    timeout.as_millis()
+                )
+            })?,
+            None => output.await,
+        }
+        .with_context(|| format!("failed to run `{}`", request.command))?;
+
+        Ok(ProcessOutput {
+            status: output.status.code(),
+            stdout: output.stdout,
+            stderr: output.stderr,
+        })
+    }
+
+    fn on_main_thread<T, Fn>(&self, f: Fn) -> impl 'static + Future<Output = T>
+    where
+        T: 'static + Send,
+        Fn: 'static + Send + for<'a> FnOnce(&'a mut AsyncAppContext) -> LocalBoxFuture<'a, T>,
+    {
+        let (return_tx, return_rx) = oneshot::channel();
+        self.main_thread_message_tx
+            .clone()
+            .unbounded_send(Box::new(move |cx| {
+                async {
+                    let result = f(cx).await;
+                    return_tx.send(result).ok();
+                }
+                .boxed_local()
+            }))
+            .expect("main thread message channel should not be closed yet");
+        async move { return_rx.await.expect("main thread message channel") }
+    }
+
     pub fn path_from_extension(&self, id: &Arc<str>, path: &Path) -> PathBuf {
         let extension_work_dir = self.work_dir.join(id.as_ref());
         normalize_path(&extension_work_dir.join(path))
@@ -274,19 +443,7 @@ impl WasmState {
         T: 'static + Send,
         Fn: 'static + Send + for<'a> FnOnce(&'a mut AsyncAppContext) -> LocalBoxFuture<'a, T>,
     {
-        let (return_tx, return_rx) = oneshot::channel();
-        self.host
-            .main_thread_message_tx
-            .clone()
-            .unbounded_send(Box::new(move |cx| {
-                async {
-                    let result = f(cx).await;
-                    return_tx.send(result).ok();
-                }
-                .boxed_local()
-            }))
-            .expect("main thread message channel should not be closed yet");
-        async move { return_rx.await.expect("main thread message channel") }
+        self.host.on_main_thread(f)
     }
 
     fn work_dir(&self) -> PathBuf {
@@ -2,9 +2,10 @@ mod since_v0_0_1;
 mod since_v0_0_4;
 mod since_v0_0_6;
 mod since_v0_0_7;
+mod since_v0_0_8;
 use indexed_docs::IndexedDocsDatabase;
 use release_channel::ReleaseChannel;
-use since_v0_0_7 as latest;
+use since_v0_0_8 as latest;
 
 use super::{wasm_engine, WasmState};
 use anyhow::{anyhow, Context, Result};
@@ -19,8 +20,11 @@ use wasmtime::{
 #[cfg(test)]
 pub use latest::CodeLabelSpanLiteral;
 pub use latest::{
+    zed::extension::formatting::{FormattingOptions, TextEdit},
+    zed::extension::linting::{Diagnostic, DiagnosticSeverity},
     zed::extension::lsp::{Completion, CompletionKind, InsertTextFormat, Symbol, SymbolKind},
     zed::extension::slash_command::{SlashCommandArgumentCompletion, SlashCommandOutput},
+    zed::extension::task::{TaskTemplate, TaskVariables},
     CodeLabel, CodeLabelSpan, Command, Range, SlashCommand,
 };
 pub use since_v0_0_4::LanguageServerConfig;
@@ -58,6 +62,7 @@ pub fn wasm_api_version_range(release_channel: ReleaseChannel) -> RangeInclusive
 }
 
 pub enum Extension {
+    V008(since_v0_0_8::Extension),
     V007(since_v0_0_7::Extension),
     V006(since_v0_0_6::Extension),
     V004(since_v0_0_4::Extension),
@@ -76,6 +81,15 @@ impl Extension {
                 latest::Extension::instantiate_async(store, &component, latest::linker())
                     .await
                     .context("failed to instantiate wasm extension")?;
+            Ok((Self::V008(extension), instance))
+        } else if release_channel == ReleaseChannel::Dev && version >= since_v0_0_7::MIN_VERSION {
+            let (extension, instance) = since_v0_0_7::Extension::instantiate_async(
+                store,
+                &component,
+                since_v0_0_7::linker(),
+            )
+            .await
+            .context("failed to instantiate wasm extension")?;
             Ok((Self::V007(extension), instance))
         } else if version >= since_v0_0_6::MIN_VERSION {
             let (extension, instance) = since_v0_0_6::Extension::instantiate_async(
@@ -109,6 +123,7 @@ impl Extension {
 
     pub async fn call_init_extension(&self, store: &mut Store<WasmState>) -> Result<()> {
         match self {
+            Extension::V008(ext) => ext.call_init_extension(store).await,
             Extension::V007(ext) => ext.call_init_extension(store).await,
             Extension::V006(ext) => ext.call_init_extension(store).await,
             Extension::V004(ext) => ext.call_init_extension(store).await,
@@ -124,10 +139,14 @@ impl Extension {
         resource: Resource<Arc<dyn LspAdapterDelegate>>,
     ) -> Result<Result<Command, String>> {
         match self {
-            Extension::V007(ext) => {
+            Extension::V008(ext) => {
                 ext.call_language_server_command(store, &language_server_id.0, resource)
                     .await
             }
+            Extension::V007(ext) => Ok(ext
+                .call_language_server_command(store, &language_server_id.0, resource)
+                .await?
+                .map(|command| command.into())),
             Extension::V006(ext) => Ok(ext
                 .call_language_server_command(store, &language_server_id.0, resource)
                 .await?
@@ -151,6 +170,14 @@ impl Extension {
         resource: Resource<Arc<dyn LspAdapterDelegate>>,
     ) -> Result<Result<Option<String>, String>> {
         match self {
+            Extension::V008(ext) => {
+                ext.call_language_server_initialization_options(
+                    store,
+                    &language_server_id.0,
+                    resource,
+                )
+                .await
+            }
             Extension::V007(ext) => {
                 ext.call_language_server_initialization_options(
                     store,
@@ -189,6 +216,14 @@ impl Extension {
         resource: Resource<Arc<dyn LspAdapterDelegate>>,
     ) -> Result<Result<Option<String>, String>> {
         match self {
+            Extension::V008(ext) => {
+                ext.call_language_server_workspace_configuration(
+                    store,
+                    &language_server_id.0,
+                    resource,
+                )
+                .await
+            }
             Extension::V007(ext) => {
                 ext.call_language_server_workspace_configuration(
                     store,
@@ -216,10 +251,19 @@ impl Extension {
         completions: Vec<latest::Completion>,
     ) -> Result<Result<Vec<Option<CodeLabel>>, String>> {
         match self {
-            Extension::V007(ext) => {
+            Extension::V008(ext) => {
                 ext.call_labels_for_completions(store, &language_server_id.0, &completions)
                     .await
             }
+            Extension::V007(ext) => Ok(ext
+                .call_labels_for_completions(store, &language_server_id.0, &completions)
+                .await?
+                .map(|labels| {
+                    labels
+                        .into_iter()
+                        .map(|label| label.map(Into::into))
+                        .collect()
+                })),
             Extension::V006(ext) => Ok(ext
                 .call_labels_for_completions(store, &language_server_id.0, &completions)
                 .await?
@@ -240,10 +284,19 @@ impl Extension {
         symbols: Vec<latest::Symbol>,
     ) -> Result<Result<Vec<Option<CodeLabel>>, String>> {
         match self {
-            Extension::V007(ext) => {
+            Extension::V008(ext) => {
                 ext.call_labels_for_symbols(store, &language_server_id.0, &symbols)
                     .await
             }
+            Extension::V007(ext) => Ok(ext
+                .call_labels_for_symbols(store, &language_server_id.0, &symbols)
+                .await?
+                .map(|labels| {
+                    labels
+                        .into_iter()
+                        .map(|label| label.map(Into::into))
+                        .collect()
+                })),
             Extension::V006(ext) => Ok(ext
                 .call_labels_for_symbols(store, &language_server_id.0, &symbols)
                 .await?
@@ -264,6 +317,10 @@ impl Extension {
         query: &str,
     ) -> Result<Result<Vec<SlashCommandArgumentCompletion>, String>> {
         match self {
+            Extension::V008(ext) => {
+                ext.call_complete_slash_command_argument(store, command, query)
+                    .await
+            }
             Extension::V007(ext) => {
                 ext.call_complete_slash_command_argument(store, command, query)
                     .await
@@ -280,6 +337,10 @@ impl Extension {
         resource: Resource<Arc<dyn LspAdapterDelegate>>,
     ) -> Result<Result<SlashCommandOutput, String>> {
         match self {
+            Extension::V008(ext) => {
+                ext.call_run_slash_command(store, command, argument, resource)
+                    .await
+            }
             Extension::V007(ext) => {
                 ext.call_run_slash_command(store, command, argument, resource)
                     .await
@@ -298,6 +359,10 @@ impl Extension {
         database: Resource<Arc<IndexedDocsDatabase>>,
     ) -> Result<Result<(), String>> {
         match self {
+            Extension::V008(ext) => {
+                ext.call_index_docs(store, provider, package_name, database)
+                    .await
+            }
             Extension::V007(ext) => {
                 ext.call_index_docs(store, provider, package_name, database)
                     .await
@@ -307,6 +372,67 @@ impl Extension {
             }
         }
     }
+
+    pub async fn call_tasks_for_worktree(
+        &self,
+        store: &mut Store<WasmState>,
+        resource: Resource<Arc<dyn LspAdapterDelegate>>,
+    ) -> Result<Result<Vec<TaskTemplate>, String>> {
+        match self {
+            Extension::V008(ext) => ext.call_tasks_for_worktree(store, resource).await,
+            Extension::V001(_) | Extension::V004(_) | Extension::V006(_) | Extension::V007(_) => {
+                Ok(Ok(Vec::new()))
+            }
+        }
+    }
+
+    pub async fn call_task_context(
+        &self,
+        store: &mut Store<WasmState>,
+        variables: &TaskVariables,
+        resource: Resource<Arc<dyn LspAdapterDelegate>>,
+    ) -> Result<Result<TaskVariables, String>> {
+        match self {
+            Extension::V008(ext) => ext.call_task_context(store, variables, resource).await,
+            Extension::V001(_) | Extension::V004(_) | Extension::V006(_) | Extension::V007(_) => {
+                Ok(Ok(Vec::new()))
+            }
+        }
+    }
+
+    pub async fn call_format_buffer(
+        &self,
+        store: &mut Store<WasmState>,
+        formatter_name: &str,
+        text: &str,
+        path: &str,
+        options: FormattingOptions,
+    ) -> Result<Result<Vec<TextEdit>, String>> {
+        match self {
+            Extension::V008(ext) => {
+                ext.call_format_buffer(store, formatter_name, text, path, options)
+                    .await
+            }
+            Extension::V001(_) | Extension::V004(_) | Extension::V006(_) | Extension::V007(_) => {
+                Err(anyhow!("`format_buffer` not available prior to v0.0.8"))
+            }
+        }
+    }
+
+    pub async fn call_lint(
+        &self,
+        store: &mut Store<WasmState>,
+        linter_name: &str,
+        text: &str,
+        path: &str,
+    ) -> Result<Result<Vec<Diagnostic>, String>> {
+        match self {
+            Extension::V008(ext) => ext.call_lint(store, linter_name, text, path).await,
+            Extension::V001(_) | Extension::V004(_) | Extension::V006(_) | Extension::V007(_) => {
+                Err(anyhow!("`lint` not available prior to v0.0.8"))
+            }
+        }
+    }
 }
 
 trait ToWasmtimeResult<T> {
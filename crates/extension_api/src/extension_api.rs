@@ -28,6 +28,7 @@ pub use wit::{
     zed::extension::slash_command::{
         SlashCommand, SlashCommandArgumentCompletion, SlashCommandOutput, SlashCommandOutputSection,
     },
+    zed::extension::task::{TaskTemplate, TaskVariables},
     CodeLabel, CodeLabelSpan, CodeLabelSpanLiteral, Command, DownloadedFileType, EnvVars,
     KeyValueStore, LanguageServerInstallationStatus, Range, Worktree,
 };
@@ -47,6 +48,24 @@ pub mod lsp {
     };
 }
 
+/// Formatting the buffers of the languages that the extension declares
+/// formatters for in its manifest.
+pub mod formatting {
+    pub use crate::wit::zed::extension::formatting::{FormattingOptions, TextEdit};
+}
+
+/// Linting the buffers of the languages that the extension declares
+/// linters for in its manifest.
+pub mod linting {
+    pub use crate::wit::zed::extension::linting::{Diagnostic, DiagnosticSeverity};
+}
+
+/// Running subprocesses, which requires the extension to declare a
+/// `process:exec` capability in its manifest that the user grants.
+pub mod process {
+    pub use crate::wit::zed::extension::process::{run_command, Command, Output};
+}
+
 /// A result returned from a Zed extension.
 pub type Result<T, E = String> = core::result::Result<T, E>;
 
@@ -138,6 +157,47 @@ pub trait Extension: Send + Sync {
     ) -> Result<(), String> {
         Err("`index_docs` not implemented".to_string())
     }
+
+    /// Returns the tasks that the extension provides for the given worktree, such as the
+    /// scripts of its `package.json` or the targets of its `Makefile`.
+    fn tasks_for_worktree(&self, _worktree: &Worktree) -> Result<Vec<TaskTemplate>, String> {
+        Ok(Vec::new())
+    }
+
+    /// Returns the values of the custom task variables that the extension provides for a task
+    /// run in the given worktree, named without their `ZED_CUSTOM_` prefix.
+    ///
+    /// The given variables are the ones known so far, like `ZED_FILE` or `ZED_SYMBOL`.
+    fn task_context(
+        &self,
+        _variables: TaskVariables,
+        _worktree: &Worktree,
+    ) -> Result<TaskVariables, String> {
+        Ok(Vec::new())
+    }
+
+    /// Returns the edits that format the given text of the buffer at the given path with the
+    /// given formatter, as byte ranges of the text and the text to replace them with.
+    fn format_buffer(
+        &self,
+        _formatter_name: String,
+        _text: String,
+        _path: String,
+        _options: formatting::FormattingOptions,
+    ) -> Result<Vec<formatting::TextEdit>, String> {
+        Err("`format_buffer` not implemented".to_string())
+    }
+
+    /// Returns the diagnostics that the given linter reports for the given text of the buffer
+    /// at the given path.
+    fn lint(
+        &self,
+        _linter_name: String,
+        _text: String,
+        _path: String,
+    ) -> Result<Vec<linting::Diagnostic>, String> {
+        Err("`lint` not implemented".to_string())
+    }
 }
 
 /// Registers the provided type as a Zed extension.
@@ -177,7 +237,7 @@ mod wit {
 
     wit_bindgen::generate!({
         skip: ["init-extension"],
-        path: "./wit/since_v0.0.7",
+        path: "./wit/since_v0.0.8",
     });
 }
 
@@ -268,6 +328,34 @@ impl wit::Guest for Component {
     ) -> Result<(), String> {
         extension().index_docs(provider, package, database)
     }
+
+    fn tasks_for_worktree(worktree: &Worktree) -> Result<Vec<TaskTemplate>, String> {
+        extension().tasks_for_worktree(worktree)
+    }
+
+    fn task_context(
+        variables: TaskVariables,
+        worktree: &Worktree,
+    ) -> Result<TaskVariables, String> {
+        extension().task_context(variables, worktree)
+    }
+
+    fn format_buffer(
+        formatter_name: String,
+        text: String,
+        path: String,
+        options: formatting::FormattingOptions,
+    ) -> Result<Vec<formatting::TextEdit>, String> {
+        extension().format_buffer(formatter_name, text, path, options)
+    }
+
+    fn lint(
+        linter_name: String,
+        text: String,
+        path: String,
+    ) -> Result<Vec<linting::Diagnostic>, String> {
+        extension().lint(linter_name, text, path)
+    }
 }
 
 /// The ID of a language server.
@@ -1,57 +1,129 @@
 use std::{
     collections::HashMap,
     env, fs,
-    path::{Path, PathBuf},
+    path::{Component, Path, PathBuf},
     process::Command,
     sync::Arc,
+    time::Duration,
 };
 
-use ::fs::{copy_recursive, CopyOptions, Fs, RealFs};
+use ::fs::{copy_recursive, CopyOptions, Fs, RealFs, Watcher};
 use anyhow::{anyhow, bail, Context, Result};
-use clap::Parser;
+use clap::{Parser, Subcommand};
 use extension::{
     extension_builder::{CompileExtensionOptions, ExtensionBuilder},
-    ExtensionManifest,
+    extension_test_runner::{ExtensionTestResult, ExtensionTestRunner},
+    ExtensionManifest, ExtensionSettings,
 };
+use futures::StreamExt;
+use gpui::SemanticVersion;
 use language::LanguageConfig;
+use node_runtime::RealNodeRuntime;
+use project::Project;
+use settings::Settings;
 use theme::ThemeRegistry;
 use tree_sitter::{Language, Query, WasmStore};
 
+/// How long to wait for more changes to the extension's files before rebuilding it.
+const WATCH_LATENCY: Duration = Duration::from_millis(200);
+
+/// The paths within an extension directory that building it writes to,
+/// which must not trigger another build when watching it.
+const BUILD_OUTPUT_PATHS: &[&str] = &[".git", "extension.wasm", "grammars", "target"];
+
 #[derive(Parser, Debug)]
-#[command(name = "zed-extension")]
+#[command(
+    name = "zed-extension",
+    args_conflicts_with_subcommands = true,
+    subcommand_negates_reqs = true
+)]
 struct Args {
+    #[command(subcommand)]
+    command: Option<Commands>,
     /// The path to the extension directory
-    #[arg(long)]
-    source_dir: PathBuf,
+    #[arg(long, required = true)]
+    source_dir: Option<PathBuf>,
     /// The output directory to place the packaged extension.
-    #[arg(long)]
-    output_dir: PathBuf,
+    #[arg(long, required = true)]
+    output_dir: Option<PathBuf>,
     /// The path to a directory where build dependencies are downloaded
-    #[arg(long)]
-    scratch_dir: PathBuf,
+    #[arg(long, required = true)]
+    scratch_dir: Option<PathBuf>,
 }
 
-#[tokio::main]
-async fn main() -> Result<()> {
+#[derive(Subcommand, Debug)]
+enum Commands {
+    /// Build the extension and install or reload it as a dev extension in a running Zed.
+    Dev {
+        /// The path to the extension directory
+        #[arg(long, default_value = ".")]
+        source_dir: PathBuf,
+        /// The path to a directory where build dependencies are downloaded
+        #[arg(long)]
+        scratch_dir: PathBuf,
+        /// Rebuild and reload the extension whenever its files change.
+        #[arg(long)]
+        watch: bool,
+        /// The path to the `zed` CLI to reload the extension with.
+        #[arg(long, default_value = "zed")]
+        zed: PathBuf,
+    },
+    /// Build the extension and run its tests, comparing their results against snapshots.
+    Test {
+        /// The path to the extension directory
+        #[arg(long, default_value = ".")]
+        source_dir: PathBuf,
+        /// The path to a directory where build dependencies are downloaded
+        #[arg(long)]
+        scratch_dir: PathBuf,
+        /// Record the results of all tests as their snapshots, instead of comparing them.
+        #[arg(long)]
+        update_snapshots: bool,
+    },
+}
+
+fn main() -> Result<()> {
     env_logger::init();
 
     let args = Args::parse();
+    match args.command {
+        Some(Commands::Dev {
+            source_dir,
+            scratch_dir,
+            watch,
+            zed,
+        }) => tokio::runtime::Runtime::new()?.block_on(dev(source_dir, scratch_dir, watch, zed)),
+        Some(Commands::Test {
+            source_dir,
+            scratch_dir,
+            update_snapshots,
+        }) => run_tests(source_dir, scratch_dir, update_snapshots),
+        None => {
+            let (Some(source_dir), Some(output_dir), Some(scratch_dir)) =
+                (args.source_dir, args.output_dir, args.scratch_dir)
+            else {
+                bail!("--source-dir, --output-dir and --scratch-dir are required");
+            };
+            tokio::runtime::Runtime::new()?.block_on(package(source_dir, output_dir, scratch_dir))
+        }
+    }
+}
+
+async fn package(source_dir: PathBuf, output_dir: PathBuf, scratch_dir: PathBuf) -> Result<()> {
     let fs = Arc::new(RealFs::default());
     let engine = wasmtime::Engine::default();
     let mut wasm_store = WasmStore::new(engine)?;
 
-    let extension_path = args
-        .source_dir
+    let extension_path = source_dir
         .canonicalize()
         .context("failed to canonicalize source_dir")?;
-    let scratch_dir = args
-        .scratch_dir
+    let scratch_dir = scratch_dir
         .canonicalize()
         .context("failed to canonicalize scratch_dir")?;
-    let output_dir = if args.output_dir.is_relative() {
-        env::current_dir()?.join(&args.output_dir)
+    let output_dir = if output_dir.is_relative() {
+        env::current_dir()?.join(&output_dir)
     } else {
-        args.output_dir
+        output_dir
     };
 
     log::info!("loading extension manifest");
@@ -90,6 +162,8 @@ async fn main() -> Result<()> {
         );
     }
 
+    let changelog = fs::read_to_string(extension_path.join("CHANGELOG.md")).ok();
+
     let manifest_json = serde_json::to_string(&rpc::ExtensionApiManifest {
         name: manifest.name,
         version: manifest.version,
@@ -100,6 +174,7 @@ async fn main() -> Result<()> {
             .repository
             .ok_or_else(|| anyhow!("missing repository in extension manifest"))?,
         wasm_api_version: manifest.lib.version.map(|version| version.to_string()),
+        changelog,
     })?;
     fs::remove_dir_all(&archive_dir)?;
     fs::write(output_dir.join("manifest.json"), manifest_json.as_bytes())?;
@@ -107,6 +182,208 @@ async fn main() -> Result<()> {
     Ok(())
 }
 
+async fn dev(source_dir: PathBuf, scratch_dir: PathBuf, watch: bool, zed: PathBuf) -> Result<()> {
+    let fs = Arc::new(RealFs::default());
+    let extension_path = source_dir
+        .canonicalize()
+        .context("failed to canonicalize source_dir")?;
+    let scratch_dir = scratch_dir
+        .canonicalize()
+        .context("failed to canonicalize scratch_dir")?;
+    let builder = ExtensionBuilder::new(scratch_dir);
+
+    if !watch {
+        return build_and_reload_dev_extension(&builder, fs, &extension_path, &zed).await;
+    }
+
+    let (mut events, watcher) = fs.watch(&extension_path, WATCH_LATENCY).await;
+    watch_source_dirs(&extension_path, &extension_path, watcher.as_ref());
+    if let Err(error) =
+        build_and_reload_dev_extension(&builder, fs.clone(), &extension_path, &zed).await
+    {
+        log::error!("{error:?}");
+    }
+
+    log::info!("watching {} for changes", extension_path.display());
+    while let Some(paths) = events.next().await {
+        if !paths
+            .iter()
+            .any(|path| is_source_path(&extension_path, path))
+        {
+            continue;
+        }
+
+        // Watch the directories that were created since the last build.
+        watch_source_dirs(&extension_path, &extension_path, watcher.as_ref());
+        if let Err(error) =
+            build_and_reload_dev_extension(&builder, fs.clone(), &extension_path, &zed).await
+        {
+            log::error!("{error:?}");
+        }
+    }
+
+    Ok(())
+}
+
+async fn build_and_reload_dev_extension(
+    builder: &ExtensionBuilder,
+    fs: Arc<dyn Fs>,
+    extension_path: &Path,
+    zed: &Path,
+) -> Result<()> {
+    log::info!("loading extension manifest");
+    let mut manifest = ExtensionManifest::load(fs, extension_path).await?;
+
+    log::info!("compiling extension");
+    builder
+        .compile_extension(
+            extension_path,
+            &mut manifest,
+            CompileExtensionOptions { release: false },
+        )
+        .await
+        .context("failed to compile extension")?;
+
+    log::info!("reloading extension {}", manifest.id);
+    let status = Command::new(zed)
+        .arg("--reload-dev-extension")
+        .arg(extension_path)
+        .status()
+        .with_context(|| format!("failed to run {}", zed.display()))?;
+    if !status.success() {
+        bail!("failed to reload extension {} into Zed", manifest.id);
+    }
+
+    Ok(())
+}
+
+/// Returns whether a change to the given path within the extension directory
+/// should rebuild the extension.
+fn is_source_path(extension_path: &Path, path: &Path) -> bool {
+    let Ok(relative_path) = path.strip_prefix(extension_path) else {
+        return false;
+    };
+    match relative_path.components().next() {
+        Some(Component::Normal(name)) => !name
+            .to_str()
+            .is_some_and(|name| BUILD_OUTPUT_PATHS.contains(&name)),
+        _ => false,
+    }
+}
+
+/// Watches the given directory and its subdirectories, except for build outputs,
+/// as the file watcher may not watch directories recursively.
+fn watch_source_dirs(extension_path: &Path, dir: &Path, watcher: &dyn Watcher) {
+    watcher.add(dir).ok();
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() && is_source_path(extension_path, &path) {
+            watch_source_dirs(extension_path, &path, watcher);
+        }
+    }
+}
+
+fn run_tests(source_dir: PathBuf, scratch_dir: PathBuf, update_snapshots: bool) -> Result<()> {
+    let extension_path = source_dir
+        .canonicalize()
+        .context("failed to canonicalize source_dir")?;
+    let scratch_dir = scratch_dir
+        .canonicalize()
+        .context("failed to canonicalize scratch_dir")?;
+
+    // Extensions run in a WASM host that needs an app, but no windows.
+    gpui::App::new().run(move |cx| {
+        settings::init(cx);
+        release_channel::init(SemanticVersion::default(), cx);
+        language::init(cx);
+        Project::init_settings(cx);
+        ExtensionSettings::register(cx);
+
+        let fs = Arc::new(RealFs::default());
+        let http_client = http::client(None);
+        let runner = ExtensionTestRunner::new(
+            fs.clone(),
+            http_client.clone(),
+            RealNodeRuntime::new(http_client),
+            scratch_dir.join("work"),
+            cx,
+        );
+        let builder = ExtensionBuilder::new(scratch_dir);
+
+        cx.spawn(|cx| async move {
+            let executor = cx.background_executor().clone();
+            let result = async {
+                log::info!("loading extension manifest");
+                let mut manifest = ExtensionManifest::load(fs, &extension_path).await?;
+
+                log::info!("compiling extension");
+                let manifest = executor
+                    .spawn({
+                        let extension_path = extension_path.clone();
+                        async move {
+                            builder
+                                .compile_extension(
+                                    &extension_path,
+                                    &mut manifest,
+                                    CompileExtensionOptions { release: false },
+                                )
+                                .await
+                                .context("failed to compile extension")?;
+                            anyhow::Ok(manifest)
+                        }
+                    })
+                    .await?;
+
+                runner
+                    .run(
+                        &extension_path,
+                        Arc::new(manifest),
+                        update_snapshots,
+                        executor.clone(),
+                    )
+                    .await
+            }
+            .await;
+
+            let status = match result {
+                Ok(outcomes) => {
+                    let mut failures = 0;
+                    for outcome in outcomes {
+                        match outcome.result {
+                            ExtensionTestResult::Passed => println!("test {} ... ok", outcome.name),
+                            ExtensionTestResult::Recorded => {
+                                println!("test {} ... recorded snapshot", outcome.name)
+                            }
+                            ExtensionTestResult::Failed { expected, actual } => {
+                                failures += 1;
+                                println!("test {} ... FAILED", outcome.name);
+                                println!("expected:\n{expected}\nactual:\n{actual}");
+                            }
+                            ExtensionTestResult::Errored(error) => {
+                                failures += 1;
+                                println!("test {} ... FAILED", outcome.name);
+                                println!("{error:?}");
+                            }
+                        }
+                    }
+                    i32::from(failures > 0)
+                }
+                Err(error) => {
+                    eprintln!("{error:?}");
+                    1
+                }
+            };
+            std::process::exit(status);
+        })
+        .detach();
+    });
+
+    Ok(())
+}
+
 async fn copy_extension_resources(
     manifest: &ExtensionManifest,
     extension_path: &Path,
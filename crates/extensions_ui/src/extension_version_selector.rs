@@ -178,8 +178,11 @@ impl PickerDelegate for ExtensionVersionSelectorDelegate {
 
             update_settings_file::<ExtensionSettings>(self.fs.clone(), cx, {
                 let extension_id = extension_id.clone();
+                let version = version.clone();
                 move |settings| {
-                    settings.auto_update_extensions.insert(extension_id, false);
+                    settings
+                        .pinned_extension_versions
+                        .insert(extension_id, version);
                 }
             });
 
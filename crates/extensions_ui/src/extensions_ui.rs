@@ -9,16 +9,20 @@ use crate::extension_version_selector::{
 use client::telemetry::Telemetry;
 use client::ExtensionMetadata;
 use editor::{Editor, EditorElement, EditorStyle};
-use extension::{ExtensionManifest, ExtensionOperation, ExtensionStore};
+use extension::{
+    ExtensionAutoUpdatePolicy, ExtensionAutoUpdateSetting, ExtensionCapability, ExtensionManifest,
+    ExtensionOperation, ExtensionSettings, ExtensionStore,
+};
+use fs::Fs;
 use fuzzy::{match_strings, StringMatchCandidate};
 use gpui::{
     actions, uniform_list, AnyElement, AppContext, EventEmitter, FocusableView, FontStyle,
-    InteractiveElement, KeyContext, ParentElement, Render, Styled, Task, TextStyle,
+    InteractiveElement, KeyContext, ParentElement, PromptLevel, Render, Styled, Task, TextStyle,
     UniformListScrollHandle, View, ViewContext, VisualContext, WeakView, WhiteSpace, WindowContext,
 };
 use num_format::{Locale, ToFormattedString};
 use release_channel::ReleaseChannel;
-use settings::Settings;
+use settings::{update_settings_file, Settings};
 use std::ops::DerefMut;
 use std::time::Duration;
 use std::{ops::Range, sync::Arc};
@@ -28,6 +32,7 @@ use util::ResultExt as _;
 use workspace::item::TabContentParams;
 use workspace::{
     item::{Item, ItemEvent},
+    notifications::{simple_message_notification, NotificationId},
     Workspace, WorkspaceId,
 };
 
@@ -80,10 +85,50 @@ pub fn init(cx: &mut AppContext) {
             _ => {}
         })
         .detach();
+
+        if let Some(store) = ExtensionStore::try_global(cx) {
+            cx.subscribe(&store, |workspace, _, event, cx| match event {
+                extension::Event::ExtensionUpdateAvailable(extension_id) => {
+                    show_update_available_notification(workspace, extension_id, cx);
+                }
+                _ => {}
+            })
+            .detach();
+        }
     })
     .detach();
 }
 
+fn show_update_available_notification(
+    workspace: &mut Workspace,
+    extension_id: &Arc<str>,
+    cx: &mut ViewContext<Workspace>,
+) {
+    let Some(version) = ExtensionStore::global(cx)
+        .read(cx)
+        .available_update(extension_id)
+        .map(|extension| extension.manifest.version.clone())
+    else {
+        return;
+    };
+
+    struct ExtensionUpdateNotification;
+
+    let notification_id = NotificationId::identified::<ExtensionUpdateNotification>(
+        SharedString::from(extension_id.clone()),
+    );
+    let extension_id = extension_id.clone();
+    workspace.show_notification(notification_id, cx, |cx| {
+        cx.new_view(move |_cx| {
+            simple_message_notification::MessageNotification::new(format!(
+                "Version {version} of the '{extension_id}' extension is available."
+            ))
+            .with_click_message("Open Extensions")
+            .on_click(|cx| cx.dispatch_action(Box::new(Extensions)))
+        })
+    });
+}
+
 #[derive(Clone)]
 pub enum ExtensionStatus {
     NotInstalled,
@@ -177,6 +222,15 @@ impl ExtensionsPage {
             .extension_themes(extension_id)
             .map(|name| name.to_string())
             .collect::<Vec<_>>();
+        let ungranted_capabilities = extension_store.ungranted_capabilities(extension_id, cx);
+        if !ungranted_capabilities.is_empty() {
+            self.prompt_to_grant_capabilities(
+                workspace.clone(),
+                extension_id.into(),
+                ungranted_capabilities,
+                cx,
+            );
+        }
         if !themes.is_empty() {
             workspace
                 .update(cx, |workspace, cx| {
@@ -192,6 +246,170 @@ impl ExtensionsPage {
         }
     }
 
+    /// Asks the user whether to grant the capabilities that a newly installed extension declares.
+    fn prompt_to_grant_capabilities(
+        &mut self,
+        workspace: WeakView<Workspace>,
+        extension_id: Arc<str>,
+        capabilities: Vec<ExtensionCapability>,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let Some(fs) = workspace
+            .update(cx, |workspace, cx| {
+                workspace.project().read(cx).fs().clone()
+            })
+            .log_err()
+        else {
+            return;
+        };
+
+        let detail = capabilities
+            .iter()
+            .map(|capability| format!("• {capability}"))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let answer = cx.prompt(
+            PromptLevel::Warning,
+            &format!("Allow the {extension_id} extension to:"),
+            Some(&detail),
+            &["Allow", "Deny"],
+        );
+        cx.spawn(|_, mut cx| async move {
+            if answer.await.log_err() != Some(0) {
+                return;
+            }
+
+            cx.update(|cx| {
+                update_settings_file::<ExtensionSettings>(fs, cx, move |settings| {
+                    settings
+                        .granted_extension_capabilities
+                        .entry(extension_id)
+                        .or_default()
+                        .extend(capabilities);
+                })
+            })
+            .log_err();
+        })
+        .detach();
+    }
+
+    fn fs(&self, cx: &AppContext) -> Option<Arc<dyn Fs>> {
+        let workspace = self.workspace.upgrade()?;
+        Some(workspace.read(cx).project().read(cx).fs().clone())
+    }
+
+    /// Upgrades the given extension, after showing the user what changed since the installed
+    /// version.
+    fn upgrade_extension(
+        &mut self,
+        extension_id: Arc<str>,
+        version: Arc<str>,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let changelog_diff = ExtensionStore::global(cx).update(cx, |store, cx| {
+            store.fetch_changelog_diff(&extension_id, version.clone(), cx)
+        });
+        cx.spawn(|this, mut cx| async move {
+            let changelog_diff = changelog_diff
+                .await
+                .log_err()
+                .flatten()
+                .filter(|changelog_diff| !changelog_diff.trim().is_empty());
+            if let Some(changelog_diff) = changelog_diff {
+                let answer = this.update(&mut cx, |_, cx| {
+                    cx.prompt(
+                        PromptLevel::Info,
+                        &format!("Update the {extension_id} extension to v{version}?"),
+                        Some(changelog_diff.trim()),
+                        &["Update", "Cancel"],
+                    )
+                })?;
+                if answer.await.log_err() != Some(0) {
+                    return Ok(());
+                }
+            }
+
+            this.update(&mut cx, |this, cx| {
+                this.telemetry
+                    .report_app_event("extensions: install extension".to_string());
+
+                // Keep a pinned extension pinned to the version that the user chose.
+                let is_pinned = ExtensionSettings::get_global(cx)
+                    .pinned_version(&extension_id)
+                    .is_some();
+                if let Some(fs) = this.fs(cx).filter(|_| is_pinned) {
+                    update_settings_file::<ExtensionSettings>(fs, cx, {
+                        let extension_id = extension_id.clone();
+                        let version = version.clone();
+                        move |settings| {
+                            settings
+                                .pinned_extension_versions
+                                .insert(extension_id, version);
+                        }
+                    });
+                }
+
+                ExtensionStore::global(cx).update(cx, |store, cx| {
+                    store.upgrade_extension(extension_id, version, cx)
+                })
+            })?
+            .await
+        })
+        .detach_and_log_err(cx);
+    }
+
+    /// Rolls the given extension back to its previously installed version, and pins it there so
+    /// that it isn't updated again automatically.
+    fn rollback_extension(
+        &mut self,
+        extension_id: Arc<str>,
+        version: Arc<str>,
+        cx: &mut ViewContext<Self>,
+    ) {
+        self.telemetry
+            .report_app_event("extensions: roll back extension".to_string());
+        if let Some(fs) = self.fs(cx) {
+            update_settings_file::<ExtensionSettings>(fs, cx, {
+                let extension_id = extension_id.clone();
+                move |settings| {
+                    settings
+                        .pinned_extension_versions
+                        .insert(extension_id, version);
+                }
+            });
+        }
+        ExtensionStore::global(cx).update(cx, |store, cx| {
+            store
+                .rollback_extension(extension_id, cx)
+                .detach_and_log_err(cx)
+        });
+    }
+
+    fn unpin_extension_version(&mut self, extension_id: Arc<str>, cx: &mut ViewContext<Self>) {
+        let Some(fs) = self.fs(cx) else {
+            return;
+        };
+        update_settings_file::<ExtensionSettings>(fs, cx, move |settings| {
+            settings.pinned_extension_versions.remove(&extension_id);
+        });
+    }
+
+    fn set_auto_update_policy(
+        &mut self,
+        extension_id: Arc<str>,
+        policy: ExtensionAutoUpdatePolicy,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let Some(fs) = self.fs(cx) else {
+            return;
+        };
+        update_settings_file::<ExtensionSettings>(fs, cx, move |settings| {
+            settings
+                .auto_update_extensions
+                .insert(extension_id, ExtensionAutoUpdateSetting::Policy(policy));
+        });
+    }
+
     /// Returns whether a dev extension currently exists for the extension with the given ID.
     fn dev_extension_exists(extension_id: &str, cx: &mut ViewContext<Self>) -> bool {
         let extension_store = ExtensionStore::global(cx).read(cx);
@@ -207,7 +425,9 @@ impl ExtensionsPage {
         match extension_store.outstanding_operations().get(extension_id) {
             Some(ExtensionOperation::Install) => ExtensionStatus::Installing,
             Some(ExtensionOperation::Remove) => ExtensionStatus::Removing,
-            Some(ExtensionOperation::Upgrade) => ExtensionStatus::Upgrading,
+            Some(ExtensionOperation::Upgrade | ExtensionOperation::Rollback) => {
+                ExtensionStatus::Upgrading
+            }
             None => match extension_store.installed_extensions().get(extension_id) {
                 Some(extension) => ExtensionStatus::Installed(extension.manifest.version.clone()),
                 None => ExtensionStatus::NotInstalled,
@@ -556,14 +776,71 @@ impl ExtensionsPage {
         extension_id: Arc<str>,
         cx: &mut WindowContext,
     ) -> View<ContextMenu> {
-        let context_menu = ContextMenu::build(cx, |context_menu, cx| {
-            context_menu.entry(
+        let extension_store = ExtensionStore::global(cx).read(cx);
+        let is_installed = extension_store
+            .installed_extensions()
+            .contains_key(&extension_id);
+        let previous_version = extension_store.previous_version(&extension_id).cloned();
+        let extension_settings = ExtensionSettings::get_global(cx);
+        let pinned_version = extension_settings.pinned_version(&extension_id).cloned();
+        let auto_update_policy = extension_settings.auto_update_policy(&extension_id);
+
+        let context_menu = ContextMenu::build(cx, |mut context_menu, cx| {
+            context_menu = context_menu.entry(
                 "Install Another Version...",
                 None,
-                cx.handler_for(&this, move |this, cx| {
-                    this.show_extension_version_list(extension_id.clone(), cx)
+                cx.handler_for(&this, {
+                    let extension_id = extension_id.clone();
+                    move |this, cx| this.show_extension_version_list(extension_id.clone(), cx)
                 }),
-            )
+            );
+            if let Some(previous_version) = previous_version {
+                context_menu = context_menu.entry(
+                    format!("Roll Back to v{previous_version}"),
+                    None,
+                    cx.handler_for(&this, {
+                        let extension_id = extension_id.clone();
+                        move |this, cx| {
+                            this.rollback_extension(
+                                extension_id.clone(),
+                                previous_version.clone(),
+                                cx,
+                            )
+                        }
+                    }),
+                );
+            }
+            if let Some(pinned_version) = pinned_version {
+                context_menu = context_menu.entry(
+                    format!("Unpin v{pinned_version}"),
+                    None,
+                    cx.handler_for(&this, {
+                        let extension_id = extension_id.clone();
+                        move |this, cx| this.unpin_extension_version(extension_id.clone(), cx)
+                    }),
+                );
+            }
+            if is_installed {
+                context_menu = context_menu.separator().header("Auto-Update");
+                for (label, policy) in [
+                    ("On", ExtensionAutoUpdatePolicy::On),
+                    ("Notify", ExtensionAutoUpdatePolicy::Notify),
+                    ("Off", ExtensionAutoUpdatePolicy::Off),
+                ] {
+                    context_menu = context_menu.toggleable_entry(
+                        label,
+                        auto_update_policy == policy,
+                        None,
+                        cx.handler_for(&this, {
+                            let extension_id = extension_id.clone();
+                            move |this, cx| {
+                                this.set_auto_update_policy(extension_id.clone(), policy, cx)
+                            }
+                        }),
+                    );
+                }
+            }
+            context_menu
         });
 
         context_menu
@@ -684,18 +961,11 @@ impl ExtensionsPage {
                                 let extension_id = extension.id.clone();
                                 let version = extension.manifest.version.clone();
                                 move |this, _, cx| {
-                                    this.telemetry.report_app_event(
-                                        "extensions: install extension".to_string(),
-                                    );
-                                    ExtensionStore::global(cx).update(cx, |store, cx| {
-                                        store
-                                            .upgrade_extension(
-                                                extension_id.clone(),
-                                                version.clone(),
-                                                cx,
-                                            )
-                                            .detach_and_log_err(cx)
-                                    });
+                                    this.upgrade_extension(
+                                        extension_id.clone(),
+                                        version.clone(),
+                                        cx,
+                                    )
                                 }
                             })),
                     )
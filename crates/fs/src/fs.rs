@@ -28,7 +28,10 @@ use util::ResultExt;
 #[cfg(any(test, feature = "test-support"))]
 use collections::{btree_map, BTreeMap};
 #[cfg(any(test, feature = "test-support"))]
-use git::repository::{FakeGitRepositoryState, GitFileStatus};
+use git::{
+    repository::{FakeGitRepositoryState, GitFileStatus},
+    status::StagingStatus,
+};
 #[cfg(any(test, feature = "test-support"))]
 use parking_lot::Mutex;
 #[cfg(any(test, feature = "test-support"))]
@@ -990,6 +993,64 @@ impl FakeFs {
         });
     }
 
+    pub fn set_head_for_repo(&self, dot_git: &Path, head_state: &[(&Path, String)]) {
+        self.with_git_state(dot_git, true, |state| {
+            state.head_contents.clear();
+            state.head_contents.extend(
+                head_state
+                    .iter()
+                    .map(|(path, content)| (path.to_path_buf(), content.clone())),
+            );
+        });
+    }
+
+    pub fn set_revision_for_repo(
+        &self,
+        dot_git: &Path,
+        revision: &str,
+        revision_state: &[(&Path, String)],
+    ) {
+        self.with_git_state(dot_git, true, |state| {
+            state.revision_contents.insert(
+                revision.to_string(),
+                revision_state
+                    .iter()
+                    .map(|(path, content)| (path.to_path_buf(), content.clone()))
+                    .collect(),
+            );
+        });
+    }
+
+    pub fn set_merge_base_for_repo(
+        &self,
+        dot_git: &Path,
+        revisions: (&str, &str),
+        merge_base: &str,
+    ) {
+        self.with_git_state(dot_git, true, |state| {
+            state.merge_bases.insert(
+                (revisions.0.to_string(), revisions.1.to_string()),
+                merge_base.to_string(),
+            );
+        });
+    }
+
+    /// Sets the history of HEAD, along with the files changed by each commit.
+    pub fn set_log_for_repo(
+        &self,
+        dot_git: &Path,
+        log: git::log::Log,
+        commit_changes: Vec<(git::Oid, Vec<git::repository::CommitFileChange>)>,
+    ) {
+        self.with_git_state(dot_git, true, |state| {
+            state.log = log;
+            state.commit_changes = commit_changes
+                .into_iter()
+                .map(|(sha, changes)| (sha.to_string(), changes))
+                .collect();
+        });
+    }
+
     pub fn set_blame_for_repo(&self, dot_git: &Path, blames: Vec<(&Path, git::blame::Blame)>) {
         self.with_git_state(dot_git, true, |state| {
             state.blames.clear();
@@ -1036,6 +1097,23 @@ impl FakeFs {
         });
     }
 
+    pub fn set_staging_status_for_repo(&self, dot_git: &Path, statuses: &[(&Path, StagingStatus)]) {
+        self.with_git_state(dot_git, true, |state| {
+            state.staging_statuses.clear();
+            state.staging_statuses.extend(
+                statuses
+                    .iter()
+                    .map(|(path, status)| ((**path).into(), *status)),
+            );
+        });
+    }
+
+    pub fn git_commits(&self, dot_git: &Path) -> Vec<(String, bool)> {
+        let mut commits = Vec::new();
+        self.with_git_state(dot_git, false, |state| commits = state.commits.clone());
+        commits
+    }
+
     pub fn paths(&self, include_dot_git: bool) -> Vec<PathBuf> {
         let mut result = Vec::new();
         let mut queue = collections::VecDeque::new();
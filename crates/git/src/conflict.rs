@@ -0,0 +1,221 @@
+use std::ops::Range;
+use text::{Anchor, BufferSnapshot};
+
+const OURS_MARKER: &str = "<<<<<<<";
+const BASE_MARKER: &str = "|||||||";
+const SEPARATOR_MARKER: &str = "=======";
+const THEIRS_MARKER: &str = ">>>>>>>";
+
+/// A region of a file with conflicting changes, delimited by the markers that git inserts when
+/// a merge, rebase or cherry-pick can't combine the changes of both sides.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Conflict {
+    /// The whole region, from the start of the `<<<<<<<` line to the end of the `>>>>>>>` line.
+    pub range: Range<Anchor>,
+    /// The text of our side, after the `<<<<<<<` line.
+    pub ours: Range<Anchor>,
+    /// The text of the common ancestor after the `|||||||` line, in `diff3` style conflicts.
+    pub base: Option<Range<Anchor>>,
+    /// The text of their side, after the `=======` line.
+    pub theirs: Range<Anchor>,
+    /// The start of the `=======` line.
+    pub separator: Anchor,
+    /// The label of our side on the `<<<<<<<` line, like `HEAD`.
+    pub ours_label: String,
+    /// The label of their side on the `>>>>>>>` line, like the name of the merged branch.
+    pub theirs_label: String,
+}
+
+/// How to resolve a [`Conflict`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConflictResolution {
+    Ours,
+    Theirs,
+    /// Keeps our side, followed by their side.
+    Both,
+}
+
+impl Conflict {
+    /// Returns the text that replaces the conflict's region when resolving it.
+    pub fn resolved_text(&self, resolution: ConflictResolution, buffer: &BufferSnapshot) -> String {
+        let text = |range: &Range<Anchor>| buffer.text_for_range(range.clone()).collect::<String>();
+        match resolution {
+            ConflictResolution::Ours => text(&self.ours),
+            ConflictResolution::Theirs => text(&self.theirs),
+            ConflictResolution::Both => text(&self.ours) + &text(&self.theirs),
+        }
+    }
+}
+
+fn marker_label<'a>(line: &'a str, marker: &str) -> Option<&'a str> {
+    let rest = line.strip_prefix(marker)?;
+    let rest = rest.trim_end_matches(['\n', '\r']);
+    if rest.is_empty() {
+        Some(rest)
+    } else {
+        rest.strip_prefix([' ', '\t']).map(str::trim)
+    }
+}
+
+/// Finds the conflicts in a buffer. Markers that don't form a complete conflict are ignored.
+pub fn parse_conflicts(buffer: &BufferSnapshot) -> Vec<Conflict> {
+    let text = buffer.text();
+    if !text.contains(OURS_MARKER) {
+        return Vec::new();
+    }
+
+    struct PendingConflict<'a> {
+        start: usize,
+        ours_start: usize,
+        ours_label: &'a str,
+        base_start: Option<(usize, usize)>,
+        separator: Option<(usize, usize)>,
+    }
+
+    let mut conflicts = Vec::new();
+    let mut pending: Option<PendingConflict> = None;
+    let mut offset = 0;
+    for line in text.split_inclusive('\n') {
+        let line_start = offset;
+        let line_end = offset + line.len();
+        offset = line_end;
+
+        if let Some(label) = marker_label(line, OURS_MARKER) {
+            // A new conflict starts, abandoning any incomplete one.
+            pending = Some(PendingConflict {
+                start: line_start,
+                ours_start: line_end,
+                ours_label: label,
+                base_start: None,
+                separator: None,
+            });
+            continue;
+        }
+        let Some(conflict) = pending.as_mut() else {
+            continue;
+        };
+        if conflict.separator.is_none() {
+            if conflict.base_start.is_none() && marker_label(line, BASE_MARKER).is_some() {
+                conflict.base_start = Some((line_start, line_end));
+            } else if marker_label(line, SEPARATOR_MARKER) == Some("") {
+                conflict.separator = Some((line_start, line_end));
+            }
+        } else if let Some(theirs_label) = marker_label(line, THEIRS_MARKER) {
+            let conflict = pending.take().unwrap();
+            let (separator_start, theirs_start) = conflict.separator.unwrap();
+            let ours_end = conflict
+                .base_start
+                .map_or(separator_start, |(base_marker_start, _)| base_marker_start);
+            conflicts.push(Conflict {
+                range: buffer.anchor_before(conflict.start)..buffer.anchor_after(line_end),
+                ours: buffer.anchor_before(conflict.ours_start)..buffer.anchor_after(ours_end),
+                base: conflict.base_start.map(|(_, base_start)| {
+                    buffer.anchor_before(base_start)..buffer.anchor_after(separator_start)
+                }),
+                theirs: buffer.anchor_before(theirs_start)..buffer.anchor_after(line_start),
+                separator: buffer.anchor_before(separator_start),
+                ours_label: conflict.ours_label.to_string(),
+                theirs_label: theirs_label.to_string(),
+            });
+        }
+    }
+    conflicts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use text::{Buffer, BufferId, ToOffset};
+    use unindent::Unindent as _;
+
+    fn text_for(buffer: &Buffer, range: &Range<Anchor>) -> String {
+        buffer.text_for_range(range.clone()).collect()
+    }
+
+    #[test]
+    fn test_parse_conflicts() {
+        let text = "
+            one
+            <<<<<<< HEAD
+            two
+            =======
+            TWO
+            >>>>>>> feature
+            three
+            <<<<<<< ours
+            four
+            ||||||| base
+            4
+            =======
+            >>>>>>> theirs
+        "
+        .unindent();
+        let buffer = Buffer::new(0, BufferId::new(1).unwrap(), text.clone());
+        let conflicts = parse_conflicts(&buffer);
+        assert_eq!(conflicts.len(), 2);
+
+        let conflict = &conflicts[0];
+        assert_eq!(
+            text_for(&buffer, &conflict.range),
+            "<<<<<<< HEAD\ntwo\n=======\nTWO\n>>>>>>> feature\n"
+        );
+        assert_eq!(text_for(&buffer, &conflict.ours), "two\n");
+        assert_eq!(conflict.base, None);
+        assert_eq!(text_for(&buffer, &conflict.theirs), "TWO\n");
+        assert_eq!(
+            conflict.separator.to_offset(&buffer),
+            text.find("=======").unwrap()
+        );
+        assert_eq!(conflict.ours_label, "HEAD");
+        assert_eq!(conflict.theirs_label, "feature");
+        assert_eq!(
+            conflict.resolved_text(ConflictResolution::Ours, &buffer),
+            "two\n"
+        );
+        assert_eq!(
+            conflict.resolved_text(ConflictResolution::Theirs, &buffer),
+            "TWO\n"
+        );
+        assert_eq!(
+            conflict.resolved_text(ConflictResolution::Both, &buffer),
+            "two\nTWO\n"
+        );
+
+        let conflict = &conflicts[1];
+        assert_eq!(text_for(&buffer, &conflict.ours), "four\n");
+        assert_eq!(
+            conflict.base.as_ref().map(|base| text_for(&buffer, base)),
+            Some("4\n".to_string())
+        );
+        assert_eq!(text_for(&buffer, &conflict.theirs), "");
+        assert_eq!(conflict.ours_label, "ours");
+        assert_eq!(conflict.theirs_label, "theirs");
+    }
+
+    #[test]
+    fn test_parse_incomplete_conflicts() {
+        let text = "
+            <<<<<<< HEAD
+            one
+            <<<<<<< HEAD
+            two
+            ========
+            =======
+            TWO
+            >>>>>>> feature
+            <<<<<<< HEAD
+            three
+            =======
+            THREE
+        "
+        .unindent();
+        let buffer = Buffer::new(0, BufferId::new(1).unwrap(), text);
+        let conflicts = parse_conflicts(&buffer);
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(text_for(&buffer, &conflicts[0].ours), "two\n========\n");
+        assert_eq!(text_for(&buffer, &conflicts[0].theirs), "TWO\n");
+
+        let buffer = Buffer::new(0, BufferId::new(1).unwrap(), "no conflicts\n".into());
+        assert_eq!(parse_conflicts(&buffer), Vec::new());
+    }
+}
@@ -178,7 +178,13 @@ impl BufferDiff {
         if let Some(patch) = patch {
             let mut divergence = 0;
             for hunk_index in 0..patch.num_hunks() {
-                let hunk = Self::process_patch_hunk(&patch, hunk_index, buffer, &mut divergence);
+                let hunk = Self::process_patch_hunk(
+                    &patch,
+                    hunk_index,
+                    diff_base,
+                    buffer,
+                    &mut divergence,
+                );
                 tree.push(hunk, buffer);
             }
         }
@@ -219,6 +225,7 @@ impl BufferDiff {
     fn process_patch_hunk(
         patch: &GitPatch<'_>,
         hunk_index: usize,
+        diff_base: &Rope,
         buffer: &text::BufferSnapshot,
         buffer_row_divergence: &mut i64,
     ) -> DiffHunk<Anchor> {
@@ -270,8 +277,13 @@ impl BufferDiff {
             row..row
         });
 
-        //unwrap_or addition without deletion
-        let diff_base_byte_range = diff_base_byte_range.unwrap_or(0..0);
+        //unwrap_or addition without deletion, which is empty at the diff base line it was added after
+        let diff_base_byte_range = diff_base_byte_range.unwrap_or_else(|| {
+            let (hunk, _) = patch.hunk(hunk_index).unwrap();
+            let offset = diff_base
+                .point_to_offset(Point::new(hunk.old_start(), 0).min(diff_base.max_point()));
+            offset..offset
+        });
 
         let start = Point::new(buffer_row_range.start, 0);
         let end = Point::new(buffer_row_range.end, 0);
@@ -363,6 +375,17 @@ mod tests {
             &[(0..1, "", "point five\n"), (2..3, "two\n", "HELLO\n")],
         );
 
+        // Added lines are located at the diff base offset they were inserted at.
+        let added_hunk = diff.hunks(&buffer).next().unwrap();
+        assert_eq!(added_hunk.diff_base_byte_range, 0..0);
+        buffer.edit([(buffer.len()..buffer.len(), "four\n")]);
+        smol::block_on(diff.update(&diff_base_rope, &buffer));
+        let added_hunk = diff.hunks(&buffer).last().unwrap();
+        assert_eq!(
+            added_hunk.diff_base_byte_range,
+            diff_base.len()..diff_base.len()
+        );
+
         diff.clear(&buffer);
         assert_hunks(diff.hunks(&buffer), &buffer, &diff_base, &[]);
     }
@@ -0,0 +1,188 @@
+use crate::commit::get_messages;
+use crate::Oid;
+use anyhow::{anyhow, Context, Result};
+use collections::HashMap;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::process::{Command, Stdio};
+use time::macros::format_description;
+use time::OffsetDateTime;
+use time::UtcOffset;
+
+#[derive(Debug, Clone, Default)]
+pub struct Log {
+    pub entries: Vec<LogEntry>,
+    pub messages: HashMap<Oid, String>,
+    pub remote_url: Option<String>,
+}
+
+impl Log {
+    /// Lists the last `limit` commits reachable from HEAD, newest first. When a path is given,
+    /// only the commits that changed it are listed.
+    pub fn for_path(
+        git_binary: &Path,
+        working_directory: &Path,
+        path: Option<&Path>,
+        limit: usize,
+        remote_url: Option<String>,
+    ) -> Result<Self> {
+        let output = run_git_log(git_binary, working_directory, path, limit)?;
+        let entries = parse_git_log(&output)?;
+
+        let shas = entries.iter().map(|entry| entry.sha).collect::<Vec<_>>();
+        let messages =
+            get_messages(&working_directory, &shas).context("failed to get commit messages")?;
+
+        Ok(Self {
+            entries,
+            messages,
+            remote_url,
+        })
+    }
+}
+
+const GIT_LOG_NO_COMMITS_ERROR: &'static str = "does not have any commits yet";
+
+/// Separates the fields of an entry in the output of `git log`.
+const FIELD_SEPARATOR: char = '\0';
+/// Terminates an entry in the output of `git log`.
+const ENTRY_TERMINATOR: char = '\x1e';
+
+fn run_git_log(
+    git_binary: &Path,
+    working_directory: &Path,
+    path: Option<&Path>,
+    limit: usize,
+) -> Result<String> {
+    let mut child = Command::new(git_binary);
+
+    child
+        .current_dir(working_directory)
+        .arg("log")
+        .arg(format!("--max-count={}", limit))
+        .arg("--date=format:%z")
+        .arg("--format=%H%x00%an%x00%ae%x00%at%x00%ad%x00%s%x1e")
+        .arg("--");
+    if let Some(path) = path {
+        child.arg(path.as_os_str());
+    }
+    child.stdout(Stdio::piped()).stderr(Stdio::piped());
+
+    #[cfg(windows)]
+    {
+        use std::os::windows::process::CommandExt;
+        child.creation_flags(windows::Win32::System::Threading::CREATE_NO_WINDOW.0);
+    }
+
+    let output = child
+        .output()
+        .map_err(|e| anyhow!("Failed to start git log process: {}", e))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        if stderr.contains(GIT_LOG_NO_COMMITS_ERROR) {
+            return Ok(String::new());
+        }
+        return Err(anyhow!("git log process failed: {}", stderr.trim()));
+    }
+
+    Ok(String::from_utf8(output.stdout)?)
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct LogEntry {
+    pub sha: Oid,
+    pub author: String,
+    pub author_mail: String,
+    pub author_time: i64,
+    pub author_tz: String,
+    pub summary: String,
+}
+
+impl LogEntry {
+    pub fn author_offset_date_time(&self) -> Result<time::OffsetDateTime> {
+        let format = format_description!("[offset_hour][offset_minute]");
+        let offset = UtcOffset::parse(&self.author_tz, &format)?;
+        let date_time_utc = OffsetDateTime::from_unix_timestamp(self.author_time)?;
+        Ok(date_time_utc.to_offset(offset))
+    }
+}
+
+// parse_git_log parses the output of `git log` with the format passed by
+// run_git_log. Each entry is terminated by a record separator and consists
+// of these fields, separated by NUL bytes:
+//
+//     <40-byte-hex-sha1>
+//     <author name>
+//     <author email>
+//     <author time, in seconds since the epoch>
+//     <author timezone, like +0100>
+//     <subject line of the commit message>
+fn parse_git_log(output: &str) -> Result<Vec<LogEntry>> {
+    output
+        .split_terminator(ENTRY_TERMINATOR)
+        .map(|entry| entry.trim_start_matches('\n'))
+        .filter(|entry| !entry.is_empty())
+        .map(|entry| {
+            let mut fields = entry.split(FIELD_SEPARATOR);
+            let mut next_field = |name: &str| {
+                fields
+                    .next()
+                    .with_context(|| format!("missing {name} in git log entry {entry:?}"))
+            };
+
+            let sha = next_field("sha")?.parse::<Oid>()?;
+            let author = next_field("author")?.to_string();
+            let author_mail = next_field("author mail")?.to_string();
+            let author_time = next_field("author time")?
+                .parse::<i64>()
+                .context("failed to parse author time")?;
+            let author_tz = next_field("author timezone")?.to_string();
+            let summary = next_field("summary")?.to_string();
+
+            Ok(LogEntry {
+                sha,
+                author,
+                author_mail,
+                author_time,
+                author_tz,
+                summary,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_git_log;
+
+    #[test]
+    fn test_parse_git_log() {
+        let output = concat!(
+            "6ad46b5257ba16d12c5ca9f0d4900320959df7f4\0Joe Schmoe\0joe.schmoe@example.com\0",
+            "1709741400\0+0100\0Joe's cool commit\x1e\n",
+            "486c2409237a2c627230589e567024a96751d475\0Jane Doe\0jane@example.com\0",
+            "1709655000\0-0500\0Initial commit\x1e\n",
+        );
+        let entries = parse_git_log(output).unwrap();
+
+        assert_eq!(entries.len(), 2);
+        assert_eq!(
+            entries[0].sha.to_string(),
+            "6ad46b5257ba16d12c5ca9f0d4900320959df7f4"
+        );
+        assert_eq!(entries[0].author, "Joe Schmoe");
+        assert_eq!(entries[0].author_mail, "joe.schmoe@example.com");
+        assert_eq!(entries[0].author_time, 1709741400);
+        assert_eq!(entries[0].summary, "Joe's cool commit");
+        assert_eq!(
+            entries[0].author_offset_date_time().unwrap().offset(),
+            time::UtcOffset::from_hms(1, 0, 0).unwrap()
+        );
+        assert_eq!(entries[1].author, "Jane Doe");
+        assert_eq!(entries[1].summary, "Initial commit");
+
+        assert_eq!(parse_git_log("").unwrap(), Vec::new());
+        assert!(parse_git_log("6ad46b5257ba16d12c5ca9f0d4900320959df7f4\0Joe\x1e").is_err());
+    }
+}
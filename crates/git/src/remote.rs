@@ -0,0 +1,241 @@
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::io::Read;
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+/// How `git pull` integrates the upstream changes into the current branch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PullMode {
+    /// Fails unless the current branch can be fast-forwarded to its upstream.
+    FastForwardOnly,
+    /// Rebases the commits of the current branch onto its upstream.
+    Rebase,
+}
+
+/// The number of commits of the current branch that its upstream doesn't have, and vice versa.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct AheadBehind {
+    pub ahead: usize,
+    pub behind: usize,
+}
+
+/// The progress of a stage of a network operation, like `Receiving objects`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RemoteProgress {
+    pub stage: String,
+    pub percentage: u8,
+}
+
+/// A username and password entered by the user, used instead of git's credential helpers.
+#[derive(Clone, PartialEq, Eq)]
+pub struct RemoteCredentials {
+    pub username: String,
+    pub password: String,
+}
+
+impl fmt::Debug for RemoteCredentials {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RemoteCredentials")
+            .field("username", &self.username)
+            .finish_non_exhaustive()
+    }
+}
+
+/// The error of a network operation that failed because git had no credentials for the remote,
+/// or because the remote rejected them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuthenticationRequired {
+    pub url: Option<String>,
+}
+
+impl fmt::Display for AuthenticationRequired {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.url {
+            Some(url) => write!(f, "authentication required for {url}"),
+            None => write!(f, "authentication required"),
+        }
+    }
+}
+
+impl std::error::Error for AuthenticationRequired {}
+
+/// Messages of git and common hosts when credentials are missing or wrong.
+const AUTHENTICATION_ERRORS: &[&str] = &[
+    "terminal prompts disabled",
+    "could not read Username",
+    "could not read Password",
+    "Authentication failed",
+    "Invalid username or password",
+];
+
+/// A credential helper answering with the credentials passed in the environment.
+const CREDENTIAL_HELPER: &str = "credential.helper=!f() { test \"$1\" = get && echo \"username=$ZED_GIT_USERNAME\" && echo \"password=$ZED_GIT_PASSWORD\"; }; f";
+
+/// Runs a git command that talks to a remote, like `fetch` or `push`, reporting the progress
+/// that git prints to stderr. Git never prompts for credentials on the terminal; when it
+/// can't authenticate, the command fails with [`AuthenticationRequired`], and can be run again
+/// with credentials entered by the user.
+pub(crate) fn run_remote_command(
+    git_binary: &Path,
+    working_directory: &Path,
+    args: &[&str],
+    credentials: Option<&RemoteCredentials>,
+    on_progress: &dyn Fn(RemoteProgress),
+) -> Result<()> {
+    let mut command = Command::new(git_binary);
+    command
+        .current_dir(working_directory)
+        .env("GIT_TERMINAL_PROMPT", "0");
+    if let Some(credentials) = credentials {
+        // The empty value resets the configured helpers, whose credentials were rejected.
+        command
+            .args(["-c", "credential.helper=", "-c", CREDENTIAL_HELPER])
+            .env("ZED_GIT_USERNAME", &credentials.username)
+            .env("ZED_GIT_PASSWORD", &credentials.password);
+    }
+    command
+        .args(args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+
+    #[cfg(windows)]
+    {
+        use std::os::windows::process::CommandExt;
+        command.creation_flags(windows::Win32::System::Threading::CREATE_NO_WINDOW.0);
+    }
+
+    let name = args.first().copied().unwrap_or_default();
+    let mut child = command
+        .spawn()
+        .map_err(|e| anyhow!("Failed to start git {} process: {}", name, e))?;
+
+    let mut stdout = child.stdout.take().context("failed to read git stdout")?;
+    let stdout = std::thread::spawn(move || {
+        let mut output = String::new();
+        stdout.read_to_string(&mut output).ok();
+        output
+    });
+
+    // Progress lines are terminated by carriage returns, so that they overwrite each other.
+    let mut stderr = child.stderr.take().context("failed to read git stderr")?;
+    let mut messages = String::new();
+    let mut line = Vec::new();
+    let mut buffer = [0; 4096];
+    let mut flush_line = |line: &mut Vec<u8>| {
+        let text = String::from_utf8_lossy(line);
+        if let Some(progress) = parse_progress(&text) {
+            on_progress(progress);
+        } else if !text.trim().is_empty() {
+            messages.push_str(text.trim_end());
+            messages.push('\n');
+        }
+        line.clear();
+    };
+    loop {
+        let len = stderr.read(&mut buffer)?;
+        if len == 0 {
+            break;
+        }
+        for &byte in &buffer[..len] {
+            if byte == b'\r' || byte == b'\n' {
+                flush_line(&mut line);
+            } else {
+                line.push(byte);
+            }
+        }
+    }
+    flush_line(&mut line);
+
+    let status = child.wait()?;
+    let stdout = stdout.join().unwrap_or_default();
+    if status.success() {
+        return Ok(());
+    }
+    if AUTHENTICATION_ERRORS
+        .iter()
+        .any(|error| messages.contains(error))
+    {
+        return Err(AuthenticationRequired {
+            url: quoted_url(&messages),
+        }
+        .into());
+    }
+    let error = if messages.trim().is_empty() {
+        stdout
+    } else {
+        messages
+    };
+    Err(anyhow!("git {} failed: {}", name, error.trim()))
+}
+
+/// Parses a progress line printed by git, like `Receiving objects:  45% (9/20)`.
+fn parse_progress(line: &str) -> Option<RemoteProgress> {
+    let line = line.trim();
+    let line = line.strip_prefix("remote:").unwrap_or(line).trim_start();
+    let (stage, rest) = line.split_once(": ")?;
+    let (percentage, _) = rest.trim_start().split_once('%')?;
+    Some(RemoteProgress {
+        stage: stage.to_string(),
+        percentage: percentage.parse().ok()?,
+    })
+}
+
+/// Finds the URL that git quotes in its error messages, like
+/// `could not read Username for 'https://example.com'`.
+fn quoted_url(messages: &str) -> Option<String> {
+    messages
+        .split('\'')
+        .skip(1)
+        .step_by(2)
+        .find(|quoted| quoted.contains("://"))
+        .map(str::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_progress() {
+        assert_eq!(
+            parse_progress("Receiving objects:  45% (9/20), 1.20 MiB | 2.00 MiB/s"),
+            Some(RemoteProgress {
+                stage: "Receiving objects".into(),
+                percentage: 45,
+            })
+        );
+        assert_eq!(
+            parse_progress("remote: Counting objects: 100% (3/3), done."),
+            Some(RemoteProgress {
+                stage: "Counting objects".into(),
+                percentage: 100,
+            })
+        );
+        assert_eq!(
+            parse_progress("remote: Enumerating objects: 5, done."),
+            None
+        );
+        assert_eq!(parse_progress("From github.com:zed-industries/zed"), None);
+    }
+
+    #[test]
+    fn test_quoted_url() {
+        assert_eq!(
+            quoted_url(
+                "fatal: could not read Username for 'https://example.com': terminal prompts disabled"
+            ),
+            Some("https://example.com".into())
+        );
+        assert_eq!(
+            quoted_url("remote: Invalid username or password.\nfatal: Authentication failed for 'https://example.com/a/b.git/'"),
+            Some("https://example.com/a/b.git/".into())
+        );
+        assert_eq!(
+            quoted_url("fatal: 'origin' does not appear to be a git repository"),
+            None
+        );
+    }
+}
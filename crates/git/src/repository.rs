@@ -1,5 +1,14 @@
 use crate::GitHostingProviderRegistry;
-use crate::{blame::Blame, status::GitStatus};
+use crate::{
+    blame::Blame,
+    log::{Log, LogEntry},
+    remote::{
+        run_remote_command, AheadBehind, AuthenticationRequired, PullMode, RemoteCredentials,
+        RemoteProgress,
+    },
+    stash::{parse_stash_list, stash_revision, Stash, STASH_LIST_ARGS},
+    status::{GitStatus, StagingStatus, StatusCode},
+};
 use anyhow::{Context, Result};
 use collections::HashMap;
 use git2::BranchType;
@@ -8,12 +17,19 @@ use rope::Rope;
 use serde::{Deserialize, Serialize};
 use std::{
     cmp::Ordering,
+    io::Write,
+    iter,
+    ops::Range,
     path::{Component, Path, PathBuf},
+    process::{Command, Stdio},
     sync::Arc,
 };
 use sum_tree::MapSeekTarget;
+use text::LineEnding;
 use util::ResultExt;
 
+const STAGE_NORMAL: i32 = 0;
+
 #[derive(Clone, Debug, Hash, PartialEq)]
 pub struct Branch {
     pub is_head: bool,
@@ -22,6 +38,63 @@ pub struct Branch {
     pub unix_timestamp: Option<i64>,
 }
 
+/// A file changed by a commit, with its contents before and after the commit.
+/// Binary files are not included.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CommitFileChange {
+    pub path: RepoPath,
+    /// The contents in the commit's first parent, or `None` if the commit added the file.
+    pub old_text: Option<String>,
+    /// The contents in the commit, or `None` if the commit deleted the file.
+    pub new_text: Option<String>,
+}
+
+/// The text that the buffers of a repository's files are diffed against.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum DiffBase {
+    /// The staged contents, showing the changes that are not staged yet.
+    #[default]
+    Index,
+    /// The contents at a revision, such as `HEAD`, a branch or a commit SHA.
+    Revision(String),
+    /// The contents at the merge base of `HEAD` and a revision, showing the changes
+    /// made since the current branch diverged from it.
+    MergeBase(String),
+}
+
+impl DiffBase {
+    pub fn head() -> Self {
+        DiffBase::Revision("HEAD".to_string())
+    }
+
+    /// Resolves the revision that files are diffed against, `None` for the index.
+    pub fn resolve(&self, repo: &dyn GitRepository) -> Result<Option<String>> {
+        match self {
+            DiffBase::Index => Ok(None),
+            DiffBase::Revision(revision) => repo.resolve_revision(revision).map(Some),
+            DiffBase::MergeBase(revision) => repo.merge_base("HEAD", revision).map(Some),
+        }
+    }
+
+    /// Loads the text of a file in the diff base, `None` if it doesn't exist there.
+    pub fn load_text(&self, repo: &dyn GitRepository, relative_file_path: &Path) -> Option<String> {
+        match self.resolve(repo).log_err()? {
+            None => repo.load_index_text(relative_file_path),
+            Some(revision) => repo.load_revision_text(relative_file_path, &revision),
+        }
+    }
+}
+
+impl std::fmt::Display for DiffBase {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DiffBase::Index => write!(f, "Index"),
+            DiffBase::Revision(revision) => write!(f, "{revision}"),
+            DiffBase::MergeBase(revision) => write!(f, "Merge base with {revision}"),
+        }
+    }
+}
+
 pub trait GitRepository: Send + Sync {
     fn reload_index(&self);
 
@@ -29,6 +102,19 @@ pub trait GitRepository: Send + Sync {
     /// Note that for symlink entries, this will return the contents of the symlink, not the target.
     fn load_index_text(&self, relative_file_path: &Path) -> Option<String>;
 
+    /// Loads the contents of a file at a revision, such as `HEAD`, a branch or a commit SHA.
+    fn load_revision_text(&self, relative_file_path: &Path, revision: &str) -> Option<String>;
+
+    /// Returns the SHA of the commit that a revision refers to.
+    fn resolve_revision(&self, revision: &str) -> Result<String>;
+
+    /// Returns the SHA of the best common ancestor of two revisions.
+    fn merge_base(&self, first: &str, second: &str) -> Result<String>;
+
+    /// Lists the files whose working tree contents differ from a revision, or from the
+    /// index when no revision is given. Untracked and deleted files are not included.
+    fn changed_paths(&self, revision: Option<&str>) -> Result<Vec<RepoPath>>;
+
     /// Returns the URL of the remote with the given name.
     fn remote_url(&self, name: &str) -> Option<String>;
     fn branch_name(&self) -> Option<String>;
@@ -47,6 +133,86 @@ pub trait GitRepository: Send + Sync {
     fn create_branch(&self, _: &str) -> Result<()>;
 
     fn blame(&self, path: &Path, content: Rope) -> Result<crate::blame::Blame>;
+
+    /// Lists the last `limit` commits reachable from HEAD, newest first, optionally only
+    /// those that changed the given path.
+    fn log(&self, path: Option<&Path>, limit: usize) -> Result<Log>;
+
+    /// Lists the files changed by a commit relative to its first parent, optionally only
+    /// the given path.
+    fn commit_changes(&self, sha: &str, path: Option<&Path>) -> Result<Vec<CommitFileChange>>;
+
+    /// Updates the index entries of the given paths to match the working tree,
+    /// removing the entries of deleted files.
+    fn stage_paths(&self, paths: &[RepoPath]) -> Result<()>;
+
+    /// Resets the index entries of the given paths to HEAD.
+    fn unstage_paths(&self, paths: &[RepoPath]) -> Result<()>;
+
+    /// Stages a single hunk of a file, replacing `diff_base_range` of its index text with
+    /// `new_text`. The range is relative to the index text with normalized line endings,
+    /// which is the diff base of the file's buffer.
+    fn stage_hunk(
+        &self,
+        path: &RepoPath,
+        diff_base_range: Range<usize>,
+        new_text: &str,
+    ) -> Result<()>;
+
+    /// Unstages the staged changes of a file that touch `diff_base_range` of its index text,
+    /// resetting them to their contents in HEAD. The range is relative to the index text with
+    /// normalized line endings, as in [`GitRepository::stage_hunk`].
+    fn unstage_hunk(&self, path: &RepoPath, diff_base_range: Range<usize>) -> Result<()>;
+
+    /// Commits the staged changes. When amending with an empty message,
+    /// the message of the amended commit is kept.
+    fn commit(&self, message: &str, amend: bool) -> Result<()>;
+
+    /// Lists the entries of the stash, newest first.
+    fn stashes(&self) -> Result<Vec<Stash>>;
+
+    /// Stashes the changes of the index and the working tree, including untracked files.
+    fn stash(&self, message: Option<&str>) -> Result<()>;
+
+    /// Applies the changes of a stash entry, keeping it in the stash.
+    fn apply_stash(&self, index: usize) -> Result<()>;
+
+    /// Applies the changes of a stash entry and removes it from the stash.
+    /// The entry is kept when applying it conflicts.
+    fn pop_stash(&self, index: usize) -> Result<()>;
+
+    /// Removes a stash entry without applying it.
+    fn drop_stash(&self, index: usize) -> Result<()>;
+
+    /// Returns how many commits the current branch is ahead and behind its upstream,
+    /// `None` if it has no upstream.
+    fn ahead_behind(&self) -> Option<AheadBehind>;
+
+    /// Fetches from the remote of the current branch's upstream, or from `origin`.
+    ///
+    /// Network operations fail with [`AuthenticationRequired`] when git has no credentials
+    /// for the remote, and can be retried with credentials entered by the user.
+    fn fetch(
+        &self,
+        credentials: Option<&RemoteCredentials>,
+        on_progress: &dyn Fn(RemoteProgress),
+    ) -> Result<()>;
+
+    /// Integrates the changes of the current branch's upstream.
+    fn pull(
+        &self,
+        mode: PullMode,
+        credentials: Option<&RemoteCredentials>,
+        on_progress: &dyn Fn(RemoteProgress),
+    ) -> Result<()>;
+
+    /// Pushes the current branch to its upstream. A branch without an upstream is pushed
+    /// to a branch with the same name on `origin`, which becomes its upstream.
+    fn push(
+        &self,
+        credentials: Option<&RemoteCredentials>,
+        on_progress: &dyn Fn(RemoteProgress),
+    ) -> Result<()>;
 }
 
 impl std::fmt::Debug for dyn GitRepository {
@@ -73,6 +239,61 @@ impl RealGitRepository {
             hosting_provider_registry,
         }
     }
+
+    fn working_directory(&self) -> Result<PathBuf> {
+        Ok(self
+            .repository
+            .lock()
+            .workdir()
+            .context("failed to read git work directory")?
+            .to_path_buf())
+    }
+
+    /// Runs a git command in the work directory, returning its standard output.
+    fn run_git_command(&self, args: &[&str]) -> Result<String> {
+        let mut command = Command::new(&self.git_binary_path);
+        command
+            .current_dir(self.working_directory()?)
+            .args(args)
+            .stdin(Stdio::null());
+
+        #[cfg(windows)]
+        {
+            use std::os::windows::process::CommandExt;
+            command.creation_flags(windows::Win32::System::Threading::CREATE_NO_WINDOW.0);
+        }
+
+        let name = args.first().copied().unwrap_or_default();
+        let output = command
+            .output()
+            .with_context(|| format!("failed to start git {name} process"))?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            let stdout = String::from_utf8_lossy(&output.stdout);
+            let error = if stderr.trim().is_empty() {
+                stdout
+            } else {
+                stderr
+            };
+            anyhow::bail!("git {} failed: {}", args.join(" "), error.trim());
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    }
+
+    fn run_remote_command(
+        &self,
+        args: &[&str],
+        credentials: Option<&RemoteCredentials>,
+        on_progress: &dyn Fn(RemoteProgress),
+    ) -> Result<()> {
+        run_remote_command(
+            &self.git_binary_path,
+            &self.working_directory()?,
+            args,
+            credentials,
+            on_progress,
+        )
+    }
 }
 
 impl GitRepository for RealGitRepository {
@@ -84,7 +305,6 @@ impl GitRepository for RealGitRepository {
 
     fn load_index_text(&self, relative_file_path: &Path) -> Option<String> {
         fn logic(repo: &git2::Repository, relative_file_path: &Path) -> Result<Option<String>> {
-            const STAGE_NORMAL: i32 = 0;
             let index = repo.index()?;
 
             // This check is required because index.get_path() unwraps internally :(
@@ -106,6 +326,61 @@ impl GitRepository for RealGitRepository {
         None
     }
 
+    fn load_revision_text(&self, relative_file_path: &Path, revision: &str) -> Option<String> {
+        fn logic(
+            repo: &git2::Repository,
+            relative_file_path: &Path,
+            revision: &str,
+        ) -> Result<Option<String>> {
+            check_path_to_repo_path_errors(relative_file_path)?;
+            let tree = repo.revparse_single(revision)?.peel_to_tree()?;
+            let oid = match tree.get_path(relative_file_path) {
+                Ok(entry) => entry.id(),
+                Err(error) if error.code() == git2::ErrorCode::NotFound => return Ok(None),
+                Err(error) => return Err(error.into()),
+            };
+
+            let content = repo.find_blob(oid)?.content().to_owned();
+            Ok(Some(String::from_utf8(content)?))
+        }
+
+        match logic(&self.repository.lock(), relative_file_path, revision) {
+            Ok(value) => return value,
+            Err(err) => log::error!("Error loading text at {revision}: {:?}", err),
+        }
+        None
+    }
+
+    fn resolve_revision(&self, revision: &str) -> Result<String> {
+        let repo = self.repository.lock();
+        let commit = repo.revparse_single(revision)?.peel_to_commit()?;
+        Ok(commit.id().to_string())
+    }
+
+    fn merge_base(&self, first: &str, second: &str) -> Result<String> {
+        let repo = self.repository.lock();
+        let first = repo.revparse_single(first)?.peel_to_commit()?;
+        let second = repo.revparse_single(second)?.peel_to_commit()?;
+        Ok(repo.merge_base(first.id(), second.id())?.to_string())
+    }
+
+    fn changed_paths(&self, revision: Option<&str>) -> Result<Vec<RepoPath>> {
+        let repo = self.repository.lock();
+        let diff = match revision {
+            Some(revision) => {
+                let tree = repo.revparse_single(revision)?.peel_to_tree()?;
+                repo.diff_tree_to_workdir_with_index(Some(&tree), None)?
+            }
+            None => repo.diff_index_to_workdir(None, None)?,
+        };
+        Ok(diff
+            .deltas()
+            .filter(|delta| delta.status() != git2::Delta::Deleted)
+            .filter_map(|delta| delta.new_file().path())
+            .map(RepoPath::from)
+            .collect())
+    }
+
     fn remote_url(&self, name: &str) -> Option<String> {
         let repo = self.repository.lock();
         let remote = repo.find_remote(name).ok()?;
@@ -200,6 +475,424 @@ impl GitRepository for RealGitRepository {
             self.hosting_provider_registry.clone(),
         )
     }
+
+    fn log(&self, path: Option<&Path>, limit: usize) -> Result<Log> {
+        let working_directory = self
+            .repository
+            .lock()
+            .workdir()
+            .context("failed to read git work directory")?
+            .to_path_buf();
+
+        const REMOTE_NAME: &str = "origin";
+        let remote_url = self.remote_url(REMOTE_NAME);
+
+        Log::for_path(
+            &self.git_binary_path,
+            &working_directory,
+            path,
+            limit,
+            remote_url,
+        )
+    }
+
+    fn commit_changes(&self, sha: &str, path: Option<&Path>) -> Result<Vec<CommitFileChange>> {
+        let repo = self.repository.lock();
+        let commit = repo.revparse_single(sha)?.peel_to_commit()?;
+        let parent_tree = match commit.parents().next() {
+            Some(parent) => Some(parent.tree()?),
+            None => None,
+        };
+        let mut options = git2::DiffOptions::new();
+        if let Some(path) = path {
+            check_path_to_repo_path_errors(path)?;
+            options.pathspec(path).disable_pathspec_match(true);
+        }
+        let diff = repo.diff_tree_to_tree(
+            parent_tree.as_ref(),
+            Some(&commit.tree()?),
+            Some(&mut options),
+        )?;
+
+        let load_text = |file: git2::DiffFile| -> Result<Option<String>> {
+            if file.id().is_zero() {
+                return Ok(None);
+            }
+            let content = repo.find_blob(file.id())?.content().to_owned();
+            Ok(String::from_utf8(content).ok())
+        };
+        let mut changes = Vec::new();
+        for delta in diff.deltas() {
+            let Some(file_path) = delta.new_file().path().or(delta.old_file().path()) else {
+                continue;
+            };
+            let old_text = load_text(delta.old_file())?;
+            let new_text = load_text(delta.new_file())?;
+            let is_binary = (old_text.is_none() && !delta.old_file().id().is_zero())
+                || (new_text.is_none() && !delta.new_file().id().is_zero());
+            if !is_binary {
+                changes.push(CommitFileChange {
+                    path: RepoPath::from(file_path),
+                    old_text,
+                    new_text,
+                });
+            }
+        }
+        Ok(changes)
+    }
+
+    fn stage_paths(&self, paths: &[RepoPath]) -> Result<()> {
+        let repo = self.repository.lock();
+        let working_directory = repo
+            .workdir()
+            .context("failed to read git work directory")?
+            .to_path_buf();
+        let mut index = repo.index()?;
+        for path in paths {
+            check_path_to_repo_path_errors(path)?;
+            if working_directory.join(path).symlink_metadata().is_ok() {
+                index
+                    .add_path(path)
+                    .with_context(|| format!("failed to stage {path:?}"))?;
+            } else {
+                index
+                    .remove_path(path)
+                    .with_context(|| format!("failed to stage the deletion of {path:?}"))?;
+            }
+        }
+        index.write()?;
+        Ok(())
+    }
+
+    fn unstage_paths(&self, paths: &[RepoPath]) -> Result<()> {
+        let repo = self.repository.lock();
+        match repo.head().and_then(|head| head.peel_to_commit()) {
+            Ok(head) => {
+                repo.reset_default(
+                    Some(head.as_object()),
+                    paths.iter().map(|path| path.as_path()),
+                )?;
+            }
+            // Without any commits, unstaging a path removes it from the index.
+            Err(error) if error.code() == git2::ErrorCode::UnbornBranch => {
+                let mut index = repo.index()?;
+                for path in paths {
+                    index.remove_path(path)?;
+                }
+                index.write()?;
+            }
+            Err(error) => return Err(error.into()),
+        }
+        Ok(())
+    }
+
+    fn stage_hunk(
+        &self,
+        path: &RepoPath,
+        diff_base_range: Range<usize>,
+        new_text: &str,
+    ) -> Result<()> {
+        check_path_to_repo_path_errors(path)?;
+        let repo = self.repository.lock();
+        let mut index = repo.index()?;
+        let mut entry = index
+            .get_path(path, STAGE_NORMAL)
+            .with_context(|| format!("{path:?} is not in the index"))?;
+        let index_text = String::from_utf8(repo.find_blob(entry.id)?.content().to_owned())?;
+        let index_text = apply_hunk(&index_text, diff_base_range, new_text)?;
+        write_index_text(&repo, &mut index, entry, &index_text)
+    }
+
+    fn unstage_hunk(&self, path: &RepoPath, diff_base_range: Range<usize>) -> Result<()> {
+        check_path_to_repo_path_errors(path)?;
+        let repo = self.repository.lock();
+        let mut index = repo.index()?;
+        let entry = index
+            .get_path(path, STAGE_NORMAL)
+            .with_context(|| format!("{path:?} is not in the index"))?;
+        let index_text = String::from_utf8(repo.find_blob(entry.id)?.content().to_owned())?;
+        // Files that are not in HEAD are unstaged to empty contents.
+        let head_text = match repo.head().and_then(|head| head.peel_to_tree()) {
+            Ok(tree) => match tree.get_path(path) {
+                Ok(tree_entry) => {
+                    String::from_utf8(repo.find_blob(tree_entry.id())?.content().to_owned())?
+                }
+                Err(error) if error.code() == git2::ErrorCode::NotFound => String::new(),
+                Err(error) => return Err(error.into()),
+            },
+            Err(error) if error.code() == git2::ErrorCode::UnbornBranch => String::new(),
+            Err(error) => return Err(error.into()),
+        };
+        let index_text = unstage_hunk(&head_text, &index_text, diff_base_range)?;
+        write_index_text(&repo, &mut index, entry, &index_text)
+    }
+
+    fn commit(&self, message: &str, amend: bool) -> Result<()> {
+        let working_directory = self
+            .repository
+            .lock()
+            .workdir()
+            .context("failed to read git work directory")?
+            .to_path_buf();
+
+        let mut command = Command::new(&self.git_binary_path);
+        command
+            .current_dir(&working_directory)
+            .args(["commit", "--quiet"])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+        let keep_message = amend && message.trim().is_empty();
+        if amend {
+            command.arg("--amend");
+        }
+        if keep_message {
+            command.arg("--no-edit");
+        } else {
+            command.args(["--file", "-"]);
+        }
+
+        #[cfg(windows)]
+        {
+            use std::os::windows::process::CommandExt;
+            command.creation_flags(windows::Win32::System::Threading::CREATE_NO_WINDOW.0);
+        }
+
+        let mut child = command
+            .spawn()
+            .context("failed to start git commit process")?;
+        if let Some(mut stdin) = child.stdin.take() {
+            if !keep_message {
+                stdin.write_all(message.as_bytes())?;
+            }
+        }
+        let output = child
+            .wait_with_output()
+            .context("failed to read git commit output")?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            let stdout = String::from_utf8_lossy(&output.stdout);
+            let error = if stderr.trim().is_empty() {
+                stdout
+            } else {
+                stderr
+            };
+            anyhow::bail!("git commit failed: {}", error.trim());
+        }
+        Ok(())
+    }
+
+    fn stashes(&self) -> Result<Vec<Stash>> {
+        parse_stash_list(&self.run_git_command(STASH_LIST_ARGS)?)
+    }
+
+    fn stash(&self, message: Option<&str>) -> Result<()> {
+        let mut args = vec!["stash", "push", "--include-untracked"];
+        if let Some(message) = message {
+            args.extend(["--message", message]);
+        }
+        self.run_git_command(&args)?;
+        Ok(())
+    }
+
+    fn apply_stash(&self, index: usize) -> Result<()> {
+        self.run_git_command(&["stash", "apply", &stash_revision(index)])?;
+        Ok(())
+    }
+
+    fn pop_stash(&self, index: usize) -> Result<()> {
+        self.run_git_command(&["stash", "pop", &stash_revision(index)])?;
+        Ok(())
+    }
+
+    fn drop_stash(&self, index: usize) -> Result<()> {
+        self.run_git_command(&["stash", "drop", &stash_revision(index)])?;
+        Ok(())
+    }
+
+    fn ahead_behind(&self) -> Option<AheadBehind> {
+        let repo = self.repository.lock();
+        let head = repo.head().ok()?;
+        if !head.is_branch() {
+            return None;
+        }
+        let local = head.target()?;
+        let upstream = git2::Branch::wrap(head).upstream().ok()?.get().target()?;
+        let (ahead, behind) = repo.graph_ahead_behind(local, upstream).log_err()?;
+        Some(AheadBehind { ahead, behind })
+    }
+
+    fn fetch(
+        &self,
+        credentials: Option<&RemoteCredentials>,
+        on_progress: &dyn Fn(RemoteProgress),
+    ) -> Result<()> {
+        self.run_remote_command(&["fetch", "--progress"], credentials, on_progress)
+    }
+
+    fn pull(
+        &self,
+        mode: PullMode,
+        credentials: Option<&RemoteCredentials>,
+        on_progress: &dyn Fn(RemoteProgress),
+    ) -> Result<()> {
+        let mode = match mode {
+            PullMode::FastForwardOnly => "--ff-only",
+            PullMode::Rebase => "--rebase",
+        };
+        self.run_remote_command(&["pull", "--progress", mode], credentials, on_progress)
+    }
+
+    fn push(
+        &self,
+        credentials: Option<&RemoteCredentials>,
+        on_progress: &dyn Fn(RemoteProgress),
+    ) -> Result<()> {
+        let (remote, refspec) = {
+            let repo = self.repository.lock();
+            let head = repo.head()?;
+            anyhow::ensure!(
+                head.is_branch(),
+                "HEAD is detached, check out a branch to push"
+            );
+            let head_ref = head.name().context("invalid branch name")?;
+            let branch_name = head.shorthand().context("invalid branch name")?;
+            let config = repo.config()?;
+            let upstream = config
+                .get_string(&format!("branch.{branch_name}.remote"))
+                .and_then(|remote| {
+                    Ok((
+                        remote,
+                        config.get_string(&format!("branch.{branch_name}.merge"))?,
+                    ))
+                });
+            match upstream {
+                Ok((remote, merge)) => (Some(remote), format!("HEAD:{merge}")),
+                Err(_) => (None, head_ref.to_string()),
+            }
+        };
+        match remote {
+            Some(remote) => self.run_remote_command(
+                &["push", "--progress", &remote, &refspec],
+                credentials,
+                on_progress,
+            ),
+            None => self.run_remote_command(
+                &["push", "--progress", "--set-upstream", "origin", &refspec],
+                credentials,
+                on_progress,
+            ),
+        }
+    }
+}
+
+/// Replaces a range of the index text with normalized line endings, keeping the line
+/// endings of the index text.
+fn apply_hunk(index_text: &str, range: Range<usize>, new_text: &str) -> Result<String> {
+    let crlf = index_text.contains("\r\n");
+    let mut text = index_text.to_string();
+    LineEnding::normalize(&mut text);
+    anyhow::ensure!(
+        range.start <= range.end
+            && range.end <= text.len()
+            && text.is_char_boundary(range.start)
+            && text.is_char_boundary(range.end),
+        "the hunk does not match the index, it may have changed"
+    );
+    text.replace_range(range, new_text);
+    if crlf {
+        text = text.replace('\n', LineEnding::Windows.as_str());
+    }
+    Ok(text)
+}
+
+/// Replaces the changes between `head_text` and `index_text` that touch `range` of the index
+/// text with their contents in HEAD.
+fn unstage_hunk(head_text: &str, index_text: &str, range: Range<usize>) -> Result<String> {
+    let crlf = index_text.contains("\r\n");
+    let mut head_text = head_text.to_string();
+    let mut text = index_text.to_string();
+    LineEnding::normalize(&mut head_text);
+    LineEnding::normalize(&mut text);
+
+    // The patch borrows the texts, so it's dropped before the index text is edited.
+    let staged_ranges = {
+        let mut options = git2::DiffOptions::new();
+        options.context_lines(0);
+        let patch = git2::Patch::from_buffers(
+            head_text.as_bytes(),
+            None,
+            text.as_bytes(),
+            None,
+            Some(&mut options),
+        )?;
+        let mut staged_ranges: Option<(Range<usize>, Range<usize>)> = None;
+        let head_lines = line_offsets(&head_text);
+        let index_lines = line_offsets(&text);
+        for hunk_index in 0..patch.num_hunks() {
+            let (hunk, _) = patch.hunk(hunk_index)?;
+            let head_range = line_range(&head_lines, hunk.old_start(), hunk.old_lines());
+            let index_range = line_range(&index_lines, hunk.new_start(), hunk.new_lines());
+            let touches_range = if index_range.is_empty() || range.is_empty() {
+                index_range.start <= range.end && range.start <= index_range.end
+            } else {
+                index_range.start < range.end && range.start < index_range.end
+            };
+            if touches_range {
+                // The text between consecutive changes is the same in HEAD and in the index.
+                staged_ranges = Some(match staged_ranges {
+                    Some((head, index)) => {
+                        (head.start..head_range.end, index.start..index_range.end)
+                    }
+                    None => (head_range, index_range),
+                });
+            }
+        }
+        staged_ranges
+    };
+    let (head_range, index_range) =
+        staged_ranges.context("there are no staged changes in the hunk")?;
+    text.replace_range(index_range, &head_text[head_range]);
+    if crlf {
+        text = text.replace('\n', LineEnding::Windows.as_str());
+    }
+    Ok(text)
+}
+
+/// The offsets of the line starts of a text.
+fn line_offsets(text: &str) -> Vec<usize> {
+    iter::once(0)
+        .chain(text.match_indices('\n').map(|(ix, _)| ix + 1))
+        .collect()
+}
+
+/// The byte range of the lines of a diff hunk, whose start is one-based
+/// unless the hunk is empty on that side.
+fn line_range(line_offsets: &[usize], start: u32, lines: u32) -> Range<usize> {
+    let start = if lines == 0 { start } else { start - 1 } as usize;
+    let offset = |line: usize| {
+        line_offsets
+            .get(line)
+            .copied()
+            .unwrap_or(*line_offsets.last().unwrap())
+    };
+    offset(start)..offset(start + lines as usize)
+}
+
+fn write_index_text(
+    repo: &git2::Repository,
+    index: &mut git2::Index,
+    mut entry: git2::IndexEntry,
+    text: &str,
+) -> Result<()> {
+    entry.id = repo.blob(text.as_bytes())?;
+    entry.file_size = text.len() as u32;
+    // Forget the file stats, so that git compares the working tree file by its contents.
+    entry.ctime = git2::IndexTime::new(0, 0);
+    entry.mtime = git2::IndexTime::new(0, 0);
+    index.add(&entry)?;
+    index.write()?;
+    Ok(())
 }
 
 #[derive(Debug, Clone, Default)]
@@ -210,9 +903,61 @@ pub struct FakeGitRepository {
 #[derive(Debug, Clone, Default)]
 pub struct FakeGitRepositoryState {
     pub index_contents: HashMap<PathBuf, String>,
+    pub head_contents: HashMap<PathBuf, String>,
+    /// The file contents at revisions other than `HEAD`.
+    pub revision_contents: HashMap<String, HashMap<PathBuf, String>>,
+    /// The merge bases of pairs of revisions.
+    pub merge_bases: HashMap<(String, String), String>,
     pub blames: HashMap<PathBuf, Blame>,
+    /// The history of HEAD, newest first.
+    pub log: Log,
+    /// The files changed by each commit of the log.
+    pub commit_changes: HashMap<String, Vec<CommitFileChange>>,
     pub worktree_statuses: HashMap<RepoPath, GitFileStatus>,
+    pub staging_statuses: HashMap<RepoPath, StagingStatus>,
     pub branch_name: Option<String>,
+    /// The messages of the commits made, and whether they amended the previous commit.
+    pub commits: Vec<(String, bool)>,
+    pub stashes: Vec<Stash>,
+    pub ahead_behind: Option<AheadBehind>,
+    /// The network operations run, like `fetch` or `pull --rebase`.
+    pub remote_operations: Vec<String>,
+    /// The credentials that network operations fail with [`AuthenticationRequired`] without.
+    pub required_credentials: Option<RemoteCredentials>,
+}
+
+impl FakeGitRepositoryState {
+    fn revision_contents(&self, revision: &str) -> Option<&HashMap<PathBuf, String>> {
+        if revision == "HEAD" {
+            Some(&self.head_contents)
+        } else {
+            self.revision_contents.get(revision)
+        }
+    }
+
+    fn run_remote_operation(
+        &mut self,
+        operation: &str,
+        credentials: Option<&RemoteCredentials>,
+        on_progress: &dyn Fn(RemoteProgress),
+    ) -> Result<()> {
+        if self.required_credentials.is_some() && self.required_credentials.as_ref() != credentials
+        {
+            return Err(AuthenticationRequired { url: None }.into());
+        }
+        on_progress(RemoteProgress {
+            stage: "Receiving objects".into(),
+            percentage: 100,
+        });
+        self.remote_operations.push(operation.to_string());
+        Ok(())
+    }
+
+    fn renumber_stashes(&mut self) {
+        for (index, stash) in self.stashes.iter_mut().enumerate() {
+            stash.index = index;
+        }
+    }
 }
 
 impl FakeGitRepository {
@@ -229,6 +974,50 @@ impl GitRepository for FakeGitRepository {
         state.index_contents.get(path).cloned()
     }
 
+    fn load_revision_text(&self, path: &Path, revision: &str) -> Option<String> {
+        let state = self.state.lock();
+        state.revision_contents(revision)?.get(path).cloned()
+    }
+
+    fn resolve_revision(&self, revision: &str) -> Result<String> {
+        let state = self.state.lock();
+        state
+            .revision_contents(revision)
+            .with_context(|| format!("unknown revision {revision}"))?;
+        Ok(revision.to_string())
+    }
+
+    fn merge_base(&self, first: &str, second: &str) -> Result<String> {
+        let state = self.state.lock();
+        let key = |first: &str, second: &str| (first.to_string(), second.to_string());
+        state
+            .merge_bases
+            .get(&key(first, second))
+            .or_else(|| state.merge_bases.get(&key(second, first)))
+            .cloned()
+            .with_context(|| format!("no merge base of {first} and {second}"))
+    }
+
+    fn changed_paths(&self, revision: Option<&str>) -> Result<Vec<RepoPath>> {
+        let state = self.state.lock();
+        let mut paths = state.worktree_statuses.keys().cloned().collect::<Vec<_>>();
+        if let Some(revision) = revision {
+            let revision_contents = state
+                .revision_contents(revision)
+                .with_context(|| format!("unknown revision {revision}"))?;
+            paths.extend(
+                state
+                    .index_contents
+                    .iter()
+                    .filter(|(path, text)| revision_contents.get(*path) != Some(*text))
+                    .map(|(path, _)| RepoPath::new(path.clone())),
+            );
+        }
+        paths.sort();
+        paths.dedup();
+        Ok(paths)
+    }
+
     fn remote_url(&self, _name: &str) -> Option<String> {
         None
     }
@@ -256,8 +1045,16 @@ impl GitRepository for FakeGitRepository {
             })
             .collect::<Vec<_>>();
         entries.sort_unstable_by(|a, b| a.0.cmp(&b.0));
+        let mut staging = state
+            .staging_statuses
+            .iter()
+            .filter(|(repo_path, _)| repo_path.0.starts_with(path_prefix))
+            .map(|(repo_path, status)| (repo_path.clone(), *status))
+            .collect::<Vec<_>>();
+        staging.sort_unstable_by(|a, b| a.0.cmp(&b.0));
         Ok(GitStatus {
             entries: entries.into(),
+            staging: staging.into(),
         })
     }
 
@@ -285,6 +1082,209 @@ impl GitRepository for FakeGitRepository {
             .with_context(|| format!("failed to get blame for {:?}", path))
             .cloned()
     }
+
+    fn log(&self, path: Option<&Path>, limit: usize) -> Result<Log> {
+        let state = self.state.lock();
+        let changes_path = |entry: &LogEntry| {
+            let Some(path) = path else {
+                return true;
+            };
+            state
+                .commit_changes
+                .get(&entry.sha.to_string())
+                .map_or(false, |changes| {
+                    changes.iter().any(|change| change.path.0.as_path() == path)
+                })
+        };
+        Ok(Log {
+            entries: state
+                .log
+                .entries
+                .iter()
+                .filter(|entry| changes_path(entry))
+                .take(limit)
+                .cloned()
+                .collect(),
+            messages: state.log.messages.clone(),
+            remote_url: state.log.remote_url.clone(),
+        })
+    }
+
+    fn commit_changes(&self, sha: &str, path: Option<&Path>) -> Result<Vec<CommitFileChange>> {
+        let state = self.state.lock();
+        let changes = state
+            .commit_changes
+            .get(sha)
+            .with_context(|| format!("unknown commit {sha}"))?;
+        Ok(changes
+            .iter()
+            .filter(|change| path.map_or(true, |path| change.path.0.as_path() == path))
+            .cloned()
+            .collect())
+    }
+
+    fn stage_paths(&self, paths: &[RepoPath]) -> Result<()> {
+        let mut state = self.state.lock();
+        for path in paths {
+            let status = state.staging_statuses.entry(path.clone()).or_default();
+            if let Some(unstaged) = status.unstaged.take() {
+                status.staged = Some(match unstaged {
+                    StatusCode::Untracked => StatusCode::Added,
+                    unstaged => unstaged,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    fn unstage_paths(&self, paths: &[RepoPath]) -> Result<()> {
+        let mut state = self.state.lock();
+        for path in paths {
+            let status = state.staging_statuses.entry(path.clone()).or_default();
+            if let Some(staged) = status.staged.take() {
+                status.unstaged = Some(match staged {
+                    StatusCode::Added => StatusCode::Untracked,
+                    staged => staged,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    fn stage_hunk(
+        &self,
+        path: &RepoPath,
+        diff_base_range: Range<usize>,
+        new_text: &str,
+    ) -> Result<()> {
+        let mut state = self.state.lock();
+        let index_text = state
+            .index_contents
+            .get_mut(&path.0)
+            .with_context(|| format!("{path:?} is not in the index"))?;
+        *index_text = apply_hunk(index_text, diff_base_range, new_text)?;
+        Ok(())
+    }
+
+    fn unstage_hunk(&self, path: &RepoPath, diff_base_range: Range<usize>) -> Result<()> {
+        let mut state = self.state.lock();
+        let head_text = state
+            .head_contents
+            .get(&path.0)
+            .cloned()
+            .unwrap_or_default();
+        let index_text = state
+            .index_contents
+            .get_mut(&path.0)
+            .with_context(|| format!("{path:?} is not in the index"))?;
+        *index_text = unstage_hunk(&head_text, index_text, diff_base_range)?;
+        Ok(())
+    }
+
+    fn commit(&self, message: &str, amend: bool) -> Result<()> {
+        let mut state = self.state.lock();
+        if !amend
+            && !state
+                .staging_statuses
+                .values()
+                .any(StagingStatus::is_staged)
+        {
+            anyhow::bail!("nothing to commit");
+        }
+        state.commits.push((message.to_string(), amend));
+        state.staging_statuses.retain(|_, status| {
+            status.staged = None;
+            status.unstaged.is_some()
+        });
+        Ok(())
+    }
+
+    fn stashes(&self) -> Result<Vec<Stash>> {
+        Ok(self.state.lock().stashes.clone())
+    }
+
+    fn stash(&self, message: Option<&str>) -> Result<()> {
+        let mut state = self.state.lock();
+        let branch_name = state.branch_name.clone().unwrap_or_else(|| "HEAD".into());
+        let message = match message {
+            Some(message) => format!("On {branch_name}: {message}"),
+            None => format!("WIP on {branch_name}"),
+        };
+        state.stashes.insert(0, Stash { index: 0, message });
+        state.renumber_stashes();
+        Ok(())
+    }
+
+    fn apply_stash(&self, index: usize) -> Result<()> {
+        let state = self.state.lock();
+        anyhow::ensure!(
+            index < state.stashes.len(),
+            "{} is not a valid reference",
+            stash_revision(index)
+        );
+        Ok(())
+    }
+
+    fn pop_stash(&self, index: usize) -> Result<()> {
+        self.drop_stash(index)
+    }
+
+    fn drop_stash(&self, index: usize) -> Result<()> {
+        let mut state = self.state.lock();
+        anyhow::ensure!(
+            index < state.stashes.len(),
+            "{} is not a valid reference",
+            stash_revision(index)
+        );
+        state.stashes.remove(index);
+        state.renumber_stashes();
+        Ok(())
+    }
+
+    fn ahead_behind(&self) -> Option<AheadBehind> {
+        self.state.lock().ahead_behind
+    }
+
+    fn fetch(
+        &self,
+        credentials: Option<&RemoteCredentials>,
+        on_progress: &dyn Fn(RemoteProgress),
+    ) -> Result<()> {
+        let mut state = self.state.lock();
+        state.run_remote_operation("fetch", credentials, on_progress)
+    }
+
+    fn pull(
+        &self,
+        mode: PullMode,
+        credentials: Option<&RemoteCredentials>,
+        on_progress: &dyn Fn(RemoteProgress),
+    ) -> Result<()> {
+        let mut state = self.state.lock();
+        let operation = match mode {
+            PullMode::FastForwardOnly => "pull --ff-only",
+            PullMode::Rebase => "pull --rebase",
+        };
+        state.run_remote_operation(operation, credentials, on_progress)?;
+        if let Some(ahead_behind) = state.ahead_behind.as_mut() {
+            ahead_behind.behind = 0;
+        }
+        Ok(())
+    }
+
+    fn push(
+        &self,
+        credentials: Option<&RemoteCredentials>,
+        on_progress: &dyn Fn(RemoteProgress),
+    ) -> Result<()> {
+        let mut state = self.state.lock();
+        state.run_remote_operation("push", credentials, on_progress)?;
+        state
+            .ahead_behind
+            .get_or_insert_with(Default::default)
+            .ahead = 0;
+        Ok(())
+    }
 }
 
 fn check_path_to_repo_path_errors(relative_file_path: &Path) -> Result<()> {
@@ -403,3 +1403,80 @@ impl<'a> MapSeekTarget<RepoPath> for RepoPathDescendants<'a> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_apply_hunk() {
+        assert_eq!(
+            apply_hunk("one\ntwo\nthree\n", 4..8, "2\n2.5\n").unwrap(),
+            "one\n2\n2.5\nthree\n"
+        );
+        // The range is relative to the text with normalized line endings.
+        assert_eq!(
+            apply_hunk("one\r\ntwo\r\nthree\r\n", 4..8, "").unwrap(),
+            "one\r\nthree\r\n"
+        );
+        assert!(apply_hunk("one\n", 2..10, "").is_err());
+    }
+
+    #[test]
+    fn test_unstage_hunk() {
+        let head_text = "one\ntwo\nthree\n";
+        let index_text = "one\n2\nthree\nfour\n";
+        assert_eq!(
+            unstage_hunk(head_text, index_text, 4..6).unwrap(),
+            "one\ntwo\nthree\nfour\n"
+        );
+        // An empty range touches the changes around it.
+        assert_eq!(
+            unstage_hunk(head_text, index_text, 12..12).unwrap(),
+            "one\n2\nthree\n"
+        );
+        assert_eq!(
+            unstage_hunk(head_text, index_text, 0..17).unwrap(),
+            head_text
+        );
+        assert_eq!(unstage_hunk("", "one\r\n", 0..0).unwrap(), "");
+        assert!(unstage_hunk(head_text, index_text, 0..4).is_err());
+    }
+
+    #[test]
+    fn test_diff_base_load_text() {
+        let path = Path::new("a.txt");
+        let state = FakeGitRepositoryState {
+            index_contents: HashMap::from_iter([(path.into(), "index\n".to_string())]),
+            head_contents: HashMap::from_iter([(path.into(), "head\n".to_string())]),
+            revision_contents: HashMap::from_iter([(
+                "base-sha".to_string(),
+                HashMap::from_iter([(path.into(), "base\n".to_string())]),
+            )]),
+            merge_bases: HashMap::from_iter([(
+                ("HEAD".to_string(), "main".to_string()),
+                "base-sha".to_string(),
+            )]),
+            ..Default::default()
+        };
+        let repo = FakeGitRepository::open(Arc::new(Mutex::new(state)));
+
+        let load = |diff_base: DiffBase| diff_base.load_text(repo.as_ref(), path);
+        assert_eq!(load(DiffBase::Index).as_deref(), Some("index\n"));
+        assert_eq!(load(DiffBase::head()).as_deref(), Some("head\n"));
+        assert_eq!(
+            load(DiffBase::Revision("base-sha".into())).as_deref(),
+            Some("base\n")
+        );
+        assert_eq!(
+            load(DiffBase::MergeBase("main".into())).as_deref(),
+            Some("base\n")
+        );
+        assert_eq!(load(DiffBase::MergeBase("unknown".into())), None);
+        assert_eq!(load(DiffBase::Revision("unknown".into())), None);
+        assert_eq!(
+            repo.changed_paths(Some("HEAD")).unwrap(),
+            [RepoPath::from(path)]
+        );
+    }
+}
@@ -0,0 +1,80 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+/// An entry of the stash, newest first.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Stash {
+    /// The position in the stash, as in `stash@{<index>}`.
+    pub index: usize,
+    /// The description of the stash, like `WIP on main: 1234567 Commit summary`.
+    pub message: String,
+}
+
+impl Stash {
+    /// The revision naming this stash entry.
+    pub fn revision(&self) -> String {
+        stash_revision(self.index)
+    }
+}
+
+pub(crate) fn stash_revision(index: usize) -> String {
+    format!("stash@{{{index}}}")
+}
+
+/// The arguments of `git stash list` whose output is parsed by [`parse_stash_list`].
+pub(crate) const STASH_LIST_ARGS: &[&str] = &["stash", "list", "--format=%gd%x00%gs"];
+
+// parse_stash_list parses the output of `git stash list` with the format of
+// STASH_LIST_ARGS, which prints a line per stash entry with its reflog selector,
+// like `stash@{0}`, and its description, separated by a NUL byte.
+pub(crate) fn parse_stash_list(output: &str) -> Result<Vec<Stash>> {
+    output
+        .lines()
+        .filter(|line| !line.is_empty())
+        .map(|line| {
+            let (selector, message) = line
+                .split_once('\0')
+                .with_context(|| format!("invalid stash entry {line:?}"))?;
+            let index = selector
+                .strip_prefix("stash@{")
+                .and_then(|rest| rest.strip_suffix('}'))
+                .with_context(|| format!("invalid stash selector {selector:?}"))?
+                .parse()
+                .with_context(|| format!("invalid stash selector {selector:?}"))?;
+            Ok(Stash {
+                index,
+                message: message.to_string(),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_stash_list() {
+        let stashes = parse_stash_list(
+            "stash@{0}\0On main: Try something\nstash@{1}\0WIP on main: 6ad46b5 Joe's cool commit\n",
+        )
+        .unwrap();
+        assert_eq!(
+            stashes,
+            [
+                Stash {
+                    index: 0,
+                    message: "On main: Try something".into(),
+                },
+                Stash {
+                    index: 1,
+                    message: "WIP on main: 6ad46b5 Joe's cool commit".into(),
+                },
+            ]
+        );
+        assert_eq!(stashes[1].revision(), "stash@{1}");
+
+        assert_eq!(parse_stash_list("").unwrap(), []);
+        assert!(parse_stash_list("stash@{x}\0On main: Oops").is_err());
+    }
+}
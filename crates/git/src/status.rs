@@ -1,5 +1,6 @@
 use crate::repository::{GitFileStatus, RepoPath};
 use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
 use std::{
     path::{Path, PathBuf},
     process::{Command, Stdio},
@@ -9,6 +10,85 @@ use std::{
 #[derive(Clone)]
 pub struct GitStatus {
     pub entries: Arc<[(RepoPath, GitFileStatus)]>,
+    /// The staged and unstaged changes of every changed file, sorted by path.
+    pub staging: Arc<[(RepoPath, StagingStatus)]>,
+}
+
+/// A change to a file, as reported by `git status`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum StatusCode {
+    Modified,
+    TypeChanged,
+    Added,
+    Deleted,
+    Renamed,
+    Copied,
+    Unmerged,
+    Untracked,
+}
+
+impl StatusCode {
+    fn from_porcelain(code: u8) -> Option<Self> {
+        match code {
+            b'M' => Some(StatusCode::Modified),
+            b'T' => Some(StatusCode::TypeChanged),
+            b'A' => Some(StatusCode::Added),
+            b'D' => Some(StatusCode::Deleted),
+            b'R' => Some(StatusCode::Renamed),
+            b'C' => Some(StatusCode::Copied),
+            b'U' => Some(StatusCode::Unmerged),
+            b'?' => Some(StatusCode::Untracked),
+            _ => None,
+        }
+    }
+}
+
+/// The changes of a file that are staged in the index, and those that are only in the working tree.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct StagingStatus {
+    /// The change between HEAD and the index.
+    pub staged: Option<StatusCode>,
+    /// The change between the index and the working tree.
+    pub unstaged: Option<StatusCode>,
+}
+
+impl StagingStatus {
+    /// Parses the two letter `XY` status of `git status --porcelain`.
+    pub fn from_porcelain(status: &str) -> Option<Self> {
+        let &[index, worktree] = status.as_bytes() else {
+            return None;
+        };
+        // Unmerged paths are reported with both sides of the merge instead.
+        if matches!(status, "DD" | "AU" | "UD" | "UA" | "DU" | "AA" | "UU") {
+            return Some(StagingStatus {
+                staged: None,
+                unstaged: Some(StatusCode::Unmerged),
+            });
+        }
+        if status == "??" {
+            return Some(StagingStatus {
+                staged: None,
+                unstaged: Some(StatusCode::Untracked),
+            });
+        }
+        let status = StagingStatus {
+            staged: StatusCode::from_porcelain(index),
+            unstaged: StatusCode::from_porcelain(worktree),
+        };
+        (status != StagingStatus::default()).then_some(status)
+    }
+
+    pub fn is_staged(&self) -> bool {
+        self.staged.is_some()
+    }
+
+    pub fn is_untracked(&self) -> bool {
+        self.unstaged == Some(StatusCode::Untracked)
+    }
+
+    pub fn is_unmerged(&self) -> bool {
+        self.unstaged == Some(StatusCode::Unmerged)
+    }
 }
 
 impl GitStatus {
@@ -57,29 +137,48 @@ impl GitStatus {
         }
 
         let stdout = String::from_utf8_lossy(&output.stdout);
-        let mut entries = stdout
-            .split('\0')
-            .filter_map(|entry| {
-                if entry.is_char_boundary(3) {
-                    let (status, path) = entry.split_at(3);
-                    let status = status.trim();
-                    Some((
-                        RepoPath(PathBuf::from(path)),
-                        match status {
-                            "A" | "??" => GitFileStatus::Added,
-                            "M" => GitFileStatus::Modified,
-                            _ => return None,
-                        },
-                    ))
-                } else {
-                    None
+        Ok(Self::parse(&stdout))
+    }
+
+    fn parse(stdout: &str) -> Self {
+        let mut entries = Vec::new();
+        let mut staging = Vec::new();
+        let mut lines = stdout.split('\0');
+        while let Some(entry) = lines.next() {
+            if !entry.is_char_boundary(3) {
+                continue;
+            }
+            let (status, path) = entry.split_at(3);
+            let path = RepoPath(PathBuf::from(path));
+            let staging_status = status.get(..2).and_then(StagingStatus::from_porcelain);
+            // Renames and copies are followed by the path they originate from.
+            if staging_status.is_some_and(|status| {
+                matches!(
+                    status.staged,
+                    Some(StatusCode::Renamed | StatusCode::Copied)
+                )
+            }) {
+                lines.next();
+            }
+            if let Some(staging_status) = staging_status {
+                staging.push((path.clone(), staging_status));
+            }
+            let status = match status.trim() {
+                _ if staging_status.is_some_and(|status| status.is_unmerged()) => {
+                    GitFileStatus::Conflict
                 }
-            })
-            .collect::<Vec<_>>();
+                "A" | "??" => GitFileStatus::Added,
+                "M" => GitFileStatus::Modified,
+                _ => continue,
+            };
+            entries.push((path, status));
+        }
         entries.sort_unstable_by(|a, b| a.0.cmp(&b.0));
-        Ok(Self {
+        staging.sort_unstable_by(|a, b| a.0.cmp(&b.0));
+        Self {
             entries: entries.into(),
-        })
+            staging: staging.into(),
+        }
     }
 
     pub fn get(&self, path: &Path) -> Option<GitFileStatus> {
@@ -94,6 +193,72 @@ impl Default for GitStatus {
     fn default() -> Self {
         Self {
             entries: Arc::new([]),
+            staging: Arc::new([]),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_status() {
+        let status = GitStatus::parse(
+            "M  staged.rs\0 M unstaged.rs\0MM both.rs\0?? new.rs\0R  renamed.rs\0original.rs\0UU conflict.rs\0 D deleted.rs\0",
+        );
+        assert_eq!(
+            status.entries.as_ref(),
+            &[
+                (
+                    RepoPath::from(Path::new("conflict.rs")),
+                    GitFileStatus::Conflict
+                ),
+                (RepoPath::from(Path::new("new.rs")), GitFileStatus::Added),
+                (
+                    RepoPath::from(Path::new("staged.rs")),
+                    GitFileStatus::Modified
+                ),
+                (
+                    RepoPath::from(Path::new("unstaged.rs")),
+                    GitFileStatus::Modified
+                ),
+            ]
+        );
+
+        let staging_status = |staged, unstaged| StagingStatus { staged, unstaged };
+        assert_eq!(
+            status.staging.as_ref(),
+            &[
+                (
+                    RepoPath::from(Path::new("both.rs")),
+                    staging_status(Some(StatusCode::Modified), Some(StatusCode::Modified))
+                ),
+                (
+                    RepoPath::from(Path::new("conflict.rs")),
+                    staging_status(None, Some(StatusCode::Unmerged))
+                ),
+                (
+                    RepoPath::from(Path::new("deleted.rs")),
+                    staging_status(None, Some(StatusCode::Deleted))
+                ),
+                (
+                    RepoPath::from(Path::new("new.rs")),
+                    staging_status(None, Some(StatusCode::Untracked))
+                ),
+                (
+                    RepoPath::from(Path::new("renamed.rs")),
+                    staging_status(Some(StatusCode::Renamed), None)
+                ),
+                (
+                    RepoPath::from(Path::new("staged.rs")),
+                    staging_status(Some(StatusCode::Modified), None)
+                ),
+                (
+                    RepoPath::from(Path::new("unstaged.rs")),
+                    staging_status(None, Some(StatusCode::Modified))
+                ),
+            ]
+        );
+    }
+}
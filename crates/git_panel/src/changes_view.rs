@@ -0,0 +1,89 @@
+use editor::{Editor, MultiBuffer, DEFAULT_MULTIBUFFER_CONTEXT};
+use futures::future;
+use git::repository::DiffBase;
+use gpui::{Context, Model, View, ViewContext, VisualContext};
+use language::Buffer;
+use project::Project;
+use util::ResultExt;
+use workspace::{notifications::NotifyTaskExt, Workspace};
+
+use crate::OpenChanges;
+
+fn changes_title(diff_base: &DiffBase) -> String {
+    match diff_base {
+        DiffBase::Index => "Unstaged Changes".to_string(),
+        DiffBase::Revision(revision) => format!("Changes since {revision}"),
+        DiffBase::MergeBase(revision) => format!("Changes since merge base with {revision}"),
+    }
+}
+
+/// Opens a multibuffer with the diff hunks of all files that differ from the project's diff base.
+pub(crate) fn open_changes(
+    workspace: &mut Workspace,
+    _: &OpenChanges,
+    cx: &mut ViewContext<Workspace>,
+) {
+    let project = workspace.project().clone();
+    let title = changes_title(project.read(cx).git_diff_base());
+    let changed_paths = project.read(cx).git_changed_paths(cx);
+
+    cx.spawn(|workspace, mut cx| async move {
+        let changed_paths = changed_paths.await?;
+        let buffers = project.update(&mut cx, |project, cx| {
+            changed_paths
+                .into_iter()
+                .map(|path| project.open_buffer(path, cx))
+                .collect::<Vec<_>>()
+        })?;
+        let mut changed_buffers = Vec::new();
+        for buffer in future::join_all(buffers).await {
+            let Some(buffer) = buffer.log_err() else {
+                continue;
+            };
+            // Wait for the hunks of buffers whose diff base was just loaded.
+            let diff_recalc = buffer.update(&mut cx, |buffer, cx| buffer.git_diff_recalc(cx))?;
+            if let Some(diff_recalc) = diff_recalc {
+                diff_recalc.await;
+            }
+            changed_buffers.push(buffer);
+        }
+
+        workspace.update(&mut cx, |workspace, cx| {
+            let editor = hunks_editor(project, changed_buffers, title, cx);
+            workspace.add_item_to_active_pane(Box::new(editor), None, cx);
+        })
+    })
+    .detach_and_notify_err(cx);
+}
+
+/// Creates an editor with an excerpt for each diff hunk of the given buffers, whose diffs
+/// must have been computed.
+pub(crate) fn hunks_editor(
+    project: Model<Project>,
+    buffers: Vec<Model<Buffer>>,
+    title: String,
+    cx: &mut ViewContext<Workspace>,
+) -> View<Editor> {
+    let replica_id = project.read(cx).replica_id();
+    let capability = project.read(cx).capability();
+    let excerpt_buffer = cx.new_model(|cx| {
+        let mut multibuffer = MultiBuffer::new(replica_id, capability);
+        for buffer in buffers {
+            let snapshot = buffer.read(cx).snapshot();
+            let hunk_ranges = snapshot
+                .git_diff_hunks_in_row_range(0..u32::MAX)
+                .map(|hunk| hunk.buffer_range)
+                .collect::<Vec<_>>();
+            if !hunk_ranges.is_empty() {
+                multibuffer.push_excerpts_with_context_lines(
+                    buffer,
+                    hunk_ranges,
+                    DEFAULT_MULTIBUFFER_CONTEXT,
+                    cx,
+                );
+            }
+        }
+        multibuffer.with_title(title)
+    });
+    cx.new_view(|cx| Editor::for_multibuffer(excerpt_buffer, Some(project), true, cx))
+}
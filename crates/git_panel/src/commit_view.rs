@@ -0,0 +1,198 @@
+use std::{
+    any::Any,
+    ffi::OsStr,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::SystemTime,
+};
+
+use anyhow::{Context as _, Result};
+use editor::{actions::ExpandAllHunkDiffs, Editor, MultiBuffer};
+use git::{repository::CommitFileChange, Oid};
+use gpui::{AppContext, AsyncAppContext, Context, Model, ViewContext, VisualContext};
+use language::{Buffer, Capability, LanguageRegistry};
+use project::ProjectPath;
+use text::BufferId;
+use workspace::{notifications::NotifyTaskExt, Workspace};
+
+use crate::changes_view::hunks_editor;
+
+/// The file of a buffer holding the contents of a file at a past revision.
+struct RevisionFile {
+    worktree_id: usize,
+    path: Arc<Path>,
+    is_deleted: bool,
+}
+
+impl language::File for RevisionFile {
+    fn as_local(&self) -> Option<&dyn language::LocalFile> {
+        None
+    }
+
+    fn mtime(&self) -> Option<SystemTime> {
+        None
+    }
+
+    fn path(&self) -> &Arc<Path> {
+        &self.path
+    }
+
+    fn full_path(&self, _: &AppContext) -> PathBuf {
+        self.path.to_path_buf()
+    }
+
+    fn file_name<'a>(&'a self, _: &'a AppContext) -> &'a OsStr {
+        self.path
+            .file_name()
+            .unwrap_or_else(|| self.path.as_os_str())
+    }
+
+    fn worktree_id(&self) -> usize {
+        self.worktree_id
+    }
+
+    fn is_deleted(&self) -> bool {
+        self.is_deleted
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn to_proto(&self) -> rpc::proto::File {
+        rpc::proto::File {
+            worktree_id: self.worktree_id as u64,
+            entry_id: None,
+            path: self.path.to_string_lossy().into(),
+            mtime: None,
+            is_deleted: self.is_deleted,
+        }
+    }
+
+    fn is_private(&self) -> bool {
+        false
+    }
+}
+
+/// Creates a read-only buffer with the contents of a file at a past revision, diffed against
+/// `diff_base` if given.
+async fn revision_buffer(
+    file: RevisionFile,
+    text: String,
+    diff_base: Option<String>,
+    languages: Arc<LanguageRegistry>,
+    cx: &mut AsyncAppContext,
+) -> Result<Model<Buffer>> {
+    let language = languages.language_for_file_path(&file.path).await.ok();
+    let buffer = cx.new_model(|cx| {
+        let buffer_id = BufferId::from(cx.entity_id().as_non_zero_u64());
+        let text_buffer = text::Buffer::new(0, buffer_id, text);
+        let mut buffer = Buffer::build(
+            text_buffer,
+            diff_base,
+            Some(Arc::new(file)),
+            Capability::ReadOnly,
+        );
+        buffer.set_language_registry(languages);
+        buffer.set_language(language, cx);
+        buffer
+    })?;
+    let diff_recalc = buffer.update(cx, |buffer, cx| buffer.git_diff_recalc(cx))?;
+    if let Some(diff_recalc) = diff_recalc {
+        diff_recalc.await;
+    }
+    Ok(buffer)
+}
+
+/// Opens a multibuffer with the hunks of the files changed by a commit, diffed against the
+/// commit's first parent.
+pub(crate) fn open_commit(
+    workspace: &mut Workspace,
+    work_directory: ProjectPath,
+    sha: Oid,
+    cx: &mut ViewContext<Workspace>,
+) {
+    let project = workspace.project().clone();
+    let languages = project.read(cx).languages().clone();
+    let worktree_id = work_directory.worktree_id.to_usize();
+    let changes = project.read(cx).git_commit_changes(work_directory, sha, cx);
+
+    cx.spawn(|workspace, mut cx| async move {
+        let mut buffers = Vec::new();
+        for change in changes.await? {
+            let CommitFileChange {
+                path,
+                old_text,
+                new_text,
+            } = change;
+            let file = RevisionFile {
+                worktree_id,
+                path: path.0.into(),
+                is_deleted: new_text.is_none(),
+            };
+            let buffer = revision_buffer(
+                file,
+                new_text.unwrap_or_default(),
+                Some(old_text.unwrap_or_default()),
+                languages.clone(),
+                &mut cx,
+            )
+            .await?;
+            buffers.push(buffer);
+        }
+
+        workspace.update(&mut cx, |workspace, cx| {
+            let title = format!("Commit {}", sha.display_short());
+            let editor = hunks_editor(project, buffers, title, cx);
+            editor.update(cx, |editor, cx| {
+                editor.set_read_only(true);
+                editor.expand_all_hunk_diffs(&ExpandAllHunkDiffs, cx);
+            });
+            workspace.add_item_to_active_pane(Box::new(editor), None, cx);
+        })
+    })
+    .detach_and_notify_err(cx);
+}
+
+/// Opens the contents of a file at a commit that changed it in a read-only editor.
+pub(crate) fn open_file_at_revision(
+    workspace: &mut Workspace,
+    path: ProjectPath,
+    sha: Oid,
+    cx: &mut ViewContext<Workspace>,
+) {
+    let project = workspace.project().clone();
+    let languages = project.read(cx).languages().clone();
+    let worktree_id = path.worktree_id.to_usize();
+    let changes = project.read(cx).git_commit_changes(path.clone(), sha, cx);
+
+    cx.spawn(|workspace, mut cx| async move {
+        let change = changes
+            .await?
+            .into_iter()
+            .next()
+            .with_context(|| format!("{:?} was not changed by this commit", path.path))?;
+        let text = change
+            .new_text
+            .with_context(|| format!("{:?} was deleted by this commit", path.path))?;
+        let file = RevisionFile {
+            worktree_id,
+            path: change.path.0.into(),
+            is_deleted: false,
+        };
+        let title = format!("{} @ {}", file.path.display(), sha.display_short());
+        let buffer = revision_buffer(file, text, None, languages, &mut cx).await?;
+
+        workspace.update(&mut cx, |workspace, cx| {
+            let multibuffer =
+                cx.new_model(|cx| MultiBuffer::singleton(buffer, cx).with_title(title));
+            let editor = cx.new_view(|cx| {
+                let mut editor = Editor::for_multibuffer(multibuffer, Some(project), false, cx);
+                editor.set_read_only(true);
+                editor
+            });
+            workspace.add_item_to_active_pane(Box::new(editor), None, cx);
+        })
+    })
+    .detach_and_notify_err(cx);
+}
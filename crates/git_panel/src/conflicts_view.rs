@@ -0,0 +1,74 @@
+use editor::{Editor, MultiBuffer, DEFAULT_MULTIBUFFER_CONTEXT};
+use futures::future;
+use git::conflict::parse_conflicts;
+use gpui::{Context, ViewContext, VisualContext};
+use util::ResultExt;
+use workspace::{
+    notifications::{NotificationId, NotifyTaskExt},
+    Toast, Workspace,
+};
+
+use crate::OpenConflicts;
+
+/// Opens a multibuffer with an excerpt for each merge conflict of the project's conflicted files.
+pub(crate) fn open_conflicts(
+    workspace: &mut Workspace,
+    _: &OpenConflicts,
+    cx: &mut ViewContext<Workspace>,
+) {
+    let project = workspace.project().clone();
+    let conflicted_paths = project.read(cx).conflicted_paths(cx);
+    if conflicted_paths.is_empty() {
+        struct NoConflicts;
+        workspace.show_toast(
+            Toast::new(
+                NotificationId::unique::<NoConflicts>(),
+                "There are no merge conflicts",
+            )
+            .autohide(),
+            cx,
+        );
+        return;
+    }
+
+    cx.spawn(|workspace, mut cx| async move {
+        let buffers = project.update(&mut cx, |project, cx| {
+            conflicted_paths
+                .into_iter()
+                .map(|path| project.open_buffer(path, cx))
+                .collect::<Vec<_>>()
+        })?;
+        let buffers = future::join_all(buffers)
+            .await
+            .into_iter()
+            .filter_map(|buffer| buffer.log_err())
+            .collect::<Vec<_>>();
+
+        workspace.update(&mut cx, |workspace, cx| {
+            let replica_id = project.read(cx).replica_id();
+            let capability = project.read(cx).capability();
+            let excerpt_buffer = cx.new_model(|cx| {
+                let mut multibuffer = MultiBuffer::new(replica_id, capability);
+                for buffer in buffers {
+                    let conflict_ranges = parse_conflicts(&buffer.read(cx).text_snapshot())
+                        .into_iter()
+                        .map(|conflict| conflict.range)
+                        .collect::<Vec<_>>();
+                    if !conflict_ranges.is_empty() {
+                        multibuffer.push_excerpts_with_context_lines(
+                            buffer,
+                            conflict_ranges,
+                            DEFAULT_MULTIBUFFER_CONTEXT,
+                            cx,
+                        );
+                    }
+                }
+                multibuffer.with_title("Conflicts".into())
+            });
+            let editor =
+                cx.new_view(|cx| Editor::for_multibuffer(excerpt_buffer, Some(project), true, cx));
+            workspace.add_item_to_active_pane(Box::new(editor), None, cx);
+        })
+    })
+    .detach_and_notify_err(cx);
+}
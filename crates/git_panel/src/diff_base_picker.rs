@@ -0,0 +1,250 @@
+use std::sync::Arc;
+
+use anyhow::anyhow;
+use fuzzy::{StringMatch, StringMatchCandidate};
+use git::repository::{DiffBase, GitRepository};
+use gpui::{
+    rems, AppContext, DismissEvent, EventEmitter, FocusHandle, FocusableView, InteractiveElement,
+    IntoElement, Model, ParentElement, Render, SharedString, Styled, Subscription, Task, View,
+    ViewContext, VisualContext, WeakView, WindowContext,
+};
+use picker::{Picker, PickerDelegate};
+use project::Project;
+use util::ResultExt;
+use workspace::{
+    notifications::NotificationId,
+    ui::{v_flex, Color, HighlightedLabel, Icon, IconName, ListItem, ListItemSpacing, Selectable},
+    ModalView, Toast, Workspace,
+};
+
+use crate::SelectDiffBase;
+
+pub struct DiffBasePicker {
+    picker: View<Picker<DiffBasePickerDelegate>>,
+    _subscription: Subscription,
+}
+
+impl DiffBasePicker {
+    fn new(delegate: DiffBasePickerDelegate, cx: &mut ViewContext<Self>) -> Self {
+        let picker = cx.new_view(|cx| Picker::uniform_list(delegate, cx));
+        let _subscription = cx.subscribe(&picker, |_, _, _, cx| cx.emit(DismissEvent));
+        Self {
+            picker,
+            _subscription,
+        }
+    }
+
+    pub(crate) fn toggle(
+        workspace: &mut Workspace,
+        _: &SelectDiffBase,
+        cx: &mut ViewContext<Workspace>,
+    ) {
+        let project = workspace.project().clone();
+        if !project.read(cx).is_local() {
+            workspace.show_error(
+                &anyhow!("The diff base of a remote project is chosen by its host"),
+                cx,
+            );
+            return;
+        }
+        let Some(repo) = project.read(cx).get_first_worktree_root_repo(cx) else {
+            workspace.show_error(&anyhow!("The project is not a git repository"), cx);
+            return;
+        };
+        let delegate = DiffBasePickerDelegate::new(project, workspace.weak_handle(), repo);
+        workspace.toggle_modal(cx, |cx| DiffBasePicker::new(delegate, cx));
+    }
+}
+
+impl ModalView for DiffBasePicker {}
+impl EventEmitter<DismissEvent> for DiffBasePicker {}
+
+impl FocusableView for DiffBasePicker {
+    fn focus_handle(&self, cx: &AppContext) -> FocusHandle {
+        self.picker.focus_handle(cx)
+    }
+}
+
+impl Render for DiffBasePicker {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        v_flex()
+            .w(rems(34.))
+            .child(self.picker.clone())
+            .on_mouse_down_out(cx.listener(|this, _, cx| {
+                this.picker.update(cx, |this, cx| {
+                    this.cancel(&Default::default(), cx);
+                })
+            }))
+    }
+}
+
+pub struct DiffBasePickerDelegate {
+    project: Model<Project>,
+    workspace: WeakView<Workspace>,
+    repo: Arc<dyn GitRepository>,
+    /// The index, `HEAD` and every other local branch with its merge base.
+    candidates: Vec<DiffBase>,
+    matches: Vec<StringMatch>,
+    /// A revision typed in the query that doesn't match any of the candidates.
+    custom_revision: Option<String>,
+    selected_index: usize,
+}
+
+impl DiffBasePickerDelegate {
+    fn new(
+        project: Model<Project>,
+        workspace: WeakView<Workspace>,
+        repo: Arc<dyn GitRepository>,
+    ) -> Self {
+        let mut branches = repo.branches().log_err().unwrap_or_default();
+        branches.retain(|branch| !branch.is_head);
+        branches.sort_unstable_by(|lhs, rhs| rhs.unix_timestamp.cmp(&lhs.unix_timestamp));
+        let candidates = [DiffBase::Index, DiffBase::head()]
+            .into_iter()
+            .chain(branches.into_iter().flat_map(|branch| {
+                [
+                    DiffBase::MergeBase(branch.name.to_string()),
+                    DiffBase::Revision(branch.name.to_string()),
+                ]
+            }))
+            .collect();
+        Self {
+            project,
+            workspace,
+            repo,
+            candidates,
+            matches: Vec::new(),
+            custom_revision: None,
+            selected_index: 0,
+        }
+    }
+
+    fn diff_base_at(&self, ix: usize) -> Option<DiffBase> {
+        match self.matches.get(ix) {
+            Some(hit) => self.candidates.get(hit.candidate_id).cloned(),
+            None => self.custom_revision.clone().map(DiffBase::Revision),
+        }
+    }
+
+    fn display_error_toast(&self, message: String, cx: &mut WindowContext<'_>) {
+        self.workspace
+            .update(cx, |workspace, cx| {
+                struct DiffBaseFailure;
+                let id = NotificationId::unique::<DiffBaseFailure>();
+                workspace.show_toast(Toast::new(id, message), cx)
+            })
+            .ok();
+    }
+}
+
+impl PickerDelegate for DiffBasePickerDelegate {
+    type ListItem = ListItem;
+
+    fn placeholder_text(&self, _cx: &mut WindowContext) -> Arc<str> {
+        "Diff against a branch or commit...".into()
+    }
+
+    fn match_count(&self) -> usize {
+        self.matches.len() + usize::from(self.custom_revision.is_some())
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(&mut self, ix: usize, _: &mut ViewContext<Picker<Self>>) {
+        self.selected_index = ix;
+    }
+
+    fn update_matches(&mut self, query: String, cx: &mut ViewContext<Picker<Self>>) -> Task<()> {
+        let candidates = self
+            .candidates
+            .iter()
+            .enumerate()
+            .map(|(id, diff_base)| {
+                let string = diff_base.to_string();
+                StringMatchCandidate {
+                    id,
+                    char_bag: string.chars().collect(),
+                    string,
+                }
+            })
+            .collect::<Vec<_>>();
+        cx.spawn(move |picker, mut cx| async move {
+            let query = query.trim().to_string();
+            let matches = if query.is_empty() {
+                candidates
+                    .into_iter()
+                    .map(|candidate| StringMatch {
+                        candidate_id: candidate.id,
+                        string: candidate.string,
+                        positions: Vec::new(),
+                        score: 0.0,
+                    })
+                    .collect()
+            } else {
+                fuzzy::match_strings(
+                    &candidates,
+                    &query,
+                    true,
+                    10000,
+                    &Default::default(),
+                    cx.background_executor().clone(),
+                )
+                .await
+            };
+            picker
+                .update(&mut cx, |picker, _| {
+                    let delegate = &mut picker.delegate;
+                    delegate.custom_revision = (!query.is_empty()
+                        && !matches.iter().any(|hit| hit.string == query))
+                    .then_some(query);
+                    delegate.matches = matches;
+                    delegate.selected_index = delegate
+                        .selected_index
+                        .min(delegate.match_count().saturating_sub(1));
+                })
+                .log_err();
+        })
+    }
+
+    fn confirm(&mut self, _: bool, cx: &mut ViewContext<Picker<Self>>) {
+        let Some(diff_base) = self.diff_base_at(self.selected_index) else {
+            return;
+        };
+        if let Err(error) = diff_base.resolve(self.repo.as_ref()) {
+            self.display_error_toast(format!("Failed to diff against {diff_base}: {error}"), cx);
+            return;
+        }
+        self.project.update(cx, |project, cx| {
+            project.set_git_diff_base(diff_base, cx);
+        });
+        cx.emit(DismissEvent);
+    }
+
+    fn dismissed(&mut self, cx: &mut ViewContext<Picker<Self>>) {
+        cx.emit(DismissEvent);
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        cx: &mut ViewContext<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let diff_base = self.diff_base_at(ix)?;
+        let label = match self.matches.get(ix) {
+            Some(hit) => HighlightedLabel::new(hit.string.clone(), hit.positions.clone()),
+            None => HighlightedLabel::new(diff_base.to_string(), Vec::new()),
+        };
+        let is_current = *self.project.read(cx).git_diff_base() == diff_base;
+        Some(
+            ListItem::new(SharedString::from(format!("diff-base-{ix}")))
+                .inset(true)
+                .spacing(ListItemSpacing::Sparse)
+                .selected(selected)
+                .start_slot(label)
+                .end_slot(is_current.then(|| Icon::new(IconName::Check).color(Color::Accent))),
+        )
+    }
+}
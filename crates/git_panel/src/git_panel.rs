@@ -0,0 +1,1004 @@
+mod changes_view;
+mod commit_view;
+mod conflicts_view;
+mod diff_base_picker;
+mod git_panel_settings;
+mod log_view;
+
+use std::{path::Path, sync::Arc, time::Duration};
+
+use anyhow::Context;
+use db::kvp::KEY_VALUE_STORE;
+use diff_base_picker::DiffBasePicker;
+use editor::Editor;
+use git::status::StatusCode;
+use git_panel_settings::{GitPanelDockPosition, GitPanelSettings};
+use gpui::{
+    actions, div, uniform_list, Action, AppContext, AsyncWindowContext, Div, ElementId,
+    EventEmitter, FocusHandle, FocusableView, InteractiveElement, IntoElement, KeyContext, Model,
+    MouseButton, ParentElement, Pixels, Render, Stateful, Styled, Subscription, Task,
+    UniformListScrollHandle, View, ViewContext, VisualContext, WeakView, WindowContext,
+};
+use menu::{Cancel, Confirm, SelectFirst, SelectLast, SelectNext, SelectPrev};
+use project::{Fs, GitStatusEntry, Project, ProjectPath};
+use serde::{Deserialize, Serialize};
+use settings::{Settings, SettingsStore};
+use util::ResultExt;
+use workspace::{
+    dock::{DockPosition, Panel, PanelEvent},
+    notifications::NotifyTaskExt,
+    ui::{
+        h_flex, v_flex, ActiveTheme, Button, ButtonCommon, ButtonStyle, CheckboxWithLabel,
+        Clickable, Color, Disableable, FluentBuilder, IconButton, IconName, IconSize, Label,
+        LabelCommon, LabelSize, ListItem, Selectable, Selection, Spacing, StyledTypography,
+        Tooltip,
+    },
+    Workspace,
+};
+
+actions!(
+    git_panel,
+    [
+        Commit,
+        StageAll,
+        UnstageAll,
+        ToggleStaged,
+        SelectDiffBase,
+        OpenChanges,
+        OpenLog,
+        OpenFileLog,
+        OpenConflicts,
+        ToggleFocus
+    ]
+);
+
+const GIT_PANEL_KEY: &str = "GitPanel";
+
+/// How long to wait for further worktree changes before reloading the status.
+const REFRESH_DEBOUNCE: Duration = Duration::from_millis(100);
+
+/// The groups of changed files shown by the git panel.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Section {
+    Staged,
+    Unstaged,
+    Untracked,
+}
+
+impl Section {
+    const ALL: [Section; 3] = [Section::Staged, Section::Unstaged, Section::Untracked];
+
+    fn title(&self) -> &'static str {
+        match self {
+            Section::Staged => "Staged Changes",
+            Section::Unstaged => "Changes",
+            Section::Untracked => "Untracked Files",
+        }
+    }
+
+    /// The change of the file that is listed in this section, if any.
+    fn status_code(&self, entry: &GitStatusEntry) -> Option<StatusCode> {
+        match self {
+            Section::Staged => entry.status.staged,
+            Section::Unstaged => entry
+                .status
+                .unstaged
+                .filter(|_| !entry.status.is_untracked()),
+            Section::Untracked => entry
+                .status
+                .unstaged
+                .filter(|_| entry.status.is_untracked()),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum ListEntry {
+    Header(Section),
+    File {
+        section: Section,
+        path: Arc<Path>,
+        status: StatusCode,
+    },
+}
+
+/// Groups the changed files by section, omitting the sections without files.
+/// A file that is partially staged is listed in both the staged and unstaged sections.
+fn list_entries(statuses: &[GitStatusEntry]) -> Vec<ListEntry> {
+    let mut entries = Vec::new();
+    for section in Section::ALL {
+        let start = entries.len();
+        entries.extend(statuses.iter().filter_map(|entry| {
+            Some(ListEntry::File {
+                section,
+                path: entry.path.clone(),
+                status: section.status_code(entry)?,
+            })
+        }));
+        if entries.len() > start {
+            entries.insert(start, ListEntry::Header(section));
+        }
+    }
+    entries
+}
+
+fn status_label(status: StatusCode) -> (&'static str, Color) {
+    match status {
+        StatusCode::Modified => ("M", Color::Modified),
+        StatusCode::TypeChanged => ("T", Color::Modified),
+        StatusCode::Added => ("A", Color::Created),
+        StatusCode::Deleted => ("D", Color::Deleted),
+        StatusCode::Renamed => ("R", Color::Modified),
+        StatusCode::Copied => ("C", Color::Created),
+        StatusCode::Unmerged => ("U", Color::Conflict),
+        StatusCode::Untracked => ("U", Color::Created),
+    }
+}
+
+pub struct GitPanel {
+    fs: Arc<dyn Fs>,
+    width: Option<Pixels>,
+    workspace: WeakView<Workspace>,
+    project: Model<Project>,
+    active: bool,
+    scroll_handle: UniformListScrollHandle,
+    focus_handle: FocusHandle,
+    pending_serialization: Task<Option<()>>,
+    /// The work directory of the repository shown, the first one of the project by default.
+    repository: Option<ProjectPath>,
+    branch: Option<Arc<str>>,
+    repository_count: usize,
+    statuses: Vec<GitStatusEntry>,
+    entries: Vec<ListEntry>,
+    selected_entry: Option<usize>,
+    commit_editor: View<Editor>,
+    amend: bool,
+    refresh_task: Task<()>,
+    status_task: Task<()>,
+    _subscriptions: Vec<Subscription>,
+}
+
+#[derive(Debug)]
+pub enum Event {
+    Focus,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SerializedGitPanel {
+    width: Option<Pixels>,
+    active: Option<bool>,
+}
+
+pub fn init_settings(cx: &mut AppContext) {
+    GitPanelSettings::register(cx);
+}
+
+pub fn init(cx: &mut AppContext) {
+    init_settings(cx);
+
+    cx.observe_new_views(|workspace: &mut Workspace, _| {
+        workspace
+            .register_action(|workspace, _: &ToggleFocus, cx| {
+                workspace.toggle_panel_focus::<GitPanel>(cx);
+            })
+            .register_action(DiffBasePicker::toggle)
+            .register_action(changes_view::open_changes)
+            .register_action(log_view::open_log)
+            .register_action(log_view::open_file_log)
+            .register_action(conflicts_view::open_conflicts);
+    })
+    .detach();
+}
+
+/// The work directories of the repositories in the project's visible worktrees, with their
+/// current branch.
+fn repositories(project: &Project, cx: &AppContext) -> Vec<(ProjectPath, Option<Arc<str>>)> {
+    project
+        .visible_worktrees(cx)
+        .flat_map(|worktree| {
+            let worktree = worktree.read(cx);
+            let worktree_id = worktree.id();
+            worktree
+                .repositories()
+                .map(|(path, entry)| {
+                    let work_directory = ProjectPath {
+                        worktree_id,
+                        path: path.clone(),
+                    };
+                    (work_directory, entry.branch())
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+impl GitPanel {
+    pub async fn load(
+        workspace: WeakView<Workspace>,
+        mut cx: AsyncWindowContext,
+    ) -> anyhow::Result<View<Self>> {
+        let serialized_panel = cx
+            .background_executor()
+            .spawn(async move { KEY_VALUE_STORE.read_kvp(GIT_PANEL_KEY) })
+            .await
+            .context("loading git panel")
+            .log_err()
+            .flatten()
+            .map(|panel| serde_json::from_str::<SerializedGitPanel>(&panel))
+            .transpose()
+            .log_err()
+            .flatten();
+
+        workspace.update(&mut cx, |workspace, cx| {
+            let panel = Self::new(workspace, cx);
+            if let Some(serialized_panel) = serialized_panel {
+                panel.update(cx, |panel, cx| {
+                    panel.width = serialized_panel.width.map(|px| px.round());
+                    panel.active = serialized_panel.active.unwrap_or(false);
+                    cx.notify();
+                });
+            }
+            panel
+        })
+    }
+
+    fn new(workspace: &mut Workspace, cx: &mut ViewContext<Workspace>) -> View<Self> {
+        let project = workspace.project().clone();
+        let workspace_handle = workspace.weak_handle();
+        let fs = workspace.app_state().fs.clone();
+        cx.new_view(|cx| {
+            let focus_handle = cx.focus_handle();
+            let focus_subscription = cx.on_focus(&focus_handle, Self::focus_in);
+
+            let mut git_panel_settings = *GitPanelSettings::get_global(cx);
+            let settings_subscription = cx.observe_global::<SettingsStore>(move |_, cx| {
+                let new_settings = *GitPanelSettings::get_global(cx);
+                if git_panel_settings != new_settings {
+                    git_panel_settings = new_settings;
+                    cx.notify();
+                }
+            });
+
+            let project_subscription =
+                cx.subscribe(&project, |git_panel, _, event, cx| match event {
+                    project::Event::WorktreeAdded
+                    | project::Event::WorktreeRemoved(_)
+                    | project::Event::WorktreeUpdatedEntries(_, _)
+                    | project::Event::WorktreeUpdatedGitRepositories => {
+                        git_panel.schedule_refresh(cx);
+                    }
+                    project::Event::GitDiffBaseChanged => cx.notify(),
+                    _ => {}
+                });
+
+            let commit_editor = cx.new_view(|cx| {
+                let mut editor = Editor::auto_height(8, cx);
+                editor.set_placeholder_text("Commit message", cx);
+                editor
+            });
+
+            let mut git_panel = Self {
+                fs,
+                width: None,
+                workspace: workspace_handle,
+                project,
+                active: false,
+                scroll_handle: UniformListScrollHandle::new(),
+                focus_handle,
+                pending_serialization: Task::ready(None),
+                repository: None,
+                branch: None,
+                repository_count: 0,
+                statuses: Vec::new(),
+                entries: Vec::new(),
+                selected_entry: None,
+                commit_editor,
+                amend: false,
+                refresh_task: Task::ready(()),
+                status_task: Task::ready(()),
+                _subscriptions: vec![
+                    settings_subscription,
+                    focus_subscription,
+                    project_subscription,
+                ],
+            };
+            git_panel.refresh(cx);
+            git_panel
+        })
+    }
+
+    fn schedule_refresh(&mut self, cx: &mut ViewContext<Self>) {
+        self.refresh_task = cx.spawn(|git_panel, mut cx| async move {
+            cx.background_executor().timer(REFRESH_DEBOUNCE).await;
+            git_panel
+                .update(&mut cx, |git_panel, cx| git_panel.refresh(cx))
+                .ok();
+        });
+    }
+
+    /// Reloads the status of the shown repository, switching to another one if it was removed.
+    fn refresh(&mut self, cx: &mut ViewContext<Self>) {
+        let repositories = repositories(self.project.read(cx), cx);
+        self.repository_count = repositories.len();
+        let current = self
+            .repository
+            .as_ref()
+            .and_then(|repository| repositories.iter().find(|(path, _)| path == repository))
+            .or_else(|| repositories.first())
+            .cloned();
+        let Some((repository, branch)) = current else {
+            self.repository = None;
+            self.branch = None;
+            self.statuses.clear();
+            self.update_entries(cx);
+            return;
+        };
+        if self.repository.as_ref() != Some(&repository) {
+            self.statuses.clear();
+            self.update_entries(cx);
+        }
+        self.repository = Some(repository.clone());
+        self.branch = branch;
+
+        let statuses = self.project.read(cx).git_status(repository.clone(), cx);
+        self.status_task = cx.spawn(|git_panel, mut cx| async move {
+            let Some(statuses) = statuses.await.log_err() else {
+                return;
+            };
+            git_panel
+                .update(&mut cx, |git_panel, cx| {
+                    if git_panel.repository.as_ref() == Some(&repository) {
+                        git_panel.statuses = statuses;
+                        git_panel.update_entries(cx);
+                    }
+                })
+                .ok();
+        });
+        cx.notify();
+    }
+
+    fn update_entries(&mut self, cx: &mut ViewContext<Self>) {
+        let selected_entry = self
+            .selected_entry
+            .and_then(|ix| self.entries.get(ix))
+            .cloned();
+        self.entries = list_entries(&self.statuses);
+        self.selected_entry = match selected_entry {
+            Some(selected_entry) => self
+                .entries
+                .iter()
+                .position(|entry| *entry == selected_entry)
+                .or_else(|| {
+                    self.selected_entry
+                        .map(|ix| ix.min(self.entries.len().saturating_sub(1)))
+                        .filter(|_| !self.entries.is_empty())
+                }),
+            None => None,
+        };
+        cx.notify();
+    }
+
+    /// Shows the next repository of the project.
+    fn cycle_repository(&mut self, cx: &mut ViewContext<Self>) {
+        let repositories = repositories(self.project.read(cx), cx);
+        let next_ix = self
+            .repository
+            .as_ref()
+            .and_then(|repository| repositories.iter().position(|(path, _)| path == repository))
+            .map_or(0, |ix| (ix + 1) % repositories.len().max(1));
+        if let Some((repository, _)) = repositories.into_iter().nth(next_ix) {
+            self.repository = Some(repository);
+            self.selected_entry = None;
+            self.refresh(cx);
+        }
+    }
+
+    fn open_log(&mut self, cx: &mut ViewContext<Self>) {
+        let Some(work_directory) = self.repository.clone() else {
+            return;
+        };
+        self.workspace
+            .update(cx, |workspace, cx| {
+                log_view::open_log_view(workspace, work_directory, None, cx);
+            })
+            .log_err();
+    }
+
+    fn stage_paths(&mut self, paths: Vec<Arc<Path>>, stage: bool, cx: &mut ViewContext<Self>) {
+        let Some(repository) = self.repository.as_ref() else {
+            return;
+        };
+        let worktree_id = repository.worktree_id;
+        let paths = paths
+            .into_iter()
+            .map(|path| ProjectPath { worktree_id, path })
+            .collect();
+        let task = self.project.update(cx, |project, cx| {
+            if stage {
+                project.stage_paths(paths, cx)
+            } else {
+                project.unstage_paths(paths, cx)
+            }
+        });
+        self.run_git_operation(task, cx);
+    }
+
+    /// Runs an operation that changes the repository, reloading the status once it's done.
+    fn run_git_operation(&mut self, task: Task<anyhow::Result<()>>, cx: &mut ViewContext<Self>) {
+        cx.spawn(|git_panel, mut cx| async move {
+            let result = task.await;
+            git_panel.update(&mut cx, |git_panel, cx| git_panel.refresh(cx))?;
+            result
+        })
+        .detach_and_notify_err(cx);
+    }
+
+    fn section_paths(&self, section: Section) -> Vec<Arc<Path>> {
+        self.entries
+            .iter()
+            .filter_map(|entry| match entry {
+                ListEntry::File {
+                    section: file_section,
+                    path,
+                    ..
+                } if *file_section == section => Some(path.clone()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn stage_all(&mut self, _: &StageAll, cx: &mut ViewContext<Self>) {
+        let mut paths = self.section_paths(Section::Unstaged);
+        paths.extend(self.section_paths(Section::Untracked));
+        if !paths.is_empty() {
+            self.stage_paths(paths, true, cx);
+        }
+    }
+
+    fn unstage_all(&mut self, _: &UnstageAll, cx: &mut ViewContext<Self>) {
+        let paths = self.section_paths(Section::Staged);
+        if !paths.is_empty() {
+            self.stage_paths(paths, false, cx);
+        }
+    }
+
+    /// Stages or unstages the selected file, or all files of the selected section.
+    fn toggle_staged(&mut self, _: &ToggleStaged, cx: &mut ViewContext<Self>) {
+        let Some(ix) = self.selected_entry else {
+            return;
+        };
+        self.toggle_staged_entry(ix, cx);
+    }
+
+    fn toggle_staged_entry(&mut self, ix: usize, cx: &mut ViewContext<Self>) {
+        match self.entries.get(ix).cloned() {
+            Some(ListEntry::Header(Section::Staged)) => self.unstage_all(&UnstageAll, cx),
+            Some(ListEntry::Header(section)) => {
+                let paths = self.section_paths(section);
+                self.stage_paths(paths, true, cx);
+            }
+            Some(ListEntry::File { section, path, .. }) => {
+                self.stage_paths(vec![path], section != Section::Staged, cx);
+            }
+            None => {}
+        }
+    }
+
+    fn commit(&mut self, _: &Commit, cx: &mut ViewContext<Self>) {
+        let Some(repository) = self.repository.clone() else {
+            return;
+        };
+        let message = self.commit_editor.read(cx).text(cx);
+        if message.trim().is_empty() {
+            return;
+        }
+        let amend = self.amend;
+        let task = self.project.update(cx, |project, cx| {
+            project.git_commit(repository, message, amend, cx)
+        });
+        let commit_editor = self.commit_editor.downgrade();
+        let task = cx.spawn(|git_panel, mut cx| async move {
+            task.await?;
+            commit_editor.update(&mut cx, |editor, cx| editor.clear(cx))?;
+            git_panel.update(&mut cx, |git_panel, cx| {
+                git_panel.amend = false;
+                cx.notify();
+            })
+        });
+        self.run_git_operation(task, cx);
+    }
+
+    fn serialize(&mut self, cx: &mut ViewContext<Self>) {
+        let width = self.width;
+        let active = Some(self.active);
+        self.pending_serialization = cx.background_executor().spawn(
+            async move {
+                KEY_VALUE_STORE
+                    .write_kvp(
+                        GIT_PANEL_KEY.into(),
+                        serde_json::to_string(&SerializedGitPanel { width, active })?,
+                    )
+                    .await?;
+                anyhow::Ok(())
+            }
+            .log_err(),
+        );
+    }
+
+    fn dispatch_context(&self, cx: &ViewContext<Self>) -> KeyContext {
+        let mut dispatch_context = KeyContext::new_with_defaults();
+        dispatch_context.add("GitPanel");
+        if self.commit_editor.focus_handle(cx).is_focused(cx) {
+            dispatch_context.add("editing");
+        } else {
+            dispatch_context.add("menu");
+            dispatch_context.add("not_editing");
+        }
+        dispatch_context
+    }
+
+    fn focus_in(&mut self, cx: &mut ViewContext<Self>) {
+        cx.emit(Event::Focus);
+    }
+
+    fn select_entry_at(&mut self, ix: usize, cx: &mut ViewContext<Self>) {
+        if ix < self.entries.len() {
+            self.selected_entry = Some(ix);
+            self.scroll_handle.scroll_to_item(ix);
+            cx.notify();
+        }
+    }
+
+    fn select_next(&mut self, _: &SelectNext, cx: &mut ViewContext<Self>) {
+        match self.selected_entry {
+            Some(ix) if ix + 1 < self.entries.len() => self.select_entry_at(ix + 1, cx),
+            _ => self.select_first(&SelectFirst {}, cx),
+        }
+    }
+
+    fn select_prev(&mut self, _: &SelectPrev, cx: &mut ViewContext<Self>) {
+        match self.selected_entry {
+            Some(ix) if ix > 0 => self.select_entry_at(ix - 1, cx),
+            _ => self.select_last(&SelectLast {}, cx),
+        }
+    }
+
+    fn select_first(&mut self, _: &SelectFirst, cx: &mut ViewContext<Self>) {
+        self.select_entry_at(0, cx);
+    }
+
+    fn select_last(&mut self, _: &SelectLast, cx: &mut ViewContext<Self>) {
+        if let Some(last_ix) = self.entries.len().checked_sub(1) {
+            self.select_entry_at(last_ix, cx);
+        }
+    }
+
+    fn cancel(&mut self, _: &Cancel, cx: &mut ViewContext<Self>) {
+        if self.selected_entry.take().is_some() {
+            cx.notify();
+        }
+    }
+
+    fn confirm(&mut self, _: &Confirm, cx: &mut ViewContext<Self>) {
+        if let Some(ix) = self.selected_entry {
+            self.open_entry(ix, cx);
+        }
+    }
+
+    fn open_entry(&mut self, ix: usize, cx: &mut ViewContext<Self>) {
+        let Some(ListEntry::File { path, .. }) = self.entries.get(ix) else {
+            return;
+        };
+        let Some(repository) = self.repository.as_ref() else {
+            return;
+        };
+        let project_path = ProjectPath {
+            worktree_id: repository.worktree_id,
+            path: path.clone(),
+        };
+        self.workspace
+            .update(cx, |workspace, cx| {
+                workspace
+                    .open_path(project_path, None, true, cx)
+                    .detach_and_log_err(cx);
+            })
+            .log_err();
+    }
+
+    fn render_entry(&self, ix: usize, cx: &mut ViewContext<Self>) -> Stateful<Div> {
+        let is_selected = self.selected_entry == Some(ix);
+        let item_id = ElementId::from(ix);
+        let item = match &self.entries[ix] {
+            ListEntry::Header(section) => {
+                let (icon, tooltip) = if *section == Section::Staged {
+                    (IconName::Dash, "Unstage All")
+                } else {
+                    (IconName::Plus, "Stage All")
+                };
+                ListItem::new(item_id.clone())
+                    .selected(is_selected)
+                    .child(
+                        h_flex().h_6().child(
+                            Label::new(section.title())
+                                .size(LabelSize::Small)
+                                .color(Color::Muted),
+                        ),
+                    )
+                    .end_hover_slot(
+                        IconButton::new(("toggle-staged-section", ix), icon)
+                            .icon_size(IconSize::Small)
+                            .tooltip(move |cx| Tooltip::text(tooltip, cx))
+                            .on_click(cx.listener(move |git_panel, _, cx| {
+                                git_panel.toggle_staged_entry(ix, cx);
+                            })),
+                    )
+            }
+            ListEntry::File {
+                section,
+                path,
+                status,
+            } => {
+                let (status_letter, status_color) = status_label(*status);
+                let (icon, tooltip) = if *section == Section::Staged {
+                    (IconName::Dash, "Unstage File")
+                } else {
+                    (IconName::Plus, "Stage File")
+                };
+                ListItem::new(item_id.clone())
+                    .selected(is_selected)
+                    .child(
+                        h_flex()
+                            .h_6()
+                            .gap_2()
+                            .child(
+                                Label::new(status_letter)
+                                    .size(LabelSize::Small)
+                                    .color(status_color),
+                            )
+                            .child(Label::new(path.to_string_lossy().to_string())),
+                    )
+                    .end_hover_slot(
+                        IconButton::new(("toggle-staged-file", ix), icon)
+                            .icon_size(IconSize::Small)
+                            .tooltip(move |cx| Tooltip::text(tooltip, cx))
+                            .on_click(cx.listener(move |git_panel, _, cx| {
+                                git_panel.toggle_staged_entry(ix, cx);
+                            })),
+                    )
+                    .on_click(cx.listener(move |git_panel, event: &gpui::ClickEvent, cx| {
+                        if event.down.button == MouseButton::Right || event.down.first_mouse {
+                            return;
+                        }
+                        git_panel.selected_entry = Some(ix);
+                        git_panel.open_entry(ix, cx);
+                        cx.notify();
+                    }))
+            }
+        };
+
+        div()
+            .text_ui(cx)
+            .id(item_id)
+            .child(item)
+            .border_1()
+            .border_r_2()
+            .rounded_none()
+            .hover(|style| {
+                if is_selected {
+                    style
+                } else {
+                    let hover_color = cx.theme().colors().ghost_element_hover;
+                    style.bg(hover_color).border_color(hover_color)
+                }
+            })
+            .when(
+                is_selected && self.focus_handle.contains_focused(cx),
+                |div| div.border_color(Color::Selected.color(cx)),
+            )
+    }
+
+    fn render_header(&self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let repository_name = self
+            .repository
+            .as_ref()
+            .map(|repository| {
+                let worktree_name = self
+                    .project
+                    .read(cx)
+                    .worktree_for_id(repository.worktree_id, cx)
+                    .map(|worktree| worktree.read(cx).root_name().to_string())
+                    .unwrap_or_default();
+                match repository.path.file_name() {
+                    Some(name) => name.to_string_lossy().to_string(),
+                    None => worktree_name,
+                }
+            })
+            .unwrap_or_default();
+        let diff_base = format!("Diff: {}", self.project.read(cx).git_diff_base());
+        let has_conflicts = self.statuses.iter().any(|entry| entry.status.is_unmerged());
+
+        h_flex()
+            .px_2()
+            .py_1()
+            .gap_1()
+            .justify_between()
+            .child(
+                h_flex()
+                    .gap_1()
+                    .child(Label::new(repository_name).size(LabelSize::Small))
+                    .when_some(self.branch.clone(), |this, branch| {
+                        this.child(
+                            Label::new(branch.to_string())
+                                .size(LabelSize::Small)
+                                .color(Color::Muted),
+                        )
+                    }),
+            )
+            .child(
+                h_flex()
+                    .gap_1()
+                    .child(
+                        Button::new("diff-base", diff_base)
+                            .label_size(LabelSize::Small)
+                            .color(Color::Muted)
+                            .tooltip(|cx| {
+                                Tooltip::for_action("Select Diff Base", &SelectDiffBase, cx)
+                            })
+                            .on_click(|_, cx| cx.dispatch_action(SelectDiffBase.boxed_clone())),
+                    )
+                    .child(
+                        IconButton::new("open-changes", IconName::ExpandVertical)
+                            .icon_size(IconSize::Small)
+                            .tooltip(|cx| Tooltip::for_action("Open Changes", &OpenChanges, cx))
+                            .on_click(|_, cx| cx.dispatch_action(OpenChanges.boxed_clone())),
+                    )
+                    .child(
+                        IconButton::new("open-log", IconName::HistoryRerun)
+                            .icon_size(IconSize::Small)
+                            .tooltip(|cx| Tooltip::for_action("Open History", &OpenLog, cx))
+                            .on_click(cx.listener(|git_panel, _, cx| {
+                                git_panel.open_log(cx);
+                            })),
+                    )
+                    .when(has_conflicts, |this| {
+                        this.child(
+                            IconButton::new("open-conflicts", IconName::ExclamationTriangle)
+                                .icon_size(IconSize::Small)
+                                .icon_color(Color::Conflict)
+                                .tooltip(|cx| {
+                                    Tooltip::for_action("Open Conflicts", &OpenConflicts, cx)
+                                })
+                                .on_click(|_, cx| cx.dispatch_action(OpenConflicts.boxed_clone())),
+                        )
+                    })
+                    .when(self.repository_count > 1, |this| {
+                        this.child(
+                            IconButton::new("next-repository", IconName::ArrowCircle)
+                                .icon_size(IconSize::Small)
+                                .tooltip(|cx| Tooltip::text("Switch Repository", cx))
+                                .on_click(cx.listener(|git_panel, _, cx| {
+                                    git_panel.cycle_repository(cx);
+                                })),
+                        )
+                    }),
+            )
+    }
+
+    fn render_commit_editor(&self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let can_commit = self.amend || self.entries.contains(&ListEntry::Header(Section::Staged));
+        v_flex()
+            .p_2()
+            .gap(Spacing::Small.rems(cx))
+            .border_t_1()
+            .border_color(cx.theme().colors().border)
+            .child(
+                div()
+                    .p_1()
+                    .rounded_md()
+                    .border_1()
+                    .border_color(cx.theme().colors().border)
+                    .child(self.commit_editor.clone()),
+            )
+            .child(
+                h_flex()
+                    .justify_between()
+                    .child(CheckboxWithLabel::new(
+                        "amend",
+                        Label::new("Amend").size(LabelSize::Small),
+                        Selection::from(self.amend),
+                        cx.listener(|git_panel, selection: &Selection, cx| {
+                            git_panel.amend = *selection == Selection::Selected;
+                            cx.notify();
+                        }),
+                    ))
+                    .child(
+                        Button::new("commit", if self.amend { "Amend" } else { "Commit" })
+                            .label_size(LabelSize::Small)
+                            .style(ButtonStyle::Filled)
+                            .disabled(!can_commit)
+                            .on_click(cx.listener(|git_panel, _, cx| {
+                                git_panel.commit(&Commit, cx);
+                            })),
+                    ),
+            )
+    }
+}
+
+impl Panel for GitPanel {
+    fn persistent_name() -> &'static str {
+        "Git Panel"
+    }
+
+    fn position(&self, cx: &WindowContext) -> DockPosition {
+        match GitPanelSettings::get_global(cx).dock {
+            GitPanelDockPosition::Left => DockPosition::Left,
+            GitPanelDockPosition::Right => DockPosition::Right,
+        }
+    }
+
+    fn position_is_valid(&self, position: DockPosition) -> bool {
+        matches!(position, DockPosition::Left | DockPosition::Right)
+    }
+
+    fn set_position(&mut self, position: DockPosition, cx: &mut ViewContext<Self>) {
+        settings::update_settings_file::<GitPanelSettings>(self.fs.clone(), cx, move |settings| {
+            let dock = match position {
+                DockPosition::Left | DockPosition::Bottom => GitPanelDockPosition::Left,
+                DockPosition::Right => GitPanelDockPosition::Right,
+            };
+            settings.dock = Some(dock);
+        });
+    }
+
+    fn size(&self, cx: &WindowContext) -> Pixels {
+        self.width
+            .unwrap_or_else(|| GitPanelSettings::get_global(cx).default_width)
+    }
+
+    fn set_size(&mut self, size: Option<Pixels>, cx: &mut ViewContext<Self>) {
+        self.width = size;
+        self.serialize(cx);
+        cx.notify();
+    }
+
+    fn icon(&self, cx: &WindowContext) -> Option<IconName> {
+        GitPanelSettings::get_global(cx)
+            .button
+            .then(|| IconName::FileGit)
+    }
+
+    fn icon_tooltip(&self, _: &WindowContext) -> Option<&'static str> {
+        Some("Git Panel")
+    }
+
+    fn toggle_action(&self) -> Box<dyn Action> {
+        Box::new(ToggleFocus)
+    }
+
+    fn starts_open(&self, _: &WindowContext) -> bool {
+        self.active
+    }
+
+    fn set_active(&mut self, active: bool, cx: &mut ViewContext<Self>) {
+        self.active = active;
+        self.serialize(cx);
+        if active {
+            self.refresh(cx);
+        }
+    }
+}
+
+impl FocusableView for GitPanel {
+    fn focus_handle(&self, _: &AppContext) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl EventEmitter<Event> for GitPanel {}
+
+impl EventEmitter<PanelEvent> for GitPanel {}
+
+impl Render for GitPanel {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let git_panel = v_flex()
+            .id("git-panel")
+            .size_full()
+            .relative()
+            .key_context(self.dispatch_context(cx))
+            .on_action(cx.listener(Self::confirm))
+            .on_action(cx.listener(Self::cancel))
+            .on_action(cx.listener(Self::select_next))
+            .on_action(cx.listener(Self::select_prev))
+            .on_action(cx.listener(Self::select_first))
+            .on_action(cx.listener(Self::select_last))
+            .on_action(cx.listener(Self::toggle_staged))
+            .on_action(cx.listener(Self::stage_all))
+            .on_action(cx.listener(Self::unstage_all))
+            .on_action(cx.listener(Self::commit))
+            .track_focus(&self.focus_handle);
+
+        if self.repository.is_none() {
+            return git_panel.child(
+                v_flex()
+                    .justify_center()
+                    .size_full()
+                    .child(h_flex().justify_center().child(Label::new("No repository")))
+                    .child(h_flex().pt(Spacing::Small.rems(cx)).justify_center().child(
+                        Label::new("Open a folder that is tracked by git").color(Color::Muted),
+                    )),
+            );
+        }
+
+        git_panel
+            .child(self.render_header(cx))
+            .child(if self.entries.is_empty() {
+                v_flex()
+                    .flex_1()
+                    .justify_center()
+                    .child(
+                        h_flex()
+                            .justify_center()
+                            .child(Label::new("No changes").color(Color::Muted)),
+                    )
+                    .into_any_element()
+            } else {
+                let items_len = self.entries.len();
+                uniform_list(cx.view().clone(), "entries", items_len, {
+                    move |git_panel, range, cx| {
+                        range.map(|ix| git_panel.render_entry(ix, cx)).collect()
+                    }
+                })
+                .flex_1()
+                .track_scroll(self.scroll_handle.clone())
+                .into_any_element()
+            })
+            .child(self.render_commit_editor(cx))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use git::status::StagingStatus;
+
+    use super::*;
+
+    #[test]
+    fn test_list_entries() {
+        let entry = |path: &str, staged, unstaged| GitStatusEntry {
+            path: Path::new(path).into(),
+            status: StagingStatus { staged, unstaged },
+        };
+        let file = |section, path: &str, status| ListEntry::File {
+            section,
+            path: Path::new(path).into(),
+            status,
+        };
+        let statuses = [
+            entry("a.txt", Some(StatusCode::Modified), None),
+            entry("b.txt", Some(StatusCode::Added), Some(StatusCode::Modified)),
+            entry("c.txt", None, Some(StatusCode::Untracked)),
+            entry("d.txt", None, Some(StatusCode::Deleted)),
+        ];
+
+        assert_eq!(
+            list_entries(&statuses),
+            [
+                ListEntry::Header(Section::Staged),
+                file(Section::Staged, "a.txt", StatusCode::Modified),
+                file(Section::Staged, "b.txt", StatusCode::Added),
+                ListEntry::Header(Section::Unstaged),
+                file(Section::Unstaged, "b.txt", StatusCode::Modified),
+                file(Section::Unstaged, "d.txt", StatusCode::Deleted),
+                ListEntry::Header(Section::Untracked),
+                file(Section::Untracked, "c.txt", StatusCode::Untracked),
+            ]
+        );
+        assert_eq!(
+            list_entries(&statuses[2..3]),
+            [
+                ListEntry::Header(Section::Untracked),
+                file(Section::Untracked, "c.txt", StatusCode::Untracked),
+            ]
+        );
+    }
+}
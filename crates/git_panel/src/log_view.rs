@@ -0,0 +1,463 @@
+use std::{
+    future::Future,
+    hash::{Hash, Hasher},
+    sync::Arc,
+};
+
+use anyhow::anyhow;
+use git::{
+    log::{Log, LogEntry},
+    parse_git_remote_url, GitHostingProvider, GitHostingProviderRegistry, Oid,
+};
+use gpui::{
+    div, uniform_list, AnyElement, AppContext, Asset, Div, ElementId, EventEmitter, FocusHandle,
+    FocusableView, InteractiveElement, IntoElement, MouseButton, ParentElement, Render,
+    SharedString, Stateful, StatefulInteractiveElement, Styled, Task, UniformListScrollHandle,
+    View, ViewContext, VisualContext, WeakView, WindowContext,
+};
+use menu::{Confirm, SelectFirst, SelectLast, SelectNext, SelectPrev};
+use project::{Project, ProjectPath};
+use workspace::{
+    item::{Item, ItemEvent, TabContentParams},
+    ui::{
+        h_flex, v_flex, ActiveTheme, Avatar, ButtonCommon, Clickable, Color, FluentBuilder, Icon,
+        IconButton, IconName, IconSize, Label, LabelCommon, LabelSize, ListItem, Selectable,
+        StyledTypography, Tooltip,
+    },
+    Workspace, WorkspaceId,
+};
+
+use crate::{commit_view, repositories, OpenFileLog, OpenLog};
+
+/// The most commits that a log lists.
+const LOG_LIMIT: usize = 1000;
+
+/// The hosting provider of a repository's remote, used to fetch the avatars of commit authors.
+#[derive(Clone)]
+struct CommitRemote {
+    host: Arc<dyn GitHostingProvider + Send + Sync + 'static>,
+    owner: String,
+    repo: String,
+}
+
+impl CommitRemote {
+    fn parse(url: &str, cx: &mut AppContext) -> Option<Self> {
+        let provider_registry = GitHostingProviderRegistry::default_global(cx);
+        let (host, remote) = parse_git_remote_url(provider_registry, url)?;
+        Some(Self {
+            host,
+            owner: remote.owner.to_string(),
+            repo: remote.repo.to_string(),
+        })
+    }
+}
+
+#[derive(Clone)]
+struct CommitAvatarAsset {
+    sha: Oid,
+    remote: CommitRemote,
+}
+
+impl Hash for CommitAvatarAsset {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.sha.hash(state);
+        self.remote.host.name().hash(state);
+    }
+}
+
+impl Asset for CommitAvatarAsset {
+    type Source = Self;
+    type Output = Option<SharedString>;
+
+    fn load(
+        source: Self::Source,
+        cx: &mut WindowContext,
+    ) -> impl Future<Output = Self::Output> + Send + 'static {
+        let client = cx.http_client();
+
+        async move {
+            let remote = &source.remote;
+            remote
+                .host
+                .commit_author_avatar_url(&remote.owner, &remote.repo, source.sha, client)
+                .await
+                .ok()
+                .flatten()
+                .map(|url| SharedString::from(url.to_string()))
+        }
+    }
+}
+
+/// Finds the work directory of the repository containing a path.
+fn work_directory_for_path(
+    project: &Project,
+    path: &ProjectPath,
+    cx: &AppContext,
+) -> Option<ProjectPath> {
+    repositories(project, cx)
+        .into_iter()
+        .map(|(work_directory, _)| work_directory)
+        .filter(|work_directory| {
+            work_directory.worktree_id == path.worktree_id
+                && path.path.starts_with(&work_directory.path)
+        })
+        .max_by_key(|work_directory| work_directory.path.components().count())
+}
+
+/// Opens the history of the repository containing the active item, or of the project's first
+/// repository.
+pub(crate) fn open_log(workspace: &mut Workspace, _: &OpenLog, cx: &mut ViewContext<Workspace>) {
+    let project = workspace.project().read(cx);
+    let work_directory = workspace
+        .active_item(cx)
+        .and_then(|item| item.project_path(cx))
+        .and_then(|path| work_directory_for_path(project, &path, cx))
+        .or_else(|| {
+            repositories(project, cx)
+                .into_iter()
+                .map(|(work_directory, _)| work_directory)
+                .next()
+        });
+    let Some(work_directory) = work_directory else {
+        workspace.show_error(&anyhow!("The project is not a git repository"), cx);
+        return;
+    };
+    open_log_view(workspace, work_directory, None, cx);
+}
+
+/// Opens the history of the active item's file.
+pub(crate) fn open_file_log(
+    workspace: &mut Workspace,
+    _: &OpenFileLog,
+    cx: &mut ViewContext<Workspace>,
+) {
+    let Some(path) = workspace
+        .active_item(cx)
+        .and_then(|item| item.project_path(cx))
+    else {
+        workspace.show_error(&anyhow!("Open a file to view its history"), cx);
+        return;
+    };
+    let Some(work_directory) = work_directory_for_path(workspace.project().read(cx), &path, cx)
+    else {
+        workspace.show_error(&anyhow!("The file is not in a git repository"), cx);
+        return;
+    };
+    open_log_view(workspace, work_directory, Some(path), cx);
+}
+
+/// Opens the history of a repository, or of a file in it.
+pub(crate) fn open_log_view(
+    workspace: &mut Workspace,
+    work_directory: ProjectPath,
+    file: Option<ProjectPath>,
+    cx: &mut ViewContext<Workspace>,
+) {
+    let log_view = cx.new_view(|cx| GitLogView::new(workspace, work_directory, file, cx));
+    workspace.add_item_to_active_pane(Box::new(log_view), None, cx);
+}
+
+/// Lists the commits of a repository or of a single file.
+pub struct GitLogView {
+    workspace: WeakView<Workspace>,
+    work_directory: ProjectPath,
+    /// The file whose history is listed, or `None` to list the history of the repository.
+    file: Option<ProjectPath>,
+    log: Log,
+    remote: Option<CommitRemote>,
+    error: Option<SharedString>,
+    loading: bool,
+    selected_index: Option<usize>,
+    focus_handle: FocusHandle,
+    scroll_handle: UniformListScrollHandle,
+    _load_task: Task<()>,
+}
+
+impl GitLogView {
+    fn new(
+        workspace: &Workspace,
+        work_directory: ProjectPath,
+        file: Option<ProjectPath>,
+        cx: &mut ViewContext<Self>,
+    ) -> Self {
+        let path = file.clone().unwrap_or_else(|| work_directory.clone());
+        let log = workspace.project().read(cx).git_log(path, LOG_LIMIT, cx);
+        let _load_task = cx.spawn(|log_view, mut cx| async move {
+            let log = log.await;
+            log_view
+                .update(&mut cx, |log_view, cx| {
+                    log_view.loading = false;
+                    match log {
+                        Ok(log) => {
+                            log_view.remote = log
+                                .remote_url
+                                .as_deref()
+                                .and_then(|url| CommitRemote::parse(url, cx));
+                            log_view.log = log;
+                        }
+                        Err(error) => log_view.error = Some(error.to_string().into()),
+                    }
+                    cx.notify();
+                })
+                .ok();
+        });
+
+        Self {
+            workspace: workspace.weak_handle(),
+            work_directory,
+            file,
+            log: Log::default(),
+            remote: None,
+            error: None,
+            loading: true,
+            selected_index: None,
+            focus_handle: cx.focus_handle(),
+            scroll_handle: UniformListScrollHandle::new(),
+            _load_task,
+        }
+    }
+
+    fn title(&self) -> String {
+        let path = self.file.as_ref().unwrap_or(&self.work_directory);
+        match path.path.file_name() {
+            Some(name) => format!("History of {}", name.to_string_lossy()),
+            None => "History".to_string(),
+        }
+    }
+
+    fn select_entry_at(&mut self, ix: usize, cx: &mut ViewContext<Self>) {
+        self.selected_index = Some(ix);
+        self.scroll_handle.scroll_to_item(ix);
+        cx.notify();
+    }
+
+    fn select_next(&mut self, _: &SelectNext, cx: &mut ViewContext<Self>) {
+        let entry_count = self.log.entries.len();
+        if entry_count > 0 {
+            let ix = self
+                .selected_index
+                .map_or(0, |ix| (ix + 1).min(entry_count - 1));
+            self.select_entry_at(ix, cx);
+        }
+    }
+
+    fn select_prev(&mut self, _: &SelectPrev, cx: &mut ViewContext<Self>) {
+        if !self.log.entries.is_empty() {
+            let ix = self.selected_index.map_or(0, |ix| ix.saturating_sub(1));
+            self.select_entry_at(ix, cx);
+        }
+    }
+
+    fn select_first(&mut self, _: &SelectFirst, cx: &mut ViewContext<Self>) {
+        if !self.log.entries.is_empty() {
+            self.select_entry_at(0, cx);
+        }
+    }
+
+    fn select_last(&mut self, _: &SelectLast, cx: &mut ViewContext<Self>) {
+        if let Some(ix) = self.log.entries.len().checked_sub(1) {
+            self.select_entry_at(ix, cx);
+        }
+    }
+
+    fn confirm(&mut self, _: &Confirm, cx: &mut ViewContext<Self>) {
+        if let Some(ix) = self.selected_index {
+            self.open_commit(ix, cx);
+        }
+    }
+
+    fn open_commit(&mut self, ix: usize, cx: &mut ViewContext<Self>) {
+        let Some(entry) = self.log.entries.get(ix) else {
+            return;
+        };
+        let sha = entry.sha;
+        let work_directory = self.work_directory.clone();
+        self.workspace
+            .update(cx, |workspace, cx| {
+                commit_view::open_commit(workspace, work_directory, sha, cx);
+            })
+            .ok();
+    }
+
+    fn open_file_at_revision(&mut self, ix: usize, cx: &mut ViewContext<Self>) {
+        let (Some(entry), Some(file)) = (self.log.entries.get(ix), self.file.clone()) else {
+            return;
+        };
+        let sha = entry.sha;
+        self.workspace
+            .update(cx, |workspace, cx| {
+                commit_view::open_file_at_revision(workspace, file, sha, cx);
+            })
+            .ok();
+    }
+
+    fn render_avatar(&self, sha: Oid, cx: &mut ViewContext<Self>) -> Option<AnyElement> {
+        let remote = self
+            .remote
+            .clone()
+            .filter(|remote| remote.host.supports_avatars())?;
+        let asset = CommitAvatarAsset { sha, remote };
+        let element = match cx.use_cached_asset::<CommitAvatarAsset>(&asset) {
+            // Loading or no avatar found
+            None | Some(None) => Icon::new(IconName::Person)
+                .color(Color::Muted)
+                .into_any_element(),
+            Some(Some(url)) => Avatar::new(url.to_string()).into_any_element(),
+        };
+        Some(element)
+    }
+
+    fn render_entry(&self, ix: usize, cx: &mut ViewContext<Self>) -> Stateful<Div> {
+        let entry = &self.log.entries[ix];
+        let is_selected = self.selected_index == Some(ix);
+        let message = self
+            .log
+            .messages
+            .get(&entry.sha)
+            .cloned()
+            .unwrap_or_else(|| entry.summary.clone());
+        let item = ListItem::new(ix)
+            .selected(is_selected)
+            .start_slot::<AnyElement>(self.render_avatar(entry.sha, cx))
+            .child(
+                v_flex()
+                    .py_1()
+                    .child(Label::new(entry.summary.clone()))
+                    .child(
+                        h_flex()
+                            .gap_2()
+                            .child(
+                                Label::new(entry.author.clone())
+                                    .size(LabelSize::Small)
+                                    .color(Color::Muted),
+                            )
+                            .child(
+                                Label::new(relative_timestamp(entry, cx))
+                                    .size(LabelSize::Small)
+                                    .color(Color::Muted),
+                            )
+                            .child(
+                                Label::new(entry.sha.display_short())
+                                    .size(LabelSize::Small)
+                                    .color(Color::Muted),
+                            ),
+                    ),
+            )
+            .when(self.file.is_some(), |item| {
+                item.end_hover_slot(
+                    IconButton::new(("open-file-at-revision", ix), IconName::File)
+                        .icon_size(IconSize::Small)
+                        .tooltip(|cx| Tooltip::text("Open File at This Revision", cx))
+                        .on_click(cx.listener(move |log_view, _, cx| {
+                            log_view.open_file_at_revision(ix, cx);
+                        })),
+                )
+            })
+            .on_click(cx.listener(move |log_view, event: &gpui::ClickEvent, cx| {
+                if event.down.button == MouseButton::Right || event.down.first_mouse {
+                    return;
+                }
+                log_view.select_entry_at(ix, cx);
+                log_view.open_commit(ix, cx);
+            }));
+
+        div()
+            .text_ui(cx)
+            .id(ElementId::from(ix))
+            .tooltip(move |cx| Tooltip::text(message.clone(), cx))
+            .child(item)
+    }
+}
+
+fn relative_timestamp(entry: &LogEntry, cx: &WindowContext) -> String {
+    match entry.author_offset_date_time() {
+        Ok(timestamp) => time_format::format_localized_timestamp(
+            timestamp,
+            time::OffsetDateTime::now_utc(),
+            cx.local_timezone(),
+            time_format::TimestampFormat::Relative,
+        ),
+        Err(_) => "Error parsing date".to_string(),
+    }
+}
+
+impl EventEmitter<ItemEvent> for GitLogView {}
+
+impl FocusableView for GitLogView {
+    fn focus_handle(&self, _: &AppContext) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Item for GitLogView {
+    type Event = ItemEvent;
+
+    fn tab_content(&self, params: TabContentParams, _: &WindowContext) -> AnyElement {
+        Label::new(self.title())
+            .color(if params.selected {
+                Color::Default
+            } else {
+                Color::Muted
+            })
+            .into_any_element()
+    }
+
+    fn telemetry_event_text(&self) -> Option<&'static str> {
+        Some("git log")
+    }
+
+    fn clone_on_split(
+        &self,
+        _workspace_id: Option<WorkspaceId>,
+        _: &mut ViewContext<Self>,
+    ) -> Option<View<Self>> {
+        None
+    }
+
+    fn to_item_events(event: &Self::Event, mut f: impl FnMut(ItemEvent)) {
+        f(*event)
+    }
+}
+
+impl Render for GitLogView {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let log_view = v_flex()
+            .size_full()
+            .key_context("GitLogView")
+            .on_action(cx.listener(Self::select_next))
+            .on_action(cx.listener(Self::select_prev))
+            .on_action(cx.listener(Self::select_first))
+            .on_action(cx.listener(Self::select_last))
+            .on_action(cx.listener(Self::confirm))
+            .track_focus(&self.focus_handle)
+            .bg(cx.theme().colors().editor_background);
+
+        let placeholder = if self.loading {
+            Some(SharedString::from("Loading history…"))
+        } else if let Some(error) = &self.error {
+            Some(error.clone())
+        } else if self.log.entries.is_empty() {
+            Some(SharedString::from("No commits"))
+        } else {
+            None
+        };
+        if let Some(placeholder) = placeholder {
+            return log_view.child(
+                v_flex().size_full().justify_center().child(
+                    h_flex()
+                        .justify_center()
+                        .child(Label::new(placeholder).color(Color::Muted)),
+                ),
+            );
+        }
+
+        let entry_count = self.log.entries.len();
+        log_view.child(
+            uniform_list(cx.view().clone(), "log-entries", entry_count, {
+                move |log_view, range, cx| range.map(|ix| log_view.render_entry(ix, cx)).collect()
+            })
+            .size_full()
+            .track_scroll(self.scroll_handle.clone()),
+        )
+    }
+}
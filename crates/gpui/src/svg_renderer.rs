@@ -1,5 +1,6 @@
-use crate::{AssetSource, DevicePixels, IsZero, Result, SharedString, Size};
-use anyhow::anyhow;
+use crate::{AssetSource, DevicePixels, ImageData, IsZero, Result, SharedString, Size};
+use anyhow::{anyhow, Context as _};
+use image::RgbaImage;
 use resvg::tiny_skia::Pixmap;
 use std::{hash::Hash, sync::Arc};
 
@@ -46,27 +47,47 @@ impl SvgRenderer {
     }
 
     pub fn render_pixmap(&self, bytes: &[u8], size: SvgSize) -> Result<Pixmap, usvg::Error> {
-        let tree = usvg::Tree::from_data(&bytes, &usvg::Options::default())?;
-
-        let size = match size {
-            SvgSize::Size(size) => size,
-            SvgSize::ScaleFactor(scale) => crate::size(
-                DevicePixels((tree.size().width() * scale) as i32),
-                DevicePixels((tree.size().height() * scale) as i32),
-            ),
-        };
-
-        // Render the SVG to a pixmap with the specified width and height.
-        let mut pixmap = resvg::tiny_skia::Pixmap::new(size.width.into(), size.height.into())
-            .ok_or(usvg::Error::InvalidSize)?;
+        render_pixmap(bytes, size)
+    }
+}
 
-        let transform = tree.view_box().to_transform(
-            resvg::tiny_skia::Size::from_wh(size.width.0 as f32, size.height.0 as f32)
-                .ok_or(usvg::Error::InvalidSize)?,
-        );
+impl ImageData {
+    /// Renders an SVG document into an image, at the given scale factor.
+    pub fn from_svg(bytes: &[u8], scale_factor: f32) -> Result<Self> {
+        let pixmap = render_pixmap(bytes, SvgSize::ScaleFactor(scale_factor))?;
+        let mut data = RgbaImage::from_raw(pixmap.width(), pixmap.height(), pixmap.take())
+            .context("invalid SVG image size")?;
 
-        resvg::render(&tree, transform, &mut pixmap.as_mut());
+        // Convert from RGBA to BGRA.
+        for pixel in data.chunks_exact_mut(4) {
+            pixel.swap(0, 2);
+        }
 
-        Ok(pixmap)
+        Ok(Self::new(data))
     }
 }
+
+fn render_pixmap(bytes: &[u8], size: SvgSize) -> Result<Pixmap, usvg::Error> {
+    let tree = usvg::Tree::from_data(&bytes, &usvg::Options::default())?;
+
+    let size = match size {
+        SvgSize::Size(size) => size,
+        SvgSize::ScaleFactor(scale) => crate::size(
+            DevicePixels((tree.size().width() * scale) as i32),
+            DevicePixels((tree.size().height() * scale) as i32),
+        ),
+    };
+
+    // Render the SVG to a pixmap with the specified width and height.
+    let mut pixmap = resvg::tiny_skia::Pixmap::new(size.width.into(), size.height.into())
+        .ok_or(usvg::Error::InvalidSize)?;
+
+    let transform = tree.view_box().to_transform(
+        resvg::tiny_skia::Size::from_wh(size.width.0 as f32, size.height.0 as f32)
+            .ok_or(usvg::Error::InvalidSize)?,
+    );
+
+    resvg::render(&tree, transform, &mut pixmap.as_mut());
+
+    Ok(pixmap)
+}
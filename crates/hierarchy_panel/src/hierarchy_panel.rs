@@ -0,0 +1,748 @@
+mod hierarchy_panel_settings;
+
+use std::sync::Arc;
+
+use anyhow::Context;
+use collections::HashMap;
+use db::kvp::KEY_VALUE_STORE;
+use editor::{scroll::Autoscroll, Editor};
+use gpui::{
+    actions, div, px, uniform_list, Action, AppContext, AsyncWindowContext, Div, ElementId,
+    EventEmitter, FocusHandle, FocusableView, InteractiveElement, IntoElement, KeyContext, Model,
+    MouseButton, ParentElement, Pixels, Render, Stateful, Styled, Subscription, Task,
+    UniformListScrollHandle, View, ViewContext, VisualContext, WeakView, WindowContext,
+};
+use hierarchy_panel_settings::{HierarchyPanelDockPosition, HierarchyPanelSettings};
+use language::{Buffer, Location, ToPoint};
+use menu::{Cancel, SelectFirst, SelectLast, SelectNext, SelectPrev};
+use project::{
+    lsp_command::{HierarchyCall, HierarchyItem},
+    Fs, Project,
+};
+use serde::{Deserialize, Serialize};
+use settings::{Settings, SettingsStore};
+use util::ResultExt;
+use workspace::{
+    dock::{DockPosition, Panel, PanelEvent},
+    ui::{
+        h_flex, v_flex, ActiveTheme, Color, FluentBuilder, IconName, Label, LabelCommon, LabelSize,
+        ListItem, Selectable, Spacing, StyledTypography,
+    },
+    Workspace,
+};
+
+actions!(
+    hierarchy_panel,
+    [
+        ShowIncomingCalls,
+        ShowOutgoingCalls,
+        ShowSupertypes,
+        ShowSubtypes,
+        ExpandSelectedEntry,
+        CollapseSelectedEntry,
+        Open,
+        OpenCallSites,
+        ToggleFocus,
+    ]
+);
+
+const HIERARCHY_PANEL_KEY: &str = "HierarchyPanel";
+
+/// The relation shown by the hierarchy panel.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HierarchyKind {
+    IncomingCalls,
+    OutgoingCalls,
+    Supertypes,
+    Subtypes,
+}
+
+impl HierarchyKind {
+    fn title(&self) -> &'static str {
+        match self {
+            HierarchyKind::IncomingCalls => "Incoming Calls",
+            HierarchyKind::OutgoingCalls => "Outgoing Calls",
+            HierarchyKind::Supertypes => "Supertypes",
+            HierarchyKind::Subtypes => "Subtypes",
+        }
+    }
+
+    fn is_call_hierarchy(&self) -> bool {
+        matches!(
+            self,
+            HierarchyKind::IncomingCalls | HierarchyKind::OutgoingCalls
+        )
+    }
+}
+
+pub struct HierarchyPanel {
+    fs: Arc<dyn Fs>,
+    width: Option<Pixels>,
+    workspace: WeakView<Workspace>,
+    project: Model<Project>,
+    active: bool,
+    scroll_handle: UniformListScrollHandle,
+    focus_handle: FocusHandle,
+    pending_serialization: Task<Option<()>>,
+    kind: HierarchyKind,
+    nodes: Vec<HierarchyNode>,
+    roots: Vec<usize>,
+    visible_entries: Vec<VisibleEntry>,
+    selected_node: Option<usize>,
+    loading_roots: bool,
+    roots_fetch_task: Task<()>,
+    children_fetch_tasks: HashMap<usize, Task<()>>,
+    _subscriptions: Vec<Subscription>,
+}
+
+/// An item of the hierarchy, with its children fetched lazily on the first expansion.
+struct HierarchyNode {
+    item: HierarchyItem,
+    /// Call expressions connecting this node to its parent, empty for roots and type hierarchies.
+    call_sites: Vec<Location>,
+    /// Indices of the child nodes, `None` until fetched from the language server.
+    children: Option<Vec<usize>>,
+    expanded: bool,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct VisibleEntry {
+    node_ix: usize,
+    depth: usize,
+}
+
+#[derive(Debug)]
+pub enum Event {
+    Focus,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SerializedHierarchyPanel {
+    width: Option<Pixels>,
+    active: Option<bool>,
+}
+
+pub fn init_settings(cx: &mut AppContext) {
+    HierarchyPanelSettings::register(cx);
+}
+
+pub fn init(cx: &mut AppContext) {
+    init_settings(cx);
+
+    cx.observe_new_views(|workspace: &mut Workspace, _| {
+        workspace
+            .register_action(|workspace, _: &ToggleFocus, cx| {
+                workspace.toggle_panel_focus::<HierarchyPanel>(cx);
+            })
+            .register_action(|workspace, _: &ShowIncomingCalls, cx| {
+                deploy(workspace, HierarchyKind::IncomingCalls, cx);
+            })
+            .register_action(|workspace, _: &ShowOutgoingCalls, cx| {
+                deploy(workspace, HierarchyKind::OutgoingCalls, cx);
+            })
+            .register_action(|workspace, _: &ShowSupertypes, cx| {
+                deploy(workspace, HierarchyKind::Supertypes, cx);
+            })
+            .register_action(|workspace, _: &ShowSubtypes, cx| {
+                deploy(workspace, HierarchyKind::Subtypes, cx);
+            });
+    })
+    .detach();
+}
+
+/// Shows the hierarchy of the symbol under the newest cursor of the active editor.
+fn deploy(workspace: &mut Workspace, kind: HierarchyKind, cx: &mut ViewContext<Workspace>) {
+    let Some(editor) = workspace.active_item_as::<Editor>(cx) else {
+        return;
+    };
+    let Some((buffer, position)) = editor.update(cx, |editor, cx| {
+        let head = editor.selections.newest_anchor().head();
+        editor.buffer().read(cx).text_anchor_for_position(head, cx)
+    }) else {
+        return;
+    };
+    if let Some(panel) = workspace.focus_panel::<HierarchyPanel>(cx) {
+        panel.update(cx, |panel, cx| panel.show(kind, buffer, position, cx));
+    }
+}
+
+impl HierarchyPanel {
+    pub async fn load(
+        workspace: WeakView<Workspace>,
+        mut cx: AsyncWindowContext,
+    ) -> anyhow::Result<View<Self>> {
+        let serialized_panel = cx
+            .background_executor()
+            .spawn(async move { KEY_VALUE_STORE.read_kvp(HIERARCHY_PANEL_KEY) })
+            .await
+            .context("loading hierarchy panel")
+            .log_err()
+            .flatten()
+            .map(|panel| serde_json::from_str::<SerializedHierarchyPanel>(&panel))
+            .transpose()
+            .log_err()
+            .flatten();
+
+        workspace.update(&mut cx, |workspace, cx| {
+            let panel = Self::new(workspace, cx);
+            if let Some(serialized_panel) = serialized_panel {
+                panel.update(cx, |panel, cx| {
+                    panel.width = serialized_panel.width.map(|px| px.round());
+                    panel.active = serialized_panel.active.unwrap_or(false);
+                    cx.notify();
+                });
+            }
+            panel
+        })
+    }
+
+    fn new(workspace: &mut Workspace, cx: &mut ViewContext<Workspace>) -> View<Self> {
+        let project = workspace.project().clone();
+        let workspace_handle = workspace.weak_handle();
+        let fs = workspace.app_state().fs.clone();
+        cx.new_view(|cx| {
+            let focus_handle = cx.focus_handle();
+            let focus_subscription = cx.on_focus(&focus_handle, Self::focus_in);
+
+            let mut hierarchy_panel_settings = *HierarchyPanelSettings::get_global(cx);
+            let settings_subscription = cx.observe_global::<SettingsStore>(move |_, cx| {
+                let new_settings = *HierarchyPanelSettings::get_global(cx);
+                if hierarchy_panel_settings != new_settings {
+                    hierarchy_panel_settings = new_settings;
+                    cx.notify();
+                }
+            });
+
+            Self {
+                fs,
+                width: None,
+                workspace: workspace_handle,
+                project,
+                active: false,
+                scroll_handle: UniformListScrollHandle::new(),
+                focus_handle,
+                pending_serialization: Task::ready(None),
+                kind: HierarchyKind::IncomingCalls,
+                nodes: Vec::new(),
+                roots: Vec::new(),
+                visible_entries: Vec::new(),
+                selected_node: None,
+                loading_roots: false,
+                roots_fetch_task: Task::ready(()),
+                children_fetch_tasks: HashMap::default(),
+                _subscriptions: vec![settings_subscription, focus_subscription],
+            }
+        })
+    }
+
+    /// Replaces the panel contents with the hierarchy of the symbol at the given position.
+    pub fn show(
+        &mut self,
+        kind: HierarchyKind,
+        buffer: Model<Buffer>,
+        position: language::Anchor,
+        cx: &mut ViewContext<Self>,
+    ) {
+        self.kind = kind;
+        self.nodes.clear();
+        self.roots.clear();
+        self.visible_entries.clear();
+        self.selected_node = None;
+        self.children_fetch_tasks.clear();
+        self.loading_roots = true;
+
+        let roots = self.project.update(cx, |project, cx| {
+            if kind.is_call_hierarchy() {
+                project.prepare_call_hierarchy(&buffer, position, cx)
+            } else {
+                project.prepare_type_hierarchy(&buffer, position, cx)
+            }
+        });
+        self.roots_fetch_task = cx.spawn(|hierarchy_panel, mut cx| async move {
+            let roots = roots.await.log_err().unwrap_or_default();
+            hierarchy_panel
+                .update(&mut cx, |hierarchy_panel, cx| {
+                    hierarchy_panel.loading_roots = false;
+                    for item in roots {
+                        let node_ix = hierarchy_panel.push_node(item, Vec::new());
+                        hierarchy_panel.roots.push(node_ix);
+                    }
+                    hierarchy_panel.selected_node = hierarchy_panel.roots.first().copied();
+                    for node_ix in hierarchy_panel.roots.clone() {
+                        hierarchy_panel.expand_node(node_ix, cx);
+                    }
+                    hierarchy_panel.update_visible_entries(cx);
+                })
+                .ok();
+        });
+        cx.notify();
+    }
+
+    fn push_node(&mut self, item: HierarchyItem, call_sites: Vec<Location>) -> usize {
+        self.nodes.push(HierarchyNode {
+            item,
+            call_sites,
+            children: None,
+            expanded: false,
+        });
+        self.nodes.len() - 1
+    }
+
+    fn expand_node(&mut self, node_ix: usize, cx: &mut ViewContext<Self>) {
+        let Some(node) = self.nodes.get_mut(node_ix) else {
+            return;
+        };
+        node.expanded = true;
+        if node.children.is_none() && !self.children_fetch_tasks.contains_key(&node_ix) {
+            let item = node.item.clone();
+            let children = self.fetch_children(&item, cx);
+            let task = cx.spawn(|hierarchy_panel, mut cx| async move {
+                let children = children.await.log_err().unwrap_or_default();
+                hierarchy_panel
+                    .update(&mut cx, |hierarchy_panel, cx| {
+                        hierarchy_panel.children_fetch_tasks.remove(&node_ix);
+                        let children = children
+                            .into_iter()
+                            .map(|call| hierarchy_panel.push_node(call.item, call.call_sites))
+                            .collect();
+                        if let Some(node) = hierarchy_panel.nodes.get_mut(node_ix) {
+                            node.children = Some(children);
+                        }
+                        hierarchy_panel.update_visible_entries(cx);
+                    })
+                    .ok();
+            });
+            self.children_fetch_tasks.insert(node_ix, task);
+        }
+        self.update_visible_entries(cx);
+    }
+
+    fn fetch_children(
+        &self,
+        item: &HierarchyItem,
+        cx: &mut ViewContext<Self>,
+    ) -> Task<anyhow::Result<Vec<HierarchyCall>>> {
+        let kind = self.kind;
+        self.project.update(cx, |project, cx| {
+            let types = match kind {
+                HierarchyKind::IncomingCalls => return project.incoming_calls(item, cx),
+                HierarchyKind::OutgoingCalls => return project.outgoing_calls(item, cx),
+                HierarchyKind::Supertypes => project.supertypes(item, cx),
+                HierarchyKind::Subtypes => project.subtypes(item, cx),
+            };
+            cx.spawn(|_, _| async move {
+                Ok(types
+                    .await?
+                    .into_iter()
+                    .map(|item| HierarchyCall {
+                        item,
+                        call_sites: Vec::new(),
+                    })
+                    .collect())
+            })
+        })
+    }
+
+    fn update_visible_entries(&mut self, cx: &mut ViewContext<Self>) {
+        self.visible_entries.clear();
+        let mut stack = self
+            .roots
+            .iter()
+            .rev()
+            .map(|&node_ix| VisibleEntry { node_ix, depth: 0 })
+            .collect::<Vec<_>>();
+        while let Some(entry) = stack.pop() {
+            self.visible_entries.push(entry);
+            let node = &self.nodes[entry.node_ix];
+            if let Some(children) = node.children.as_ref().filter(|_| node.expanded) {
+                stack.extend(children.iter().rev().map(|&node_ix| VisibleEntry {
+                    node_ix,
+                    depth: entry.depth + 1,
+                }));
+            }
+        }
+        cx.notify();
+    }
+
+    fn serialize(&mut self, cx: &mut ViewContext<Self>) {
+        let width = self.width;
+        let active = Some(self.active);
+        self.pending_serialization = cx.background_executor().spawn(
+            async move {
+                KEY_VALUE_STORE
+                    .write_kvp(
+                        HIERARCHY_PANEL_KEY.into(),
+                        serde_json::to_string(&SerializedHierarchyPanel { width, active })?,
+                    )
+                    .await?;
+                anyhow::Ok(())
+            }
+            .log_err(),
+        );
+    }
+
+    fn dispatch_context(&self, _: &ViewContext<Self>) -> KeyContext {
+        let mut dispatch_context = KeyContext::new_with_defaults();
+        dispatch_context.add("HierarchyPanel");
+        dispatch_context.add("menu");
+        dispatch_context
+    }
+
+    fn focus_in(&mut self, cx: &mut ViewContext<Self>) {
+        cx.emit(Event::Focus);
+    }
+
+    fn selected_entry_ix(&self) -> Option<usize> {
+        let selected_node = self.selected_node?;
+        self.visible_entries
+            .iter()
+            .position(|entry| entry.node_ix == selected_node)
+    }
+
+    fn select_entry_at(&mut self, ix: usize, cx: &mut ViewContext<Self>) {
+        if let Some(entry) = self.visible_entries.get(ix) {
+            self.selected_node = Some(entry.node_ix);
+            self.scroll_handle.scroll_to_item(ix);
+            cx.notify();
+        }
+    }
+
+    fn select_next(&mut self, _: &SelectNext, cx: &mut ViewContext<Self>) {
+        match self.selected_entry_ix() {
+            Some(ix) if ix + 1 < self.visible_entries.len() => self.select_entry_at(ix + 1, cx),
+            _ => self.select_first(&SelectFirst {}, cx),
+        }
+    }
+
+    fn select_prev(&mut self, _: &SelectPrev, cx: &mut ViewContext<Self>) {
+        match self.selected_entry_ix() {
+            Some(ix) if ix > 0 => self.select_entry_at(ix - 1, cx),
+            _ => self.select_last(&SelectLast {}, cx),
+        }
+    }
+
+    fn select_first(&mut self, _: &SelectFirst, cx: &mut ViewContext<Self>) {
+        self.select_entry_at(0, cx);
+    }
+
+    fn select_last(&mut self, _: &SelectLast, cx: &mut ViewContext<Self>) {
+        if let Some(last_ix) = self.visible_entries.len().checked_sub(1) {
+            self.select_entry_at(last_ix, cx);
+        }
+    }
+
+    fn expand_selected_entry(&mut self, _: &ExpandSelectedEntry, cx: &mut ViewContext<Self>) {
+        if let Some(node_ix) = self.selected_node {
+            self.expand_node(node_ix, cx);
+        }
+    }
+
+    fn collapse_selected_entry(&mut self, _: &CollapseSelectedEntry, cx: &mut ViewContext<Self>) {
+        let Some(node_ix) = self.selected_node else {
+            return;
+        };
+        if self.nodes[node_ix].expanded {
+            self.nodes[node_ix].expanded = false;
+            self.update_visible_entries(cx);
+        } else if let Some(parent_ix) = self.selected_entry_ix().and_then(|ix| {
+            let depth = self.visible_entries[ix].depth;
+            self.visible_entries[..ix]
+                .iter()
+                .rev()
+                .find(|entry| entry.depth < depth)
+                .copied()
+        }) {
+            self.selected_node = Some(parent_ix.node_ix);
+            cx.notify();
+        }
+    }
+
+    fn toggle_expanded(&mut self, node_ix: usize, cx: &mut ViewContext<Self>) {
+        if self.nodes[node_ix].expanded {
+            self.nodes[node_ix].expanded = false;
+            self.update_visible_entries(cx);
+        } else {
+            self.expand_node(node_ix, cx);
+        }
+    }
+
+    fn cancel(&mut self, _: &Cancel, cx: &mut ViewContext<Self>) {
+        if self.selected_node.take().is_some() {
+            cx.notify();
+        }
+    }
+
+    fn open(&mut self, _: &Open, cx: &mut ViewContext<Self>) {
+        if let Some(node_ix) = self.selected_node {
+            self.open_item(node_ix, cx);
+        }
+    }
+
+    fn open_call_sites(&mut self, _: &OpenCallSites, cx: &mut ViewContext<Self>) {
+        if let Some(node_ix) = self.selected_node {
+            self.open_node_call_sites(node_ix, cx);
+        }
+    }
+
+    /// Opens the item's declaration, selecting its name.
+    fn open_item(&mut self, node_ix: usize, cx: &mut ViewContext<Self>) {
+        let Some(node) = self.nodes.get(node_ix) else {
+            return;
+        };
+        let buffer = node.item.location.buffer.clone();
+        let buffer_snapshot = buffer.read(cx).snapshot();
+        let selection_range = node.item.selection_range.start.to_point(&buffer_snapshot)
+            ..node.item.selection_range.end.to_point(&buffer_snapshot);
+        self.workspace
+            .update(cx, |workspace, cx| {
+                let pane = workspace.active_pane().clone();
+                let editor = workspace.open_project_item::<Editor>(pane, buffer, cx);
+                editor.update(cx, |editor, cx| {
+                    editor.change_selections(Some(Autoscroll::center()), cx, |s| {
+                        s.select_ranges([selection_range]);
+                    });
+                });
+            })
+            .log_err();
+    }
+
+    /// Opens all call expressions connecting the node to its parent in a multibuffer.
+    fn open_node_call_sites(&mut self, node_ix: usize, cx: &mut ViewContext<Self>) {
+        let Some(node) = self.nodes.get(node_ix) else {
+            return;
+        };
+        if node.call_sites.is_empty() {
+            self.open_item(node_ix, cx);
+            return;
+        }
+        let call_sites = node.call_sites.clone();
+        let title = format!("Call sites of {}", node.item.name);
+        self.workspace
+            .update(cx, |workspace, cx| {
+                let replica_id = workspace.project().read(cx).replica_id();
+                Editor::open_locations_in_multibuffer(
+                    workspace, call_sites, replica_id, title, false, cx,
+                );
+            })
+            .log_err();
+    }
+
+    fn render_entry(&self, entry: VisibleEntry, cx: &mut ViewContext<Self>) -> Stateful<Div> {
+        let settings = HierarchyPanelSettings::get_global(cx);
+        let node = &self.nodes[entry.node_ix];
+        let is_selected = self.selected_node == Some(entry.node_ix);
+        let toggle_state = match &node.children {
+            Some(children) if children.is_empty() => None,
+            _ => Some(node.expanded),
+        };
+        let call_site_count = node.call_sites.len();
+        let node_ix = entry.node_ix;
+        let item_id = ElementId::from(node_ix);
+
+        div()
+            .text_ui(cx)
+            .id(item_id.clone())
+            .child(
+                ListItem::new(item_id)
+                    .indent_level(entry.depth)
+                    .indent_step_size(px(settings.indent_size))
+                    .selected(is_selected)
+                    .toggle(toggle_state)
+                    .on_toggle(cx.listener(move |hierarchy_panel, _, cx| {
+                        hierarchy_panel.toggle_expanded(node_ix, cx);
+                    }))
+                    .child(
+                        h_flex()
+                            .h_6()
+                            .gap_1()
+                            .child(Label::new(node.item.name.clone()))
+                            .when_some(node.item.detail.clone(), |this, detail| {
+                                this.child(
+                                    Label::new(detail)
+                                        .size(LabelSize::Small)
+                                        .color(Color::Muted),
+                                )
+                            })
+                            .when(call_site_count > 1, |this| {
+                                this.child(
+                                    Label::new(format!("({call_site_count})"))
+                                        .size(LabelSize::Small)
+                                        .color(Color::Muted),
+                                )
+                            }),
+                    )
+                    .on_click(
+                        cx.listener(move |hierarchy_panel, event: &gpui::ClickEvent, cx| {
+                            if event.down.button == MouseButton::Right || event.down.first_mouse {
+                                return;
+                            }
+                            hierarchy_panel.selected_node = Some(node_ix);
+                            if event.down.click_count > 1 {
+                                hierarchy_panel.open_node_call_sites(node_ix, cx);
+                            } else {
+                                hierarchy_panel.open_item(node_ix, cx);
+                            }
+                            cx.notify();
+                        }),
+                    ),
+            )
+            .border_1()
+            .border_r_2()
+            .rounded_none()
+            .hover(|style| {
+                if is_selected {
+                    style
+                } else {
+                    let hover_color = cx.theme().colors().ghost_element_hover;
+                    style.bg(hover_color).border_color(hover_color)
+                }
+            })
+            .when(
+                is_selected && self.focus_handle.contains_focused(cx),
+                |div| div.border_color(Color::Selected.color(cx)),
+            )
+    }
+}
+
+impl Panel for HierarchyPanel {
+    fn persistent_name() -> &'static str {
+        "Hierarchy Panel"
+    }
+
+    fn position(&self, cx: &WindowContext) -> DockPosition {
+        match HierarchyPanelSettings::get_global(cx).dock {
+            HierarchyPanelDockPosition::Left => DockPosition::Left,
+            HierarchyPanelDockPosition::Right => DockPosition::Right,
+        }
+    }
+
+    fn position_is_valid(&self, position: DockPosition) -> bool {
+        matches!(position, DockPosition::Left | DockPosition::Right)
+    }
+
+    fn set_position(&mut self, position: DockPosition, cx: &mut ViewContext<Self>) {
+        settings::update_settings_file::<HierarchyPanelSettings>(
+            self.fs.clone(),
+            cx,
+            move |settings| {
+                let dock = match position {
+                    DockPosition::Left | DockPosition::Bottom => HierarchyPanelDockPosition::Left,
+                    DockPosition::Right => HierarchyPanelDockPosition::Right,
+                };
+                settings.dock = Some(dock);
+            },
+        );
+    }
+
+    fn size(&self, cx: &WindowContext) -> Pixels {
+        self.width
+            .unwrap_or_else(|| HierarchyPanelSettings::get_global(cx).default_width)
+    }
+
+    fn set_size(&mut self, size: Option<Pixels>, cx: &mut ViewContext<Self>) {
+        self.width = size;
+        self.serialize(cx);
+        cx.notify();
+    }
+
+    fn icon(&self, cx: &WindowContext) -> Option<IconName> {
+        HierarchyPanelSettings::get_global(cx)
+            .button
+            .then(|| IconName::FileTree)
+    }
+
+    fn icon_tooltip(&self, _: &WindowContext) -> Option<&'static str> {
+        Some("Hierarchy Panel")
+    }
+
+    fn toggle_action(&self) -> Box<dyn Action> {
+        Box::new(ToggleFocus)
+    }
+
+    fn starts_open(&self, _: &WindowContext) -> bool {
+        self.active
+    }
+
+    fn set_active(&mut self, active: bool, cx: &mut ViewContext<Self>) {
+        self.active = active;
+        self.serialize(cx);
+    }
+}
+
+impl FocusableView for HierarchyPanel {
+    fn focus_handle(&self, _: &AppContext) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl EventEmitter<Event> for HierarchyPanel {}
+
+impl EventEmitter<PanelEvent> for HierarchyPanel {}
+
+impl Render for HierarchyPanel {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let hierarchy_panel = v_flex()
+            .id("hierarchy-panel")
+            .size_full()
+            .relative()
+            .key_context(self.dispatch_context(cx))
+            .on_action(cx.listener(Self::open))
+            .on_action(cx.listener(Self::open_call_sites))
+            .on_action(cx.listener(Self::cancel))
+            .on_action(cx.listener(Self::select_next))
+            .on_action(cx.listener(Self::select_prev))
+            .on_action(cx.listener(Self::select_first))
+            .on_action(cx.listener(Self::select_last))
+            .on_action(cx.listener(Self::expand_selected_entry))
+            .on_action(cx.listener(Self::collapse_selected_entry))
+            .track_focus(&self.focus_handle);
+
+        if self.visible_entries.is_empty() {
+            let header = if self.loading_roots {
+                "Loading hierarchy"
+            } else {
+                "No hierarchy available"
+            };
+            hierarchy_panel.child(
+                v_flex()
+                    .justify_center()
+                    .size_full()
+                    .child(h_flex().justify_center().child(Label::new(header)))
+                    .child(
+                        h_flex().pt(Spacing::Small.rems(cx)).justify_center().child(
+                            Label::new(
+                                "Show the call or type hierarchy of a symbol from the editor",
+                            )
+                            .color(Color::Muted),
+                        ),
+                    ),
+            )
+        } else {
+            hierarchy_panel
+                .child(
+                    h_flex().px_2().py_1().child(
+                        Label::new(self.kind.title())
+                            .size(LabelSize::Small)
+                            .color(Color::Muted),
+                    ),
+                )
+                .child({
+                    let items_len = self.visible_entries.len();
+                    uniform_list(cx.view().clone(), "entries", items_len, {
+                        move |hierarchy_panel, range, cx| {
+                            hierarchy_panel
+                                .visible_entries
+                                .get(range)
+                                .map(|entries| entries.to_vec())
+                                .unwrap_or_default()
+                                .into_iter()
+                                .map(|entry| hierarchy_panel.render_entry(entry, cx))
+                                .collect()
+                        }
+                    })
+                    .size_full()
+                    .track_scroll(self.scroll_handle.clone())
+                })
+        }
+    }
+}
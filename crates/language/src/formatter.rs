@@ -0,0 +1,30 @@
+use std::{ops::Range, path::Path, sync::Arc};
+
+use anyhow::Result;
+use gpui::{AppContext, Task};
+
+/// The options to format a buffer with.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FormattingOptions {
+    /// The number of spaces that a tab is worth.
+    pub tab_size: u32,
+    /// Whether to indent with tabs rather than spaces.
+    pub hard_tabs: bool,
+}
+
+/// A formatter for the buffers of a language that is not a language server,
+/// like one provided by an extension.
+pub trait BufferFormatter: Send + Sync {
+    /// The name of the formatter, unique among the formatters of a language.
+    fn name(&self) -> Arc<str>;
+
+    /// Returns the edits that format the given text of the buffer at the given path,
+    /// as byte ranges of the text and the text to replace them with.
+    fn format(
+        &self,
+        text: String,
+        path: &Path,
+        options: FormattingOptions,
+        cx: &AppContext,
+    ) -> Task<Result<Vec<(Range<usize>, String)>>>;
+}
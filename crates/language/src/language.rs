@@ -8,9 +8,11 @@
 //! Notably we do *not* assign a single language to a single file; in real world a single file can consist of multiple programming languages - HTML is a good example of that - and `language` crate tends to reflect that status quo in its API.
 mod buffer;
 mod diagnostic_set;
+mod formatter;
 mod highlight_map;
 mod language_registry;
 pub mod language_settings;
+mod linter;
 mod outline;
 pub mod proto;
 mod syntax_map;
@@ -66,10 +68,12 @@ use util::serde::default_true;
 pub use buffer::Operation;
 pub use buffer::*;
 pub use diagnostic_set::DiagnosticEntry;
+pub use formatter::{BufferFormatter, FormattingOptions};
 pub use language_registry::{
     LanguageNotFound, LanguageQueries, LanguageRegistry, LanguageServerBinaryStatus,
     PendingLanguageServer, QUERY_FILENAME_PREFIXES,
 };
+pub use linter::{BufferLinter, LintDiagnostic};
 pub use lsp::LanguageServerId;
 pub use outline::{render_item, Outline, OutlineItem};
 pub use syntax_map::{OwnedSyntaxLayer, SyntaxLayer};
@@ -3,8 +3,8 @@ use crate::{
         all_language_settings, AllLanguageSettingsContent, LanguageSettingsContent,
     },
     task_context::ContextProvider,
-    with_parser, CachedLspAdapter, File, Language, LanguageConfig, LanguageId, LanguageMatcher,
-    LanguageServerName, LspAdapter, LspAdapterDelegate, PLAIN_TEXT,
+    with_parser, BufferFormatter, BufferLinter, CachedLspAdapter, File, Language, LanguageConfig,
+    LanguageId, LanguageMatcher, LanguageServerName, LspAdapter, LspAdapterDelegate, PLAIN_TEXT,
 };
 use anyhow::{anyhow, Context as _, Result};
 use collections::{hash_map, HashMap};
@@ -49,6 +49,10 @@ struct LanguageRegistryState {
     lsp_adapters: HashMap<Arc<str>, Vec<Arc<CachedLspAdapter>>>,
     available_lsp_adapters:
         HashMap<LanguageServerName, Arc<dyn Fn() -> Arc<CachedLspAdapter> + 'static + Send + Sync>>,
+    /// The formatters of each language, along with the id of the extension that registered them.
+    formatters: HashMap<Arc<str>, Vec<(Arc<str>, Arc<dyn BufferFormatter>)>>,
+    /// The linters of each language, along with the id of the extension that registered them.
+    linters: HashMap<Arc<str>, Vec<(Arc<str>, Arc<dyn BufferLinter>)>>,
     loading_languages: HashMap<LanguageId, Vec<oneshot::Sender<Result<Arc<Language>>>>>,
     subscription: (watch::Sender<()>, watch::Receiver<()>),
     theme: Option<Arc<Theme>>,
@@ -159,6 +163,8 @@ impl LanguageRegistry {
                 loading_languages: Default::default(),
                 lsp_adapters: Default::default(),
                 available_lsp_adapters: HashMap::default(),
+                formatters: HashMap::default(),
+                linters: HashMap::default(),
                 subscription: watch::channel(),
                 theme: Default::default(),
                 version: 0,
@@ -273,6 +279,52 @@ impl LanguageRegistry {
             .push(CachedLspAdapter::new(adapter, false));
     }
 
+    /// Registers a [`BufferFormatter`] of the given extension for the language with the given name.
+    pub fn register_formatter(
+        &self,
+        language_name: Arc<str>,
+        extension_id: Arc<str>,
+        formatter: Arc<dyn BufferFormatter>,
+    ) {
+        self.state
+            .write()
+            .formatters
+            .entry(language_name)
+            .or_default()
+            .push((extension_id, formatter));
+    }
+
+    pub fn remove_formatter(&self, language_name: &str, extension_id: &str, name: &str) {
+        if let Some(formatters) = self.state.write().formatters.get_mut(language_name) {
+            formatters.retain(|(formatter_extension_id, formatter)| {
+                formatter_extension_id.as_ref() != extension_id || formatter.name().as_ref() != name
+            });
+        }
+    }
+
+    /// Registers a [`BufferLinter`] of the given extension for the language with the given name.
+    pub fn register_linter(
+        &self,
+        language_name: Arc<str>,
+        extension_id: Arc<str>,
+        linter: Arc<dyn BufferLinter>,
+    ) {
+        self.state
+            .write()
+            .linters
+            .entry(language_name)
+            .or_default()
+            .push((extension_id, linter));
+    }
+
+    pub fn remove_linter(&self, language_name: &str, extension_id: &str, name: &str) {
+        if let Some(linters) = self.state.write().linters.get_mut(language_name) {
+            linters.retain(|(linter_extension_id, linter)| {
+                linter_extension_id.as_ref() != extension_id || linter.name().as_ref() != name
+            });
+        }
+    }
+
     #[cfg(any(feature = "test-support", test))]
     pub fn register_fake_lsp_adapter(
         &self,
@@ -709,6 +761,33 @@ impl LanguageRegistry {
         self.state.read().languages.iter().cloned().collect()
     }
 
+    /// Returns the [`BufferFormatter`]s registered for the given language,
+    /// in the order they were registered.
+    pub fn formatters(&self, language: &Arc<Language>) -> Vec<Arc<dyn BufferFormatter>> {
+        self.state
+            .read()
+            .formatters
+            .get(&language.config.name)
+            .map(|formatters| {
+                formatters
+                    .iter()
+                    .map(|(_, formatter)| formatter.clone())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Returns the [`BufferLinter`]s registered for the given language, along with the
+    /// IDs of the extensions that registered them.
+    pub fn linters(&self, language: &Arc<Language>) -> Vec<(Arc<str>, Arc<dyn BufferLinter>)> {
+        self.state
+            .read()
+            .linters
+            .get(&language.config.name)
+            .cloned()
+            .unwrap_or_default()
+    }
+
     pub fn lsp_adapters(&self, language: &Arc<Language>) -> Vec<Arc<CachedLspAdapter>> {
         self.state
             .read()
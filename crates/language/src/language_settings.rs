@@ -120,6 +120,10 @@ pub struct LanguageSettings {
     pub code_actions_on_format: HashMap<String, bool>,
     /// Whether to perform linked edits
     pub linked_edits: bool,
+    /// Whether to color code using semantic tokens from the language server.
+    pub semantic_tokens: bool,
+    /// Whether to show code lenses from the language server above their lines.
+    pub code_lens: bool,
     /// Task configuration for this language.
     pub tasks: LanguageTaskConfig,
 }
@@ -342,6 +346,16 @@ pub struct LanguageSettingsContent {
     ///
     /// Default: true
     pub linked_edits: Option<bool>,
+    /// Whether to color code using semantic tokens provided by the language server,
+    /// on top of the tree-sitter syntax highlighting.
+    ///
+    /// Default: false
+    pub semantic_tokens: Option<bool>,
+    /// Whether to show the code lenses provided by the language server
+    /// (e.g. reference counts or "Run test") above the lines they refer to.
+    ///
+    /// Default: false
+    pub code_lens: Option<bool>,
     /// Task configuration for this language.
     ///
     /// Default: {}
@@ -836,6 +850,8 @@ fn merge_settings(settings: &mut LanguageSettings, src: &LanguageSettingsContent
         src.code_actions_on_format.clone(),
     );
     merge(&mut settings.linked_edits, src.linked_edits);
+    merge(&mut settings.semantic_tokens, src.semantic_tokens);
+    merge(&mut settings.code_lens, src.code_lens);
     merge(&mut settings.tasks, src.tasks.clone());
 
     merge(
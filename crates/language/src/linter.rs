@@ -0,0 +1,26 @@
+use std::{ops::Range, path::Path, sync::Arc};
+
+use anyhow::Result;
+use gpui::{AppContext, Task};
+use lsp::DiagnosticSeverity;
+
+/// A diagnostic that a [`BufferLinter`] reports.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LintDiagnostic {
+    /// The byte range of the linted text that the diagnostic applies to.
+    pub range: Range<usize>,
+    pub severity: DiagnosticSeverity,
+    pub message: String,
+    pub code: Option<String>,
+}
+
+/// A linter for the buffers of a language that is not a language server,
+/// like one provided by an extension.
+pub trait BufferLinter: Send + Sync {
+    /// The name of the linter, which is the source of its diagnostics.
+    fn name(&self) -> Arc<str>;
+
+    /// Returns the diagnostics for the given text of the buffer at the given path.
+    fn lint(&self, text: String, path: &Path, cx: &AppContext)
+        -> Task<Result<Vec<LintDiagnostic>>>;
+}
@@ -0,0 +1,899 @@
+use std::{mem, ops::Range, path::Path, sync::Arc};
+
+use anyhow::{anyhow, Context as _, Result};
+use async_trait::async_trait;
+use client::proto::{self, PeerId};
+use gpui::{AppContext, AsyncAppContext, Model};
+use language::{
+    point_from_lsp, point_to_lsp,
+    proto::{deserialize_anchor, deserialize_version, serialize_anchor, serialize_version},
+    Anchor, Bias, Buffer, PointUtf16, ToPointUtf16,
+};
+use lsp::{
+    CallHierarchyServerCapability, LanguageServer, LanguageServerId, ServerCapabilities,
+    TypeHierarchyServerCapability,
+};
+use text::BufferId;
+
+use super::{language_server_for_buffer, LspCommand};
+use crate::{Location, Project};
+
+/// An entry of a call or type hierarchy, as returned by the language server.
+#[derive(Clone, Debug)]
+pub struct HierarchyItem {
+    pub name: String,
+    pub kind: lsp::SymbolKind,
+    pub detail: Option<String>,
+    /// The full range of the symbol, e.g. the whole function body.
+    pub location: Location,
+    /// The range to select when navigating to the symbol, e.g. the function name.
+    pub selection_range: Range<Anchor>,
+    pub language_server_id: LanguageServerId,
+    /// The original item, sent back to the server when resolving calls, supertypes or subtypes.
+    pub(crate) lsp_item: serde_json::Value,
+}
+
+/// An incoming or outgoing call of a call hierarchy item.
+#[derive(Clone, Debug)]
+pub struct HierarchyCall {
+    /// The caller for incoming calls, the callee for outgoing calls.
+    pub item: HierarchyItem,
+    /// Ranges of the call expressions: inside the caller for incoming calls,
+    /// inside the item the calls were requested for for outgoing calls.
+    pub call_sites: Vec<Location>,
+}
+
+pub(crate) struct PrepareCallHierarchy {
+    pub position: PointUtf16,
+}
+
+pub(crate) struct CallHierarchyIncomingCalls {
+    pub item: lsp::CallHierarchyItem,
+}
+
+pub(crate) struct CallHierarchyOutgoingCalls {
+    pub item: lsp::CallHierarchyItem,
+}
+
+pub(crate) struct PrepareTypeHierarchy {
+    pub position: PointUtf16,
+}
+
+pub(crate) struct TypeHierarchySupertypes {
+    pub item: lsp::TypeHierarchyItem,
+}
+
+pub(crate) struct TypeHierarchySubtypes {
+    pub item: lsp::TypeHierarchyItem,
+}
+
+/// Fields shared by the LSP call and type hierarchy items.
+trait LspHierarchyItem: serde::Serialize {
+    fn name(&self) -> &str;
+    fn kind(&self) -> lsp::SymbolKind;
+    fn detail(&self) -> Option<&str>;
+    fn uri(&self) -> &lsp::Url;
+    fn range(&self) -> lsp::Range;
+    fn selection_range(&self) -> lsp::Range;
+}
+
+impl LspHierarchyItem for lsp::CallHierarchyItem {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn kind(&self) -> lsp::SymbolKind {
+        self.kind
+    }
+
+    fn detail(&self) -> Option<&str> {
+        self.detail.as_deref()
+    }
+
+    fn uri(&self) -> &lsp::Url {
+        &self.uri
+    }
+
+    fn range(&self) -> lsp::Range {
+        self.range
+    }
+
+    fn selection_range(&self) -> lsp::Range {
+        self.selection_range
+    }
+}
+
+impl LspHierarchyItem for lsp::TypeHierarchyItem {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn kind(&self) -> lsp::SymbolKind {
+        self.kind
+    }
+
+    fn detail(&self) -> Option<&str> {
+        self.detail.as_deref()
+    }
+
+    fn uri(&self) -> &lsp::Url {
+        &self.uri
+    }
+
+    fn range(&self) -> lsp::Range {
+        self.range
+    }
+
+    fn selection_range(&self) -> lsp::Range {
+        self.selection_range
+    }
+}
+
+fn supports_call_hierarchy(capabilities: &ServerCapabilities) -> bool {
+    match &capabilities.call_hierarchy_provider {
+        Some(CallHierarchyServerCapability::Simple(supported)) => *supported,
+        Some(CallHierarchyServerCapability::Options(_)) => true,
+        None => false,
+    }
+}
+
+fn supports_type_hierarchy(capabilities: &ServerCapabilities) -> bool {
+    match &capabilities.type_hierarchy_provider {
+        Some(TypeHierarchyServerCapability::Simple(supported)) => *supported,
+        Some(_) => true,
+        None => false,
+    }
+}
+
+fn range_from_lsp_in_buffer(buffer: &Buffer, range: lsp::Range) -> Range<Anchor> {
+    let start = buffer.clip_point_utf16(point_from_lsp(range.start), Bias::Left);
+    let end = buffer.clip_point_utf16(point_from_lsp(range.end), Bias::Left);
+    buffer.anchor_after(start)..buffer.anchor_before(end)
+}
+
+async fn hierarchy_item_from_lsp(
+    lsp_item: &impl LspHierarchyItem,
+    project: &Model<Project>,
+    buffer: &Model<Buffer>,
+    server_id: LanguageServerId,
+    cx: &mut AsyncAppContext,
+) -> Result<HierarchyItem> {
+    let (lsp_adapter, language_server) =
+        language_server_for_buffer(project, buffer, server_id, cx)?;
+    let item_buffer = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer_via_lsp(
+                lsp_item.uri().clone(),
+                language_server.server_id(),
+                lsp_adapter.name.clone(),
+                cx,
+            )
+        })?
+        .await?;
+    let (range, selection_range) = item_buffer.update(cx, |item_buffer, _| {
+        (
+            range_from_lsp_in_buffer(item_buffer, lsp_item.range()),
+            range_from_lsp_in_buffer(item_buffer, lsp_item.selection_range()),
+        )
+    })?;
+    Ok(HierarchyItem {
+        name: lsp_item.name().to_string(),
+        kind: lsp_item.kind(),
+        detail: lsp_item.detail().map(ToString::to_string),
+        location: Location {
+            buffer: item_buffer,
+            range,
+        },
+        selection_range,
+        language_server_id: language_server.server_id(),
+        lsp_item: serde_json::to_value(lsp_item)?,
+    })
+}
+
+async fn hierarchy_items_from_lsp(
+    lsp_items: Option<Vec<impl LspHierarchyItem>>,
+    project: Model<Project>,
+    buffer: Model<Buffer>,
+    server_id: LanguageServerId,
+    mut cx: AsyncAppContext,
+) -> Result<Vec<HierarchyItem>> {
+    let mut items = Vec::new();
+    for lsp_item in lsp_items.unwrap_or_default() {
+        items
+            .push(hierarchy_item_from_lsp(&lsp_item, &project, &buffer, server_id, &mut cx).await?);
+    }
+    Ok(items)
+}
+
+fn location_to_proto(
+    location: Location,
+    project: &mut Project,
+    peer_id: PeerId,
+    cx: &mut AppContext,
+) -> proto::Location {
+    let buffer_id = project.create_buffer_for_peer(&location.buffer, peer_id, cx);
+    proto::Location {
+        start: Some(serialize_anchor(&location.range.start)),
+        end: Some(serialize_anchor(&location.range.end)),
+        buffer_id: buffer_id.into(),
+    }
+}
+
+async fn location_from_proto(
+    location: proto::Location,
+    project: &Model<Project>,
+    cx: &mut AsyncAppContext,
+) -> Result<Location> {
+    let buffer_id = BufferId::new(location.buffer_id)?;
+    let buffer = project
+        .update(cx, |project, cx| {
+            project.wait_for_remote_buffer(buffer_id, cx)
+        })?
+        .await?;
+    let start = location
+        .start
+        .and_then(deserialize_anchor)
+        .ok_or_else(|| anyhow!("missing location start"))?;
+    let end = location
+        .end
+        .and_then(deserialize_anchor)
+        .ok_or_else(|| anyhow!("missing location end"))?;
+    buffer
+        .update(cx, |buffer, _| buffer.wait_for_anchors([start, end]))?
+        .await?;
+    Ok(Location {
+        buffer,
+        range: start..end,
+    })
+}
+
+fn hierarchy_item_to_proto(
+    item: HierarchyItem,
+    project: &mut Project,
+    peer_id: PeerId,
+    cx: &mut AppContext,
+) -> proto::HierarchyItem {
+    proto::HierarchyItem {
+        name: item.name,
+        kind: unsafe { mem::transmute::<lsp::SymbolKind, i32>(item.kind) },
+        detail: item.detail,
+        location: Some(location_to_proto(item.location, project, peer_id, cx)),
+        selection_start: Some(serialize_anchor(&item.selection_range.start)),
+        selection_end: Some(serialize_anchor(&item.selection_range.end)),
+        language_server_id: item.language_server_id.0 as u64,
+        lsp_item: serde_json::to_vec(&item.lsp_item).unwrap(),
+    }
+}
+
+async fn hierarchy_item_from_proto(
+    item: proto::HierarchyItem,
+    project: &Model<Project>,
+    cx: &mut AsyncAppContext,
+) -> Result<HierarchyItem> {
+    let location = location_from_proto(
+        item.location.context("missing hierarchy item location")?,
+        project,
+        cx,
+    )
+    .await?;
+    let selection_start = item
+        .selection_start
+        .and_then(deserialize_anchor)
+        .ok_or_else(|| anyhow!("missing selection start"))?;
+    let selection_end = item
+        .selection_end
+        .and_then(deserialize_anchor)
+        .ok_or_else(|| anyhow!("missing selection end"))?;
+    Ok(HierarchyItem {
+        name: item.name,
+        kind: unsafe { mem::transmute::<i32, lsp::SymbolKind>(item.kind) },
+        detail: item.detail,
+        location,
+        selection_range: selection_start..selection_end,
+        language_server_id: LanguageServerId(item.language_server_id as usize),
+        lsp_item: serde_json::from_slice(&item.lsp_item)?,
+    })
+}
+
+fn hierarchy_items_to_proto(
+    items: Vec<HierarchyItem>,
+    project: &mut Project,
+    peer_id: PeerId,
+    cx: &mut AppContext,
+) -> proto::HierarchyItemsResponse {
+    proto::HierarchyItemsResponse {
+        items: items
+            .into_iter()
+            .map(|item| hierarchy_item_to_proto(item, project, peer_id, cx))
+            .collect(),
+    }
+}
+
+async fn hierarchy_items_from_proto(
+    message: proto::HierarchyItemsResponse,
+    project: Model<Project>,
+    mut cx: AsyncAppContext,
+) -> Result<Vec<HierarchyItem>> {
+    let mut items = Vec::new();
+    for item in message.items {
+        items.push(hierarchy_item_from_proto(item, &project, &mut cx).await?);
+    }
+    Ok(items)
+}
+
+fn hierarchy_calls_to_proto(
+    calls: Vec<HierarchyCall>,
+    project: &mut Project,
+    peer_id: PeerId,
+    cx: &mut AppContext,
+) -> proto::HierarchyCallsResponse {
+    proto::HierarchyCallsResponse {
+        calls: calls
+            .into_iter()
+            .map(|call| proto::HierarchyCall {
+                item: Some(hierarchy_item_to_proto(call.item, project, peer_id, cx)),
+                call_sites: call
+                    .call_sites
+                    .into_iter()
+                    .map(|location| location_to_proto(location, project, peer_id, cx))
+                    .collect(),
+            })
+            .collect(),
+    }
+}
+
+async fn hierarchy_calls_from_proto(
+    message: proto::HierarchyCallsResponse,
+    project: Model<Project>,
+    mut cx: AsyncAppContext,
+) -> Result<Vec<HierarchyCall>> {
+    let mut calls = Vec::new();
+    for call in message.calls {
+        let item = hierarchy_item_from_proto(
+            call.item.context("missing hierarchy call item")?,
+            &project,
+            &mut cx,
+        )
+        .await?;
+        let mut call_sites = Vec::new();
+        for location in call.call_sites {
+            call_sites.push(location_from_proto(location, &project, &mut cx).await?);
+        }
+        calls.push(HierarchyCall { item, call_sites });
+    }
+    Ok(calls)
+}
+
+async fn position_from_proto(
+    position: Option<proto::Anchor>,
+    version: &[proto::VectorClockEntry],
+    buffer: &Model<Buffer>,
+    cx: &mut AsyncAppContext,
+) -> Result<PointUtf16> {
+    let position = position
+        .and_then(deserialize_anchor)
+        .ok_or_else(|| anyhow!("invalid position"))?;
+    buffer
+        .update(cx, |buffer, _| {
+            buffer.wait_for_version(deserialize_version(version))
+        })?
+        .await?;
+    buffer.update(cx, |buffer, _| position.to_point_utf16(buffer))
+}
+
+fn text_document_position(path: &Path, position: PointUtf16) -> lsp::TextDocumentPositionParams {
+    lsp::TextDocumentPositionParams {
+        text_document: lsp::TextDocumentIdentifier {
+            uri: lsp::Url::from_file_path(path).unwrap(),
+        },
+        position: point_to_lsp(position),
+    }
+}
+
+#[async_trait(?Send)]
+impl LspCommand for PrepareCallHierarchy {
+    type Response = Vec<HierarchyItem>;
+    type LspRequest = lsp::request::CallHierarchyPrepare;
+    type ProtoRequest = proto::PrepareCallHierarchy;
+
+    fn check_capabilities(&self, capabilities: &ServerCapabilities) -> bool {
+        supports_call_hierarchy(capabilities)
+    }
+
+    fn to_lsp(
+        &self,
+        path: &Path,
+        _: &Buffer,
+        _: &Arc<LanguageServer>,
+        _: &AppContext,
+    ) -> lsp::CallHierarchyPrepareParams {
+        lsp::CallHierarchyPrepareParams {
+            text_document_position_params: text_document_position(path, self.position),
+            work_done_progress_params: Default::default(),
+        }
+    }
+
+    async fn response_from_lsp(
+        self,
+        message: Option<Vec<lsp::CallHierarchyItem>>,
+        project: Model<Project>,
+        buffer: Model<Buffer>,
+        server_id: LanguageServerId,
+        cx: AsyncAppContext,
+    ) -> Result<Vec<HierarchyItem>> {
+        hierarchy_items_from_lsp(message, project, buffer, server_id, cx).await
+    }
+
+    fn to_proto(&self, project_id: u64, buffer: &Buffer) -> proto::PrepareCallHierarchy {
+        proto::PrepareCallHierarchy {
+            project_id,
+            buffer_id: buffer.remote_id().into(),
+            position: Some(serialize_anchor(&buffer.anchor_before(self.position))),
+            version: serialize_version(&buffer.version()),
+        }
+    }
+
+    async fn from_proto(
+        message: proto::PrepareCallHierarchy,
+        _: Model<Project>,
+        buffer: Model<Buffer>,
+        mut cx: AsyncAppContext,
+    ) -> Result<Self> {
+        Ok(Self {
+            position: position_from_proto(message.position, &message.version, &buffer, &mut cx)
+                .await?,
+        })
+    }
+
+    fn response_to_proto(
+        response: Vec<HierarchyItem>,
+        project: &mut Project,
+        peer_id: PeerId,
+        _: &clock::Global,
+        cx: &mut AppContext,
+    ) -> proto::HierarchyItemsResponse {
+        hierarchy_items_to_proto(response, project, peer_id, cx)
+    }
+
+    async fn response_from_proto(
+        self,
+        message: proto::HierarchyItemsResponse,
+        project: Model<Project>,
+        _: Model<Buffer>,
+        cx: AsyncAppContext,
+    ) -> Result<Vec<HierarchyItem>> {
+        hierarchy_items_from_proto(message, project, cx).await
+    }
+
+    fn buffer_id_from_proto(message: &proto::PrepareCallHierarchy) -> Result<BufferId> {
+        BufferId::new(message.buffer_id)
+    }
+}
+
+#[async_trait(?Send)]
+impl LspCommand for CallHierarchyIncomingCalls {
+    type Response = Vec<HierarchyCall>;
+    type LspRequest = lsp::request::CallHierarchyIncomingCalls;
+    type ProtoRequest = proto::CallHierarchyIncomingCalls;
+
+    fn check_capabilities(&self, capabilities: &ServerCapabilities) -> bool {
+        supports_call_hierarchy(capabilities)
+    }
+
+    fn to_lsp(
+        &self,
+        _: &Path,
+        _: &Buffer,
+        _: &Arc<LanguageServer>,
+        _: &AppContext,
+    ) -> lsp::CallHierarchyIncomingCallsParams {
+        lsp::CallHierarchyIncomingCallsParams {
+            item: self.item.clone(),
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        }
+    }
+
+    async fn response_from_lsp(
+        self,
+        message: Option<Vec<lsp::CallHierarchyIncomingCall>>,
+        project: Model<Project>,
+        buffer: Model<Buffer>,
+        server_id: LanguageServerId,
+        mut cx: AsyncAppContext,
+    ) -> Result<Vec<HierarchyCall>> {
+        let mut calls = Vec::new();
+        for call in message.unwrap_or_default() {
+            let item =
+                hierarchy_item_from_lsp(&call.from, &project, &buffer, server_id, &mut cx).await?;
+            let caller_buffer = item.location.buffer.clone();
+            let call_sites = caller_buffer.update(&mut cx, |caller, _| {
+                call.from_ranges
+                    .into_iter()
+                    .map(|range| Location {
+                        buffer: caller_buffer.clone(),
+                        range: range_from_lsp_in_buffer(caller, range),
+                    })
+                    .collect()
+            })?;
+            calls.push(HierarchyCall { item, call_sites });
+        }
+        Ok(calls)
+    }
+
+    fn to_proto(&self, project_id: u64, buffer: &Buffer) -> proto::CallHierarchyIncomingCalls {
+        proto::CallHierarchyIncomingCalls {
+            project_id,
+            buffer_id: buffer.remote_id().into(),
+            lsp_item: serde_json::to_vec(&self.item).unwrap(),
+        }
+    }
+
+    async fn from_proto(
+        message: proto::CallHierarchyIncomingCalls,
+        _: Model<Project>,
+        _: Model<Buffer>,
+        _: AsyncAppContext,
+    ) -> Result<Self> {
+        Ok(Self {
+            item: serde_json::from_slice(&message.lsp_item)?,
+        })
+    }
+
+    fn response_to_proto(
+        response: Vec<HierarchyCall>,
+        project: &mut Project,
+        peer_id: PeerId,
+        _: &clock::Global,
+        cx: &mut AppContext,
+    ) -> proto::HierarchyCallsResponse {
+        hierarchy_calls_to_proto(response, project, peer_id, cx)
+    }
+
+    async fn response_from_proto(
+        self,
+        message: proto::HierarchyCallsResponse,
+        project: Model<Project>,
+        _: Model<Buffer>,
+        cx: AsyncAppContext,
+    ) -> Result<Vec<HierarchyCall>> {
+        hierarchy_calls_from_proto(message, project, cx).await
+    }
+
+    fn buffer_id_from_proto(message: &proto::CallHierarchyIncomingCalls) -> Result<BufferId> {
+        BufferId::new(message.buffer_id)
+    }
+}
+
+#[async_trait(?Send)]
+impl LspCommand for CallHierarchyOutgoingCalls {
+    type Response = Vec<HierarchyCall>;
+    type LspRequest = lsp::request::CallHierarchyOutgoingCalls;
+    type ProtoRequest = proto::CallHierarchyOutgoingCalls;
+
+    fn check_capabilities(&self, capabilities: &ServerCapabilities) -> bool {
+        supports_call_hierarchy(capabilities)
+    }
+
+    fn to_lsp(
+        &self,
+        _: &Path,
+        _: &Buffer,
+        _: &Arc<LanguageServer>,
+        _: &AppContext,
+    ) -> lsp::CallHierarchyOutgoingCallsParams {
+        lsp::CallHierarchyOutgoingCallsParams {
+            item: self.item.clone(),
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        }
+    }
+
+    async fn response_from_lsp(
+        self,
+        message: Option<Vec<lsp::CallHierarchyOutgoingCall>>,
+        project: Model<Project>,
+        buffer: Model<Buffer>,
+        server_id: LanguageServerId,
+        mut cx: AsyncAppContext,
+    ) -> Result<Vec<HierarchyCall>> {
+        let mut calls = Vec::new();
+        for call in message.unwrap_or_default() {
+            let item =
+                hierarchy_item_from_lsp(&call.to, &project, &buffer, server_id, &mut cx).await?;
+            // The call sites of outgoing calls are located in the item the calls were requested for.
+            let call_sites = buffer.update(&mut cx, |caller, _| {
+                call.from_ranges
+                    .into_iter()
+                    .map(|range| Location {
+                        buffer: buffer.clone(),
+                        range: range_from_lsp_in_buffer(caller, range),
+                    })
+                    .collect()
+            })?;
+            calls.push(HierarchyCall { item, call_sites });
+        }
+        Ok(calls)
+    }
+
+    fn to_proto(&self, project_id: u64, buffer: &Buffer) -> proto::CallHierarchyOutgoingCalls {
+        proto::CallHierarchyOutgoingCalls {
+            project_id,
+            buffer_id: buffer.remote_id().into(),
+            lsp_item: serde_json::to_vec(&self.item).unwrap(),
+        }
+    }
+
+    async fn from_proto(
+        message: proto::CallHierarchyOutgoingCalls,
+        _: Model<Project>,
+        _: Model<Buffer>,
+        _: AsyncAppContext,
+    ) -> Result<Self> {
+        Ok(Self {
+            item: serde_json::from_slice(&message.lsp_item)?,
+        })
+    }
+
+    fn response_to_proto(
+        response: Vec<HierarchyCall>,
+        project: &mut Project,
+        peer_id: PeerId,
+        _: &clock::Global,
+        cx: &mut AppContext,
+    ) -> proto::HierarchyCallsResponse {
+        hierarchy_calls_to_proto(response, project, peer_id, cx)
+    }
+
+    async fn response_from_proto(
+        self,
+        message: proto::HierarchyCallsResponse,
+        project: Model<Project>,
+        _: Model<Buffer>,
+        cx: AsyncAppContext,
+    ) -> Result<Vec<HierarchyCall>> {
+        hierarchy_calls_from_proto(message, project, cx).await
+    }
+
+    fn buffer_id_from_proto(message: &proto::CallHierarchyOutgoingCalls) -> Result<BufferId> {
+        BufferId::new(message.buffer_id)
+    }
+}
+
+#[async_trait(?Send)]
+impl LspCommand for PrepareTypeHierarchy {
+    type Response = Vec<HierarchyItem>;
+    type LspRequest = lsp::request::TypeHierarchyPrepare;
+    type ProtoRequest = proto::PrepareTypeHierarchy;
+
+    fn check_capabilities(&self, capabilities: &ServerCapabilities) -> bool {
+        supports_type_hierarchy(capabilities)
+    }
+
+    fn to_lsp(
+        &self,
+        path: &Path,
+        _: &Buffer,
+        _: &Arc<LanguageServer>,
+        _: &AppContext,
+    ) -> lsp::TypeHierarchyPrepareParams {
+        lsp::TypeHierarchyPrepareParams {
+            text_document_position_params: text_document_position(path, self.position),
+            work_done_progress_params: Default::default(),
+        }
+    }
+
+    async fn response_from_lsp(
+        self,
+        message: Option<Vec<lsp::TypeHierarchyItem>>,
+        project: Model<Project>,
+        buffer: Model<Buffer>,
+        server_id: LanguageServerId,
+        cx: AsyncAppContext,
+    ) -> Result<Vec<HierarchyItem>> {
+        hierarchy_items_from_lsp(message, project, buffer, server_id, cx).await
+    }
+
+    fn to_proto(&self, project_id: u64, buffer: &Buffer) -> proto::PrepareTypeHierarchy {
+        proto::PrepareTypeHierarchy {
+            project_id,
+            buffer_id: buffer.remote_id().into(),
+            position: Some(serialize_anchor(&buffer.anchor_before(self.position))),
+            version: serialize_version(&buffer.version()),
+        }
+    }
+
+    async fn from_proto(
+        message: proto::PrepareTypeHierarchy,
+        _: Model<Project>,
+        buffer: Model<Buffer>,
+        mut cx: AsyncAppContext,
+    ) -> Result<Self> {
+        Ok(Self {
+            position: position_from_proto(message.position, &message.version, &buffer, &mut cx)
+                .await?,
+        })
+    }
+
+    fn response_to_proto(
+        response: Vec<HierarchyItem>,
+        project: &mut Project,
+        peer_id: PeerId,
+        _: &clock::Global,
+        cx: &mut AppContext,
+    ) -> proto::HierarchyItemsResponse {
+        hierarchy_items_to_proto(response, project, peer_id, cx)
+    }
+
+    async fn response_from_proto(
+        self,
+        message: proto::HierarchyItemsResponse,
+        project: Model<Project>,
+        _: Model<Buffer>,
+        cx: AsyncAppContext,
+    ) -> Result<Vec<HierarchyItem>> {
+        hierarchy_items_from_proto(message, project, cx).await
+    }
+
+    fn buffer_id_from_proto(message: &proto::PrepareTypeHierarchy) -> Result<BufferId> {
+        BufferId::new(message.buffer_id)
+    }
+}
+
+#[async_trait(?Send)]
+impl LspCommand for TypeHierarchySupertypes {
+    type Response = Vec<HierarchyItem>;
+    type LspRequest = lsp::request::TypeHierarchySupertypes;
+    type ProtoRequest = proto::TypeHierarchySupertypes;
+
+    fn check_capabilities(&self, capabilities: &ServerCapabilities) -> bool {
+        supports_type_hierarchy(capabilities)
+    }
+
+    fn to_lsp(
+        &self,
+        _: &Path,
+        _: &Buffer,
+        _: &Arc<LanguageServer>,
+        _: &AppContext,
+    ) -> lsp::TypeHierarchySupertypesParams {
+        lsp::TypeHierarchySupertypesParams {
+            item: self.item.clone(),
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        }
+    }
+
+    async fn response_from_lsp(
+        self,
+        message: Option<Vec<lsp::TypeHierarchyItem>>,
+        project: Model<Project>,
+        buffer: Model<Buffer>,
+        server_id: LanguageServerId,
+        cx: AsyncAppContext,
+    ) -> Result<Vec<HierarchyItem>> {
+        hierarchy_items_from_lsp(message, project, buffer, server_id, cx).await
+    }
+
+    fn to_proto(&self, project_id: u64, buffer: &Buffer) -> proto::TypeHierarchySupertypes {
+        proto::TypeHierarchySupertypes {
+            project_id,
+            buffer_id: buffer.remote_id().into(),
+            lsp_item: serde_json::to_vec(&self.item).unwrap(),
+        }
+    }
+
+    async fn from_proto(
+        message: proto::TypeHierarchySupertypes,
+        _: Model<Project>,
+        _: Model<Buffer>,
+        _: AsyncAppContext,
+    ) -> Result<Self> {
+        Ok(Self {
+            item: serde_json::from_slice(&message.lsp_item)?,
+        })
+    }
+
+    fn response_to_proto(
+        response: Vec<HierarchyItem>,
+        project: &mut Project,
+        peer_id: PeerId,
+        _: &clock::Global,
+        cx: &mut AppContext,
+    ) -> proto::HierarchyItemsResponse {
+        hierarchy_items_to_proto(response, project, peer_id, cx)
+    }
+
+    async fn response_from_proto(
+        self,
+        message: proto::HierarchyItemsResponse,
+        project: Model<Project>,
+        _: Model<Buffer>,
+        cx: AsyncAppContext,
+    ) -> Result<Vec<HierarchyItem>> {
+        hierarchy_items_from_proto(message, project, cx).await
+    }
+
+    fn buffer_id_from_proto(message: &proto::TypeHierarchySupertypes) -> Result<BufferId> {
+        BufferId::new(message.buffer_id)
+    }
+}
+
+#[async_trait(?Send)]
+impl LspCommand for TypeHierarchySubtypes {
+    type Response = Vec<HierarchyItem>;
+    type LspRequest = lsp::request::TypeHierarchySubtypes;
+    type ProtoRequest = proto::TypeHierarchySubtypes;
+
+    fn check_capabilities(&self, capabilities: &ServerCapabilities) -> bool {
+        supports_type_hierarchy(capabilities)
+    }
+
+    fn to_lsp(
+        &self,
+        _: &Path,
+        _: &Buffer,
+        _: &Arc<LanguageServer>,
+        _: &AppContext,
+    ) -> lsp::TypeHierarchySubtypesParams {
+        lsp::TypeHierarchySubtypesParams {
+            item: self.item.clone(),
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        }
+    }
+
+    async fn response_from_lsp(
+        self,
+        message: Option<Vec<lsp::TypeHierarchyItem>>,
+        project: Model<Project>,
+        buffer: Model<Buffer>,
+        server_id: LanguageServerId,
+        cx: AsyncAppContext,
+    ) -> Result<Vec<HierarchyItem>> {
+        hierarchy_items_from_lsp(message, project, buffer, server_id, cx).await
+    }
+
+    fn to_proto(&self, project_id: u64, buffer: &Buffer) -> proto::TypeHierarchySubtypes {
+        proto::TypeHierarchySubtypes {
+            project_id,
+            buffer_id: buffer.remote_id().into(),
+            lsp_item: serde_json::to_vec(&self.item).unwrap(),
+        }
+    }
+
+    async fn from_proto(
+        message: proto::TypeHierarchySubtypes,
+        _: Model<Project>,
+        _: Model<Buffer>,
+        _: AsyncAppContext,
+    ) -> Result<Self> {
+        Ok(Self {
+            item: serde_json::from_slice(&message.lsp_item)?,
+        })
+    }
+
+    fn response_to_proto(
+        response: Vec<HierarchyItem>,
+        project: &mut Project,
+        peer_id: PeerId,
+        _: &clock::Global,
+        cx: &mut AppContext,
+    ) -> proto::HierarchyItemsResponse {
+        hierarchy_items_to_proto(response, project, peer_id, cx)
+    }
+
+    async fn response_from_proto(
+        self,
+        message: proto::HierarchyItemsResponse,
+        project: Model<Project>,
+        _: Model<Buffer>,
+        cx: AsyncAppContext,
+    ) -> Result<Vec<HierarchyItem>> {
+        hierarchy_items_from_proto(message, project, cx).await
+    }
+
+    fn buffer_id_from_proto(message: &proto::TypeHierarchySubtypes) -> Result<BufferId> {
+        BufferId::new(message.buffer_id)
+    }
+}
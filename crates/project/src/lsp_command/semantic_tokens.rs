@@ -0,0 +1,211 @@
+use std::ops::Range;
+
+use language::{point_from_lsp, Anchor, Bias, BufferSnapshot};
+use lsp::{
+    SemanticTokensFullOptions, SemanticTokensLegend, SemanticTokensServerCapabilities,
+    ServerCapabilities,
+};
+use rpc::proto;
+
+/// A single token produced by the language server's `textDocument/semanticTokens` response.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SemanticToken {
+    pub range: Range<Anchor>,
+    /// The token type, as named in the server's legend (e.g. `function`, `macro`).
+    pub token_type: String,
+    /// Names of the modifiers set for this token (e.g. `readonly`, `static`).
+    pub token_modifiers: Vec<String>,
+}
+
+pub(super) fn semantic_tokens_legend(
+    capabilities: &ServerCapabilities,
+) -> Option<&SemanticTokensLegend> {
+    match capabilities.semantic_tokens_provider.as_ref()? {
+        SemanticTokensServerCapabilities::SemanticTokensOptions(options) => Some(&options.legend),
+        SemanticTokensServerCapabilities::SemanticTokensRegistrationOptions(options) => {
+            Some(&options.semantic_tokens_options.legend)
+        }
+    }
+}
+
+pub(super) fn supports_full_semantic_tokens(capabilities: &ServerCapabilities) -> bool {
+    let full = match capabilities.semantic_tokens_provider.as_ref() {
+        Some(SemanticTokensServerCapabilities::SemanticTokensOptions(options)) => &options.full,
+        Some(SemanticTokensServerCapabilities::SemanticTokensRegistrationOptions(options)) => {
+            &options.semantic_tokens_options.full
+        }
+        None => return false,
+    };
+    match full {
+        Some(SemanticTokensFullOptions::Bool(enabled)) => *enabled,
+        Some(SemanticTokensFullOptions::Delta { .. }) => true,
+        None => false,
+    }
+}
+
+/// Decodes the relative, integer-encoded token data of an LSP response into absolute ranges,
+/// resolving token types and modifiers through the server's legend.
+///
+/// Tokens referring to types outside of the legend are skipped. Every token is decoded as lying
+/// on a single line, since multi-line tokens are not advertised as supported by the client.
+pub(super) fn decode_semantic_tokens(
+    data: &[lsp::SemanticToken],
+    legend: &SemanticTokensLegend,
+    snapshot: &BufferSnapshot,
+) -> Vec<SemanticToken> {
+    let mut tokens = Vec::with_capacity(data.len());
+    let mut line = 0;
+    let mut start = 0;
+    for token in data {
+        if token.delta_line == 0 {
+            start += token.delta_start;
+        } else {
+            line += token.delta_line;
+            start = token.delta_start;
+        }
+
+        let Some(token_type) = legend.token_types.get(token.token_type as usize) else {
+            continue;
+        };
+        let token_modifiers = legend
+            .token_modifiers
+            .iter()
+            .enumerate()
+            .filter(|(ix, _)| *ix < 32 && token.token_modifiers_bitset & (1 << ix) != 0)
+            .map(|(_, modifier)| modifier.as_str().to_string())
+            .collect();
+
+        let range_start = point_from_lsp(lsp::Position::new(line, start));
+        let range_end = point_from_lsp(lsp::Position::new(line, start + token.length));
+        let range_start = snapshot.clip_point_utf16(range_start, Bias::Left);
+        let range_end = snapshot.clip_point_utf16(range_end, Bias::Left);
+        if range_start == range_end {
+            continue;
+        }
+
+        tokens.push(SemanticToken {
+            range: snapshot.anchor_after(range_start)..snapshot.anchor_before(range_end),
+            token_type: token_type.as_str().to_string(),
+            token_modifiers,
+        });
+    }
+    tokens
+}
+
+pub(super) fn semantic_token_to_proto(token: SemanticToken) -> proto::SemanticToken {
+    proto::SemanticToken {
+        start: Some(language::proto::serialize_anchor(&token.range.start)),
+        end: Some(language::proto::serialize_anchor(&token.range.end)),
+        token_type: token.token_type,
+        token_modifiers: token.token_modifiers,
+    }
+}
+
+pub(super) fn semantic_token_from_proto(token: proto::SemanticToken) -> Option<SemanticToken> {
+    let start = language::proto::deserialize_anchor(token.start?)?;
+    let end = language::proto::deserialize_anchor(token.end?)?;
+    Some(SemanticToken {
+        range: start..end,
+        token_type: token.token_type,
+        token_modifiers: token.token_modifiers,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::{Context as _, TestAppContext};
+    use language::{Buffer, ToPoint as _};
+    use lsp::{SemanticTokenModifier, SemanticTokenType};
+
+    fn legend() -> SemanticTokensLegend {
+        SemanticTokensLegend {
+            token_types: vec![
+                SemanticTokenType::FUNCTION,
+                SemanticTokenType::VARIABLE,
+                SemanticTokenType::MACRO,
+            ],
+            token_modifiers: vec![
+                SemanticTokenModifier::DECLARATION,
+                SemanticTokenModifier::READONLY,
+            ],
+        }
+    }
+
+    fn token(
+        delta_line: u32,
+        delta_start: u32,
+        length: u32,
+        token_type: u32,
+        token_modifiers_bitset: u32,
+    ) -> lsp::SemanticToken {
+        lsp::SemanticToken {
+            delta_line,
+            delta_start,
+            length,
+            token_type,
+            token_modifiers_bitset,
+        }
+    }
+
+    #[gpui::test]
+    fn test_decode_semantic_tokens(cx: &mut TestAppContext) {
+        let buffer = cx.new_model(|cx| {
+            Buffer::local(
+                "fn main() {\n    let x = 1;\n    println!(\"{x}\");\n}\n",
+                cx,
+            )
+        });
+        let snapshot = buffer.read_with(cx, |buffer, _| buffer.snapshot());
+
+        let data = [
+            token(0, 3, 4, 0, 0b01),
+            token(1, 8, 1, 1, 0b11),
+            token(1, 4, 7, 2, 0),
+            // Unknown token types are dropped.
+            token(0, 9, 1, 42, 0),
+        ];
+        let tokens = decode_semantic_tokens(&data, &legend(), &snapshot);
+        let tokens = tokens
+            .iter()
+            .map(|token| {
+                (
+                    snapshot
+                        .text_for_range(token.range.clone())
+                        .collect::<String>(),
+                    token.range.start.to_point(&snapshot),
+                    token.token_type.as_str(),
+                    token
+                        .token_modifiers
+                        .iter()
+                        .map(String::as_str)
+                        .collect::<Vec<_>>(),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            tokens,
+            [
+                (
+                    "main".to_string(),
+                    text::Point::new(0, 3),
+                    "function",
+                    vec!["declaration"]
+                ),
+                (
+                    "x".to_string(),
+                    text::Point::new(1, 8),
+                    "variable",
+                    vec!["declaration", "readonly"]
+                ),
+                (
+                    "println".to_string(),
+                    text::Point::new(2, 4),
+                    "macro",
+                    vec![]
+                ),
+            ]
+        );
+    }
+}
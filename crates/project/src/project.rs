@@ -7,6 +7,7 @@ mod prettier_support;
 pub mod project_settings;
 pub mod search;
 mod task_inventory;
+mod task_provider_registry;
 pub mod terminals;
 
 #[cfg(test)]
@@ -22,7 +23,7 @@ use client::{
     TypedEnvelope, UserStore,
 };
 use clock::ReplicaId;
-use collections::{btree_map, BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
+use collections::{btree_map, hash_map, BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
 use debounced_delay::DebouncedDelay;
 use futures::{
     channel::mpsc::{self, UnboundedReceiver},
@@ -32,7 +33,14 @@ use futures::{
     AsyncWriteExt, Future, FutureExt, StreamExt, TryFutureExt,
 };
 use fuzzy::CharBag;
-use git::{blame::Blame, repository::GitRepository};
+use git::{
+    blame::Blame,
+    log::{Log, LogEntry},
+    remote::{AuthenticationRequired, PullMode, RemoteCredentials, RemoteProgress},
+    repository::{CommitFileChange, DiffBase, GitFileStatus, GitRepository, RepoPath},
+    stash::Stash,
+    status::{StagingStatus, StatusCode},
+};
 use globset::{Glob, GlobSet, GlobSetBuilder};
 use gpui::{
     AnyModel, AppContext, AsyncAppContext, BackgroundExecutor, BorrowAppContext, Context, Entity,
@@ -43,17 +51,18 @@ use itertools::Itertools;
 use language::{
     language_settings::{
         language_settings, AllLanguageSettings, FormatOnSave, Formatter, InlayHintKind,
+        LanguageSettings,
     },
     markdown, point_to_lsp, prepare_completion_documentation,
     proto::{
         deserialize_anchor, deserialize_line_ending, deserialize_version, serialize_anchor,
         serialize_line_ending, serialize_version, split_operations,
     },
-    range_from_lsp, Bias, Buffer, BufferSnapshot, CachedLspAdapter, Capability, CodeLabel,
-    ContextProvider, Diagnostic, DiagnosticEntry, DiagnosticSet, Diff, Documentation,
-    Event as BufferEvent, File as _, Language, LanguageRegistry, LanguageServerName, LocalFile,
-    LspAdapterDelegate, Patch, PendingLanguageServer, PointUtf16, TextBufferSnapshot, ToOffset,
-    ToPointUtf16, Transaction, Unclipped,
+    range_from_lsp, Bias, Buffer, BufferFormatter, BufferLinter, BufferSnapshot, CachedLspAdapter,
+    Capability, CodeLabel, ContextProvider, Diagnostic, DiagnosticEntry, DiagnosticSet, Diff,
+    Documentation, Event as BufferEvent, File as _, FormattingOptions, Language, LanguageRegistry,
+    LanguageServerName, LocalFile, LspAdapterDelegate, Patch, PendingLanguageServer, PointUtf16,
+    TextBufferSnapshot, ToOffset, ToPointUtf16, Transaction, Unclipped,
 };
 use log::error;
 use lsp::{
@@ -105,7 +114,8 @@ use std::{
 };
 use task::{
     static_source::{StaticSource, TrackedFile},
-    RevealStrategy, TaskContext, TaskTemplate, TaskVariables, VariableName,
+    DependsOrder, RevealStrategy, TaskContext, TaskTemplate, TaskTemplates, TaskVariables,
+    VariableName,
 };
 use terminals::Terminals;
 use text::{Anchor, BufferId, LineEnding};
@@ -124,6 +134,7 @@ pub use prettier::FORMAT_SUFFIX as TEST_PRETTIER_FORMAT_SUFFIX;
 pub use task_inventory::{
     BasicContextProvider, ContextProviderWithTasks, Inventory, TaskSourceKind,
 };
+pub use task_provider_registry::{TaskProvider, TaskProviderRegistry};
 pub use worktree::{
     Entry, EntryKind, File, LocalWorktree, PathChange, ProjectEntryId, RepositoryEntry,
     UpdatedEntriesSet, UpdatedGitRepositoriesSet, Worktree, WorktreeId, WorktreeSettings,
@@ -132,6 +143,7 @@ pub use worktree::{
 
 const MAX_SERVER_REINSTALL_ATTEMPT_COUNT: u64 = 4;
 const SERVER_REINSTALL_DEBOUNCE_TIMEOUT: Duration = Duration::from_secs(1);
+const LINT_DEBOUNCE_TIMEOUT: Duration = Duration::from_millis(300);
 const SERVER_LAUNCHING_BEFORE_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(5);
 pub const SERVER_PROGRESS_THROTTLE_TIMEOUT: Duration = Duration::from_millis(100);
 
@@ -173,6 +185,8 @@ pub struct Project {
     language_server_ids: HashMap<(WorktreeId, LanguageServerName), LanguageServerId>,
     language_server_statuses: BTreeMap<LanguageServerId, LanguageServerStatus>,
     last_formatting_failure: Option<String>,
+    git_operation: Option<GitOperation>,
+    last_git_operation_failure: Option<GitOperationFailure>,
     last_workspace_edits_by_language_server: HashMap<LanguageServerId, ProjectTransaction>,
     language_server_watched_paths: HashMap<LanguageServerId, HashMap<WorktreeId, GlobSet>>,
     language_server_watcher_registrations:
@@ -181,6 +195,10 @@ pub struct Project {
     next_entry_id: Arc<AtomicUsize>,
     join_project_response_message_id: u32,
     next_diagnostic_group_id: usize,
+    /// The IDs under which the diagnostics of each [`BufferLinter`] are stored,
+    /// like those of language servers, keyed by extension ID and linter name.
+    linter_ids: HashMap<(Arc<str>, Arc<str>), LanguageServerId>,
+    buffers_being_linted: HashMap<BufferId, Task<()>>,
     diagnostic_summaries:
         HashMap<WorktreeId, HashMap<Arc<Path>, HashMap<LanguageServerId, DiagnosticSummary>>>,
     diagnostics: HashMap<
@@ -218,9 +236,13 @@ pub struct Project {
     prettiers_per_worktree: HashMap<WorktreeId, HashSet<Option<PathBuf>>>,
     prettier_instances: HashMap<PathBuf, PrettierInstance>,
     tasks: Model<Inventory>,
+    task_providers: Arc<TaskProviderRegistry>,
+    task_provider_sources: HashMap<(Arc<str>, WorktreeId), mpsc::UnboundedSender<String>>,
+    _maintain_task_providers: Task<()>,
     hosted_project_id: Option<ProjectId>,
     dev_server_project_id: Option<client::DevServerProjectId>,
     search_history: SearchHistory,
+    git_diff_base: DiffBase,
     snippets: Model<SnippetProvider>,
     yarn: Model<YarnPathStore>,
 }
@@ -306,6 +328,43 @@ impl PartialEq for LanguageServerPromptRequest {
     }
 }
 
+/// A request for the credentials of a git remote, made when git couldn't authenticate
+/// a network operation. The operation is retried with the credentials entered by the user.
+#[derive(Clone, Debug)]
+pub struct GitCredentialsRequest {
+    /// The git command that failed, like `git push`.
+    pub operation: String,
+    pub url: Option<String>,
+    response_channel: Sender<Option<RemoteCredentials>>,
+}
+
+impl GitCredentialsRequest {
+    /// Retries the operation with the given credentials, or fails it when they are `None`.
+    pub async fn respond(self, credentials: Option<RemoteCredentials>) -> Option<()> {
+        self.response_channel.send(credentials).await.ok()
+    }
+}
+
+impl PartialEq for GitCredentialsRequest {
+    fn eq(&self, other: &Self) -> bool {
+        self.operation == other.operation && self.url == other.url
+    }
+}
+
+/// A git command changing the stash or talking to a remote, running in the background.
+#[derive(Clone, Debug)]
+pub struct GitOperation {
+    /// The command, like `git fetch`.
+    pub title: String,
+    pub progress: Option<RemoteProgress>,
+}
+
+#[derive(Clone, Debug)]
+pub struct GitOperationFailure {
+    pub title: String,
+    pub error: String,
+}
+
 #[derive(Clone, Debug, PartialEq)]
 pub enum Event {
     LanguageServerAdded(LanguageServerId),
@@ -313,6 +372,7 @@ pub enum Event {
     LanguageServerLog(LanguageServerId, String),
     Notification(String),
     LanguageServerPrompt(LanguageServerPromptRequest),
+    GitCredentialsPrompt(GitCredentialsRequest),
     LanguageNotFound(Model<Buffer>),
     ActiveEntryChanged(Option<ProjectEntryId>),
     ActivateProjectPanel,
@@ -321,6 +381,7 @@ pub enum Event {
     WorktreeRemoved(WorktreeId),
     WorktreeUpdatedEntries(WorktreeId, UpdatedEntriesSet),
     WorktreeUpdatedGitRepositories,
+    GitDiffBaseChanged,
     DiskBasedDiagnosticsStarted {
         language_server_id: LanguageServerId,
     },
@@ -345,6 +406,8 @@ pub enum Event {
     Reshared,
     Rejoined,
     RefreshInlayHints,
+    RefreshSemanticTokens,
+    RefreshCodeLens,
     RevealInProjectPanel(ProjectEntryId),
     SnippetEdit(BufferId, Vec<(lsp::Range, Snippet)>),
 }
@@ -466,6 +529,17 @@ pub struct CodeAction {
     pub lsp_action: lsp::CodeAction,
 }
 
+/// A code lens provided by a language server.
+#[derive(Clone, Debug)]
+pub struct CodeLens {
+    /// The id of the language server that produced this code lens.
+    pub server_id: LanguageServerId,
+    /// The range of the buffer this code lens is shown for.
+    pub range: Range<Anchor>,
+    /// The raw code lens provided by the language server, resolved if the server supports it.
+    pub lsp_lens: lsp::CodeLens,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum ResolveState {
     Resolved,
@@ -590,6 +664,7 @@ enum FormatOperation {
     Lsp(Vec<(Range<Anchor>, String)>),
     External(Diff),
     Prettier(Diff),
+    Formatter(Vec<(Range<Anchor>, String)>),
 }
 
 impl FormatTrigger {
@@ -668,6 +743,9 @@ impl Project {
         client.add_model_request_handler(Self::handle_inlay_hints);
         client.add_model_request_handler(Self::handle_resolve_inlay_hint);
         client.add_model_request_handler(Self::handle_refresh_inlay_hints);
+        client.add_model_request_handler(Self::handle_refresh_semantic_tokens);
+        client.add_model_request_handler(Self::handle_refresh_code_lens);
+        client.add_model_request_handler(Self::handle_execute_code_lens);
         client.add_model_request_handler(Self::handle_reload_buffers);
         client.add_model_request_handler(Self::handle_synchronize_buffers);
         client.add_model_request_handler(Self::handle_format_buffers);
@@ -690,11 +768,28 @@ impl Project {
         client.add_model_message_handler(Self::handle_update_diff_base);
         client.add_model_request_handler(Self::handle_lsp_command::<lsp_ext_command::ExpandMacro>);
         client.add_model_request_handler(Self::handle_blame_buffer);
+        client.add_model_request_handler(Self::handle_get_git_status);
+        client.add_model_request_handler(Self::handle_stage_git_paths);
+        client.add_model_request_handler(Self::handle_unstage_git_paths);
+        client.add_model_request_handler(Self::handle_stage_git_hunk);
+        client.add_model_request_handler(Self::handle_unstage_git_hunk);
+        client.add_model_request_handler(Self::handle_git_commit);
+        client.add_model_request_handler(Self::handle_get_git_changed_paths);
+        client.add_model_request_handler(Self::handle_get_git_log);
+        client.add_model_request_handler(Self::handle_get_git_commit_changes);
         client.add_model_request_handler(Self::handle_multi_lsp_query);
         client.add_model_request_handler(Self::handle_restart_language_servers);
         client.add_model_request_handler(Self::handle_task_context_for_location);
         client.add_model_request_handler(Self::handle_task_templates);
         client.add_model_request_handler(Self::handle_lsp_command::<LinkedEditingRange>);
+        client.add_model_request_handler(Self::handle_lsp_command::<GetSemanticTokens>);
+        client.add_model_request_handler(Self::handle_lsp_command::<GetCodeLens>);
+        client.add_model_request_handler(Self::handle_lsp_command::<PrepareCallHierarchy>);
+        client.add_model_request_handler(Self::handle_lsp_command::<CallHierarchyIncomingCalls>);
+        client.add_model_request_handler(Self::handle_lsp_command::<CallHierarchyOutgoingCalls>);
+        client.add_model_request_handler(Self::handle_lsp_command::<PrepareTypeHierarchy>);
+        client.add_model_request_handler(Self::handle_lsp_command::<TypeHierarchySupertypes>);
+        client.add_model_request_handler(Self::handle_lsp_command::<TypeHierarchySubtypes>);
     }
 
     pub fn local(
@@ -710,6 +805,7 @@ impl Project {
             cx.spawn(move |this, cx| Self::send_buffer_ordered_messages(this, rx, cx))
                 .detach();
             let tasks = Inventory::new(cx);
+            let task_providers = TaskProviderRegistry::default_global(cx);
             let global_snippets_dir = paths::config_dir().join("snippets");
             let snippets =
                 SnippetProvider::new(fs.clone(), BTreeSet::from_iter([global_snippets_dir]), cx);
@@ -748,6 +844,8 @@ impl Project {
                 fs,
                 next_entry_id: Default::default(),
                 next_diagnostic_group_id: Default::default(),
+                linter_ids: HashMap::default(),
+                buffers_being_linted: HashMap::default(),
                 diagnostics: Default::default(),
                 diagnostic_summaries: Default::default(),
                 supplementary_language_servers: HashMap::default(),
@@ -755,6 +853,8 @@ impl Project {
                 language_server_ids: HashMap::default(),
                 language_server_statuses: Default::default(),
                 last_formatting_failure: None,
+                git_operation: None,
+                last_git_operation_failure: None,
                 last_workspace_edits_by_language_server: Default::default(),
                 language_server_watched_paths: HashMap::default(),
                 language_server_watcher_registrations: HashMap::default(),
@@ -764,6 +864,7 @@ impl Project {
                 nonce: StdRng::from_entropy().gen(),
                 terminals: Terminals {
                     local_handles: Vec::new(),
+                    task_diagnostics: HashMap::default(),
                 },
                 current_lsp_settings: ProjectSettings::get_global(cx).lsp.clone(),
                 node: Some(node),
@@ -771,9 +872,13 @@ impl Project {
                 prettiers_per_worktree: HashMap::default(),
                 prettier_instances: HashMap::default(),
                 tasks,
+                task_providers: task_providers.clone(),
+                task_provider_sources: HashMap::default(),
+                _maintain_task_providers: Self::maintain_task_providers(task_providers, cx),
                 hosted_project_id: None,
                 dev_server_project_id: None,
                 search_history: Self::new_search_history(),
+                git_diff_base: DiffBase::default(),
             }
         })
     }
@@ -879,6 +984,8 @@ impl Project {
                 fs,
                 next_entry_id: Default::default(),
                 next_diagnostic_group_id: Default::default(),
+                linter_ids: HashMap::default(),
+                buffers_being_linted: HashMap::default(),
                 diagnostic_summaries: Default::default(),
                 diagnostics: Default::default(),
                 client_subscriptions: Default::default(),
@@ -914,6 +1021,8 @@ impl Project {
                     })
                     .collect(),
                 last_formatting_failure: None,
+                git_operation: None,
+                last_git_operation_failure: None,
                 last_workspace_edits_by_language_server: Default::default(),
                 language_server_watched_paths: HashMap::default(),
                 language_server_watcher_registrations: HashMap::default(),
@@ -924,6 +1033,7 @@ impl Project {
                 nonce: StdRng::from_entropy().gen(),
                 terminals: Terminals {
                     local_handles: Vec::new(),
+                    task_diagnostics: HashMap::default(),
                 },
                 current_lsp_settings: ProjectSettings::get_global(cx).lsp.clone(),
                 node: None,
@@ -931,12 +1041,16 @@ impl Project {
                 prettiers_per_worktree: HashMap::default(),
                 prettier_instances: HashMap::default(),
                 tasks,
+                task_providers: TaskProviderRegistry::default_global(cx),
+                task_provider_sources: HashMap::default(),
+                _maintain_task_providers: Task::ready(()),
                 hosted_project_id: None,
                 dev_server_project_id: response
                     .payload
                     .dev_server_project_id
                     .map(|dev_server_project_id| DevServerProjectId(dev_server_project_id)),
                 search_history: Self::new_search_history(),
+                git_diff_base: DiffBase::default(),
             };
             this.set_role(role, cx);
             for worktree in worktrees {
@@ -2101,6 +2215,7 @@ impl Project {
         self.detect_language_for_buffer(buffer, cx);
         self.register_buffer_with_language_servers(buffer, cx);
         cx.observe_release(buffer, |this, buffer, cx| {
+            this.buffers_being_linted.remove(&buffer.remote_id());
             if let Some(file) = File::from_dyn(buffer.file()) {
                 if file.is_local() {
                     let uri = lsp::Url::from_file_path(file.abs_path(cx)).unwrap();
@@ -2205,7 +2320,163 @@ impl Project {
                         .insert(server.server_id(), vec![snapshot]);
                 }
             }
+
+            self.lint_buffer(buffer_handle, cx);
+        }
+    }
+
+    /// Lints the given buffer with the [`BufferLinter`]s registered for its language once it
+    /// has not been edited for a while, storing their diagnostics like those of language servers.
+    fn lint_buffer(&mut self, buffer_handle: &Model<Buffer>, cx: &mut ModelContext<Self>) {
+        if !self.is_local() {
+            return;
+        }
+
+        let buffer = buffer_handle.read(cx);
+        let buffer_id = buffer.remote_id();
+        let Some(file) = File::from_dyn(buffer.file()) else {
+            return;
+        };
+        let Some(abs_path) = file.as_local().map(|file| file.abs_path(cx)) else {
+            return;
+        };
+        let worktree_id = file.worktree_id(cx);
+        let path = file.path.clone();
+        let linters = buffer
+            .language()
+            .map(|language| self.languages.linters(language))
+            .unwrap_or_default();
+        // Even without linters, the diagnostics of the ones that were unregistered must be cleared.
+        if linters.is_empty()
+            && self
+                .linter_ids_with_diagnostics(worktree_id, &path)
+                .is_empty()
+        {
+            return;
         }
+
+        let buffer = buffer_handle.downgrade();
+        let task = cx.spawn(move |project, mut cx| async move {
+            cx.background_executor().timer(LINT_DEBOUNCE_TIMEOUT).await;
+
+            let Some(buffer) = buffer.upgrade() else {
+                return;
+            };
+            let Ok((snapshot, lints)) = buffer.update(&mut cx, |buffer, cx| {
+                let snapshot = buffer.text_snapshot();
+                let lints = linters
+                    .iter()
+                    .map(|(extension_id, linter)| {
+                        (
+                            extension_id.clone(),
+                            linter.name(),
+                            linter.lint(snapshot.text(), &abs_path, cx),
+                        )
+                    })
+                    .collect::<Vec<_>>();
+                (snapshot, lints)
+            }) else {
+                return;
+            };
+
+            let mut results = Vec::new();
+            for (extension_id, linter_name, lint) in lints {
+                if let Some(diagnostics) = lint
+                    .await
+                    .with_context(|| format!("failed to lint via {linter_name}"))
+                    .log_err()
+                {
+                    results.push((extension_id, linter_name, diagnostics));
+                }
+            }
+
+            project
+                .update(&mut cx, |project, cx| {
+                    // The buffer was edited while it was being linted, so it will be linted again.
+                    if buffer.read(cx).version() != *snapshot.version() {
+                        return;
+                    }
+
+                    let mut linted_ids = HashSet::default();
+                    for (extension_id, linter_name, diagnostics) in results {
+                        let linter_id = *project
+                            .linter_ids
+                            .entry((extension_id, linter_name.clone()))
+                            .or_insert_with(|| project.languages.next_language_server_id());
+                        linted_ids.insert(linter_id);
+                        let diagnostics = diagnostics
+                            .into_iter()
+                            .map(|diagnostic| {
+                                let start =
+                                    snapshot.clip_offset(diagnostic.range.start, Bias::Left);
+                                let end = snapshot.clip_offset(diagnostic.range.end, Bias::Right);
+                                DiagnosticEntry {
+                                    range: Unclipped(snapshot.offset_to_point_utf16(start))
+                                        ..Unclipped(snapshot.offset_to_point_utf16(end)),
+                                    diagnostic: Diagnostic {
+                                        source: Some(linter_name.to_string()),
+                                        code: diagnostic.code,
+                                        severity: diagnostic.severity,
+                                        message: diagnostic.message.trim().to_string(),
+                                        group_id: post_inc(&mut project.next_diagnostic_group_id),
+                                        is_primary: true,
+                                        is_disk_based: false,
+                                        is_unnecessary: false,
+                                    },
+                                }
+                            })
+                            .collect();
+                        project
+                            .update_diagnostic_entries(
+                                linter_id,
+                                abs_path.clone(),
+                                None,
+                                diagnostics,
+                                cx,
+                            )
+                            .log_err();
+                    }
+
+                    let stale_linter_ids = project
+                        .linter_ids_with_diagnostics(worktree_id, &path)
+                        .into_iter()
+                        .filter(|linter_id| !linted_ids.contains(linter_id))
+                        .collect::<Vec<_>>();
+                    for linter_id in stale_linter_ids {
+                        project
+                            .update_diagnostic_entries(
+                                linter_id,
+                                abs_path.clone(),
+                                None,
+                                Vec::new(),
+                                cx,
+                            )
+                            .log_err();
+                    }
+                })
+                .ok();
+        });
+        self.buffers_being_linted.insert(buffer_id, task);
+    }
+
+    /// The IDs of the linters that have diagnostics stored for the given path.
+    fn linter_ids_with_diagnostics(
+        &self,
+        worktree_id: WorktreeId,
+        path: &Path,
+    ) -> Vec<LanguageServerId> {
+        self.diagnostics
+            .get(&worktree_id)
+            .and_then(|diagnostics| diagnostics.get(path))
+            .into_iter()
+            .flatten()
+            .map(|(server_id, _)| *server_id)
+            .filter(|server_id| {
+                self.linter_ids
+                    .values()
+                    .any(|linter_id| linter_id == server_id)
+            })
+            .collect()
     }
 
     fn unregister_buffer_from_language_servers(
@@ -2416,6 +2687,9 @@ impl Project {
         ) {
             self.request_buffer_diff_recalculation(&buffer, cx);
         }
+        if matches!(event, BufferEvent::Edited { .. } | BufferEvent::Reloaded) {
+            self.lint_buffer(&buffer, cx);
+        }
 
         match event {
             BufferEvent::Operation(operation) => {
@@ -2831,6 +3105,116 @@ impl Project {
         })
     }
 
+    fn maintain_task_providers(
+        task_providers: Arc<TaskProviderRegistry>,
+        cx: &mut ModelContext<Project>,
+    ) -> Task<()> {
+        let mut subscription = task_providers.subscribe();
+        cx.spawn(move |project, mut cx| async move {
+            while let Some(()) = subscription.next().await {
+                let Some(project) = project.upgrade() else {
+                    break;
+                };
+                project
+                    .update(&mut cx, |project, cx| {
+                        project.refresh_provided_tasks(None, cx)
+                    })
+                    .ok();
+            }
+        })
+    }
+
+    /// Asks the registered [`TaskProvider`]s for the tasks of the given local worktree, or of all of them,
+    /// replacing the tasks they provided before and dropping the ones of unregistered providers.
+    fn refresh_provided_tasks(
+        &mut self,
+        worktree_id: Option<WorktreeId>,
+        cx: &mut ModelContext<Self>,
+    ) {
+        let providers = self.task_providers.providers();
+        let provider_ids = providers
+            .iter()
+            .map(|provider| provider.id())
+            .collect::<HashSet<_>>();
+        let mut unregistered_providers = HashSet::default();
+        self.task_provider_sources.retain(|(provider_id, _), _| {
+            let registered = provider_ids.contains(provider_id);
+            if !registered {
+                unregistered_providers.insert(provider_id.clone());
+            }
+            registered
+        });
+        self.tasks.update(cx, |inventory, _| {
+            for provider_id in &unregistered_providers {
+                inventory.remove_provider_sources(provider_id);
+            }
+        });
+
+        let worktrees = self
+            .visible_worktrees(cx)
+            .filter(|worktree| {
+                let worktree = worktree.read(cx);
+                worktree.is_local() && worktree_id.map_or(true, |id| worktree.id() == id)
+            })
+            .collect::<Vec<_>>();
+        for worktree in worktrees {
+            let worktree_id = worktree.read(cx).id();
+            let delegate = ProjectLspAdapterDelegate::new(self, &worktree, cx);
+            for provider in &providers {
+                let provider_id = provider.id();
+                let tasks = provider.tasks_for_worktree(delegate.clone(), cx);
+                cx.spawn(|project, mut cx| async move {
+                    let tasks = tasks
+                        .await
+                        .with_context(|| format!("listing the tasks of {provider_id}"))?;
+                    project.update(&mut cx, |project, cx| {
+                        project.set_provided_tasks(provider_id, worktree_id, tasks, cx)
+                    })?
+                })
+                .detach_and_log_err(cx);
+            }
+        }
+    }
+
+    fn set_provided_tasks(
+        &mut self,
+        provider_id: Arc<str>,
+        worktree_id: WorktreeId,
+        tasks: TaskTemplates,
+        cx: &mut ModelContext<Self>,
+    ) -> Result<()> {
+        // The provider or the worktree may have gone away while the tasks were listed.
+        if self.task_providers.provider(&provider_id).is_none()
+            || self.worktree_for_id(worktree_id, cx).is_none()
+        {
+            return Ok(());
+        }
+
+        let contents = serde_json::to_string(&tasks)?;
+        match self
+            .task_provider_sources
+            .entry((provider_id.clone(), worktree_id))
+        {
+            hash_map::Entry::Occupied(entry) => entry.get().unbounded_send(contents)?,
+            hash_map::Entry::Vacant(entry) => {
+                let (tasks_tx, tasks_rx) = mpsc::unbounded();
+                tasks_tx.unbounded_send(contents)?;
+                self.tasks.update(cx, |inventory, cx| {
+                    inventory.add_source(
+                        TaskSourceKind::Provider {
+                            id: provider_id,
+                            worktree: worktree_id,
+                        },
+                        |tx, cx| StaticSource::new(TrackedFile::new(tasks_rx, tx, cx)),
+                        cx,
+                    );
+                });
+                entry.insert(tasks_tx);
+            }
+        }
+        Ok(())
+    }
+
     fn detect_language_for_buffer(
         &mut self,
         buffer_handle: &Model<Buffer>,
@@ -3346,6 +3730,46 @@ impl Project {
             })
             .detach();
 
+        language_server
+            .on_request::<lsp::request::SemanticTokensRefresh, _, _>({
+                let this = this.clone();
+                move |(), mut cx| {
+                    let this = this.clone();
+                    async move {
+                        this.update(&mut cx, |project, cx| {
+                            cx.emit(Event::RefreshSemanticTokens);
+                            project.remote_id().map(|project_id| {
+                                project
+                                    .client
+                                    .send(proto::RefreshSemanticTokens { project_id })
+                            })
+                        })?
+                        .transpose()?;
+                        Ok(())
+                    }
+                }
+            })
+            .detach();
+
+        language_server
+            .on_request::<lsp::request::CodeLensRefresh, _, _>({
+                let this = this.clone();
+                move |(), mut cx| {
+                    let this = this.clone();
+                    async move {
+                        this.update(&mut cx, |project, cx| {
+                            cx.emit(Event::RefreshCodeLens);
+                            project.remote_id().map(|project_id| {
+                                project.client.send(proto::RefreshCodeLens { project_id })
+                            })
+                        })?
+                        .transpose()?;
+                        Ok(())
+                    }
+                }
+            })
+            .detach();
+
         language_server
             .on_request::<lsp::request::ShowMessageRequest, _, _>({
                 let this = this.clone();
@@ -4322,6 +4746,21 @@ impl Project {
         self.last_formatting_failure.as_deref()
     }
 
+    /// The git operation running in the background, see [`Project::git_fetch`].
+    pub fn git_operation(&self) -> Option<&GitOperation> {
+        self.git_operation.as_ref()
+    }
+
+    pub fn last_git_operation_failure(&self) -> Option<&GitOperationFailure> {
+        self.last_git_operation_failure.as_ref()
+    }
+
+    pub fn dismiss_git_operation_failure(&mut self, cx: &mut ModelContext<Self>) {
+        if self.last_git_operation_failure.take().is_some() {
+            cx.notify();
+        }
+    }
+
     pub fn update_diagnostics(
         &mut self,
         language_server_id: LanguageServerId,
@@ -4777,8 +5216,8 @@ impl Project {
 
         let mut project_transaction = ProjectTransaction::default();
         for (buffer, buffer_abs_path) in &buffers_with_paths {
-            let (primary_adapter_and_server, adapters_and_servers) =
-                project.update(&mut cx, |project, cx| {
+            let (primary_adapter_and_server, adapters_and_servers, buffer_formatter) = project
+                .update(&mut cx, |project, cx| {
                     let buffer = buffer.read(cx);
 
                     let adapters_and_servers = project
@@ -4790,7 +5229,11 @@ impl Project {
                         .primary_language_server_for_buffer(buffer, cx)
                         .map(|(adapter, lsp)| (adapter.clone(), lsp.clone()));
 
-                    (primary_adapter, adapters_and_servers)
+                    let buffer_formatter = buffer.language().and_then(|language| {
+                        project.languages.formatters(language).into_iter().next()
+                    });
+
+                    (primary_adapter, adapters_and_servers, buffer_formatter)
                 })?;
 
             let settings = buffer.update(&mut cx, |buffer, cx| {
@@ -4914,7 +5357,12 @@ impl Project {
                     .map(FormatOperation::External);
                 }
                 (Formatter::Auto, FormatOnSave::On | FormatOnSave::Off) => {
-                    let prettier = if prettier_settings.allowed {
+                    // A formatter registered for the buffer's language, like one provided by an
+                    // extension, was installed specifically for it, so it takes precedence over
+                    // Prettier and language servers.
+                    let formatter_and_buffer =
+                        buffer_formatter.as_ref().zip(buffer_abs_path.as_ref());
+                    let prettier = if formatter_and_buffer.is_none() && prettier_settings.allowed {
                         prettier_support::format_with_prettier(&project, buffer, &mut cx)
                             .await
                             .transpose()
@@ -4924,7 +5372,21 @@ impl Project {
                         None
                     };
 
-                    if let Some(operation) = prettier {
+                    if let Some((formatter, buffer_abs_path)) = formatter_and_buffer {
+                        format_operation = Some(FormatOperation::Formatter(
+                            Self::format_via_formatter(
+                                buffer,
+                                buffer_abs_path,
+                                formatter,
+                                &settings,
+                                &mut cx,
+                            )
+                            .await
+                            .with_context(|| {
+                                format!("failed to format via {}", formatter.name())
+                            })?,
+                        ));
+                    } else if let Some(operation) = prettier {
                         format_operation = Some(operation);
                     } else if let Some((language_server, buffer_abs_path)) = server_and_buffer {
                         format_operation = Some(FormatOperation::Lsp(
@@ -4977,6 +5439,9 @@ impl Project {
                         FormatOperation::Prettier(diff) => {
                             b.apply_diff(diff, cx);
                         }
+                        FormatOperation::Formatter(edits) => {
+                            b.edit(edits, None, cx);
+                        }
                     }
 
                     if let Some(transaction_id) = whitespace_transaction_id {
@@ -5048,6 +5513,37 @@ impl Project {
         }
     }
 
+    async fn format_via_formatter(
+        buffer: &Model<Buffer>,
+        abs_path: &Path,
+        formatter: &Arc<dyn BufferFormatter>,
+        settings: &LanguageSettings,
+        cx: &mut AsyncAppContext,
+    ) -> Result<Vec<(Range<Anchor>, String)>> {
+        let snapshot = buffer.update(cx, |buffer, _| buffer.snapshot())?;
+        let options = FormattingOptions {
+            tab_size: settings.tab_size.get(),
+            hard_tabs: settings.hard_tabs,
+        };
+        let edits = cx
+            .update(|cx| formatter.format(snapshot.text(), abs_path, options, cx))?
+            .await?;
+
+        // Anchor the edits in the formatted snapshot, so that they still apply
+        // if the buffer was edited while it was being formatted.
+        Ok(edits
+            .into_iter()
+            .map(|(range, new_text)| {
+                let start = snapshot.clip_offset(range.start, Bias::Left);
+                let end = snapshot.clip_offset(range.end, Bias::Right);
+                (
+                    snapshot.anchor_before(start)..snapshot.anchor_after(end),
+                    new_text,
+                )
+            })
+            .collect())
+    }
+
     async fn format_via_external_command(
         buffer: &Model<Buffer>,
         buffer_abs_path: Option<&Path>,
@@ -5672,41 +6168,181 @@ impl Project {
         self.linked_edit_impl(buffer, position, cx)
     }
 
-    #[inline(never)]
-    fn completions_impl(
+    /// Requests the semantic tokens of the whole buffer from the first language server that provides them.
+    pub fn semantic_tokens(
         &self,
         buffer: &Model<Buffer>,
-        position: PointUtf16,
-        context: CompletionContext,
         cx: &mut ModelContext<Self>,
-    ) -> Task<Result<Vec<Completion>>> {
-        let language_registry = self.languages.clone();
+    ) -> Task<Result<Vec<SemanticToken>>> {
+        let Some(server_id) = self
+            .language_servers_for_buffer(buffer.read(cx), cx)
+            .find(|(_, server)| server.capabilities().semantic_tokens_provider.is_some())
+            .map(|(_, server)| LanguageServerToQuery::Other(server.server_id()))
+            .or_else(|| self.is_remote().then_some(LanguageServerToQuery::Primary))
+        else {
+            return Task::ready(Ok(Vec::new()));
+        };
 
-        if self.is_local() {
-            let snapshot = buffer.read(cx).snapshot();
-            let offset = position.to_offset(&snapshot);
-            let scope = snapshot.language_scope_at(offset);
-            let language = snapshot.language().cloned();
+        self.request_lsp(buffer.clone(), server_id, GetSemanticTokens, cx)
+    }
 
-            let server_ids: Vec<_> = self
-                .language_servers_for_buffer(buffer.read(cx), cx)
-                .filter(|(_, server)| server.capabilities().completion_provider.is_some())
-                .filter(|(adapter, _)| {
-                    scope
-                        .as_ref()
-                        .map(|scope| scope.language_allowed(&adapter.name))
-                        .unwrap_or(true)
-                })
-                .map(|(_, server)| server.server_id())
-                .collect();
+    /// Requests the code lenses of the whole buffer from the first language server that provides them.
+    pub fn code_lens(
+        &self,
+        buffer: &Model<Buffer>,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<Vec<CodeLens>>> {
+        let Some(server_id) = self
+            .language_servers_for_buffer(buffer.read(cx), cx)
+            .find(|(_, server)| server.capabilities().code_lens_provider.is_some())
+            .map(|(_, server)| LanguageServerToQuery::Other(server.server_id()))
+            .or_else(|| self.is_remote().then_some(LanguageServerToQuery::Primary))
+        else {
+            return Task::ready(Ok(Vec::new()));
+        };
 
-            let buffer = buffer.clone();
-            cx.spawn(move |this, mut cx| async move {
-                let mut tasks = Vec::with_capacity(server_ids.len());
-                this.update(&mut cx, |this, cx| {
-                    for server_id in server_ids {
-                        let lsp_adapter = this.language_server_adapter_for_id(server_id);
-                        tasks.push((
+        self.request_lsp(buffer.clone(), server_id, GetCodeLens, cx)
+    }
+
+    /// Resolves the call hierarchy items for the symbol at the given position.
+    pub fn prepare_call_hierarchy<T: ToPointUtf16>(
+        &self,
+        buffer: &Model<Buffer>,
+        position: T,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<Vec<HierarchyItem>>> {
+        let position = position.to_point_utf16(buffer.read(cx));
+        self.request_lsp(
+            buffer.clone(),
+            LanguageServerToQuery::Primary,
+            PrepareCallHierarchy { position },
+            cx,
+        )
+    }
+
+    /// Returns the callers of an item returned by [`Project::prepare_call_hierarchy`].
+    pub fn incoming_calls(
+        &self,
+        item: &HierarchyItem,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<Vec<HierarchyCall>>> {
+        let lsp_item = match serde_json::from_value(item.lsp_item.clone()) {
+            Ok(lsp_item) => lsp_item,
+            Err(error) => return Task::ready(Err(error.into())),
+        };
+        self.request_lsp(
+            item.location.buffer.clone(),
+            LanguageServerToQuery::Other(item.language_server_id),
+            CallHierarchyIncomingCalls { item: lsp_item },
+            cx,
+        )
+    }
+
+    /// Returns the callees of an item returned by [`Project::prepare_call_hierarchy`].
+    pub fn outgoing_calls(
+        &self,
+        item: &HierarchyItem,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<Vec<HierarchyCall>>> {
+        let lsp_item = match serde_json::from_value(item.lsp_item.clone()) {
+            Ok(lsp_item) => lsp_item,
+            Err(error) => return Task::ready(Err(error.into())),
+        };
+        self.request_lsp(
+            item.location.buffer.clone(),
+            LanguageServerToQuery::Other(item.language_server_id),
+            CallHierarchyOutgoingCalls { item: lsp_item },
+            cx,
+        )
+    }
+
+    /// Resolves the type hierarchy items for the symbol at the given position.
+    pub fn prepare_type_hierarchy<T: ToPointUtf16>(
+        &self,
+        buffer: &Model<Buffer>,
+        position: T,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<Vec<HierarchyItem>>> {
+        let position = position.to_point_utf16(buffer.read(cx));
+        self.request_lsp(
+            buffer.clone(),
+            LanguageServerToQuery::Primary,
+            PrepareTypeHierarchy { position },
+            cx,
+        )
+    }
+
+    /// Returns the supertypes of an item returned by [`Project::prepare_type_hierarchy`].
+    pub fn supertypes(
+        &self,
+        item: &HierarchyItem,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<Vec<HierarchyItem>>> {
+        let lsp_item = match serde_json::from_value(item.lsp_item.clone()) {
+            Ok(lsp_item) => lsp_item,
+            Err(error) => return Task::ready(Err(error.into())),
+        };
+        self.request_lsp(
+            item.location.buffer.clone(),
+            LanguageServerToQuery::Other(item.language_server_id),
+            TypeHierarchySupertypes { item: lsp_item },
+            cx,
+        )
+    }
+
+    /// Returns the subtypes of an item returned by [`Project::prepare_type_hierarchy`].
+    pub fn subtypes(
+        &self,
+        item: &HierarchyItem,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<Vec<HierarchyItem>>> {
+        let lsp_item = match serde_json::from_value(item.lsp_item.clone()) {
+            Ok(lsp_item) => lsp_item,
+            Err(error) => return Task::ready(Err(error.into())),
+        };
+        self.request_lsp(
+            item.location.buffer.clone(),
+            LanguageServerToQuery::Other(item.language_server_id),
+            TypeHierarchySubtypes { item: lsp_item },
+            cx,
+        )
+    }
+
+    #[inline(never)]
+    fn completions_impl(
+        &self,
+        buffer: &Model<Buffer>,
+        position: PointUtf16,
+        context: CompletionContext,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<Vec<Completion>>> {
+        let language_registry = self.languages.clone();
+
+        if self.is_local() {
+            let snapshot = buffer.read(cx).snapshot();
+            let offset = position.to_offset(&snapshot);
+            let scope = snapshot.language_scope_at(offset);
+            let language = snapshot.language().cloned();
+
+            let server_ids: Vec<_> = self
+                .language_servers_for_buffer(buffer.read(cx), cx)
+                .filter(|(_, server)| server.capabilities().completion_provider.is_some())
+                .filter(|(adapter, _)| {
+                    scope
+                        .as_ref()
+                        .map(|scope| scope.language_allowed(&adapter.name))
+                        .unwrap_or(true)
+                })
+                .map(|(_, server)| server.server_id())
+                .collect();
+
+            let buffer = buffer.clone();
+            cx.spawn(move |this, mut cx| async move {
+                let mut tasks = Vec::with_capacity(server_ids.len());
+                this.update(&mut cx, |this, cx| {
+                    for server_id in server_ids {
+                        let lsp_adapter = this.language_server_adapter_for_id(server_id);
+                        tasks.push((
                             lsp_adapter,
                             this.request_lsp(
                                 buffer.clone(),
@@ -6284,6 +6920,66 @@ impl Project {
         }
     }
 
+    /// Runs the command of a code lens through `workspace/executeCommand`,
+    /// returning the edits the language server applied while executing it.
+    pub fn execute_code_lens(
+        &self,
+        buffer_handle: Model<Buffer>,
+        lens: CodeLens,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<ProjectTransaction>> {
+        let Some(command) = lens.lsp_lens.command.clone() else {
+            return Task::ready(Err(anyhow!("code lens has no command")));
+        };
+        if self.is_local() {
+            let buffer = buffer_handle.read(cx);
+            let lang_server = if let Some((_, server)) =
+                self.language_server_for_buffer(buffer, lens.server_id, cx)
+            {
+                server.clone()
+            } else {
+                return Task::ready(Ok(Default::default()));
+            };
+            cx.spawn(move |this, mut cx| async move {
+                this.update(&mut cx, |this, _| {
+                    this.last_workspace_edits_by_language_server
+                        .remove(&lang_server.server_id());
+                })?;
+
+                lang_server
+                    .request::<lsp::request::ExecuteCommand>(lsp::ExecuteCommandParams {
+                        command: command.command,
+                        arguments: command.arguments.unwrap_or_default(),
+                        ..Default::default()
+                    })
+                    .await?;
+
+                this.update(&mut cx, |this, _| {
+                    this.last_workspace_edits_by_language_server
+                        .remove(&lang_server.server_id())
+                        .unwrap_or_default()
+                })
+            })
+        } else if let Some(project_id) = self.remote_id() {
+            let client = self.client.clone();
+            let request = proto::ExecuteCodeLens {
+                project_id,
+                buffer_id: buffer_handle.read(cx).remote_id().into(),
+                lens: Some(Self::serialize_code_lens(&lens)),
+            };
+            cx.spawn(move |this, cx| async move {
+                let response = client
+                    .request(request)
+                    .await?
+                    .transaction
+                    .ok_or_else(|| anyhow!("missing transaction"))?;
+                Self::deserialize_project_transaction(this, response, true, cx).await
+            })
+        } else {
+            Task::ready(Err(anyhow!("project does not have a remote id")))
+        }
+    }
+
     fn apply_on_type_formatting(
         &self,
         buffer: Model<Buffer>,
@@ -7545,6 +8241,8 @@ impl Project {
         self.task_inventory().update(cx, |inventory, _| {
             inventory.remove_worktree_sources(id_to_remove);
         });
+        self.task_provider_sources
+            .retain(|(_, worktree_id), _| *worktree_id != id_to_remove);
 
         self.worktrees.retain(|worktree| {
             if let Some(worktree) = worktree.upgrade() {
@@ -7564,6 +8262,11 @@ impl Project {
     }
 
     fn add_worktree(&mut self, worktree: &Model<Worktree>, cx: &mut ModelContext<Self>) {
+        worktree.update(cx, |worktree, _| {
+            if let Some(worktree) = worktree.as_local_mut() {
+                worktree.set_git_diff_base(self.git_diff_base.clone());
+            }
+        });
         cx.observe(worktree, |_, _, cx| cx.notify()).detach();
         cx.subscribe(worktree, |this, worktree, event, cx| {
             let is_local = worktree.read(cx).is_local();
@@ -7574,6 +8277,15 @@ impl Project {
                         this.update_local_worktree_language_servers(&worktree, changes, cx);
                         this.update_local_worktree_settings(&worktree, changes, cx);
                         this.update_prettier_settings(&worktree, changes, cx);
+                        // Task providers mostly read the files at the root of a worktree,
+                        // like its `package.json` or `Makefile`.
+                        if changes
+                            .iter()
+                            .any(|(path, _, _)| path.components().count() == 1)
+                        {
+                            let worktree_id = worktree.read(cx).id();
+                            this.refresh_provided_tasks(Some(worktree_id), cx);
+                        }
                     }
 
                     cx.emit(Event::WorktreeUpdatedEntries(
@@ -7588,9 +8300,13 @@ impl Project {
                 }
                 worktree::Event::UpdatedGitRepositories(updated_repos) => {
                     if is_local {
+                        let work_directories = updated_repos
+                            .iter()
+                            .map(|(work_directory, _)| work_directory.clone())
+                            .collect::<Vec<_>>();
                         this.update_local_worktree_buffers_git_repos(
                             worktree.clone(),
-                            updated_repos,
+                            &work_directories,
                             cx,
                         )
                     }
@@ -7729,10 +8445,11 @@ impl Project {
         }
     }
 
+    /// Reloads the diff bases of the buffers in the repositories with the given work directories.
     fn update_local_worktree_buffers_git_repos(
         &mut self,
         worktree_handle: Model<Worktree>,
-        changed_repos: &UpdatedGitRepositoriesSet,
+        changed_work_directories: &[Arc<Path>],
         cx: &mut ModelContext<Self>,
     ) {
         debug_assert!(worktree_handle.read(cx).is_local());
@@ -7747,9 +8464,9 @@ impl Project {
                     return None;
                 }
                 let path = &project_path.path;
-                changed_repos
+                changed_work_directories
                     .iter()
-                    .find(|(work_dir, _)| path.starts_with(work_dir))?;
+                    .find(|work_dir| path.starts_with(work_dir))?;
                 let path = path.clone();
                 let abs_path = worktree_handle.read(cx).absolutize(&path).ok()?;
                 Some(async move {
@@ -7772,9 +8489,9 @@ impl Project {
                     return None;
                 }
                 let path = file.path();
-                changed_repos
+                changed_work_directories
                     .iter()
-                    .find(|(work_dir, _)| path.starts_with(work_dir))?;
+                    .find(|work_dir| path.starts_with(work_dir))?;
                 Some((buffer, path.clone(), file.abs_path(cx)))
             })
             .collect::<Vec<_>>();
@@ -7791,8 +8508,10 @@ impl Project {
             let future_buffers = future_buffers.collect::<Vec<_>>().await;
 
             // Reload the diff base for every buffer whose containing git repository has changed.
-            let snapshot =
-                worktree_handle.update(&mut cx, |tree, _| tree.as_local().unwrap().snapshot())?;
+            let (snapshot, git_diff_base) = worktree_handle.update(&mut cx, |tree, _| {
+                let tree = tree.as_local().unwrap();
+                (tree.snapshot(), tree.git_diff_base().clone())
+            })?;
             let diff_bases_by_buffer = cx
                 .background_executor()
                 .spawn(async move {
@@ -7807,6 +8526,7 @@ impl Project {
                         .map(|(buffer, path, abs_path, repo, local_repo_entry)| {
                             let fs = fs.clone();
                             let snapshot = snapshot.clone();
+                            let git_diff_base = git_diff_base.clone();
                             async move {
                                 let abs_path_metadata = fs
                                     .metadata(&abs_path)
@@ -7822,7 +8542,8 @@ impl Project {
                                     None
                                 } else {
                                     let relative_path = repo.relativize(&snapshot, &path).ok()?;
-                                    local_repo_entry.repo().load_index_text(&relative_path)
+                                    git_diff_base
+                                        .load_text(local_repo_entry.repo().as_ref(), &relative_path)
                                 };
                                 Some((buffer, base_text))
                             }
@@ -8243,9 +8964,718 @@ impl Project {
                     })
                     .await?;
 
-                Ok(deserialize_blame_buffer_response(response))
-            })
+                Ok(deserialize_blame_buffer_response(response))
+            })
+        }
+    }
+
+    /// Lists the files of the visible worktrees that have unresolved merge conflicts.
+    pub fn conflicted_paths(&self, cx: &AppContext) -> Vec<ProjectPath> {
+        self.visible_worktrees(cx)
+            .flat_map(|worktree| {
+                let worktree = worktree.read(cx);
+                let worktree_id = worktree.id();
+                worktree
+                    .files(false, 0)
+                    .filter(|entry| entry.git_status == Some(GitFileStatus::Conflict))
+                    .map(move |entry| ProjectPath {
+                        worktree_id,
+                        path: entry.path.clone(),
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect()
+    }
+
+    /// Lists the changed files of the repository whose work directory is at the given path,
+    /// with their paths relative to the worktree. Files outside of the worktree are omitted.
+    pub fn git_status(
+        &self,
+        work_directory: ProjectPath,
+        cx: &AppContext,
+    ) -> Task<Result<Vec<GitStatusEntry>>> {
+        if self.is_local() {
+            let status_params = maybe!({
+                let worktree = self
+                    .worktree_for_id(work_directory.worktree_id, cx)
+                    .context("failed to get worktree")?
+                    .read(cx)
+                    .as_local()
+                    .context("worktree was not local")?
+                    .snapshot();
+                let (repo_entry, local_repo_entry) = worktree
+                    .repo_for_path(&work_directory.path)
+                    .ok_or(NoRepositoryError {})?;
+                let repo = local_repo_entry.repo().clone();
+                anyhow::Ok((worktree, repo_entry, repo))
+            });
+
+            cx.background_executor().spawn(async move {
+                let (worktree, repo_entry, repo) = status_params?;
+                let status = repo.statuses(Path::new(""))?;
+                Ok(status
+                    .staging
+                    .iter()
+                    .filter_map(|(repo_path, status)| {
+                        Some(GitStatusEntry {
+                            path: repo_entry.unrelativize(&worktree, repo_path)?,
+                            status: *status,
+                        })
+                    })
+                    .collect())
+            })
+        } else {
+            let project_id = self.remote_id();
+            let client = self.client.clone();
+
+            cx.spawn(|_| async move {
+                let project_id = project_id.context("unable to get project id")?;
+                let response = client
+                    .request(proto::GetGitStatus {
+                        project_id,
+                        worktree_id: work_directory.worktree_id.to_proto(),
+                        work_directory: work_directory.path.to_string_lossy().into(),
+                    })
+                    .await?;
+
+                Ok(response
+                    .entries
+                    .into_iter()
+                    .map(deserialize_git_status_entry)
+                    .collect())
+            })
+        }
+    }
+
+    /// Adds the current contents of the given files to their repositories' index.
+    pub fn stage_paths(&self, paths: Vec<ProjectPath>, cx: &AppContext) -> Task<Result<()>> {
+        self.update_git_index(paths, true, cx)
+    }
+
+    /// Resets the given files in their repositories' index to their committed contents.
+    pub fn unstage_paths(&self, paths: Vec<ProjectPath>, cx: &AppContext) -> Task<Result<()>> {
+        self.update_git_index(paths, false, cx)
+    }
+
+    fn update_git_index(
+        &self,
+        paths: Vec<ProjectPath>,
+        stage: bool,
+        cx: &AppContext,
+    ) -> Task<Result<()>> {
+        if self.is_local() {
+            let repositories = maybe!({
+                let mut repositories = HashMap::default();
+                for project_path in &paths {
+                    let worktree = self
+                        .worktree_for_id(project_path.worktree_id, cx)
+                        .context("failed to get worktree")?
+                        .read(cx)
+                        .as_local()
+                        .context("worktree was not local")?;
+                    let (repo_entry, local_repo_entry) = worktree
+                        .repo_for_path(&project_path.path)
+                        .ok_or(NoRepositoryError {})?;
+                    let repo_path = repo_entry
+                        .relativize(worktree, &project_path.path)
+                        .context("failed to relativize path")?;
+                    repositories
+                        .entry((project_path.worktree_id, repo_entry.work_directory_id()))
+                        .or_insert_with(|| (local_repo_entry.repo().clone(), Vec::new()))
+                        .1
+                        .push(repo_path);
+                }
+                anyhow::Ok(repositories)
+            });
+
+            cx.background_executor().spawn(async move {
+                for (_, (repo, repo_paths)) in repositories? {
+                    if stage {
+                        repo.stage_paths(&repo_paths)?;
+                    } else {
+                        repo.unstage_paths(&repo_paths)?;
+                    }
+                }
+                Ok(())
+            })
+        } else {
+            let project_id = self.remote_id();
+            let client = self.client.clone();
+            let mut paths_by_worktree = HashMap::<WorktreeId, Vec<String>>::default();
+            for project_path in paths {
+                paths_by_worktree
+                    .entry(project_path.worktree_id)
+                    .or_default()
+                    .push(project_path.path.to_string_lossy().into());
+            }
+
+            cx.spawn(|_| async move {
+                let project_id = project_id.context("unable to get project id")?;
+                for (worktree_id, paths) in paths_by_worktree {
+                    let worktree_id = worktree_id.to_proto();
+                    if stage {
+                        client
+                            .request(proto::StageGitPaths {
+                                project_id,
+                                worktree_id,
+                                paths,
+                            })
+                            .await?;
+                    } else {
+                        client
+                            .request(proto::UnstageGitPaths {
+                                project_id,
+                                worktree_id,
+                                paths,
+                            })
+                            .await?;
+                    }
+                }
+                Ok(())
+            })
+        }
+    }
+
+    /// Stages a single hunk of the buffer, replacing the given range of its diff base, which
+    /// is the file's contents in the index, with the new text.
+    pub fn stage_hunk(
+        &self,
+        buffer: &Model<Buffer>,
+        diff_base_range: Range<usize>,
+        new_text: String,
+        cx: &AppContext,
+    ) -> Task<Result<()>> {
+        if self.is_local() {
+            let stage_params = self.git_repository_for_buffer(buffer, cx);
+
+            cx.background_executor().spawn(async move {
+                let (repo, relative_path) = stage_params?;
+                repo.stage_hunk(&relative_path, diff_base_range, &new_text)
+                    .with_context(|| format!("Failed to stage hunk in {:?}", relative_path.0))
+            })
+        } else {
+            let project_id = self.remote_id();
+            let buffer_id = buffer.read(cx).remote_id();
+            let client = self.client.clone();
+
+            cx.spawn(|_| async move {
+                let project_id = project_id.context("unable to get project id for buffer")?;
+                client
+                    .request(proto::StageGitHunk {
+                        project_id,
+                        buffer_id: buffer_id.into(),
+                        diff_base_start: diff_base_range.start as u64,
+                        diff_base_end: diff_base_range.end as u64,
+                        new_text,
+                    })
+                    .await?;
+                Ok(())
+            })
+        }
+    }
+
+    /// Unstages the staged changes of the buffer's file that touch the given range of its
+    /// diff base, resetting them in the index to their committed contents.
+    pub fn unstage_hunk(
+        &self,
+        buffer: &Model<Buffer>,
+        diff_base_range: Range<usize>,
+        cx: &AppContext,
+    ) -> Task<Result<()>> {
+        if self.is_local() {
+            let unstage_params = self.git_repository_for_buffer(buffer, cx);
+
+            cx.background_executor().spawn(async move {
+                let (repo, relative_path) = unstage_params?;
+                repo.unstage_hunk(&relative_path, diff_base_range)
+                    .with_context(|| format!("Failed to unstage hunk in {:?}", relative_path.0))
+            })
+        } else {
+            let project_id = self.remote_id();
+            let buffer_id = buffer.read(cx).remote_id();
+            let client = self.client.clone();
+
+            cx.spawn(|_| async move {
+                let project_id = project_id.context("unable to get project id for buffer")?;
+                client
+                    .request(proto::UnstageGitHunk {
+                        project_id,
+                        buffer_id: buffer_id.into(),
+                        diff_base_start: diff_base_range.start as u64,
+                        diff_base_end: diff_base_range.end as u64,
+                    })
+                    .await?;
+                Ok(())
+            })
+        }
+    }
+
+    fn git_repository_for_buffer(
+        &self,
+        buffer: &Model<Buffer>,
+        cx: &AppContext,
+    ) -> Result<(Arc<dyn GitRepository>, RepoPath)> {
+        let buffer_project_path = buffer
+            .read(cx)
+            .project_path(cx)
+            .context("failed to get buffer project path")?;
+        let worktree = self
+            .worktree_for_id(buffer_project_path.worktree_id, cx)
+            .context("failed to get worktree")?
+            .read(cx)
+            .as_local()
+            .context("worktree was not local")?;
+        anyhow::ensure!(
+            *worktree.git_diff_base() == DiffBase::Index,
+            "hunks can only be staged while diffing against the index"
+        );
+        let (repo_entry, local_repo_entry) = worktree
+            .repo_for_path(&buffer_project_path.path)
+            .ok_or(NoRepositoryError {})?;
+        let relative_path = repo_entry
+            .relativize(worktree, &buffer_project_path.path)
+            .context("failed to relativize buffer path")?;
+        Ok((local_repo_entry.repo().clone(), relative_path))
+    }
+
+    /// Commits the staged changes of the repository whose work directory is at the given path.
+    /// When amending, the message may be empty to keep the message of the amended commit.
+    pub fn git_commit(
+        &self,
+        work_directory: ProjectPath,
+        message: String,
+        amend: bool,
+        cx: &AppContext,
+    ) -> Task<Result<()>> {
+        if self.is_local() {
+            let repo = maybe!({
+                let worktree = self
+                    .worktree_for_id(work_directory.worktree_id, cx)
+                    .context("failed to get worktree")?
+                    .read(cx)
+                    .as_local()
+                    .context("worktree was not local")?;
+                let (_, local_repo_entry) = worktree
+                    .repo_for_path(&work_directory.path)
+                    .ok_or(NoRepositoryError {})?;
+                anyhow::Ok(local_repo_entry.repo().clone())
+            });
+
+            cx.background_executor()
+                .spawn(async move { repo?.commit(&message, amend) })
+        } else {
+            let project_id = self.remote_id();
+            let client = self.client.clone();
+
+            cx.spawn(|_| async move {
+                let project_id = project_id.context("unable to get project id")?;
+                client
+                    .request(proto::GitCommit {
+                        project_id,
+                        worktree_id: work_directory.worktree_id.to_proto(),
+                        work_directory: work_directory.path.to_string_lossy().into(),
+                        message,
+                        amend,
+                    })
+                    .await?;
+                Ok(())
+            })
+        }
+    }
+
+    /// The text that buffers are diffed against, see [`Project::set_git_diff_base`].
+    pub fn git_diff_base(&self) -> &DiffBase {
+        &self.git_diff_base
+    }
+
+    /// Changes the text that the buffers of local worktrees are diffed against, and reloads
+    /// the diff bases of the open buffers. The diff bases of a remote project's buffers are
+    /// chosen by its host.
+    pub fn set_git_diff_base(&mut self, git_diff_base: DiffBase, cx: &mut ModelContext<Self>) {
+        if self.git_diff_base == git_diff_base {
+            return;
+        }
+        self.git_diff_base = git_diff_base.clone();
+        for worktree in self.worktrees().collect::<Vec<_>>() {
+            let work_directories = worktree.update(cx, |worktree, _| {
+                let worktree = worktree.as_local_mut()?;
+                worktree.set_git_diff_base(git_diff_base.clone());
+                Some(
+                    worktree
+                        .repositories()
+                        .map(|(work_directory, _)| work_directory.clone())
+                        .collect::<Vec<_>>(),
+                )
+            });
+            if let Some(work_directories) = work_directories {
+                self.update_local_worktree_buffers_git_repos(worktree, &work_directories, cx);
+            }
+        }
+        cx.emit(Event::GitDiffBaseChanged);
+    }
+
+    /// Lists the files of all repositories whose contents differ from the diff base.
+    pub fn git_changed_paths(&self, cx: &AppContext) -> Task<Result<Vec<ProjectPath>>> {
+        if self.is_local() {
+            let repositories = self
+                .worktrees()
+                .filter_map(|worktree| {
+                    let worktree = worktree.read(cx).as_local()?;
+                    let snapshot = worktree.snapshot();
+                    let repositories = worktree
+                        .repositories()
+                        .filter_map(|(_, repo_entry)| {
+                            let local_repo_entry = worktree.get_local_repo(repo_entry)?;
+                            Some((repo_entry.clone(), local_repo_entry.repo().clone()))
+                        })
+                        .collect::<Vec<_>>();
+                    Some((snapshot, worktree.git_diff_base().clone(), repositories))
+                })
+                .collect::<Vec<_>>();
+
+            cx.background_executor().spawn(async move {
+                let mut changed_paths = Vec::new();
+                for (snapshot, git_diff_base, repositories) in repositories {
+                    for (repo_entry, repo) in repositories {
+                        let revision = git_diff_base.resolve(repo.as_ref())?;
+                        let paths = repo.changed_paths(revision.as_deref())?;
+                        changed_paths.extend(paths.iter().filter_map(|repo_path| {
+                            Some(ProjectPath {
+                                worktree_id: snapshot.id(),
+                                path: repo_entry.unrelativize(&snapshot, repo_path)?,
+                            })
+                        }));
+                    }
+                }
+                Ok(changed_paths)
+            })
+        } else {
+            let project_id = self.remote_id();
+            let client = self.client.clone();
+
+            cx.spawn(|_| async move {
+                let project_id = project_id.context("unable to get project id")?;
+                let response = client
+                    .request(proto::GetGitChangedPaths { project_id })
+                    .await?;
+                Ok(response
+                    .paths
+                    .into_iter()
+                    .map(|path| ProjectPath {
+                        worktree_id: WorktreeId::from_proto(path.worktree_id),
+                        path: PathBuf::from(path.path).into(),
+                    })
+                    .collect())
+            })
+        }
+    }
+
+    /// Finds the repository containing a path of a local worktree, and the path relative to
+    /// it. The relative path is `None` for the repository's work directory.
+    fn local_repo_for_path(
+        &self,
+        path: &ProjectPath,
+        cx: &AppContext,
+    ) -> Result<(Arc<dyn GitRepository>, Option<RepoPath>)> {
+        let worktree = self
+            .worktree_for_id(path.worktree_id, cx)
+            .context("failed to get worktree")?
+            .read(cx)
+            .as_local()
+            .context("worktree was not local")?
+            .snapshot();
+        let (repo_entry, local_repo_entry) = worktree
+            .repo_for_path(&path.path)
+            .ok_or(NoRepositoryError {})?;
+        let relative_path = repo_entry
+            .relativize(&worktree, &path.path)
+            .context("failed to relativize path")?;
+        let relative_path = (!relative_path.0.as_os_str().is_empty()).then_some(relative_path);
+        Ok((local_repo_entry.repo().clone(), relative_path))
+    }
+
+    /// Lists the last `limit` commits of the repository containing a path, newest first. Unless
+    /// the path is the repository's work directory, only the commits that changed it are listed.
+    pub fn git_log(&self, path: ProjectPath, limit: usize, cx: &AppContext) -> Task<Result<Log>> {
+        if self.is_local() {
+            let repo = self.local_repo_for_path(&path, cx);
+            cx.background_executor().spawn(async move {
+                let (repo, relative_path) = repo?;
+                repo.log(relative_path.as_ref().map(|path| path.as_path()), limit)
+            })
+        } else {
+            let project_id = self.remote_id();
+            let client = self.client.clone();
+
+            cx.spawn(|_| async move {
+                let project_id = project_id.context("unable to get project id")?;
+                let response = client
+                    .request(proto::GetGitLog {
+                        project_id,
+                        worktree_id: path.worktree_id.to_proto(),
+                        path: path.path.to_string_lossy().into(),
+                        limit: limit as u32,
+                    })
+                    .await?;
+                Ok(deserialize_git_log_response(response))
+            })
+        }
+    }
+
+    /// Lists the files changed by a commit of the repository containing a path, with their
+    /// contents before and after the commit. Unless the path is the repository's work
+    /// directory, only the changes of that path are listed.
+    pub fn git_commit_changes(
+        &self,
+        path: ProjectPath,
+        sha: git::Oid,
+        cx: &AppContext,
+    ) -> Task<Result<Vec<CommitFileChange>>> {
+        if self.is_local() {
+            let repo = self.local_repo_for_path(&path, cx);
+            cx.background_executor().spawn(async move {
+                let (repo, relative_path) = repo?;
+                repo.commit_changes(
+                    &sha.to_string(),
+                    relative_path.as_ref().map(|path| path.as_path()),
+                )
+            })
+        } else {
+            let project_id = self.remote_id();
+            let client = self.client.clone();
+
+            cx.spawn(|_| async move {
+                let project_id = project_id.context("unable to get project id")?;
+                let response = client
+                    .request(proto::GetGitCommitChanges {
+                        project_id,
+                        worktree_id: path.worktree_id.to_proto(),
+                        path: path.path.to_string_lossy().into(),
+                        sha: sha.as_bytes().into(),
+                    })
+                    .await?;
+                Ok(response
+                    .changes
+                    .into_iter()
+                    .map(|change| CommitFileChange {
+                        path: RepoPath::new(PathBuf::from(change.path)),
+                        old_text: change.old_text,
+                        new_text: change.new_text,
+                    })
+                    .collect())
+            })
+        }
+    }
+
+    /// Lists the stash of the repository whose work directory is at the given path.
+    pub fn git_stashes(
+        &self,
+        work_directory: ProjectPath,
+        cx: &AppContext,
+    ) -> Task<Result<Vec<Stash>>> {
+        if !self.is_local() {
+            return Task::ready(Err(anyhow!(
+                "git stash is only supported in local projects"
+            )));
+        }
+        let repo = self.local_repo_for_path(&work_directory, cx);
+        cx.background_executor()
+            .spawn(async move { repo?.0.stashes() })
+    }
+
+    /// Stashes the changes of the repository whose work directory is at the given path.
+    pub fn git_stash(
+        &mut self,
+        work_directory: ProjectPath,
+        message: Option<String>,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<()>> {
+        self.run_git_operation(&work_directory, "git stash", cx, move |repo, _, _| {
+            repo.stash(message.as_deref())
+        })
+    }
+
+    pub fn git_apply_stash(
+        &mut self,
+        work_directory: ProjectPath,
+        index: usize,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<()>> {
+        self.run_git_operation(&work_directory, "git stash apply", cx, move |repo, _, _| {
+            repo.apply_stash(index)
+        })
+    }
+
+    pub fn git_pop_stash(
+        &mut self,
+        work_directory: ProjectPath,
+        index: usize,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<()>> {
+        self.run_git_operation(&work_directory, "git stash pop", cx, move |repo, _, _| {
+            repo.pop_stash(index)
+        })
+    }
+
+    pub fn git_drop_stash(
+        &mut self,
+        work_directory: ProjectPath,
+        index: usize,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<()>> {
+        self.run_git_operation(&work_directory, "git stash drop", cx, move |repo, _, _| {
+            repo.drop_stash(index)
+        })
+    }
+
+    /// Fetches the remote of the repository whose work directory is at the given path.
+    /// When git has no credentials for the remote, they are requested with
+    /// [`Event::GitCredentialsPrompt`].
+    pub fn git_fetch(
+        &mut self,
+        work_directory: ProjectPath,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<()>> {
+        self.run_git_operation(
+            &work_directory,
+            "git fetch",
+            cx,
+            |repo, credentials, progress| repo.fetch(credentials, progress),
+        )
+    }
+
+    pub fn git_pull(
+        &mut self,
+        work_directory: ProjectPath,
+        mode: PullMode,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<()>> {
+        self.run_git_operation(
+            &work_directory,
+            "git pull",
+            cx,
+            move |repo, credentials, progress| repo.pull(mode, credentials, progress),
+        )
+    }
+
+    pub fn git_push(
+        &mut self,
+        work_directory: ProjectPath,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<()>> {
+        self.run_git_operation(
+            &work_directory,
+            "git push",
+            cx,
+            |repo, credentials, progress| repo.push(credentials, progress),
+        )
+    }
+
+    /// Runs a git operation on the background executor, one at a time, tracking its progress
+    /// in [`Project::git_operation`] and its failure in [`Project::last_git_operation_failure`].
+    fn run_git_operation(
+        &mut self,
+        work_directory: &ProjectPath,
+        title: &str,
+        cx: &mut ModelContext<Self>,
+        operation: impl Fn(
+                &dyn GitRepository,
+                Option<&RemoteCredentials>,
+                &dyn Fn(RemoteProgress),
+            ) -> Result<()>
+            + Send
+            + Sync
+            + 'static,
+    ) -> Task<Result<()>> {
+        if let Some(running) = &self.git_operation {
+            return Task::ready(Err(anyhow!("{} is still running", running.title)));
         }
+        let repo = if !self.is_local() {
+            Err(anyhow!("{title} is only supported in local projects"))
+        } else {
+            self.local_repo_for_path(work_directory, cx)
+                .map(|(repo, _)| repo)
+        };
+        let repo = match repo {
+            Ok(repo) => repo,
+            Err(error) => {
+                self.last_git_operation_failure = Some(GitOperationFailure {
+                    title: title.to_string(),
+                    error: format!("{error:#}"),
+                });
+                cx.notify();
+                return Task::ready(Err(error));
+            }
+        };
+
+        let title = title.to_string();
+        self.git_operation = Some(GitOperation {
+            title: title.clone(),
+            progress: None,
+        });
+        self.last_git_operation_failure = None;
+        cx.notify();
+
+        let operation = Arc::new(operation);
+        cx.spawn(|this, mut cx| async move {
+            let mut credentials = None;
+            let result = loop {
+                let (progress_tx, mut progress_rx) = mpsc::unbounded();
+                let task = cx.background_executor().spawn({
+                    let repo = repo.clone();
+                    let operation = operation.clone();
+                    let credentials = credentials.clone();
+                    async move {
+                        operation(repo.as_ref(), credentials.as_ref(), &|progress| {
+                            progress_tx.unbounded_send(progress).ok();
+                        })
+                    }
+                });
+                while let Some(progress) = progress_rx.next().await {
+                    this.update(&mut cx, |this, cx| {
+                        if let Some(git_operation) = this.git_operation.as_mut() {
+                            git_operation.progress = Some(progress);
+                            cx.notify();
+                        }
+                    })?;
+                }
+
+                let error = match task.await {
+                    Ok(()) => break Ok(()),
+                    Err(error) => error,
+                };
+                let Some(url) = error
+                    .downcast_ref::<AuthenticationRequired>()
+                    .map(|error| error.url.clone())
+                else {
+                    break Err(error);
+                };
+                let (tx, rx) = smol::channel::bounded(1);
+                this.update(&mut cx, |_, cx| {
+                    cx.emit(Event::GitCredentialsPrompt(GitCredentialsRequest {
+                        operation: title.clone(),
+                        url,
+                        response_channel: tx,
+                    }));
+                })?;
+                match rx.recv().await {
+                    Ok(Some(entered_credentials)) => credentials = Some(entered_credentials),
+                    _ => break Err(error.context(format!("{title} failed"))),
+                }
+            };
+
+            this.update(&mut cx, |this, cx| {
+                this.git_operation = None;
+                if let Err(error) = &result {
+                    this.last_git_operation_failure = Some(GitOperationFailure {
+                        title: title.clone(),
+                        error: format!("{error:#}"),
+                    });
+                }
+                cx.notify();
+            })?;
+            result
+        })
     }
 
     // RPC message handlers
@@ -8277,6 +9707,167 @@ impl Project {
         Ok(serialize_blame_buffer_response(blame))
     }
 
+    async fn handle_get_git_status(
+        this: Model<Self>,
+        envelope: TypedEnvelope<proto::GetGitStatus>,
+        mut cx: AsyncAppContext,
+    ) -> Result<proto::GetGitStatusResponse> {
+        let work_directory = ProjectPath {
+            worktree_id: WorktreeId::from_proto(envelope.payload.worktree_id),
+            path: PathBuf::from(envelope.payload.work_directory).into(),
+        };
+        let entries = this
+            .update(&mut cx, |this, cx| this.git_status(work_directory, cx))?
+            .await?;
+        Ok(proto::GetGitStatusResponse {
+            entries: entries
+                .into_iter()
+                .map(serialize_git_status_entry)
+                .collect(),
+        })
+    }
+
+    async fn handle_stage_git_paths(
+        this: Model<Self>,
+        envelope: TypedEnvelope<proto::StageGitPaths>,
+        mut cx: AsyncAppContext,
+    ) -> Result<proto::Ack> {
+        let worktree_id = WorktreeId::from_proto(envelope.payload.worktree_id);
+        let paths = deserialize_project_paths(worktree_id, envelope.payload.paths);
+        this.update(&mut cx, |this, cx| this.stage_paths(paths, cx))?
+            .await?;
+        Ok(proto::Ack {})
+    }
+
+    async fn handle_unstage_git_paths(
+        this: Model<Self>,
+        envelope: TypedEnvelope<proto::UnstageGitPaths>,
+        mut cx: AsyncAppContext,
+    ) -> Result<proto::Ack> {
+        let worktree_id = WorktreeId::from_proto(envelope.payload.worktree_id);
+        let paths = deserialize_project_paths(worktree_id, envelope.payload.paths);
+        this.update(&mut cx, |this, cx| this.unstage_paths(paths, cx))?
+            .await?;
+        Ok(proto::Ack {})
+    }
+
+    async fn handle_stage_git_hunk(
+        this: Model<Self>,
+        envelope: TypedEnvelope<proto::StageGitHunk>,
+        mut cx: AsyncAppContext,
+    ) -> Result<proto::Ack> {
+        let buffer_id = BufferId::new(envelope.payload.buffer_id)?;
+        let diff_base_range =
+            envelope.payload.diff_base_start as usize..envelope.payload.diff_base_end as usize;
+        let buffer = this.update(&mut cx, |this, cx| {
+            this.buffer_store.read(cx).get_existing(buffer_id)
+        })??;
+        this.update(&mut cx, |this, cx| {
+            this.stage_hunk(&buffer, diff_base_range, envelope.payload.new_text, cx)
+        })?
+        .await?;
+        Ok(proto::Ack {})
+    }
+
+    async fn handle_unstage_git_hunk(
+        this: Model<Self>,
+        envelope: TypedEnvelope<proto::UnstageGitHunk>,
+        mut cx: AsyncAppContext,
+    ) -> Result<proto::Ack> {
+        let buffer_id = BufferId::new(envelope.payload.buffer_id)?;
+        let diff_base_range =
+            envelope.payload.diff_base_start as usize..envelope.payload.diff_base_end as usize;
+        let buffer = this.update(&mut cx, |this, cx| {
+            this.buffer_store.read(cx).get_existing(buffer_id)
+        })??;
+        this.update(&mut cx, |this, cx| {
+            this.unstage_hunk(&buffer, diff_base_range, cx)
+        })?
+        .await?;
+        Ok(proto::Ack {})
+    }
+
+    async fn handle_get_git_changed_paths(
+        this: Model<Self>,
+        _: TypedEnvelope<proto::GetGitChangedPaths>,
+        mut cx: AsyncAppContext,
+    ) -> Result<proto::GetGitChangedPathsResponse> {
+        let paths = this
+            .update(&mut cx, |this, cx| this.git_changed_paths(cx))?
+            .await?;
+        Ok(proto::GetGitChangedPathsResponse {
+            paths: paths
+                .into_iter()
+                .map(|path| proto::ProjectPath {
+                    worktree_id: path.worktree_id.to_proto(),
+                    path: path.path.to_string_lossy().into(),
+                })
+                .collect(),
+        })
+    }
+
+    async fn handle_get_git_log(
+        this: Model<Self>,
+        envelope: TypedEnvelope<proto::GetGitLog>,
+        mut cx: AsyncAppContext,
+    ) -> Result<proto::GetGitLogResponse> {
+        let path = ProjectPath {
+            worktree_id: WorktreeId::from_proto(envelope.payload.worktree_id),
+            path: PathBuf::from(envelope.payload.path).into(),
+        };
+        let limit = envelope.payload.limit as usize;
+        let log = this
+            .update(&mut cx, |this, cx| this.git_log(path, limit, cx))?
+            .await?;
+        Ok(serialize_git_log_response(log))
+    }
+
+    async fn handle_get_git_commit_changes(
+        this: Model<Self>,
+        envelope: TypedEnvelope<proto::GetGitCommitChanges>,
+        mut cx: AsyncAppContext,
+    ) -> Result<proto::GetGitCommitChangesResponse> {
+        let path = ProjectPath {
+            worktree_id: WorktreeId::from_proto(envelope.payload.worktree_id),
+            path: PathBuf::from(envelope.payload.path).into(),
+        };
+        let sha = git::Oid::from_bytes(&envelope.payload.sha)?;
+        let changes = this
+            .update(&mut cx, |this, cx| this.git_commit_changes(path, sha, cx))?
+            .await?;
+        Ok(proto::GetGitCommitChangesResponse {
+            changes: changes
+                .into_iter()
+                .map(|change| proto::GitCommitFileChange {
+                    path: change.path.to_string_lossy().into(),
+                    old_text: change.old_text,
+                    new_text: change.new_text,
+                })
+                .collect(),
+        })
+    }
+
+    async fn handle_git_commit(
+        this: Model<Self>,
+        envelope: TypedEnvelope<proto::GitCommit>,
+        mut cx: AsyncAppContext,
+    ) -> Result<proto::Ack> {
+        let work_directory = ProjectPath {
+            worktree_id: WorktreeId::from_proto(envelope.payload.worktree_id),
+            path: PathBuf::from(envelope.payload.work_directory).into(),
+        };
+        this.update(&mut cx, |this, cx| {
+            this.git_commit(
+                work_directory,
+                envelope.payload.message,
+                envelope.payload.amend,
+                cx,
+            )
+        })?
+        .await?;
+        Ok(proto::Ack {})
+    }
+
     async fn handle_multi_lsp_query(
         project: Model<Self>,
         envelope: TypedEnvelope<proto::MultiLspQuery>,
@@ -9170,6 +10761,33 @@ impl Project {
         })
     }
 
+    async fn handle_execute_code_lens(
+        this: Model<Self>,
+        envelope: TypedEnvelope<proto::ExecuteCodeLens>,
+        mut cx: AsyncAppContext,
+    ) -> Result<proto::ExecuteCodeLensResponse> {
+        let sender_id = envelope.original_sender_id()?;
+        let lens = Self::deserialize_code_lens(
+            envelope
+                .payload
+                .lens
+                .ok_or_else(|| anyhow!("invalid code lens"))?,
+        )?;
+        let execute_code_lens = this.update(&mut cx, |this, cx| {
+            let buffer_id = BufferId::new(envelope.payload.buffer_id)?;
+            let buffer = this.buffer_store.read(cx).get_existing(buffer_id)?;
+            anyhow::Ok(this.execute_code_lens(buffer, lens, cx))
+        })??;
+
+        let project_transaction = execute_code_lens.await?;
+        let project_transaction = this.update(&mut cx, |this, cx| {
+            this.serialize_project_transaction_for_peer(project_transaction, sender_id, cx)
+        })?;
+        Ok(proto::ExecuteCodeLensResponse {
+            transaction: Some(project_transaction),
+        })
+    }
+
     async fn handle_on_type_formatting(
         this: Model<Self>,
         envelope: TypedEnvelope<proto::OnTypeFormatting>,
@@ -9365,6 +10983,12 @@ impl Project {
                             name: name.to_string(),
                         })
                     }
+                    TaskSourceKind::Provider { id, worktree } => {
+                        proto::task_source_kind::Kind::Provider(proto::task_source_kind::Provider {
+                            id: id.to_string(),
+                            worktree_id: worktree.to_proto(),
+                        })
+                    }
                 });
                 let kind = Some(proto::TaskSourceKind { kind });
                 let template = Some(proto::TaskTemplate {
@@ -9492,6 +11116,28 @@ impl Project {
         Ok(proto::Ack {})
     }
 
+    async fn handle_refresh_semantic_tokens(
+        this: Model<Self>,
+        _: TypedEnvelope<proto::RefreshSemanticTokens>,
+        mut cx: AsyncAppContext,
+    ) -> Result<proto::Ack> {
+        this.update(&mut cx, |_, cx| {
+            cx.emit(Event::RefreshSemanticTokens);
+        })?;
+        Ok(proto::Ack {})
+    }
+
+    async fn handle_refresh_code_lens(
+        this: Model<Self>,
+        _: TypedEnvelope<proto::RefreshCodeLens>,
+        mut cx: AsyncAppContext,
+    ) -> Result<proto::Ack> {
+        this.update(&mut cx, |_, cx| {
+            cx.emit(Event::RefreshCodeLens);
+        })?;
+        Ok(proto::Ack {})
+    }
+
     async fn handle_lsp_command<T: LspCommand>(
         this: Model<Self>,
         envelope: TypedEnvelope<T::ProtoRequest>,
@@ -10052,6 +11698,32 @@ impl Project {
         })
     }
 
+    fn serialize_code_lens(lens: &CodeLens) -> proto::CodeLens {
+        proto::CodeLens {
+            server_id: lens.server_id.0 as u64,
+            start: Some(serialize_anchor(&lens.range.start)),
+            end: Some(serialize_anchor(&lens.range.end)),
+            lsp_lens: serde_json::to_vec(&lens.lsp_lens).unwrap(),
+        }
+    }
+
+    fn deserialize_code_lens(lens: proto::CodeLens) -> Result<CodeLens> {
+        let start = lens
+            .start
+            .and_then(deserialize_anchor)
+            .ok_or_else(|| anyhow!("invalid start"))?;
+        let end = lens
+            .end
+            .and_then(deserialize_anchor)
+            .ok_or_else(|| anyhow!("invalid end"))?;
+        let lsp_lens = serde_json::from_slice(&lens.lsp_lens)?;
+        Ok(CodeLens {
+            server_id: LanguageServerId(lens.server_id as usize),
+            range: start..end,
+            lsp_lens,
+        })
+    }
+
     async fn handle_buffer_saved(
         this: Model<Self>,
         envelope: TypedEnvelope<proto::BufferSaved>,
@@ -10345,6 +12017,19 @@ impl Project {
     ) -> Task<Option<TaskContext>> {
         if self.is_local() {
             let cwd = self.task_cwd(cx).log_err().flatten();
+            let worktree_delegate = location
+                .buffer
+                .read(cx)
+                .file()
+                .and_then(|file| {
+                    self.worktree_for_id(WorktreeId::from_usize(file.worktree_id()), cx)
+                })
+                .filter(|worktree| worktree.read(cx).is_local())
+                .map(|worktree| {
+                    ProjectLspAdapterDelegate::new(self, &worktree, cx)
+                        as Arc<dyn LspAdapterDelegate>
+                });
+            let task_providers = self.task_providers.providers();
 
             cx.spawn(|project, cx| async move {
                 let mut task_variables = cx
@@ -10359,6 +12044,22 @@ impl Project {
                     })
                     .ok()
                     .flatten()?;
+                if let Some(worktree_delegate) = worktree_delegate {
+                    for provider in task_providers {
+                        let provided_variables = cx
+                            .update(|cx| {
+                                provider.task_context(
+                                    task_variables.clone(),
+                                    worktree_delegate.clone(),
+                                    cx,
+                                )
+                            })
+                            .ok()?;
+                        if let Some(provided_variables) = provided_variables.await.log_err() {
+                            task_variables.extend(provided_variables);
+                        }
+                    }
+                }
                 // Remove all custom entries starting with _, as they're not intended for use by the end user.
                 task_variables.sweep();
                 Some(TaskContext {
@@ -10476,6 +12177,12 @@ impl Project {
                                 name: language.name.into(),
                             }
                         }
+                        proto::task_source_kind::Kind::Provider(provider) => {
+                            TaskSourceKind::Provider {
+                                id: provider.id.into(),
+                                worktree: WorktreeId::from_proto(provider.worktree_id),
+                            }
+                        }
                     };
 
                     let proto_template = template_pair.template?;
@@ -10495,6 +12202,11 @@ impl Project {
                         allow_concurrent_runs: proto_template.allow_concurrent_runs,
                         reveal,
                         tags: proto_template.tags,
+                        // Problem matchers and dependencies only apply to tasks that run locally
+                        problem_matcher: Vec::new(),
+                        depends_on: Vec::new(),
+                        depends_order: DependsOrder::default(),
+                        ready_pattern: None,
                     };
                     Some((task_source_kind, task_template))
                 })
@@ -11317,6 +13029,61 @@ async fn load_shell_environment(
     Ok(parsed_env)
 }
 
+fn serialize_git_status_entry(entry: GitStatusEntry) -> proto::GitStatusEntry {
+    fn serialize_code(code: StatusCode) -> i32 {
+        let code = match code {
+            StatusCode::Modified => proto::git_status_entry::Code::Modified,
+            StatusCode::TypeChanged => proto::git_status_entry::Code::TypeChanged,
+            StatusCode::Added => proto::git_status_entry::Code::Added,
+            StatusCode::Deleted => proto::git_status_entry::Code::Deleted,
+            StatusCode::Renamed => proto::git_status_entry::Code::Renamed,
+            StatusCode::Copied => proto::git_status_entry::Code::Copied,
+            StatusCode::Unmerged => proto::git_status_entry::Code::Unmerged,
+            StatusCode::Untracked => proto::git_status_entry::Code::Untracked,
+        };
+        code as i32
+    }
+
+    proto::GitStatusEntry {
+        path: entry.path.to_string_lossy().into(),
+        staged: entry.status.staged.map(serialize_code),
+        unstaged: entry.status.unstaged.map(serialize_code),
+    }
+}
+
+fn deserialize_git_status_entry(entry: proto::GitStatusEntry) -> GitStatusEntry {
+    fn deserialize_code(code: i32) -> Option<StatusCode> {
+        Some(match proto::git_status_entry::Code::from_i32(code)? {
+            proto::git_status_entry::Code::Modified => StatusCode::Modified,
+            proto::git_status_entry::Code::TypeChanged => StatusCode::TypeChanged,
+            proto::git_status_entry::Code::Added => StatusCode::Added,
+            proto::git_status_entry::Code::Deleted => StatusCode::Deleted,
+            proto::git_status_entry::Code::Renamed => StatusCode::Renamed,
+            proto::git_status_entry::Code::Copied => StatusCode::Copied,
+            proto::git_status_entry::Code::Unmerged => StatusCode::Unmerged,
+            proto::git_status_entry::Code::Untracked => StatusCode::Untracked,
+        })
+    }
+
+    GitStatusEntry {
+        path: PathBuf::from(entry.path).into(),
+        status: StagingStatus {
+            staged: entry.staged.and_then(deserialize_code),
+            unstaged: entry.unstaged.and_then(deserialize_code),
+        },
+    }
+}
+
+fn deserialize_project_paths(worktree_id: WorktreeId, paths: Vec<String>) -> Vec<ProjectPath> {
+    paths
+        .into_iter()
+        .map(|path| ProjectPath {
+            worktree_id,
+            path: PathBuf::from(path).into(),
+        })
+        .collect()
+}
+
 fn serialize_blame_buffer_response(blame: git::blame::Blame) -> proto::BlameBufferResponse {
     let entries = blame
         .entries
@@ -11415,6 +13182,65 @@ fn deserialize_blame_buffer_response(response: proto::BlameBufferResponse) -> gi
     }
 }
 
+fn serialize_git_log_response(log: Log) -> proto::GetGitLogResponse {
+    let entries = log
+        .entries
+        .into_iter()
+        .map(|entry| proto::GitLogEntry {
+            sha: entry.sha.as_bytes().into(),
+            author: entry.author,
+            author_mail: entry.author_mail,
+            author_time: entry.author_time,
+            author_tz: entry.author_tz,
+            summary: entry.summary,
+        })
+        .collect::<Vec<_>>();
+
+    let messages = log
+        .messages
+        .into_iter()
+        .map(|(oid, message)| proto::CommitMessage {
+            oid: oid.as_bytes().into(),
+            message,
+        })
+        .collect::<Vec<_>>();
+
+    proto::GetGitLogResponse {
+        entries,
+        messages,
+        remote_url: log.remote_url,
+    }
+}
+
+fn deserialize_git_log_response(response: proto::GetGitLogResponse) -> Log {
+    let entries = response
+        .entries
+        .into_iter()
+        .filter_map(|entry| {
+            Some(LogEntry {
+                sha: git::Oid::from_bytes(&entry.sha).ok()?,
+                author: entry.author,
+                author_mail: entry.author_mail,
+                author_time: entry.author_time,
+                author_tz: entry.author_tz,
+                summary: entry.summary,
+            })
+        })
+        .collect::<Vec<_>>();
+
+    let messages = response
+        .messages
+        .into_iter()
+        .filter_map(|message| Some((git::Oid::from_bytes(&message.oid).ok()?, message.message)))
+        .collect::<HashMap<_, _>>();
+
+    Log {
+        entries,
+        messages,
+        remote_url: response.remote_url,
+    }
+}
+
 fn remove_empty_hover_blocks(mut hover: Hover) -> Option<Hover> {
     hover
         .contents
@@ -11426,6 +13252,13 @@ fn remove_empty_hover_blocks(mut hover: Hover) -> Option<Hover> {
     }
 }
 
+/// A changed file of a git repository, see [`Project::git_status`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GitStatusEntry {
+    pub path: Arc<Path>,
+    pub status: StagingStatus,
+}
+
 #[derive(Debug)]
 pub struct NoRepositoryError {}
 
@@ -1,11 +1,19 @@
 use crate::{Event, *};
 use fs::FakeFs;
 use futures::{future, StreamExt};
+use git::{
+    log::{Log, LogEntry},
+    remote::{AheadBehind, PullMode, RemoteCredentials},
+    repository::{CommitFileChange, DiffBase, GitFileStatus},
+    stash::Stash,
+    status::{StagingStatus, StatusCode},
+};
 use gpui::{AppContext, SemanticVersion, UpdateGlobal};
 use language::{
     language_settings::{AllLanguageSettings, LanguageSettingsContent},
-    tree_sitter_rust, tree_sitter_typescript, Diagnostic, FakeLspAdapter, LanguageConfig,
-    LanguageMatcher, LineEnding, OffsetRangeExt, Point, ToPoint,
+    tree_sitter_rust, tree_sitter_typescript, BufferFormatter, BufferLinter, Diagnostic,
+    FakeLspAdapter, FormattingOptions, LanguageConfig, LanguageMatcher, LineEnding, LintDiagnostic,
+    OffsetRangeExt, Point, ToPoint,
 };
 use lsp::NumberOrString;
 use parking_lot::Mutex;
@@ -294,6 +302,156 @@ async fn test_managing_project_specific_settings(cx: &mut gpui::TestAppContext)
     );
 }
 
+#[gpui::test]
+async fn test_provided_tasks(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    struct ScriptTaskProvider;
+
+    impl TaskProvider for ScriptTaskProvider {
+        fn id(&self) -> Arc<str> {
+            "scripts".into()
+        }
+
+        fn tasks_for_worktree(
+            &self,
+            worktree: Arc<dyn LspAdapterDelegate>,
+            cx: &AppContext,
+        ) -> Task<Result<TaskTemplates>> {
+            cx.background_executor().spawn(async move {
+                let scripts = worktree.read_text_file("scripts.txt".into()).await?;
+                Ok(TaskTemplates(
+                    scripts
+                        .lines()
+                        .map(|script| TaskTemplate {
+                            label: format!("run {script}"),
+                            command: "run".to_string(),
+                            args: vec![
+                                script.to_string(),
+                                VariableName::Custom("SCRIPT_FILE".into()).template_value(),
+                            ],
+                            ..TaskTemplate::default()
+                        })
+                        .collect(),
+                ))
+            })
+        }
+
+        fn task_context(
+            &self,
+            variables: TaskVariables,
+            _: Arc<dyn LspAdapterDelegate>,
+            _: &AppContext,
+        ) -> Task<Result<TaskVariables>> {
+            let filename = variables
+                .get(&VariableName::Filename)
+                .unwrap_or_default()
+                .to_string();
+            Task::ready(Ok(TaskVariables::from_iter([(
+                VariableName::Custom("SCRIPT_FILE".into()),
+                format!("--file={filename}"),
+            )])))
+        }
+    }
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/the-root",
+        json!({
+            "scripts.txt": "build\ntest\n",
+            "src": {
+                "main.rs": "fn main() {}",
+            },
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs.clone(), ["/the-root".as_ref()], cx).await;
+    let worktree_id = project.update(cx, |project, cx| {
+        project.worktrees().next().unwrap().read(cx).id()
+    });
+    let provided_task_labels = |cx: &mut gpui::TestAppContext| {
+        project.update(cx, |project, cx| {
+            project
+                .task_inventory()
+                .read(cx)
+                .list_tasks(None, None, Some(worktree_id), cx)
+                .into_iter()
+                .map(|(source_kind, task)| {
+                    assert_eq!(
+                        source_kind,
+                        TaskSourceKind::Provider {
+                            id: "scripts".into(),
+                            worktree: worktree_id,
+                        }
+                    );
+                    task.label
+                })
+                .sorted()
+                .collect::<Vec<_>>()
+        })
+    };
+    cx.executor().run_until_parked();
+    assert!(provided_task_labels(cx).is_empty());
+
+    let task_providers = cx.update(TaskProviderRegistry::default_global);
+    task_providers.register_provider(Arc::new(ScriptTaskProvider));
+    cx.executor().run_until_parked();
+    assert_eq!(provided_task_labels(cx), ["run build", "run test"]);
+
+    fs.save(
+        "/the-root/scripts.txt".as_ref(),
+        &"build\nlint\n".into(),
+        Default::default(),
+    )
+    .await
+    .unwrap();
+    cx.executor().run_until_parked();
+    assert_eq!(provided_task_labels(cx), ["run build", "run lint"]);
+
+    let buffer = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer("/the-root/src/main.rs", cx)
+        })
+        .await
+        .unwrap();
+    let task_context = project
+        .update(cx, |project, cx| {
+            let location = Location {
+                buffer: buffer.clone(),
+                range: Anchor::MIN..Anchor::MIN,
+            };
+            project.task_context_for_location(TaskVariables::default(), location, cx)
+        })
+        .await
+        .unwrap();
+    assert_eq!(
+        task_context
+            .task_variables
+            .get(&VariableName::Custom("SCRIPT_FILE".into())),
+        Some("--file=main.rs")
+    );
+    let resolved_task = project
+        .update(cx, |project, cx| {
+            project
+                .task_inventory()
+                .read(cx)
+                .list_tasks(None, None, Some(worktree_id), cx)
+                .into_iter()
+                .find(|(_, task)| task.label == "run lint")
+        })
+        .and_then(|(source_kind, task)| task.resolve_task(&source_kind.to_id_base(), &task_context))
+        .unwrap();
+    assert_eq!(
+        resolved_task.resolved.unwrap().args,
+        ["lint", "--file=main.rs"]
+    );
+
+    task_providers.unregister_provider("scripts");
+    cx.executor().run_until_parked();
+    assert!(provided_task_labels(cx).is_empty());
+}
+
 #[gpui::test]
 async fn test_managing_language_servers(cx: &mut gpui::TestAppContext) {
     init_test(cx);
@@ -2012,6 +2170,250 @@ async fn test_diagnostics_from_multiple_language_servers(cx: &mut gpui::TestAppC
     });
 }
 
+#[gpui::test]
+async fn test_formatters_and_linters(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    struct FakeFormatter;
+
+    impl BufferFormatter for FakeFormatter {
+        fn name(&self) -> Arc<str> {
+            "fake-formatter".into()
+        }
+
+        fn format(
+            &self,
+            text: String,
+            _path: &Path,
+            _options: FormattingOptions,
+            _cx: &AppContext,
+        ) -> Task<Result<Vec<(Range<usize>, String)>>> {
+            let edits = text
+                .match_indices("one")
+                .map(|(ix, word)| (ix..ix + word.len(), word.to_uppercase()))
+                .collect();
+            Task::ready(Ok(edits))
+        }
+    }
+
+    struct FakeLinter;
+
+    impl BufferLinter for FakeLinter {
+        fn name(&self) -> Arc<str> {
+            "fake-linter".into()
+        }
+
+        fn lint(
+            &self,
+            text: String,
+            _path: &Path,
+            _cx: &AppContext,
+        ) -> Task<Result<Vec<LintDiagnostic>>> {
+            let diagnostics = text
+                .match_indices("two")
+                .map(|(ix, word)| LintDiagnostic {
+                    range: ix..ix + word.len(),
+                    severity: DiagnosticSeverity::WARNING,
+                    message: "avoid two".to_string(),
+                    code: None,
+                })
+                .collect();
+            Task::ready(Ok(diagnostics))
+        }
+    }
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree("/dir", json!({ "a.rs": "one two three" }))
+        .await;
+
+    let project = Project::test(fs, ["/dir".as_ref()], cx).await;
+    let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+    language_registry.add(rust_lang());
+    language_registry.register_formatter(
+        "Rust".into(),
+        "fake-extension".into(),
+        Arc::new(FakeFormatter),
+    );
+    language_registry.register_linter("Rust".into(), "fake-extension".into(), Arc::new(FakeLinter));
+
+    let buffer = project
+        .update(cx, |project, cx| project.open_local_buffer("/dir/a.rs", cx))
+        .await
+        .unwrap();
+
+    // The buffer is linted once it has not been edited for a while.
+    cx.executor().advance_clock(LINT_DEBOUNCE_TIMEOUT);
+    cx.executor().run_until_parked();
+    buffer.update(cx, |buffer, _| {
+        let diagnostics = buffer
+            .snapshot()
+            .diagnostics_in_range::<_, usize>(0..buffer.len(), false)
+            .map(|entry| {
+                (
+                    entry.range,
+                    entry.diagnostic.severity,
+                    entry.diagnostic.source,
+                    entry.diagnostic.message,
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            diagnostics,
+            [(
+                4..7,
+                DiagnosticSeverity::WARNING,
+                Some("fake-linter".to_string()),
+                "avoid two".to_string()
+            )]
+        );
+    });
+
+    // The registered formatter takes precedence when formatting automatically.
+    project
+        .update(cx, |project, cx| {
+            project.format(
+                HashSet::from_iter([buffer.clone()]),
+                true,
+                FormatTrigger::Manual,
+                cx,
+            )
+        })
+        .await
+        .unwrap();
+    buffer.update(cx, |buffer, _| assert_eq!(buffer.text(), "ONE two three\n"));
+
+    // Diagnostics are cleared once the linter no longer reports them.
+    buffer.update(cx, |buffer, cx| buffer.edit([(4..8, "")], None, cx));
+    cx.executor().advance_clock(LINT_DEBOUNCE_TIMEOUT);
+    cx.executor().run_until_parked();
+    buffer.update(cx, |buffer, _| {
+        assert_eq!(
+            buffer
+                .snapshot()
+                .diagnostics_in_range::<_, usize>(0..buffer.len(), false)
+                .count(),
+            0
+        );
+    });
+
+    // Linters are only removed along with the extension that registered them.
+    language_registry.remove_linter("Rust", "other-extension", "fake-linter");
+    buffer.update(cx, |buffer, cx| buffer.edit([(4..4, "two ")], None, cx));
+    cx.executor().advance_clock(LINT_DEBOUNCE_TIMEOUT);
+    cx.executor().run_until_parked();
+    buffer.update(cx, |buffer, _| {
+        assert_eq!(
+            buffer
+                .snapshot()
+                .diagnostics_in_range::<_, usize>(0..buffer.len(), false)
+                .count(),
+            1
+        );
+    });
+
+    // The diagnostics of a removed linter are cleared once the buffer is linted again.
+    language_registry.remove_linter("Rust", "fake-extension", "fake-linter");
+    buffer.update(cx, |buffer, cx| buffer.edit([(0..0, " ")], None, cx));
+    cx.executor().advance_clock(LINT_DEBOUNCE_TIMEOUT);
+    cx.executor().run_until_parked();
+    buffer.update(cx, |buffer, _| {
+        assert_eq!(
+            buffer
+                .snapshot()
+                .diagnostics_in_range::<_, usize>(0..buffer.len(), false)
+                .count(),
+            0
+        );
+    });
+}
+
+#[gpui::test]
+async fn test_linters_with_the_same_name(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    struct FakeLinter {
+        word: &'static str,
+    }
+
+    impl BufferLinter for FakeLinter {
+        fn name(&self) -> Arc<str> {
+            "fake-linter".into()
+        }
+
+        fn lint(
+            &self,
+            text: String,
+            _path: &Path,
+            _cx: &AppContext,
+        ) -> Task<Result<Vec<LintDiagnostic>>> {
+            let diagnostics = text
+                .match_indices(self.word)
+                .map(|(ix, word)| LintDiagnostic {
+                    range: ix..ix + word.len(),
+                    severity: DiagnosticSeverity::WARNING,
+                    message: format!("avoid {word}"),
+                    code: None,
+                })
+                .collect();
+            Task::ready(Ok(diagnostics))
+        }
+    }
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree("/dir", json!({ "a.rs": "one two three" }))
+        .await;
+
+    let project = Project::test(fs, ["/dir".as_ref()], cx).await;
+    let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+    language_registry.add(rust_lang());
+    language_registry.register_linter(
+        "Rust".into(),
+        "extension-a".into(),
+        Arc::new(FakeLinter { word: "one" }),
+    );
+    language_registry.register_linter(
+        "Rust".into(),
+        "extension-b".into(),
+        Arc::new(FakeLinter { word: "three" }),
+    );
+
+    let buffer = project
+        .update(cx, |project, cx| project.open_local_buffer("/dir/a.rs", cx))
+        .await
+        .unwrap();
+    let diagnostics = |buffer: &Model<Buffer>, cx: &mut gpui::TestAppContext| {
+        buffer.update(cx, |buffer, _| {
+            buffer
+                .snapshot()
+                .diagnostics_in_range::<_, usize>(0..buffer.len(), false)
+                .map(|entry| (entry.range, entry.diagnostic.message))
+                .collect::<Vec<_>>()
+        })
+    };
+
+    // The diagnostics of linters with the same name but from different extensions
+    // are stored separately, rather than replacing each other.
+    cx.executor().advance_clock(LINT_DEBOUNCE_TIMEOUT);
+    cx.executor().run_until_parked();
+    assert_eq!(
+        diagnostics(&buffer, cx),
+        [
+            (0..3, "avoid one".to_string()),
+            (8..13, "avoid three".to_string())
+        ]
+    );
+
+    // Removing one of them only clears its own diagnostics.
+    language_registry.remove_linter("Rust", "extension-a", "fake-linter");
+    buffer.update(cx, |buffer, cx| buffer.edit([(13..13, " ")], None, cx));
+    cx.executor().advance_clock(LINT_DEBOUNCE_TIMEOUT);
+    cx.executor().run_until_parked();
+    assert_eq!(
+        diagnostics(&buffer, cx),
+        [(8..13, "avoid three".to_string())]
+    );
+}
+
 #[gpui::test]
 async fn test_edits_from_lsp2_with_past_version(cx: &mut gpui::TestAppContext) {
     init_test(cx);
@@ -2490,14 +2892,15 @@ async fn test_definition(cx: &mut gpui::TestAppContext) {
 }
 
 #[gpui::test]
-async fn test_completions_without_edit_ranges(cx: &mut gpui::TestAppContext) {
+async fn test_call_hierarchy(cx: &mut gpui::TestAppContext) {
     init_test(cx);
 
     let fs = FakeFs::new(cx.executor());
     fs.insert_tree(
         "/dir",
         json!({
-            "a.ts": "",
+            "a.rs": "fn a() {}",
+            "b.rs": "fn b() { a(); a(); }",
         }),
     )
     .await;
@@ -2505,15 +2908,12 @@ async fn test_completions_without_edit_ranges(cx: &mut gpui::TestAppContext) {
     let project = Project::test(fs, ["/dir".as_ref()], cx).await;
 
     let language_registry = project.read_with(cx, |project, _| project.languages().clone());
-    language_registry.add(typescript_lang());
-    let mut fake_language_servers = language_registry.register_fake_lsp_adapter(
-        "TypeScript",
+    language_registry.add(rust_lang());
+    let mut fake_servers = language_registry.register_fake_lsp_adapter(
+        "Rust",
         FakeLspAdapter {
             capabilities: lsp::ServerCapabilities {
-                completion_provider: Some(lsp::CompletionOptions {
-                    trigger_characters: Some(vec![":".to_string()]),
-                    ..Default::default()
-                }),
+                call_hierarchy_provider: Some(lsp::CallHierarchyServerCapability::Simple(true)),
                 ..Default::default()
             },
             ..Default::default()
@@ -2521,44 +2921,252 @@ async fn test_completions_without_edit_ranges(cx: &mut gpui::TestAppContext) {
     );
 
     let buffer = project
-        .update(cx, |p, cx| p.open_local_buffer("/dir/a.ts", cx))
+        .update(cx, |project, cx| project.open_local_buffer("/dir/a.rs", cx))
         .await
         .unwrap();
 
-    let fake_server = fake_language_servers.next().await.unwrap();
-
-    let text = "let a = b.fqn";
-    buffer.update(cx, |buffer, cx| buffer.set_text(text, cx));
-    let completions = project.update(cx, |project, cx| {
-        project.completions(&buffer, text.len(), DEFAULT_COMPLETION_CONTEXT, cx)
+    let fake_server = fake_servers.next().await.unwrap();
+    let lsp_item = |path: &str, name: &str, range: lsp::Range| lsp::CallHierarchyItem {
+        name: name.to_string(),
+        kind: lsp::SymbolKind::FUNCTION,
+        tags: None,
+        detail: Some(format!("fn {name}()")),
+        uri: lsp::Url::from_file_path(path).unwrap(),
+        range,
+        selection_range: range,
+        data: None,
+    };
+    let a_item = lsp_item(
+        "/dir/a.rs",
+        "a",
+        lsp::Range::new(lsp::Position::new(0, 3), lsp::Position::new(0, 4)),
+    );
+    let b_item = lsp_item(
+        "/dir/b.rs",
+        "b",
+        lsp::Range::new(lsp::Position::new(0, 3), lsp::Position::new(0, 4)),
+    );
+    fake_server.handle_request::<lsp::request::CallHierarchyPrepare, _, _>({
+        let a_item = a_item.clone();
+        move |params, _| {
+            let a_item = a_item.clone();
+            async move {
+                assert_eq!(
+                    params.text_document_position_params.position,
+                    lsp::Position::new(0, 3)
+                );
+                Ok(Some(vec![a_item]))
+            }
+        }
     });
-
-    fake_server
-        .handle_request::<lsp::request::Completion, _, _>(|_, _| async move {
-            Ok(Some(lsp::CompletionResponse::Array(vec![
-                lsp::CompletionItem {
-                    label: "fullyQualifiedName?".into(),
-                    insert_text: Some("fullyQualifiedName".into()),
-                    ..Default::default()
-                },
-            ])))
-        })
-        .next()
-        .await;
-    let completions = completions.await.unwrap();
-    let snapshot = buffer.update(cx, |buffer, _| buffer.snapshot());
-    assert_eq!(completions.len(), 1);
-    assert_eq!(completions[0].new_text, "fullyQualifiedName");
-    assert_eq!(
-        completions[0].old_range.to_offset(&snapshot),
-        text.len() - 3..text.len()
+    fake_server.handle_request::<lsp::request::CallHierarchyIncomingCalls, _, _>(
+        move |params, _| {
+            let b_item = b_item.clone();
+            async move {
+                assert_eq!(params.item.name, "a");
+                Ok(Some(vec![lsp::CallHierarchyIncomingCall {
+                    from: b_item,
+                    from_ranges: vec![
+                        lsp::Range::new(lsp::Position::new(0, 9), lsp::Position::new(0, 10)),
+                        lsp::Range::new(lsp::Position::new(0, 14), lsp::Position::new(0, 15)),
+                    ],
+                }]))
+            }
+        },
     );
 
-    let text = "let a = \"atoms/cmp\"";
-    buffer.update(cx, |buffer, cx| buffer.set_text(text, cx));
-    let completions = project.update(cx, |project, cx| {
-        project.completions(&buffer, text.len() - 1, DEFAULT_COMPLETION_CONTEXT, cx)
-    });
+    let items = project
+        .update(cx, |project, cx| {
+            project.prepare_call_hierarchy(&buffer, 3, cx)
+        })
+        .await
+        .unwrap();
+    assert_eq!(items.len(), 1);
+    assert_eq!(items[0].name, "a");
+
+    let calls = project
+        .update(cx, |project, cx| project.incoming_calls(&items[0], cx))
+        .await
+        .unwrap();
+    assert_eq!(calls.len(), 1);
+    cx.update(|cx| {
+        let call = &calls[0];
+        let caller_buffer = call.item.location.buffer.read(cx);
+        assert_eq!(
+            caller_buffer
+                .file()
+                .unwrap()
+                .as_local()
+                .unwrap()
+                .abs_path(cx),
+            Path::new("/dir/b.rs"),
+        );
+        assert_eq!(call.item.name, "b");
+        assert_eq!(call.item.detail.as_deref(), Some("fn b()"));
+        assert_eq!(
+            call.call_sites
+                .iter()
+                .map(|location| location.range.to_offset(caller_buffer))
+                .collect::<Vec<_>>(),
+            [9..10, 14..15],
+        );
+    });
+}
+
+#[gpui::test]
+async fn test_code_lens(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/dir",
+        json!({
+            "a.rs": "fn a() {}\n\n#[test]\nfn test_a() {}\n",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs, ["/dir".as_ref()], cx).await;
+
+    let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+    language_registry.add(rust_lang());
+    let mut fake_servers = language_registry.register_fake_lsp_adapter(
+        "Rust",
+        FakeLspAdapter {
+            capabilities: lsp::ServerCapabilities {
+                code_lens_provider: Some(lsp::CodeLensOptions {
+                    resolve_provider: Some(true),
+                }),
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+    );
+
+    let buffer = project
+        .update(cx, |project, cx| project.open_local_buffer("/dir/a.rs", cx))
+        .await
+        .unwrap();
+
+    let fake_server = fake_servers.next().await.unwrap();
+    fake_server.handle_request::<lsp::request::CodeLensRequest, _, _>(|_, _| async move {
+        Ok(Some(vec![
+            lsp::CodeLens {
+                range: lsp::Range::new(lsp::Position::new(0, 3), lsp::Position::new(0, 4)),
+                command: None,
+                data: Some(json!({ "references": "a" })),
+            },
+            lsp::CodeLens {
+                range: lsp::Range::new(lsp::Position::new(3, 3), lsp::Position::new(3, 9)),
+                command: Some(lsp::Command {
+                    title: "Run test".to_string(),
+                    command: "run-test".to_string(),
+                    arguments: None,
+                }),
+                data: None,
+            },
+        ]))
+    });
+    fake_server.handle_request::<lsp::request::CodeLensResolve, _, _>(|lens, _| async move {
+        assert_eq!(lens.data, Some(json!({ "references": "a" })));
+        Ok(lsp::CodeLens {
+            command: Some(lsp::Command {
+                title: "2 references".to_string(),
+                command: "show-references".to_string(),
+                arguments: None,
+            }),
+            ..lens
+        })
+    });
+
+    let lenses = project
+        .update(cx, |project, cx| project.code_lens(&buffer, cx))
+        .await
+        .unwrap();
+    buffer.update(cx, |buffer, _| {
+        assert_eq!(
+            lenses
+                .iter()
+                .map(|lens| (
+                    lens.range.to_offset(buffer),
+                    lens.lsp_lens.command.as_ref().unwrap().title.as_str()
+                ))
+                .collect::<Vec<_>>(),
+            [(3..4, "2 references"), (22..28, "Run test")],
+        );
+    });
+}
+
+#[gpui::test]
+async fn test_completions_without_edit_ranges(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/dir",
+        json!({
+            "a.ts": "",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs, ["/dir".as_ref()], cx).await;
+
+    let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+    language_registry.add(typescript_lang());
+    let mut fake_language_servers = language_registry.register_fake_lsp_adapter(
+        "TypeScript",
+        FakeLspAdapter {
+            capabilities: lsp::ServerCapabilities {
+                completion_provider: Some(lsp::CompletionOptions {
+                    trigger_characters: Some(vec![":".to_string()]),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+    );
+
+    let buffer = project
+        .update(cx, |p, cx| p.open_local_buffer("/dir/a.ts", cx))
+        .await
+        .unwrap();
+
+    let fake_server = fake_language_servers.next().await.unwrap();
+
+    let text = "let a = b.fqn";
+    buffer.update(cx, |buffer, cx| buffer.set_text(text, cx));
+    let completions = project.update(cx, |project, cx| {
+        project.completions(&buffer, text.len(), DEFAULT_COMPLETION_CONTEXT, cx)
+    });
+
+    fake_server
+        .handle_request::<lsp::request::Completion, _, _>(|_, _| async move {
+            Ok(Some(lsp::CompletionResponse::Array(vec![
+                lsp::CompletionItem {
+                    label: "fullyQualifiedName?".into(),
+                    insert_text: Some("fullyQualifiedName".into()),
+                    ..Default::default()
+                },
+            ])))
+        })
+        .next()
+        .await;
+    let completions = completions.await.unwrap();
+    let snapshot = buffer.update(cx, |buffer, _| buffer.snapshot());
+    assert_eq!(completions.len(), 1);
+    assert_eq!(completions[0].new_text, "fullyQualifiedName");
+    assert_eq!(
+        completions[0].old_range.to_offset(&snapshot),
+        text.len() - 3..text.len()
+    );
+
+    let text = "let a = \"atoms/cmp\"";
+    buffer.update(cx, |buffer, cx| buffer.set_text(text, cx));
+    let completions = project.update(cx, |project, cx| {
+        project.completions(&buffer, text.len() - 1, DEFAULT_COMPLETION_CONTEXT, cx)
+    });
 
     fake_server
         .handle_request::<lsp::request::Completion, _, _>(|_, _| async move {
@@ -4599,6 +5207,603 @@ async fn test_create_entry(cx: &mut gpui::TestAppContext) {
     assert!(result.is_err())
 }
 
+#[gpui::test]
+async fn test_git_staging(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor().clone());
+    fs.insert_tree(
+        "/dir",
+        json!({
+            ".git": {},
+            "a.txt": "one\n2\nthree\n",
+            "b.txt": "",
+            "sub": {
+                "c.txt": "",
+            },
+        }),
+    )
+    .await;
+    fs.set_index_for_repo(
+        Path::new("/dir/.git"),
+        &[(Path::new("a.txt"), "one\ntwo\nthree\n".into())],
+    );
+    fs.set_head_for_repo(
+        Path::new("/dir/.git"),
+        &[(Path::new("a.txt"), "one\ntwo\nthree\n".into())],
+    );
+    let modified = StagingStatus {
+        staged: None,
+        unstaged: Some(StatusCode::Modified),
+    };
+    let untracked = StagingStatus {
+        staged: None,
+        unstaged: Some(StatusCode::Untracked),
+    };
+    let added = StagingStatus {
+        staged: Some(StatusCode::Added),
+        unstaged: None,
+    };
+    fs.set_staging_status_for_repo(
+        Path::new("/dir/.git"),
+        &[
+            (Path::new("a.txt"), modified),
+            (Path::new("b.txt"), untracked),
+            (Path::new("sub/c.txt"), added),
+        ],
+    );
+
+    let project = Project::test(fs.clone(), ["/dir".as_ref()], cx).await;
+    cx.executor().run_until_parked();
+    let worktree_id = project.update(cx, |project, cx| {
+        project.worktrees().next().unwrap().read(cx).id()
+    });
+    let work_directory = ProjectPath {
+        worktree_id,
+        path: Path::new("").into(),
+    };
+    let git_status = |cx: &mut gpui::TestAppContext| {
+        project.update(cx, |project, cx| {
+            project.git_status(work_directory.clone(), cx)
+        })
+    };
+
+    assert_eq!(
+        git_status(cx).await.unwrap(),
+        vec![
+            GitStatusEntry {
+                path: Path::new("a.txt").into(),
+                status: modified,
+            },
+            GitStatusEntry {
+                path: Path::new("b.txt").into(),
+                status: untracked,
+            },
+            GitStatusEntry {
+                path: Path::new("sub/c.txt").into(),
+                status: added,
+            },
+        ]
+    );
+
+    project
+        .update(cx, |project, cx| {
+            project.stage_paths(vec![(worktree_id, "b.txt").into()], cx)
+        })
+        .await
+        .unwrap();
+    project
+        .update(cx, |project, cx| {
+            project.unstage_paths(vec![(worktree_id, "sub/c.txt").into()], cx)
+        })
+        .await
+        .unwrap();
+    assert_eq!(
+        git_status(cx)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|entry| (entry.path, entry.status))
+            .collect::<Vec<_>>(),
+        vec![
+            (Path::new("a.txt").into(), modified),
+            (Path::new("b.txt").into(), added),
+            (Path::new("sub/c.txt").into(), untracked),
+        ]
+    );
+
+    // Stage the change of the second line of a.txt, which replaces "two\n" in the index.
+    let buffer = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer("/dir/a.txt", cx)
+        })
+        .await
+        .unwrap();
+    project
+        .update(cx, |project, cx| {
+            project.stage_hunk(&buffer, 4..8, "2\n".into(), cx)
+        })
+        .await
+        .unwrap();
+    let mut index_text = None;
+    fs.with_git_state(Path::new("/dir/.git"), false, |state| {
+        index_text = state.index_contents.get(Path::new("a.txt")).cloned();
+    });
+    assert_eq!(index_text.as_deref(), Some("one\n2\nthree\n"));
+
+    // Unstaging it resets the line to its committed contents.
+    project
+        .update(cx, |project, cx| project.unstage_hunk(&buffer, 4..6, cx))
+        .await
+        .unwrap();
+    fs.with_git_state(Path::new("/dir/.git"), false, |state| {
+        index_text = state.index_contents.get(Path::new("a.txt")).cloned();
+    });
+    assert_eq!(index_text.as_deref(), Some("one\ntwo\nthree\n"));
+
+    project
+        .update(cx, |project, cx| {
+            project.git_commit(work_directory.clone(), "Add b.txt".into(), false, cx)
+        })
+        .await
+        .unwrap();
+    assert_eq!(
+        fs.git_commits(Path::new("/dir/.git")),
+        vec![("Add b.txt".to_string(), false)]
+    );
+    assert_eq!(
+        git_status(cx)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|entry| entry.path)
+            .collect::<Vec<_>>(),
+        vec![Path::new("a.txt").into(), Path::new("sub/c.txt").into()]
+    );
+
+    // Nothing is left to commit.
+    assert!(project
+        .update(cx, |project, cx| {
+            project.git_commit(work_directory.clone(), "Empty".into(), false, cx)
+        })
+        .await
+        .is_err());
+}
+
+#[gpui::test]
+async fn test_git_diff_base(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor().clone());
+    fs.insert_tree(
+        "/dir",
+        json!({
+            ".git": {},
+            "a.txt": "one\ntwo\nthree\n",
+            "b.txt": "b\n",
+            "c.txt": "c\n",
+        }),
+    )
+    .await;
+    let dot_git = Path::new("/dir/.git");
+    fs.set_index_for_repo(
+        dot_git,
+        &[
+            (Path::new("a.txt"), "one\ntwo\n".into()),
+            (Path::new("b.txt"), "b\n".into()),
+            (Path::new("c.txt"), "c\n".into()),
+        ],
+    );
+    fs.set_head_for_repo(
+        dot_git,
+        &[
+            (Path::new("a.txt"), "one\n".into()),
+            (Path::new("b.txt"), "b\n".into()),
+            (Path::new("c.txt"), "c\n".into()),
+        ],
+    );
+    fs.set_revision_for_repo(
+        dot_git,
+        "base-sha",
+        &[(Path::new("b.txt"), "base\n".into())],
+    );
+    fs.set_merge_base_for_repo(dot_git, ("HEAD", "main"), "base-sha");
+
+    let project = Project::test(fs.clone(), ["/dir".as_ref()], cx).await;
+    cx.executor().run_until_parked();
+    let buffer_a = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer("/dir/a.txt", cx)
+        })
+        .await
+        .unwrap();
+    let buffer_b = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer("/dir/b.txt", cx)
+        })
+        .await
+        .unwrap();
+    let diff_bases = |cx: &mut gpui::TestAppContext| {
+        [&buffer_a, &buffer_b].map(|buffer| {
+            buffer.read_with(cx, |buffer, _| {
+                buffer.diff_base().map(|text| text.to_string())
+            })
+        })
+    };
+    let changed_paths = |cx: &mut gpui::TestAppContext| {
+        project.update(cx, |project, cx| project.git_changed_paths(cx))
+    };
+    let worktree_id = project.update(cx, |project, cx| {
+        project.worktrees().next().unwrap().read(cx).id()
+    });
+    assert_eq!(
+        diff_bases(cx),
+        [Some("one\ntwo\n".into()), Some("b\n".into())]
+    );
+    assert_eq!(changed_paths(cx).await.unwrap(), Vec::new());
+
+    project.update(cx, |project, cx| {
+        project.set_git_diff_base(DiffBase::head(), cx)
+    });
+    cx.executor().run_until_parked();
+    assert_eq!(diff_bases(cx), [Some("one\n".into()), Some("b\n".into())]);
+    assert_eq!(
+        changed_paths(cx).await.unwrap(),
+        vec![ProjectPath::from((worktree_id, "a.txt"))]
+    );
+    // Hunks are relative to the index, so they can't be staged against another diff base.
+    assert!(project
+        .update(cx, |project, cx| {
+            project.stage_hunk(&buffer_a, 0..0, "zero\n".into(), cx)
+        })
+        .await
+        .is_err());
+
+    // Files that don't exist in the merge base have no diff base.
+    project.update(cx, |project, cx| {
+        project.set_git_diff_base(DiffBase::MergeBase("main".into()), cx)
+    });
+    cx.executor().run_until_parked();
+    assert_eq!(diff_bases(cx), [None, Some("base\n".into())]);
+    assert_eq!(
+        changed_paths(cx).await.unwrap(),
+        vec![
+            ProjectPath::from((worktree_id, "a.txt")),
+            ProjectPath::from((worktree_id, "b.txt")),
+            ProjectPath::from((worktree_id, "c.txt")),
+        ]
+    );
+
+    // Buffers opened afterwards use the selected diff base as well.
+    let buffer_c = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer("/dir/c.txt", cx)
+        })
+        .await
+        .unwrap();
+    buffer_c.read_with(cx, |buffer, _| assert!(buffer.diff_base().is_none()));
+}
+
+#[gpui::test]
+async fn test_git_log(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor().clone());
+    fs.insert_tree(
+        "/dir",
+        json!({
+            ".git": {},
+            "a.txt": "two\n",
+            "b.txt": "b\n",
+        }),
+    )
+    .await;
+    let first_sha: git::Oid = "486c2409237a2c627230589e567024a96751d475".parse().unwrap();
+    let second_sha: git::Oid = "6ad46b5257ba16d12c5ca9f0d4900320959df7f4".parse().unwrap();
+    let log_entry = |sha: git::Oid, summary: &str| LogEntry {
+        sha,
+        author: "Joe Schmoe".into(),
+        author_mail: "joe.schmoe@example.com".into(),
+        author_time: 1709741400,
+        author_tz: "+0100".into(),
+        summary: summary.into(),
+    };
+    let a_change = CommitFileChange {
+        path: Path::new("a.txt").into(),
+        old_text: Some("one\n".into()),
+        new_text: Some("two\n".into()),
+    };
+    let b_change = CommitFileChange {
+        path: Path::new("b.txt").into(),
+        old_text: None,
+        new_text: Some("b\n".into()),
+    };
+    fs.set_log_for_repo(
+        Path::new("/dir/.git"),
+        Log {
+            entries: vec![
+                log_entry(second_sha, "Change a"),
+                log_entry(first_sha, "Add b"),
+            ],
+            messages: [(second_sha, "Change a\n\nDetails".to_string())]
+                .into_iter()
+                .collect(),
+            remote_url: None,
+        },
+        vec![
+            (second_sha, vec![a_change.clone()]),
+            (first_sha, vec![b_change.clone()]),
+        ],
+    );
+
+    let project = Project::test(fs.clone(), ["/dir".as_ref()], cx).await;
+    cx.executor().run_until_parked();
+    let worktree_id = project.update(cx, |project, cx| {
+        project.worktrees().next().unwrap().read(cx).id()
+    });
+    let work_directory = ProjectPath::from((worktree_id, ""));
+    let file_a = ProjectPath::from((worktree_id, "a.txt"));
+
+    let log = project
+        .update(cx, |project, cx| {
+            project.git_log(work_directory.clone(), 10, cx)
+        })
+        .await
+        .unwrap();
+    assert_eq!(
+        log.entries
+            .iter()
+            .map(|entry| entry.summary.as_str())
+            .collect::<Vec<_>>(),
+        ["Change a", "Add b"]
+    );
+    assert_eq!(
+        log.messages.get(&second_sha).map(String::as_str),
+        Some("Change a\n\nDetails")
+    );
+    let log = project
+        .update(cx, |project, cx| {
+            project.git_log(work_directory.clone(), 1, cx)
+        })
+        .await
+        .unwrap();
+    assert_eq!(log.entries, [log_entry(second_sha, "Change a")]);
+
+    // The log of a file only lists the commits that changed it.
+    let log = project
+        .update(cx, |project, cx| project.git_log(file_a.clone(), 10, cx))
+        .await
+        .unwrap();
+    assert_eq!(log.entries, [log_entry(second_sha, "Change a")]);
+
+    let changes = project
+        .update(cx, |project, cx| {
+            project.git_commit_changes(work_directory.clone(), first_sha, cx)
+        })
+        .await
+        .unwrap();
+    assert_eq!(changes, [b_change]);
+    let changes = project
+        .update(cx, |project, cx| {
+            project.git_commit_changes(file_a.clone(), first_sha, cx)
+        })
+        .await
+        .unwrap();
+    assert!(changes.is_empty());
+    let changes = project
+        .update(cx, |project, cx| {
+            project.git_commit_changes(file_a.clone(), second_sha, cx)
+        })
+        .await
+        .unwrap();
+    assert_eq!(changes, [a_change]);
+}
+
+#[gpui::test]
+async fn test_conflicted_paths(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor().clone());
+    fs.insert_tree(
+        "/dir",
+        json!({
+            ".git": {},
+            "a.txt": "<<<<<<< HEAD\na\n=======\nA\n>>>>>>> other\n",
+            "b.txt": "b\n",
+            "c": {
+                "d.txt": "<<<<<<< HEAD\nd\n=======\nD\n>>>>>>> other\n",
+            },
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs.clone(), ["/dir".as_ref()], cx).await;
+    cx.executor().run_until_parked();
+    let worktree_id = project.update(cx, |project, cx| {
+        project.worktrees().next().unwrap().read(cx).id()
+    });
+    assert_eq!(
+        project.update(cx, |project, cx| project.conflicted_paths(cx)),
+        Vec::new()
+    );
+
+    fs.set_status_for_repo_via_working_copy_change(
+        Path::new("/dir/.git"),
+        &[
+            (Path::new("a.txt"), GitFileStatus::Conflict),
+            (Path::new("b.txt"), GitFileStatus::Modified),
+            (Path::new("c/d.txt"), GitFileStatus::Conflict),
+        ],
+    );
+    cx.executor().run_until_parked();
+    assert_eq!(
+        project.update(cx, |project, cx| project.conflicted_paths(cx)),
+        [
+            ProjectPath::from((worktree_id, "a.txt")),
+            ProjectPath::from((worktree_id, "c/d.txt")),
+        ]
+    );
+}
+
+#[gpui::test]
+async fn test_git_remote_operations(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor().clone());
+    fs.insert_tree(
+        "/dir",
+        json!({
+            ".git": {},
+            "a.txt": "a\n",
+        }),
+    )
+    .await;
+    let dot_git = Path::new("/dir/.git");
+    let credentials = RemoteCredentials {
+        username: "joe".into(),
+        password: "secret".into(),
+    };
+    fs.with_git_state(dot_git, true, |state| {
+        state.branch_name = Some("main".into());
+        state.ahead_behind = Some(AheadBehind {
+            ahead: 2,
+            behind: 1,
+        });
+        state.required_credentials = Some(credentials.clone());
+    });
+
+    let project = Project::test(fs.clone(), ["/dir".as_ref()], cx).await;
+    cx.executor().run_until_parked();
+    let worktree_id = project.update(cx, |project, cx| {
+        project.worktrees().next().unwrap().read(cx).id()
+    });
+    let work_directory = ProjectPath::from((worktree_id, ""));
+    let ahead_behind = |cx: &mut gpui::TestAppContext| {
+        project.update(cx, |project, cx| {
+            let worktree = project.worktrees().next().unwrap().read(cx);
+            worktree.root_git_entry().unwrap().ahead_behind()
+        })
+    };
+    assert_eq!(
+        ahead_behind(cx),
+        Some(AheadBehind {
+            ahead: 2,
+            behind: 1
+        })
+    );
+
+    let requests = Arc::new(Mutex::new(Vec::new()));
+    project.update(cx, |_, cx| {
+        cx.subscribe(&project, {
+            let requests = requests.clone();
+            move |_, _, event, _| {
+                if let Event::GitCredentialsPrompt(request) = event {
+                    requests.lock().push(request.clone());
+                }
+            }
+        })
+        .detach();
+    });
+
+    // Without credentials, the operation waits for the user to enter them.
+    let push = project.update(cx, |project, cx| {
+        project.git_push(work_directory.clone(), cx)
+    });
+    cx.executor().run_until_parked();
+    project.update(cx, |project, _| {
+        assert_eq!(project.git_operation().unwrap().title, "git push");
+    });
+    let request = requests.lock().pop().unwrap();
+    assert_eq!(request.operation, "git push");
+
+    // Other operations can't run at the same time.
+    let fetch = project.update(cx, |project, cx| {
+        project.git_fetch(work_directory.clone(), cx)
+    });
+    assert_eq!(
+        fetch.await.unwrap_err().to_string(),
+        "git push is still running"
+    );
+
+    // Wrong credentials are asked for again.
+    request
+        .respond(Some(RemoteCredentials {
+            username: "joe".into(),
+            password: "wrong".into(),
+        }))
+        .await
+        .unwrap();
+    cx.executor().run_until_parked();
+    let request = requests.lock().pop().unwrap();
+    request.respond(Some(credentials.clone())).await.unwrap();
+    push.await.unwrap();
+    project.update(cx, |project, _| {
+        assert!(project.git_operation().is_none());
+        assert!(project.last_git_operation_failure().is_none());
+    });
+    fs.with_git_state(dot_git, true, |state| {
+        assert_eq!(state.remote_operations, ["push"]);
+    });
+    cx.executor().run_until_parked();
+    assert_eq!(
+        ahead_behind(cx),
+        Some(AheadBehind {
+            ahead: 0,
+            behind: 1
+        })
+    );
+
+    // Cancelling the prompt fails the operation.
+    let pull = project.update(cx, |project, cx| {
+        project.git_pull(work_directory.clone(), PullMode::Rebase, cx)
+    });
+    cx.executor().run_until_parked();
+    let request = requests.lock().pop().unwrap();
+    request.respond(None).await.unwrap();
+    pull.await.unwrap_err();
+    project.update(cx, |project, cx| {
+        assert!(project.git_operation().is_none());
+        let failure = project.last_git_operation_failure().unwrap();
+        assert_eq!(failure.title, "git pull");
+        assert_eq!(failure.error, "git pull failed: authentication required");
+        project.dismiss_git_operation_failure(cx);
+        assert!(project.last_git_operation_failure().is_none());
+    });
+
+    project
+        .update(cx, |project, cx| {
+            project.git_stash(work_directory.clone(), Some("Try something".into()), cx)
+        })
+        .await
+        .unwrap();
+    let stashes = project
+        .update(cx, |project, cx| {
+            project.git_stashes(work_directory.clone(), cx)
+        })
+        .await
+        .unwrap();
+    assert_eq!(
+        stashes,
+        [Stash {
+            index: 0,
+            message: "On main: Try something".into(),
+        }]
+    );
+    project
+        .update(cx, |project, cx| {
+            project.git_pop_stash(work_directory.clone(), 0, cx)
+        })
+        .await
+        .unwrap();
+    let stashes = project
+        .update(cx, |project, cx| {
+            project.git_stashes(work_directory.clone(), cx)
+        })
+        .await
+        .unwrap();
+    assert!(stashes.is_empty());
+}
+
 #[gpui::test]
 async fn test_multiple_language_server_hovers(cx: &mut gpui::TestAppContext) {
     init_test(cx);
@@ -57,19 +57,22 @@ pub enum TaskSourceKind {
     },
     /// Languages-specific tasks coming from extensions.
     Language { name: Arc<str> },
+    /// Tasks of a worktree coming from a registered [`crate::TaskProvider`], such as an extension.
+    Provider { id: Arc<str>, worktree: WorktreeId },
 }
 
 impl TaskSourceKind {
     pub fn abs_path(&self) -> Option<&Path> {
         match self {
             Self::AbsPath { abs_path, .. } | Self::Worktree { abs_path, .. } => Some(abs_path),
-            Self::UserInput | Self::Language { .. } => None,
+            Self::UserInput | Self::Language { .. } | Self::Provider { .. } => None,
         }
     }
 
     pub fn worktree(&self) -> Option<WorktreeId> {
         match self {
             Self::Worktree { id, .. } => Some(*id),
+            Self::Provider { worktree, .. } => Some(*worktree),
             _ => None,
         }
     }
@@ -88,6 +91,7 @@ impl TaskSourceKind {
                 format!("{id_base}_{id}_{}", abs_path.display())
             }
             TaskSourceKind::Language { name } => format!("language_{name}"),
+            TaskSourceKind::Provider { id, worktree } => format!("provider_{id}_{worktree}"),
         }
     }
 }
@@ -151,6 +155,14 @@ impl Inventory {
         self.sources.retain(|s| s.kind.worktree() != Some(worktree));
     }
 
+    /// Removes the sources of the [`crate::TaskProvider`] with the given id in all worktrees,
+    /// making the tasks it provided unavailable in the fetch results.
+    pub fn remove_provider_sources(&mut self, provider_id: &str) {
+        self.sources.retain(|s| {
+            !matches!(&s.kind, TaskSourceKind::Provider { id, .. } if id.as_ref() == provider_id)
+        });
+    }
+
     /// Pulls its task sources relevant to the worktree and the language given,
     /// returns all task templates with their source kinds, in no specific order.
     pub fn list_tasks(
@@ -382,6 +394,45 @@ impl Inventory {
         }
     }
 
+    /// Resolves the tasks that a task depends on with the context it got resolved with, looking them up by their labels
+    /// in the task's own source first, then in the other sources of its worktree and the global ones.
+    pub fn resolve_task_dependencies(
+        &self,
+        task_source_kind: &TaskSourceKind,
+        resolved_task: &mut ResolvedTask,
+    ) -> Result<()> {
+        let worktree = task_source_kind.worktree();
+        let candidates = self
+            .sources
+            .iter()
+            .filter(|source| {
+                let source_worktree = source.kind.worktree();
+                source_worktree.is_none() || source_worktree == worktree
+            })
+            .sorted_by_key(|source| {
+                (
+                    &source.kind != task_source_kind,
+                    task_source_kind_preference(&source.kind),
+                )
+            })
+            .flat_map(|source| {
+                let id_base = source.kind.to_id_base();
+                source
+                    .source
+                    .tasks_to_schedule()
+                    .0
+                    .into_iter()
+                    .map(move |task| (id_base.clone(), task))
+            })
+            .collect::<Vec<_>>();
+        resolved_task.resolve_dependencies(&|label| {
+            candidates
+                .iter()
+                .find(|(_, task)| task.label == label)
+                .cloned()
+        })
+    }
+
     /// Deletes a resolved task from history, using its id.
     /// A similar may still resurface in `used_and_current_resolved_tasks` when its [`TaskTemplate`] is resolved again.
     pub fn delete_previously_used(&mut self, id: &TaskId) {
@@ -417,7 +468,8 @@ fn task_source_kind_preference(kind: &TaskSourceKind) -> u32 {
         TaskSourceKind::Language { .. } => 1,
         TaskSourceKind::UserInput => 2,
         TaskSourceKind::Worktree { .. } => 3,
-        TaskSourceKind::AbsPath { .. } => 4,
+        TaskSourceKind::Provider { .. } => 4,
+        TaskSourceKind::AbsPath { .. } => 5,
     }
 }
 
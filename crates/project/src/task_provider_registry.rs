@@ -0,0 +1,99 @@
+//! Tasks and task variables that are provided for a worktree at runtime, rather than read from task files.
+
+use std::sync::Arc;
+
+use anyhow::Result;
+use collections::BTreeMap;
+use gpui::{AppContext, Global, Task};
+use language::LspAdapterDelegate;
+use parking_lot::RwLock;
+use postage::watch;
+use task::{TaskTemplates, TaskVariables};
+
+/// A provider of the tasks available in a worktree and of custom variables for the tasks run in it,
+/// such as an extension that reads the scripts of a `package.json`.
+pub trait TaskProvider: Send + Sync {
+    /// The ID of the provider, unique among the registered providers.
+    fn id(&self) -> Arc<str>;
+
+    /// Returns the tasks available in the given worktree.
+    fn tasks_for_worktree(
+        &self,
+        worktree: Arc<dyn LspAdapterDelegate>,
+        cx: &AppContext,
+    ) -> Task<Result<TaskTemplates>>;
+
+    /// Returns the [`task::VariableName::Custom`] variables to resolve the tasks run in the given worktree with,
+    /// given the variables of the task context built so far.
+    fn task_context(
+        &self,
+        variables: TaskVariables,
+        worktree: Arc<dyn LspAdapterDelegate>,
+        cx: &AppContext,
+    ) -> Task<Result<TaskVariables>>;
+}
+
+#[derive(Default)]
+struct GlobalTaskProviderRegistry(Arc<TaskProviderRegistry>);
+
+impl Global for GlobalTaskProviderRegistry {}
+
+struct TaskProviderRegistryState {
+    providers: BTreeMap<Arc<str>, Arc<dyn TaskProvider>>,
+    subscription: (watch::Sender<()>, watch::Receiver<()>),
+}
+
+/// The registered [`TaskProvider`]s, which every local project asks for the tasks of its worktrees.
+pub struct TaskProviderRegistry {
+    state: RwLock<TaskProviderRegistryState>,
+}
+
+impl Default for TaskProviderRegistry {
+    fn default() -> Self {
+        Self {
+            state: RwLock::new(TaskProviderRegistryState {
+                providers: BTreeMap::default(),
+                subscription: watch::channel(),
+            }),
+        }
+    }
+}
+
+impl TaskProviderRegistry {
+    /// Returns the global [`TaskProviderRegistry`].
+    ///
+    /// Inserts a default [`TaskProviderRegistry`] if one does not yet exist.
+    pub fn default_global(cx: &mut AppContext) -> Arc<Self> {
+        cx.default_global::<GlobalTaskProviderRegistry>().0.clone()
+    }
+
+    /// Registers the given [`TaskProvider`], replacing a previously registered one with the same ID.
+    pub fn register_provider(&self, provider: Arc<dyn TaskProvider>) {
+        let mut state = self.state.write();
+        state.providers.insert(provider.id(), provider);
+        *state.subscription.0.borrow_mut() = ();
+    }
+
+    /// Unregisters the [`TaskProvider`] with the given ID, if any.
+    pub fn unregister_provider(&self, id: &str) {
+        let mut state = self.state.write();
+        if state.providers.remove(id).is_some() {
+            *state.subscription.0.borrow_mut() = ();
+        }
+    }
+
+    /// Returns the [`TaskProvider`] with the given ID.
+    pub fn provider(&self, id: &str) -> Option<Arc<dyn TaskProvider>> {
+        self.state.read().providers.get(id).cloned()
+    }
+
+    /// Returns the registered [`TaskProvider`]s, ordered by their IDs.
+    pub fn providers(&self) -> Vec<Arc<dyn TaskProvider>> {
+        self.state.read().providers.values().cloned().collect()
+    }
+
+    /// Returns a receiver that is notified whenever a provider is registered or unregistered.
+    pub fn subscribe(&self) -> watch::Receiver<()> {
+        self.state.read().subscription.1.clone()
+    }
+}
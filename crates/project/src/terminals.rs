@@ -1,10 +1,13 @@
 use crate::Project;
 use anyhow::Context as _;
-use collections::HashMap;
+use collections::{HashMap, HashSet};
 use gpui::{
-    AnyWindowHandle, AppContext, Context, Entity, Model, ModelContext, SharedString, WeakModel,
+    AnyWindowHandle, AppContext, Context, Entity, EntityId, Model, ModelContext, SharedString,
+    WeakModel,
 };
 use itertools::Itertools;
+use language::{Diagnostic, DiagnosticEntry, LanguageServerId, PointUtf16, Unclipped};
+use lsp::DiagnosticSeverity;
 use settings::{Settings, SettingsLocation};
 use smol::channel::bounded;
 use std::{
@@ -12,19 +15,33 @@ use std::{
     fs::File,
     io::Write,
     path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
 };
-use task::{SpawnInTerminal, TerminalWorkDir};
+use task::{Problem, ProblemMatcher, ProblemSeverity, SpawnInTerminal, TerminalWorkDir};
 use terminal::{
     terminal_settings::{self, Shell, TerminalSettings, VenvSettingsContent},
     TaskState, TaskStatus, Terminal, TerminalBuilder,
 };
-use util::ResultExt;
+use util::{post_inc, ResultExt};
+
+/// How often the output of a running task is matched against its problem matchers.
+const TASK_PROBLEMS_MATCH_INTERVAL: Duration = Duration::from_millis(100);
 
 // #[cfg(target_os = "macos")]
 // use std::os::unix::ffi::OsStrExt;
 
 pub struct Terminals {
     pub(crate) local_handles: Vec<WeakModel<terminal::Terminal>>,
+    /// The diagnostics published by the problem matchers of each task, by the task's full label.
+    pub(crate) task_diagnostics: HashMap<String, TaskDiagnostics>,
+}
+
+pub(crate) struct TaskDiagnostics {
+    server_id: LanguageServerId,
+    /// The terminal of the task's latest run, the only one whose problems are published.
+    terminal_id: EntityId,
+    paths: HashSet<PathBuf>,
 }
 
 #[derive(Debug, Clone)]
@@ -71,6 +88,29 @@ impl Project {
         spawn_task: Option<SpawnInTerminal>,
         window: AnyWindowHandle,
         cx: &mut ModelContext<Self>,
+    ) -> anyhow::Result<Model<Terminal>> {
+        self.spawn_terminal(working_directory, spawn_task, None, window, cx)
+    }
+
+    /// Starts a shell in a terminal that shows the history of a terminal
+    /// from a previous session above the shell's output.
+    pub fn restore_terminal(
+        &mut self,
+        working_directory: Option<TerminalWorkDir>,
+        history: String,
+        window: AnyWindowHandle,
+        cx: &mut ModelContext<Self>,
+    ) -> anyhow::Result<Model<Terminal>> {
+        self.spawn_terminal(working_directory, None, Some(history), window, cx)
+    }
+
+    fn spawn_terminal(
+        &mut self,
+        working_directory: Option<TerminalWorkDir>,
+        spawn_task: Option<SpawnInTerminal>,
+        history: Option<String>,
+        window: AnyWindowHandle,
+        cx: &mut ModelContext<Self>,
     ) -> anyhow::Result<Model<Terminal>> {
         // used only for TerminalSettings::get
         let worktree = {
@@ -103,6 +143,7 @@ impl Project {
         // https://github.com/alacritty/alacritty/blob/fd1a3cc79192d1d03839f0fd8c72e1f8d0fce42e/extra/man/alacritty.5.scd?plain=1#L47-L52
 
         let mut retained_script = None;
+        let mut task_problem_matchers = None;
 
         let venv_base_directory = working_directory
             .as_ref()
@@ -139,6 +180,18 @@ impl Project {
                 if let Some(spawn_task) = spawn_task {
                     log::debug!("Spawning task: {spawn_task:?}");
                     env.extend(spawn_task.env);
+                    if !spawn_task.problem_matchers.is_empty() {
+                        let cwd = spawn_task
+                            .cwd
+                            .as_ref()
+                            .and_then(|cwd| cwd.local_path())
+                            .map(ToOwned::to_owned);
+                        task_problem_matchers = Some((
+                            spawn_task.full_label.clone(),
+                            spawn_task.problem_matchers,
+                            cwd,
+                        ));
+                    }
                     // Activate minimal Python virtual environment
                     if let Some(python_settings) = &python_settings.as_option() {
                         self.set_python_venv_path_for_tasks(
@@ -178,6 +231,8 @@ impl Project {
             Some(settings.blinking),
             settings.alternate_scroll,
             settings.max_scroll_history_lines,
+            settings.shell_integration && is_terminal,
+            history,
             window,
             completion_tx,
             cx,
@@ -204,6 +259,10 @@ impl Project {
             })
             .detach();
 
+            if let Some((task_label, problem_matchers, cwd)) = task_problem_matchers {
+                self.match_task_problems(&terminal_handle, task_label, problem_matchers, cwd, cx);
+            }
+
             // if the terminal is not a task, activate full Python virtual environment
             if is_terminal {
                 if let Some(python_settings) = &python_settings.as_option() {
@@ -225,6 +284,149 @@ impl Project {
         terminal
     }
 
+    /// Runs the problem matchers of a task over its output while it runs, replacing the
+    /// diagnostics of the task's previous run.
+    fn match_task_problems(
+        &mut self,
+        terminal: &Model<Terminal>,
+        task_label: String,
+        problem_matchers: Vec<ProblemMatcher>,
+        cwd: Option<PathBuf>,
+        cx: &mut ModelContext<Self>,
+    ) {
+        let server_id = match self.terminals.task_diagnostics.get(&task_label) {
+            Some(diagnostics) => diagnostics.server_id,
+            None => self.languages.next_language_server_id(),
+        };
+        let terminal_id = terminal.entity_id();
+        self.publish_task_problems(server_id, task_label.clone(), terminal_id, Vec::new(), cx);
+
+        // Wakeups that arrive while the output is being matched are coalesced into one.
+        let (output_tx, output_rx) = bounded(1);
+        let subscription = cx.subscribe(terminal, {
+            let output_tx = output_tx.clone();
+            move |_, _, event: &terminal::Event, _| {
+                if matches!(event, terminal::Event::Wakeup) {
+                    output_tx.try_send(()).ok();
+                }
+            }
+        });
+        let completed = terminal.update(cx, |terminal, cx| terminal.wait_for_completed_task(cx));
+        cx.spawn(|_, _| async move {
+            completed.await;
+            output_tx.send(()).await.ok();
+        })
+        .detach();
+
+        let problem_matchers = Arc::new(problem_matchers);
+        let terminal = terminal.downgrade();
+        cx.spawn(|project, mut cx| async move {
+            let _subscription = subscription;
+            let mut last_problems = Vec::new();
+            while output_rx.recv().await.is_ok() {
+                // Matching serializes the whole output of the task, so it is batched instead
+                // of running on every wakeup.
+                cx.background_executor()
+                    .timer(TASK_PROBLEMS_MATCH_INTERVAL)
+                    .await;
+                let Ok((output, finished)) = terminal.update(&mut cx, |terminal, _| {
+                    let finished = terminal
+                        .task()
+                        .map_or(true, |task| task.status != TaskStatus::Running);
+                    (terminal.text(), finished)
+                }) else {
+                    break;
+                };
+                let problem_matchers = problem_matchers.clone();
+                let cwd = cwd.clone();
+                let problems = cx
+                    .background_executor()
+                    .spawn(async move {
+                        problem_matchers
+                            .iter()
+                            .filter_map(|matcher| {
+                                matcher.problems(&output, cwd.as_deref()).log_err()
+                            })
+                            .flatten()
+                            .collect::<Vec<_>>()
+                    })
+                    .await;
+                let superseded = project.update(&mut cx, |project, cx| {
+                    let latest_terminal_id = project
+                        .terminals
+                        .task_diagnostics
+                        .get(&task_label)
+                        .map(|diagnostics| diagnostics.terminal_id);
+                    if latest_terminal_id != Some(terminal_id) {
+                        return true;
+                    }
+                    if problems != last_problems {
+                        last_problems = problems.clone();
+                        project.publish_task_problems(
+                            server_id,
+                            task_label.clone(),
+                            terminal_id,
+                            problems,
+                            cx,
+                        );
+                    }
+                    false
+                })?;
+                if finished || superseded {
+                    break;
+                }
+            }
+            anyhow::Ok(())
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn publish_task_problems(
+        &mut self,
+        server_id: LanguageServerId,
+        task_label: String,
+        terminal_id: EntityId,
+        problems: Vec<Problem>,
+        cx: &mut ModelContext<Self>,
+    ) {
+        let mut diagnostics_by_path = HashMap::<PathBuf, Vec<_>>::default();
+        for problem in problems {
+            let group_id = post_inc(&mut self.next_diagnostic_group_id);
+            let (path, entry) = diagnostic_entry(problem, group_id);
+            diagnostics_by_path.entry(path).or_default().push(entry);
+        }
+
+        let previous_paths = self
+            .terminals
+            .task_diagnostics
+            .remove(&task_label)
+            .map(|diagnostics| diagnostics.paths)
+            .unwrap_or_default();
+        for path in previous_paths {
+            diagnostics_by_path.entry(path).or_default();
+        }
+
+        let mut paths = HashSet::default();
+        for (path, diagnostics) in diagnostics_by_path {
+            let has_diagnostics = !diagnostics.is_empty();
+            let updated = self
+                .update_diagnostic_entries(server_id, path.clone(), None, diagnostics, cx)
+                .log_err()
+                .is_some();
+            if updated && has_diagnostics {
+                paths.insert(path);
+            }
+        }
+        self.terminals.task_diagnostics.insert(
+            task_label,
+            TaskDiagnostics {
+                server_id,
+                terminal_id,
+                paths,
+            },
+        );
+    }
+
     pub fn find_activate_script_path(
         &mut self,
         settings: &VenvSettingsContent,
@@ -420,3 +622,42 @@ mod tests {
         assert_eq!(env.get("OTHER").unwrap(), "aaa");
     }
 }
+
+fn diagnostic_entry(
+    problem: Problem,
+    group_id: usize,
+) -> (PathBuf, DiagnosticEntry<Unclipped<PointUtf16>>) {
+    // Problems are reported with 1-based lines and columns, and span their whole line when no
+    // column is given
+    let start = PointUtf16::new(
+        problem.line.saturating_sub(1),
+        problem.column.map_or(0, |column| column.saturating_sub(1)),
+    );
+    let end = match (problem.end_line, problem.end_column) {
+        (end_line, Some(end_column)) => PointUtf16::new(
+            end_line.unwrap_or(problem.line).saturating_sub(1),
+            end_column.saturating_sub(1),
+        ),
+        (Some(end_line), None) => PointUtf16::new(end_line, 0),
+        (None, None) if problem.column.is_some() => start,
+        (None, None) => PointUtf16::new(problem.line, 0),
+    };
+    let severity = match problem.severity {
+        ProblemSeverity::Error => DiagnosticSeverity::ERROR,
+        ProblemSeverity::Warning => DiagnosticSeverity::WARNING,
+        ProblemSeverity::Info => DiagnosticSeverity::INFORMATION,
+    };
+    let entry = DiagnosticEntry {
+        range: Unclipped(start)..Unclipped(end.max(start)),
+        diagnostic: Diagnostic {
+            source: Some(problem.source),
+            code: problem.code,
+            severity,
+            message: problem.message,
+            group_id,
+            is_primary: true,
+            ..Default::default()
+        },
+    };
+    (problem.path, entry)
+}
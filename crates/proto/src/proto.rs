@@ -252,6 +252,34 @@ messages!(
     (GetReferencesResponse, Background),
     (GetSignatureHelp, Background),
     (GetSignatureHelpResponse, Background),
+    (GetSemanticTokens, Background),
+    (GetSemanticTokensResponse, Background),
+    (PrepareCallHierarchy, Background),
+    (CallHierarchyIncomingCalls, Background),
+    (CallHierarchyOutgoingCalls, Background),
+    (PrepareTypeHierarchy, Background),
+    (TypeHierarchySupertypes, Background),
+    (TypeHierarchySubtypes, Background),
+    (HierarchyItemsResponse, Background),
+    (HierarchyCallsResponse, Background),
+    (GetCodeLens, Background),
+    (GetCodeLensResponse, Background),
+    (ExecuteCodeLens, Background),
+    (ExecuteCodeLensResponse, Background),
+    (RefreshCodeLens, Foreground),
+    (GetGitStatus, Background),
+    (GetGitStatusResponse, Background),
+    (StageGitPaths, Background),
+    (UnstageGitPaths, Background),
+    (StageGitHunk, Background),
+    (UnstageGitHunk, Background),
+    (GitCommit, Background),
+    (GetGitChangedPaths, Background),
+    (GetGitChangedPathsResponse, Background),
+    (GetGitLog, Background),
+    (GetGitLogResponse, Background),
+    (GetGitCommitChanges, Background),
+    (GetGitCommitChangesResponse, Background),
     (GetSupermavenApiKey, Background),
     (GetSupermavenApiKeyResponse, Background),
     (GetTypeDefinition, Background),
@@ -295,6 +323,7 @@ messages!(
     (PrepareRenameResponse, Background),
     (ProjectEntryResponse, Foreground),
     (RefreshInlayHints, Foreground),
+    (RefreshSemanticTokens, Foreground),
     (RejoinChannelBuffers, Foreground),
     (RejoinChannelBuffersResponse, Foreground),
     (RejoinRoom, Foreground),
@@ -431,6 +460,25 @@ request_messages!(
     (GetProjectSymbols, GetProjectSymbolsResponse),
     (GetReferences, GetReferencesResponse),
     (GetSignatureHelp, GetSignatureHelpResponse),
+    (GetSemanticTokens, GetSemanticTokensResponse),
+    (PrepareCallHierarchy, HierarchyItemsResponse),
+    (CallHierarchyIncomingCalls, HierarchyCallsResponse),
+    (CallHierarchyOutgoingCalls, HierarchyCallsResponse),
+    (PrepareTypeHierarchy, HierarchyItemsResponse),
+    (TypeHierarchySupertypes, HierarchyItemsResponse),
+    (TypeHierarchySubtypes, HierarchyItemsResponse),
+    (GetCodeLens, GetCodeLensResponse),
+    (ExecuteCodeLens, ExecuteCodeLensResponse),
+    (RefreshCodeLens, Ack),
+    (GetGitStatus, GetGitStatusResponse),
+    (StageGitPaths, Ack),
+    (UnstageGitPaths, Ack),
+    (StageGitHunk, Ack),
+    (UnstageGitHunk, Ack),
+    (GitCommit, Ack),
+    (GetGitChangedPaths, GetGitChangedPathsResponse),
+    (GetGitLog, GetGitLogResponse),
+    (GetGitCommitChanges, GetGitCommitChangesResponse),
     (GetSupermavenApiKey, GetSupermavenApiKeyResponse),
     (GetTypeDefinition, GetTypeDefinitionResponse),
     (LinkedEditingRange, LinkedEditingRangeResponse),
@@ -457,6 +505,7 @@ request_messages!(
     (Ping, Ack),
     (PrepareRename, PrepareRenameResponse),
     (RefreshInlayHints, Ack),
+    (RefreshSemanticTokens, Ack),
     (RejoinChannelBuffers, RejoinChannelBuffersResponse),
     (RejoinRoom, RejoinRoomResponse),
     (ReloadBuffers, ReloadBuffersResponse),
@@ -532,6 +581,25 @@ entity_messages!(
     GetProjectSymbols,
     GetReferences,
     GetSignatureHelp,
+    GetSemanticTokens,
+    PrepareCallHierarchy,
+    CallHierarchyIncomingCalls,
+    CallHierarchyOutgoingCalls,
+    PrepareTypeHierarchy,
+    TypeHierarchySupertypes,
+    TypeHierarchySubtypes,
+    GetCodeLens,
+    ExecuteCodeLens,
+    RefreshCodeLens,
+    GetGitStatus,
+    StageGitPaths,
+    UnstageGitPaths,
+    StageGitHunk,
+    UnstageGitHunk,
+    GitCommit,
+    GetGitChangedPaths,
+    GetGitLog,
+    GetGitCommitChanges,
     GetTypeDefinition,
     InlayHints,
     JoinProject,
@@ -547,6 +615,7 @@ entity_messages!(
     PerformRename,
     PrepareRename,
     RefreshInlayHints,
+    RefreshSemanticTokens,
     ReloadBuffers,
     RemoveProjectCollaborator,
     RenameProjectEntry,
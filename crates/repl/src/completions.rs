@@ -0,0 +1,139 @@
+use std::{ops::Range, sync::Arc};
+
+use anyhow::Result;
+use editor::{CompletionProvider, Editor};
+use gpui::{Model, Task, ViewContext, WeakView};
+use language::{
+    Anchor, Buffer, CodeLabel, CompletionContext, LanguageServerId, Point, ToPoint as _,
+    Transaction,
+};
+use parking_lot::RwLock;
+use project::{Completion, Project};
+use util::ResultExt as _;
+
+use crate::session::Session;
+
+/// Completes code with the matches of the kernel's `complete_reply`, in addition to the
+/// completions of the project's language servers.
+pub struct KernelCompletionProvider {
+    pub project: Model<Project>,
+    pub session: WeakView<Session>,
+}
+
+impl CompletionProvider for KernelCompletionProvider {
+    fn completions(
+        &self,
+        buffer: &Model<Buffer>,
+        buffer_position: Anchor,
+        trigger: CompletionContext,
+        cx: &mut ViewContext<Editor>,
+    ) -> Task<Result<Vec<Completion>>> {
+        let project_completions = self
+            .project
+            .completions(buffer, buffer_position, trigger, cx);
+        let kernel_completions = self.session.upgrade().map(|session| {
+            // Only the current line is sent, as the rest of the buffer may not be valid code
+            let snapshot = buffer.read(cx).snapshot();
+            let cursor = buffer_position.to_point(&snapshot);
+            let line_start = Point::new(cursor.row, 0);
+            let line = snapshot
+                .text_for_range(line_start..cursor)
+                .collect::<String>();
+            let cursor_pos = line.chars().count();
+            let reply = session.update(cx, |session, cx| {
+                session.complete(line.clone(), cursor_pos, cx)
+            });
+            (snapshot, line_start, line, reply)
+        });
+
+        cx.spawn(|_, _| async move {
+            let mut completions = project_completions.await.log_err().unwrap_or_default();
+            let Some((snapshot, line_start, line, reply)) = kernel_completions else {
+                return Ok(completions);
+            };
+            let Some(reply) = reply.await.log_err() else {
+                return Ok(completions);
+            };
+
+            let range = byte_range(&line, reply.cursor_start..reply.cursor_end);
+            let line_offset = snapshot.point_to_offset(line_start);
+            let old_range = snapshot.anchor_before(line_offset + range.start)
+                ..snapshot.anchor_after(line_offset + range.end);
+            for new_text in reply.matches {
+                if completions
+                    .iter()
+                    .any(|completion| completion.new_text == new_text)
+                {
+                    continue;
+                }
+                completions.push(Completion {
+                    old_range: old_range.clone(),
+                    label: CodeLabel::plain(new_text.clone(), None),
+                    new_text,
+                    server_id: LanguageServerId(usize::MAX),
+                    documentation: None,
+                    lsp_completion: Default::default(),
+                    confirm: None,
+                    show_new_completions_on_confirm: false,
+                });
+            }
+            Ok(completions)
+        })
+    }
+
+    fn resolve_completions(
+        &self,
+        buffer: Model<Buffer>,
+        completion_indices: Vec<usize>,
+        completions: Arc<RwLock<Box<[Completion]>>>,
+        cx: &mut ViewContext<Editor>,
+    ) -> Task<Result<bool>> {
+        self.project
+            .resolve_completions(buffer, completion_indices, completions, cx)
+    }
+
+    fn apply_additional_edits_for_completion(
+        &self,
+        buffer: Model<Buffer>,
+        completion: Completion,
+        push_to_history: bool,
+        cx: &mut ViewContext<Editor>,
+    ) -> Task<Result<Option<Transaction>>> {
+        self.project
+            .apply_additional_edits_for_completion(buffer, completion, push_to_history, cx)
+    }
+
+    fn is_completion_trigger(
+        &self,
+        buffer: &Model<Buffer>,
+        position: Anchor,
+        text: &str,
+        trigger_in_words: bool,
+        cx: &mut ViewContext<Editor>,
+    ) -> bool {
+        self.project
+            .is_completion_trigger(buffer, position, text, trigger_in_words, cx)
+    }
+}
+
+/// Converts a range of the `complete_reply`, which counts Unicode code points, to a byte range.
+fn byte_range(line: &str, range: Range<usize>) -> Range<usize> {
+    let offset = |position: usize| {
+        line.char_indices()
+            .nth(position)
+            .map_or(line.len(), |(offset, _)| offset)
+    };
+    offset(range.start)..offset(range.end)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_byte_range() {
+        assert_eq!(byte_range("df.he", 3..5), 3..5);
+        assert_eq!(byte_range("πr = np.ar", 8..10), 9..11);
+        assert_eq!(byte_range("x", 0..7), 0..1);
+    }
+}
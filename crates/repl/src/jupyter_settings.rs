@@ -21,6 +21,17 @@ pub struct JupyterSettings {
     pub dock: JupyterDockPosition,
     pub default_width: Pixels,
     pub kernel_selections: HashMap<String, String>,
+    pub servers: Vec<JupyterServer>,
+}
+
+/// A Jupyter server whose kernels can be used alongside the local ones.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct JupyterServer {
+    /// The URL of the server, such as `http://localhost:8888`.
+    pub url: String,
+    /// The token used to authenticate with the server.
+    #[serde(default)]
+    pub token: Option<String>,
 }
 
 impl JupyterSettings {
@@ -46,6 +57,10 @@ pub struct JupyterSettingsContent {
     ///
     /// Default: `{}`
     pub kernel_selections: Option<HashMap<String, String>>,
+    /// Jupyter servers to list kernels from, in addition to the local kernels.
+    ///
+    /// Default: `[]`
+    pub servers: Option<Vec<JupyterServer>>,
 }
 
 impl JupyterSettingsContent {
@@ -60,6 +75,7 @@ impl Default for JupyterSettingsContent {
             dock: Some(JupyterDockPosition::Right),
             default_width: Some(640.0),
             kernel_selections: Some(HashMap::new()),
+            servers: Some(Vec::new()),
         }
     }
 }
@@ -92,6 +108,10 @@ impl Settings for JupyterSettings {
                     settings.kernel_selections.insert(k.clone(), v.clone());
                 }
             }
+
+            if let Some(servers) = &value.servers {
+                settings.servers = servers.clone();
+            }
         }
 
         Ok(settings)
@@ -131,7 +151,10 @@ mod tests {
                         "jupyter": {
                             "enabled": true,
                             "dock": "left",
-                            "default_width": 800.0
+                            "default_width": 800.0,
+                            "servers": [
+                                { "url": "http://localhost:8888", "token": "secret" }
+                            ]
                         }
                     }"#,
                     cx,
@@ -148,5 +171,12 @@ mod tests {
             JupyterSettings::get_global(cx).default_width,
             Pixels::from(800.0)
         );
+        assert_eq!(
+            JupyterSettings::get_global(cx).servers,
+            vec![JupyterServer {
+                url: "http://localhost:8888".into(),
+                token: Some("secret".into()),
+            }]
+        );
     }
 }
@@ -11,20 +11,39 @@ use runtimelib::{
     dirs, ConnectionInfo, ExecutionState, JupyterKernelspec, JupyterMessage, JupyterMessageContent,
     KernelInfoReply,
 };
+use settings::Settings as _;
 use smol::{net::TcpListener, process::Command};
 use std::{
     fmt::Debug,
+    future::Future,
     net::{IpAddr, Ipv4Addr, SocketAddr},
     path::PathBuf,
     sync::Arc,
 };
 use ui::{Color, Indicator};
 
+use crate::{
+    jupyter_settings::{JupyterServer, JupyterSettings},
+    remote_kernels::{self, RemoteKernel},
+};
+
 #[derive(Debug, Clone)]
 pub struct KernelSpecification {
     pub name: String,
-    pub path: PathBuf,
     pub kernelspec: JupyterKernelspec,
+    pub location: KernelLocation,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum KernelLocation {
+    /// A kernelspec directory, as in /usr/local/share/jupyter/kernels/python3
+    Local(PathBuf),
+    /// A kernelspec of a Jupyter server. A new kernel is started on the server for each
+    /// session, unless `kernel_id` refers to a kernel that is already running there.
+    Remote {
+        server: JupyterServer,
+        kernel_id: Option<String>,
+    },
 }
 
 impl KernelSpecification {
@@ -115,23 +134,28 @@ impl Kernel {
 }
 
 pub struct RunningKernel {
-    pub process: smol::process::Child,
-    _shell_task: Task<anyhow::Result<()>>,
-    _iopub_task: Task<anyhow::Result<()>>,
-    _control_task: Task<anyhow::Result<()>>,
-    _routing_task: Task<anyhow::Result<()>>,
-    connection_path: PathBuf,
+    connection: KernelConnection,
+    _tasks: Vec<Task<anyhow::Result<()>>>,
     pub request_tx: mpsc::Sender<JupyterMessage>,
     pub execution_state: ExecutionState,
     pub kernel_info: Option<KernelInfoReply>,
 }
 
-type JupyterMessageChannel = stream::SelectAll<Receiver<JupyterMessage>>;
+#[derive(Debug)]
+enum KernelConnection {
+    Local {
+        process: smol::process::Child,
+        connection_path: PathBuf,
+    },
+    Remote(RemoteKernel),
+}
+
+pub(crate) type JupyterMessageChannel = stream::SelectAll<Receiver<JupyterMessage>>;
 
 impl Debug for RunningKernel {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("RunningKernel")
-            .field("process", &self.process)
+            .field("connection", &self.connection)
             .finish()
     }
 }
@@ -142,6 +166,59 @@ impl RunningKernel {
         entity_id: EntityId,
         fs: Arc<dyn Fs>,
         cx: &mut AppContext,
+    ) -> Task<anyhow::Result<(Self, JupyterMessageChannel)>> {
+        match kernel_specification.location.clone() {
+            KernelLocation::Local(_) => Self::start_local(kernel_specification, entity_id, fs, cx),
+            KernelLocation::Remote { server, kernel_id } => {
+                let http_client = cx.http_client();
+                cx.spawn(|cx| async move {
+                    let connection = remote_kernels::connect(
+                        &kernel_specification.name,
+                        server,
+                        kernel_id,
+                        http_client,
+                    )
+                    .await?;
+
+                    let mut messages_rx = SelectAll::new();
+                    messages_rx.push(connection.messages_rx);
+                    anyhow::Ok((
+                        Self {
+                            connection: KernelConnection::Remote(connection.kernel),
+                            _tasks: vec![cx.background_executor().spawn(connection.io)],
+                            request_tx: connection.request_tx,
+                            execution_state: ExecutionState::Busy,
+                            kernel_info: None,
+                        },
+                        messages_rx,
+                    ))
+                })
+            }
+        }
+    }
+
+    /// Whether the kernel was already running on a Jupyter server when the session
+    /// connected to it, in which case it is left running when the session ends.
+    pub fn is_attached(&self) -> bool {
+        match &self.connection {
+            KernelConnection::Local { .. } => false,
+            KernelConnection::Remote(kernel) => kernel.is_attached(),
+        }
+    }
+
+    /// Stops the kernel process, or the kernel that was started on a Jupyter server.
+    pub async fn kill(&mut self) -> Result<()> {
+        match &mut self.connection {
+            KernelConnection::Local { process, .. } => Ok(process.kill()?),
+            KernelConnection::Remote(kernel) => kernel.shutdown().await,
+        }
+    }
+
+    fn start_local(
+        kernel_specification: KernelSpecification,
+        entity_id: EntityId,
+        fs: Arc<dyn Fs>,
+        cx: &mut AppContext,
     ) -> Task<anyhow::Result<(Self, JupyterMessageChannel)>> {
         cx.spawn(|cx| async move {
             let ip = IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1));
@@ -210,15 +287,10 @@ impl RunningKernel {
             let _routing_task = cx.background_executor().spawn({
                 async move {
                     while let Some(message) = request_rx.next().await {
-                        match message.content {
-                            JupyterMessageContent::DebugRequest(_)
-                            | JupyterMessageContent::InterruptRequest(_)
-                            | JupyterMessageContent::ShutdownRequest(_) => {
-                                control_request_tx.send(message).await?;
-                            }
-                            _ => {
-                                shell_request_tx.send(message).await?;
-                            }
+                        if is_control_request(&message.content) {
+                            control_request_tx.send(message).await?;
+                        } else {
+                            shell_request_tx.send(message).await?;
                         }
                     }
                     anyhow::Ok(())
@@ -249,13 +321,12 @@ impl RunningKernel {
 
             anyhow::Ok((
                 Self {
-                    process,
+                    connection: KernelConnection::Local {
+                        process,
+                        connection_path,
+                    },
+                    _tasks: vec![_shell_task, _iopub_task, _control_task, _routing_task],
                     request_tx,
-                    _shell_task,
-                    _iopub_task,
-                    _control_task,
-                    _routing_task,
-                    connection_path,
                     execution_state: ExecutionState::Busy,
                     kernel_info: None,
                 },
@@ -267,12 +338,27 @@ impl RunningKernel {
 
 impl Drop for RunningKernel {
     fn drop(&mut self) {
-        std::fs::remove_file(&self.connection_path).ok();
+        if let KernelConnection::Local {
+            connection_path, ..
+        } = &self.connection
+        {
+            std::fs::remove_file(connection_path).ok();
+        }
 
         self.request_tx.close_channel();
     }
 }
 
+/// Whether the message has to be sent on the control channel rather than the shell channel.
+pub(crate) fn is_control_request(content: &JupyterMessageContent) -> bool {
+    matches!(
+        content,
+        JupyterMessageContent::DebugRequest(_)
+            | JupyterMessageContent::InterruptRequest(_)
+            | JupyterMessageContent::ShutdownRequest(_)
+    )
+}
+
 async fn read_kernelspec_at(
     // Path should be a directory to a jupyter kernelspec, as in
     // /usr/local/share/jupyter/kernels/python3
@@ -296,8 +382,8 @@ async fn read_kernelspec_at(
 
     Ok(KernelSpecification {
         name: kernel_name,
-        path,
         kernelspec: spec,
+        location: KernelLocation::Local(path),
     })
 }
 
@@ -340,6 +426,24 @@ pub async fn kernel_specifications(fs: Arc<dyn Fs>) -> anyhow::Result<Vec<Kernel
     Ok(kernel_dirs)
 }
 
+/// Lists the local kernels along with the kernels of the Jupyter servers in the settings.
+pub fn available_kernel_specifications(
+    fs: Arc<dyn Fs>,
+    cx: &AppContext,
+) -> impl Future<Output = Result<Vec<KernelSpecification>>> {
+    let servers = JupyterSettings::get_global(cx).servers.clone();
+    let http_client = cx.http_client();
+    async move {
+        let (local, remote) = futures::join!(
+            kernel_specifications(fs),
+            remote_kernels::kernel_specifications(servers, http_client)
+        );
+        let mut kernel_specifications = local?;
+        kernel_specifications.extend(remote);
+        Ok(kernel_specifications)
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
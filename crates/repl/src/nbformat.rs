@@ -0,0 +1,551 @@
+//! A model of the Jupyter notebook format (nbformat v4).
+//!
+//! Notebook and cell metadata are kept as raw JSON, so that anything Zed doesn't
+//! understand is written back unchanged when a notebook is saved.
+//! See https://nbformat.readthedocs.io/en/latest/format_description.html
+
+use anyhow::{Context as _, Result};
+use collections::HashMap;
+use runtimelib::{JupyterMessageContent, MimeBundle};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Notebook {
+    pub cells: Vec<Cell>,
+    #[serde(default)]
+    pub metadata: Map<String, Value>,
+    pub nbformat: u32,
+    pub nbformat_minor: u32,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "cell_type", rename_all = "lowercase")]
+pub enum Cell {
+    Code(CodeCell),
+    Markdown(TextCell),
+    Raw(TextCell),
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct CodeCell {
+    pub execution_count: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    #[serde(default)]
+    pub metadata: Map<String, Value>,
+    #[serde(default)]
+    pub outputs: Vec<Output>,
+    #[serde(with = "multiline_string")]
+    pub source: String,
+    /// Set when the kernel asked to clear the outputs once the next one arrives.
+    #[serde(skip)]
+    clear_pending: bool,
+    /// The indices of the outputs shown with a `display_id`, which can be updated in place.
+    #[serde(skip)]
+    display_ids: HashMap<String, Vec<usize>>,
+}
+
+/// A markdown or raw cell.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct TextCell {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub attachments: Option<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    #[serde(default)]
+    pub metadata: Map<String, Value>,
+    #[serde(with = "multiline_string")]
+    pub source: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "output_type", rename_all = "snake_case")]
+pub enum Output {
+    Stream {
+        name: String,
+        #[serde(with = "multiline_string")]
+        text: String,
+    },
+    DisplayData {
+        data: Map<String, Value>,
+        #[serde(default)]
+        metadata: Map<String, Value>,
+    },
+    ExecuteResult {
+        data: Map<String, Value>,
+        execution_count: Option<u32>,
+        #[serde(default)]
+        metadata: Map<String, Value>,
+    },
+    Error {
+        ename: String,
+        evalue: String,
+        traceback: Vec<String>,
+    },
+}
+
+impl Notebook {
+    pub fn parse(text: &str) -> Result<Self> {
+        let notebook: Self = serde_json::from_str(text).context("invalid notebook")?;
+        anyhow::ensure!(
+            notebook.nbformat == 4,
+            "unsupported notebook format version {}",
+            notebook.nbformat
+        );
+        Ok(notebook)
+    }
+
+    /// Serializes the notebook the same way Jupyter does, with single space indentation.
+    pub fn to_json(&self) -> Result<String> {
+        let mut bytes = Vec::new();
+        let formatter = serde_json::ser::PrettyFormatter::with_indent(b" ");
+        let mut serializer = serde_json::Serializer::with_formatter(&mut bytes, formatter);
+        self.serialize(&mut serializer)?;
+        bytes.push(b'\n');
+        Ok(String::from_utf8(bytes)?)
+    }
+
+    /// The name of the kernel the notebook was last run with.
+    pub fn kernel_name(&self) -> Option<&str> {
+        self.metadata.get("kernelspec")?.get("name")?.as_str()
+    }
+
+    /// The language of the notebook's code cells.
+    pub fn language_name(&self) -> Option<&str> {
+        self.metadata
+            .get("language_info")
+            .and_then(|info| info.get("name"))
+            .or_else(|| self.metadata.get("kernelspec")?.get("language"))?
+            .as_str()
+    }
+}
+
+impl Cell {
+    pub fn source(&self) -> &str {
+        match self {
+            Cell::Code(cell) => &cell.source,
+            Cell::Markdown(cell) | Cell::Raw(cell) => &cell.source,
+        }
+    }
+
+    pub fn set_source(&mut self, source: String) {
+        match self {
+            Cell::Code(cell) => cell.source = source,
+            Cell::Markdown(cell) | Cell::Raw(cell) => cell.source = source,
+        }
+    }
+}
+
+impl CodeCell {
+    /// Clears the outputs and execution count before the cell is run again.
+    pub fn reset(&mut self) {
+        self.execution_count = None;
+        self.clear_outputs();
+        self.clear_pending = false;
+    }
+
+    fn clear_outputs(&mut self) {
+        self.outputs.clear();
+        self.display_ids.clear();
+    }
+
+    /// Records a message sent by the kernel in response to executing this cell,
+    /// returning whether the cell changed.
+    pub fn handle_message(&mut self, content: &JupyterMessageContent) -> bool {
+        let (output_type, value) = match content {
+            JupyterMessageContent::StreamContent(stream) => {
+                ("stream", serde_json::to_value(stream))
+            }
+            JupyterMessageContent::DisplayData(data) => {
+                ("display_data", serde_json::to_value(data))
+            }
+            JupyterMessageContent::UpdateDisplayData(data) => {
+                ("update_display_data", serde_json::to_value(data))
+            }
+            JupyterMessageContent::ExecuteResult(result) => {
+                ("execute_result", serde_json::to_value(result))
+            }
+            JupyterMessageContent::ErrorOutput(error) => ("error", serde_json::to_value(error)),
+            JupyterMessageContent::ExecuteInput(input) => {
+                return self.set_execution_count(serde_json::to_value(input).ok());
+            }
+            JupyterMessageContent::ExecuteReply(reply) => {
+                return self.set_execution_count(serde_json::to_value(reply).ok());
+            }
+            JupyterMessageContent::ClearOutput(clear) => {
+                let wait = serde_json::to_value(clear)
+                    .ok()
+                    .and_then(|clear| clear.get("wait")?.as_bool())
+                    .unwrap_or(false);
+                if wait {
+                    self.clear_pending = true;
+                } else {
+                    self.clear_outputs();
+                }
+                return true;
+            }
+            _ => return false,
+        };
+        let Ok(value) = value else {
+            return false;
+        };
+        let display_id = value
+            .pointer("/transient/display_id")
+            .and_then(Value::as_str)
+            .map(str::to_string);
+
+        if output_type == "update_display_data" {
+            let Some(indices) = display_id.and_then(|id| self.display_ids.get(&id)) else {
+                return false;
+            };
+            let Some(output) = Output::from_message("display_data", value) else {
+                return false;
+            };
+            for ix in indices {
+                if let Some(existing_output) = self.outputs.get_mut(*ix) {
+                    *existing_output = output.clone();
+                }
+            }
+            return true;
+        }
+
+        let Some(output) = Output::from_message(output_type, value) else {
+            return false;
+        };
+        self.push_output(output);
+        if let Some(display_id) = display_id {
+            self.display_ids
+                .entry(display_id)
+                .or_default()
+                .push(self.outputs.len() - 1);
+        }
+        true
+    }
+
+    fn set_execution_count(&mut self, content: Option<Value>) -> bool {
+        let execution_count = content
+            .and_then(|content| content.get("execution_count")?.as_u64())
+            .map(|count| count as u32);
+        if execution_count.is_some() && execution_count != self.execution_count {
+            self.execution_count = execution_count;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn push_output(&mut self, output: Output) {
+        if std::mem::take(&mut self.clear_pending) {
+            self.clear_outputs();
+        }
+
+        // Consecutive writes to the same stream are stored as a single output.
+        if let (
+            Output::Stream { name, text },
+            Some(Output::Stream {
+                name: last_name,
+                text: last_text,
+            }),
+        ) = (&output, self.outputs.last_mut())
+        {
+            if name == last_name {
+                last_text.push_str(text);
+                return;
+            }
+        }
+        self.outputs.push(output);
+    }
+}
+
+impl Output {
+    fn from_message(output_type: &str, mut content: Value) -> Option<Self> {
+        let fields = content.as_object_mut()?;
+        // Only display data has a transient field, which isn't stored in notebooks.
+        fields.remove("transient");
+        fields.insert("output_type".into(), output_type.into());
+        serde_json::from_value(content).ok()
+    }
+
+    /// Converts the output's data to a bundle that can be rendered like kernel messages.
+    pub fn mime_bundle(data: &Map<String, Value>) -> Option<MimeBundle> {
+        let data = data
+            .iter()
+            .map(|(mime_type, value)| {
+                let value = match value {
+                    // Text is usually stored as a list of lines, except for JSON data.
+                    Value::Array(lines) if !mime_type.ends_with("json") => Value::String(
+                        lines
+                            .iter()
+                            .filter_map(|line| line.as_str())
+                            .collect::<String>(),
+                    ),
+                    value => value.clone(),
+                };
+                (mime_type.clone(), value)
+            })
+            .collect::<Map<_, _>>();
+        serde_json::from_value(Value::Object(data)).ok()
+    }
+}
+
+/// Text in notebooks can be stored either as a single string or as a list of lines,
+/// and is written back as a list of lines, like Jupyter does.
+mod multiline_string {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(text: &str, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(text.split_inclusive('\n'))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<String, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum MultilineString {
+            Single(String),
+            Lines(Vec<String>),
+        }
+
+        Ok(match MultilineString::deserialize(deserializer)? {
+            MultilineString::Single(text) => text,
+            MultilineString::Lines(lines) => lines.concat(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const NOTEBOOK: &str = r##"{
+ "cells": [
+  {
+   "cell_type": "markdown",
+   "id": "a1",
+   "metadata": {},
+   "source": [
+    "# Title\n",
+    "\n",
+    "Some text"
+   ]
+  },
+  {
+   "cell_type": "code",
+   "execution_count": 2,
+   "id": "b2",
+   "metadata": {
+    "scrolled": true
+   },
+   "outputs": [
+    {
+     "name": "stdout",
+     "output_type": "stream",
+     "text": [
+      "hello\n"
+     ]
+    },
+    {
+     "data": {
+      "image/png": "iVBORw0KGgo=",
+      "text/plain": [
+       "<Figure>"
+      ]
+     },
+     "metadata": {
+      "needs_background": "light"
+     },
+     "output_type": "display_data"
+    },
+    {
+     "data": {
+      "text/plain": [
+       "3"
+      ]
+     },
+     "execution_count": 2,
+     "metadata": {},
+     "output_type": "execute_result"
+    },
+    {
+     "ename": "ZeroDivisionError",
+     "evalue": "division by zero",
+     "output_type": "error",
+     "traceback": [
+      "Traceback"
+     ]
+    }
+   ],
+   "source": [
+    "print('hello')\n",
+    "1 + 2"
+   ]
+  },
+  {
+   "cell_type": "raw",
+   "metadata": {},
+   "source": "raw text"
+  }
+ ],
+ "metadata": {
+  "kernelspec": {
+   "display_name": "Python 3",
+   "language": "python",
+   "name": "python3"
+  },
+  "language_info": {
+   "name": "python",
+   "version": "3.12.0"
+  },
+  "custom": {
+   "unknown": [1, 2, 3]
+  }
+ },
+ "nbformat": 4,
+ "nbformat_minor": 5
+}
+"##;
+
+    #[test]
+    fn test_parse_notebook() {
+        let notebook = Notebook::parse(NOTEBOOK).unwrap();
+        assert_eq!(notebook.cells.len(), 3);
+        assert_eq!(notebook.kernel_name(), Some("python3"));
+        assert_eq!(notebook.language_name(), Some("python"));
+
+        assert_eq!(notebook.cells[0].source(), "# Title\n\nSome text");
+        assert!(matches!(notebook.cells[0], Cell::Markdown(_)));
+        assert_eq!(notebook.cells[2].source(), "raw text");
+        assert!(matches!(notebook.cells[2], Cell::Raw(_)));
+
+        let Cell::Code(cell) = &notebook.cells[1] else {
+            panic!("expected a code cell");
+        };
+        assert_eq!(cell.source, "print('hello')\n1 + 2");
+        assert_eq!(cell.execution_count, Some(2));
+        assert_eq!(cell.metadata["scrolled"], json!(true));
+        assert_eq!(cell.outputs.len(), 4);
+        assert_eq!(
+            cell.outputs[0],
+            Output::Stream {
+                name: "stdout".into(),
+                text: "hello\n".into()
+            }
+        );
+        assert!(matches!(
+            &cell.outputs[2],
+            Output::ExecuteResult {
+                execution_count: Some(2),
+                ..
+            }
+        ));
+
+        assert!(Notebook::parse(r#"{"cells": [], "nbformat": 3, "nbformat_minor": 0}"#).is_err());
+        assert!(Notebook::parse("[]").is_err());
+    }
+
+    #[test]
+    fn test_serialize_notebook() {
+        let mut notebook = Notebook::parse(NOTEBOOK).unwrap();
+        let json = notebook.to_json().unwrap();
+        assert_eq!(Notebook::parse(&json).unwrap(), notebook);
+
+        let value: Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["metadata"]["custom"], json!({ "unknown": [1, 2, 3] }));
+        assert_eq!(value["cells"][2]["source"], json!(["raw text"]));
+        assert_eq!(
+            value["cells"][1]["outputs"][1]["data"]["image/png"],
+            "iVBORw0KGgo="
+        );
+        assert!(value["cells"][0].get("execution_count").is_none());
+
+        notebook.cells[1].set_source("x = 1\n\ny = 2\n".into());
+        let Cell::Code(cell) = &mut notebook.cells[1] else {
+            panic!("expected a code cell");
+        };
+        cell.reset();
+        let value: Value = serde_json::from_str(&notebook.to_json().unwrap()).unwrap();
+        assert_eq!(
+            value["cells"][1],
+            json!({
+                "cell_type": "code",
+                "execution_count": null,
+                "id": "b2",
+                "metadata": { "scrolled": true },
+                "outputs": [],
+                "source": ["x = 1\n", "\n", "y = 2\n"]
+            })
+        );
+    }
+
+    #[test]
+    fn test_merge_stream_outputs() {
+        let mut cell = CodeCell::default();
+        cell.push_output(Output::Stream {
+            name: "stdout".into(),
+            text: "one\n".into(),
+        });
+        cell.push_output(Output::Stream {
+            name: "stdout".into(),
+            text: "two\n".into(),
+        });
+        cell.push_output(Output::Stream {
+            name: "stderr".into(),
+            text: "three\n".into(),
+        });
+        assert_eq!(
+            cell.outputs,
+            vec![
+                Output::Stream {
+                    name: "stdout".into(),
+                    text: "one\ntwo\n".into()
+                },
+                Output::Stream {
+                    name: "stderr".into(),
+                    text: "three\n".into()
+                }
+            ]
+        );
+
+        cell.clear_pending = true;
+        let output = Output::from_message(
+            "display_data",
+            json!({ "data": { "text/plain": "4" }, "metadata": {}, "transient": {} }),
+        )
+        .unwrap();
+        cell.push_output(output.clone());
+        assert_eq!(cell.outputs, vec![output]);
+    }
+
+    #[test]
+    fn test_update_display_data() {
+        let message = |msg_type: &str, text: &str| {
+            JupyterMessageContent::from_type_and_content(
+                msg_type,
+                json!({
+                    "data": { "text/plain": text },
+                    "metadata": {},
+                    "transient": { "display_id": "progress" }
+                }),
+            )
+            .unwrap()
+        };
+
+        let mut cell = CodeCell::default();
+        assert!(cell.handle_message(&message("display_data", "10%")));
+        assert!(cell.handle_message(&message("display_data", "other")));
+        assert!(cell.handle_message(&message("update_display_data", "50%")));
+        let output = |text: &str| {
+            Output::from_message(
+                "display_data",
+                json!({ "data": { "text/plain": text }, "metadata": {} }),
+            )
+            .unwrap()
+        };
+        assert_eq!(cell.outputs, vec![output("50%"), output("50%")]);
+
+        cell.reset();
+        assert!(!cell.handle_message(&message("update_display_data", "90%")));
+        assert_eq!(cell.outputs, vec![]);
+    }
+}
@@ -0,0 +1,761 @@
+use crate::{
+    jupyter_settings::JupyterSettings,
+    kernels::{available_kernel_specifications, Kernel, KernelSpecification, RunningKernel},
+    nbformat::{Cell, Notebook},
+    outputs::{ExecutionStatus, ExecutionView, LineHeight as _},
+    session::EditorBlock,
+};
+use anyhow::{Context as _, Result};
+use collections::{HashMap, HashSet};
+use editor::{
+    display_map::{BlockDisposition, BlockId, BlockProperties, BlockStyle},
+    Editor, EditorEvent,
+};
+use futures::{future::Shared, FutureExt as _, StreamExt as _};
+use gpui::{
+    actions, AnyElement, AnyView, AppContext, EventEmitter, FocusHandle, FocusableView, Model,
+    Subscription, Task, View,
+};
+use language::Buffer;
+use multi_buffer::{ExcerptId, ExcerptRange, MultiBuffer};
+use project::{Project, ProjectEntryId, ProjectPath};
+use runtimelib::{
+    ExecuteRequest, ExecutionState, InterruptRequest, JupyterMessage, JupyterMessageContent,
+};
+use settings::Settings as _;
+use std::{any::TypeId, ffi::OsStr};
+use ui::{prelude::*, ButtonLike, Tooltip};
+use workspace::{
+    item::{Item, ItemEvent, ProjectItem, TabContentParams},
+    searchable::SearchableItemHandle,
+};
+
+actions!(
+    notebook,
+    [RunCell, RunAllCells, ClearAllOutputs, InterruptKernel]
+);
+
+pub fn init(cx: &mut AppContext) {
+    workspace::register_project_item::<NotebookEditor>(cx);
+}
+
+/// An `.ipynb` file opened as a notebook.
+pub struct NotebookItem {
+    buffer: Model<Buffer>,
+    notebook: Notebook,
+}
+
+impl project::Item for NotebookItem {
+    fn try_open(
+        project: &Model<Project>,
+        path: &ProjectPath,
+        cx: &mut AppContext,
+    ) -> Option<Task<Result<Model<Self>>>> {
+        if path.path.extension().and_then(OsStr::to_str) != Some("ipynb")
+            || !JupyterSettings::enabled(cx)
+        {
+            return None;
+        }
+
+        let open_buffer = project.update(cx, |project, cx| project.open_buffer(path.clone(), cx));
+        Some(cx.spawn(|mut cx| async move {
+            let buffer = open_buffer.await?;
+            let notebook = buffer.read_with(&cx, |buffer, _| Notebook::parse(&buffer.text()))??;
+            cx.new_model(|_| NotebookItem { buffer, notebook })
+        }))
+    }
+
+    fn entry_id(&self, cx: &AppContext) -> Option<ProjectEntryId> {
+        project::Item::entry_id(self.buffer.read(cx), cx)
+    }
+
+    fn project_path(&self, cx: &AppContext) -> Option<ProjectPath> {
+        project::Item::project_path(self.buffer.read(cx), cx)
+    }
+}
+
+/// Presents the cells of a notebook as excerpts of a multibuffer, with the outputs
+/// of code cells rendered in blocks below them.
+pub struct NotebookEditor {
+    project: Model<Project>,
+    item: Model<NotebookItem>,
+    editor: View<Editor>,
+    multi_buffer: Model<MultiBuffer>,
+    /// The metadata and outputs of the notebook. The source of each cell lives in the
+    /// buffer at the same index of `cells`, and is only written back when saving.
+    notebook: Notebook,
+    cells: Vec<CellState>,
+    outputs_edited: bool,
+    kernel: Kernel,
+    kernel_specification: Option<KernelSpecification>,
+    /// The cells that are waiting for results, keyed by the id of their execute request.
+    executions: HashMap<String, ExcerptId>,
+    messaging_task: Task<()>,
+    _subscriptions: Vec<Subscription>,
+}
+
+struct CellState {
+    excerpt_id: ExcerptId,
+    buffer: Model<Buffer>,
+    output: Option<(BlockId, View<ExecutionView>)>,
+    _output_subscription: Option<Subscription>,
+}
+
+impl NotebookEditor {
+    fn new(project: Model<Project>, item: Model<NotebookItem>, cx: &mut ViewContext<Self>) -> Self {
+        let multi_buffer = cx.new_model(|cx| {
+            MultiBuffer::new(project.read(cx).replica_id(), project.read(cx).capability())
+        });
+        let editor = cx.new_view(|cx| {
+            Editor::for_multibuffer(multi_buffer.clone(), Some(project.clone()), false, cx)
+        });
+        let subscriptions = vec![cx.subscribe(&editor, |_, _, event: &EditorEvent, cx| {
+            cx.emit(event.clone())
+        })];
+
+        let notebook = item.read(cx).notebook.clone();
+        let mut this = Self {
+            project,
+            item,
+            editor,
+            multi_buffer,
+            notebook: Notebook {
+                cells: Vec::new(),
+                ..notebook.clone()
+            },
+            cells: Vec::new(),
+            outputs_edited: false,
+            kernel: Kernel::Shutdown,
+            kernel_specification: None,
+            executions: HashMap::default(),
+            messaging_task: Task::ready(()),
+            _subscriptions: subscriptions,
+        };
+        this.set_notebook(notebook, cx);
+        this
+    }
+
+    fn set_notebook(&mut self, notebook: Notebook, cx: &mut ViewContext<Self>) {
+        let blocks_to_remove = self
+            .cells
+            .drain(..)
+            .filter_map(|cell| Some(cell.output?.0))
+            .collect::<HashSet<_>>();
+        self.editor.update(cx, |editor, cx| {
+            editor.remove_blocks(blocks_to_remove, None, cx);
+        });
+        self.multi_buffer
+            .update(cx, |multi_buffer, cx| multi_buffer.clear(cx));
+        self.executions.clear();
+        self.outputs_edited = false;
+
+        let language_registry = self.project.read(cx).languages().clone();
+        let mut code_buffers = Vec::new();
+        let mut markdown_buffers = Vec::new();
+        for cell in &notebook.cells {
+            let buffer = cx.new_model(|cx| {
+                let mut buffer = Buffer::local(cell.source(), cx);
+                buffer.set_language_registry(language_registry.clone());
+                buffer
+            });
+            match cell {
+                Cell::Code(_) => code_buffers.push(buffer.downgrade()),
+                Cell::Markdown(_) => markdown_buffers.push(buffer.downgrade()),
+                Cell::Raw(_) => {}
+            }
+
+            let len = buffer.read(cx).len();
+            let excerpt_id = self.multi_buffer.update(cx, |multi_buffer, cx| {
+                multi_buffer.push_excerpts(
+                    buffer.clone(),
+                    [ExcerptRange {
+                        context: 0..len,
+                        primary: None,
+                    }],
+                    cx,
+                )[0]
+            });
+            self.cells.push(CellState {
+                excerpt_id,
+                buffer,
+                output: None,
+                _output_subscription: None,
+            });
+        }
+
+        let code_language = notebook.language_name().unwrap_or("python");
+        for (language_name, buffers) in [
+            (code_language, code_buffers),
+            ("Markdown", markdown_buffers),
+        ] {
+            if buffers.is_empty() {
+                continue;
+            }
+            let language = language_registry.language_for_name(language_name);
+            cx.spawn(|_, mut cx| async move {
+                let language = language.await?;
+                for buffer in buffers {
+                    buffer
+                        .update(&mut cx, |buffer, cx| {
+                            buffer.set_language(Some(language.clone()), cx)
+                        })
+                        .ok();
+                }
+                anyhow::Ok(())
+            })
+            .detach_and_log_err(cx);
+        }
+
+        for (ix, cell) in notebook.cells.iter().enumerate() {
+            if let Cell::Code(cell) = cell {
+                if !cell.outputs.is_empty() {
+                    let execution_view =
+                        cx.new_view(|cx| ExecutionView::from_notebook_outputs(&cell.outputs, cx));
+                    self.set_output(ix, Some(execution_view), cx);
+                }
+            }
+        }
+
+        self.notebook = notebook;
+        cx.notify();
+    }
+
+    fn cell_ix(&self, excerpt_id: ExcerptId) -> Option<usize> {
+        self.cells
+            .iter()
+            .position(|cell| cell.excerpt_id == excerpt_id)
+    }
+
+    /// Returns the index of the cell containing the newest cursor.
+    fn active_cell_ix(&self, cx: &AppContext) -> Option<usize> {
+        let head = self.editor.read(cx).selections.newest_anchor().head();
+        self.cell_ix(head.excerpt_id)
+    }
+
+    /// Replaces the output block shown below the cell.
+    fn set_output(
+        &mut self,
+        ix: usize,
+        execution_view: Option<View<ExecutionView>>,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let cell = &mut self.cells[ix];
+        let excerpt_id = cell.excerpt_id;
+        let previous_output = cell.output.take();
+        let block_id = self.editor.update(cx, |editor, cx| {
+            if let Some((block_id, _)) = previous_output {
+                editor.remove_blocks(HashSet::from_iter([block_id]), None, cx);
+            }
+            let execution_view = execution_view.clone()?;
+            let position = editor
+                .buffer()
+                .read(cx)
+                .snapshot(cx)
+                .anchor_in_excerpt(excerpt_id, language::Anchor::MAX)?;
+            let block = BlockProperties {
+                position,
+                height: execution_view.num_lines(cx).saturating_add(1),
+                style: BlockStyle::Sticky,
+                render: EditorBlock::create_output_area_render(execution_view),
+                disposition: BlockDisposition::Below,
+            };
+            Some(editor.insert_blocks([block], None, cx)[0])
+        });
+        // Resize the block whenever the outputs change, such as when a JSON tree is expanded
+        self.cells[ix]._output_subscription = execution_view.as_ref().map(|execution_view| {
+            cx.observe(execution_view, move |this, _, cx| {
+                this.refresh_output(ix, cx)
+            })
+        });
+        self.cells[ix].output = block_id.zip(execution_view);
+    }
+
+    /// Resizes the cell's output block to fit its contents.
+    fn refresh_output(&mut self, ix: usize, cx: &mut ViewContext<Self>) {
+        let Some((block_id, execution_view)) = self.cells[ix].output.clone() else {
+            return;
+        };
+        self.editor.update(cx, |editor, cx| {
+            let mut replacements = HashMap::default();
+            replacements.insert(
+                block_id,
+                (
+                    Some(execution_view.num_lines(cx).saturating_add(1)),
+                    EditorBlock::create_output_area_render(execution_view.clone()),
+                ),
+            );
+            editor.replace_blocks(replacements, None, cx);
+        });
+    }
+
+    fn run_cell(&mut self, _: &RunCell, cx: &mut ViewContext<Self>) {
+        if let Some(ix) = self.active_cell_ix(cx) {
+            self.run_cells(vec![ix], cx);
+        }
+    }
+
+    fn run_all_cells(&mut self, _: &RunAllCells, cx: &mut ViewContext<Self>) {
+        self.run_cells((0..self.cells.len()).collect(), cx);
+    }
+
+    fn run_cells(&mut self, indices: Vec<usize>, cx: &mut ViewContext<Self>) {
+        let status = match &self.kernel {
+            Kernel::RunningKernel(_) => ExecutionStatus::Queued,
+            Kernel::StartingKernel(_) | Kernel::Shutdown => ExecutionStatus::ConnectingToKernel,
+            Kernel::ErroredLaunch(error) => ExecutionStatus::KernelErrored(error.clone()),
+            Kernel::ShuttingDown => ExecutionStatus::ShuttingDown,
+        };
+
+        let mut messages = Vec::new();
+        for ix in indices {
+            let Some(Cell::Code(cell)) = self.notebook.cells.get_mut(ix) else {
+                continue;
+            };
+            cell.reset();
+            self.outputs_edited = true;
+
+            let code = self.cells[ix].buffer.read(cx).text();
+            let message: JupyterMessage = ExecuteRequest {
+                code,
+                ..ExecuteRequest::default()
+            }
+            .into();
+            self.executions
+                .insert(message.header.msg_id.clone(), self.cells[ix].excerpt_id);
+            messages.push(message);
+
+            let status = status.clone();
+            let execution_view = cx.new_view(|cx| ExecutionView::new(status, cx));
+            self.set_output(ix, Some(execution_view), cx);
+        }
+        cx.emit(EditorEvent::DirtyChanged);
+
+        let pending_kernel = match &self.kernel {
+            Kernel::RunningKernel(_) => {
+                for message in messages {
+                    self.send(message);
+                }
+                return;
+            }
+            Kernel::StartingKernel(task) => task.clone(),
+            Kernel::Shutdown => self.start_kernel(cx),
+            Kernel::ErroredLaunch(_) | Kernel::ShuttingDown => return,
+        };
+
+        // Queue up the executions to run after the kernel starts
+        cx.spawn(|this, mut cx| async move {
+            pending_kernel.await;
+            this.update(&mut cx, |this, _| {
+                for message in messages {
+                    this.send(message);
+                }
+            })
+            .ok();
+        })
+        .detach();
+    }
+
+    fn start_kernel(&mut self, cx: &mut ViewContext<Self>) -> Shared<Task<()>> {
+        let fs = self.project.read(cx).fs().clone();
+        let entity_id = cx.entity_id();
+        let kernel_specifications = available_kernel_specifications(fs.clone(), cx);
+        let pending_kernel = cx
+            .spawn(|this, mut cx| async move {
+                let kernel: Result<_> = async {
+                    let kernel_specifications = kernel_specifications.await?;
+                    let kernel = this.update(&mut cx, |this, cx| {
+                        let kernel_specification = this
+                            .kernel_specification(kernel_specifications, cx)
+                            .context("No kernel found for the notebook")?;
+                        this.kernel_specification = Some(kernel_specification.clone());
+                        anyhow::Ok(RunningKernel::new(kernel_specification, entity_id, fs, cx))
+                    })??;
+                    kernel.await
+                }
+                .await;
+
+                this.update(&mut cx, |this, cx| {
+                    match kernel {
+                        Ok((kernel, mut messages_rx)) => {
+                            this.kernel = Kernel::RunningKernel(kernel);
+                            this.messaging_task = cx.spawn(|this, mut cx| async move {
+                                while let Some(message) = messages_rx.next().await {
+                                    this.update(&mut cx, |this, cx| this.route(&message, cx))
+                                        .ok();
+                                }
+                            });
+                        }
+                        Err(error) => {
+                            let error = error.to_string();
+                            for (_, excerpt_id) in this.executions.drain() {
+                                let execution_view = this
+                                    .cells
+                                    .iter()
+                                    .find(|cell| cell.excerpt_id == excerpt_id)
+                                    .and_then(|cell| Some(cell.output.as_ref()?.1.clone()));
+                                if let Some(execution_view) = execution_view {
+                                    execution_view.update(cx, |execution_view, cx| {
+                                        execution_view.status =
+                                            ExecutionStatus::KernelErrored(error.clone());
+                                        cx.notify();
+                                    });
+                                }
+                            }
+                            this.kernel = Kernel::ErroredLaunch(error);
+                        }
+                    }
+                    cx.notify();
+                })
+                .ok();
+            })
+            .shared();
+        self.kernel = Kernel::StartingKernel(pending_kernel.clone());
+        cx.notify();
+        pending_kernel
+    }
+
+    /// Picks the kernel the notebook was saved with, falling back to the kernel
+    /// selected for its language in the settings, or any kernel for its language.
+    fn kernel_specification(
+        &self,
+        kernel_specifications: Vec<KernelSpecification>,
+        cx: &AppContext,
+    ) -> Option<KernelSpecification> {
+        let language = self
+            .notebook
+            .language_name()
+            .unwrap_or("python")
+            .to_lowercase();
+        let selected_kernel = JupyterSettings::get_global(cx)
+            .kernel_selections
+            .get(&language);
+
+        let find = |matches: &dyn Fn(&KernelSpecification) -> bool| {
+            kernel_specifications
+                .iter()
+                .find(|spec| matches(spec))
+                .cloned()
+        };
+        self.notebook
+            .kernel_name()
+            .and_then(|name| find(&|spec| spec.name == name))
+            .or_else(|| {
+                let selected_kernel = selected_kernel?.to_lowercase();
+                find(&|spec| spec.name.to_lowercase() == selected_kernel)
+            })
+            .or_else(|| find(&|spec| spec.kernelspec.language.to_lowercase() == language))
+    }
+
+    fn send(&mut self, message: JupyterMessage) {
+        if let Kernel::RunningKernel(kernel) = &mut self.kernel {
+            kernel.request_tx.try_send(message).ok();
+        }
+    }
+
+    fn route(&mut self, message: &JupyterMessage, cx: &mut ViewContext<Self>) {
+        let Some(parent_message_id) = message
+            .parent_header
+            .as_ref()
+            .map(|header| header.msg_id.clone())
+        else {
+            return;
+        };
+
+        match &message.content {
+            JupyterMessageContent::Status(status) => {
+                self.kernel.set_execution_state(&status.execution_state);
+                cx.notify();
+            }
+            JupyterMessageContent::KernelInfoReply(reply) => {
+                self.kernel.set_kernel_info(reply);
+                cx.notify();
+            }
+            _ => {}
+        }
+
+        let Some(ix) = self
+            .executions
+            .get(&parent_message_id)
+            .and_then(|excerpt_id| self.cell_ix(*excerpt_id))
+        else {
+            return;
+        };
+
+        if let Some(Cell::Code(cell)) = self.notebook.cells.get_mut(ix) {
+            if cell.handle_message(&message.content) {
+                self.outputs_edited = true;
+                cx.emit(EditorEvent::DirtyChanged);
+            }
+        }
+        if let Some((_, execution_view)) = &self.cells[ix].output {
+            execution_view.update(cx, |execution_view, cx| {
+                execution_view.push_message(&message.content, cx);
+            });
+        }
+
+        // The kernel goes back to idle once it has sent all the outputs for a request.
+        if let JupyterMessageContent::Status(status) = &message.content {
+            if matches!(status.execution_state, ExecutionState::Idle) {
+                self.executions.remove(&parent_message_id);
+            }
+        }
+    }
+
+    fn clear_all_outputs(&mut self, _: &ClearAllOutputs, cx: &mut ViewContext<Self>) {
+        for ix in 0..self.cells.len() {
+            if let Some(Cell::Code(cell)) = self.notebook.cells.get_mut(ix) {
+                if !cell.outputs.is_empty() || cell.execution_count.is_some() {
+                    cell.reset();
+                    self.outputs_edited = true;
+                }
+            }
+            self.set_output(ix, None, cx);
+        }
+        self.executions.clear();
+        cx.emit(EditorEvent::DirtyChanged);
+    }
+
+    fn interrupt_kernel(&mut self, _: &InterruptKernel, _: &mut ViewContext<Self>) {
+        self.send(InterruptRequest {}.into());
+    }
+
+    /// Serializes the notebook with the current contents of its cells.
+    fn to_json(&self, cx: &AppContext) -> Result<String> {
+        let mut notebook = self.notebook.clone();
+        for (cell, state) in notebook.cells.iter_mut().zip(&self.cells) {
+            cell.set_source(state.buffer.read(cx).text());
+        }
+        notebook.to_json()
+    }
+
+    fn write(
+        &mut self,
+        project: Model<Project>,
+        path: Option<ProjectPath>,
+        cx: &mut ViewContext<Self>,
+    ) -> Task<Result<()>> {
+        let json = match self.to_json(cx) {
+            Ok(json) => json,
+            Err(error) => return Task::ready(Err(error)),
+        };
+        let file_buffer = self.item.read(cx).buffer.clone();
+        file_buffer.update(cx, |buffer, cx| {
+            buffer.set_text(json, cx);
+        });
+        let save = project.update(cx, |project, cx| match path {
+            Some(path) => project.save_buffer_as(file_buffer, path, cx),
+            None => project.save_buffer(file_buffer, cx),
+        });
+
+        cx.spawn(|this, mut cx| async move {
+            save.await?;
+            this.update(&mut cx, |this, cx| {
+                for cell in &this.cells {
+                    cell.buffer.update(cx, |buffer, cx| {
+                        buffer.did_save(buffer.version(), None, cx);
+                    });
+                }
+                this.outputs_edited = false;
+                cx.emit(EditorEvent::Saved);
+                cx.emit(EditorEvent::DirtyChanged);
+            })
+        })
+    }
+
+    fn render_toolbar(&self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let kernel_name = self
+            .kernel_specification
+            .as_ref()
+            .map(|spec| spec.name.clone())
+            .or_else(|| self.notebook.kernel_name().map(ToOwned::to_owned))
+            .unwrap_or_else(|| "No kernel".to_string());
+        let status = match &self.kernel {
+            Kernel::RunningKernel(_) => kernel_name,
+            Kernel::StartingKernel(_) => format!("{kernel_name} (Starting)"),
+            Kernel::ErroredLaunch(error) => format!("{kernel_name} (Error: {error})"),
+            Kernel::ShuttingDown => format!("{kernel_name} (Shutting Down)"),
+            Kernel::Shutdown => kernel_name,
+        };
+
+        h_flex()
+            .w_full()
+            .px_2()
+            .py_1()
+            .gap_2()
+            .border_b_1()
+            .border_color(cx.theme().colors().border)
+            .child(self.kernel.dot())
+            .child(Label::new(status).size(LabelSize::Small))
+            .child(div().flex_1())
+            .child(
+                ButtonLike::new("run-all-cells")
+                    .child(Label::new("Run All").size(LabelSize::Small))
+                    .tooltip(|cx| Tooltip::for_action("Run All Cells", &RunAllCells, cx))
+                    .on_click(cx.listener(|this, _, cx| this.run_all_cells(&RunAllCells, cx))),
+            )
+            .child(
+                ButtonLike::new("clear-all-outputs")
+                    .child(Label::new("Clear Outputs").size(LabelSize::Small))
+                    .on_click(
+                        cx.listener(|this, _, cx| this.clear_all_outputs(&ClearAllOutputs, cx)),
+                    ),
+            )
+            .when(matches!(self.kernel, Kernel::RunningKernel(_)), |this| {
+                this.child(
+                    ButtonLike::new("interrupt-kernel")
+                        .child(Label::new("Interrupt").size(LabelSize::Small))
+                        .on_click(
+                            cx.listener(|this, _, cx| this.interrupt_kernel(&InterruptKernel, cx)),
+                        ),
+                )
+            })
+    }
+}
+
+impl EventEmitter<EditorEvent> for NotebookEditor {}
+
+impl FocusableView for NotebookEditor {
+    fn focus_handle(&self, cx: &AppContext) -> FocusHandle {
+        self.editor.focus_handle(cx)
+    }
+}
+
+impl Render for NotebookEditor {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        v_flex()
+            .key_context("Notebook")
+            .on_action(cx.listener(Self::run_cell))
+            .on_action(cx.listener(Self::run_all_cells))
+            .on_action(cx.listener(Self::clear_all_outputs))
+            .on_action(cx.listener(Self::interrupt_kernel))
+            .size_full()
+            .child(self.render_toolbar(cx))
+            .child(self.editor.clone())
+    }
+}
+
+impl Item for NotebookEditor {
+    type Event = EditorEvent;
+
+    fn to_item_events(event: &EditorEvent, f: impl FnMut(ItemEvent)) {
+        Editor::to_item_events(event, f)
+    }
+
+    fn tab_content(&self, params: TabContentParams, cx: &WindowContext) -> AnyElement {
+        let title = self
+            .item
+            .read(cx)
+            .buffer
+            .read(cx)
+            .file()
+            .map(|file| file.file_name(cx).to_string_lossy().to_string())
+            .unwrap_or_else(|| "untitled".to_string());
+        Label::new(title)
+            .single_line()
+            .color(if params.selected {
+                Color::Default
+            } else {
+                Color::Muted
+            })
+            .italic(params.preview)
+            .into_any_element()
+    }
+
+    fn telemetry_event_text(&self) -> Option<&'static str> {
+        Some("notebook")
+    }
+
+    fn for_each_project_item(
+        &self,
+        cx: &AppContext,
+        f: &mut dyn FnMut(gpui::EntityId, &dyn project::Item),
+    ) {
+        let buffer = &self.item.read(cx).buffer;
+        f(buffer.entity_id(), buffer.read(cx))
+    }
+
+    fn is_singleton(&self, _: &AppContext) -> bool {
+        true
+    }
+
+    fn deactivated(&mut self, cx: &mut ViewContext<Self>) {
+        self.editor.update(cx, |editor, cx| editor.deactivated(cx));
+    }
+
+    fn is_dirty(&self, cx: &AppContext) -> bool {
+        self.outputs_edited || self.multi_buffer.read(cx).is_dirty(cx)
+    }
+
+    fn has_conflict(&self, cx: &AppContext) -> bool {
+        self.item.read(cx).buffer.read(cx).has_conflict()
+    }
+
+    fn can_save(&self, _: &AppContext) -> bool {
+        true
+    }
+
+    fn save(
+        &mut self,
+        _format: bool,
+        project: Model<Project>,
+        cx: &mut ViewContext<Self>,
+    ) -> Task<Result<()>> {
+        self.write(project, None, cx)
+    }
+
+    fn save_as(
+        &mut self,
+        project: Model<Project>,
+        path: ProjectPath,
+        cx: &mut ViewContext<Self>,
+    ) -> Task<Result<()>> {
+        self.write(project, Some(path), cx)
+    }
+
+    fn reload(&mut self, project: Model<Project>, cx: &mut ViewContext<Self>) -> Task<Result<()>> {
+        let file_buffer = self.item.read(cx).buffer.clone();
+        let reload = project.update(cx, |project, cx| {
+            project.reload_buffers(HashSet::from_iter([file_buffer.clone()]), false, cx)
+        });
+        cx.spawn(|this, mut cx| async move {
+            reload.await?;
+            let notebook =
+                file_buffer.read_with(&cx, |buffer, _| Notebook::parse(&buffer.text()))??;
+            this.update(&mut cx, |this, cx| {
+                this.set_notebook(notebook, cx);
+                cx.emit(EditorEvent::DirtyChanged);
+            })
+        })
+    }
+
+    fn act_as_type<'a>(
+        &'a self,
+        type_id: TypeId,
+        self_handle: &'a View<Self>,
+        _: &'a AppContext,
+    ) -> Option<AnyView> {
+        if type_id == TypeId::of::<Self>() {
+            Some(self_handle.to_any())
+        } else if type_id == TypeId::of::<Editor>() {
+            Some(self.editor.to_any())
+        } else {
+            None
+        }
+    }
+
+    fn as_searchable(&self, _: &View<Self>) -> Option<Box<dyn SearchableItemHandle>> {
+        Some(Box::new(self.editor.clone()))
+    }
+}
+
+impl ProjectItem for NotebookEditor {
+    type Item = NotebookItem;
+
+    fn for_project_item(
+        project: Model<Project>,
+        item: Model<Self::Item>,
+        cx: &mut ViewContext<Self>,
+    ) -> Self {
+        Self::new(project, item, cx)
+    }
+}
@@ -1,7 +1,9 @@
 use std::sync::Arc;
 
+use crate::nbformat;
 use crate::stdio::TerminalOutput;
 use anyhow::Result;
+use collections::HashMap;
 use gpui::{img, AnyElement, FontWeight, ImageData, Render, TextRun, View};
 use runtimelib::datatable::TableSchema;
 use runtimelib::media::datatable::TabularDataResource;
@@ -11,6 +13,16 @@ use settings::Settings;
 use theme::ThemeSettings;
 use ui::{div, prelude::*, v_flex, IntoElement, Styled, ViewContext};
 
+mod html;
+mod json;
+mod latex;
+mod markdown;
+
+use html::HtmlView;
+use json::JsonView;
+use latex::latex_to_text;
+pub(crate) use markdown::MarkdownView;
+
 // Given these outputs are destined for the editor with the block decorations API, all of them must report
 // how many lines they will take up in the editor.
 pub trait LineHeight: Sized {
@@ -20,10 +32,14 @@ pub trait LineHeight: Sized {
 // When deciding what to render from a collection of mediatypes, we need to rank them in order of importance
 fn rank_mime_type(mimetype: &MimeType) -> usize {
     match mimetype {
-        MimeType::DataTable(_) => 6,
-        MimeType::Png(_) => 4,
-        MimeType::Jpeg(_) => 3,
-        MimeType::Markdown(_) => 2,
+        MimeType::DataTable(_) => 9,
+        MimeType::Png(_) => 8,
+        MimeType::Jpeg(_) => 7,
+        MimeType::Svg(_) => 6,
+        MimeType::Markdown(_) => 5,
+        MimeType::Html(_) => 4,
+        MimeType::Latex(_) => 3,
+        MimeType::Json(_) => 2,
         MimeType::Plain(_) => 1,
         // All other media types are not supported in Zed at this time
         _ => 0,
@@ -80,6 +96,19 @@ impl ImageView {
             image: Arc::new(gpui_image_data),
         });
     }
+
+    fn from_svg(svg: &str, cx: &WindowContext) -> Result<Self> {
+        // Render at the display's resolution, but size the view in logical pixels
+        let scale_factor = cx.scale_factor();
+        let image = ImageData::from_svg(svg.as_bytes(), scale_factor)?;
+        let size = image.size();
+
+        Ok(ImageView {
+            height: (u32::from(size.height) as f32 / scale_factor) as u32,
+            width: (u32::from(size.width) as f32 / scale_factor) as u32,
+            image: Arc::new(image),
+        })
+    }
 }
 
 impl LineHeight for ImageView {
@@ -312,20 +341,25 @@ pub enum OutputType {
     ErrorOutput(ErrorView),
     Message(String),
     Table(TableView),
+    Markdown(MarkdownView),
+    Html(HtmlView),
+    Json(JsonView),
     ClearOutputWaitMarker,
 }
 
 impl OutputType {
-    fn render(&self, cx: &ViewContext<ExecutionView>) -> Option<AnyElement> {
+    fn render(&self, ix: usize, cx: &ViewContext<ExecutionView>) -> Option<AnyElement> {
         let el = match self {
             // Note: in typical frontends we would show the execute_result.execution_count
             // Here we can just handle either
             Self::Plain(stdio) => Some(stdio.render(cx)),
-            // Self::Markdown(markdown) => Some(markdown.render(theme)),
             Self::Stream(stdio) => Some(stdio.render(cx)),
             Self::Image(image) => Some(image.render(cx)),
             Self::Message(message) => Some(div().child(message.clone()).into_any_element()),
             Self::Table(table) => Some(table.render(cx)),
+            Self::Markdown(markdown) => Some(markdown.render()),
+            Self::Html(html) => Some(html.render(cx)),
+            Self::Json(json) => Some(json.render(ix, cx)),
             Self::ErrorOutput(error_view) => error_view.render(cx),
             Self::ClearOutputWaitMarker => None,
         };
@@ -336,11 +370,25 @@ impl OutputType {
     pub fn new(data: &MimeBundle, cx: &mut WindowContext) -> Self {
         match data.richest(rank_mime_type) {
             Some(MimeType::Plain(text)) => OutputType::Plain(TerminalOutput::from(text)),
-            Some(MimeType::Markdown(text)) => OutputType::Plain(TerminalOutput::from(text)),
+            Some(MimeType::Markdown(text)) => {
+                OutputType::Markdown(MarkdownView::from(text.clone(), cx))
+            }
+            Some(MimeType::Html(html)) => match HtmlView::from(html, cx) {
+                Ok(view) => OutputType::Html(view),
+                Err(error) => OutputType::Message(format!("Failed to render HTML: {}", error)),
+            },
+            Some(MimeType::Latex(latex)) => {
+                OutputType::Plain(TerminalOutput::from(&latex_to_text(latex)))
+            }
+            Some(MimeType::Json(json)) => OutputType::Json(JsonView::new(json.clone())),
             Some(MimeType::Png(data)) | Some(MimeType::Jpeg(data)) => match ImageView::from(data) {
                 Ok(view) => OutputType::Image(view),
                 Err(error) => OutputType::Message(format!("Failed to load image: {}", error)),
             },
+            Some(MimeType::Svg(svg)) => match ImageView::from_svg(svg, cx) {
+                Ok(view) => OutputType::Image(view),
+                Err(error) => OutputType::Message(format!("Failed to load image: {}", error)),
+            },
             Some(MimeType::DataTable(data)) => OutputType::Table(TableView::new(data.clone(), cx)),
             // Any other media types are not supported
             _ => OutputType::Message("Unsupported media type".to_string()),
@@ -357,6 +405,9 @@ impl LineHeight for OutputType {
             Self::Image(image) => image.num_lines(cx),
             Self::Message(message) => message.lines().count() as u8,
             Self::Table(table) => table.num_lines(cx),
+            Self::Markdown(markdown) => markdown.num_lines(cx),
+            Self::Html(html) => html.num_lines(cx),
+            Self::Json(json) => json.num_lines(cx),
             Self::ErrorOutput(error_view) => error_view.num_lines(cx),
             Self::ClearOutputWaitMarker => 0,
         }
@@ -379,6 +430,8 @@ pub enum ExecutionStatus {
 pub struct ExecutionView {
     pub outputs: Vec<OutputType>,
     pub status: ExecutionStatus,
+    /// The indices of the outputs shown with a `display_id`, which are replaced by `update_display_data`.
+    display_ids: HashMap<String, Vec<usize>>,
 }
 
 impl ExecutionView {
@@ -386,14 +439,73 @@ impl ExecutionView {
         Self {
             outputs: Default::default(),
             status,
+            display_ids: Default::default(),
+        }
+    }
+
+    /// Creates a view of the outputs that were saved in a notebook
+    pub fn from_notebook_outputs(outputs: &[nbformat::Output], cx: &mut ViewContext<Self>) -> Self {
+        let outputs = outputs
+            .iter()
+            .map(|output| match output {
+                nbformat::Output::Stream { text, .. } => {
+                    OutputType::Stream(TerminalOutput::from(text))
+                }
+                nbformat::Output::DisplayData { data, .. }
+                | nbformat::Output::ExecuteResult { data, .. } => {
+                    match nbformat::Output::mime_bundle(data) {
+                        Some(data) => OutputType::new(&data, cx),
+                        None => OutputType::Message("Unsupported media type".to_string()),
+                    }
+                }
+                nbformat::Output::Error {
+                    ename,
+                    evalue,
+                    traceback,
+                } => OutputType::ErrorOutput(ErrorView {
+                    ename: ename.clone(),
+                    evalue: evalue.clone(),
+                    traceback: TerminalOutput::from(&traceback.join("\n")),
+                }),
+            })
+            .collect();
+
+        Self {
+            outputs,
+            status: ExecutionStatus::Finished,
+            display_ids: Default::default(),
         }
     }
 
     /// Accept a Jupyter message belonging to this execution
     pub fn push_message(&mut self, message: &JupyterMessageContent, cx: &mut ViewContext<Self>) {
+        let mut display_id = None;
         let output: OutputType = match message {
             JupyterMessageContent::ExecuteResult(result) => OutputType::new(&result.data, cx),
-            JupyterMessageContent::DisplayData(result) => OutputType::new(&result.data, cx),
+            JupyterMessageContent::DisplayData(result) => {
+                display_id = result
+                    .transient
+                    .as_ref()
+                    .and_then(|transient| transient.display_id.clone());
+                OutputType::new(&result.data, cx)
+            }
+            JupyterMessageContent::UpdateDisplayData(update) => {
+                let Some(indices) = update
+                    .transient
+                    .display_id
+                    .as_ref()
+                    .and_then(|display_id| self.display_ids.get(display_id))
+                else {
+                    return;
+                };
+                for ix in indices.clone() {
+                    if let Some(output) = self.outputs.get_mut(ix) {
+                        *output = OutputType::new(&update.data, cx);
+                    }
+                }
+                cx.notify();
+                return;
+            }
             JupyterMessageContent::StreamContent(result) => {
                 // Previous stream data will combine together, handling colors, carriage returns, etc
                 if let Some(new_terminal) = self.apply_terminal_text(&result.text) {
@@ -443,6 +555,7 @@ impl ExecutionView {
             JupyterMessageContent::ClearOutput(options) => {
                 if !options.wait {
                     self.outputs.clear();
+                    self.display_ids.clear();
                     cx.notify();
                     return;
                 }
@@ -468,6 +581,14 @@ impl ExecutionView {
         // Check for a clear output marker as the previous output, so we can clear it out
         if let Some(OutputType::ClearOutputWaitMarker) = self.outputs.last() {
             self.outputs.clear();
+            self.display_ids.clear();
+        }
+
+        if let Some(display_id) = display_id {
+            self.display_ids
+                .entry(display_id)
+                .or_default()
+                .push(self.outputs.len());
         }
 
         self.outputs.push(output);
@@ -475,6 +596,13 @@ impl ExecutionView {
         cx.notify();
     }
 
+    fn toggle_json(&mut self, ix: usize, path: &str, cx: &mut ViewContext<Self>) {
+        if let Some(OutputType::Json(json)) = self.outputs.get_mut(ix) {
+            json.toggle(path);
+            cx.notify();
+        }
+    }
+
     fn apply_terminal_text(&mut self, text: &str) -> Option<OutputType> {
         if let Some(last_output) = self.outputs.last_mut() {
             match last_output {
@@ -520,7 +648,12 @@ impl Render for ExecutionView {
 
         div()
             .w_full()
-            .children(self.outputs.iter().filter_map(|output| output.render(cx)))
+            .children(
+                self.outputs
+                    .iter()
+                    .enumerate()
+                    .filter_map(|(ix, output)| output.render(ix, cx)),
+            )
             .into_any_element()
     }
 }
@@ -0,0 +1,302 @@
+use std::{cell::RefCell, rc::Rc};
+
+use anyhow::Result;
+use gpui::AnyElement;
+use html_to_markdown::{
+    convert_html_to_markdown, markdown, HandleTag, HandlerOutcome, HtmlElement, MarkdownWriter,
+    StartTagOutcome, TagHandler,
+};
+use serde_json::{json, Map, Value};
+use ui::prelude::*;
+use util::ResultExt as _;
+
+use super::{markdown::MarkdownView, ExecutionView, LineHeight, TableView};
+
+/// HtmlView renders `text/html` output, such as the tables of pandas DataFrames. Tables are
+/// rendered like tabular data resources, and the rest of the document is converted to Markdown.
+pub struct HtmlView {
+    text: Option<MarkdownView>,
+    tables: Vec<TableView>,
+}
+
+impl HtmlView {
+    pub fn from(html: &str, cx: &mut WindowContext) -> Result<Self> {
+        let (text, tables) = extract(html)?;
+        let text = (!text.is_empty()).then(|| MarkdownView::from(text, cx));
+        let tables = tables
+            .iter()
+            .filter_map(|table| serde_json::from_value(table.to_resource()).log_err())
+            .map(|table| TableView::new(table, cx))
+            .collect();
+        Ok(Self { text, tables })
+    }
+
+    pub fn render(&self, cx: &ViewContext<ExecutionView>) -> AnyElement {
+        v_flex()
+            .w_full()
+            .gap_2()
+            .children(self.text.as_ref().map(|text| text.render()))
+            .children(self.tables.iter().map(|table| table.render(cx)))
+            .into_any_element()
+    }
+}
+
+impl LineHeight for HtmlView {
+    fn num_lines(&self, cx: &mut WindowContext) -> u8 {
+        self.text
+            .iter()
+            .map(|text| text.num_lines(cx))
+            .chain(self.tables.iter().map(|table| table.num_lines(cx)))
+            .fold(0, |acc, lines| acc.saturating_add(lines))
+    }
+}
+
+/// Converts the HTML to Markdown, except for its tables which are returned separately.
+fn extract(html: &str) -> Result<(String, Vec<HtmlTable>)> {
+    let tables = Rc::new(RefCell::new(TableExtractor::default()));
+    let mut handlers: Vec<TagHandler> = vec![
+        tables.clone(),
+        Rc::new(RefCell::new(markdown::WebpageChromeRemover)),
+        Rc::new(RefCell::new(markdown::ParagraphHandler)),
+        Rc::new(RefCell::new(markdown::HeadingHandler)),
+        Rc::new(RefCell::new(markdown::ListHandler)),
+        Rc::new(RefCell::new(markdown::StyledTextHandler)),
+        Rc::new(RefCell::new(markdown::CodeHandler)),
+    ];
+    let text = convert_html_to_markdown(html.as_bytes(), &mut handlers)?;
+    let tables = std::mem::take(&mut tables.borrow_mut().tables);
+    Ok((text, tables))
+}
+
+#[derive(Debug, Default, PartialEq)]
+struct HtmlTable {
+    rows: Vec<HtmlTableRow>,
+}
+
+#[derive(Debug, Default, PartialEq)]
+struct HtmlTableRow {
+    cells: Vec<String>,
+    is_header: bool,
+}
+
+impl HtmlTable {
+    /// Converts the table to a tabular data resource, using the last header row for the column
+    /// names. Columns whose values are all numbers are typed as numbers so they align right.
+    fn to_resource(&self) -> Value {
+        let header = self.rows.iter().rev().find(|row| row.is_header);
+        let body = self
+            .rows
+            .iter()
+            .filter(|row| !row.is_header)
+            .collect::<Vec<_>>();
+        let column_count = self
+            .rows
+            .iter()
+            .map(|row| row.cells.len())
+            .max()
+            .unwrap_or(0);
+
+        let mut names = Vec::<String>::with_capacity(column_count);
+        for ix in 0..column_count {
+            let name = header
+                .and_then(|header| header.cells.get(ix))
+                .cloned()
+                .unwrap_or_else(|| ix.to_string());
+            // Rows are keyed by column name, so the names have to be unique
+            let mut unique_name = name.clone();
+            let mut suffix = 2;
+            while names.contains(&unique_name) {
+                unique_name = format!("{name} ({suffix})");
+                suffix += 1;
+            }
+            names.push(unique_name);
+        }
+
+        let fields = names
+            .iter()
+            .enumerate()
+            .map(|(ix, name)| {
+                let is_number = body.iter().all(|row| {
+                    row.cells
+                        .get(ix)
+                        .map_or(true, |cell| cell.is_empty() || cell.parse::<f64>().is_ok())
+                });
+                json!({
+                    "name": name,
+                    "type": if is_number { "number" } else { "string" },
+                })
+            })
+            .collect::<Vec<_>>();
+
+        let data = body
+            .iter()
+            .map(|row| {
+                names
+                    .iter()
+                    .zip(row.cells.iter())
+                    .map(|(name, cell)| (name.clone(), Value::String(cell.clone())))
+                    .collect::<Map<_, _>>()
+            })
+            .collect::<Vec<_>>();
+
+        json!({
+            "schema": { "fields": fields },
+            "data": data,
+        })
+    }
+}
+
+/// Collects the cells of tables, instead of writing them as Markdown.
+#[derive(Default)]
+struct TableExtractor {
+    tables: Vec<HtmlTable>,
+    current_table: Option<HtmlTable>,
+    in_cell: bool,
+}
+
+impl HandleTag for TableExtractor {
+    fn should_handle(&self, tag: &str) -> bool {
+        matches!(tag, "table" | "tr" | "th" | "td")
+    }
+
+    fn handle_tag_start(
+        &mut self,
+        tag: &HtmlElement,
+        writer: &mut MarkdownWriter,
+    ) -> StartTagOutcome {
+        match tag.tag() {
+            "table" => {
+                self.current_table.get_or_insert_with(HtmlTable::default);
+            }
+            "tr" => {
+                if let Some(table) = self.current_table.as_mut() {
+                    table.rows.push(HtmlTableRow {
+                        cells: Vec::new(),
+                        is_header: writer.is_inside("thead"),
+                    });
+                }
+            }
+            "th" | "td" => {
+                if let Some(row) = self
+                    .current_table
+                    .as_mut()
+                    .and_then(|table| table.rows.last_mut())
+                {
+                    // Rows outside of a `thead` are headers when they only contain `th` cells
+                    if tag.tag() == "th" && row.cells.is_empty() {
+                        row.is_header = true;
+                    } else if tag.tag() == "td" && !writer.is_inside("thead") {
+                        row.is_header = false;
+                    }
+                    row.cells.push(String::new());
+                    self.in_cell = true;
+                }
+            }
+            _ => {}
+        }
+        StartTagOutcome::Continue
+    }
+
+    fn handle_tag_end(&mut self, tag: &HtmlElement, writer: &mut MarkdownWriter) {
+        match tag.tag() {
+            "th" | "td" => self.in_cell = false,
+            // Nested tables are flattened into the outermost one
+            "table" if !writer.is_inside("table") => {
+                if let Some(table) = self.current_table.take() {
+                    if !table.rows.is_empty() {
+                        self.tables.push(table);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn handle_text(&mut self, text: &str, _writer: &mut MarkdownWriter) -> HandlerOutcome {
+        if self.current_table.is_none() {
+            return HandlerOutcome::NoOp;
+        }
+        if self.in_cell {
+            self.push_text(text);
+        }
+        HandlerOutcome::Handled
+    }
+}
+
+impl TableExtractor {
+    fn push_text(&mut self, text: &str) {
+        let Some(cell) = self
+            .current_table
+            .as_mut()
+            .and_then(|table| table.rows.last_mut())
+            .and_then(|row| row.cells.last_mut())
+        else {
+            return;
+        };
+        for word in text.split_whitespace() {
+            if !cell.is_empty() {
+                cell.push(' ');
+            }
+            cell.push_str(word);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_html_tables() {
+        // The output of a pandas DataFrame
+        let html = r#"<div>
+<style scoped>
+    .dataframe tbody tr th:only-of-type {
+        vertical-align: middle;
+    }
+</style>
+<p>A <strong>small</strong> frame</p>
+<table border="1" class="dataframe">
+  <thead>
+    <tr style="text-align: right;">
+      <th></th>
+      <th>name</th>
+      <th>score</th>
+    </tr>
+  </thead>
+  <tbody>
+    <tr>
+      <th>0</th>
+      <td>Ada Lovelace</td>
+      <td>1.5</td>
+    </tr>
+    <tr>
+      <th>1</th>
+      <td>Grace Hopper</td>
+      <td>2</td>
+    </tr>
+  </tbody>
+</table>
+</div>"#;
+
+        let (text, tables) = extract(html).unwrap();
+        assert_eq!(text, "A **small** frame");
+        assert_eq!(tables.len(), 1);
+        assert_eq!(
+            tables[0].to_resource(),
+            json!({
+                "schema": {
+                    "fields": [
+                        { "name": "", "type": "number" },
+                        { "name": "name", "type": "string" },
+                        { "name": "score", "type": "number" },
+                    ]
+                },
+                "data": [
+                    { "": "0", "name": "Ada Lovelace", "score": "1.5" },
+                    { "": "1", "name": "Grace Hopper", "score": "2" },
+                ]
+            })
+        );
+    }
+}
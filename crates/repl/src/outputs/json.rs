@@ -0,0 +1,188 @@
+use collections::HashSet;
+use gpui::AnyElement;
+use serde_json::Value;
+use ui::prelude::*;
+
+use super::{ExecutionView, LineHeight};
+
+/// JsonView renders `application/json` data as a tree, whose objects and arrays can be expanded
+/// and collapsed by clicking on them.
+pub struct JsonView {
+    value: Value,
+    /// The JSON pointers of the expanded objects and arrays. Only the root starts out expanded.
+    expanded: HashSet<String>,
+}
+
+struct JsonRow {
+    depth: usize,
+    path: String,
+    key: Option<String>,
+    text: String,
+    /// Whether an object or array is expanded, or `None` for other values.
+    expanded: Option<bool>,
+}
+
+impl JsonView {
+    pub fn new(value: Value) -> Self {
+        Self {
+            value,
+            expanded: HashSet::from_iter([String::new()]),
+        }
+    }
+
+    pub fn toggle(&mut self, path: &str) {
+        if !self.expanded.remove(path) {
+            self.expanded.insert(path.to_string());
+        }
+    }
+
+    fn rows(&self) -> Vec<JsonRow> {
+        let mut rows = Vec::new();
+        self.push_rows(&self.value, 0, String::new(), None, &mut rows);
+        rows
+    }
+
+    fn push_rows(
+        &self,
+        value: &Value,
+        depth: usize,
+        path: String,
+        key: Option<String>,
+        rows: &mut Vec<JsonRow>,
+    ) {
+        let children: Vec<(String, &Value)> = match value {
+            Value::Object(map) => map
+                .iter()
+                .map(|(key, value)| (key.clone(), value))
+                .collect(),
+            Value::Array(items) => items
+                .iter()
+                .enumerate()
+                .map(|(ix, value)| (ix.to_string(), value))
+                .collect(),
+            _ => {
+                rows.push(JsonRow {
+                    depth,
+                    path,
+                    key,
+                    text: value.to_string(),
+                    expanded: None,
+                });
+                return;
+            }
+        };
+
+        let text = match value {
+            Value::Array(_) if children.len() == 1 => "[1 item]".to_string(),
+            Value::Array(_) => format!("[{} items]", children.len()),
+            _ if children.len() == 1 => "{1 key}".to_string(),
+            _ => format!("{{{} keys}}", children.len()),
+        };
+        let expanded = self.expanded.contains(&path);
+        rows.push(JsonRow {
+            depth,
+            path: path.clone(),
+            key,
+            text,
+            expanded: Some(expanded),
+        });
+
+        if expanded {
+            for (key, value) in children {
+                // Escape the key as a JSON pointer reference token (RFC 6901)
+                let child_path = format!("{path}/{}", key.replace('~', "~0").replace('/', "~1"));
+                self.push_rows(value, depth + 1, child_path, Some(key), rows);
+            }
+        }
+    }
+
+    pub fn render(&self, output_ix: usize, cx: &ViewContext<ExecutionView>) -> AnyElement {
+        let rows = self.rows().into_iter().map(|row| {
+            let key = row.key.map(|key| {
+                Label::new(format!("{key}:"))
+                    .color(Color::Muted)
+                    .into_any_element()
+            });
+            let content = h_flex()
+                .gap_1()
+                .pl(rems(row.depth as f32))
+                .children(row.expanded.map(|expanded| {
+                    Icon::new(if expanded {
+                        IconName::ChevronDown
+                    } else {
+                        IconName::ChevronRight
+                    })
+                    .size(IconSize::XSmall)
+                    .color(Color::Muted)
+                }))
+                .children(key)
+                .child(row.text);
+
+            if row.expanded.is_none() {
+                return content.into_any_element();
+            }
+
+            let path = row.path;
+            content
+                .id(ElementId::Name(format!("json-{output_ix}-{path}").into()))
+                .cursor_pointer()
+                .on_click(cx.listener(move |execution_view, _, cx| {
+                    execution_view.toggle_json(output_ix, &path, cx);
+                }))
+                .into_any_element()
+        });
+
+        v_flex().w_full().children(rows).into_any_element()
+    }
+}
+
+impl LineHeight for JsonView {
+    fn num_lines(&self, _cx: &mut WindowContext) -> u8 {
+        self.rows().len().min(u8::MAX as usize) as u8
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_json_rows() {
+        let mut view = JsonView::new(json!({ "a/b": [1, 2], "c": { "d": null } }));
+        let summary = |view: &JsonView| {
+            view.rows()
+                .into_iter()
+                .map(|row| {
+                    let key = row.key.map(|key| format!("{key}: ")).unwrap_or_default();
+                    let marker = match row.expanded {
+                        Some(true) => " -",
+                        Some(false) => " +",
+                        None => "",
+                    };
+                    format!("{}{key}{}{marker}", "  ".repeat(row.depth), row.text)
+                })
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            summary(&view),
+            ["{2 keys} -", "  a/b: [2 items] +", "  c: {1 key} +"]
+        );
+
+        view.toggle("/a~1b");
+        view.toggle("/c");
+        view.toggle("/c");
+        assert_eq!(
+            summary(&view),
+            [
+                "{2 keys} -",
+                "  a/b: [2 items] -",
+                "    0: 1",
+                "    1: 2",
+                "  c: {1 key} +"
+            ]
+        );
+        assert_eq!(view.rows()[3].path, "/a~1b/1");
+    }
+}
@@ -0,0 +1,289 @@
+use std::{iter::Peekable, str::Chars};
+
+/// Converts `text/latex` output to plain text, approximating common math notation with Unicode
+/// symbols, such as `\frac{\alpha}{2}` as `α/2`. Unknown commands are kept as they are.
+pub fn latex_to_text(latex: &str) -> String {
+    let latex = strip_math_delimiters(latex.trim());
+    let text = convert(&mut latex.chars().peekable(), false);
+    text.lines()
+        .map(|line| line.split_whitespace().collect::<Vec<_>>().join(" "))
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn strip_math_delimiters(latex: &str) -> &str {
+    for (start, end) in [("$$", "$$"), ("\\[", "\\]"), ("\\(", "\\)"), ("$", "$")] {
+        if let Some(inner) = latex
+            .strip_prefix(start)
+            .and_then(|latex| latex.strip_suffix(end))
+        {
+            return inner;
+        }
+    }
+    latex
+}
+
+/// Converts the text up to the end of the input, or up to the closing brace of the current group.
+fn convert(chars: &mut Peekable<Chars>, in_group: bool) -> String {
+    let mut text = String::new();
+    while let Some(char) = chars.next() {
+        match char {
+            '}' if in_group => break,
+            '{' => text.push_str(&convert(chars, true)),
+            '\\' => text.push_str(&command(chars)),
+            '^' => text.push_str(&script(&argument(chars), true)),
+            '_' => text.push_str(&script(&argument(chars), false)),
+            // Alignment points in environments such as `align`
+            '&' => {}
+            '~' => text.push(' '),
+            char => text.push(char),
+        }
+    }
+    text
+}
+
+/// Converts the argument of a command or script, which is either a group or a single token.
+fn argument(chars: &mut Peekable<Chars>) -> String {
+    while chars.next_if(|char| char.is_whitespace()).is_some() {}
+    match chars.next() {
+        Some('{') => convert(chars, true),
+        Some('\\') => command(chars),
+        Some(char) => char.to_string(),
+        None => String::new(),
+    }
+}
+
+fn command(chars: &mut Peekable<Chars>) -> String {
+    let mut name = String::new();
+    while let Some(char) = chars.next_if(|char| char.is_ascii_alphabetic()) {
+        name.push(char);
+    }
+    if name.is_empty() {
+        return match chars.next() {
+            Some('\\') => "\n".into(),
+            Some(',' | ':' | ';' | ' ') => " ".into(),
+            Some('!') | None => String::new(),
+            Some(char) => char.to_string(),
+        };
+    }
+
+    match name.as_str() {
+        "frac" | "dfrac" | "tfrac" => {
+            let numerator = argument(chars);
+            let denominator = argument(chars);
+            format!(
+                "{}/{}",
+                parenthesize(&numerator),
+                parenthesize(&denominator)
+            )
+        }
+        "sqrt" => {
+            // Skip the degree of the root, as in `\sqrt[3]{x}`
+            if chars.next_if_eq(&'[').is_some() {
+                while chars.next().map_or(false, |char| char != ']') {}
+            }
+            format!("√{}", parenthesize(&argument(chars)))
+        }
+        "text" | "textrm" | "textbf" | "textit" | "mathrm" | "mathbf" | "mathit" | "mathsf"
+        | "mathtt" | "mathcal" | "mathbb" | "boldsymbol" | "operatorname" => argument(chars),
+        "begin" | "end" => {
+            argument(chars);
+            String::new()
+        }
+        "left" | "right" | "big" | "Big" | "bigg" | "Bigg" | "displaystyle" => String::new(),
+        "quad" | "qquad" => " ".into(),
+        function @ ("sin" | "cos" | "tan" | "log" | "ln" | "exp" | "lim" | "max" | "min"
+        | "det" | "sup" | "inf") => function.into(),
+        name => symbol(name).map_or_else(|| format!("\\{name}"), Into::into),
+    }
+}
+
+fn parenthesize(text: &str) -> String {
+    if text.chars().count() <= 1 || text.chars().all(|char| char.is_alphanumeric()) {
+        text.into()
+    } else {
+        format!("({text})")
+    }
+}
+
+/// Formats a superscript or subscript, using Unicode characters when they all exist.
+fn script(text: &str, superscript: bool) -> String {
+    const SUPERSCRIPTS: &[(char, char)] = &[
+        ('0', '⁰'),
+        ('1', '¹'),
+        ('2', '²'),
+        ('3', '³'),
+        ('4', '⁴'),
+        ('5', '⁵'),
+        ('6', '⁶'),
+        ('7', '⁷'),
+        ('8', '⁸'),
+        ('9', '⁹'),
+        ('+', '⁺'),
+        ('-', '⁻'),
+        ('=', '⁼'),
+        ('(', '⁽'),
+        (')', '⁾'),
+        ('i', 'ⁱ'),
+        ('n', 'ⁿ'),
+    ];
+    const SUBSCRIPTS: &[(char, char)] = &[
+        ('0', '₀'),
+        ('1', '₁'),
+        ('2', '₂'),
+        ('3', '₃'),
+        ('4', '₄'),
+        ('5', '₅'),
+        ('6', '₆'),
+        ('7', '₇'),
+        ('8', '₈'),
+        ('9', '₉'),
+        ('+', '₊'),
+        ('-', '₋'),
+        ('=', '₌'),
+        ('(', '₍'),
+        (')', '₎'),
+        ('a', 'ₐ'),
+        ('e', 'ₑ'),
+        ('i', 'ᵢ'),
+        ('j', 'ⱼ'),
+        ('k', 'ₖ'),
+        ('m', 'ₘ'),
+        ('n', 'ₙ'),
+        ('o', 'ₒ'),
+        ('t', 'ₜ'),
+        ('x', 'ₓ'),
+    ];
+    let (table, marker) = if superscript {
+        (SUPERSCRIPTS, '^')
+    } else {
+        (SUBSCRIPTS, '_')
+    };
+
+    let converted = text
+        .chars()
+        .map(|char| {
+            table
+                .iter()
+                .find(|(plain, _)| *plain == char)
+                .map(|(_, script)| *script)
+        })
+        .collect::<Option<String>>();
+    match converted {
+        Some(converted) if !converted.is_empty() => converted,
+        _ => format!("{marker}{}", parenthesize(text)),
+    }
+}
+
+fn symbol(name: &str) -> Option<&'static str> {
+    Some(match name {
+        "alpha" => "α",
+        "beta" => "β",
+        "gamma" => "γ",
+        "delta" => "δ",
+        "epsilon" | "varepsilon" => "ε",
+        "zeta" => "ζ",
+        "eta" => "η",
+        "theta" | "vartheta" => "θ",
+        "iota" => "ι",
+        "kappa" => "κ",
+        "lambda" => "λ",
+        "mu" => "μ",
+        "nu" => "ν",
+        "xi" => "ξ",
+        "pi" => "π",
+        "rho" => "ρ",
+        "sigma" => "σ",
+        "tau" => "τ",
+        "upsilon" => "υ",
+        "phi" | "varphi" => "φ",
+        "chi" => "χ",
+        "psi" => "ψ",
+        "omega" => "ω",
+        "Gamma" => "Γ",
+        "Delta" => "Δ",
+        "Theta" => "Θ",
+        "Lambda" => "Λ",
+        "Xi" => "Ξ",
+        "Pi" => "Π",
+        "Sigma" => "Σ",
+        "Phi" => "Φ",
+        "Psi" => "Ψ",
+        "Omega" => "Ω",
+        "infty" => "∞",
+        "pm" => "±",
+        "mp" => "∓",
+        "times" => "×",
+        "cdot" => "·",
+        "div" => "÷",
+        "leq" | "le" => "≤",
+        "geq" | "ge" => "≥",
+        "neq" | "ne" => "≠",
+        "approx" => "≈",
+        "equiv" => "≡",
+        "sim" => "∼",
+        "propto" => "∝",
+        "sum" => "∑",
+        "prod" => "∏",
+        "int" => "∫",
+        "oint" => "∮",
+        "partial" => "∂",
+        "nabla" => "∇",
+        "in" => "∈",
+        "notin" => "∉",
+        "subset" => "⊂",
+        "subseteq" => "⊆",
+        "cup" => "∪",
+        "cap" => "∩",
+        "emptyset" => "∅",
+        "forall" => "∀",
+        "exists" => "∃",
+        "neg" => "¬",
+        "wedge" | "land" => "∧",
+        "vee" | "lor" => "∨",
+        "to" | "rightarrow" => "→",
+        "leftarrow" => "←",
+        "Rightarrow" => "⇒",
+        "Leftarrow" => "⇐",
+        "leftrightarrow" => "↔",
+        "Leftrightarrow" | "iff" => "⇔",
+        "mapsto" => "↦",
+        "ldots" | "dots" => "…",
+        "cdots" => "⋯",
+        "vdots" => "⋮",
+        "ddots" => "⋱",
+        "langle" => "⟨",
+        "rangle" => "⟩",
+        "lfloor" => "⌊",
+        "rfloor" => "⌋",
+        "lceil" => "⌈",
+        "rceil" => "⌉",
+        "circ" => "∘",
+        "degree" => "°",
+        "hbar" => "ħ",
+        "ell" => "ℓ",
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_latex_to_text() {
+        assert_eq!(
+            latex_to_text(r"$$\frac{1}{2} \alpha^2 + x_{i} \leq \sqrt{y + 1}$$"),
+            "1/2 α² + xᵢ ≤ √(y + 1)"
+        );
+        assert_eq!(
+            latex_to_text(r"$\sum_{n=0}^{\infty} \frac{x^n}{n!} = e^{x}$"),
+            "∑ₙ₌₀^∞ xⁿ/(n!) = e^x"
+        );
+        assert_eq!(
+            latex_to_text(r"\begin{align} a &= \mathrm{b}_{max} \\ c &= \unknown d \end{align}"),
+            "a = bₘₐₓ\nc = \\unknown d"
+        );
+    }
+}
@@ -0,0 +1,94 @@
+use gpui::{AnyElement, FontWeight, StyleRefinement, TextStyleRefinement, UnderlineStyle, View};
+use markdown::{Markdown, MarkdownStyle};
+use settings::Settings as _;
+use theme::ThemeSettings;
+use ui::prelude::*;
+
+use super::LineHeight;
+
+/// MarkdownView renders `text/markdown` output, as well as the text of HTML output.
+pub struct MarkdownView {
+    markdown: View<Markdown>,
+    num_lines: u8,
+}
+
+impl MarkdownView {
+    pub fn from(text: String, cx: &mut WindowContext) -> Self {
+        // Headings and code blocks are surrounded by some spacing, so leave a bit of room for them.
+        let num_lines = text
+            .lines()
+            .map(|line| {
+                if line.starts_with('#') || line.starts_with("```") {
+                    2
+                } else {
+                    1
+                }
+            })
+            .sum::<usize>()
+            .min(u8::MAX as usize) as u8;
+
+        let style = markdown_style(cx);
+        let markdown = cx.new_view(|cx| Markdown::new(text, style, None, cx, None));
+
+        Self {
+            markdown,
+            num_lines,
+        }
+    }
+
+    pub fn render(&self) -> AnyElement {
+        div()
+            .w_full()
+            .child(self.markdown.clone())
+            .into_any_element()
+    }
+}
+
+impl LineHeight for MarkdownView {
+    fn num_lines(&self, _cx: &mut WindowContext) -> u8 {
+        self.num_lines
+    }
+}
+
+fn markdown_style(cx: &WindowContext) -> MarkdownStyle {
+    let settings = ThemeSettings::get_global(cx);
+    let colors = cx.theme().colors();
+    let mut base_text_style = cx.text_style();
+    base_text_style.refine(&TextStyleRefinement {
+        font_family: Some(settings.buffer_font.family.clone()),
+        font_size: Some(settings.buffer_font_size.into()),
+        color: Some(colors.editor_foreground),
+        ..Default::default()
+    });
+
+    MarkdownStyle {
+        base_text_style,
+        code_block: StyleRefinement::default().my(rems(0.5)),
+        inline_code: TextStyleRefinement {
+            background_color: Some(colors.editor_background),
+            ..Default::default()
+        },
+        block_quote: TextStyleRefinement {
+            color: Some(Color::Muted.color(cx)),
+            ..Default::default()
+        },
+        link: TextStyleRefinement {
+            color: Some(colors.text_accent),
+            underline: Some(UnderlineStyle {
+                thickness: px(1.),
+                color: Some(colors.text_accent),
+                wavy: false,
+            }),
+            ..Default::default()
+        },
+        rule_color: Color::Muted.color(cx),
+        block_quote_border_color: Color::Muted.color(cx),
+        syntax: cx.theme().syntax().clone(),
+        selection_background_color: cx.theme().players().local().selection,
+        break_style: Default::default(),
+        heading: StyleRefinement::default()
+            .font_weight(FontWeight::BOLD)
+            .mt(rems(0.5))
+            .mb_0(),
+    }
+}
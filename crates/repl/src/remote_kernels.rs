@@ -0,0 +1,555 @@
+//! Kernels running on a Jupyter server. They are listed and started with the server's REST API,
+//! and messages are exchanged with them over the server's websocket channel protocol:
+//! https://jupyter-server.readthedocs.io/en/latest/developers/websocket-protocols.html
+
+use anyhow::{anyhow, Context as _, Result};
+use async_tungstenite::tungstenite::{self, http::Request as WebSocketRequest};
+use collections::HashMap;
+use futures::{
+    channel::mpsc::{self, Receiver},
+    future::{self, BoxFuture},
+    stream::BoxStream,
+    AsyncReadExt as _, FutureExt as _, Sink, SinkExt as _, StreamExt as _, TryFutureExt as _,
+    TryStreamExt as _,
+};
+use http::{AsyncBody, HttpClient, Method, Request, Url};
+use runtimelib::{JupyterKernelspec, JupyterMessage, JupyterMessageContent};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::Value;
+use std::{fmt::Debug, pin::Pin, sync::Arc};
+use util::ResultExt as _;
+
+use crate::{
+    jupyter_settings::JupyterServer,
+    kernels::{is_control_request, KernelLocation, KernelSpecification},
+};
+
+/// A kernel on a Jupyter server that a session is connected to.
+pub struct RemoteKernel {
+    http_client: Arc<dyn HttpClient>,
+    server: JupyterServer,
+    kernel_id: String,
+    attached: bool,
+}
+
+impl Debug for RemoteKernel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RemoteKernel")
+            .field("url", &self.server.url)
+            .field("kernel_id", &self.kernel_id)
+            .field("attached", &self.attached)
+            .finish()
+    }
+}
+
+impl RemoteKernel {
+    /// Whether the kernel was already running before we connected to it.
+    pub fn is_attached(&self) -> bool {
+        self.attached
+    }
+
+    /// Shuts down the kernel on the server, unless it was already running before we connected to it.
+    pub async fn shutdown(&self) -> Result<()> {
+        if self.attached {
+            return Ok(());
+        }
+        let path = format!("api/kernels/{}", self.kernel_id);
+        send_request(
+            self.http_client.as_ref(),
+            &self.server,
+            Method::DELETE,
+            &path,
+            None,
+        )
+        .await?;
+        Ok(())
+    }
+}
+
+pub(crate) struct RemoteConnection {
+    pub kernel: RemoteKernel,
+    pub request_tx: mpsc::Sender<JupyterMessage>,
+    pub messages_rx: Receiver<JupyterMessage>,
+    /// Forwards the messages between the channels and the websocket, until either side is closed.
+    pub io: BoxFuture<'static, Result<()>>,
+}
+
+#[derive(Deserialize)]
+struct KernelSpecs {
+    kernelspecs: HashMap<String, KernelSpec>,
+}
+
+#[derive(Deserialize)]
+struct KernelSpec {
+    name: String,
+    spec: JupyterKernelspec,
+}
+
+#[derive(Deserialize)]
+struct LiveKernel {
+    id: String,
+    name: String,
+}
+
+/// A message as it is sent over the websocket, with the channel it belongs to.
+#[derive(Serialize, Deserialize)]
+struct WireMessage {
+    channel: String,
+    header: Value,
+    #[serde(default)]
+    parent_header: Value,
+    #[serde(default)]
+    metadata: Value,
+    content: Value,
+    #[serde(default)]
+    buffers: Vec<Value>,
+}
+
+/// Lists the kernelspecs and the running kernels of the given servers. Servers that can't be
+/// reached are logged and skipped.
+pub async fn kernel_specifications(
+    servers: Vec<JupyterServer>,
+    http_client: Arc<dyn HttpClient>,
+) -> Vec<KernelSpecification> {
+    let specifications = future::join_all(
+        servers
+            .into_iter()
+            .map(|server| server_kernel_specifications(server, http_client.as_ref())),
+    )
+    .await;
+    specifications.into_iter().flatten().flatten().collect()
+}
+
+async fn server_kernel_specifications(
+    server: JupyterServer,
+    http_client: &dyn HttpClient,
+) -> Option<Vec<KernelSpecification>> {
+    let result = async {
+        let specs: KernelSpecs =
+            request(http_client, &server, Method::GET, "api/kernelspecs", None).await?;
+        let live_kernels: Vec<LiveKernel> =
+            request(http_client, &server, Method::GET, "api/kernels", None).await?;
+        anyhow::Ok((specs, live_kernels))
+    }
+    .await
+    .with_context(|| format!("failed to list the kernels of {}", server.url));
+    let (specs, live_kernels) = result.log_err()?;
+
+    let mut specs = specs.kernelspecs.into_values().collect::<Vec<_>>();
+    specs.sort_by(|a, b| a.name.cmp(&b.name));
+
+    // Running kernels are named by their id, so that they can be chosen in `kernel_selections`.
+    let live_kernels = live_kernels
+        .into_iter()
+        .filter_map(|kernel| {
+            let spec = specs.iter().find(|spec| spec.name == kernel.name)?;
+            Some(KernelSpecification {
+                name: kernel.id.clone(),
+                kernelspec: spec.spec.clone(),
+                location: KernelLocation::Remote {
+                    server: server.clone(),
+                    kernel_id: Some(kernel.id),
+                },
+            })
+        })
+        .collect::<Vec<_>>();
+
+    Some(
+        specs
+            .into_iter()
+            .map(|spec| KernelSpecification {
+                name: spec.name,
+                kernelspec: spec.spec,
+                location: KernelLocation::Remote {
+                    server: server.clone(),
+                    kernel_id: None,
+                },
+            })
+            .chain(live_kernels)
+            .collect(),
+    )
+}
+
+/// Connects to the kernel with the given id, or starts a new kernel from the named kernelspec.
+pub(crate) async fn connect(
+    kernel_name: &str,
+    server: JupyterServer,
+    kernel_id: Option<String>,
+    http_client: Arc<dyn HttpClient>,
+) -> Result<RemoteConnection> {
+    let attached = kernel_id.is_some();
+    let kernel_id = match kernel_id {
+        Some(kernel_id) => kernel_id,
+        None => {
+            let body = serde_json::json!({ "name": kernel_name });
+            let kernel: LiveKernel = request(
+                http_client.as_ref(),
+                &server,
+                Method::POST,
+                "api/kernels",
+                Some(body),
+            )
+            .await
+            .with_context(|| format!("failed to start a {kernel_name} kernel on {}", server.url))?;
+            kernel.id
+        }
+    };
+
+    let kernel = RemoteKernel {
+        http_client,
+        server,
+        kernel_id,
+        attached,
+    };
+
+    let (mut websocket_tx, mut websocket_rx) = match open_channels(&kernel).await {
+        Ok(websocket) => websocket,
+        Err(error) => {
+            kernel.shutdown().await.log_err();
+            return Err(error);
+        }
+    };
+
+    let (request_tx, mut request_rx) = mpsc::channel::<JupyterMessage>(100);
+    let (mut messages_tx, messages_rx) = mpsc::channel::<JupyterMessage>(100);
+
+    let send = async move {
+        while let Some(message) = request_rx.next().await {
+            let message = serde_json::to_string(&encode_message(&message)?)?;
+            websocket_tx
+                .send(tungstenite::Message::Text(message))
+                .await?;
+        }
+        websocket_tx.close().await
+    };
+
+    let receive = async move {
+        while let Some(message) = websocket_rx.next().await {
+            match message? {
+                tungstenite::Message::Text(text) => {
+                    let Some(message) = decode_message(&text).log_err() else {
+                        continue;
+                    };
+                    messages_tx.send(message).await?;
+                }
+                tungstenite::Message::Close(_) => break,
+                _ => {}
+            }
+        }
+        anyhow::Ok(())
+    };
+
+    Ok(RemoteConnection {
+        kernel,
+        request_tx,
+        messages_rx,
+        io: future::try_join(send, receive).map_ok(|_| ()).boxed(),
+    })
+}
+
+type WebSocketSink = Pin<Box<dyn Sink<tungstenite::Message, Error = anyhow::Error> + Send>>;
+type WebSocketStream = BoxStream<'static, Result<tungstenite::Message>>;
+
+async fn open_channels(kernel: &RemoteKernel) -> Result<(WebSocketSink, WebSocketStream)> {
+    let mut url = api_url(
+        &kernel.server,
+        &format!("api/kernels/{}/channels", kernel.kernel_id),
+    )?;
+    url.query_pairs_mut()
+        .append_pair("session_id", &uuid::Uuid::new_v4().to_string());
+
+    let mut request = WebSocketRequest::builder();
+    if let Some(token) = &kernel.server.token {
+        request = request.header("Authorization", format!("token {token}"));
+    }
+
+    let host = url
+        .host_str()
+        .zip(url.port_or_known_default())
+        .ok_or_else(|| anyhow!("missing host in jupyter server url {url}"))?;
+    let stream = smol::net::TcpStream::connect(host).await?;
+
+    fn split<S>(websocket: S) -> (WebSocketSink, WebSocketStream)
+    where
+        S: futures::Stream<Item = Result<tungstenite::Message, tungstenite::Error>>
+            + Sink<tungstenite::Message, Error = tungstenite::Error>
+            + Send
+            + 'static,
+    {
+        let (sink, stream) = websocket.split();
+        (
+            Box::pin(sink.sink_map_err(|error| anyhow!(error))),
+            stream.map_err(|error| anyhow!(error)).boxed(),
+        )
+    }
+
+    match url.scheme() {
+        "https" => {
+            url.set_scheme("wss").unwrap();
+            let request = request.uri(url.as_str()).body(())?;
+            let (websocket, _) =
+                async_tungstenite::async_std::client_async_tls(request, stream).await?;
+            Ok(split(websocket))
+        }
+        "http" => {
+            url.set_scheme("ws").unwrap();
+            let request = request.uri(url.as_str()).body(())?;
+            let (websocket, _) = async_tungstenite::client_async(request, stream).await?;
+            Ok(split(websocket))
+        }
+        _ => Err(anyhow!("invalid jupyter server url: {url}")),
+    }
+}
+
+fn encode_message(message: &JupyterMessage) -> Result<WireMessage> {
+    let channel = if is_control_request(&message.content) {
+        "control"
+    } else {
+        "shell"
+    };
+    Ok(WireMessage {
+        channel: channel.into(),
+        header: serde_json::to_value(&message.header)?,
+        parent_header: match &message.parent_header {
+            Some(parent_header) => serde_json::to_value(parent_header)?,
+            None => Value::Object(Default::default()),
+        },
+        metadata: message.metadata.clone(),
+        content: serde_json::to_value(&message.content)?,
+        buffers: Vec::new(),
+    })
+}
+
+fn decode_message(text: &str) -> Result<JupyterMessage> {
+    let message: WireMessage = serde_json::from_str(text)?;
+    let msg_type = message
+        .header
+        .get("msg_type")
+        .and_then(Value::as_str)
+        .context("message without a type")?;
+    let content = JupyterMessageContent::from_type_and_content(msg_type, message.content)?;
+
+    let mut decoded = JupyterMessage::new(content, None);
+    decoded.header = serde_json::from_value(message.header)?;
+    // Messages without a parent have an empty parent header
+    decoded.parent_header = match message.parent_header {
+        Value::Object(parent_header) if parent_header.is_empty() => None,
+        Value::Null => None,
+        parent_header => Some(serde_json::from_value(parent_header)?),
+    };
+    decoded.metadata = message.metadata;
+    Ok(decoded)
+}
+
+fn api_url(server: &JupyterServer, path: &str) -> Result<Url> {
+    let mut url = Url::parse(&server.url)
+        .with_context(|| format!("invalid jupyter server url: {}", server.url))?;
+    if !url.path().ends_with('/') {
+        url.set_path(&format!("{}/", url.path()));
+    }
+    Ok(url.join(path)?)
+}
+
+async fn send_request(
+    http_client: &dyn HttpClient,
+    server: &JupyterServer,
+    method: Method,
+    path: &str,
+    body: Option<Value>,
+) -> Result<Vec<u8>> {
+    let url = api_url(server, path)?;
+    let mut request = Request::builder()
+        .method(method)
+        .uri(url.as_str())
+        .header("Content-Type", "application/json");
+    if let Some(token) = &server.token {
+        request = request.header("Authorization", format!("token {token}"));
+    }
+    let body = match body {
+        Some(body) => AsyncBody::from(serde_json::to_string(&body)?),
+        None => AsyncBody::default(),
+    };
+
+    let mut response = http_client.send(request.body(body)?).await?;
+    let mut body = Vec::new();
+    response.body_mut().read_to_end(&mut body).await?;
+    if !response.status().is_success() {
+        anyhow::bail!(
+            "request to {url} failed with status {}: {}",
+            response.status(),
+            String::from_utf8_lossy(&body)
+        );
+    }
+    Ok(body)
+}
+
+async fn request<T: DeserializeOwned>(
+    http_client: &dyn HttpClient,
+    server: &JupyterServer,
+    method: Method,
+    path: &str,
+    body: Option<Value>,
+) -> Result<T> {
+    let body = send_request(http_client, server, method, path, body).await?;
+    Ok(serde_json::from_slice(&body)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_tungstenite::tungstenite::handshake::server::{Request, Response};
+    use http::{FakeHttpClient, Response as HttpResponse};
+    use runtimelib::ExecuteRequest;
+    use serde_json::json;
+
+    #[gpui::test]
+    async fn test_remote_kernel_specifications() {
+        let http_client = FakeHttpClient::create(|request| async move {
+            assert_eq!(request.headers()["Authorization"], "token secret");
+            let body = match request.uri().path() {
+                "/jupyter/api/kernelspecs" => json!({
+                    "default": "python3",
+                    "kernelspecs": {
+                        "python3": {
+                            "name": "python3",
+                            "resources": {},
+                            "spec": {
+                                "argv": ["python", "-m", "ipykernel_launcher", "-f", "{connection_file}"],
+                                "display_name": "Python 3 (ipykernel)",
+                                "language": "python",
+                                "env": {},
+                                "metadata": {}
+                            }
+                        }
+                    }
+                }),
+                "/jupyter/api/kernels" => json!([
+                    { "id": "d3b2c1", "name": "python3", "execution_state": "idle" },
+                    { "id": "a1b2c3", "name": "removed", "execution_state": "idle" }
+                ]),
+                path => panic!("unexpected request to {path}"),
+            };
+            Ok(HttpResponse::builder()
+                .status(200)
+                .body(body.to_string().into())
+                .unwrap())
+        });
+
+        let server = JupyterServer {
+            url: "http://localhost:8888/jupyter".into(),
+            token: Some("secret".into()),
+        };
+        let unreachable_server = JupyterServer {
+            url: "not a url".into(),
+            token: None,
+        };
+        let specifications =
+            kernel_specifications(vec![server.clone(), unreachable_server], http_client).await;
+
+        assert_eq!(
+            specifications
+                .iter()
+                .map(|specification| (specification.name.as_str(), &specification.location))
+                .collect::<Vec<_>>(),
+            vec![
+                (
+                    "python3",
+                    &KernelLocation::Remote {
+                        server: server.clone(),
+                        kernel_id: None
+                    }
+                ),
+                (
+                    "d3b2c1",
+                    &KernelLocation::Remote {
+                        server,
+                        kernel_id: Some("d3b2c1".into())
+                    }
+                ),
+            ]
+        );
+        assert_eq!(specifications[1].kernelspec.language, "python");
+    }
+
+    #[test]
+    fn test_remote_kernel_channels() {
+        smol::block_on(async {
+            let listener = smol::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let port = listener.local_addr().unwrap().port();
+
+            // A stand-in for the Jupyter server, which answers the first request with a status message.
+            let _server = smol::spawn(async move {
+                let (stream, _) = listener.accept().await.unwrap();
+                let mut websocket = async_tungstenite::accept_hdr_async(
+                    stream,
+                    |request: &Request, response: Response| {
+                        assert_eq!(request.uri().path(), "/jupyter/api/kernels/abc/channels");
+                        assert_eq!(request.headers()["Authorization"], "token secret");
+                        Ok(response)
+                    },
+                )
+                .await
+                .unwrap();
+
+                let Some(Ok(tungstenite::Message::Text(text))) = websocket.next().await else {
+                    panic!("expected a text message");
+                };
+                let request: Value = serde_json::from_str(&text).unwrap();
+                assert_eq!(request["channel"], "shell");
+                assert_eq!(request["header"]["msg_type"], "execute_request");
+                assert_eq!(request["content"]["code"], "1 + 1");
+
+                let status = json!({
+                    "channel": "iopub",
+                    "header": {
+                        "msg_id": "status-1",
+                        "msg_type": "status",
+                        "username": "jovyan",
+                        "session": "kernel-session",
+                        "date": "2024-06-01T12:00:00Z",
+                        "version": "5.3"
+                    },
+                    "parent_header": request["header"],
+                    "metadata": {},
+                    "content": { "execution_state": "busy" },
+                    "buffers": []
+                });
+                websocket
+                    .send(tungstenite::Message::Text(status.to_string()))
+                    .await
+                    .unwrap();
+                websocket
+            });
+
+            let server = JupyterServer {
+                url: format!("http://127.0.0.1:{port}/jupyter"),
+                token: Some("secret".into()),
+            };
+            let connection = connect(
+                "python3",
+                server,
+                Some("abc".into()),
+                FakeHttpClient::with_404_response(),
+            )
+            .await
+            .unwrap();
+            assert!(connection.kernel.is_attached());
+
+            let mut request_tx = connection.request_tx;
+            let mut messages_rx = connection.messages_rx;
+            let _io = smol::spawn(connection.io);
+
+            let request: JupyterMessage = ExecuteRequest {
+                code: "1 + 1".into(),
+                ..ExecuteRequest::default()
+            }
+            .into();
+            request_tx.send(request.clone()).await.unwrap();
+
+            let reply = messages_rx.next().await.unwrap();
+            assert!(matches!(reply.content, JupyterMessageContent::Status(_)));
+            assert_eq!(reply.header.msg_id, "status-1");
+            assert_eq!(reply.parent_header.unwrap().msg_id, request.header.msg_id);
+        });
+    }
+}
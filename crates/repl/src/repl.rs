@@ -3,15 +3,21 @@ use gpui::{AppContext, PlatformDispatcher};
 use settings::Settings as _;
 use std::{sync::Arc, time::Duration};
 
+mod completions;
 mod jupyter_settings;
 mod kernels;
+mod nbformat;
+mod notebook;
 mod outputs;
+mod remote_kernels;
 mod runtime_panel;
 mod session;
 mod stdio;
+mod variable_explorer;
 
 pub use jupyter_settings::JupyterSettings;
 pub use kernels::{Kernel, KernelSpecification};
+pub use notebook::NotebookEditor;
 pub use runtime_panel::Run;
 pub use runtime_panel::{RuntimePanel, SessionSupport};
 pub use runtimelib::ExecutionState;
@@ -44,5 +50,6 @@ fn zed_dispatcher(cx: &mut AppContext) -> impl Dispatcher {
 pub fn init(cx: &mut AppContext) {
     set_dispatcher(zed_dispatcher(cx));
     JupyterSettings::register(cx);
-    runtime_panel::init(cx)
+    runtime_panel::init(cx);
+    notebook::init(cx);
 }
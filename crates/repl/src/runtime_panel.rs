@@ -1,6 +1,6 @@
 use crate::{
-    jupyter_settings::{JupyterDockPosition, JupyterSettings},
-    kernels::{kernel_specifications, KernelSpecification},
+    jupyter_settings::{JupyterDockPosition, JupyterServer, JupyterSettings},
+    kernels::{available_kernel_specifications, KernelSpecification},
     session::{Session, SessionEvent},
 };
 use anyhow::{Context as _, Result};
@@ -9,11 +9,11 @@ use editor::{Anchor, Editor, RangeToAnchorExt};
 use futures::StreamExt as _;
 use gpui::{
     actions, prelude::*, AppContext, AsyncWindowContext, EntityId, EventEmitter, FocusHandle,
-    FocusOutEvent, FocusableView, Subscription, Task, View, WeakView,
+    FocusOutEvent, FocusableView, Model, Subscription, Task, View, WeakView,
 };
 use language::{Language, Point};
 use multi_buffer::MultiBufferRow;
-use project::Fs;
+use project::{Fs, Project};
 use settings::{Settings as _, SettingsStore};
 use std::{ops::Range, sync::Arc};
 use ui::{prelude::*, ButtonLike, ElevationIndex, KeyBinding};
@@ -39,7 +39,9 @@ pub fn init(cx: &mut AppContext) {
 
 pub struct RuntimePanel {
     fs: Arc<dyn Fs>,
+    project: Model<Project>,
     enabled: bool,
+    servers: Vec<JupyterServer>,
     focus_handle: FocusHandle,
     width: Option<Pixels>,
     sessions: HashMap<EntityId, View<Session>>,
@@ -64,6 +66,7 @@ impl RuntimePanel {
                     let focus_handle = cx.focus_handle();
 
                     let fs = workspace.app_state().fs.clone();
+                    let project = workspace.project().clone();
 
                     // Make a channel that we receive editor events on (for repl::Run, repl::ClearOutputs)
                     // This allows us to inject actions on the editor from the repl panel without requiring the editor to
@@ -76,6 +79,11 @@ impl RuntimePanel {
                         cx.on_focus_out(&focus_handle, Self::focus_out),
                         cx.observe_global::<SettingsStore>(move |this, cx| {
                             this.set_enabled(JupyterSettings::enabled(cx), cx);
+                            let servers = &JupyterSettings::get_global(cx).servers;
+                            if this.servers != *servers {
+                                this.servers = servers.clone();
+                                this.refresh_kernelspecs(cx).detach_and_log_err(cx);
+                            }
                         }),
                         cx.observe_new_views(
                             move |editor: &mut Editor, cx: &mut ViewContext<Editor>| {
@@ -131,12 +139,14 @@ impl RuntimePanel {
 
                     let runtime_panel = Self {
                         fs: fs.clone(),
+                        project,
                         width: None,
                         focus_handle,
                         kernel_specifications: Vec::new(),
                         sessions: Default::default(),
                         _subscriptions: subscriptions,
                         enabled: JupyterSettings::enabled(cx),
+                        servers: JupyterSettings::get_global(cx).servers.clone(),
                         _editor_events_task,
                     };
 
@@ -228,7 +238,7 @@ impl RuntimePanel {
     }
 
     pub fn refresh_kernelspecs(&mut self, cx: &mut ViewContext<Self>) -> Task<anyhow::Result<()>> {
-        let kernel_specifications = kernel_specifications(self.fs.clone());
+        let kernel_specifications = available_kernel_specifications(self.fs.clone(), cx);
         cx.spawn(|this, mut cx| async move {
             let kernel_specifications = kernel_specifications.await?;
 
@@ -284,8 +294,15 @@ impl RuntimePanel {
             .with_context(|| format!("No kernel found for language: {}", language.name()))?;
 
         let session = self.sessions.entry(entity_id).or_insert_with(|| {
-            let view =
-                cx.new_view(|cx| Session::new(editor, self.fs.clone(), kernel_specification, cx));
+            let view = cx.new_view(|cx| {
+                Session::new(
+                    editor,
+                    self.project.clone(),
+                    self.fs.clone(),
+                    kernel_specification,
+                    cx,
+                )
+            });
             cx.notify();
 
             let subscription = cx.subscribe(
@@ -1,7 +1,10 @@
 use crate::{
+    completions::KernelCompletionProvider,
     kernels::{Kernel, KernelSpecification, RunningKernel},
     outputs::{ExecutionStatus, ExecutionView, LineHeight as _},
+    variable_explorer::VariableExplorer,
 };
+use anyhow::{anyhow, Context as _, Result};
 use collections::{HashMap, HashSet};
 use editor::{
     display_map::{
@@ -9,20 +12,21 @@ use editor::{
     },
     Anchor, AnchorRangeExt as _, Editor, MultiBuffer, ToPoint,
 };
-use futures::{FutureExt as _, StreamExt as _};
+use futures::{channel::oneshot, FutureExt as _, StreamExt as _};
 use gpui::{
     div, prelude::*, EventEmitter, Model, Render, Subscription, Task, View, ViewContext, WeakView,
 };
 use language::Point;
-use project::Fs;
+use project::{Fs, Project};
 use runtimelib::{
-    ExecuteRequest, InterruptRequest, JupyterMessage, JupyterMessageContent, KernelInfoRequest,
-    ShutdownRequest,
+    CompleteReply, CompleteRequest, ExecuteRequest, ExecutionState, InspectRequest,
+    InterruptRequest, JupyterMessage, JupyterMessageContent, KernelInfoRequest, ShutdownRequest,
 };
 use settings::Settings as _;
 use std::{ops::Range, sync::Arc, time::Duration};
 use theme::{ActiveTheme, ThemeSettings};
 use ui::{h_flex, prelude::*, v_flex, ButtonLike, ButtonStyle, Label};
+use util::ResultExt as _;
 
 pub struct Session {
     pub editor: WeakView<Editor>,
@@ -30,15 +34,19 @@ pub struct Session {
     blocks: HashMap<String, EditorBlock>,
     pub messaging_task: Task<()>,
     pub kernel_specification: KernelSpecification,
+    pub(crate) variable_explorer: VariableExplorer,
+    /// Requests whose reply is awaited, such as `complete_request` and `inspect_request`.
+    pending_replies: HashMap<String, oneshot::Sender<JupyterMessageContent>>,
     _buffer_subscription: Subscription,
 }
 
-struct EditorBlock {
+pub(crate) struct EditorBlock {
     editor: WeakView<Editor>,
     code_range: Range<Anchor>,
     invalidation_anchor: Anchor,
     block_id: BlockId,
     execution_view: View<ExecutionView>,
+    _execution_view_subscription: Subscription,
 }
 
 impl EditorBlock {
@@ -81,12 +89,33 @@ impl EditorBlock {
             (block_id, invalidation_anchor)
         })?;
 
+        // Resize the block whenever the outputs change, such as when a JSON tree is expanded
+        let _execution_view_subscription = cx.observe(&execution_view, {
+            let editor = editor.clone();
+            move |_, execution_view, cx| {
+                editor
+                    .update(cx, |editor, cx| {
+                        let mut replacements = HashMap::default();
+                        replacements.insert(
+                            block_id,
+                            (
+                                Some(execution_view.num_lines(cx).saturating_add(1)),
+                                Self::create_output_area_render(execution_view.clone()),
+                            ),
+                        );
+                        editor.replace_blocks(replacements, None, cx);
+                    })
+                    .ok();
+            }
+        });
+
         anyhow::Ok(Self {
             editor,
             code_range,
             invalidation_anchor,
             block_id,
             execution_view,
+            _execution_view_subscription,
         })
     }
 
@@ -94,23 +123,9 @@ impl EditorBlock {
         self.execution_view.update(cx, |execution_view, cx| {
             execution_view.push_message(&message.content, cx);
         });
-
-        self.editor
-            .update(cx, |editor, cx| {
-                let mut replacements = HashMap::default();
-                replacements.insert(
-                    self.block_id,
-                    (
-                        Some(self.execution_view.num_lines(cx).saturating_add(1)),
-                        Self::create_output_area_render(self.execution_view.clone()),
-                    ),
-                );
-                editor.replace_blocks(replacements, None, cx);
-            })
-            .ok();
     }
 
-    fn create_output_area_render(execution_view: View<ExecutionView>) -> RenderBlock {
+    pub(crate) fn create_output_area_render(execution_view: View<ExecutionView>) -> RenderBlock {
         let render = move |cx: &mut BlockContext| {
             let execution_view = execution_view.clone();
             let text_font = ThemeSettings::get_global(cx).buffer_font.family.clone();
@@ -147,6 +162,7 @@ impl EditorBlock {
 impl Session {
     pub fn new(
         editor: WeakView<Editor>,
+        project: Model<Project>,
         fs: Arc<dyn Fs>,
         kernel_specification: KernelSpecification,
         cx: &mut ViewContext<Self>,
@@ -206,6 +222,13 @@ impl Session {
 
         let subscription = match editor.upgrade() {
             Some(editor) => {
+                let session = cx.view().downgrade();
+                editor.update(cx, |editor, _| {
+                    editor.set_completion_provider(Box::new(KernelCompletionProvider {
+                        project,
+                        session,
+                    }));
+                });
                 let buffer = editor.read(cx).buffer().clone();
                 cx.subscribe(&buffer, Self::on_buffer_event)
             }
@@ -218,6 +241,8 @@ impl Session {
             messaging_task: Task::ready(()),
             blocks: HashMap::default(),
             kernel_specification,
+            variable_explorer: VariableExplorer::new(),
+            pending_replies: HashMap::default(),
             _buffer_subscription: subscription,
         };
     }
@@ -264,6 +289,90 @@ impl Session {
         anyhow::Ok(())
     }
 
+    /// Sends a request to the kernel, resolving with the content of its reply.
+    fn request(
+        &mut self,
+        message: JupyterMessage,
+        cx: &mut ViewContext<Self>,
+    ) -> oneshot::Receiver<JupyterMessageContent> {
+        let (reply_tx, reply_rx) = oneshot::channel();
+        if let Kernel::RunningKernel(_) = self.kernel {
+            self.pending_replies
+                .insert(message.header.msg_id.clone(), reply_tx);
+            self.send(message, cx).ok();
+        }
+        reply_rx
+    }
+
+    /// Asks the kernel to complete the code at the cursor position, which is counted in
+    /// Unicode code points.
+    pub fn complete(
+        &mut self,
+        code: String,
+        cursor_pos: usize,
+        cx: &mut ViewContext<Self>,
+    ) -> Task<Result<CompleteReply>> {
+        // The shell channel handles one request at a time, so a busy kernel would only reply
+        // after the execution has finished
+        let is_idle = match &self.kernel {
+            Kernel::RunningKernel(kernel) => {
+                matches!(kernel.execution_state, ExecutionState::Idle)
+            }
+            _ => false,
+        };
+        if !is_idle {
+            return Task::ready(Err(anyhow!("kernel is not idle")));
+        }
+
+        let reply = self.request(CompleteRequest { code, cursor_pos }.into(), cx);
+        cx.background_executor().spawn(async move {
+            match reply.await.context("kernel did not reply")? {
+                JupyterMessageContent::CompleteReply(reply) => Ok(reply),
+                _ => Err(anyhow!("unexpected reply to complete_request")),
+            }
+        })
+    }
+
+    /// Shows the kernel's `inspect_reply` for a variable in the variable explorer.
+    pub fn inspect(&mut self, name: String, cx: &mut ViewContext<Self>) {
+        let request = InspectRequest {
+            cursor_pos: name.chars().count(),
+            code: name.clone(),
+            detail_level: Some(0),
+        };
+        let reply = self.request(request.into(), cx);
+        cx.spawn(|this, mut cx| async move {
+            let reply = reply.await.context("kernel did not reply")?;
+            this.update(&mut cx, |this, cx| {
+                if let JupyterMessageContent::InspectReply(reply) = reply {
+                    this.variable_explorer.set_inspection(name, &reply, cx);
+                    cx.notify();
+                }
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
+    /// Lists the variables of the kernel again, when the kernel's language is supported.
+    fn refresh_variables(&mut self, cx: &mut ViewContext<Self>) {
+        let Some(code) =
+            VariableExplorer::introspection_code(&self.kernel_specification.kernelspec.language)
+        else {
+            return;
+        };
+        let message: JupyterMessage = ExecuteRequest {
+            code: code.to_string(),
+            silent: true,
+            store_history: false,
+            ..ExecuteRequest::default()
+        }
+        .into();
+        self.variable_explorer
+            .start_refresh(message.header.msg_id.clone());
+        self.send(message, cx).ok();
+        cx.notify();
+    }
+
     pub fn clear_outputs(&mut self, cx: &mut ViewContext<Self>) {
         let blocks_to_remove: HashSet<BlockId> =
             self.blocks.values().map(|block| block.block_id).collect();
@@ -366,9 +475,26 @@ impl Session {
                 self.kernel.set_kernel_info(&reply);
                 cx.notify();
             }
+            JupyterMessageContent::ExecuteReply(_)
+                if self.blocks.contains_key(parent_message_id) =>
+            {
+                self.refresh_variables(cx);
+            }
+            JupyterMessageContent::CompleteReply(_) | JupyterMessageContent::InspectReply(_) => {
+                if let Some(reply_tx) = self.pending_replies.remove(parent_message_id) {
+                    reply_tx.send(message.content.clone()).ok();
+                }
+                return;
+            }
             _ => {}
         }
 
+        if self.variable_explorer.is_refreshing(parent_message_id) {
+            self.variable_explorer.push_message(&message.content);
+            cx.notify();
+            return;
+        }
+
         if let Some(block) = self.blocks.get_mut(parent_message_id) {
             block.handle_message(&message, cx);
             return;
@@ -395,13 +521,16 @@ impl Session {
                 let mut request_tx = kernel.request_tx.clone();
 
                 cx.spawn(|this, mut cx| async move {
-                    let message: JupyterMessage = ShutdownRequest { restart: false }.into();
-                    request_tx.try_send(message).ok();
+                    // Kernels that were already running on a Jupyter server are shared, so leave them running
+                    if !kernel.is_attached() {
+                        let message: JupyterMessage = ShutdownRequest { restart: false }.into();
+                        request_tx.try_send(message).ok();
 
-                    // Give the kernel a bit of time to clean up
-                    cx.background_executor().timer(Duration::from_secs(3)).await;
+                        // Give the kernel a bit of time to clean up
+                        cx.background_executor().timer(Duration::from_secs(3)).await;
 
-                    kernel.process.kill().ok();
+                        kernel.kill().await.log_err();
+                    }
 
                     this.update(&mut cx, |this, cx| {
                         cx.emit(SessionEvent::Shutdown(this.editor.clone()));
@@ -470,6 +599,10 @@ impl Render for Session {
             Kernel::Shutdown => format!("{} (Shutdown)", self.kernel_specification.name),
         };
 
+        let supports_variables =
+            VariableExplorer::introspection_code(&self.kernel_specification.kernelspec.language)
+                .is_some();
+
         return v_flex()
             .gap_1()
             .child(
@@ -478,6 +611,9 @@ impl Render for Session {
                     .child(self.kernel.dot())
                     .child(Label::new(status_text)),
             )
-            .child(h_flex().gap_2().children(buttons));
+            .child(h_flex().gap_2().children(buttons))
+            .when(matches!(self.kernel, Kernel::RunningKernel(_)), |this| {
+                this.child(self.variable_explorer.render(supports_variables, cx))
+            });
     }
 }
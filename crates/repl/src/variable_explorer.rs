@@ -0,0 +1,322 @@
+use anyhow::Result;
+use gpui::AnyElement;
+use runtimelib::{ExecutionState, InspectReply, JupyterMessageContent, MimeType, Stdio};
+use serde::Deserialize;
+use ui::{prelude::*, ButtonLike, Tooltip};
+
+use crate::{outputs::MarkdownView, session::Session};
+
+/// Lists the variables of the kernel namespace, using a snippet that the kernel runs silently
+/// after each execution, with the `inspect_reply` of the selected variable shown below the list.
+pub struct VariableExplorer {
+    variables: Vec<Variable>,
+    refresh: Option<Refresh>,
+    error: Option<String>,
+    inspection: Option<Inspection>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct Variable {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub type_name: String,
+    /// The shape of arrays and data frames, or the length of collections.
+    pub size: Option<String>,
+    pub repr: String,
+}
+
+struct Refresh {
+    msg_id: String,
+    stdout: String,
+}
+
+struct Inspection {
+    name: String,
+    view: MarkdownView,
+}
+
+/// Prints the variables of the user namespace as JSON, without leaving anything behind.
+const PYTHON_INTROSPECTION: &str = r#"
+def __zed_variables():
+    import json, types
+
+    hidden = {"In", "Out", "exit", "quit", "get_ipython"}
+    skipped_types = (types.ModuleType, types.FunctionType, types.BuiltinFunctionType, type)
+    variables = []
+    for name, value in list(globals().items()):
+        if name.startswith("_") or name in hidden or isinstance(value, skipped_types):
+            continue
+        shape = getattr(value, "shape", None)
+        if isinstance(shape, tuple):
+            size = " × ".join(str(dimension) for dimension in shape) or None
+        else:
+            try:
+                size = str(len(value))
+            except Exception:
+                size = None
+        try:
+            text = repr(value)
+        except Exception as error:
+            text = f"<{type(error).__name__}>"
+        if len(text) > 200:
+            text = text[:200] + "…"
+        variables.append({"name": name, "type": type(value).__name__, "size": size, "repr": text})
+    print(json.dumps(variables))
+
+__zed_variables()
+del __zed_variables
+"#;
+
+impl VariableExplorer {
+    pub fn new() -> Self {
+        Self {
+            variables: Vec::new(),
+            refresh: None,
+            error: None,
+            inspection: None,
+        }
+    }
+
+    /// The code that lists the variables of a kernel, for the languages that are supported.
+    pub fn introspection_code(language: &str) -> Option<&'static str> {
+        match language.to_lowercase().as_str() {
+            "python" => Some(PYTHON_INTROSPECTION),
+            _ => None,
+        }
+    }
+
+    pub fn start_refresh(&mut self, msg_id: String) {
+        self.refresh = Some(Refresh {
+            msg_id,
+            stdout: String::new(),
+        });
+    }
+
+    pub fn is_refreshing(&self, msg_id: &str) -> bool {
+        self.refresh
+            .as_ref()
+            .map_or(false, |refresh| refresh.msg_id == msg_id)
+    }
+
+    /// Accepts a message in reply to the introspection request. The variables are updated once
+    /// the kernel is idle again, as by then all of the output has been received.
+    pub fn push_message(&mut self, content: &JupyterMessageContent) {
+        let Some(refresh) = self.refresh.as_mut() else {
+            return;
+        };
+        match content {
+            JupyterMessageContent::StreamContent(stream)
+                if matches!(stream.name, Stdio::Stdout) =>
+            {
+                refresh.stdout.push_str(&stream.text);
+            }
+            JupyterMessageContent::ErrorOutput(error) => {
+                self.error = Some(format!("{}: {}", error.ename, error.evalue));
+            }
+            JupyterMessageContent::Status(status)
+                if matches!(status.execution_state, ExecutionState::Idle) =>
+            {
+                if let Some(refresh) = self.refresh.take() {
+                    match parse_variables(&refresh.stdout) {
+                        Ok(variables) => {
+                            self.variables = variables;
+                            self.error = None;
+                        }
+                        Err(error) if self.error.is_none() => {
+                            self.error = Some(error.to_string());
+                        }
+                        Err(_) => {}
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    pub fn set_inspection(&mut self, name: String, reply: &InspectReply, cx: &mut WindowContext) {
+        let text = if reply.found {
+            inspection_markdown(reply)
+        } else {
+            format!("No information available for `{name}`")
+        };
+        self.inspection = Some(Inspection {
+            name,
+            view: MarkdownView::from(text, cx),
+        });
+    }
+
+    pub fn render(&self, supported: bool, cx: &mut ViewContext<Session>) -> AnyElement {
+        let header = h_flex()
+            .justify_between()
+            .child(Label::new("Variables").color(Color::Muted))
+            .when(self.refresh.is_some(), |header| {
+                header.child(Label::new("Refreshing…").size(LabelSize::Small))
+            });
+
+        if !supported {
+            return v_flex()
+                .gap_1()
+                .child(header)
+                .child(
+                    Label::new("Variables are only listed for Python kernels")
+                        .size(LabelSize::Small)
+                        .color(Color::Muted),
+                )
+                .into_any_element();
+        }
+
+        let rows = self.variables.iter().map(|variable| {
+            let name = variable.name.clone();
+            ButtonLike::new(ElementId::Name(
+                format!("variable-{}", variable.name).into(),
+            ))
+            .full_width()
+            .selected(
+                self.inspection
+                    .as_ref()
+                    .map_or(false, |inspection| inspection.name == variable.name),
+            )
+            .child(
+                h_flex()
+                    .w_full()
+                    .gap_2()
+                    .child(Label::new(variable.name.clone()))
+                    .child(
+                        Label::new(match &variable.size {
+                            Some(size) => format!("{} [{size}]", variable.type_name),
+                            None => variable.type_name.clone(),
+                        })
+                        .color(Color::Muted),
+                    )
+                    .child(
+                        div().flex_1().overflow_hidden().child(
+                            Label::new(
+                                variable.repr.lines().next().unwrap_or_default().to_string(),
+                            )
+                            .size(LabelSize::Small)
+                            .color(Color::Muted),
+                        ),
+                    ),
+            )
+            .tooltip({
+                let repr = variable.repr.clone();
+                move |cx| Tooltip::text(repr.clone(), cx)
+            })
+            .on_click(cx.listener(move |session, _, cx| {
+                session.inspect(name.clone(), cx);
+            }))
+        });
+
+        v_flex()
+            .gap_1()
+            .child(header)
+            .children(self.error.as_ref().map(|error| {
+                Label::new(error.clone())
+                    .size(LabelSize::Small)
+                    .color(Color::Error)
+            }))
+            .when(self.variables.is_empty() && self.error.is_none(), |list| {
+                list.child(
+                    Label::new("No variables defined")
+                        .size(LabelSize::Small)
+                        .color(Color::Muted),
+                )
+            })
+            .children(rows)
+            .children(self.inspection.as_ref().map(|inspection| {
+                v_flex()
+                    .gap_1()
+                    .pt_2()
+                    .child(
+                        h_flex()
+                            .justify_between()
+                            .child(Label::new(inspection.name.clone()))
+                            .child(
+                                IconButton::new("close-inspection", IconName::Close)
+                                    .icon_size(IconSize::Small)
+                                    .on_click(cx.listener(|session, _, cx| {
+                                        session.variable_explorer.inspection = None;
+                                        cx.notify();
+                                    })),
+                            ),
+                    )
+                    .child(inspection.view.render())
+            }))
+            .into_any_element()
+    }
+}
+
+fn parse_variables(stdout: &str) -> Result<Vec<Variable>> {
+    Ok(serde_json::from_str(stdout.trim())?)
+}
+
+/// Kernels such as IPython reply with plain text that is colored with ANSI escape codes, which
+/// is shown as a code block.
+fn inspection_markdown(reply: &InspectReply) -> String {
+    let rank = |mimetype: &MimeType| match mimetype {
+        MimeType::Markdown(_) => 2,
+        MimeType::Plain(_) => 1,
+        _ => 0,
+    };
+    match reply.data.richest(rank) {
+        Some(MimeType::Markdown(text)) => text.clone(),
+        Some(MimeType::Plain(text)) => format!("```\n{}\n```", strip_ansi_escapes(text).trim_end()),
+        _ => String::new(),
+    }
+}
+
+fn strip_ansi_escapes(text: &str) -> String {
+    let mut stripped = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(char) = chars.next() {
+        if char != '\x1b' {
+            stripped.push(char);
+            continue;
+        }
+        // Control sequences end with a character in the range `@` to `~`
+        if chars.next() == Some('[') {
+            for char in chars.by_ref() {
+                if ('@'..='~').contains(&char) {
+                    break;
+                }
+            }
+        }
+    }
+    stripped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_variables() {
+        let stdout = r#"[{"name": "df", "type": "DataFrame", "size": "3 × 2", "repr": "   a  b"}, {"name": "x", "type": "int", "size": null, "repr": "1"}]
+"#;
+        assert_eq!(
+            parse_variables(stdout).unwrap(),
+            [
+                Variable {
+                    name: "df".into(),
+                    type_name: "DataFrame".into(),
+                    size: Some("3 × 2".into()),
+                    repr: "   a  b".into(),
+                },
+                Variable {
+                    name: "x".into(),
+                    type_name: "int".into(),
+                    size: None,
+                    repr: "1".into(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_strip_ansi_escapes() {
+        assert_eq!(
+            strip_ansi_escapes("\x1b[0;31mType:\x1b[0m      int\n\x1b[1mString form:\x1b[0m 1"),
+            "Type:      int\nString form: 1"
+        );
+    }
+}
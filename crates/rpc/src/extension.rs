@@ -11,6 +11,7 @@ pub struct ExtensionApiManifest {
     pub repository: String,
     pub schema_version: Option<i32>,
     pub wasm_api_version: Option<String>,
+    pub changelog: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Debug, PartialEq)]
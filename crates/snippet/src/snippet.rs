@@ -1,28 +1,105 @@
 use anyhow::{anyhow, Context, Result};
+use regex::{Captures, Regex};
 use smallvec::SmallVec;
-use std::{collections::BTreeMap, ops::Range};
+use std::{collections::BTreeMap, mem, ops::Range};
 
 #[derive(Clone, Debug, Default, PartialEq)]
 pub struct Snippet {
     pub text: String,
     pub tabstops: Vec<TabStop>,
+    /// The values offered for each tabstop, in the same order as `tabstops`.
+    pub choices: Vec<Option<Vec<String>>>,
+    /// Mirrors of tabstops whose text is derived from the tabstop's text.
+    pub transforms: Vec<TabStopTransform>,
 }
 
 type TabStop = SmallVec<[Range<isize>; 2]>;
 
+/// A mirror of a tabstop, such as `${1/(.*)/${1:/upcase}/}`, that is updated
+/// from the tabstop's text when leaving that tabstop.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TabStopTransform {
+    /// The index of the mirrored tabstop in [`Snippet::tabstops`].
+    pub tabstop_index: usize,
+    pub range: Range<isize>,
+    pub transform: Transform,
+}
+
+/// A regex substitution, as in `/(.*)/${1:/upcase}/g`.
+#[derive(Clone, Debug)]
+pub struct Transform {
+    regex: Regex,
+    format: Vec<FormatItem>,
+    global: bool,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum FormatItem {
+    Text(String),
+    Group(usize),
+    CaseChange(usize, CaseChange),
+    Conditional {
+        group: usize,
+        /// The text to insert if the group matched, `None` to insert the group itself.
+        if_matched: Option<String>,
+        otherwise: String,
+    },
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum CaseChange {
+    Upcase,
+    Downcase,
+    Capitalize,
+    CamelCase,
+    PascalCase,
+}
+
+struct ParseState<'a> {
+    text: String,
+    tabstops: BTreeMap<usize, TabStop>,
+    choices: BTreeMap<usize, Vec<String>>,
+    transforms: Vec<(usize, Range<isize>, Transform)>,
+    variables: &'a dyn Fn(&str) -> Option<String>,
+}
+
 impl Snippet {
     pub fn parse(source: &str) -> Result<Self> {
-        let mut text = String::with_capacity(source.len());
-        let mut tabstops = BTreeMap::new();
-        parse_snippet(source, false, &mut text, &mut tabstops)
-            .context("failed to parse snippet")?;
+        Self::parse_with_variables(source, &|_| None)
+    }
+
+    /// Parses the snippet, resolving variables such as `$TM_FILENAME` with the given function.
+    ///
+    /// Variables that resolve to nothing or to an empty string are replaced by their default value.
+    /// Unknown variables without a default are replaced by their name.
+    pub fn parse_with_variables(
+        source: &str,
+        variables: &dyn Fn(&str) -> Option<String>,
+    ) -> Result<Self> {
+        let mut state = ParseState {
+            text: String::with_capacity(source.len()),
+            tabstops: BTreeMap::new(),
+            choices: BTreeMap::new(),
+            transforms: Vec::new(),
+            variables,
+        };
+        parse_snippet(source, false, &mut state).context("failed to parse snippet")?;
+        let ParseState {
+            text,
+            mut tabstops,
+            mut choices,
+            transforms,
+            ..
+        } = state;
 
         let len = text.len() as isize;
         let final_tabstop = tabstops.remove(&0);
+        let mut tabstop_indices = tabstops.keys().copied().collect::<Vec<_>>();
         let mut tabstops = tabstops.into_values().collect::<Vec<_>>();
 
         if let Some(final_tabstop) = final_tabstop {
             tabstops.push(final_tabstop);
+            tabstop_indices.push(0);
         } else {
             let end_tabstop = [len..len].into_iter().collect();
             if !tabstops.last().map_or(false, |t| *t == end_tabstop) {
@@ -30,26 +107,136 @@ impl Snippet {
             }
         }
 
-        Ok(Snippet { text, tabstops })
+        let mut choices = tabstop_indices
+            .iter()
+            .map(|index| choices.remove(index))
+            .collect::<Vec<_>>();
+        choices.resize(tabstops.len(), None);
+        let transforms = transforms
+            .into_iter()
+            .filter_map(|(index, range, transform)| {
+                Some(TabStopTransform {
+                    tabstop_index: tabstop_indices.iter().position(|ix| *ix == index)?,
+                    range,
+                    transform,
+                })
+            })
+            .collect();
+
+        Ok(Snippet {
+            text,
+            tabstops,
+            choices,
+            transforms,
+        })
+    }
+}
+
+impl Transform {
+    /// Replaces the first match of the regex in the text, or every match if the
+    /// transform is global, with the format string.
+    pub fn apply(&self, text: &str) -> String {
+        let format = |captures: &Captures| {
+            let mut result = String::new();
+            for item in &self.format {
+                item.append_to(captures, &mut result);
+            }
+            result
+        };
+        if self.global {
+            self.regex.replace_all(text, format).into_owned()
+        } else {
+            self.regex.replace(text, format).into_owned()
+        }
+    }
+}
+
+impl PartialEq for Transform {
+    fn eq(&self, other: &Self) -> bool {
+        self.regex.as_str() == other.regex.as_str()
+            && self.format == other.format
+            && self.global == other.global
+    }
+}
+
+impl FormatItem {
+    fn append_to(&self, captures: &Captures, result: &mut String) {
+        let group = |index: usize| captures.get(index).map(|m| m.as_str());
+        match self {
+            FormatItem::Text(text) => result.push_str(text),
+            FormatItem::Group(index) => result.push_str(group(*index).unwrap_or_default()),
+            FormatItem::CaseChange(index, case_change) => {
+                result.push_str(&case_change.apply(group(*index).unwrap_or_default()))
+            }
+            FormatItem::Conditional {
+                group: index,
+                if_matched,
+                otherwise,
+            } => match group(*index).filter(|text| !text.is_empty()) {
+                Some(text) => result.push_str(if_matched.as_deref().unwrap_or(text)),
+                None => result.push_str(otherwise),
+            },
+        }
+    }
+}
+
+impl CaseChange {
+    fn apply(self, text: &str) -> String {
+        match self {
+            CaseChange::Upcase => text.to_uppercase(),
+            CaseChange::Downcase => text.to_lowercase(),
+            CaseChange::Capitalize => capitalize(text, true),
+            CaseChange::CamelCase | CaseChange::PascalCase => text
+                .split(|c: char| !c.is_alphanumeric())
+                .filter(|word| !word.is_empty())
+                .enumerate()
+                .map(|(ix, word)| capitalize(word, ix > 0 || self == CaseChange::PascalCase))
+                .collect(),
+        }
+    }
+}
+
+fn capitalize(text: &str, upper: bool) -> String {
+    let mut chars = text.chars();
+    match chars.next() {
+        Some(first) if upper => first.to_uppercase().chain(chars).collect(),
+        Some(first) => first.to_lowercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+impl<'a> ParseState<'a> {
+    fn new(variables: &'a dyn Fn(&str) -> Option<String>) -> Self {
+        Self {
+            text: String::new(),
+            tabstops: BTreeMap::new(),
+            choices: BTreeMap::new(),
+            transforms: Vec::new(),
+            variables,
+        }
     }
 }
 
-fn parse_snippet<'a>(
-    mut source: &'a str,
-    nested: bool,
-    text: &mut String,
-    tabstops: &mut BTreeMap<usize, TabStop>,
-) -> Result<&'a str> {
+fn parse_snippet<'a>(mut source: &'a str, nested: bool, state: &mut ParseState) -> Result<&'a str> {
     loop {
         match source.chars().next() {
             None => return Ok(""),
             Some('$') => {
-                source = parse_tabstop(&source[1..], text, tabstops)?;
+                let rest = &source[1..];
+                let is_variable = rest
+                    .strip_prefix('{')
+                    .unwrap_or(rest)
+                    .starts_with(|c: char| c == '_' || c.is_ascii_alphabetic());
+                source = if is_variable {
+                    parse_variable(rest, state)?
+                } else {
+                    parse_tabstop(rest, state)?
+                };
             }
             Some('\\') => {
                 source = &source[1..];
                 if let Some(c) = source.chars().next() {
-                    text.push(c);
+                    state.text.push(c);
                     source = &source[c.len_utf8()..];
                 }
             }
@@ -57,54 +244,322 @@ fn parse_snippet<'a>(
                 if nested {
                     return Ok(source);
                 } else {
-                    text.push('}');
+                    state.text.push('}');
                     source = &source[1..];
                 }
             }
             Some(_) => {
                 let chunk_end = source.find(['}', '$', '\\']).unwrap_or(source.len());
                 let (chunk, rest) = source.split_at(chunk_end);
-                text.push_str(chunk);
+                state.text.push_str(chunk);
                 source = rest;
             }
         }
     }
 }
 
-fn parse_tabstop<'a>(
-    mut source: &'a str,
-    text: &mut String,
-    tabstops: &mut BTreeMap<usize, TabStop>,
-) -> Result<&'a str> {
-    let tabstop_start = text.len();
+fn parse_tabstop<'a>(mut source: &'a str, state: &mut ParseState) -> Result<&'a str> {
+    let tabstop_start = state.text.len();
     let tabstop_index;
     if source.starts_with('{') {
         let (index, rest) = parse_int(&source[1..])?;
         tabstop_index = index;
         source = rest;
 
-        if source.starts_with(':') {
-            source = parse_snippet(&source[1..], true, text, tabstops)?;
+        if let Some(rest) = source.strip_prefix(':') {
+            source = parse_snippet(rest, true, state)?;
+        } else if let Some(rest) = source.strip_prefix('|') {
+            let (choices, rest) = parse_choices(rest)?;
+            state
+                .text
+                .push_str(choices.first().map_or("", String::as_str));
+            state.choices.insert(tabstop_index, choices);
+            source = rest;
+        } else if let Some(rest) = source.strip_prefix('/') {
+            // A transformed mirror isn't a part of the tabstop itself, its text is
+            // derived from the tabstop's text instead.
+            let (transform, rest) = parse_transform(rest)?;
+            let mirrored_text = state
+                .tabstops
+                .get(&tabstop_index)
+                .and_then(|tabstop| tabstop.first())
+                .map(|range| state.text[range.start as usize..range.end as usize].to_string())
+                .unwrap_or_default();
+            state.text.push_str(&transform.apply(&mirrored_text));
+            state.transforms.push((
+                tabstop_index,
+                tabstop_start as isize..state.text.len() as isize,
+                transform,
+            ));
+            return parse_closing_brace(rest);
         }
 
-        if source.starts_with('}') {
-            source = &source[1..];
-        } else {
-            return Err(anyhow!("expected a closing brace"));
-        }
+        source = parse_closing_brace(source)?;
     } else {
         let (index, rest) = parse_int(source)?;
         tabstop_index = index;
         source = rest;
     }
 
-    tabstops
+    state
+        .tabstops
         .entry(tabstop_index)
         .or_default()
-        .push(tabstop_start as isize..text.len() as isize);
+        .push(tabstop_start as isize..state.text.len() as isize);
     Ok(source)
 }
 
+fn parse_choices(mut source: &str) -> Result<(Vec<String>, &str)> {
+    let mut choices = Vec::new();
+    let mut choice = String::new();
+    loop {
+        let mut chars = source.chars();
+        match chars.next() {
+            None => return Err(anyhow!("expected the end of the choices")),
+            Some('\\') => {
+                if let Some(c) = chars.next() {
+                    choice.push(c);
+                }
+            }
+            Some(',') => choices.push(mem::take(&mut choice)),
+            Some('|') => {
+                choices.push(choice);
+                return Ok((choices, chars.as_str()));
+            }
+            Some(c) => choice.push(c),
+        }
+        source = chars.as_str();
+    }
+}
+
+fn parse_variable<'a>(mut source: &'a str, state: &mut ParseState) -> Result<&'a str> {
+    let braced = source.starts_with('{');
+    if braced {
+        source = &source[1..];
+    }
+    let (name, rest) = parse_variable_name(source)?;
+    source = rest;
+    let value = (state.variables)(name);
+
+    if !braced {
+        state.text.push_str(value.as_deref().unwrap_or(name));
+        return Ok(source);
+    }
+
+    if let Some(rest) = source.strip_prefix(':') {
+        match value.filter(|value| !value.is_empty()) {
+            Some(value) => {
+                // Tabstops in the default are dropped along with it.
+                let mut discarded = ParseState::new(state.variables);
+                source = parse_snippet(rest, true, &mut discarded)?;
+                state.text.push_str(&value);
+            }
+            None => source = parse_snippet(rest, true, state)?,
+        }
+    } else if let Some(rest) = source.strip_prefix('/') {
+        let (transform, rest) = parse_transform(rest)?;
+        state
+            .text
+            .push_str(&transform.apply(value.as_deref().unwrap_or(name)));
+        source = rest;
+    } else {
+        state.text.push_str(value.as_deref().unwrap_or(name));
+    }
+
+    parse_closing_brace(source)
+}
+
+fn parse_variable_name(source: &str) -> Result<(&str, &str)> {
+    let len = source
+        .find(|c: char| c != '_' && !c.is_ascii_alphanumeric())
+        .unwrap_or(source.len());
+    if len == 0 || source.starts_with(|c: char| c.is_ascii_digit()) {
+        return Err(anyhow!("expected a variable name"));
+    }
+    Ok(source.split_at(len))
+}
+
+/// Parses the `regex/format/options` part of a transform, up to its closing brace.
+fn parse_transform(source: &str) -> Result<(Transform, &str)> {
+    let (mut pattern, source) = parse_regex(source)?;
+    let (format, source) = parse_format(source)?;
+    let options_len = source
+        .find('}')
+        .ok_or_else(|| anyhow!("expected a closing brace"))?;
+    let (options, source) = source.split_at(options_len);
+
+    let mut flags = String::new();
+    let mut global = false;
+    for option in options.chars() {
+        match option {
+            'g' => global = true,
+            'i' | 'm' | 's' => flags.push(option),
+            _ => {}
+        }
+    }
+    if !flags.is_empty() {
+        pattern = format!("(?{flags}){pattern}");
+    }
+    let regex = Regex::new(&pattern).context("invalid transform regex")?;
+
+    Ok((
+        Transform {
+            regex,
+            format,
+            global,
+        },
+        source,
+    ))
+}
+
+fn parse_regex(mut source: &str) -> Result<(String, &str)> {
+    let mut pattern = String::new();
+    loop {
+        let mut chars = source.chars();
+        match chars.next() {
+            None => return Err(anyhow!("expected a closing slash")),
+            Some('/') => return Ok((pattern, chars.as_str())),
+            Some('\\') => match chars.next() {
+                Some('/') => pattern.push('/'),
+                Some(c) => {
+                    pattern.push('\\');
+                    pattern.push(c);
+                }
+                None => pattern.push('\\'),
+            },
+            Some(c) => pattern.push(c),
+        }
+        source = chars.as_str();
+    }
+}
+
+fn parse_format(mut source: &str) -> Result<(Vec<FormatItem>, &str)> {
+    let mut items = Vec::new();
+    let mut text = String::new();
+    loop {
+        let mut chars = source.chars();
+        match chars.next() {
+            None => return Err(anyhow!("expected a closing slash")),
+            Some('/') => {
+                if !text.is_empty() {
+                    items.push(FormatItem::Text(text));
+                }
+                return Ok((items, chars.as_str()));
+            }
+            Some('\\') => {
+                if let Some(c) = chars.next() {
+                    text.push(unescape(c));
+                }
+            }
+            Some('$')
+                if chars
+                    .as_str()
+                    .starts_with(|c: char| c == '{' || c.is_ascii_digit()) =>
+            {
+                if !text.is_empty() {
+                    items.push(FormatItem::Text(mem::take(&mut text)));
+                }
+                let (item, rest) = parse_format_item(chars.as_str())?;
+                items.push(item);
+                source = rest;
+                continue;
+            }
+            Some(c) => text.push(c),
+        }
+        source = chars.as_str();
+    }
+}
+
+fn parse_format_item(source: &str) -> Result<(FormatItem, &str)> {
+    let Some(source) = source.strip_prefix('{') else {
+        let (group, rest) = parse_int(source)?;
+        return Ok((FormatItem::Group(group), rest));
+    };
+
+    let (group, mut source) = parse_int(source)?;
+    let item = if let Some(rest) = source.strip_prefix(":/") {
+        let len = rest.find('}').unwrap_or(rest.len());
+        let case_change = match &rest[..len] {
+            "upcase" => CaseChange::Upcase,
+            "downcase" => CaseChange::Downcase,
+            "capitalize" => CaseChange::Capitalize,
+            "camelcase" => CaseChange::CamelCase,
+            "pascalcase" => CaseChange::PascalCase,
+            other => return Err(anyhow!("unknown case change {other:?}")),
+        };
+        source = &rest[len..];
+        FormatItem::CaseChange(group, case_change)
+    } else if let Some(rest) = source.strip_prefix(":+") {
+        let (if_matched, rest) = parse_format_text(rest, '}');
+        source = rest;
+        FormatItem::Conditional {
+            group,
+            if_matched: Some(if_matched),
+            otherwise: String::new(),
+        }
+    } else if let Some(rest) = source.strip_prefix(":?") {
+        let (if_matched, rest) = parse_format_text(rest, ':');
+        let rest = rest
+            .strip_prefix(':')
+            .ok_or_else(|| anyhow!("expected a colon"))?;
+        let (otherwise, rest) = parse_format_text(rest, '}');
+        source = rest;
+        FormatItem::Conditional {
+            group,
+            if_matched: Some(if_matched),
+            otherwise,
+        }
+    } else if let Some(rest) = source
+        .strip_prefix(":-")
+        .or_else(|| source.strip_prefix(':'))
+    {
+        let (otherwise, rest) = parse_format_text(rest, '}');
+        source = rest;
+        FormatItem::Conditional {
+            group,
+            if_matched: None,
+            otherwise,
+        }
+    } else {
+        FormatItem::Group(group)
+    };
+
+    Ok((item, parse_closing_brace(source)?))
+}
+
+/// Parses text up to the given unescaped terminator, which is not consumed.
+fn parse_format_text(mut source: &str, terminator: char) -> (String, &str) {
+    let mut text = String::new();
+    loop {
+        let mut chars = source.chars();
+        match chars.next() {
+            None => return (text, source),
+            Some(c) if c == terminator => return (text, source),
+            Some('\\') => {
+                if let Some(c) = chars.next() {
+                    text.push(unescape(c));
+                }
+            }
+            Some(c) => text.push(c),
+        }
+        source = chars.as_str();
+    }
+}
+
+fn unescape(c: char) -> char {
+    match c {
+        'n' => '\n',
+        't' => '\t',
+        c => c,
+    }
+}
+
+fn parse_closing_brace(source: &str) -> Result<&str> {
+    source
+        .strip_prefix('}')
+        .ok_or_else(|| anyhow!("expected a closing brace"))
+}
+
 fn parse_int(source: &str) -> Result<(usize, &str)> {
     let len = source
         .find(|c: char| !c.is_ascii_digit())
@@ -199,6 +654,95 @@ mod tests {
         assert_eq!(tabstops(&snippet), &[vec![3..3]]);
     }
 
+    #[test]
+    fn test_snippet_with_choices() {
+        let snippet = Snippet::parse("log(${1|info,warn\\,error,debug|}, $2)").unwrap();
+        assert_eq!(snippet.text, "log(info, )");
+        assert_eq!(
+            tabstops(&snippet),
+            &[vec![4..8], vec![10..10], vec![11..11]]
+        );
+        assert_eq!(
+            snippet.choices,
+            &[
+                Some(vec!["info".into(), "warn,error".into(), "debug".into()]),
+                None,
+                None
+            ]
+        );
+    }
+
+    #[test]
+    fn test_snippet_with_variables() {
+        let variables = |name: &str| match name {
+            "TM_FILENAME" => Some("main.rs".to_string()),
+            "TM_SELECTED_TEXT" => Some(String::new()),
+            _ => None,
+        };
+
+        let snippet = Snippet::parse_with_variables(
+            "// $TM_FILENAME: ${TM_SELECTED_TEXT:${1:todo}} ${UNKNOWN}",
+            &variables,
+        )
+        .unwrap();
+        assert_eq!(snippet.text, "// main.rs: todo UNKNOWN");
+        assert_eq!(tabstops(&snippet), &[vec![12..16], vec![24..24]]);
+
+        // Tabstops in the default are dropped when the variable is resolved.
+        let snippet =
+            Snippet::parse_with_variables("${TM_FILENAME:${1:file}}$2", &variables).unwrap();
+        assert_eq!(snippet.text, "main.rs");
+        assert_eq!(tabstops(&snippet), &[vec![7..7]]);
+
+        let snippet =
+            Snippet::parse_with_variables("mod ${TM_FILENAME/(.*)\\..+$/$1/};", &variables)
+                .unwrap();
+        assert_eq!(snippet.text, "mod main;");
+    }
+
+    #[test]
+    fn test_snippet_with_transforms() {
+        let snippet = Snippet::parse("${1:foo} ${1/(.*)/${1:/upcase}/}$0").unwrap();
+        assert_eq!(snippet.text, "foo FOO");
+        assert_eq!(tabstops(&snippet), &[vec![0..3], vec![7..7]]);
+        assert_eq!(snippet.transforms.len(), 1);
+        assert_eq!(snippet.transforms[0].tabstop_index, 0);
+        assert_eq!(snippet.transforms[0].range, 4..7);
+        assert_eq!(snippet.transforms[0].transform.apply("bar"), "BAR");
+
+        // Transforms of tabstops that don't exist are dropped.
+        let snippet = Snippet::parse("a${2/a/b/}").unwrap();
+        assert_eq!(snippet.text, "a");
+        assert!(snippet.transforms.is_empty());
+    }
+
+    #[test]
+    fn test_transform_formats() {
+        let transform = |source: &str| {
+            let snippet = Snippet::parse(&format!("$1${{1{source}}}")).unwrap();
+            snippet.transforms[0].transform.clone()
+        };
+
+        assert_eq!(transform("/o/0/").apply("foo"), "f0o");
+        assert_eq!(transform("/o/0/g").apply("foo"), "f00");
+        assert_eq!(transform("/O/0/gi").apply("foo"), "f00");
+        assert_eq!(transform("/a\\/b/a_b/").apply("a/b"), "a_b");
+        assert_eq!(
+            transform("/(\\w+)_(\\w+)/${1:/capitalize}$2/").apply("foo_bar"),
+            "Foobar"
+        );
+        assert_eq!(
+            transform("/(.*)/${1:/pascalcase} ${1:/camelcase}/").apply("foo-bar_baz"),
+            "FooBarBaz fooBarBaz"
+        );
+        assert_eq!(transform("/(a)?b/${1:?x:y}/").apply("ab"), "x");
+        assert_eq!(transform("/(a)?b/${1:?x:y}/").apply("b"), "y");
+        assert_eq!(transform("/(a)?b/${1:+x}/").apply("b"), "");
+        assert_eq!(transform("/(a)?b/${1:-y}/").apply("ab"), "a");
+        assert_eq!(transform("/(a)?b/${1:y}/").apply("b"), "y");
+        assert_eq!(transform("/(.*)/\\/$1\\n/").apply("x"), "/x\n");
+    }
+
     fn tabstops(snippet: &Snippet) -> Vec<Vec<Range<isize>>> {
         snippet.tabstops.iter().map(|t| t.to_vec()).collect()
     }
@@ -1,6 +1,7 @@
 //! Baseline interface of Tasks in Zed: all tasks in Zed are intended to use those for implementing their own logic.
 #![deny(missing_docs)]
 
+mod problem_matcher;
 pub mod static_source;
 mod task_template;
 mod vscode_format;
@@ -12,7 +13,11 @@ use std::path::PathBuf;
 use std::str::FromStr;
 use std::{borrow::Cow, path::Path};
 
-pub use task_template::{RevealStrategy, TaskTemplate, TaskTemplates};
+pub use problem_matcher::{
+    BackgroundMatcher, FileLocation, FileLocationKind, Problem, ProblemMatcher,
+    ProblemMatcherDefinition, ProblemPattern, ProblemSeverity,
+};
+pub use task_template::{DependsOrder, RevealStrategy, TaskTemplate, TaskTemplates};
 pub use vscode_format::VsCodeTaskFile;
 
 /// Task identifier, unique within the application.
@@ -78,6 +83,14 @@ pub struct SpawnInTerminal {
     pub allow_concurrent_runs: bool,
     /// What to do with the terminal pane and tab, after the command was started.
     pub reveal: RevealStrategy,
+    /// Problem matchers to turn the output of the task into diagnostics, once it has finished.
+    pub problem_matchers: Vec<ProblemMatcher>,
+    /// Tasks to run before this task, resolved with the same context.
+    pub depends_on: Vec<SpawnInTerminal>,
+    /// Whether the tasks in `depends_on` are run all at once or one after another.
+    pub depends_order: DependsOrder,
+    /// A regex that marks the task as ready for its dependents once its output matches.
+    pub ready_pattern: Option<String>,
 }
 
 /// A final form of the [`TaskTemplate`], that got resolved with a particualar [`TaskContext`] and now is ready to spawn the actual task.
@@ -95,6 +108,8 @@ pub struct ResolvedTask {
     pub resolved_label: String,
     /// Variables that were substituted during the task template resolution.
     substituted_variables: HashSet<VariableName>,
+    /// The context the task got resolved with, to resolve the tasks it depends on.
+    task_context: TaskContext,
     /// Further actions that need to take place after the resolved task is spawned,
     /// with all task variables resolved.
     pub resolved: Option<SpawnInTerminal>,
@@ -118,6 +133,26 @@ impl ResolvedTask {
             .map(|resolved| resolved.label.as_str())
             .unwrap_or_else(|| self.resolved_label.as_str())
     }
+
+    /// Resolves the tasks this task depends on, transitively, with the context the task got resolved with.
+    /// `find_task` looks up a task template by its label, returning it along with the id base of its source.
+    ///
+    /// The dependencies are stored in the resolved task, so that re-running it runs the same tasks.
+    pub fn resolve_dependencies(
+        &mut self,
+        find_task: &dyn Fn(&str) -> Option<(String, TaskTemplate)>,
+    ) -> anyhow::Result<()> {
+        let Some(resolved) = self.resolved.as_mut() else {
+            return Ok(());
+        };
+        let mut dependents = vec![self.original_task.label.clone()];
+        resolved.depends_on = self.original_task.resolve_dependencies(
+            &self.task_context,
+            find_task,
+            &mut dependents,
+        )?;
+        Ok(())
+    }
 }
 
 /// Variables, available for use in [`TaskContext`] when a Zed's [`TaskTemplate`] gets resolved into a [`ResolvedTask`].
@@ -0,0 +1,638 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Context};
+use regex::{Captures, Regex};
+use schemars::JsonSchema;
+use serde::{Deserialize, Deserializer, Serialize};
+
+/// Finds problems, such as compiler errors, in the output of a task, to be shown as diagnostics.
+/// Either the name of a built-in matcher, or a custom definition.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(untagged)]
+pub enum ProblemMatcher {
+    /// One of the built-in matchers: `$rustc`, `$tsc`, `$tsc-watch`, `$gcc` or `$eslint-stylish`.
+    Named(String),
+    /// A matcher with custom patterns, or that adjusts a built-in one.
+    Custom(ProblemMatcherDefinition),
+}
+
+/// A custom problem matcher. Most fields use the same names as Visual Studio Code problem matchers.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct ProblemMatcherDefinition {
+    /// The source the problems are reported with, defaults to the name of the `base` matcher.
+    #[serde(default)]
+    pub owner: Option<String>,
+    /// A built-in matcher to extend, whose patterns are used when no `pattern` is given.
+    #[serde(default)]
+    pub base: Option<String>,
+    /// How the file paths of the problems are resolved, defaults to `auto_detect`.
+    #[serde(default, alias = "fileLocation")]
+    pub file_location: Option<FileLocation>,
+    /// The severity of problems whose pattern does not capture one, defaults to `error`.
+    #[serde(default)]
+    pub severity: Option<ProblemSeverity>,
+    /// The pattern of a single line, or the patterns of consecutive lines that make up a problem.
+    #[serde(default, deserialize_with = "one_or_many")]
+    #[schemars(with = "OneOrMany<ProblemPattern>")]
+    pub pattern: Vec<ProblemPattern>,
+    /// How a task that keeps running, such as a compiler in watch mode, reports each new run.
+    #[serde(default)]
+    pub background: Option<BackgroundMatcher>,
+}
+
+/// The output of a background task that marks the start of a new run, whose problems replace
+/// those of the previous runs.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct BackgroundMatcher {
+    /// The regular expression matching the line that starts a new run.
+    #[serde(default, alias = "beginsPattern", deserialize_with = "regexp_pattern")]
+    #[schemars(with = "Option<String>")]
+    pub begins_pattern: Option<String>,
+}
+
+/// Deserializes a regular expression given either as a string, or as an object with a `regexp`
+/// field, as Visual Studio Code accepts both for background patterns.
+fn regexp_pattern<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Pattern {
+        Regexp(String),
+        Object { regexp: String },
+    }
+
+    Ok(match Option::<Pattern>::deserialize(deserializer)? {
+        Some(Pattern::Regexp(regexp) | Pattern::Object { regexp }) => Some(regexp),
+        None => None,
+    })
+}
+
+/// A regular expression matching one line of a problem, along with the capture groups that hold
+/// the parts of the problem.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct ProblemPattern {
+    /// The regular expression to match lines with.
+    pub regexp: String,
+    /// The capture group of the file path.
+    #[serde(default)]
+    pub file: Option<usize>,
+    /// The capture group of the 1-based line number.
+    #[serde(default)]
+    pub line: Option<usize>,
+    /// The capture group of the 1-based column number.
+    #[serde(default)]
+    pub column: Option<usize>,
+    /// The capture group of the line where the problem ends.
+    #[serde(default, alias = "endLine")]
+    pub end_line: Option<usize>,
+    /// The capture group of the column where the problem ends.
+    #[serde(default, alias = "endColumn")]
+    pub end_column: Option<usize>,
+    /// The capture group of the severity, such as `error` or `warning`.
+    #[serde(default)]
+    pub severity: Option<usize>,
+    /// The capture group of the code of the problem, such as `E0308`.
+    #[serde(default)]
+    pub code: Option<usize>,
+    /// The capture group of the message, defaults to the whole line.
+    #[serde(default)]
+    pub message: Option<usize>,
+    /// Whether the last pattern of a multi-line matcher matches any number of lines, each of
+    /// which is a problem.
+    #[serde(default, rename = "loop")]
+    pub repeat: bool,
+}
+
+/// How the file paths of problems are resolved.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(untagged)]
+pub enum FileLocation {
+    /// Paths are resolved relative to the task's working directory.
+    Kind(FileLocationKind),
+    /// Paths are resolved relative to the given directory.
+    Relative(FileLocationKind, String),
+}
+
+/// Whether file paths are absolute or relative.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum FileLocationKind {
+    /// Paths are absolute.
+    Absolute,
+    /// Paths are relative.
+    Relative,
+    /// Absolute paths are used as they are, and other paths are relative.
+    #[serde(alias = "autoDetect")]
+    AutoDetect,
+}
+
+/// The severity of a problem.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ProblemSeverity {
+    /// An error.
+    Error,
+    /// A warning.
+    Warning,
+    /// Information, such as notes and hints.
+    Info,
+}
+
+/// A problem found in the output of a task.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Problem {
+    /// The file of the problem.
+    pub path: PathBuf,
+    /// The 1-based line of the problem.
+    pub line: u32,
+    /// The 1-based column of the problem, if it was reported.
+    pub column: Option<u32>,
+    /// The 1-based line where the problem ends, if it was reported.
+    pub end_line: Option<u32>,
+    /// The 1-based column where the problem ends, if it was reported.
+    pub end_column: Option<u32>,
+    /// The severity of the problem.
+    pub severity: ProblemSeverity,
+    /// The code of the problem, such as `E0308`.
+    pub code: Option<String>,
+    /// The message of the problem.
+    pub message: String,
+    /// The owner of the matcher that found the problem.
+    pub source: String,
+}
+
+#[derive(JsonSchema)]
+#[serde(untagged)]
+#[allow(dead_code)]
+pub(crate) enum OneOrMany<T> {
+    One(T),
+    Many(Vec<T>),
+}
+
+/// Deserializes a single value or an array of them, as tasks may list a single problem matcher
+/// or pattern without wrapping it in an array.
+pub(crate) fn one_or_many<'de, D, T>(deserializer: D) -> Result<Vec<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Values<T> {
+        One(T),
+        Many(Vec<T>),
+    }
+
+    Ok(match Values::deserialize(deserializer)? {
+        Values::One(value) => vec![value],
+        Values::Many(values) => values,
+    })
+}
+
+const TSC_WATCH_BEGINS_PATTERN: &str = r"(?:Starting compilation in watch mode|File change detected\. Starting incremental compilation)\.\.\.";
+
+fn builtin_matcher(name: &str) -> Option<ProblemMatcherDefinition> {
+    let pattern = |regexp: &str| ProblemPattern {
+        regexp: regexp.to_string(),
+        ..ProblemPattern::default()
+    };
+    let (owner, pattern) = match name {
+        "$rustc" => (
+            "rustc",
+            vec![
+                ProblemPattern {
+                    severity: Some(1),
+                    code: Some(2),
+                    message: Some(3),
+                    ..pattern(r"^(warning|error)(?:\[(\w+)\])?: (.*)$")
+                },
+                ProblemPattern {
+                    file: Some(1),
+                    line: Some(2),
+                    column: Some(3),
+                    ..pattern(r"^\s*-->\s*(.+?):(\d+):(\d+)$")
+                },
+            ],
+        ),
+        "$tsc" | "$tsc-watch" => (
+            "typescript",
+            vec![ProblemPattern {
+                file: Some(1),
+                line: Some(2),
+                column: Some(3),
+                severity: Some(4),
+                code: Some(5),
+                message: Some(6),
+                ..pattern(
+                    r"^([^\s].*)[\(:](\d+)[,:](\d+)(?:\):\s+|\s+-\s+)(error|warning|info)\s+TS(\d+)\s*:\s*(.*)$",
+                )
+            }],
+        ),
+        "$gcc" => (
+            "gcc",
+            vec![ProblemPattern {
+                file: Some(1),
+                line: Some(2),
+                column: Some(3),
+                severity: Some(4),
+                message: Some(5),
+                ..pattern(r"^(.*?):(\d+):(\d*):?\s+(?:fatal\s+)?(warning|error):\s+(.*)$")
+            }],
+        ),
+        "$eslint-stylish" => (
+            "eslint",
+            vec![
+                ProblemPattern {
+                    file: Some(1),
+                    ..pattern(r"^((?:[a-zA-Z]:)*[./\\]+.*?)$")
+                },
+                ProblemPattern {
+                    line: Some(1),
+                    column: Some(2),
+                    severity: Some(3),
+                    message: Some(4),
+                    code: Some(5),
+                    repeat: true,
+                    ..pattern(r"^\s+(\d+):(\d+)\s+(error|warning|info)\s+(.+?)(?:\s\s+(.*))?$")
+                },
+            ],
+        ),
+        _ => return None,
+    };
+    let background = (name == "$tsc-watch").then(|| BackgroundMatcher {
+        begins_pattern: Some(TSC_WATCH_BEGINS_PATTERN.to_string()),
+    });
+    Some(ProblemMatcherDefinition {
+        owner: Some(owner.to_string()),
+        pattern,
+        background,
+        ..ProblemMatcherDefinition::default()
+    })
+}
+
+impl ProblemMatcher {
+    /// Resolves the built-in matcher this matcher refers to or is based on.
+    pub fn definition(&self) -> anyhow::Result<ProblemMatcherDefinition> {
+        match self {
+            Self::Named(name) => {
+                builtin_matcher(name).ok_or_else(|| anyhow!("Unknown problem matcher `{name}`"))
+            }
+            Self::Custom(definition) => {
+                let Some(base) = definition.base.as_deref() else {
+                    return Ok(definition.clone());
+                };
+                let base = builtin_matcher(base)
+                    .ok_or_else(|| anyhow!("Unknown base problem matcher `{base}`"))?;
+                Ok(ProblemMatcherDefinition {
+                    owner: definition.owner.clone().or(base.owner),
+                    base: None,
+                    file_location: definition.file_location.clone().or(base.file_location),
+                    severity: definition.severity.or(base.severity),
+                    pattern: if definition.pattern.is_empty() {
+                        base.pattern
+                    } else {
+                        definition.pattern.clone()
+                    },
+                    background: definition.background.clone().or(base.background),
+                })
+            }
+        }
+    }
+
+    /// Finds the problems in the output of a task, resolving their paths against the task's
+    /// working directory. For background tasks, only the output of the latest run is matched.
+    pub fn problems(&self, output: &str, cwd: Option<&Path>) -> anyhow::Result<Vec<Problem>> {
+        let definition = self.definition()?;
+        anyhow::ensure!(
+            !definition.pattern.is_empty(),
+            "Problem matcher has no patterns"
+        );
+        let regexes = definition
+            .pattern
+            .iter()
+            .map(|pattern| {
+                Regex::new(&pattern.regexp)
+                    .with_context(|| format!("Invalid problem pattern `{}`", pattern.regexp))
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        let patterns = definition.pattern.iter().zip(regexes).collect::<Vec<_>>();
+        let (last_pattern, first_patterns) = patterns.split_last().expect("checked above");
+
+        let mut lines = output.lines().collect::<Vec<_>>();
+        let begins_pattern = definition
+            .background
+            .as_ref()
+            .and_then(|background| background.begins_pattern.as_ref());
+        if let Some(begins_pattern) = begins_pattern {
+            let begins_regex = Regex::new(begins_pattern)
+                .with_context(|| format!("Invalid background pattern `{begins_pattern}`"))?;
+            if let Some(begin_ix) = lines.iter().rposition(|line| begins_regex.is_match(line)) {
+                lines.drain(..=begin_ix);
+            }
+        }
+        let mut problems = Vec::new();
+        let mut ix = 0;
+        'lines: while ix < lines.len() {
+            let mut captured = CapturedProblem::default();
+            let mut line_ix = ix;
+            for (pattern, regex) in first_patterns {
+                let Some(captures) = lines.get(line_ix).and_then(|line| regex.captures(line))
+                else {
+                    ix += 1;
+                    continue 'lines;
+                };
+                captured.capture(pattern, &captures);
+                line_ix += 1;
+            }
+
+            let (pattern, regex) = last_pattern;
+            let mut matched_last = false;
+            while let Some(captures) = lines.get(line_ix).and_then(|line| regex.captures(line)) {
+                let mut captured = captured.clone();
+                captured.capture(pattern, &captures);
+                problems.extend(captured.into_problem(&definition, cwd));
+                line_ix += 1;
+                matched_last = true;
+                if !pattern.repeat {
+                    break;
+                }
+            }
+            ix = if matched_last { line_ix } else { ix + 1 };
+        }
+        Ok(problems)
+    }
+}
+
+#[derive(Clone, Default)]
+struct CapturedProblem {
+    file: Option<String>,
+    line: Option<u32>,
+    column: Option<u32>,
+    end_line: Option<u32>,
+    end_column: Option<u32>,
+    severity: Option<String>,
+    code: Option<String>,
+    message: Option<String>,
+}
+
+impl CapturedProblem {
+    fn capture(&mut self, pattern: &ProblemPattern, captures: &Captures) {
+        let text = |group: Option<usize>| {
+            let text = captures.get(group?)?.as_str().trim();
+            (!text.is_empty()).then(|| text.to_string())
+        };
+        let number = |group: Option<usize>| text(group)?.parse::<u32>().ok();
+
+        self.file = text(pattern.file).or(self.file.take());
+        self.line = number(pattern.line).or(self.line);
+        self.column = number(pattern.column).or(self.column);
+        self.end_line = number(pattern.end_line).or(self.end_line);
+        self.end_column = number(pattern.end_column).or(self.end_column);
+        self.severity = text(pattern.severity).or(self.severity.take());
+        self.code = text(pattern.code).or(self.code.take());
+        // Without a message group, the whole line is the message, unless a previous line had one
+        self.message = text(pattern.message)
+            .or(self.message.take())
+            .or_else(|| text(Some(0)));
+    }
+
+    fn into_problem(
+        self,
+        definition: &ProblemMatcherDefinition,
+        cwd: Option<&Path>,
+    ) -> Option<Problem> {
+        let path = PathBuf::from(self.file?);
+        let path = match &definition.file_location {
+            Some(FileLocation::Kind(FileLocationKind::Absolute)) => path,
+            Some(FileLocation::Relative(kind, base)) => {
+                if *kind != FileLocationKind::Relative && path.is_absolute() {
+                    path
+                } else {
+                    Path::new(base).join(path)
+                }
+            }
+            Some(FileLocation::Kind(FileLocationKind::Relative)) => cwd?.join(path),
+            Some(FileLocation::Kind(FileLocationKind::AutoDetect)) | None => {
+                if path.is_absolute() {
+                    path
+                } else {
+                    cwd?.join(path)
+                }
+            }
+        };
+        let severity = match self.severity.as_deref().map(str::to_lowercase).as_deref() {
+            Some("error" | "fatal") => ProblemSeverity::Error,
+            Some("warning" | "warn") => ProblemSeverity::Warning,
+            Some("info" | "note" | "help" | "hint") => ProblemSeverity::Info,
+            _ => definition.severity.unwrap_or(ProblemSeverity::Error),
+        };
+        Some(Problem {
+            path,
+            line: self.line?,
+            column: self.column,
+            end_line: self.end_line,
+            end_column: self.end_column,
+            severity,
+            code: self.code,
+            message: self.message?,
+            source: definition
+                .owner
+                .clone()
+                .unwrap_or_else(|| "task".to_string()),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rustc_problems() {
+        let output = r#"   Compiling zed v0.1.0 (/project/crates/zed)
+error[E0308]: mismatched types
+  --> src/main.rs:4:18
+   |
+4  |     let x: i32 = "one";
+   |            ---   ^^^^^ expected `i32`, found `&str`
+
+warning: unused variable: `y`
+ --> src/lib.rs:10:9
+warning: `zed` (bin "zed") generated 1 warning
+error: could not compile `zed` (bin "zed") due to 1 previous error
+"#;
+        let problems = ProblemMatcher::Named("$rustc".into())
+            .problems(output, Some(Path::new("/project")))
+            .unwrap();
+        assert_eq!(
+            problems,
+            [
+                Problem {
+                    path: PathBuf::from("/project/src/main.rs"),
+                    line: 4,
+                    column: Some(18),
+                    end_line: None,
+                    end_column: None,
+                    severity: ProblemSeverity::Error,
+                    code: Some("E0308".into()),
+                    message: "mismatched types".into(),
+                    source: "rustc".into(),
+                },
+                Problem {
+                    path: PathBuf::from("/project/src/lib.rs"),
+                    line: 10,
+                    column: Some(9),
+                    end_line: None,
+                    end_column: None,
+                    severity: ProblemSeverity::Warning,
+                    code: None,
+                    message: "unused variable: `y`".into(),
+                    source: "rustc".into(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_looping_problems() {
+        let output = "
+/project/src/app.js
+  1:10  error    'foo' is defined but never used  no-unused-vars
+  3:1   warning  Unexpected console statement     no-console
+
+✖ 2 problems (1 error, 1 warning)
+";
+        let problems = ProblemMatcher::Named("$eslint-stylish".into())
+            .problems(output, None)
+            .unwrap();
+        assert_eq!(
+            problems
+                .iter()
+                .map(|problem| (
+                    problem.path.to_string_lossy().to_string(),
+                    problem.line,
+                    problem.severity,
+                    problem.message.as_str(),
+                    problem.code.as_deref(),
+                ))
+                .collect::<Vec<_>>(),
+            [
+                (
+                    "/project/src/app.js".to_string(),
+                    1,
+                    ProblemSeverity::Error,
+                    "'foo' is defined but never used",
+                    Some("no-unused-vars"),
+                ),
+                (
+                    "/project/src/app.js".to_string(),
+                    3,
+                    ProblemSeverity::Warning,
+                    "Unexpected console statement",
+                    Some("no-console"),
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_background_problems() {
+        let output = "
+[10:00:00 AM] Starting compilation in watch mode...
+src/a.ts(1,7): error TS2322: Type 'string' is not assignable to type 'number'.
+[10:00:01 AM] Found 1 error. Watching for file changes.
+[10:01:00 AM] File change detected. Starting incremental compilation...
+src/b.ts(2,3): error TS2304: Cannot find name 'foo'.
+";
+        let matcher = ProblemMatcher::Named("$tsc-watch".into());
+        let problems = matcher
+            .problems(output, Some(Path::new("/project")))
+            .unwrap();
+        assert_eq!(
+            problems
+                .iter()
+                .map(|problem| (problem.path.clone(), problem.message.as_str()))
+                .collect::<Vec<_>>(),
+            [(
+                PathBuf::from("/project/src/b.ts"),
+                "Cannot find name 'foo'."
+            )]
+        );
+
+        // Until the next run starts, the problems of the last one are still reported.
+        let (first_run, _) = output.split_once("[10:01:00 AM]").unwrap();
+        assert_eq!(
+            matcher
+                .problems(first_run, Some(Path::new("/project")))
+                .unwrap()
+                .len(),
+            1
+        );
+
+        // Background patterns may also be given as objects, like in Visual Studio Code.
+        let matcher: ProblemMatcher = serde_json_lenient::from_str(
+            r#"{
+                "base": "$tsc",
+                "background": {
+                    "activeBegin": true,
+                    "beginsPattern": { "regexp": "File change detected" },
+                    "endsPattern": "Watching for file changes"
+                }
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(
+            matcher.definition().unwrap().background,
+            Some(BackgroundMatcher {
+                begins_pattern: Some("File change detected".into())
+            })
+        );
+    }
+
+    #[test]
+    fn test_custom_problem_matcher() {
+        let matcher: ProblemMatcher = serde_json_lenient::from_str(
+            r#"{
+                "owner": "lint",
+                "file_location": ["relative", "/project/web"],
+                "severity": "warning",
+                "pattern": {
+                    "regexp": "^(.+):(\\d+): (.+)$",
+                    "file": 1,
+                    "line": 2,
+                    "message": 3
+                }
+            }"#,
+        )
+        .unwrap();
+        let problems = matcher
+            .problems(
+                "index.ts:7: missing semicolon\nok\n",
+                Some(Path::new("/project")),
+            )
+            .unwrap();
+        assert_eq!(
+            problems,
+            [Problem {
+                path: PathBuf::from("/project/web/index.ts"),
+                line: 7,
+                column: None,
+                end_line: None,
+                end_column: None,
+                severity: ProblemSeverity::Warning,
+                code: None,
+                message: "missing semicolon".into(),
+                source: "lint".into(),
+            }]
+        );
+
+        assert!(ProblemMatcher::Named("$unknown".into())
+            .problems("", None)
+            .is_err());
+    }
+}
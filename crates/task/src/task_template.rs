@@ -8,8 +8,9 @@ use sha2::{Digest, Sha256};
 use util::{truncate_and_remove_front, ResultExt};
 
 use crate::{
-    ResolvedTask, SpawnInTerminal, TaskContext, TaskId, TerminalWorkDir, VariableName,
-    ZED_VARIABLE_NAME_PREFIX,
+    problem_matcher::{one_or_many, FileLocation, OneOrMany},
+    ProblemMatcher, ResolvedTask, SpawnInTerminal, TaskContext, TaskId, TerminalWorkDir,
+    VariableName, ZED_VARIABLE_NAME_PREFIX,
 };
 
 /// A template definition of a Zed task to run.
@@ -24,6 +25,8 @@ pub struct TaskTemplate {
     /// Human readable name of the task to display in the UI.
     pub label: String,
     /// Executable command to spawn.
+    /// May be empty for composite tasks, which only run the tasks they depend on.
+    #[serde(default)]
     pub command: String,
     /// Arguments to the command.
     #[serde(default)]
@@ -49,6 +52,35 @@ pub struct TaskTemplate {
     /// Represents the tags which this template attaches to. Adding this removes this task from other UI.
     #[serde(default)]
     pub tags: Vec<String>,
+
+    /// Problem matchers that turn the output of the task into diagnostics, either the names of built-in
+    /// matchers such as `$rustc`, `$tsc`, `$gcc` and `$eslint-stylish`, or custom regex patterns.
+    #[serde(default, deserialize_with = "one_or_many")]
+    #[schemars(with = "OneOrMany<ProblemMatcher>")]
+    pub problem_matcher: Vec<ProblemMatcher>,
+
+    /// Labels of the tasks to run before this task. The task is not run if any of them fails.
+    #[serde(default, deserialize_with = "one_or_many")]
+    #[schemars(with = "OneOrMany<String>")]
+    pub depends_on: Vec<String>,
+    /// Whether the tasks in `depends_on` are run all at once (default) or one after another.
+    #[serde(default)]
+    pub depends_order: DependsOrder,
+    /// Makes the task a background task, such as a dev server that keeps running:
+    /// the tasks that depend on it are run once its output matches this regex, rather than once it finishes.
+    #[serde(default)]
+    pub ready_pattern: Option<String>,
+}
+
+/// The order to run the dependencies of a task in.
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DependsOrder {
+    /// Run all dependencies at once.
+    #[default]
+    Parallel,
+    /// Run the dependencies one after another, in the order they are listed.
+    Sequence,
 }
 
 /// What to do with the terminal pane and tab, after the command was started.
@@ -86,7 +118,9 @@ impl TaskTemplate {
     /// Every [`ResolvedTask`] gets a [`TaskId`], based on the `id_base` (to avoid collision with various task sources),
     /// and hashes of its template and [`TaskContext`], see [`ResolvedTask`] fields' documentation for more details.
     pub fn resolve_task(&self, id_base: &str, cx: &TaskContext) -> Option<ResolvedTask> {
-        if self.label.trim().is_empty() || self.command.trim().is_empty() {
+        if self.label.trim().is_empty()
+            || (self.command.trim().is_empty() && self.depends_on.is_empty())
+        {
             return None;
         }
 
@@ -156,6 +190,26 @@ impl TaskTemplate {
             &mut substituted_variables,
         )?;
 
+        let problem_matchers = self
+            .problem_matcher
+            .iter()
+            .map(|matcher| match matcher {
+                ProblemMatcher::Custom(definition) => {
+                    let mut definition = definition.clone();
+                    if let Some(FileLocation::Relative(_, base)) = &mut definition.file_location {
+                        *base = substitute_all_template_variables_in_str(
+                            base,
+                            &task_variables,
+                            &variable_names,
+                            &mut substituted_variables,
+                        )?;
+                    }
+                    Some(ProblemMatcher::Custom(definition))
+                }
+                ProblemMatcher::Named(_) => Some(matcher.clone()),
+            })
+            .collect::<Option<Vec<_>>>()?;
+
         let task_hash = to_hex_hash(&self)
             .context("hashing task template")
             .log_err()?;
@@ -174,6 +228,7 @@ impl TaskTemplate {
             id: id.clone(),
             substituted_variables,
             original_task: self.clone(),
+            task_context: cx.clone(),
             resolved_label: full_label.clone(),
             resolved: Some(SpawnInTerminal {
                 id,
@@ -194,9 +249,49 @@ impl TaskTemplate {
                 use_new_terminal: self.use_new_terminal,
                 allow_concurrent_runs: self.allow_concurrent_runs,
                 reveal: self.reveal,
+                problem_matchers,
+                depends_on: Vec::new(),
+                depends_order: self.depends_order,
+                ready_pattern: self.ready_pattern.clone(),
             }),
         })
     }
+
+    /// Resolves the tasks in `depends_on` with the [`TaskContext`] given, along with their own dependencies.
+    /// `find_task` looks up a task by its label, returning the id base of its source and its template.
+    pub(crate) fn resolve_dependencies(
+        &self,
+        cx: &TaskContext,
+        find_task: &dyn Fn(&str) -> Option<(String, TaskTemplate)>,
+        dependents: &mut Vec<String>,
+    ) -> anyhow::Result<Vec<SpawnInTerminal>> {
+        self.depends_on
+            .iter()
+            .map(|label| {
+                if let Some(position) = dependents.iter().position(|dependent| dependent == label) {
+                    let cycle = dependents[position..]
+                        .iter()
+                        .chain(Some(label))
+                        .map(|label| format!("`{label}`"))
+                        .collect::<Vec<_>>()
+                        .join(" -> ");
+                    bail!("Task dependencies form a cycle: {cycle}");
+                }
+                let (id_base, template) = find_task(label).with_context(|| {
+                    format!("Task `{}` depends on unknown task `{label}`", self.label)
+                })?;
+                let mut resolved = template
+                    .resolve_task(&id_base, cx)
+                    .and_then(|task| task.resolved)
+                    .with_context(|| format!("Failed to resolve task `{label}`"))?;
+
+                dependents.push(label.clone());
+                resolved.depends_on = template.resolve_dependencies(cx, find_task, dependents)?;
+                dependents.pop();
+                Ok(resolved)
+            })
+            .collect()
+    }
 }
 
 const MAX_DISPLAY_VARIABLE_LENGTH: usize = 15;
@@ -702,4 +797,120 @@ mod tests {
             .insert(VariableName::Symbol, "my-symbol".to_string());
         assert!(faulty_go_test.resolve_task("base", &context).is_some());
     }
+
+    #[test]
+    fn test_resolving_dependencies() {
+        let templates = [
+            TaskTemplate {
+                label: "e2e".into(),
+                command: "npm run e2e".into(),
+                depends_on: vec!["build".into(), "serve".into()],
+                depends_order: DependsOrder::Sequence,
+                ..TaskTemplate::default()
+            },
+            TaskTemplate {
+                label: "build".into(),
+                command: "npm run build".into(),
+                ..TaskTemplate::default()
+            },
+            TaskTemplate {
+                label: "serve".into(),
+                command: "npm run serve".into(),
+                ready_pattern: Some("Listening on".into()),
+                depends_on: vec!["build".into()],
+                ..TaskTemplate::default()
+            },
+        ];
+        let find_task = |label: &str| {
+            templates
+                .iter()
+                .find(|template| template.label == label)
+                .map(|template| (TEST_ID_BASE.to_string(), template.clone()))
+        };
+
+        let mut resolved = templates[0]
+            .resolve_task(TEST_ID_BASE, &TaskContext::default())
+            .unwrap();
+        resolved.resolve_dependencies(&find_task).unwrap();
+        let resolved = resolved.resolved.unwrap();
+        assert_eq!(resolved.depends_order, DependsOrder::Sequence);
+        let dependencies = resolved
+            .depends_on
+            .iter()
+            .map(|task| {
+                (
+                    task.label.as_str(),
+                    task.ready_pattern.as_deref(),
+                    task.depends_on
+                        .iter()
+                        .map(|task| task.label.as_str())
+                        .collect::<Vec<_>>(),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            dependencies,
+            [
+                ("build", None, vec![]),
+                ("serve", Some("Listening on"), vec!["build"]),
+            ]
+        );
+
+        let composite = TaskTemplate {
+            label: "all".into(),
+            depends_on: vec!["e2e".into(), "missing".into()],
+            ..TaskTemplate::default()
+        };
+        let mut resolved = composite
+            .resolve_task(TEST_ID_BASE, &TaskContext::default())
+            .expect("composite tasks resolve without a command");
+        assert_eq!(
+            resolved
+                .resolve_dependencies(&find_task)
+                .unwrap_err()
+                .to_string(),
+            "Task `all` depends on unknown task `missing`"
+        );
+    }
+
+    #[test]
+    fn test_dependency_cycles() {
+        let templates = [
+            TaskTemplate {
+                label: "a".into(),
+                command: "echo a".into(),
+                depends_on: vec!["b".into()],
+                ..TaskTemplate::default()
+            },
+            TaskTemplate {
+                label: "b".into(),
+                command: "echo b".into(),
+                depends_on: vec!["c".into()],
+                ..TaskTemplate::default()
+            },
+            TaskTemplate {
+                label: "c".into(),
+                command: "echo c".into(),
+                depends_on: vec!["a".into()],
+                ..TaskTemplate::default()
+            },
+        ];
+        let find_task = |label: &str| {
+            templates
+                .iter()
+                .find(|template| template.label == label)
+                .map(|template| (TEST_ID_BASE.to_string(), template.clone()))
+        };
+
+        let mut resolved = templates[1]
+            .resolve_task(TEST_ID_BASE, &TaskContext::default())
+            .unwrap();
+        assert_eq!(
+            resolved
+                .resolve_dependencies(&find_task)
+                .unwrap_err()
+                .to_string(),
+            "Task dependencies form a cycle: `b` -> `c` -> `a` -> `b`"
+        );
+    }
 }
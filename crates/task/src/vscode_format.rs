@@ -1,9 +1,12 @@
-use anyhow::bail;
+use anyhow::{bail, Context};
 use collections::HashMap;
 use serde::Deserialize;
 use util::ResultExt;
 
-use crate::{TaskTemplate, TaskTemplates, VariableName};
+use crate::{
+    problem_matcher::one_or_many, FileLocation, ProblemMatcher, TaskTemplate, TaskTemplates,
+    VariableName,
+};
 
 #[derive(Clone, Debug, Deserialize, PartialEq)]
 #[serde(rename_all = "camelCase")]
@@ -85,21 +88,19 @@ impl EnvVariableReplacer {
 
 impl VsCodeTaskDefinition {
     fn to_zed_format(self, replacer: &EnvVariableReplacer) -> anyhow::Result<TaskTemplate> {
-        if self.other_attributes.contains_key("dependsOn") {
-            bail!("Encountered unsupported `dependsOn` key during deserialization");
-        }
-        // `type` might not be set in e.g. tasks that use `dependsOn`; we still want to deserialize the whole object though (hence command is an Option),
-        // as that way we can provide more specific description of why deserialization failed.
-        // E.g. if the command is missing due to `dependsOn` presence, we can check other_attributes first before doing this (and provide nice error message)
-        // catch-all if on value.command presence.
-        let Some(command) = self.command else {
-            bail!("Missing `type` field in task");
+        let depends_on: Vec<String> = match self.other_attributes.get("dependsOn") {
+            Some(depends_on) => one_or_many(depends_on.clone())
+                .context("Unsupported `dependsOn` in task, only task labels are supported")?,
+            None => Vec::new(),
         };
-
-        let (command, args) = match command {
-            Command::Npm { script } => ("npm".to_owned(), vec!["run".to_string(), script]),
-            Command::Shell { command, args } => (command, args),
-            Command::Gulp { task } => ("gulp".to_owned(), vec![task]),
+        // `type` might not be set in tasks that use `dependsOn`, which then only run their dependencies;
+        // we still want to deserialize the whole object though (hence command is an Option).
+        let (command, args) = match self.command {
+            Some(Command::Npm { script }) => ("npm".to_owned(), vec!["run".to_string(), script]),
+            Some(Command::Shell { command, args }) => (command, args),
+            Some(Command::Gulp { task }) => ("gulp".to_owned(), vec![task]),
+            None if !depends_on.is_empty() => (String::new(), Vec::new()),
+            None => bail!("Missing `type` field in task"),
         };
         // Per VSC docs, only `command`, `args` and `options` support variable substitution.
         let command = replacer.replace(&command);
@@ -108,16 +109,77 @@ impl VsCodeTaskDefinition {
             label: self.label,
             command,
             args,
+            depends_on,
             ..Default::default()
         };
+        if let Some(depends_order) = self.other_attributes.get("dependsOrder") {
+            ret.depends_order = serde_json_lenient::from_value(depends_order.clone())
+                .context("Unsupported `dependsOrder` in task")?;
+        }
         if let Some(options) = self.options {
             ret.cwd = options.cwd.map(|cwd| replacer.replace(&cwd));
             ret.env = options.env;
         }
+        if let Some(problem_matcher) = self.other_attributes.get("problemMatcher") {
+            ret.problem_matcher = one_or_many(problem_matcher.clone())
+                .context("Unsupported `problemMatcher` in task")
+                .log_err()
+                .unwrap_or_default()
+                .into_iter()
+                .map(|matcher| match matcher {
+                    ProblemMatcher::Custom(mut definition) => {
+                        if let Some(FileLocation::Relative(_, base)) = &mut definition.file_location
+                        {
+                            *base = replacer.replace(base);
+                        }
+                        ProblemMatcher::Custom(definition)
+                    }
+                    ProblemMatcher::Named(name) => ProblemMatcher::Named(name),
+                })
+                .collect();
+        }
+        let is_background = self
+            .other_attributes
+            .get("isBackground")
+            .and_then(|is_background| is_background.as_bool())
+            .unwrap_or(false);
+        if is_background {
+            ret.ready_pattern = self
+                .other_attributes
+                .get("problemMatcher")
+                .and_then(background_ends_pattern);
+        }
         Ok(ret)
     }
 }
 
+/// Code considers a background task active once its problem matcher's `background.endsPattern` matches the output,
+/// which the `$tsc-watch` matcher defines for the end of a compilation.
+fn background_ends_pattern(problem_matcher: &serde_json_lenient::Value) -> Option<String> {
+    match problem_matcher {
+        serde_json_lenient::Value::Array(matchers) => {
+            matchers.iter().find_map(background_ends_pattern)
+        }
+        serde_json_lenient::Value::String(name) => {
+            (name == "$tsc-watch").then(|| TSC_WATCH_ENDS_PATTERN.to_string())
+        }
+        serde_json_lenient::Value::Object(matcher) => {
+            let ends_pattern = matcher
+                .get("background")
+                .and_then(|background| background.get("endsPattern"));
+            match ends_pattern {
+                Some(serde_json_lenient::Value::String(pattern)) => Some(pattern.clone()),
+                Some(pattern) => pattern.get("regexp")?.as_str().map(ToOwned::to_owned),
+                None => matcher.get("base").and_then(background_ends_pattern),
+            }
+        }
+        _ => None,
+    }
+}
+
+const TSC_WATCH_ENDS_PATTERN: &str =
+    r"(?:Compilation complete\.|Found \d+ errors?\.) Watching for file changes\.";
+
 /// [`VsCodeTaskFile`] is a superset of Code's task definition format.
 #[derive(Debug, Deserialize, PartialEq)]
 pub struct VsCodeTaskFile {
@@ -154,8 +216,9 @@ mod tests {
     use std::collections::HashMap;
 
     use crate::{
-        vscode_format::{Command, VsCodeTaskDefinition},
-        TaskTemplate, TaskTemplates, VsCodeTaskFile,
+        vscode_format::{Command, VsCodeTaskDefinition, TSC_WATCH_ENDS_PATTERN},
+        FileLocation, FileLocationKind, ProblemMatcher, ProblemMatcherDefinition, TaskTemplate,
+        TaskTemplates, VsCodeTaskFile,
     };
 
     use super::EnvVariableReplacer;
@@ -257,6 +320,7 @@ mod tests {
                 label: "gulp: tests".to_string(),
                 command: "npm".to_string(),
                 args: vec!["run".to_string(), "build:tests:notypecheck".to_string()],
+                problem_matcher: vec![ProblemMatcher::Named("$tsc".to_string())],
                 ..Default::default()
             },
             TaskTemplate {
@@ -268,18 +332,21 @@ mod tests {
                     "${ZED_WORKTREE_ROOT}/src".to_string(),
                     "--watch".to_string(),
                 ],
+                problem_matcher: vec![ProblemMatcher::Named("$tsc-watch".to_string())],
                 ..Default::default()
             },
             TaskTemplate {
                 label: "npm: build:compiler".to_string(),
                 command: "npm".to_string(),
                 args: vec!["run".to_string(), "build:compiler".to_string()],
+                problem_matcher: vec![ProblemMatcher::Named("$tsc".to_string())],
                 ..Default::default()
             },
             TaskTemplate {
                 label: "npm: build:tests".to_string(),
                 command: "npm".to_string(),
                 args: vec!["run".to_string(), "build:tests:notypecheck".to_string()],
+                problem_matcher: vec![ProblemMatcher::Named("$tsc".to_string())],
                 ..Default::default()
             },
         ];
@@ -355,33 +422,64 @@ mod tests {
             .iter()
             .zip(expected)
             .for_each(|(lhs, rhs)| compare_without_other_attributes(lhs.clone(), rhs));
+        let tsc_matcher = |base: &str| {
+            vec![ProblemMatcher::Custom(ProblemMatcherDefinition {
+                base: Some(base.to_string()),
+                file_location: Some(FileLocation::Relative(
+                    FileLocationKind::Relative,
+                    "${ZED_WORKTREE_ROOT}/editors/code/".to_string(),
+                )),
+                ..Default::default()
+            })]
+        };
         let expected = vec![
             TaskTemplate {
                 label: "Build Extension in Background".to_string(),
                 command: "npm".to_string(),
                 args: vec!["run".to_string(), "watch".to_string()],
+                problem_matcher: tsc_matcher("$tsc-watch"),
+                ready_pattern: Some(TSC_WATCH_ENDS_PATTERN.to_string()),
                 ..Default::default()
             },
             TaskTemplate {
                 label: "Build Extension".to_string(),
                 command: "npm".to_string(),
                 args: vec!["run".to_string(), "build".to_string()],
+                problem_matcher: tsc_matcher("$tsc"),
                 ..Default::default()
             },
             TaskTemplate {
                 label: "Build Server".to_string(),
                 command: "cargo build --package rust-analyzer".to_string(),
+                problem_matcher: vec![ProblemMatcher::Named("$rustc".to_string())],
                 ..Default::default()
             },
             TaskTemplate {
                 label: "Build Server (Release)".to_string(),
                 command: "cargo build --release --package rust-analyzer".to_string(),
+                problem_matcher: vec![ProblemMatcher::Named("$rustc".to_string())],
                 ..Default::default()
             },
             TaskTemplate {
                 label: "Pretest".to_string(),
                 command: "npm".to_string(),
                 args: vec!["run".to_string(), "pretest".to_string()],
+                problem_matcher: tsc_matcher("$tsc"),
+                ..Default::default()
+            },
+            TaskTemplate {
+                label: "Build Server and Extension".to_string(),
+                depends_on: vec!["Build Server".to_string(), "Build Extension".to_string()],
+                problem_matcher: vec![ProblemMatcher::Named("$rustc".to_string())],
+                ..Default::default()
+            },
+            TaskTemplate {
+                label: "Build Server (Release) and Extension".to_string(),
+                depends_on: vec![
+                    "Build Server (Release)".to_string(),
+                    "Build Extension".to_string(),
+                ],
+                problem_matcher: vec![ProblemMatcher::Named("$rustc".to_string())],
                 ..Default::default()
             },
         ];
@@ -0,0 +1,855 @@
+//! Inline images, printed with the iTerm2 inline image protocol or the Kitty graphics protocol.
+//!
+//! Alacritty doesn't support either protocol, so images are decoded from the pty output before
+//! it reaches the parser. Each image is replaced by a single cell carrying an OSC 8 hyperlink
+//! with a private URI, which anchors the image to the grid as it scrolls, followed by the
+//! cursor movement of the protocol, which reserves the lines the image covers.
+
+use std::{
+    collections::{BTreeMap, VecDeque},
+    env,
+    fs::File,
+    io::Read,
+    iter,
+    ops::RangeInclusive,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use alacritty_terminal::{
+    event::WindowSize,
+    grid::Dimensions,
+    index::{Column, Line, Point as AlacPoint},
+    term::cell::Hyperlink,
+    Term,
+};
+use anyhow::{anyhow, bail, Context, Result};
+use collections::HashMap;
+use flate2::read::ZlibDecoder;
+use gpui::ImageData;
+use image::{DynamicImage, RgbImage, RgbaImage};
+use parking_lot::Mutex;
+use util::ResultExt;
+
+use crate::pty_filter::{SequenceHandler, SequenceKind};
+
+const IMAGE_URI: &str = "zed-image:";
+
+const ITERM_PREFIX: &[u8] = b"1337;File=";
+const KITTY_PREFIX: &[u8] = b"G";
+
+/// Larger images are rejected rather than decoded.
+const MAX_IMAGE_DIMENSION: u32 = 10_000;
+/// The size of the largest image's pixels, which limits decompressed data and image files.
+const MAX_IMAGE_BYTES: usize = MAX_IMAGE_DIMENSION as usize * MAX_IMAGE_DIMENSION as usize * 4;
+/// The chunks of a Kitty transmission are base64 encoded, so they may exceed the image size
+/// by a third before the transmission is dropped.
+const MAX_KITTY_TRANSMISSION_BYTES: usize = MAX_IMAGE_BYTES / 3 * 4 + 4;
+/// Once the images of a terminal take more memory, the oldest ones are dropped.
+const MAX_STORED_IMAGE_BYTES: usize = 256 * 1024 * 1024;
+/// Images transmitted with the Kitty protocol can be placed again by their id,
+/// this many of them are kept around.
+const MAX_KITTY_IMAGES: usize = 64;
+
+/// The images of a terminal, by the id of the link that anchors them to the grid.
+pub(crate) type ImageStore = Arc<Mutex<BTreeMap<usize, TerminalImage>>>;
+
+/// A decoded image and the cells it covers.
+#[derive(Clone, Debug)]
+pub struct TerminalImage {
+    pub data: Arc<ImageData>,
+    pub columns: usize,
+    pub lines: usize,
+    /// Whether the image keeps its aspect ratio within its cells, rather than filling them.
+    pub preserve_aspect_ratio: bool,
+    kitty_id: Option<u32>,
+}
+
+/// An image whose top left corner is at the given point of the grid.
+#[derive(Clone, Debug)]
+pub struct ImagePlacement {
+    pub point: AlacPoint,
+    pub image: TerminalImage,
+}
+
+/// Whether the link anchors an image, rather than being printed by a program.
+pub fn is_image_link(link: &Hyperlink) -> bool {
+    link.uri().starts_with(IMAGE_URI)
+}
+
+/// The images that cover any of the given lines.
+pub(crate) fn images_in<T>(
+    term: &Term<T>,
+    store: &ImageStore,
+    lines: RangeInclusive<Line>,
+) -> Vec<ImagePlacement> {
+    let store = store.lock();
+    if store.is_empty() {
+        return Vec::new();
+    }
+
+    // Images anchored above the range may reach into it.
+    let mut line = Line(lines.start().0 - term.screen_lines() as i32).max(term.topmost_line());
+    let last_line = (*lines.end()).min(term.bottommost_line());
+    let mut placements = Vec::new();
+    while line <= last_line {
+        let row = &term.grid()[line];
+        for (column, cell) in row.into_iter().enumerate() {
+            let Some(id) = cell
+                .hyperlink()
+                .and_then(|link| link.uri().strip_prefix(IMAGE_URI)?.parse().ok())
+            else {
+                continue;
+            };
+            if let Some(image) = store.get(&id) {
+                if line.0 + image.lines as i32 > lines.start().0 {
+                    placements.push(ImagePlacement {
+                        point: AlacPoint::new(line, Column(column)),
+                        image: image.clone(),
+                    });
+                }
+            }
+        }
+        line = Line(line.0 + 1);
+    }
+    placements
+}
+
+/// The size of an image in one direction, as given by the iTerm2 protocol.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Dimension {
+    Auto,
+    Cells(u32),
+    Pixels(u32),
+    Percent(u32),
+}
+
+impl Dimension {
+    fn parse(value: &str) -> Self {
+        let parsed = if let Some(pixels) = value.strip_suffix("px") {
+            pixels.parse().map(Dimension::Pixels)
+        } else if let Some(percent) = value.strip_suffix('%') {
+            percent.parse().map(Dimension::Percent)
+        } else {
+            value.parse().map(Dimension::Cells)
+        };
+        parsed.unwrap_or(Dimension::Auto)
+    }
+
+    fn cells(cells: u32) -> Self {
+        if cells == 0 {
+            Dimension::Auto
+        } else {
+            Dimension::Cells(cells)
+        }
+    }
+}
+
+/// The control data of a Kitty graphics command, `ESC _ G <control> ; <payload> ESC \`.
+#[derive(Clone, Debug, PartialEq)]
+struct KittyCommand {
+    action: u8,
+    format: u32,
+    medium: u8,
+    compression: Option<u8>,
+    width: u32,
+    height: u32,
+    image_id: Option<u32>,
+    more_chunks: bool,
+    columns: u32,
+    lines: u32,
+    quiet: u32,
+    move_cursor: bool,
+    delete: u8,
+}
+
+impl KittyCommand {
+    fn parse(control: &str) -> Self {
+        let mut command = KittyCommand {
+            action: b't',
+            format: 32,
+            medium: b'd',
+            compression: None,
+            width: 0,
+            height: 0,
+            image_id: None,
+            more_chunks: false,
+            columns: 0,
+            lines: 0,
+            quiet: 0,
+            move_cursor: true,
+            delete: b'a',
+        };
+        for pair in control.split(',') {
+            let Some((key, value)) = pair.split_once('=') else {
+                continue;
+            };
+            let number = value.parse::<u32>().ok();
+            let char = value.bytes().next();
+            match key {
+                "a" => command.action = char.unwrap_or(b't'),
+                "f" => command.format = number.unwrap_or(32),
+                "t" => command.medium = char.unwrap_or(b'd'),
+                "o" => command.compression = char,
+                "s" => command.width = number.unwrap_or(0),
+                "v" => command.height = number.unwrap_or(0),
+                "i" => command.image_id = number,
+                "m" => command.more_chunks = value == "1",
+                "c" => command.columns = number.unwrap_or(0),
+                "r" => command.lines = number.unwrap_or(0),
+                "q" => command.quiet = number.unwrap_or(0),
+                "C" => command.move_cursor = value != "1",
+                "d" => command.delete = char.unwrap_or(b'a'),
+                _ => {}
+            }
+        }
+        command
+    }
+}
+
+/// A Kitty image transmitted in several chunks.
+struct KittyTransmission {
+    command: KittyCommand,
+    payload: Vec<u8>,
+    /// Whether the payload grew too large, in which case the remaining chunks are discarded.
+    too_large: bool,
+}
+
+/// Decodes the images in the pty output into an [`ImageStore`], replacing each one with the
+/// cell that anchors it to the grid.
+pub(crate) struct ImageDecoder {
+    store: ImageStore,
+    window_size: WindowSize,
+    next_image_id: usize,
+    kitty_images: HashMap<u32, Arc<ImageData>>,
+    kitty_image_order: VecDeque<u32>,
+    kitty_transmission: Option<KittyTransmission>,
+    responses: Vec<u8>,
+}
+
+impl SequenceHandler for ImageDecoder {
+    fn may_handle(&self, kind: SequenceKind, params: &[u8]) -> bool {
+        let prefix = match kind {
+            SequenceKind::Osc => ITERM_PREFIX,
+            SequenceKind::Apc => KITTY_PREFIX,
+        };
+        let len = params.len().min(prefix.len());
+        params[..len] == prefix[..len]
+    }
+
+    fn handle(&mut self, kind: SequenceKind, params: &[u8], output: &mut Vec<u8>) -> bool {
+        match kind {
+            SequenceKind::Osc => {
+                let Some(params) = params.strip_prefix(ITERM_PREFIX) else {
+                    return false;
+                };
+                self.handle_iterm_image(params, output).log_err();
+            }
+            SequenceKind::Apc => {
+                let Some(params) = params.strip_prefix(KITTY_PREFIX) else {
+                    return false;
+                };
+                self.handle_kitty_command(params, output);
+            }
+        }
+        true
+    }
+}
+
+impl ImageDecoder {
+    pub fn new(store: ImageStore, window_size: WindowSize) -> Self {
+        Self {
+            store,
+            window_size,
+            next_image_id: 0,
+            kitty_images: HashMap::default(),
+            kitty_image_order: VecDeque::new(),
+            kitty_transmission: None,
+            responses: Vec::new(),
+        }
+    }
+
+    pub fn set_window_size(&mut self, window_size: WindowSize) {
+        self.window_size = window_size;
+    }
+
+    /// The responses to Kitty graphics commands, which must be written back to the pty.
+    pub fn take_responses(&mut self) -> Vec<u8> {
+        std::mem::take(&mut self.responses)
+    }
+
+    /// Handles `ESC ] 1337 ; File = <args> : <base64 data> BEL`.
+    fn handle_iterm_image(&mut self, params: &[u8], output: &mut Vec<u8>) -> Result<()> {
+        let params = std::str::from_utf8(params)?;
+        let (args, data) = params.split_once(':').context("missing image data")?;
+        let mut width = Dimension::Auto;
+        let mut height = Dimension::Auto;
+        let mut preserve_aspect_ratio = true;
+        let mut inline = false;
+        for arg in args.split(';') {
+            match arg.split_once('=') {
+                Some(("width", value)) => width = Dimension::parse(value),
+                Some(("height", value)) => height = Dimension::parse(value),
+                Some(("preserveAspectRatio", value)) => preserve_aspect_ratio = value != "0",
+                Some(("inline", value)) => inline = value == "1",
+                _ => {}
+            }
+        }
+        // Files that aren't inline are downloads, which aren't supported.
+        if !inline {
+            return Ok(());
+        }
+
+        let image = image::load_from_memory(&base64::decode(data)?)?.into_rgba8();
+        let (columns, lines) = self.cell_area(
+            image.width(),
+            image.height(),
+            width,
+            height,
+            preserve_aspect_ratio,
+        );
+        let data = image_data(image)?;
+        self.place(data, columns, lines, preserve_aspect_ratio, None, output);
+
+        // The cursor moves to the start of the line below the image.
+        output.push(b'\r');
+        output.extend(iter::repeat(b'\n').take(lines));
+        Ok(())
+    }
+
+    /// Handles `ESC _ G <control> ; <payload> ESC \`.
+    fn handle_kitty_command(&mut self, params: &[u8], output: &mut Vec<u8>) {
+        let (control, payload) = match params.iter().position(|&byte| byte == b';') {
+            Some(ix) => (&params[..ix], &params[ix + 1..]),
+            None => (params, &[][..]),
+        };
+        let command = KittyCommand::parse(&String::from_utf8_lossy(control));
+
+        // Only the first chunk of a transmission has the full control data.
+        let (command, payload) = if let Some(mut transmission) = self.kitty_transmission.take() {
+            if transmission.payload.len() + payload.len() > MAX_KITTY_TRANSMISSION_BYTES {
+                transmission.too_large = true;
+                transmission.payload = Vec::new();
+            }
+            if !transmission.too_large {
+                transmission.payload.extend_from_slice(payload);
+            }
+            if command.more_chunks {
+                self.kitty_transmission = Some(transmission);
+                return;
+            }
+            if transmission.too_large {
+                self.respond(
+                    &transmission.command,
+                    Some("EFBIG:image data is too large".to_string()),
+                );
+                return;
+            }
+            (transmission.command, transmission.payload)
+        } else if command.more_chunks && matches!(command.action, b't' | b'T' | b'q') {
+            self.kitty_transmission = Some(KittyTransmission {
+                command,
+                payload: payload.to_vec(),
+                too_large: false,
+            });
+            return;
+        } else {
+            (command, payload.to_vec())
+        };
+
+        match command.action {
+            b't' | b'T' | b'q' => match decode_kitty_image(&command, &payload) {
+                Ok(image) => {
+                    if command.action != b'q' {
+                        if let Some(image_id) = command.image_id {
+                            self.store_kitty_image(image_id, image.clone());
+                        }
+                    }
+                    if command.action == b'T' {
+                        self.place_kitty_image(&command, image, output);
+                    }
+                    self.respond(&command, None);
+                }
+                Err(error) => self.respond(&command, Some(format!("EINVAL:{error}"))),
+            },
+            b'p' => {
+                let image = command
+                    .image_id
+                    .and_then(|image_id| self.kitty_images.get(&image_id).cloned());
+                match image {
+                    Some(image) => {
+                        self.place_kitty_image(&command, image, output);
+                        self.respond(&command, None);
+                    }
+                    None => self.respond(&command, Some("ENOENT:image not found".to_string())),
+                }
+            }
+            b'd' => self.delete_kitty_images(&command),
+            _ => {}
+        }
+    }
+
+    fn place_kitty_image(
+        &mut self,
+        command: &KittyCommand,
+        image: Arc<ImageData>,
+        output: &mut Vec<u8>,
+    ) {
+        let size = image.size();
+        let (columns, lines) = self.cell_area(
+            u32::from(size.width),
+            u32::from(size.height),
+            Dimension::cells(command.columns),
+            Dimension::cells(command.lines),
+            true,
+        );
+        self.place(image, columns, lines, true, command.image_id, output);
+
+        // The cursor moves to the cell after the image, on its last line.
+        if command.move_cursor {
+            output.extend(iter::repeat(b'\n').take(lines - 1));
+            if columns > 1 {
+                output.extend_from_slice(format!("\x1b[{}C", columns - 1).as_bytes());
+            }
+        } else {
+            output.extend_from_slice(b"\x1b[D");
+        }
+    }
+
+    fn store_kitty_image(&mut self, image_id: u32, image: Arc<ImageData>) {
+        self.kitty_image_order.retain(|id| *id != image_id);
+        self.kitty_image_order.push_back(image_id);
+        self.kitty_images.insert(image_id, image);
+        while self.kitty_image_order.len() > MAX_KITTY_IMAGES {
+            if let Some(oldest) = self.kitty_image_order.pop_front() {
+                self.kitty_images.remove(&oldest);
+            }
+        }
+    }
+
+    fn delete_kitty_images(&mut self, command: &KittyCommand) {
+        // Uppercase deletions also free the image data.
+        let free = command.delete.is_ascii_uppercase();
+        match command.delete.to_ascii_lowercase() {
+            b'a' => {
+                self.store
+                    .lock()
+                    .retain(|_, image| image.kitty_id.is_none());
+                if free {
+                    self.kitty_images.clear();
+                    self.kitty_image_order.clear();
+                }
+            }
+            b'i' => {
+                let Some(image_id) = command.image_id else {
+                    return;
+                };
+                self.store
+                    .lock()
+                    .retain(|_, image| image.kitty_id != Some(image_id));
+                if free {
+                    self.kitty_images.remove(&image_id);
+                    self.kitty_image_order.retain(|id| *id != image_id);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Queues the response to a command, which is only sent for commands with an image id.
+    fn respond(&mut self, command: &KittyCommand, error: Option<String>) {
+        let Some(image_id) = command.image_id else {
+            return;
+        };
+        let message = match error {
+            None if command.quiet == 0 => "OK".to_string(),
+            Some(error) if command.quiet < 2 => error,
+            _ => return,
+        };
+        self.responses
+            .extend_from_slice(format!("\x1b_Gi={image_id};{message}\x1b\\").as_bytes());
+    }
+
+    /// The number of columns and lines an image covers, scaled down to fit on the screen.
+    fn cell_area(
+        &self,
+        image_width: u32,
+        image_height: u32,
+        width: Dimension,
+        height: Dimension,
+        preserve_aspect_ratio: bool,
+    ) -> (usize, usize) {
+        let cell_width = self.window_size.cell_width.max(1) as f32;
+        let cell_height = self.window_size.cell_height.max(1) as f32;
+        let max_width = self.window_size.num_cols.max(1) as f32 * cell_width;
+        let max_height = self.window_size.num_lines.max(1) as f32 * cell_height;
+        let resolve = |dimension, cell_size: f32, max_size: f32| match dimension {
+            Dimension::Auto => None,
+            Dimension::Cells(cells) => Some(cells as f32 * cell_size),
+            Dimension::Pixels(pixels) => Some(pixels as f32),
+            Dimension::Percent(percent) => Some(max_size * percent as f32 / 100.),
+        };
+
+        let (image_width, image_height) = (image_width.max(1) as f32, image_height.max(1) as f32);
+        let (mut width, mut height) = match (
+            resolve(width, cell_width, max_width),
+            resolve(height, cell_height, max_height),
+        ) {
+            (None, None) => (image_width, image_height),
+            (Some(width), None) if preserve_aspect_ratio => {
+                (width, image_height * width / image_width)
+            }
+            (None, Some(height)) if preserve_aspect_ratio => {
+                (image_width * height / image_height, height)
+            }
+            (width, height) => (width.unwrap_or(image_width), height.unwrap_or(image_height)),
+        };
+        if preserve_aspect_ratio {
+            let scale = (max_width / width).min(max_height / height).min(1.);
+            width *= scale;
+            height *= scale;
+        } else {
+            width = width.min(max_width);
+            height = height.min(max_height);
+        }
+
+        (
+            (width / cell_width).ceil().max(1.) as usize,
+            (height / cell_height).ceil().max(1.) as usize,
+        )
+    }
+
+    /// Stores an image and writes the cell that anchors it to the grid.
+    fn place(
+        &mut self,
+        data: Arc<ImageData>,
+        columns: usize,
+        lines: usize,
+        preserve_aspect_ratio: bool,
+        kitty_id: Option<u32>,
+        output: &mut Vec<u8>,
+    ) {
+        let id = self.next_image_id;
+        self.next_image_id += 1;
+
+        let mut store = self.store.lock();
+        store.insert(
+            id,
+            TerminalImage {
+                data,
+                columns,
+                lines,
+                preserve_aspect_ratio,
+                kitty_id,
+            },
+        );
+        while store.len() > 1
+            && store
+                .values()
+                .map(|image| image.data.as_bytes().len())
+                .sum::<usize>()
+                > MAX_STORED_IMAGE_BYTES
+        {
+            store.pop_first();
+        }
+
+        output.extend_from_slice(
+            format!("\x1b]8;id=zed-image-{id};{IMAGE_URI}{id}\x1b\\ \x1b]8;;\x1b\\").as_bytes(),
+        );
+    }
+}
+
+fn decode_kitty_image(command: &KittyCommand, payload: &[u8]) -> Result<Arc<ImageData>> {
+    let data = base64::decode(payload)?;
+    let data = match command.medium {
+        b'd' => data,
+        b'f' | b't' => read_kitty_image_file(
+            &PathBuf::from(String::from_utf8(data)?),
+            command.medium == b't',
+        )?,
+        medium => bail!("unsupported transmission medium {:?}", medium as char),
+    };
+    let data = match command.compression {
+        None => data,
+        Some(b'z') => {
+            let mut decompressed = Vec::new();
+            ZlibDecoder::new(data.as_slice())
+                .take(MAX_IMAGE_BYTES as u64 + 1)
+                .read_to_end(&mut decompressed)?;
+            if decompressed.len() > MAX_IMAGE_BYTES {
+                bail!("decompressed image data is too large");
+            }
+            decompressed
+        }
+        Some(compression) => bail!("unsupported compression {:?}", compression as char),
+    };
+
+    let mismatch = || anyhow!("image data doesn't match its size");
+    let image = match command.format {
+        24 => DynamicImage::ImageRgb8(
+            RgbImage::from_raw(command.width, command.height, data).ok_or_else(mismatch)?,
+        )
+        .into_rgba8(),
+        32 => RgbaImage::from_raw(command.width, command.height, data).ok_or_else(mismatch)?,
+        100 => image::load_from_memory_with_format(&data, image::ImageFormat::Png)?.into_rgba8(),
+        format => bail!("unsupported format {format}"),
+    };
+    image_data(image)
+}
+
+/// Reads an image file named by a program, following the rules of Kitty: only regular files
+/// outside of the kernel's virtual file systems are read, and only temporary files whose name
+/// shows that they were written for the graphics protocol are deleted.
+fn read_kitty_image_file(path: &Path, is_temporary: bool) -> Result<Vec<u8>> {
+    let path = path
+        .canonicalize()
+        .with_context(|| format!("reading image file {path:?}"))?;
+    let is_virtual_file = ["/proc", "/sys", "/dev"]
+        .iter()
+        .any(|virtual_dir| path.starts_with(virtual_dir));
+    if is_virtual_file && !path.starts_with("/dev/shm") {
+        bail!("image file {path:?} is not a regular file");
+    }
+    let metadata =
+        std::fs::metadata(&path).with_context(|| format!("reading image file {path:?}"))?;
+    if !metadata.is_file() {
+        bail!("image file {path:?} is not a regular file");
+    }
+    if metadata.len() > MAX_IMAGE_BYTES as u64 {
+        bail!("image file {path:?} is too large");
+    }
+
+    let mut contents = Vec::new();
+    File::open(&path)
+        .and_then(|file| {
+            file.take(MAX_IMAGE_BYTES as u64 + 1)
+                .read_to_end(&mut contents)
+        })
+        .with_context(|| format!("reading image file {path:?}"))?;
+    if contents.len() > MAX_IMAGE_BYTES {
+        bail!("image file {path:?} is too large");
+    }
+
+    if is_temporary && is_kitty_temporary_file(&path) {
+        std::fs::remove_file(&path).log_err();
+    }
+    Ok(contents)
+}
+
+fn is_kitty_temporary_file(path: &Path) -> bool {
+    let is_named_for_protocol = path
+        .file_name()
+        .is_some_and(|name| name.to_string_lossy().contains("tty-graphics-protocol"));
+    is_named_for_protocol
+        && [env::temp_dir(), "/tmp".into(), "/dev/shm".into()]
+            .iter()
+            .filter_map(|temp_dir| temp_dir.canonicalize().ok())
+            .any(|temp_dir| path.starts_with(temp_dir))
+}
+
+fn image_data(mut image: RgbaImage) -> Result<Arc<ImageData>> {
+    let (width, height) = image.dimensions();
+    if width == 0 || height == 0 || width > MAX_IMAGE_DIMENSION || height > MAX_IMAGE_DIMENSION {
+        bail!("unsupported image size {width}x{height}");
+    }
+    // Convert from RGBA to BGRA.
+    for pixel in image.chunks_exact_mut(4) {
+        pixel.swap(0, 2);
+    }
+    Ok(Arc::new(ImageData::new(image)))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+    use crate::pty_filter::SequenceScanner;
+
+    fn decoder() -> SequenceScanner<ImageDecoder> {
+        SequenceScanner::new(ImageDecoder::new(
+            ImageStore::default(),
+            WindowSize {
+                num_lines: 24,
+                num_cols: 80,
+                cell_width: 10,
+                cell_height: 20,
+            },
+        ))
+    }
+
+    fn scan(decoder: &mut SequenceScanner<ImageDecoder>, input: &str) -> String {
+        let mut output = Vec::new();
+        decoder.scan(input.as_bytes(), &mut output);
+        String::from_utf8(output).unwrap()
+    }
+
+    fn stored_images(decoder: &SequenceScanner<ImageDecoder>) -> Vec<(usize, usize, usize)> {
+        decoder
+            .handler
+            .store
+            .lock()
+            .iter()
+            .map(|(id, image)| (*id, image.columns, image.lines))
+            .collect()
+    }
+
+    fn png(width: u32, height: u32) -> String {
+        let mut bytes = Vec::new();
+        DynamicImage::ImageRgba8(RgbaImage::new(width, height))
+            .write_to(&mut Cursor::new(&mut bytes), image::ImageFormat::Png)
+            .unwrap();
+        base64::encode(bytes)
+    }
+
+    #[test]
+    fn test_iterm_images() {
+        let mut decoder = decoder();
+        let output = scan(
+            &mut decoder,
+            &format!("a\x1b]1337;File=inline=1:{}\x07b", png(25, 30)),
+        );
+        assert_eq!(
+            output,
+            "a\x1b]8;id=zed-image-0;zed-image:0\x1b\\ \x1b]8;;\x1b\\\r\n\nb"
+        );
+        assert_eq!(stored_images(&decoder), vec![(0, 3, 2)]);
+
+        // Sizes in cells, keeping the aspect ratio, and in percent of the screen.
+        scan(
+            &mut decoder,
+            &format!("\x1b]1337;File=inline=1;width=5:{}\x07", png(20, 40)),
+        );
+        scan(
+            &mut decoder,
+            &format!(
+                "\x1b]1337;File=inline=1;width=50%;height=2;preserveAspectRatio=0:{}\x1b\\",
+                png(20, 40)
+            ),
+        );
+        // Images that don't fit on the screen are scaled down.
+        scan(
+            &mut decoder,
+            &format!("\x1b]1337;File=inline=1:{}\x07", png(1600, 960)),
+        );
+        assert_eq!(
+            stored_images(&decoder)[1..],
+            [(1, 5, 5), (2, 40, 2), (3, 80, 24)]
+        );
+
+        // Downloads aren't displayed.
+        let output = scan(
+            &mut decoder,
+            &format!("\x1b]1337;File=name=eA==:{}\x07", png(1, 1)),
+        );
+        assert_eq!(output, "");
+        assert_eq!(stored_images(&decoder).len(), 4);
+    }
+
+    #[test]
+    fn test_kitty_images() {
+        let mut decoder = decoder();
+        let pixels = base64::encode([255u8; 2 * 2 * 4]);
+        let output = scan(
+            &mut decoder,
+            &format!("\x1b_Ga=T,f=32,s=2,v=2,c=3,r=2,i=7;{pixels}\x1b\\x"),
+        );
+        assert_eq!(
+            output,
+            "\x1b]8;id=zed-image-0;zed-image:0\x1b\\ \x1b]8;;\x1b\\\n\x1b[2Cx"
+        );
+        assert_eq!(stored_images(&decoder), vec![(0, 3, 2)]);
+        assert_eq!(decoder.handler.take_responses(), b"\x1b_Gi=7;OK\x1b\\");
+
+        // Chunked transmission, placed later without moving the cursor.
+        let (first, second) = pixels.split_at(8);
+        scan(
+            &mut decoder,
+            &format!("\x1b_Ga=t,f=32,s=2,v=2,i=8,q=1,m=1;{first}\x1b\\\x1b_Gm=0;{second}\x1b\\"),
+        );
+        let output = scan(&mut decoder, "\x1b_Ga=p,i=8,C=1\x1b\\");
+        assert_eq!(
+            output,
+            "\x1b]8;id=zed-image-1;zed-image:1\x1b\\ \x1b]8;;\x1b\\\x1b[D"
+        );
+        assert_eq!(stored_images(&decoder), vec![(0, 3, 2), (1, 1, 1)]);
+
+        // Queries and errors.
+        scan(
+            &mut decoder,
+            &format!("\x1b_Ga=q,s=2,v=2,i=9;{pixels}\x1b\\"),
+        );
+        scan(
+            &mut decoder,
+            &format!("\x1b_Ga=q,s=3,v=2,i=10;{pixels}\x1b\\"),
+        );
+        scan(&mut decoder, "\x1b_Ga=p,i=11\x1b\\");
+        assert_eq!(
+            String::from_utf8(decoder.handler.take_responses()).unwrap(),
+            "\x1b_Gi=8;OK\x1b\\\
+             \x1b_Gi=9;OK\x1b\\\
+             \x1b_Gi=10;EINVAL:image data doesn't match its size\x1b\\\
+             \x1b_Gi=11;ENOENT:image not found\x1b\\"
+        );
+        assert_eq!(stored_images(&decoder).len(), 2);
+
+        scan(&mut decoder, "\x1b_Ga=d,d=i,i=7\x1b\\");
+        assert_eq!(stored_images(&decoder), vec![(1, 1, 1)]);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_kitty_image_files() {
+        let mut decoder = decoder();
+        let pixels = [255u8; 4];
+        let temp_dir = env::temp_dir().canonicalize().unwrap();
+        let protocol_file = temp_dir.join(format!(
+            "zed-tty-graphics-protocol-{}.rgba",
+            std::process::id()
+        ));
+        let other_file = temp_dir.join(format!("zed-image-{}.rgba", std::process::id()));
+        std::fs::write(&protocol_file, pixels).unwrap();
+        std::fs::write(&other_file, pixels).unwrap();
+
+        // Only temporary files written for the graphics protocol are deleted once read.
+        for (image_id, path) in [(1, &protocol_file), (2, &other_file)] {
+            let path = base64::encode(path.to_string_lossy().as_bytes());
+            scan(
+                &mut decoder,
+                &format!("\x1b_Ga=t,t=t,f=32,s=1,v=1,i={image_id};{path}\x1b\\"),
+            );
+        }
+        assert!(!protocol_file.exists());
+        assert!(other_file.exists());
+        std::fs::remove_file(&other_file).unwrap();
+
+        // Files that aren't regular files are never read.
+        let path = base64::encode("/dev/zero");
+        scan(
+            &mut decoder,
+            &format!("\x1b_Ga=t,t=f,f=32,s=1,v=1,i=3;{path}\x1b\\"),
+        );
+        assert_eq!(
+            String::from_utf8(decoder.handler.take_responses()).unwrap(),
+            "\x1b_Gi=1;OK\x1b\\\
+             \x1b_Gi=2;OK\x1b\\\
+             \x1b_Gi=3;EINVAL:image file \"/dev/zero\" is not a regular file\x1b\\"
+        );
+    }
+
+    #[test]
+    fn test_other_sequences_pass_through() {
+        let mut decoder = decoder();
+        for input in [
+            "\x1b]0;title\x07",
+            "\x1b]1337;SetMark\x07",
+            "\x1b_Xdata\x1b\\",
+            "\x1b]1337;File=inline=1",
+        ] {
+            let mut output = Vec::new();
+            decoder.scan(input.as_bytes(), &mut output);
+            decoder.flush(&mut output);
+            assert_eq!(String::from_utf8(output).unwrap(), input);
+        }
+    }
+}
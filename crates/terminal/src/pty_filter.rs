@@ -0,0 +1,333 @@
+//! Filtering of the pty output before it reaches alacritty's parser, which rewrites the escape
+//! sequences that alacritty ignores into ones it understands.
+
+use std::{
+    io::{self, Read, Write},
+    mem,
+    sync::Arc,
+};
+
+use alacritty_terminal::{
+    event::{OnResize, WindowSize},
+    tty::{ChildEvent, EventedPty, EventedReadWrite, Pty},
+};
+use polling::{Event, PollMode, Poller};
+use util::ResultExt;
+
+use crate::{images::ImageDecoder, shell_integration::MarkScanner};
+
+const READ_CHUNK_SIZE: usize = 4096;
+/// Longer sequences are passed through rather than buffered, so that an unterminated
+/// sequence can't swallow the rest of the output.
+const MAX_SEQUENCE_LENGTH: usize = 16 * 1024 * 1024;
+
+const ESC: u8 = 0x1b;
+const BEL: u8 = 0x07;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum SequenceKind {
+    /// An operating system command, `ESC ]`, terminated by BEL or ST.
+    Osc,
+    /// An application program command, `ESC _`, terminated by ST.
+    Apc,
+}
+
+pub(crate) trait SequenceHandler {
+    /// Whether a sequence whose parameters start with `params` may be handled. This is called
+    /// with the parameters received so far, sequences that can't be handled are passed through
+    /// without being buffered.
+    fn may_handle(&self, kind: SequenceKind, params: &[u8]) -> bool;
+
+    /// Handles a complete sequence, writing its replacement to `output`.
+    /// Returns `false` to pass the sequence through unchanged.
+    fn handle(&mut self, kind: SequenceKind, params: &[u8], output: &mut Vec<u8>) -> bool;
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+enum ScanState {
+    #[default]
+    Ground,
+    Escape,
+    Sequence(SequenceKind),
+    SequenceEscape(SequenceKind),
+}
+
+/// Finds the OSC and APC sequences in a byte stream that its handler wants to replace.
+/// All other bytes are passed through unchanged.
+#[derive(Debug, Default)]
+pub(crate) struct SequenceScanner<H> {
+    pub handler: H,
+    state: ScanState,
+    sequence: Vec<u8>,
+}
+
+impl<H: SequenceHandler> SequenceScanner<H> {
+    pub fn new(handler: H) -> Self {
+        Self {
+            handler,
+            state: ScanState::Ground,
+            sequence: Vec::new(),
+        }
+    }
+
+    pub fn scan(&mut self, input: &[u8], output: &mut Vec<u8>) {
+        for &byte in input {
+            self.advance(byte, output);
+        }
+    }
+
+    /// Passes through a sequence that was cut off by the end of the stream.
+    pub fn flush(&mut self, output: &mut Vec<u8>) {
+        self.pass_through(output);
+    }
+
+    fn advance(&mut self, byte: u8, output: &mut Vec<u8>) {
+        match self.state {
+            ScanState::Ground => {
+                if byte == ESC {
+                    self.sequence.push(byte);
+                    self.state = ScanState::Escape;
+                } else {
+                    output.push(byte);
+                }
+            }
+            ScanState::Escape => {
+                let kind = match byte {
+                    b']' => Some(SequenceKind::Osc),
+                    b'_' => Some(SequenceKind::Apc),
+                    _ => None,
+                };
+                match kind {
+                    Some(kind) if self.handler.may_handle(kind, &[]) => {
+                        self.sequence.push(byte);
+                        self.state = ScanState::Sequence(kind);
+                    }
+                    _ => {
+                        self.pass_through(output);
+                        self.advance(byte, output);
+                    }
+                }
+            }
+            ScanState::Sequence(kind) => match byte {
+                BEL if kind == SequenceKind::Osc => self.finish(kind, &[BEL], output),
+                ESC => {
+                    self.sequence.push(byte);
+                    self.state = ScanState::SequenceEscape(kind);
+                }
+                _ => {
+                    self.sequence.push(byte);
+                    if self.sequence.len() > MAX_SEQUENCE_LENGTH
+                        || !self.handler.may_handle(kind, &self.sequence[2..])
+                    {
+                        self.pass_through(output);
+                    }
+                }
+            },
+            ScanState::SequenceEscape(kind) => {
+                self.sequence.pop();
+                if byte == b'\\' {
+                    self.finish(kind, &[ESC, b'\\'], output);
+                } else {
+                    // The sequence was interrupted by another escape sequence.
+                    self.pass_through(output);
+                    self.sequence.push(ESC);
+                    self.state = ScanState::Escape;
+                    self.advance(byte, output);
+                }
+            }
+        }
+    }
+
+    fn pass_through(&mut self, output: &mut Vec<u8>) {
+        output.append(&mut self.sequence);
+        self.state = ScanState::Ground;
+    }
+
+    fn finish(&mut self, kind: SequenceKind, terminator: &[u8], output: &mut Vec<u8>) {
+        let sequence = mem::take(&mut self.sequence);
+        self.state = ScanState::Ground;
+        if !self.handler.handle(kind, &sequence[2..], output) {
+            output.extend_from_slice(&sequence);
+            output.extend_from_slice(terminator);
+        }
+    }
+}
+
+/// A pty whose output has its inline images decoded, see [`ImageDecoder`], and its
+/// shell integration marks rewritten into hyperlinks, see [`MarkScanner`].
+pub(crate) struct FilteredPty {
+    pty: Pty,
+    images: SequenceScanner<ImageDecoder>,
+    marks: Option<SequenceScanner<MarkScanner>>,
+    scanned: Vec<u8>,
+    output: Vec<u8>,
+}
+
+impl FilteredPty {
+    pub fn new(pty: Pty, images: ImageDecoder, shell_integration: bool) -> Self {
+        Self {
+            pty,
+            images: SequenceScanner::new(images),
+            marks: shell_integration.then(|| SequenceScanner::new(MarkScanner::default())),
+            scanned: Vec::new(),
+            output: Vec::new(),
+        }
+    }
+
+    fn filter_marks(&mut self) {
+        match &mut self.marks {
+            Some(marks) => marks.scan(&self.scanned, &mut self.output),
+            None => self.output.extend_from_slice(&self.scanned),
+        }
+        self.scanned.clear();
+    }
+
+    /// Answers the queries of programs that use the Kitty graphics protocol.
+    fn write_responses(&mut self) {
+        let responses = self.images.handler.take_responses();
+        if !responses.is_empty() {
+            self.pty.writer().write_all(&responses).log_err();
+        }
+    }
+}
+
+impl Read for FilteredPty {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let mut chunk = [0; READ_CHUNK_SIZE];
+        // A read that only contained the start of a sequence produces no output,
+        // which must not be reported as the end of the stream.
+        while self.output.is_empty() {
+            let read = self.pty.reader().read(&mut chunk)?;
+            if read == 0 {
+                self.images.flush(&mut self.scanned);
+                self.filter_marks();
+                if let Some(marks) = &mut self.marks {
+                    marks.flush(&mut self.output);
+                }
+                break;
+            }
+            self.images.scan(&chunk[..read], &mut self.scanned);
+            self.write_responses();
+            self.filter_marks();
+        }
+
+        let len = buf.len().min(self.output.len());
+        buf[..len].copy_from_slice(&self.output[..len]);
+        self.output.drain(..len);
+        Ok(len)
+    }
+}
+
+impl EventedReadWrite for FilteredPty {
+    type Reader = Self;
+    type Writer = <Pty as EventedReadWrite>::Writer;
+
+    unsafe fn register(
+        &mut self,
+        poll: &Arc<Poller>,
+        interest: Event,
+        mode: PollMode,
+    ) -> io::Result<()> {
+        self.pty.register(poll, interest, mode)
+    }
+
+    fn reregister(
+        &mut self,
+        poll: &Arc<Poller>,
+        interest: Event,
+        mode: PollMode,
+    ) -> io::Result<()> {
+        self.pty.reregister(poll, interest, mode)
+    }
+
+    fn deregister(&mut self, poll: &Arc<Poller>) -> io::Result<()> {
+        self.pty.deregister(poll)
+    }
+
+    fn reader(&mut self) -> &mut Self::Reader {
+        self
+    }
+
+    fn writer(&mut self) -> &mut Self::Writer {
+        self.pty.writer()
+    }
+}
+
+impl EventedPty for FilteredPty {
+    fn next_child_event(&mut self) -> Option<ChildEvent> {
+        self.pty.next_child_event()
+    }
+}
+
+impl OnResize for FilteredPty {
+    fn on_resize(&mut self, window_size: WindowSize) {
+        self.images.handler.set_window_size(window_size);
+        self.pty.on_resize(window_size)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Replaces OSC 7 sequences with their parameters in brackets.
+    #[derive(Default)]
+    struct TestHandler;
+
+    impl SequenceHandler for TestHandler {
+        fn may_handle(&self, kind: SequenceKind, params: &[u8]) -> bool {
+            kind == SequenceKind::Osc && b"7;".starts_with(&params[..params.len().min(2)])
+        }
+
+        fn handle(&mut self, _: SequenceKind, params: &[u8], output: &mut Vec<u8>) -> bool {
+            match params.strip_prefix(b"7;") {
+                Some(params) => {
+                    output.push(b'[');
+                    output.extend_from_slice(params);
+                    output.push(b']');
+                    true
+                }
+                None => false,
+            }
+        }
+    }
+
+    fn scan(chunks: &[&[u8]]) -> String {
+        let mut scanner = SequenceScanner::new(TestHandler);
+        let mut output = Vec::new();
+        for chunk in chunks {
+            scanner.scan(chunk, &mut output);
+        }
+        scanner.flush(&mut output);
+        String::from_utf8(output).unwrap()
+    }
+
+    #[test]
+    fn test_sequences_split_across_reads() {
+        let input = b"a\x1b]7;x\x07b\x1b]7;y\x1b\\c";
+        for split in 0..input.len() {
+            let (first, second) = input.split_at(split);
+            assert_eq!(scan(&[first, second]), "a[x]b[y]c", "split at {split}");
+        }
+    }
+
+    #[test]
+    fn test_other_sequences_pass_through() {
+        for input in [
+            "\x1b[31mred\x1b[0m",
+            "\x1b]0;title\x07",
+            "\x1b]7",
+            "\x1b]7;x\x1b[0m",
+            "\x1b_Gx\x1b\\",
+            "\x1b\x1b]0;title\x07",
+        ] {
+            assert_eq!(scan(&[input.as_bytes()]), input);
+        }
+    }
+
+    #[test]
+    fn test_long_sequences_pass_through() {
+        let input = format!("\x1b]7;{}\x07b", "x".repeat(MAX_SEQUENCE_LENGTH));
+        assert_eq!(scan(&[input.as_bytes()]), input);
+    }
+}
@@ -0,0 +1,461 @@
+//! Shell integration: scripts that make bash, zsh and fish report their prompts and
+//! commands with OSC 133 marks, and the tracking of those marks in the terminal grid.
+//!
+//! Alacritty ignores OSC 133, so the pty output is filtered before it reaches the parser and
+//! every mark is rewritten into an OSC 8 hyperlink with a private URI. Alacritty stores
+//! hyperlinks on each cell, which keeps the marks attached to the right lines as the grid
+//! scrolls and reflows.
+
+use std::{
+    ops::RangeInclusive,
+    path::{Path, PathBuf},
+};
+
+use alacritty_terminal::{
+    grid::{Dimensions, Row},
+    index::{Column, Line, Point as AlacPoint},
+    term::cell::{Cell, Hyperlink},
+    Term,
+};
+use anyhow::{Context, Result};
+use collections::HashMap;
+use util::ResultExt;
+
+use crate::{
+    pty_filter::{SequenceHandler, SequenceKind},
+    terminal_settings::Shell,
+};
+
+const SCHEME: &str = "zed-shell-integration:";
+const PROMPT_URI: &str = "zed-shell-integration:prompt";
+const COMMAND_URI: &str = "zed-shell-integration:command";
+
+/// OSC 133 is the FinalTerm semantic prompt protocol, OSC 633 is the VS Code flavor of it.
+const MARK_PREFIXES: [&[u8]; 2] = [b"133;", b"633;"];
+/// Longer sequences are not marks we know, so they are passed through untouched.
+const MAX_MARK_LEN: usize = 512;
+
+const BASH_SCRIPT: &str = include_str!("shell_integration/zed.bash");
+const ZSH_SCRIPT: &str = include_str!("shell_integration/zed.zsh");
+const ZSH_ENV_SCRIPT: &str = include_str!("shell_integration/zshenv.zsh");
+const FISH_SCRIPT: &str = include_str!("shell_integration/zed.fish");
+
+/// A prompt, the command entered at it and the output of that command.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CommandBlock {
+    /// The first line of the prompt.
+    pub prompt_line: Line,
+    /// The lines printed by the command, if it printed any.
+    pub output: Option<RangeInclusive<Line>>,
+    /// The exit status reported for the command, once the shell has shown the next prompt.
+    pub exit_status: Option<i32>,
+}
+
+/// Whether the link was inserted by the shell integration, rather than printed by a program.
+pub fn is_shell_integration_link(link: &Hyperlink) -> bool {
+    link.uri().starts_with(SCHEME)
+}
+
+/// Makes the shell source Zed's integration script on startup.
+/// Shells without an integration script are returned unchanged.
+pub(crate) fn prepare_shell(shell: Shell, env: &mut HashMap<String, String>) -> Shell {
+    let (program, args) = match &shell {
+        Shell::System => match Shell::retrieve_system_shell() {
+            Some(program) => (program, Vec::new()),
+            None => return shell,
+        },
+        Shell::Program(program) => (program.clone(), Vec::new()),
+        Shell::WithArguments { program, args } => (program.clone(), args.clone()),
+    };
+    let Some(name) = Path::new(&program)
+        .file_name()
+        .and_then(|name| name.to_str())
+    else {
+        return shell;
+    };
+    if !matches!(name, "bash" | "zsh" | "fish") {
+        return shell;
+    }
+    let Some(scripts_dir) = write_scripts().log_err() else {
+        return shell;
+    };
+    // Alacritty starts the system shell as a login shell on macOS.
+    let login = cfg!(target_os = "macos") && shell == Shell::System;
+
+    match name {
+        "bash" => {
+            // Bash only reads `--init-file` when it's started as a plain interactive shell.
+            if !args.is_empty() {
+                return shell;
+            }
+            if login {
+                env.insert("ZED_SHELL_INTEGRATION_LOGIN".to_string(), "1".to_string());
+            }
+            Shell::WithArguments {
+                program,
+                args: vec![
+                    "--init-file".to_string(),
+                    scripts_dir.join("zed.bash").to_string_lossy().into_owned(),
+                ],
+            }
+        }
+        "zsh" => {
+            // Zsh reads our `.zshenv` from `ZDOTDIR`, which restores the user's `ZDOTDIR`
+            // before any of their startup files are read.
+            let user_zdotdir = env
+                .get("ZDOTDIR")
+                .cloned()
+                .or_else(|| std::env::var("ZDOTDIR").ok());
+            if let Some(user_zdotdir) = user_zdotdir {
+                env.insert("ZED_USER_ZDOTDIR".to_string(), user_zdotdir);
+            }
+            env.insert(
+                "ZDOTDIR".to_string(),
+                scripts_dir.join("zsh").to_string_lossy().into_owned(),
+            );
+            shell
+        }
+        _ => {
+            let mut fish_args = Vec::new();
+            if login {
+                fish_args.push("--login".to_string());
+            }
+            fish_args.push("--init-command".to_string());
+            fish_args.push(format!(
+                "source '{}'",
+                scripts_dir.join("zed.fish").to_string_lossy()
+            ));
+            fish_args.extend(args);
+            Shell::WithArguments {
+                program,
+                args: fish_args,
+            }
+        }
+    }
+}
+
+fn write_scripts() -> Result<PathBuf> {
+    let dir = paths::temp_dir().join("shell_integration");
+    let zsh_dir = dir.join("zsh");
+    std::fs::create_dir_all(&zsh_dir)
+        .with_context(|| format!("creating shell integration directory {zsh_dir:?}"))?;
+    for (path, contents) in [
+        (dir.join("zed.bash"), BASH_SCRIPT),
+        (dir.join("zed.fish"), FISH_SCRIPT),
+        (zsh_dir.join(".zshenv"), ZSH_ENV_SCRIPT),
+        (zsh_dir.join("zed.zsh"), ZSH_SCRIPT),
+    ] {
+        if std::fs::read_to_string(&path).ok().as_deref() != Some(contents) {
+            std::fs::write(&path, contents)
+                .with_context(|| format!("writing shell integration script {path:?}"))?;
+        }
+    }
+    Ok(dir)
+}
+
+/// Rewrites OSC 133 and OSC 633 marks:
+/// * `A` (prompt start) opens a link to `zed-shell-integration:prompt`, followed by the exit
+///   status of the previous command when the shell reported one.
+/// * `B` (command start) opens a link to `zed-shell-integration:command`.
+/// * `C` (output start) and `D` (command finished) close the link.
+///
+/// Other marks are dropped.
+#[derive(Debug, Default)]
+pub(crate) struct MarkScanner {
+    last_exit_status: Option<i32>,
+    next_link_id: usize,
+}
+
+impl SequenceHandler for MarkScanner {
+    fn may_handle(&self, kind: SequenceKind, params: &[u8]) -> bool {
+        kind == SequenceKind::Osc
+            && params.len() <= MAX_MARK_LEN
+            && MARK_PREFIXES.iter().any(|prefix| {
+                let len = params.len().min(prefix.len());
+                params[..len] == prefix[..len]
+            })
+    }
+
+    fn handle(&mut self, _: SequenceKind, params: &[u8], output: &mut Vec<u8>) -> bool {
+        let Some(mark) = MARK_PREFIXES
+            .iter()
+            .find_map(|prefix| params.strip_prefix(*prefix))
+        else {
+            return false;
+        };
+        self.apply_mark(&String::from_utf8_lossy(mark), output);
+        true
+    }
+}
+
+impl MarkScanner {
+    fn apply_mark(&mut self, mark: &str, output: &mut Vec<u8>) {
+        let mut params = mark.split(';');
+        match params.next() {
+            Some("A") => {
+                let uri = match self.last_exit_status {
+                    Some(exit_status) => format!("{PROMPT_URI}:{exit_status}"),
+                    None => PROMPT_URI.to_string(),
+                };
+                self.open_link(&uri, output);
+            }
+            Some("B") => self.open_link(COMMAND_URI, output),
+            Some("C") => {
+                self.last_exit_status = None;
+                close_link(output);
+            }
+            Some("D") => {
+                self.last_exit_status = params.next().and_then(|status| status.parse().ok());
+                close_link(output);
+            }
+            _ => {}
+        }
+    }
+
+    fn open_link(&mut self, uri: &str, output: &mut Vec<u8>) {
+        let id = self.next_link_id;
+        self.next_link_id += 1;
+        output.extend_from_slice(format!("\x1b]8;id=zed-{id};{uri}\x1b\\").as_bytes());
+    }
+}
+
+fn close_link(output: &mut Vec<u8>) {
+    output.extend_from_slice(b"\x1b]8;;\x1b\\");
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum LineMark {
+    Prompt {
+        id: String,
+        exit_status: Option<i32>,
+    },
+    Command,
+    Output,
+}
+
+fn line_mark(row: &Row<Cell>) -> LineMark {
+    let mut mark = LineMark::Output;
+    for cell in row {
+        let Some(link) = cell.hyperlink() else {
+            continue;
+        };
+        if let Some(exit_status) = link.uri().strip_prefix(PROMPT_URI) {
+            return LineMark::Prompt {
+                id: link.id().to_string(),
+                exit_status: exit_status
+                    .strip_prefix(':')
+                    .and_then(|status| status.parse().ok()),
+            };
+        } else if link.uri() == COMMAND_URI {
+            mark = LineMark::Command;
+        }
+    }
+    mark
+}
+
+#[derive(Default)]
+struct BlocksBuilder {
+    blocks: Vec<CommandBlock>,
+    current: Option<(String, CommandBlock)>,
+}
+
+impl BlocksBuilder {
+    /// Returns whether a new block starts at this line.
+    fn push_line(&mut self, line: Line, mark: LineMark) -> bool {
+        match mark {
+            LineMark::Prompt { id, exit_status } => {
+                if self
+                    .current
+                    .as_ref()
+                    .is_some_and(|(current_id, _)| *current_id == id)
+                {
+                    return false;
+                }
+                // The exit status of a command is reported right before the next prompt.
+                if let Some((_, mut block)) = self.current.take() {
+                    block.exit_status = exit_status;
+                    self.blocks.push(block);
+                }
+                self.current = Some((
+                    id,
+                    CommandBlock {
+                        prompt_line: line,
+                        output: None,
+                        exit_status: None,
+                    },
+                ));
+                true
+            }
+            LineMark::Command => false,
+            LineMark::Output => {
+                if let Some((_, block)) = &mut self.current {
+                    let start = block.output.as_ref().map_or(line, |output| *output.start());
+                    block.output = Some(start..=line);
+                }
+                false
+            }
+        }
+    }
+
+    fn finish(mut self) -> Vec<CommandBlock> {
+        self.blocks
+            .extend(self.current.take().map(|(_, block)| block));
+        self.blocks
+    }
+}
+
+/// All command blocks in the scrollback and on the screen, oldest first.
+pub(crate) fn command_blocks<T>(term: &Term<T>) -> Vec<CommandBlock> {
+    command_blocks_in(term, term.topmost_line()..=term.bottommost_line())
+}
+
+/// Command blocks with their prompt within the given lines. Lines below the range are
+/// scanned until the next prompt, so that the exit status of the last block is known.
+pub(crate) fn command_blocks_in<T>(
+    term: &Term<T>,
+    lines: RangeInclusive<Line>,
+) -> Vec<CommandBlock> {
+    // Lines below the cursor haven't been written to yet.
+    let last_line = term.grid().cursor.point.line;
+    let mut builder = BlocksBuilder::default();
+    let mut line = *lines.start();
+    while line <= last_line {
+        let new_block = builder.push_line(line, line_mark(&term.grid()[line]));
+        if new_block && line > *lines.end() {
+            break;
+        }
+        line = Line(line.0 + 1);
+    }
+
+    let mut blocks = builder.finish();
+    blocks.retain(|block| lines.contains(&block.prompt_line));
+    blocks
+}
+
+/// The text printed by the command of the block.
+pub(crate) fn command_output<T>(term: &Term<T>, block: &CommandBlock) -> String {
+    let Some(output) = &block.output else {
+        return String::new();
+    };
+    let start = AlacPoint::new(*output.start(), Column(0));
+    let end = AlacPoint::new(*output.end(), term.last_column());
+    term.bounds_to_string(start, end).trim_end().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pty_filter::SequenceScanner;
+
+    fn scan(chunks: &[&[u8]]) -> String {
+        let mut scanner = SequenceScanner::new(MarkScanner::default());
+        let mut output = Vec::new();
+        for chunk in chunks {
+            scanner.scan(chunk, &mut output);
+        }
+        scanner.flush(&mut output);
+        String::from_utf8(output).unwrap()
+    }
+
+    #[test]
+    fn test_marks_become_links() {
+        assert_eq!(
+            scan(&[b"\x1b]133;A\x07$ \x1b]133;B\x07ls\r\n\x1b]133;C\x07file\r\n\x1b]133;D;1\x07\x1b]133;A\x07$ "]),
+            "\x1b]8;id=zed-0;zed-shell-integration:prompt\x1b\\$ \
+             \x1b]8;id=zed-1;zed-shell-integration:command\x1b\\ls\r\n\
+             \x1b]8;;\x1b\\file\r\n\
+             \x1b]8;;\x1b\\\
+             \x1b]8;id=zed-2;zed-shell-integration:prompt:1\x1b\\$ "
+        );
+
+        // String terminators, VS Code marks and unknown marks.
+        assert_eq!(
+            scan(&[b"\x1b]633;A\x1b\\$ \x1b]633;E;ls\x07\x1b]133;P;k=v\x1b\\"]),
+            "\x1b]8;id=zed-0;zed-shell-integration:prompt\x1b\\$ "
+        );
+    }
+
+    #[test]
+    fn test_marks_split_across_reads() {
+        let expected = scan(&[b"a\x1b]133;D;0\x07\x1b]133;A\x07b"]);
+        let input = b"a\x1b]133;D;0\x07\x1b]133;A\x07b";
+        for split in 0..input.len() {
+            let (first, second) = input.split_at(split);
+            assert_eq!(scan(&[first, second]), expected, "split at {split}");
+        }
+        assert_eq!(
+            expected,
+            "a\x1b]8;;\x1b\\\x1b]8;id=zed-0;zed-shell-integration:prompt:0\x1b\\b"
+        );
+    }
+
+    #[test]
+    fn test_other_sequences_pass_through() {
+        for input in [
+            "\x1b[31mred\x1b[0m",
+            "\x1b]0;title\x07",
+            "\x1b]8;;https://zed.dev\x1b\\link\x1b]8;;\x1b\\",
+            "\x1b]13;x\x07",
+            "\x1b]133",
+            "\x1b]133;A\x1b[0m",
+            "\x1b\x1b]0;title\x07",
+        ] {
+            assert_eq!(scan(&[input.as_bytes()]), input);
+        }
+
+        let long_sequence = format!("\x1b]133;{}\x07", "x".repeat(MAX_MARK_LEN));
+        assert_eq!(scan(&[long_sequence.as_bytes()]), long_sequence);
+    }
+
+    #[test]
+    fn test_command_blocks() {
+        let prompt = |id: &str, exit_status| LineMark::Prompt {
+            id: id.to_string(),
+            exit_status,
+        };
+        let mut builder = BlocksBuilder::default();
+        for (line, mark) in [
+            LineMark::Output,
+            prompt("1", None),
+            LineMark::Command,
+            LineMark::Output,
+            LineMark::Output,
+            prompt("2", Some(0)),
+            prompt("2", Some(0)),
+            prompt("3", Some(2)),
+            LineMark::Output,
+            prompt("4", None),
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            builder.push_line(Line(line as i32), mark);
+        }
+
+        assert_eq!(
+            builder.finish(),
+            vec![
+                CommandBlock {
+                    prompt_line: Line(1),
+                    output: Some(Line(3)..=Line(4)),
+                    exit_status: Some(0),
+                },
+                CommandBlock {
+                    prompt_line: Line(5),
+                    output: None,
+                    exit_status: Some(2),
+                },
+                CommandBlock {
+                    prompt_line: Line(7),
+                    output: Some(Line(8)..=Line(8)),
+                    exit_status: None,
+                },
+                CommandBlock {
+                    prompt_line: Line(9),
+                    output: None,
+                    exit_status: None,
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,51 @@
+# Zed shell integration for bash, sourced through `--init-file`.
+# Reports prompts and commands to Zed with OSC 133 escape sequences.
+
+# `--init-file` replaces the usual startup files, so read them here.
+if [[ -n "$ZED_SHELL_INTEGRATION_LOGIN" ]]; then
+    unset ZED_SHELL_INTEGRATION_LOGIN
+    [[ -r /etc/profile ]] && builtin source /etc/profile
+    for __zed_profile in ~/.bash_profile ~/.bash_login ~/.profile; do
+        if [[ -r "$__zed_profile" ]]; then
+            builtin source "$__zed_profile"
+            break
+        fi
+    done
+    unset __zed_profile
+else
+    [[ -r /etc/bash.bashrc ]] && builtin source /etc/bash.bashrc
+    [[ -r ~/.bashrc ]] && builtin source ~/.bashrc
+fi
+
+if [[ -z "$__zed_shell_integration" ]]; then
+    __zed_shell_integration=1
+    __zed_executing=
+
+    __zed_precmd() {
+        local exit_status=$?
+        if [[ -n "$__zed_executing" ]]; then
+            builtin printf '\e]133;D;%s\a' "$exit_status"
+            __zed_executing=
+        fi
+        return $exit_status
+    }
+
+    # Runs last in PROMPT_COMMAND, so prompts set by other commands are marked too.
+    __zed_update_prompt() {
+        local exit_status=$?
+        if [[ "$PS1" != *'\e]133;A'* ]]; then
+            PS1='\[\e]133;A\a\]'"$PS1"'\[\e]133;B\a\]'
+        fi
+        return $exit_status
+    }
+
+    if [[ "$(declare -p PROMPT_COMMAND 2>/dev/null)" == "declare -a"* ]]; then
+        PROMPT_COMMAND=(__zed_precmd "${PROMPT_COMMAND[@]}" __zed_update_prompt)
+    else
+        PROMPT_COMMAND="__zed_precmd${PROMPT_COMMAND:+; $PROMPT_COMMAND}; __zed_update_prompt"
+    fi
+
+    # PS0 is printed after a command is read and before it runs. The empty substring
+    # expansion records that a command is running without printing anything.
+    PS0+='${__zed_executing:0:$((__zed_executing=1, 0))}\e]133;C\a'
+fi
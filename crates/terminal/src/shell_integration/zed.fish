@@ -0,0 +1,28 @@
+# Zed shell integration for fish, sourced through `--init-command`.
+# Reports prompts and commands to Zed with OSC 133 escape sequences.
+
+status is-interactive; or exit
+set -q __zed_shell_integration; and exit
+set -g __zed_shell_integration 1
+
+function __zed_restore_status
+    return $argv[1]
+end
+
+functions --copy fish_prompt __zed_original_fish_prompt
+
+function fish_prompt
+    set -l last_status $status
+    printf '\e]133;A\a'
+    __zed_restore_status $last_status
+    __zed_original_fish_prompt
+    printf '\e]133;B\a'
+end
+
+function __zed_preexec --on-event fish_preexec
+    printf '\e]133;C\a'
+end
+
+function __zed_postexec --on-event fish_postexec
+    printf '\e]133;D;%s\a' $status
+end
@@ -1,8 +1,11 @@
+pub mod images;
 pub mod mappings;
 
 pub use alacritty_terminal;
 
+mod pty_filter;
 mod pty_info;
+pub mod shell_integration;
 pub mod terminal_settings;
 
 use alacritty_terminal::{
@@ -13,18 +16,23 @@ use alacritty_terminal::{
     selection::{Selection, SelectionRange, SelectionType},
     sync::FairMutex,
     term::{
-        cell::Cell,
+        cell::{Cell, Hyperlink},
         search::{Match, RegexIter, RegexSearch},
         Config, RenderableCursor, TermMode,
     },
     tty::{self, setup_env},
-    vte::ansi::{ClearMode, Handler, NamedPrivateMode, PrivateMode, Rgb},
+    vte::ansi::{
+        ClearMode, Handler, NamedPrivateMode, PrivateMode, Processor, Rgb, StdSyncHandler,
+    },
     Term,
 };
 use anyhow::{bail, Result};
 
 use futures::{
-    channel::mpsc::{unbounded, UnboundedReceiver, UnboundedSender},
+    channel::{
+        mpsc::{unbounded, UnboundedReceiver, UnboundedSender},
+        oneshot,
+    },
     FutureExt,
 };
 
@@ -35,9 +43,13 @@ use mappings::mouse::{
 
 use collections::{HashMap, VecDeque};
 use futures::StreamExt;
+use images::{is_image_link, ImageDecoder, ImagePlacement, ImageStore};
+use pty_filter::FilteredPty;
 use pty_info::PtyProcessInfo;
+use regex::Regex;
 use serde::{Deserialize, Serialize};
 use settings::Settings;
+use shell_integration::{is_shell_integration_link, CommandBlock};
 use smol::channel::{Receiver, Sender};
 use task::TaskId;
 use terminal_settings::{AlternateScroll, Shell, TerminalBlink, TerminalSettings};
@@ -47,6 +59,7 @@ use util::truncate_and_trailoff;
 use std::{
     cmp::{self, min},
     fmt::Display,
+    mem,
     ops::{Deref, Index, RangeInclusive},
     path::PathBuf,
     sync::Arc,
@@ -76,6 +89,9 @@ actions!(
         ScrollPageDown,
         ScrollToTop,
         ScrollToBottom,
+        ScrollToPreviousPrompt,
+        ScrollToNextPrompt,
+        CopyLastCommandOutput,
     ]
 );
 
@@ -87,6 +103,7 @@ const SCROLL_MULTIPLIER: f32 = 4.;
 #[cfg(not(target_os = "macos"))]
 const SCROLL_MULTIPLIER: f32 = 1.;
 const MAX_SEARCH_LINES: usize = 100;
+const OUTPUT_WATCHERS_CHECK_INTERVAL: Duration = Duration::from_millis(100);
 const DEBUG_TERMINAL_WIDTH: Pixels = px(500.);
 const DEBUG_TERMINAL_HEIGHT: Pixels = px(30.);
 const DEBUG_CELL_WIDTH: Pixels = px(5.);
@@ -317,6 +334,8 @@ impl TerminalBuilder {
         blink_settings: Option<TerminalBlink>,
         alternate_scroll: AlternateScroll,
         max_scroll_history_lines: Option<usize>,
+        shell_integration: bool,
+        history: Option<String>,
         window: AnyWindowHandle,
         completion_tx: Sender<()>,
         cx: &mut AppContext,
@@ -332,8 +351,16 @@ impl TerminalBuilder {
             release_channel::AppVersion::global(cx).to_string(),
         );
 
+        // Tasks run a single command, there are no prompts to report.
+        let shell_integration = shell_integration && task.is_none();
+        let spawned_shell = if shell_integration {
+            shell_integration::prepare_shell(shell.clone(), &mut env)
+        } else {
+            shell.clone()
+        };
+
         let pty_options = {
-            let alac_shell = match shell.clone() {
+            let alac_shell = match spawned_shell {
                 Shell::System => None,
                 Shell::Program(program) => {
                     Some(alacritty_terminal::tty::Shell::new(program, Vec::new()))
@@ -393,6 +420,15 @@ impl TerminalBuilder {
             term.unset_private_mode(PrivateMode::Named(NamedPrivateMode::AlternateScroll));
         }
 
+        //Replay the history of a restored terminal, dimmed, above the new shell's output
+        if let Some(history) = history.filter(|history| !history.is_empty()) {
+            let history = format!("\x1b[2m{}\x1b[0m\r\n", history.replace('\n', "\r\n"));
+            let mut processor = Processor::<StdSyncHandler>::new();
+            for byte in history.bytes() {
+                processor.advance(&mut term, byte);
+            }
+        }
+
         let term = Arc::new(FairMutex::new(term));
 
         //Setup the pty...
@@ -414,10 +450,15 @@ impl TerminalBuilder {
         let pty_info = PtyProcessInfo::new(&pty);
 
         //And connect them together
+        let images = ImageStore::default();
         let event_loop = EventLoop::new(
             term.clone(),
             ZedListener(events_tx.clone()),
-            pty,
+            FilteredPty::new(
+                pty,
+                ImageDecoder::new(images.clone(), TerminalSize::default().into()),
+                shell_integration,
+            ),
             pty_options.hold,
             false,
         )?;
@@ -449,6 +490,9 @@ impl TerminalBuilder {
             hovered_word: false,
             url_regex,
             word_regex,
+            output_watchers: Vec::new(),
+            output_watchers_check: None,
+            images,
         };
 
         Ok(TerminalBuilder {
@@ -547,6 +591,10 @@ pub struct TerminalContent {
     pub cursor_char: char,
     pub size: TerminalSize,
     pub last_hovered_word: Option<HoveredWord>,
+    /// Command blocks with their prompt on the screen, see [`Terminal::command_blocks`].
+    pub command_blocks: Vec<CommandBlock>,
+    /// Inline images that cover any of the lines on the screen.
+    pub images: Vec<ImagePlacement>,
 }
 
 #[derive(Clone)]
@@ -571,6 +619,8 @@ impl Default for TerminalContent {
             cursor_char: Default::default(),
             size: Default::default(),
             last_hovered_word: None,
+            command_blocks: Vec::new(),
+            images: Vec::new(),
         }
     }
 }
@@ -603,6 +653,9 @@ pub struct Terminal {
     url_regex: RegexSearch,
     word_regex: RegexSearch,
     task: Option<TaskState>,
+    output_watchers: Vec<(Regex, oneshot::Sender<bool>)>,
+    output_watchers_check: Option<Task<()>>,
+    images: ImageStore,
 }
 
 pub struct TaskState {
@@ -675,6 +728,7 @@ impl Terminal {
             }
             AlacTermEvent::Wakeup => {
                 cx.emit(Event::Wakeup);
+                self.schedule_output_watchers_check(cx);
 
                 if self.pty_info.has_changed() {
                     cx.emit(Event::TitleChanged);
@@ -811,7 +865,11 @@ impl Terminal {
                 )
                 .grid_clamp(term, Boundary::Grid);
 
-                let link = term.grid().index(point).hyperlink();
+                let link = term
+                    .grid()
+                    .index(point)
+                    .hyperlink()
+                    .filter(|link| !is_internal_link(link));
                 let found_word = if link.is_some() {
                     let mut min_index = point;
                     loop {
@@ -1126,11 +1184,17 @@ impl Terminal {
             self.process_terminal_event(&e, &mut terminal, cx)
         }
 
-        self.last_content = Self::make_content(&terminal, &self.last_content);
+        self.last_content = Self::make_content(&terminal, &self.last_content, &self.images);
     }
 
-    fn make_content(term: &Term<ZedListener>, last_content: &TerminalContent) -> TerminalContent {
+    fn make_content(
+        term: &Term<ZedListener>,
+        last_content: &TerminalContent,
+        images: &ImageStore,
+    ) -> TerminalContent {
         let content = term.renderable_content();
+        let top_line = Line(-(content.display_offset as i32));
+        let bottom_line = Line(top_line.0 + term.screen_lines() as i32 - 1);
         TerminalContent {
             cells: content
                 .display_iter
@@ -1154,6 +1218,8 @@ impl Terminal {
             cursor_char: term.grid()[content.cursor.point].c,
             size: last_content.size,
             last_hovered_word: last_content.last_hovered_word.clone(),
+            command_blocks: shell_integration::command_blocks_in(term, top_line..=bottom_line),
+            images: images::images_in(term, images, top_line..=bottom_line),
         }
     }
 
@@ -1182,6 +1248,83 @@ impl Terminal {
         lines
     }
 
+    /// The last `n` lines of the scrollback and the screen, up to the cursor.
+    /// Empty while a program uses the alternate screen.
+    pub fn last_lines(&self, n: usize) -> String {
+        let term = self.term.lock_unfair();
+        if n == 0 || term.mode().contains(TermMode::ALT_SCREEN) {
+            return String::new();
+        }
+        let end_line = term.grid().cursor.point.line;
+        let start_line = cmp::max(
+            end_line - (n.min(MAX_SCROLL_HISTORY_LINES) - 1),
+            term.topmost_line(),
+        );
+        let start = AlacPoint::new(start_line, Column(0));
+        let end = AlacPoint::new(end_line, term.last_column());
+        term.bounds_to_string(start, end).trim_end().to_string()
+    }
+
+    /// The text of the scrollback and the screen, with wrapped lines joined.
+    pub fn text(&self) -> String {
+        let term = self.term.lock_unfair();
+        let start = AlacPoint::new(term.topmost_line(), Column(0));
+        let end = AlacPoint::new(term.bottommost_line(), term.last_column());
+        term.bounds_to_string(start, end)
+    }
+
+    /// The prompts, commands and command outputs of the terminal's shell, oldest first.
+    /// Empty unless the shell reports them through shell integration.
+    pub fn command_blocks(&self) -> Vec<CommandBlock> {
+        let term = self.term.lock_unfair();
+        shell_integration::command_blocks(&term)
+    }
+
+    /// The output of the last command run in the shell, if the shell reports its commands
+    /// through shell integration.
+    pub fn last_command_output(&self) -> Option<String> {
+        let term = self.term.lock_unfair();
+        let blocks = shell_integration::command_blocks(&term);
+        let mut blocks = blocks.iter().rev();
+        let mut block = blocks.next()?;
+        // Skip the prompt that is waiting for the next command.
+        if block.output.is_none() && block.exit_status.is_none() {
+            block = blocks.next()?;
+        }
+        Some(shell_integration::command_output(&term, block))
+    }
+
+    pub fn scroll_to_previous_prompt(&mut self) {
+        let top_line = Line(-(self.last_content.display_offset as i32));
+        let previous_prompt = self
+            .command_blocks()
+            .into_iter()
+            .rev()
+            .find(|block| block.prompt_line < top_line);
+        if let Some(block) = previous_prompt {
+            self.events
+                .push_back(InternalEvent::Scroll(AlacScroll::Delta(
+                    top_line.0 - block.prompt_line.0,
+                )));
+        }
+    }
+
+    pub fn scroll_to_next_prompt(&mut self) {
+        let top_line = Line(-(self.last_content.display_offset as i32));
+        let next_prompt = self
+            .command_blocks()
+            .into_iter()
+            .find(|block| block.prompt_line > top_line);
+        match next_prompt {
+            Some(block) => self
+                .events
+                .push_back(InternalEvent::Scroll(AlacScroll::Delta(
+                    top_line.0 - block.prompt_line.0,
+                ))),
+            None => self.scroll_to_bottom(),
+        }
+    }
+
     pub fn focus_in(&self) {
         if self.last_content.mode.contains(TermMode::FOCUS_IN_OUT) {
             self.write_to_pty("\x1b[I".to_string());
@@ -1376,7 +1519,10 @@ impl Terminal {
             //Hyperlinks
             if self.selection_phase == SelectionPhase::Ended {
                 let mouse_cell_index = content_index_for_mouse(position, &self.last_content.size);
-                if let Some(link) = self.last_content.cells[mouse_cell_index].hyperlink() {
+                if let Some(link) = self.last_content.cells[mouse_cell_index]
+                    .hyperlink()
+                    .filter(|link| !is_internal_link(link))
+                {
                     cx.open_url(link.uri());
                 } else if self.secondary_pressed {
                     self.events
@@ -1540,11 +1686,66 @@ impl Terminal {
         Task::ready(())
     }
 
+    /// Resolves to `true` once the output of the terminal matches the pattern,
+    /// or to `false` if the terminal's task finishes without the output matching it.
+    pub fn wait_for_output(&mut self, pattern: Regex, cx: &mut AppContext) -> Task<bool> {
+        if pattern.is_match(&self.text()) {
+            return Task::ready(true);
+        }
+        if self
+            .task()
+            .map_or(true, |task| task.status != TaskStatus::Running)
+        {
+            return Task::ready(false);
+        }
+        let (tx, rx) = oneshot::channel();
+        self.output_watchers.push((pattern, tx));
+        cx.background_executor()
+            .spawn(async move { rx.await.unwrap_or(false) })
+    }
+
+    /// Matching the watchers against the output serializes the whole terminal content,
+    /// so the checks are batched instead of running on every wakeup.
+    fn schedule_output_watchers_check(&mut self, cx: &mut ModelContext<Self>) {
+        if self.output_watchers.is_empty() || self.output_watchers_check.is_some() {
+            return;
+        }
+        self.output_watchers_check = Some(cx.spawn(|terminal, mut cx| async move {
+            cx.background_executor()
+                .timer(OUTPUT_WATCHERS_CHECK_INTERVAL)
+                .await;
+            terminal
+                .update(&mut cx, |terminal, _| {
+                    terminal.output_watchers_check = None;
+                    terminal.check_output_watchers(false);
+                })
+                .ok();
+        }));
+    }
+
+    fn check_output_watchers(&mut self, task_finished: bool) {
+        if self.output_watchers.is_empty() {
+            return;
+        }
+        let text = self.text();
+        for (pattern, tx) in mem::take(&mut self.output_watchers) {
+            if pattern.is_match(&text) {
+                tx.send(true).ok();
+            } else if task_finished {
+                tx.send(false).ok();
+            } else {
+                self.output_watchers.push((pattern, tx));
+            }
+        }
+    }
+
     fn register_task_finished(
         &mut self,
         error_code: Option<i32>,
         cx: &mut ModelContext<'_, Terminal>,
     ) {
+        self.output_watchers_check = None;
+        self.check_output_watchers(true);
         self.completion_tx.try_send(()).ok();
         let task = match &mut self.task {
             Some(task) => task,
@@ -1642,6 +1843,12 @@ impl EventEmitter<Event> for Terminal {}
 
 /// Based on alacritty/src/display/hint.rs > regex_match_at
 /// Retrieve the match, if the specified point is inside the content matching the regex.
+/// Whether the hyperlink was inserted by Zed to track a position in the grid, such as a shell
+/// integration mark or an inline image, rather than printed by a program.
+pub fn is_internal_link(link: &Hyperlink) -> bool {
+    is_shell_integration_link(link) || is_image_link(link)
+}
+
 fn regex_match_at<T>(term: &Term<T>, point: AlacPoint, regex: &mut RegexSearch) -> Option<Match> {
     visible_regex_match_iter(term, regex).find(|rm| rm.contains(&point))
 }
@@ -1777,11 +1984,14 @@ mod tests {
         index::{Column, Line, Point as AlacPoint},
         term::cell::Cell,
     };
-    use gpui::{point, size, Pixels};
+    use collections::HashMap;
+    use gpui::{point, size, Pixels, TestAppContext};
     use rand::{distributions::Alphanumeric, rngs::ThreadRng, thread_rng, Rng};
 
     use crate::{
-        content_index_for_mouse, rgb_for_index, IndexedCell, TerminalContent, TerminalSize,
+        content_index_for_mouse, rgb_for_index,
+        terminal_settings::{AlternateScroll, Shell},
+        IndexedCell, TerminalBuilder, TerminalContent, TerminalSize,
     };
 
     #[test]
@@ -1869,6 +2079,47 @@ mod tests {
         );
     }
 
+    #[cfg(unix)]
+    #[gpui::test]
+    fn test_last_lines_of_restored_history(cx: &mut TestAppContext) {
+        let cx = cx.add_empty_window();
+        let history = (1..=10)
+            .map(|ix| format!("line {ix}"))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let (completion_tx, _completion_rx) = smol::channel::unbounded();
+        let builder = cx
+            .update(|cx| {
+                TerminalBuilder::new(
+                    None,
+                    None,
+                    Shell::WithArguments {
+                        program: "/bin/sh".to_string(),
+                        args: vec!["-c".to_string(), "sleep 10".to_string()],
+                    },
+                    HashMap::default(),
+                    None,
+                    AlternateScroll::On,
+                    None,
+                    false,
+                    Some(history.clone()),
+                    cx.window_handle(),
+                    completion_tx,
+                    cx,
+                )
+            })
+            .unwrap();
+        let terminal = &builder.terminal;
+
+        assert_eq!(terminal.last_lines(0), "");
+        assert_eq!(terminal.last_lines(3), "line 9\nline 10");
+        assert_eq!(
+            terminal.last_lines(100),
+            history,
+            "The whole history should be returned when it has fewer lines than requested"
+        );
+    }
+
     fn get_cells(size: TerminalSize, rng: &mut ThreadRng) -> Vec<Vec<char>> {
         let mut cells = Vec::new();
 
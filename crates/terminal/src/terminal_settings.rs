@@ -44,6 +44,8 @@ pub struct TerminalSettings {
     pub detect_venv: VenvSettings,
     pub max_scroll_history_lines: Option<usize>,
     pub toolbar: Toolbar,
+    pub shell_integration: bool,
+    pub saved_scrollback_lines: usize,
 }
 
 #[derive(Clone, Debug, Default, Serialize, Deserialize, JsonSchema)]
@@ -171,6 +173,17 @@ pub struct TerminalSettingsContent {
     pub max_scroll_history_lines: Option<usize>,
     /// Toolbar related settings
     pub toolbar: Option<ToolbarContent>,
+    /// Whether to make bash, zsh and fish report their prompts and commands to Zed,
+    /// which marks the exit status of each command and allows jumping between prompts.
+    /// Existing terminals will not pick up this change until they are recreated.
+    ///
+    /// Default: true
+    pub shell_integration: Option<bool>,
+    /// The number of scrollback lines saved for each terminal, which are shown
+    /// above a new shell when the workspace is reopened. 0 disables saving the scrollback.
+    ///
+    /// Default: 0
+    pub saved_scrollback_lines: Option<usize>,
 }
 
 impl settings::Settings for TerminalSettings {
@@ -34,6 +34,10 @@ define_connection! {
             DROP TABLE terminals;
 
             ALTER TABLE terminals2 RENAME TO terminals;
+        ),
+        sql!(
+            ALTER TABLE terminals ADD COLUMN title TEXT;
+            ALTER TABLE terminals ADD COLUMN scrollback TEXT;
         )];
 }
 
@@ -51,21 +55,130 @@ impl TerminalDb {
     }
 
     query! {
-        pub async fn save_working_directory(
+        pub async fn save_terminal_state(
+            item_id: ItemId,
+            workspace_id: WorkspaceId,
+            working_directory: PathBuf,
+            title: String,
+            scrollback: String
+        ) -> Result<()> {
+            INSERT OR REPLACE INTO terminals(item_id, workspace_id, working_directory, title, scrollback)
+            VALUES (?, ?, ?, ?, ?)
+        }
+    }
+
+    query! {
+        pub async fn save_working_directory_and_title(
             item_id: ItemId,
             workspace_id: WorkspaceId,
-            working_directory: PathBuf
+            working_directory: PathBuf,
+            title: String
         ) -> Result<()> {
-            INSERT OR REPLACE INTO terminals(item_id, workspace_id, working_directory)
-            VALUES (?, ?, ?)
+            INSERT INTO terminals(item_id, workspace_id, working_directory, title)
+            VALUES (?, ?, ?, ?)
+            ON CONFLICT(workspace_id, item_id) DO UPDATE SET
+                working_directory = excluded.working_directory,
+                title = excluded.title
         }
     }
 
     query! {
-        pub fn get_working_directory(item_id: ItemId, workspace_id: WorkspaceId) -> Result<Option<PathBuf>> {
-            SELECT working_directory
+        pub fn get_terminal_state(
+            item_id: ItemId,
+            workspace_id: WorkspaceId
+        ) -> Result<Option<(PathBuf, Option<String>, Option<String>)>> {
+            SELECT working_directory, title, scrollback
             FROM terminals
             WHERE item_id = ? AND workspace_id = ?
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use db::open_test_db;
+
+    use super::*;
+
+    #[gpui::test]
+    async fn test_terminal_state_round_trip() {
+        let db = TerminalDb(open_test_db("test_terminal_state_round_trip").await);
+        let workspace_id = db
+            .write(|conn| {
+                conn.select_row::<WorkspaceId>(sql!(
+                    INSERT INTO workspaces DEFAULT VALUES RETURNING workspace_id
+                ))
+                .unwrap()()
+                .unwrap()
+                .unwrap()
+            })
+            .await;
+
+        assert_eq!(db.get_terminal_state(1, workspace_id).unwrap(), None);
+
+        db.save_terminal_state(
+            1,
+            workspace_id,
+            PathBuf::from("/the-project"),
+            "cargo test".to_string(),
+            "$ cargo test\nok".to_string(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            db.get_terminal_state(1, workspace_id).unwrap(),
+            Some((
+                PathBuf::from("/the-project"),
+                Some("cargo test".to_string()),
+                Some("$ cargo test\nok".to_string())
+            ))
+        );
+
+        // Saving the state of the same terminal again replaces it.
+        db.save_terminal_state(
+            1,
+            workspace_id,
+            PathBuf::from("/the-project/src"),
+            "zsh".to_string(),
+            String::new(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            db.get_terminal_state(1, workspace_id).unwrap(),
+            Some((
+                PathBuf::from("/the-project/src"),
+                Some("zsh".to_string()),
+                Some(String::new())
+            ))
+        );
+        assert_eq!(db.get_terminal_state(2, workspace_id).unwrap(), None);
+
+        // Saving only the working directory and title keeps the saved scrollback.
+        db.save_terminal_state(
+            2,
+            workspace_id,
+            PathBuf::from("/the-project"),
+            "zsh".to_string(),
+            "$ ls".to_string(),
+        )
+        .await
+        .unwrap();
+        db.save_working_directory_and_title(
+            2,
+            workspace_id,
+            PathBuf::from("/the-project/docs"),
+            "vim".to_string(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            db.get_terminal_state(2, workspace_id).unwrap(),
+            Some((
+                PathBuf::from("/the-project/docs"),
+                Some("vim".to_string()),
+                Some("$ ls".to_string())
+            ))
+        );
+    }
+}
@@ -1,6 +1,6 @@
 use editor::{CursorLayout, HighlightedRange, HighlightedRangeLine};
 use gpui::{
-    div, fill, point, px, relative, size, AnyElement, AvailableSpace, Bounds, ContentMask,
+    div, fill, point, px, relative, size, AnyElement, AvailableSpace, Bounds, ContentMask, Corners,
     DispatchPhase, Element, ElementId, FocusHandle, Font, FontStyle, FontWeight, GlobalElementId,
     HighlightStyle, Hitbox, Hsla, InputHandler, InteractiveElement, Interactivity, IntoElement,
     LayoutId, Model, ModelContext, ModifiersChangedEvent, MouseButton, MouseMoveEvent, Pixels,
@@ -20,11 +20,14 @@ use terminal::{
             CursorShape as AlacCursorShape, NamedColor,
         },
     },
+    images::ImagePlacement,
+    is_internal_link,
     terminal_settings::TerminalSettings,
     HoveredWord, IndexedCell, Terminal, TerminalContent, TerminalSize,
 };
 use theme::{ActiveTheme, Theme, ThemeSettings};
 use ui::{ParentElement, Tooltip};
+use util::ResultExt;
 use workspace::Workspace;
 
 use std::{fmt::Debug, ops::RangeInclusive};
@@ -45,6 +48,9 @@ pub struct LayoutState {
     display_offset: usize,
     hyperlink_tooltip: Option<AnyElement>,
     gutter: Pixels,
+    /// Display lines of the visible prompts, with the color of their command's exit status.
+    prompt_markers: Vec<(i32, Hsla)>,
+    images: Vec<ImagePlacement>,
     last_hovered_word: Option<HoveredWord>,
     block_below_cursor_element: Option<AnyElement>,
 }
@@ -353,7 +359,10 @@ impl TerminalElement {
         }
 
         let underline = (flags.intersects(Flags::ALL_UNDERLINES)
-            || indexed.cell.hyperlink().is_some())
+            || indexed
+                .cell
+                .hyperlink()
+                .is_some_and(|link| !is_internal_link(&link)))
         .then(|| UnderlineStyle {
             color: Some(fg),
             thickness: Pixels::from(1.0),
@@ -728,11 +737,26 @@ impl Element for TerminalElement {
                     cursor_char,
                     selection,
                     cursor,
+                    command_blocks,
+                    images,
                     ..
                 } = &self.terminal.read(cx).last_content;
                 let mode = *mode;
                 let display_offset = *display_offset;
 
+                let prompt_markers = command_blocks
+                    .iter()
+                    .map(|block| {
+                        let color = match block.exit_status {
+                            Some(0) => theme.status().success,
+                            Some(_) => theme.status().error,
+                            None => theme.colors().text_muted,
+                        };
+                        (block.prompt_line.0 + display_offset as i32, color)
+                    })
+                    .collect::<Vec<_>>();
+                let images = images.clone();
+
                 // searches, highlights to a single range representations
                 let mut relative_highlighted_ranges = Vec::new();
                 for search_match in search_matches {
@@ -848,6 +872,8 @@ impl Element for TerminalElement {
                     display_offset,
                     hyperlink_tooltip,
                     gutter,
+                    prompt_markers,
+                    images,
                     last_hovered_word,
                     block_below_cursor_element,
                 }
@@ -912,6 +938,15 @@ impl Element for TerminalElement {
                         rect.paint(origin, &layout, cx);
                     }
 
+                    for (line, color) in &layout.prompt_markers {
+                        let marker_origin = point(
+                            origin.x - layout.gutter * 0.75,
+                            origin.y + *line as f32 * layout.dimensions.line_height,
+                        );
+                        let marker_size = size(layout.gutter * 0.5, layout.dimensions.line_height);
+                        cx.paint_quad(fill(Bounds::new(marker_origin, marker_size), *color));
+                    }
+
                     for (relative_highlighted_range, color) in
                         layout.relative_highlighted_ranges.iter()
                     {
@@ -933,6 +968,10 @@ impl Element for TerminalElement {
                         cell.paint(origin, &layout, bounds, cx);
                     }
 
+                    for placement in &layout.images {
+                        paint_image(placement, origin, &layout, cx);
+                    }
+
                     if self.cursor_visible {
                         if let Some(mut cursor) = cursor {
                             cursor.paint(origin, cx);
@@ -1039,7 +1078,10 @@ pub fn is_blank(cell: &IndexedCell) -> bool {
         return false;
     }
 
-    if cell.hyperlink().is_some() {
+    if cell
+        .hyperlink()
+        .is_some_and(|link| !is_internal_link(&link))
+    {
         return false;
     }
 
@@ -1053,6 +1095,42 @@ pub fn is_blank(cell: &IndexedCell) -> bool {
     return true;
 }
 
+fn paint_image(
+    placement: &ImagePlacement,
+    origin: Point<Pixels>,
+    layout: &LayoutState,
+    cx: &mut WindowContext,
+) {
+    let image = &placement.image;
+    let line = placement.point.line.0 + layout.display_offset as i32;
+    let image_origin = origin
+        + point(
+            placement.point.column.0 as f32 * layout.dimensions.cell_width,
+            line as f32 * layout.dimensions.line_height,
+        );
+    let area = size(
+        image.columns as f32 * layout.dimensions.cell_width,
+        image.lines as f32 * layout.dimensions.line_height,
+    );
+    // The cell size may have changed since the image was printed.
+    let image_size = if image.preserve_aspect_ratio {
+        let data_size = image.data.size();
+        let width = u32::from(data_size.width) as f32;
+        let height = u32::from(data_size.height) as f32;
+        let scale = (area.width.0 / width).min(area.height.0 / height);
+        size(px(width * scale), px(height * scale))
+    } else {
+        area
+    };
+    cx.paint_image(
+        Bounds::new(image_origin, image_size),
+        Corners::default(),
+        image.data.clone(),
+        false,
+    )
+    .log_err();
+}
+
 fn to_highlighted_range_lines(
     range: &RangeInclusive<AlacPoint>,
     layout: &LayoutState,
@@ -1,9 +1,13 @@
-use std::{ops::ControlFlow, path::PathBuf, sync::Arc};
+use std::{cell::RefCell, ops::ControlFlow, path::PathBuf, rc::Rc, sync::Arc};
 
 use crate::TerminalView;
 use collections::{HashMap, HashSet};
 use db::kvp::KEY_VALUE_STORE;
-use futures::future::join_all;
+use futures::{
+    channel::oneshot,
+    future::{join_all, LocalBoxFuture, Shared},
+    FutureExt as _,
+};
 use gpui::{
     actions, Action, AppContext, AsyncWindowContext, DismissEvent, Entity, EventEmitter,
     ExternalPaths, FocusHandle, FocusableView, IntoElement, Model, ParentElement, Pixels, Render,
@@ -11,13 +15,14 @@ use gpui::{
 };
 use itertools::Itertools;
 use project::{Fs, ProjectEntryId};
+use regex::Regex;
 use search::{buffer_search::DivRegistrar, BufferSearchBar};
 use serde::{Deserialize, Serialize};
 use settings::Settings;
-use task::{RevealStrategy, SpawnInTerminal, TaskId, TerminalWorkDir};
+use task::{DependsOrder, RevealStrategy, SpawnInTerminal, TaskId, TerminalWorkDir};
 use terminal::{
     terminal_settings::{Shell, TerminalDockPosition, TerminalSettings},
-    Terminal,
+    TaskStatus, Terminal,
 };
 use ui::{
     h_flex, ButtonCommon, Clickable, ContextMenu, FluentBuilder, IconButton, IconSize, Selectable,
@@ -32,7 +37,7 @@ use workspace::{
     DraggedTab, NewTerminal, Pane, ToggleZoom, Workspace,
 };
 
-use anyhow::Result;
+use anyhow::{anyhow, Context as _, Result};
 
 const TERMINAL_PANEL_KEY: &str = "TerminalPanel";
 
@@ -308,8 +313,10 @@ impl TerminalPanel {
         cx: &mut ViewContext<Self>,
     ) {
         match event {
-            pane::Event::ActivateItem { .. } => self.serialize(cx),
-            pane::Event::RemoveItem { .. } => self.serialize(cx),
+            pane::Event::ActivateItem { .. } | pane::Event::RemoveItem { .. } => {
+                self.serialize(cx);
+                self.save_terminal_states(cx);
+            }
             pane::Event::Remove => cx.emit(PanelEvent::Close),
             pane::Event::ZoomIn => cx.emit(PanelEvent::ZoomIn),
             pane::Event::ZoomOut => cx.emit(PanelEvent::ZoomOut),
@@ -347,6 +354,57 @@ impl TerminalPanel {
     }
 
     fn spawn_task(&mut self, spawn_in_terminal: &SpawnInTerminal, cx: &mut ViewContext<Self>) {
+        if spawn_in_terminal.depends_on.is_empty() {
+            self.spawn_single_task(spawn_in_terminal, cx)
+                .detach_and_log_err(cx);
+            return;
+        }
+
+        self.spawn_task_with_dependencies(spawn_in_terminal.clone(), cx)
+            .detach_and_log_err(cx);
+    }
+
+    fn spawn_task_with_dependencies(
+        &mut self,
+        spawn_in_terminal: SpawnInTerminal,
+        cx: &mut ViewContext<Self>,
+    ) -> Task<Result<()>> {
+        let workspace = self.workspace.clone();
+        cx.spawn(|terminal_panel, mut cx| async move {
+            let dependencies_result = run_task_dependencies(
+                spawn_in_terminal.depends_on.clone(),
+                spawn_in_terminal.depends_order,
+                spawn_dependency_in_terminal(terminal_panel.clone(), cx.clone()),
+                DependencyRuns::default(),
+            )
+            .await;
+            if let Err(error) = dependencies_result {
+                workspace.update(&mut cx, |workspace, cx| {
+                    let error = error.context(format!(
+                        "Task `{}` was not run",
+                        spawn_in_terminal.full_label
+                    ));
+                    workspace.show_error(&error, cx);
+                })?;
+                return Ok(());
+            }
+            // Composite tasks only run their dependencies
+            if !spawn_in_terminal.command.is_empty() {
+                terminal_panel
+                    .update(&mut cx, |terminal_panel, cx| {
+                        terminal_panel.spawn_single_task(&spawn_in_terminal, cx)
+                    })?
+                    .await?;
+            }
+            anyhow::Ok(())
+        })
+    }
+
+    fn spawn_single_task(
+        &mut self,
+        spawn_in_terminal: &SpawnInTerminal,
+        cx: &mut ViewContext<Self>,
+    ) -> Task<Result<Model<Terminal>>> {
         let mut spawn_task = spawn_in_terminal.clone();
         // Set up shell args unconditionally, as tasks are always spawned inside of a shell.
         let Some((shell, mut user_args)) = (match TerminalSettings::get_global(cx).shell.clone() {
@@ -354,7 +412,7 @@ impl TerminalPanel {
             Shell::Program(shell) => Some((shell, Vec::new())),
             Shell::WithArguments { program, args } => Some((program, args)),
         }) else {
-            return;
+            return Task::ready(Err(anyhow!("No shell found to spawn the task in")));
         };
         #[cfg(target_os = "windows")]
         let windows_shell_type = Shell::to_windows_shell_type(&shell);
@@ -417,16 +475,12 @@ impl TerminalPanel {
         let use_new_terminal = spawn_in_terminal.use_new_terminal;
 
         if allow_concurrent_runs && use_new_terminal {
-            self.spawn_in_new_terminal(spawn_task, cx)
-                .detach_and_log_err(cx);
-            return;
+            return self.spawn_in_new_terminal(spawn_task, cx);
         }
 
         let terminals_for_task = self.terminals_for_task(&spawn_in_terminal.full_label, cx);
         if terminals_for_task.is_empty() {
-            self.spawn_in_new_terminal(spawn_task, cx)
-                .detach_and_log_err(cx);
-            return;
+            return self.spawn_in_new_terminal(spawn_task, cx);
         }
         let (existing_item_index, existing_terminal) = terminals_for_task
             .last()
@@ -437,30 +491,44 @@ impl TerminalPanel {
                 !use_new_terminal,
                 "Should have handled 'allow_concurrent_runs && use_new_terminal' case above"
             );
-            self.replace_terminal(spawn_task, existing_item_index, existing_terminal, cx);
+            Task::ready(
+                self.replace_terminal(spawn_task, existing_item_index, existing_terminal, cx)
+                    .context("Failed to replace the task's terminal"),
+            )
         } else {
+            let (terminal_tx, terminal_rx) = oneshot::channel();
             self.deferred_tasks.insert(
                 spawn_in_terminal.id.clone(),
                 cx.spawn(|terminal_panel, mut cx| async move {
                     wait_for_terminals_tasks(terminals_for_task, &mut cx).await;
-                    terminal_panel
-                        .update(&mut cx, |terminal_panel, cx| {
-                            if use_new_terminal {
+                    let terminal = terminal_panel.update(&mut cx, |terminal_panel, cx| {
+                        if use_new_terminal {
+                            terminal_panel.spawn_in_new_terminal(spawn_task, cx)
+                        } else {
+                            Task::ready(
                                 terminal_panel
-                                    .spawn_in_new_terminal(spawn_task, cx)
-                                    .detach_and_log_err(cx);
-                            } else {
-                                terminal_panel.replace_terminal(
-                                    spawn_task,
-                                    existing_item_index,
-                                    existing_terminal,
-                                    cx,
-                                );
-                            }
-                        })
-                        .ok();
+                                    .replace_terminal(
+                                        spawn_task,
+                                        existing_item_index,
+                                        existing_terminal,
+                                        cx,
+                                    )
+                                    .context("Failed to replace the task's terminal"),
+                            )
+                        }
+                    });
+                    let terminal = match terminal {
+                        Ok(terminal) => terminal.await,
+                        Err(error) => Err(error),
+                    };
+                    terminal_tx.send(terminal).ok();
                 }),
             );
+            cx.spawn(|_, _| async move {
+                terminal_rx
+                    .await
+                    .context("The task was cancelled before it started")?
+            })
         }
     }
 
@@ -575,27 +643,39 @@ impl TerminalPanel {
             })?;
             terminal_panel.update(&mut cx, |this, cx| {
                 this.pending_terminals_to_add = this.pending_terminals_to_add.saturating_sub(1);
-                this.serialize(cx)
+                this.serialize(cx);
+                this.save_terminal_states(cx);
             })?;
             result
         })
     }
 
+    /// Saves the state of every terminal in the panel, including its scrollback. Unlike the
+    /// layout of the panel, it is not saved on resize, which would copy the scrollback repeatedly.
+    fn save_terminal_states(&self, cx: &mut ViewContext<Self>) {
+        for item in self.pane.read(cx).items() {
+            let Some(terminal_view) = item.act_as::<TerminalView>(cx) else {
+                continue;
+            };
+            let item_id = item.item_id().as_u64();
+            if let Some(save) = terminal_view.read(cx).save_state(item_id, true, cx) {
+                save.detach();
+            }
+        }
+    }
+
     fn serialize(&mut self, cx: &mut ViewContext<Self>) {
+        // Task terminals are serialized too, and are restored as shells showing the task's output.
         let mut items_to_serialize = HashSet::default();
         let items = self
             .pane
             .read(cx)
             .items()
             .filter_map(|item| {
-                let terminal_view = item.act_as::<TerminalView>(cx)?;
-                if terminal_view.read(cx).terminal().read(cx).task().is_some() {
-                    None
-                } else {
-                    let id = item.item_id().as_u64();
-                    items_to_serialize.insert(id);
-                    Some(id)
-                }
+                item.act_as::<TerminalView>(cx)?;
+                let id = item.item_id().as_u64();
+                items_to_serialize.insert(id);
+                Some(id)
             })
             .collect::<Vec<_>>();
         let active_item_id = self
@@ -631,7 +711,7 @@ impl TerminalPanel {
         terminal_item_index: usize,
         terminal_to_replace: View<TerminalView>,
         cx: &mut ViewContext<'_, Self>,
-    ) -> Option<()> {
+    ) -> Option<Model<Terminal>> {
         let project = self
             .workspace
             .update(cx, |workspace, _| workspace.project().clone())
@@ -645,7 +725,7 @@ impl TerminalPanel {
                 .log_err()
         })?;
         terminal_to_replace.update(cx, |terminal_to_replace, cx| {
-            terminal_to_replace.set_terminal(new_terminal, cx);
+            terminal_to_replace.set_terminal(new_terminal.clone(), cx);
         });
 
         match reveal {
@@ -662,7 +742,7 @@ impl TerminalPanel {
             RevealStrategy::Never => {}
         }
 
-        Some(())
+        Some(new_terminal)
     }
 
     pub fn pane(&self) -> &View<Pane> {
@@ -690,6 +770,132 @@ async fn wait_for_terminals_tasks(
     let _: Vec<()> = join_all(pending_tasks).await;
 }
 
+/// The runs of the dependencies started by one composite task, so that a task
+/// several dependencies depend on is spawned only once.
+type DependencyRuns =
+    Rc<RefCell<HashMap<TaskId, Shared<LocalBoxFuture<'static, Result<(), Arc<anyhow::Error>>>>>>>;
+
+/// Spawns a single dependency of a task, resolving once it is ready.
+type SpawnDependency = Rc<dyn Fn(SpawnInTerminal) -> LocalBoxFuture<'static, Result<()>>>;
+
+/// Spawns dependencies in the terminal panel. A dependency is ready once it finished successfully,
+/// or, for background tasks, once its output matches its ready pattern.
+fn spawn_dependency_in_terminal(
+    terminal_panel: WeakView<TerminalPanel>,
+    cx: AsyncWindowContext,
+) -> SpawnDependency {
+    Rc::new(move |task| {
+        let terminal_panel = terminal_panel.clone();
+        let mut cx = cx.clone();
+        async move {
+            let ready_pattern = task
+                .ready_pattern
+                .as_deref()
+                .map(Regex::new)
+                .transpose()
+                .with_context(|| format!("Invalid ready pattern of task `{}`", task.full_label))?;
+
+            let terminal = terminal_panel
+                .update(&mut cx, |terminal_panel, cx| {
+                    terminal_panel.spawn_single_task(&task, cx)
+                })?
+                .await?;
+            if let Some(ready_pattern) = ready_pattern {
+                let ready = terminal
+                    .update(&mut cx, |terminal, cx| {
+                        terminal.wait_for_output(ready_pattern, cx)
+                    })?
+                    .await;
+                if ready {
+                    return Ok(());
+                }
+            } else {
+                terminal
+                    .update(&mut cx, |terminal, cx| terminal.wait_for_completed_task(cx))?
+                    .await;
+            }
+
+            let status = terminal.update(&mut cx, |terminal, _| {
+                terminal.task().map(|task_state| task_state.status)
+            })?;
+            match status {
+                Some(TaskStatus::Completed { success: true }) => Ok(()),
+                _ => Err(anyhow!("Task `{}` failed", task.full_label)),
+            }
+        }
+        .boxed_local()
+    })
+}
+
+/// Runs the dependencies of a task, resolving once all of them are ready.
+fn run_task_dependencies(
+    dependencies: Vec<SpawnInTerminal>,
+    depends_order: DependsOrder,
+    spawn: SpawnDependency,
+    runs: DependencyRuns,
+) -> LocalBoxFuture<'static, Result<()>> {
+    async move {
+        match depends_order {
+            DependsOrder::Sequence => {
+                for dependency in dependencies {
+                    run_task_dependency(dependency, spawn.clone(), runs.clone()).await?;
+                }
+            }
+            DependsOrder::Parallel => {
+                let dependencies = dependencies
+                    .into_iter()
+                    .map(|dependency| run_task_dependency(dependency, spawn.clone(), runs.clone()));
+                join_all(dependencies)
+                    .await
+                    .into_iter()
+                    .collect::<Result<Vec<_>>>()?;
+            }
+        }
+        Ok(())
+    }
+    .boxed_local()
+}
+
+fn run_task_dependency(
+    task: SpawnInTerminal,
+    spawn: SpawnDependency,
+    runs: DependencyRuns,
+) -> LocalBoxFuture<'static, Result<()>> {
+    let run = runs
+        .borrow_mut()
+        .entry(task.id.clone())
+        .or_insert_with(|| {
+            run_task_dependency_once(task, spawn, runs.clone())
+                .map(|result| result.map_err(Arc::new))
+                .boxed_local()
+                .shared()
+        })
+        .clone();
+    async move { run.await.map_err(|error| anyhow!("{error:#}")) }.boxed_local()
+}
+
+fn run_task_dependency_once(
+    task: SpawnInTerminal,
+    spawn: SpawnDependency,
+    runs: DependencyRuns,
+) -> LocalBoxFuture<'static, Result<()>> {
+    async move {
+        run_task_dependencies(
+            task.depends_on.clone(),
+            task.depends_order,
+            spawn.clone(),
+            runs,
+        )
+        .await?;
+        // Composite tasks only run their dependencies
+        if task.command.is_empty() {
+            return Ok(());
+        }
+        spawn(task).await
+    }
+    .boxed_local()
+}
+
 fn add_paths_to_terminal(pane: &mut Pane, paths: &[PathBuf], cx: &mut ViewContext<'_, Pane>) {
     if let Some(terminal_view) = pane
         .active_item()
@@ -827,3 +1033,246 @@ struct SerializedTerminalPanel {
     width: Option<Pixels>,
     height: Option<Pixels>,
 }
+
+#[cfg(test)]
+mod tests {
+    use gpui::TestAppContext;
+
+    use super::*;
+
+    #[gpui::test]
+    async fn test_sequential_dependencies(cx: &mut TestAppContext) {
+        let tasks = FakeTasks::new();
+        let build = task("build", Vec::new());
+        let test = task("test", Vec::new());
+        let run = cx.foreground_executor().spawn(run_task_dependencies(
+            vec![build, test],
+            DependsOrder::Sequence,
+            tasks.spawn(),
+            DependencyRuns::default(),
+        ));
+
+        cx.run_until_parked();
+        assert_eq!(tasks.started(), ["build"]);
+        tasks.complete("build", Ok(()));
+        cx.run_until_parked();
+        assert_eq!(tasks.started(), ["build", "test"]);
+        tasks.complete("test", Ok(()));
+        run.await.unwrap();
+    }
+
+    #[gpui::test]
+    async fn test_parallel_dependencies(cx: &mut TestAppContext) {
+        let tasks = FakeTasks::new();
+        let build = task("build", Vec::new());
+        let lint = task("lint", Vec::new());
+        let run = cx.foreground_executor().spawn(run_task_dependencies(
+            vec![build, lint],
+            DependsOrder::Parallel,
+            tasks.spawn(),
+            DependencyRuns::default(),
+        ));
+
+        cx.run_until_parked();
+        assert_eq!(tasks.started(), ["build", "lint"]);
+        tasks.complete("lint", Ok(()));
+        tasks.complete("build", Ok(()));
+        run.await.unwrap();
+    }
+
+    #[gpui::test]
+    async fn test_shared_dependency_runs_once(cx: &mut TestAppContext) {
+        let tasks = FakeTasks::new();
+        let build = task("build", Vec::new());
+        let serve = task("serve", vec![build.clone()]);
+        // Composite tasks are not spawned, only their dependencies are.
+        let mut all = task("all", vec![build.clone(), serve]);
+        all.command = String::new();
+        let run = cx.foreground_executor().spawn(run_task_dependencies(
+            vec![all, build],
+            DependsOrder::Parallel,
+            tasks.spawn(),
+            DependencyRuns::default(),
+        ));
+
+        cx.run_until_parked();
+        assert_eq!(tasks.started(), ["build"]);
+        tasks.complete("build", Ok(()));
+        cx.run_until_parked();
+        assert_eq!(
+            tasks.started(),
+            ["build", "serve"],
+            "Each task should run once, even if several tasks depend on it"
+        );
+        tasks.complete("serve", Ok(()));
+        run.await.unwrap();
+    }
+
+    #[gpui::test]
+    async fn test_failing_dependency(cx: &mut TestAppContext) {
+        let tasks = FakeTasks::new();
+        let build = task("build", Vec::new());
+        let deploy = task("deploy", vec![build]);
+        let e2e = task("e2e", Vec::new());
+        let run = cx.foreground_executor().spawn(run_task_dependencies(
+            vec![deploy, e2e],
+            DependsOrder::Sequence,
+            tasks.spawn(),
+            DependencyRuns::default(),
+        ));
+
+        cx.run_until_parked();
+        tasks.complete("build", Err(anyhow!("Task `build` failed")));
+        let error = run.await.unwrap_err();
+        assert_eq!(error.to_string(), "Task `build` failed");
+        assert_eq!(
+            tasks.started(),
+            ["build"],
+            "No task should run after a dependency failed"
+        );
+    }
+
+    /// Runs a task with a failing dependency in real terminals, checking that the dependency's
+    /// failure is detected and shown.
+    #[cfg(unix)]
+    #[gpui::test]
+    async fn test_failing_dependency_in_terminal(cx: &mut TestAppContext) {
+        use project::Project;
+        use settings::SettingsStore;
+        use std::time::{Duration, Instant};
+        use workspace::AppState;
+
+        cx.executor().allow_parking();
+        let params = cx.update(AppState::test);
+        cx.update(|cx| {
+            editor::init(cx);
+            Project::init_settings(cx);
+            language::init(cx);
+            crate::init(cx);
+            SettingsStore::update_global(cx, |store, cx| {
+                store.update_user_settings::<TerminalSettings>(cx, |settings| {
+                    settings.shell = Some(Shell::Program("/bin/sh".to_string()));
+                });
+            });
+        });
+        let project = Project::test(params.fs.clone(), [], cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project, cx));
+        let terminal_panel = workspace.update(cx, |workspace, cx| {
+            cx.new_view(|cx| TerminalPanel::new(workspace, cx))
+        });
+
+        let mut build = task("build", Vec::new());
+        build.command = "exit 1".to_string();
+        let deploy = task("deploy", vec![build]);
+        let run = terminal_panel.update(cx, |terminal_panel, cx| {
+            terminal_panel.spawn_task_with_dependencies(deploy, cx)
+        });
+        let (result_tx, mut result_rx) = oneshot::channel();
+        cx.spawn(|_| async move {
+            result_tx.send(run.await).ok();
+        })
+        .detach();
+        // The shell runs outside of the test executor, so its progress is polled for, while
+        // advancing the fake clock for the terminal's batched event checks.
+        let started_at = Instant::now();
+        let result = loop {
+            cx.executor().advance_clock(Duration::from_millis(100));
+            cx.run_until_parked();
+            if let Ok(Some(result)) = result_rx.try_recv() {
+                break result;
+            }
+            assert!(
+                started_at.elapsed() < Duration::from_secs(60),
+                "Timed out waiting for the task to run"
+            );
+            std::thread::sleep(Duration::from_millis(10));
+        };
+        result.unwrap();
+
+        let statuses = terminal_panel.update(cx, |terminal_panel, cx| {
+            terminal_panel
+                .pane
+                .read(cx)
+                .items()
+                .filter_map(|item| item.act_as::<TerminalView>(cx))
+                .filter_map(|terminal_view| {
+                    let task_state = terminal_view.read(cx).terminal().read(cx).task()?;
+                    Some((task_state.full_label.clone(), task_state.status))
+                })
+                .collect::<Vec<_>>()
+        });
+        assert_eq!(
+            statuses,
+            [(
+                "build".to_string(),
+                TaskStatus::Completed { success: false }
+            )],
+            "The task should not run after its dependency failed"
+        );
+        workspace.update(cx, |workspace, _| {
+            assert_eq!(workspace.notification_ids().len(), 1);
+        });
+    }
+
+    /// Tasks that are spawned by recording their labels, and become ready once completed
+    /// by the test.
+    struct FakeTasks {
+        started: RefCell<Vec<String>>,
+        completions: RefCell<HashMap<String, oneshot::Sender<Result<()>>>>,
+    }
+
+    impl FakeTasks {
+        fn new() -> Rc<Self> {
+            Rc::new(Self {
+                started: RefCell::default(),
+                completions: RefCell::default(),
+            })
+        }
+
+        fn spawn(self: &Rc<Self>) -> SpawnDependency {
+            let tasks = self.clone();
+            Rc::new(move |task| {
+                let (completion_tx, completion_rx) = oneshot::channel();
+                tasks.started.borrow_mut().push(task.full_label.clone());
+                tasks
+                    .completions
+                    .borrow_mut()
+                    .insert(task.full_label, completion_tx);
+                async move { completion_rx.await? }.boxed_local()
+            })
+        }
+
+        fn started(&self) -> Vec<String> {
+            self.started.borrow().clone()
+        }
+
+        fn complete(&self, label: &str, result: Result<()>) {
+            let completion_tx = self
+                .completions
+                .borrow_mut()
+                .remove(label)
+                .unwrap_or_else(|| panic!("Task `{label}` was not started"));
+            completion_tx.send(result).ok();
+        }
+    }
+
+    fn task(label: &str, depends_on: Vec<SpawnInTerminal>) -> SpawnInTerminal {
+        SpawnInTerminal {
+            id: TaskId(label.to_string()),
+            full_label: label.to_string(),
+            label: label.to_string(),
+            command: format!("echo {label}"),
+            args: Vec::new(),
+            command_label: format!("echo {label}"),
+            cwd: None,
+            env: HashMap::default(),
+            use_new_terminal: false,
+            allow_concurrent_runs: false,
+            reveal: RevealStrategy::Never,
+            problem_matchers: Vec::new(),
+            depends_on,
+            depends_order: DependsOrder::Sequence,
+            ready_pattern: None,
+        }
+    }
+}
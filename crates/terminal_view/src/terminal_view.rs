@@ -6,10 +6,10 @@ use collections::HashSet;
 use editor::{scroll::Autoscroll, Editor};
 use futures::{stream::FuturesUnordered, StreamExt};
 use gpui::{
-    anchored, deferred, div, impl_actions, AnyElement, AppContext, DismissEvent, EventEmitter,
-    FocusHandle, FocusableView, KeyContext, KeyDownEvent, Keystroke, Model, MouseButton,
-    MouseDownEvent, Pixels, Render, ScrollWheelEvent, Styled, Subscription, Task, View,
-    VisualContext, WeakView,
+    anchored, deferred, div, impl_actions, AnyElement, AppContext, ClipboardItem, DismissEvent,
+    EventEmitter, FocusHandle, FocusableView, KeyContext, KeyDownEvent, Keystroke, Model,
+    MouseButton, MouseDownEvent, Pixels, Render, ScrollWheelEvent, Styled, Subscription, Task,
+    View, VisualContext, WeakView,
 };
 use language::Bias;
 use persistence::TERMINAL_DB;
@@ -21,9 +21,9 @@ use terminal::{
         term::{search::RegexSearch, TermMode},
     },
     terminal_settings::{TerminalBlink, TerminalSettings, WorkingDirectory},
-    Clear, Copy, Event, MaybeNavigationTarget, Paste, ScrollLineDown, ScrollLineUp, ScrollPageDown,
-    ScrollPageUp, ScrollToBottom, ScrollToTop, ShowCharacterPalette, TaskStatus, Terminal,
-    TerminalSize,
+    Clear, Copy, CopyLastCommandOutput, Event, MaybeNavigationTarget, Paste, ScrollLineDown,
+    ScrollLineUp, ScrollPageDown, ScrollPageUp, ScrollToBottom, ScrollToNextPrompt,
+    ScrollToPreviousPrompt, ScrollToTop, ShowCharacterPalette, TaskStatus, Terminal, TerminalSize,
 };
 use terminal_element::{is_blank, TerminalElement};
 use ui::{h_flex, prelude::*, ContextMenu, Icon, IconName, Label, Tooltip};
@@ -33,7 +33,7 @@ use workspace::{
     notifications::NotifyResultExt,
     register_deserializable_item,
     searchable::{SearchEvent, SearchOptions, SearchableItem, SearchableItemHandle},
-    CloseActiveItem, NewCenterTerminal, OpenVisible, Pane, ToolbarItemLocation, Workspace,
+    CloseActiveItem, ItemId, NewCenterTerminal, OpenVisible, Pane, ToolbarItemLocation, Workspace,
     WorkspaceId,
 };
 
@@ -170,6 +170,20 @@ impl TerminalView {
         let focus_out = cx.on_focus_out(&focus_handle, |terminal_view, _event, cx| {
             terminal_view.focus_out(cx);
         });
+        let app_quit = cx.on_app_quit({
+            let terminal_view = cx.view().downgrade();
+            move |cx| {
+                let save = terminal_view.upgrade().and_then(|terminal_view| {
+                    let item_id = terminal_view.entity_id().as_u64();
+                    terminal_view.read(cx).save_state(item_id, true, cx)
+                });
+                async move {
+                    if let Some(save) = save {
+                        save.await;
+                    }
+                }
+            }
+        });
 
         Self {
             terminal,
@@ -189,6 +203,7 @@ impl TerminalView {
             _subscriptions: vec![
                 focus_in,
                 focus_out,
+                app_quit,
                 cx.observe_global::<SettingsStore>(Self::settings_changed),
             ],
             _terminal_subscriptions: terminal_subscriptions,
@@ -199,6 +214,52 @@ impl TerminalView {
         &self.terminal
     }
 
+    /// Saves the working directory and title of the terminal, which are restored when the
+    /// workspace is reopened, along with its scrollback if `save_scrollback` is set.
+    ///
+    /// Copying the scrollback blocks the terminal, so it is only saved when the terminal
+    /// is serialized and when the app quits.
+    fn save_state(
+        &self,
+        item_id: ItemId,
+        save_scrollback: bool,
+        cx: &AppContext,
+    ) -> Option<Task<()>> {
+        let workspace_id = self.workspace_id?;
+        let terminal = self.terminal.read(cx);
+        let working_directory = terminal.get_cwd().unwrap_or_default();
+        let title = match terminal.task() {
+            Some(task) => task.full_label.clone(),
+            None => terminal.breadcrumb_text.clone(),
+        };
+        if !save_scrollback {
+            return Some(cx.background_executor().spawn(async move {
+                TERMINAL_DB
+                    .save_working_directory_and_title(
+                        item_id,
+                        workspace_id,
+                        working_directory,
+                        title,
+                    )
+                    .await
+                    .log_err();
+            }));
+        }
+
+        let saved_scrollback_lines = TerminalSettings::get_global(cx).saved_scrollback_lines;
+        let scrollback = if saved_scrollback_lines == 0 {
+            String::new()
+        } else {
+            terminal.last_lines(saved_scrollback_lines)
+        };
+        Some(cx.background_executor().spawn(async move {
+            TERMINAL_DB
+                .save_terminal_state(item_id, workspace_id, working_directory, title, scrollback)
+                .await
+                .log_err();
+        }))
+    }
+
     pub fn has_bell(&self) -> bool {
         self.has_bell
     }
@@ -397,6 +458,28 @@ impl TerminalView {
         cx.notify();
     }
 
+    fn scroll_to_previous_prompt(
+        &mut self,
+        _: &ScrollToPreviousPrompt,
+        cx: &mut ViewContext<Self>,
+    ) {
+        self.terminal
+            .update(cx, |term, _| term.scroll_to_previous_prompt());
+        cx.notify();
+    }
+
+    fn scroll_to_next_prompt(&mut self, _: &ScrollToNextPrompt, cx: &mut ViewContext<Self>) {
+        self.terminal
+            .update(cx, |term, _| term.scroll_to_next_prompt());
+        cx.notify();
+    }
+
+    fn copy_last_command_output(&mut self, _: &CopyLastCommandOutput, cx: &mut ViewContext<Self>) {
+        if let Some(output) = self.terminal.read(cx).last_command_output() {
+            cx.write_to_clipboard(ClipboardItem::new(output));
+        }
+    }
+
     pub fn should_show_cursor(&self, focused: bool, cx: &mut gpui::ViewContext<Self>) -> bool {
         //Don't blink the cursor when not focused, blinking is disabled, or paused
         if !focused
@@ -612,21 +695,8 @@ fn subscribe_for_terminal_events(
 
             Event::TitleChanged => {
                 cx.emit(ItemEvent::UpdateTab);
-                let terminal = this.terminal().read(cx);
-                if terminal.task().is_none() {
-                    if let Some(cwd) = terminal.get_cwd() {
-                        let item_id = cx.entity_id();
-                        if let Some(workspace_id) = this.workspace_id {
-                            cx.background_executor()
-                                .spawn(async move {
-                                    TERMINAL_DB
-                                        .save_working_directory(item_id.as_u64(), workspace_id, cwd)
-                                        .await
-                                        .log_err();
-                                })
-                                .detach();
-                        }
-                    }
+                if let Some(save) = this.save_state(cx.entity_id().as_u64(), false, cx) {
+                    save.detach();
                 }
             }
 
@@ -911,6 +981,9 @@ impl Render for TerminalView {
             .on_action(cx.listener(TerminalView::scroll_page_down))
             .on_action(cx.listener(TerminalView::scroll_to_top))
             .on_action(cx.listener(TerminalView::scroll_to_bottom))
+            .on_action(cx.listener(TerminalView::scroll_to_previous_prompt))
+            .on_action(cx.listener(TerminalView::scroll_to_next_prompt))
+            .on_action(cx.listener(TerminalView::copy_last_command_output))
             .on_action(cx.listener(TerminalView::show_character_palette))
             .on_action(cx.listener(TerminalView::select_all))
             .on_key_down(cx.listener(Self::key_down))
@@ -1085,12 +1158,18 @@ impl Item for TerminalView {
     ) -> Task<anyhow::Result<View<Self>>> {
         let window = cx.window_handle();
         cx.spawn(|pane, mut cx| async move {
+            let (from_db, title, scrollback) = TERMINAL_DB
+                .get_terminal_state(item_id, workspace_id)
+                .log_err()
+                .flatten()
+                .map_or(
+                    (None, None, None),
+                    |(working_directory, title, scrollback)| {
+                        (Some(working_directory), title, scrollback)
+                    },
+                );
             let cwd = cx
                 .update(|cx| {
-                    let from_db = TERMINAL_DB
-                        .get_working_directory(item_id, workspace_id)
-                        .log_err()
-                        .flatten();
                     if from_db
                         .as_ref()
                         .is_some_and(|from_db| !from_db.as_os_str().is_empty())
@@ -1109,8 +1188,14 @@ impl Item for TerminalView {
                 .flatten();
 
             let terminal = project.update(&mut cx, |project, cx| {
-                project.create_terminal(cwd, None, window, cx)
+                match scrollback.filter(|scrollback| !scrollback.is_empty()) {
+                    Some(scrollback) => project.restore_terminal(cwd, scrollback, window, cx),
+                    None => project.create_terminal(cwd, None, window, cx),
+                }
             })??;
+            if let Some(title) = title.filter(|title| !title.is_empty()) {
+                terminal.update(&mut cx, |terminal, _| terminal.breadcrumb_text = title)?;
+            }
             pane.update(&mut cx, |_, cx| {
                 cx.new_view(|cx| TerminalView::new(terminal, workspace, Some(workspace_id), cx))
             })
@@ -1118,14 +1203,12 @@ impl Item for TerminalView {
     }
 
     fn added_to_workspace(&mut self, workspace: &mut Workspace, cx: &mut ViewContext<Self>) {
-        if self.terminal().read(cx).task().is_none() {
-            if let Some((new_id, old_id)) = workspace.database_id().zip(self.workspace_id) {
-                cx.background_executor()
-                    .spawn(TERMINAL_DB.update_workspace_id(new_id, old_id, cx.entity_id().as_u64()))
-                    .detach();
-            }
-            self.workspace_id = workspace.database_id();
+        if let Some((new_id, old_id)) = workspace.database_id().zip(self.workspace_id) {
+            cx.background_executor()
+                .spawn(TERMINAL_DB.update_workspace_id(new_id, old_id, cx.entity_id().as_u64()))
+                .detach();
         }
+        self.workspace_id = workspace.database_id();
     }
 
     fn to_item_events(event: &Self::Event, mut f: impl FnMut(ItemEvent)) {
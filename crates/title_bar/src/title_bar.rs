@@ -20,7 +20,7 @@ use project::{Project, RepositoryEntry};
 use recent_projects::RecentProjects;
 use rpc::proto::DevServerStatus;
 use smallvec::SmallVec;
-use std::sync::Arc;
+use std::{fmt::Write, sync::Arc};
 use theme::ActiveTheme;
 use ui::{
     h_flex, prelude::*, Avatar, Button, ButtonLike, ButtonStyle, ContextMenu, Icon, IconName,
@@ -380,10 +380,18 @@ impl TitleBar {
             names_and_branches.next().flatten()
         };
         let workspace = self.workspace.upgrade()?;
-        let branch_name = entry
+        let mut branch_name = entry
             .as_ref()
             .and_then(RepositoryEntry::branch)
             .map(|branch| util::truncate_and_trailoff(&branch, MAX_BRANCH_NAME_LENGTH))?;
+        if let Some(ahead_behind) = entry.as_ref().and_then(RepositoryEntry::ahead_behind) {
+            if ahead_behind.ahead > 0 {
+                write!(branch_name, " ↑{}", ahead_behind.ahead).ok();
+            }
+            if ahead_behind.behind > 0 {
+                write!(branch_name, " ↓{}", ahead_behind.behind).ok();
+            }
+        }
         Some(
             Button::new("project_branch_trigger", branch_name)
                 .color(Color::Muted)
@@ -0,0 +1,124 @@
+use editor::Editor;
+use git::remote::RemoteCredentials;
+use gpui::{
+    div, AppContext, DismissEvent, EventEmitter, FocusHandle, FocusableView, InteractiveElement,
+    IntoElement, ParentElement, Render, Styled, View, ViewContext, VisualContext,
+};
+use project::GitCredentialsRequest;
+use ui::{h_flex, v_flex, ActiveTheme, Color, Label, LabelCommon, LabelSize, StyledExt};
+use workspace::{ModalView, Workspace};
+
+/// Shows a [`CredentialsPrompt`] when a git operation of the workspace's project
+/// needs credentials.
+pub(crate) fn observe_credentials_requests(
+    workspace: &mut Workspace,
+    cx: &mut ViewContext<Workspace>,
+) {
+    cx.subscribe(workspace.project(), |workspace, _, event, cx| {
+        if let project::Event::GitCredentialsPrompt(request) = event {
+            let request = request.clone();
+            workspace.toggle_modal(cx, |cx| CredentialsPrompt::new(request, cx));
+        }
+    })
+    .detach();
+}
+
+/// A modal asking for the username and password of a git remote. Dismissing it fails the
+/// operation that requested them.
+pub struct CredentialsPrompt {
+    request: Option<GitCredentialsRequest>,
+    username_editor: View<Editor>,
+    password_editor: View<Editor>,
+}
+
+impl CredentialsPrompt {
+    fn new(request: GitCredentialsRequest, cx: &mut ViewContext<Self>) -> Self {
+        let username_editor = cx.new_view(|cx| {
+            let mut editor = Editor::single_line(cx);
+            editor.set_placeholder_text("Username", cx);
+            editor
+        });
+        let password_editor = cx.new_view(|cx| {
+            let mut editor = Editor::single_line(cx);
+            editor.set_placeholder_text("Password or token", cx);
+            editor.set_masked(true, cx);
+            editor
+        });
+        Self {
+            request: Some(request),
+            username_editor,
+            password_editor,
+        }
+    }
+
+    fn confirm(&mut self, _: &menu::Confirm, cx: &mut ViewContext<Self>) {
+        let username = self.username_editor.read(cx).text(cx);
+        let password = self.password_editor.read(cx).text(cx);
+        if password.is_empty() && self.username_editor.focus_handle(cx).is_focused(cx) {
+            cx.focus_view(&self.password_editor);
+            return;
+        }
+        if let Some(request) = self.request.take() {
+            let credentials = RemoteCredentials { username, password };
+            cx.background_executor()
+                .spawn(request.respond(Some(credentials)))
+                .detach();
+        }
+        cx.emit(DismissEvent);
+    }
+
+    fn cancel(&mut self, _: &menu::Cancel, cx: &mut ViewContext<Self>) {
+        cx.emit(DismissEvent);
+    }
+}
+
+impl ModalView for CredentialsPrompt {}
+impl EventEmitter<DismissEvent> for CredentialsPrompt {}
+
+impl FocusableView for CredentialsPrompt {
+    fn focus_handle(&self, cx: &AppContext) -> FocusHandle {
+        self.username_editor.focus_handle(cx)
+    }
+}
+
+impl Render for CredentialsPrompt {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let (operation, url) = self
+            .request
+            .as_ref()
+            .map(|request| (request.operation.clone(), request.url.clone()))
+            .unwrap_or_default();
+        let title = match url {
+            Some(url) => format!("{operation} needs credentials for {url}"),
+            None => format!("{operation} needs credentials"),
+        };
+        let field = |editor: &View<Editor>| {
+            div()
+                .px_2()
+                .py_1()
+                .rounded_md()
+                .border_1()
+                .border_color(cx.theme().colors().border)
+                .child(editor.clone())
+        };
+
+        v_flex()
+            .elevation_2(cx)
+            .key_context("CredentialsPrompt")
+            .on_action(cx.listener(Self::confirm))
+            .on_action(cx.listener(Self::cancel))
+            .w_96()
+            .p_2()
+            .gap_2()
+            .child(Label::new(title))
+            .child(field(&self.username_editor))
+            .child(field(&self.password_editor))
+            .child(
+                h_flex().child(
+                    Label::new("Press enter to retry, or escape to cancel.")
+                        .color(Color::Muted)
+                        .size(LabelSize::Small),
+                ),
+            )
+    }
+}
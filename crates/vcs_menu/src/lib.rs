@@ -1,13 +1,17 @@
+mod credentials_prompt;
+mod stash_list;
+
 use anyhow::{Context, Result};
 use fuzzy::{StringMatch, StringMatchCandidate};
-use git::repository::Branch;
+use git::{remote::PullMode, repository::Branch};
 use gpui::{
     actions, rems, AnyElement, AppContext, DismissEvent, EventEmitter, FocusHandle, FocusableView,
-    InteractiveElement, IntoElement, ParentElement, Render, SharedString, Styled, Subscription,
-    Task, View, ViewContext, VisualContext, WindowContext,
+    InteractiveElement, IntoElement, ModelContext, ParentElement, Render, SharedString, Styled,
+    Subscription, Task, View, ViewContext, VisualContext, WindowContext,
 };
 use picker::{Picker, PickerDelegate};
-use std::{ops::Not, sync::Arc};
+use project::{Project, ProjectPath};
+use std::{ops::Not, path::Path, sync::Arc};
 use ui::{
     h_flex, v_flex, Button, ButtonCommon, Clickable, Color, HighlightedLabel, Label, LabelCommon,
     LabelSize, ListItem, ListItemSpacing, Selectable,
@@ -16,17 +20,100 @@ use util::ResultExt;
 use workspace::notifications::NotificationId;
 use workspace::{ModalView, Toast, Workspace};
 
+pub use stash_list::StashList;
+
 actions!(branches, [OpenRecent]);
 
+actions!(
+    git,
+    [
+        Fetch,
+        Pull,
+        PullRebase,
+        Push,
+        StashChanges,
+        StashPop,
+        OpenStashes
+    ]
+);
+
 pub fn init(cx: &mut AppContext) {
-    cx.observe_new_views(|workspace: &mut Workspace, _| {
+    cx.observe_new_views(|workspace: &mut Workspace, cx| {
         workspace.register_action(|workspace, action, cx| {
             BranchList::open(workspace, action, cx).log_err();
         });
+        workspace.register_action(|workspace, _: &Fetch, cx| {
+            run_git_operation(workspace, cx, |project, work_directory, cx| {
+                project.git_fetch(work_directory, cx)
+            });
+        });
+        workspace.register_action(|workspace, _: &Pull, cx| {
+            run_git_operation(workspace, cx, |project, work_directory, cx| {
+                project.git_pull(work_directory, PullMode::FastForwardOnly, cx)
+            });
+        });
+        workspace.register_action(|workspace, _: &PullRebase, cx| {
+            run_git_operation(workspace, cx, |project, work_directory, cx| {
+                project.git_pull(work_directory, PullMode::Rebase, cx)
+            });
+        });
+        workspace.register_action(|workspace, _: &Push, cx| {
+            run_git_operation(workspace, cx, |project, work_directory, cx| {
+                project.git_push(work_directory, cx)
+            });
+        });
+        workspace.register_action(|workspace, _: &StashChanges, cx| {
+            run_git_operation(workspace, cx, |project, work_directory, cx| {
+                project.git_stash(work_directory, None, cx)
+            });
+        });
+        workspace.register_action(|workspace, _: &StashPop, cx| {
+            run_git_operation(workspace, cx, |project, work_directory, cx| {
+                project.git_pop_stash(work_directory, 0, cx)
+            });
+        });
+        workspace.register_action(StashList::open);
+        credentials_prompt::observe_credentials_requests(workspace, cx);
     })
     .detach();
 }
 
+/// The work directory of the repository containing the first worktree,
+/// whose branch is shown in the title bar.
+fn root_work_directory(project: &Project, cx: &AppContext) -> Option<ProjectPath> {
+    let worktree = project.visible_worktrees(cx).next()?.read(cx);
+    worktree.root_git_entry()?;
+    Some(ProjectPath {
+        worktree_id: worktree.id(),
+        path: Arc::from(Path::new("")),
+    })
+}
+
+/// Starts a git operation on the repository of the first worktree. Its progress and
+/// failure are shown by the activity indicator.
+fn run_git_operation(
+    workspace: &mut Workspace,
+    cx: &mut ViewContext<Workspace>,
+    operation: impl FnOnce(&mut Project, ProjectPath, &mut ModelContext<Project>) -> Task<Result<()>>,
+) {
+    let project = workspace.project().clone();
+    let Some(work_directory) = root_work_directory(project.read(cx), cx) else {
+        struct NoRepository;
+        workspace.show_toast(
+            Toast::new(
+                NotificationId::unique::<NoRepository>(),
+                "The project is not in a git repository",
+            )
+            .autohide(),
+            cx,
+        );
+        return;
+    };
+    project
+        .update(cx, |project, cx| operation(project, work_directory, cx))
+        .detach_and_log_err(cx);
+}
+
 pub struct BranchList {
     pub picker: View<Picker<BranchListDelegate>>,
     rem_width: f32,
@@ -0,0 +1,265 @@
+use anyhow::Context;
+use fuzzy::{StringMatch, StringMatchCandidate};
+use git::stash::Stash;
+use gpui::{
+    rems, AnyElement, AppContext, DismissEvent, EventEmitter, FocusHandle, FocusableView,
+    InteractiveElement, IntoElement, Model, ParentElement, Render, SharedString, Styled,
+    Subscription, Task, View, ViewContext, VisualContext, WindowContext,
+};
+use picker::{Picker, PickerDelegate};
+use project::{Project, ProjectPath};
+use std::sync::Arc;
+use ui::{
+    h_flex, v_flex, Button, ButtonCommon, Clickable, Color, HighlightedLabel, Label, LabelCommon,
+    LabelSize, ListItem, ListItemSpacing, Selectable,
+};
+use util::ResultExt;
+use workspace::{notifications::NotifyTaskExt, ModalView, Workspace};
+
+use crate::{root_work_directory, OpenStashes};
+
+/// A picker of the stash entries of the repository in the title bar, applying, popping or
+/// dropping the selected one.
+pub struct StashList {
+    pub picker: View<Picker<StashListDelegate>>,
+    _subscription: Subscription,
+}
+
+impl StashList {
+    fn new(delegate: StashListDelegate, cx: &mut ViewContext<Self>) -> Self {
+        let picker = cx.new_view(|cx| Picker::uniform_list(delegate, cx));
+        let _subscription = cx.subscribe(&picker, |_, _, _, cx| cx.emit(DismissEvent));
+        Self {
+            picker,
+            _subscription,
+        }
+    }
+
+    pub fn open(workspace: &mut Workspace, _: &OpenStashes, cx: &mut ViewContext<Workspace>) {
+        let project = workspace.project().clone();
+        let work_directory = root_work_directory(project.read(cx), cx);
+        cx.spawn(|workspace, mut cx| async move {
+            let work_directory =
+                work_directory.context("failed to get root repository for first worktree")?;
+            let stashes = project
+                .update(&mut cx, |project, cx| {
+                    project.git_stashes(work_directory.clone(), cx)
+                })?
+                .await?;
+            workspace.update(&mut cx, |workspace, cx| {
+                let delegate = StashListDelegate {
+                    project,
+                    work_directory,
+                    stashes,
+                    matches: Vec::new(),
+                    selected_index: 0,
+                };
+                workspace.toggle_modal(cx, |cx| StashList::new(delegate, cx));
+            })
+        })
+        .detach_and_notify_err(cx);
+    }
+}
+
+impl ModalView for StashList {}
+impl EventEmitter<DismissEvent> for StashList {}
+
+impl FocusableView for StashList {
+    fn focus_handle(&self, cx: &AppContext) -> FocusHandle {
+        self.picker.focus_handle(cx)
+    }
+}
+
+impl Render for StashList {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        v_flex()
+            .w(rems(34.))
+            .child(self.picker.clone())
+            .on_mouse_down_out(cx.listener(|this, _, cx| {
+                this.picker.update(cx, |this, cx| {
+                    this.cancel(&Default::default(), cx);
+                })
+            }))
+    }
+}
+
+/// What to do with the selected stash entry.
+#[derive(Clone, Copy)]
+enum StashAction {
+    Apply,
+    Pop,
+    Drop,
+}
+
+pub struct StashListDelegate {
+    project: Model<Project>,
+    work_directory: ProjectPath,
+    stashes: Vec<Stash>,
+    matches: Vec<StringMatch>,
+    selected_index: usize,
+}
+
+impl StashListDelegate {
+    fn selected_stash(&self) -> Option<&Stash> {
+        let candidate_id = self.matches.get(self.selected_index)?.candidate_id;
+        self.stashes.get(candidate_id)
+    }
+
+    fn run(&self, action: StashAction, cx: &mut ViewContext<Picker<Self>>) {
+        let Some(index) = self.selected_stash().map(|stash| stash.index) else {
+            return;
+        };
+        let work_directory = self.work_directory.clone();
+        self.project
+            .update(cx, |project, cx| match action {
+                StashAction::Apply => project.git_apply_stash(work_directory, index, cx),
+                StashAction::Pop => project.git_pop_stash(work_directory, index, cx),
+                StashAction::Drop => project.git_drop_stash(work_directory, index, cx),
+            })
+            .detach_and_log_err(cx);
+        cx.emit(DismissEvent);
+    }
+}
+
+impl PickerDelegate for StashListDelegate {
+    type ListItem = ListItem;
+
+    fn placeholder_text(&self, _cx: &mut WindowContext) -> Arc<str> {
+        "Select stash...".into()
+    }
+
+    fn no_matches_text(&self, _cx: &mut WindowContext) -> SharedString {
+        if self.stashes.is_empty() {
+            "The stash is empty".into()
+        } else {
+            "No matches".into()
+        }
+    }
+
+    fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(&mut self, ix: usize, _: &mut ViewContext<Picker<Self>>) {
+        self.selected_index = ix;
+    }
+
+    fn update_matches(&mut self, query: String, cx: &mut ViewContext<Picker<Self>>) -> Task<()> {
+        let candidates = self
+            .stashes
+            .iter()
+            .enumerate()
+            .map(|(ix, stash)| StringMatchCandidate {
+                id: ix,
+                char_bag: stash.message.chars().collect(),
+                string: stash.message.clone(),
+            })
+            .collect::<Vec<_>>();
+        cx.spawn(move |picker, mut cx| async move {
+            let matches = if query.is_empty() {
+                candidates
+                    .into_iter()
+                    .map(|candidate| StringMatch {
+                        candidate_id: candidate.id,
+                        string: candidate.string,
+                        positions: Vec::new(),
+                        score: 0.0,
+                    })
+                    .collect()
+            } else {
+                let mut matches = fuzzy::match_strings(
+                    &candidates,
+                    &query,
+                    true,
+                    10000,
+                    &Default::default(),
+                    cx.background_executor().clone(),
+                )
+                .await;
+                // The newest stash entries come first, like in `git stash list`.
+                matches.sort_unstable_by_key(|m| m.candidate_id);
+                matches
+            };
+            picker
+                .update(&mut cx, |picker, _| {
+                    let delegate = &mut picker.delegate;
+                    delegate.matches = matches;
+                    delegate.selected_index = delegate
+                        .selected_index
+                        .min(delegate.matches.len().saturating_sub(1));
+                })
+                .log_err();
+        })
+    }
+
+    fn confirm(&mut self, secondary: bool, cx: &mut ViewContext<Picker<Self>>) {
+        let action = if secondary {
+            StashAction::Apply
+        } else {
+            StashAction::Pop
+        };
+        self.run(action, cx);
+    }
+
+    fn dismissed(&mut self, cx: &mut ViewContext<Picker<Self>>) {
+        cx.emit(DismissEvent);
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        _cx: &mut ViewContext<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let hit = &self.matches[ix];
+        let stash = self.stashes.get(hit.candidate_id)?;
+        Some(
+            ListItem::new(SharedString::from(format!("stash-{ix}")))
+                .inset(true)
+                .spacing(ListItemSpacing::Sparse)
+                .selected(selected)
+                .start_slot(HighlightedLabel::new(
+                    hit.string.clone(),
+                    hit.positions.clone(),
+                ))
+                .end_slot(
+                    Label::new(stash.revision())
+                        .color(Color::Muted)
+                        .size(LabelSize::Small),
+                ),
+        )
+    }
+
+    fn render_header(&self, _: &mut ViewContext<Picker<Self>>) -> Option<AnyElement> {
+        Some(
+            v_flex()
+                .mt_1()
+                .child(Label::new("Stashes").size(LabelSize::Small).ml_3())
+                .into_any_element(),
+        )
+    }
+
+    fn render_footer(&self, cx: &mut ViewContext<Picker<Self>>) -> Option<AnyElement> {
+        self.selected_stash()?;
+        let mut button = |id: &'static str, label: &'static str, action: StashAction| {
+            Button::new(id, label).on_click(cx.listener(move |picker, _, cx| {
+                picker.delegate.run(action, cx);
+            }))
+        };
+        Some(
+            h_flex()
+                .mr_3()
+                .pb_2()
+                .gap_1()
+                .child(h_flex().w_full())
+                .child(button("stash-drop", "Drop", StashAction::Drop))
+                .child(button("stash-apply", "Apply", StashAction::Apply))
+                .child(button("stash-pop", "Pop", StashAction::Pop).style(ui::ButtonStyle::Filled))
+                .into_any_element(),
+        )
+    }
+}
@@ -5,12 +5,9 @@ use serde_derive::Deserialize;
 use workspace::{SaveIntent, Workspace};
 
 use crate::{
+    ex_command::{ExCommand, ParsedCommand},
     motion::{EndOfDocument, Motion, StartOfDocument},
-    normal::{
-        move_cursor,
-        search::{range_regex, FindCommand, ReplaceCommand},
-        JoinLines,
-    },
+    normal::{move_cursor, search::FindCommand, JoinLines},
     state::Mode,
     Vim,
 };
@@ -33,14 +30,11 @@ pub fn register(workspace: &mut Workspace, _: &mut ViewContext<Workspace>) {
 
 pub fn command_interceptor(mut query: &str, cx: &AppContext) -> Option<CommandInterceptResult> {
     // Note: this is a very poor simulation of vim's command palette.
-    // In the future we should adjust it to handle parsing range syntax,
-    // and then calling the appropriate commands with/without ranges.
+    // Commands that take a line range (:substitute, :global, :normal, ...)
+    // are parsed by `ex_command`, everything else is matched by name below.
     //
     // We also need to support passing arguments to commands like :w
     // (ideally with filename autocompletion).
-    //
-    // For now, you can only do a replace on the % range, and you can
-    // only use a specific line number range to "go to line"
     while query.starts_with(':') {
         query = &query[1..];
     }
@@ -321,7 +315,10 @@ pub fn command_interceptor(mut query: &str, cx: &AppContext) -> Option<CommandIn
         "0" => ("0", StartOfDocument.boxed_clone()),
 
         _ => {
-            if query.starts_with('/') || query.starts_with('?') {
+            let command = ParsedCommand::parse(query);
+            if (query.starts_with('/') || query.starts_with('?'))
+                && command.as_ref().map_or(true, ParsedCommand::is_range_only)
+            {
                 (
                     query,
                     FindCommand {
@@ -330,20 +327,12 @@ pub fn command_interceptor(mut query: &str, cx: &AppContext) -> Option<CommandIn
                     }
                     .boxed_clone(),
                 )
-            } else if query.starts_with('%') {
-                (
-                    query,
-                    ReplaceCommand {
-                        query: query.to_string(),
-                    }
-                    .boxed_clone(),
-                )
             } else if let Ok(line) = query.parse::<u32>() {
                 (query, GoToLine { line }.boxed_clone())
-            } else if range_regex().is_match(query) {
+            } else if command.is_some() {
                 (
                     query,
-                    ReplaceCommand {
+                    ExCommand {
                         query: query.to_string(),
                     }
                     .boxed_clone(),
@@ -0,0 +1,945 @@
+use std::ops::Range;
+
+use anyhow::{anyhow, Result};
+use collections::HashMap;
+use editor::{
+    actions::JoinLines, scroll::Autoscroll, Anchor, Editor, MultiBufferSnapshot, ToPoint,
+};
+use gpui::{impl_actions, Action, Keystroke, ViewContext, WindowContext};
+use language::Point;
+use multi_buffer::MultiBufferRow;
+use regex::{Regex, RegexBuilder};
+use serde_derive::Deserialize;
+use util::ResultExt;
+use workspace::Workspace;
+
+use crate::{
+    motion::Motion,
+    normal::{move_cursor, search::ReplaceCommand},
+    state::Mode,
+    Vim,
+};
+
+/// Runs an ex command with an optional line range, such as `:'<,'>s/a/b/g`,
+/// `:g/pattern/d` or `:5,10normal @q`.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct ExCommand {
+    pub query: String,
+}
+
+impl_actions!(vim, [ExCommand]);
+
+pub(crate) fn register(workspace: &mut Workspace, _: &mut ViewContext<Workspace>) {
+    workspace.register_action(|_: &mut Workspace, action: &ExCommand, cx| {
+        let Some(command) = ParsedCommand::parse(&action.query) else {
+            return;
+        };
+        command.run(cx).log_err();
+    });
+}
+
+/// An ex command together with the range of lines it applies to.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct ParsedCommand {
+    range: Option<LineRange>,
+    command: Command,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Command {
+    /// A bare range, such as `:'a` or `:/foo/+1`, which moves to its last line.
+    GoToLine,
+    Substitute(Substitution),
+    Global {
+        pattern: String,
+        invert: bool,
+        command: Option<Box<ParsedCommand>>,
+    },
+    Normal {
+        keys: String,
+    },
+    Delete,
+    Join,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Substitution {
+    /// The text following the command name, e.g. `/foo/bar/g`.
+    source: String,
+    pattern: String,
+    replacement: String,
+    flags: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct LineRange {
+    start: Address,
+    end: Option<Address>,
+    /// Whether the end address is relative to the start address (`;`) rather
+    /// than to the cursor (`,`).
+    end_relative_to_start: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Address {
+    base: AddressBase,
+    offset: i64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum AddressBase {
+    Line(u32),
+    CurrentLine,
+    LastLine,
+    Mark(char),
+    SearchForward(String),
+    SearchBackward(String),
+}
+
+/// What is needed to resolve the addresses of a range to buffer rows.
+struct RangeContext<'a> {
+    snapshot: &'a MultiBufferSnapshot,
+    marks: &'a HashMap<String, Vec<Anchor>>,
+    visual_rows: Option<Range<u32>>,
+}
+
+impl ParsedCommand {
+    pub(crate) fn parse(query: &str) -> Option<Self> {
+        let (range, rest) = LineRange::parse(query.trim_start())?;
+        let rest = rest.trim_start();
+        let command = if rest.is_empty() {
+            range.as_ref()?;
+            Command::GoToLine
+        } else {
+            Command::parse(rest)?
+        };
+        Some(Self { range, command })
+    }
+
+    /// Whether this is only a range, which moves the cursor rather than editing.
+    pub(crate) fn is_range_only(&self) -> bool {
+        self.command == Command::GoToLine
+    }
+
+    fn run(&self, cx: &mut WindowContext) -> Result<()> {
+        Vim::update(cx, |vim, cx| {
+            let rows = vim
+                .update_active_editor(cx, |vim, editor, cx| {
+                    let snapshot = editor.buffer().read(cx).snapshot(cx);
+                    let current_row = editor.selections.newest::<Point>(cx).head().row;
+                    let visual_rows = vim.state().mode.is_visual().then(|| {
+                        let selection = editor.selections.newest::<Point>(cx);
+                        selection.start.row..selection.end.row + 1
+                    });
+                    let context = RangeContext {
+                        snapshot: &snapshot,
+                        marks: &vim.state().marks,
+                        visual_rows,
+                    };
+                    self.resolve_rows(current_row, &context)
+                })
+                .ok_or_else(|| anyhow!("no active editor"))??;
+            vim.switch_mode(Mode::Normal, false, cx);
+
+            match &self.command {
+                Command::GoToLine => move_to_row(vim, rows.end - 1, cx),
+                Command::Substitute(substitution) if substitution.confirms() => {
+                    let query = format!("{},{}s{}", rows.start + 1, rows.end, substitution.source);
+                    cx.dispatch_action(ReplaceCommand { query }.boxed_clone());
+                }
+                Command::Normal { keys } => {
+                    let line_starts = vim
+                        .update_active_editor(cx, |_, editor, cx| {
+                            let snapshot = editor.buffer().read(cx).snapshot(cx);
+                            rows.clone()
+                                .map(|row| snapshot.anchor_before(Point::new(row, 0)))
+                                .collect()
+                        })
+                        .unwrap_or_default();
+                    run_normal(line_starts, keys, vim, cx);
+                }
+                Command::Global {
+                    pattern,
+                    invert,
+                    command,
+                } => {
+                    let regex = vim_regex(pattern, None)?;
+                    let line_starts = vim
+                        .update_active_editor(cx, |_, editor, cx| {
+                            let snapshot = editor.buffer().read(cx).snapshot(cx);
+                            rows.clone()
+                                .filter(|row| {
+                                    regex.is_match(&line_text(&snapshot, *row)) != *invert
+                                })
+                                .map(|row| snapshot.anchor_before(Point::new(row, 0)))
+                                .collect::<Vec<_>>()
+                        })
+                        .unwrap_or_default();
+                    match command.as_deref() {
+                        Some(ParsedCommand {
+                            command: Command::Normal { keys },
+                            ..
+                        }) => run_normal(line_starts, keys, vim, cx),
+                        Some(command) => {
+                            let row = vim
+                                .update_active_editor(cx, |vim, editor, cx| {
+                                    edit_in_transaction(editor, cx, |editor, cx| {
+                                        command.run_on_line_starts(&line_starts, vim, editor, cx)
+                                    })
+                                })
+                                .transpose()?
+                                .flatten();
+                            if let Some(row) = row {
+                                move_to_row(vim, row, cx);
+                            }
+                        }
+                        None => {}
+                    }
+                }
+                Command::Substitute(_) | Command::Delete | Command::Join => {
+                    let row = vim
+                        .update_active_editor(cx, |_, editor, cx| {
+                            edit_in_transaction(editor, cx, |editor, cx| {
+                                self.command.run_on_rows(rows, editor, cx)
+                            })
+                        })
+                        .transpose()?
+                        .flatten();
+                    if let Some(row) = row {
+                        move_to_row(vim, row, cx);
+                    }
+                }
+            }
+            Ok(())
+        })
+    }
+
+    /// Returns the rows this command applies to, defaulting to the current line,
+    /// or to the whole buffer for `:global`.
+    fn resolve_rows(&self, current_row: u32, context: &RangeContext) -> Result<Range<u32>> {
+        match &self.range {
+            Some(range) => range.resolve(current_row, context),
+            None if matches!(self.command, Command::Global { .. }) => {
+                Ok(0..context.snapshot.max_point().row + 1)
+            }
+            None => Ok(current_row..current_row + 1),
+        }
+    }
+
+    /// Runs a command given to `:global` on each of the matching lines, returning
+    /// the row the cursor should end up on.
+    fn run_on_line_starts(
+        &self,
+        line_starts: &[Anchor],
+        vim: &Vim,
+        editor: &mut Editor,
+        cx: &mut ViewContext<Editor>,
+    ) -> Result<Option<u32>> {
+        let mut cursor_row = None;
+        for line_start in line_starts {
+            let snapshot = editor.buffer().read(cx).snapshot(cx);
+            let current_row = line_start.to_point(&snapshot).row;
+            let context = RangeContext {
+                snapshot: &snapshot,
+                marks: &vim.state().marks,
+                visual_rows: None,
+            };
+            let rows = self.resolve_rows(current_row, &context)?;
+            cursor_row = match self.command {
+                Command::GoToLine => Some(rows.end - 1),
+                _ => self.command.run_on_rows(rows, editor, cx)?.or(cursor_row),
+            };
+        }
+        Ok(cursor_row)
+    }
+}
+
+impl Command {
+    fn parse(source: &str) -> Option<Self> {
+        let name_len = source
+            .find(|c: char| !c.is_ascii_alphabetic())
+            .unwrap_or(source.len());
+        let (name, rest) = source.split_at(name_len);
+        match name {
+            "s" | "su" | "sub" | "subs" | "subst" | "substi" | "substit" | "substitu"
+            | "substitut" | "substitute" => Substitution::parse(rest).map(Command::Substitute),
+            "g" | "gl" | "glo" | "glob" | "globa" | "global" => match rest.strip_prefix('!') {
+                Some(rest) => Self::parse_global(rest, true),
+                None => Self::parse_global(rest, false),
+            },
+            "v" | "vg" | "vgl" | "vglo" | "vglob" | "vgloba" | "vglobal" => {
+                Self::parse_global(rest, true)
+            }
+            "norm" | "norma" | "normal" => {
+                let rest = rest.strip_prefix('!').unwrap_or(rest);
+                if !rest.starts_with(char::is_whitespace) {
+                    return None;
+                }
+                let keys = rest.trim_start();
+                (!keys.is_empty()).then(|| Command::Normal {
+                    keys: keys.to_string(),
+                })
+            }
+            "d" | "de" | "del" | "dele" | "delet" | "delete" if rest.trim().is_empty() => {
+                Some(Command::Delete)
+            }
+            "j" | "jo" | "joi" | "join" if rest.trim().is_empty() => Some(Command::Join),
+            _ => None,
+        }
+    }
+
+    fn parse_global(source: &str, invert: bool) -> Option<Self> {
+        let delimiter = source.chars().next().filter(|c| is_delimiter(*c))?;
+        let (pattern, rest) = split_at_delimiter(&source[delimiter.len_utf8()..], delimiter);
+        let rest = rest.unwrap_or("").trim_start();
+        let command = if rest.is_empty() {
+            None
+        } else {
+            let command = ParsedCommand::parse(rest)?;
+            if matches!(command.command, Command::Global { .. }) {
+                return None;
+            }
+            Some(Box::new(command))
+        };
+        Some(Command::Global {
+            pattern,
+            invert,
+            command,
+        })
+    }
+
+    /// Runs a command that edits the given rows synchronously, returning the row
+    /// the cursor should end up on.
+    fn run_on_rows(
+        &self,
+        rows: Range<u32>,
+        editor: &mut Editor,
+        cx: &mut ViewContext<Editor>,
+    ) -> Result<Option<u32>> {
+        match self {
+            Command::Substitute(substitution) => substitution.run(rows, editor, cx),
+            Command::Delete => Ok(Some(delete_rows(rows, editor, cx))),
+            Command::Join => {
+                let max_row = editor.buffer().read(cx).snapshot(cx).max_point().row;
+                let end_row = (rows.end - 1).min(max_row);
+                editor.change_selections(None, cx, |s| {
+                    s.select_ranges([Point::new(rows.start, 0)..Point::new(end_row, 0)])
+                });
+                editor.join_lines(&JoinLines, cx);
+                Ok(Some(rows.start))
+            }
+            Command::GoToLine | Command::Global { .. } | Command::Normal { .. } => Ok(None),
+        }
+    }
+}
+
+impl Substitution {
+    fn parse(source: &str) -> Option<Self> {
+        let delimiter = source.chars().next().filter(|c| is_delimiter(*c))?;
+        let (pattern, rest) = split_at_delimiter(&source[delimiter.len_utf8()..], delimiter);
+        let (replacement, rest) = split_at_delimiter(rest.unwrap_or(""), delimiter);
+        let flags = rest.unwrap_or("").trim();
+        if !flags.chars().all(|c| "&cegiInp#lr".contains(c)) {
+            return None;
+        }
+        Some(Self {
+            source: source.to_string(),
+            pattern,
+            replacement,
+            flags: flags.to_string(),
+        })
+    }
+
+    /// Whether each replacement should be confirmed, which is done using the
+    /// buffer search bar.
+    fn confirms(&self) -> bool {
+        self.flags.contains('c')
+    }
+
+    fn run(
+        &self,
+        rows: Range<u32>,
+        editor: &mut Editor,
+        cx: &mut ViewContext<Editor>,
+    ) -> Result<Option<u32>> {
+        let case_sensitive = if self.flags.contains('I') {
+            Some(true)
+        } else if self.flags.contains('i') {
+            Some(false)
+        } else {
+            None
+        };
+        let regex = vim_regex(&self.pattern, case_sensitive)?;
+        let replacement = vim_replacement(&self.replacement);
+        let replace_all = self.flags.contains('g');
+
+        let snapshot = editor.buffer().read(cx).snapshot(cx);
+        let mut edits = Vec::new();
+        let mut added_rows = 0;
+        let mut last_row = None;
+        for row in rows {
+            let text = line_text(&snapshot, row);
+            if !regex.is_match(&text) {
+                continue;
+            }
+            let new_text = if replace_all {
+                regex.replace_all(&text, replacement.as_str())
+            } else {
+                regex.replace(&text, replacement.as_str())
+            };
+            last_row = Some(row + added_rows);
+            added_rows += new_text.matches('\n').count() as u32;
+            let range = Point::new(row, 0)..Point::new(row, text.len() as u32);
+            edits.push((range, new_text.into_owned()));
+        }
+        editor
+            .buffer()
+            .update(cx, |buffer, cx| buffer.edit(edits, None, cx));
+        Ok(last_row)
+    }
+}
+
+impl LineRange {
+    fn parse(source: &str) -> Option<(Option<Self>, &str)> {
+        if let Some(rest) = source.strip_prefix('%') {
+            let range = LineRange {
+                start: Address {
+                    base: AddressBase::Line(1),
+                    offset: 0,
+                },
+                end: Some(Address {
+                    base: AddressBase::LastLine,
+                    offset: 0,
+                }),
+                end_relative_to_start: false,
+            };
+            return Some((Some(range), rest));
+        }
+
+        let (start, rest) = Address::parse(source)?;
+        let Some(separator) = rest.chars().next().filter(|c| *c == ',' || *c == ';') else {
+            return Some((
+                start.map(|start| LineRange {
+                    start,
+                    end: None,
+                    end_relative_to_start: false,
+                }),
+                rest,
+            ));
+        };
+        let (end, rest) = Address::parse(&rest[1..])?;
+        let range = LineRange {
+            start: start.unwrap_or(Address::current_line()),
+            end: Some(end.unwrap_or(Address::current_line())),
+            end_relative_to_start: separator == ';',
+        };
+        Some((Some(range), rest))
+    }
+
+    /// Returns the rows covered by the range, swapping backwards ranges.
+    fn resolve(&self, current_row: u32, context: &RangeContext) -> Result<Range<u32>> {
+        let start = self.start.resolve(current_row, context)?;
+        let end = match &self.end {
+            Some(end) if self.end_relative_to_start => end.resolve(start, context)?,
+            Some(end) => end.resolve(current_row, context)?,
+            None => start,
+        };
+        Ok(start.min(end)..start.max(end) + 1)
+    }
+}
+
+impl Address {
+    fn current_line() -> Self {
+        Self {
+            base: AddressBase::CurrentLine,
+            offset: 0,
+        }
+    }
+
+    fn parse(mut source: &str) -> Option<(Option<Self>, &str)> {
+        let mut chars = source.chars();
+        let base = match chars.next() {
+            Some(c) if c.is_ascii_digit() => {
+                let len = digits_len(source);
+                let line = source[..len].parse().ok()?;
+                source = &source[len..];
+                Some(AddressBase::Line(line))
+            }
+            Some('.') => {
+                source = &source[1..];
+                Some(AddressBase::CurrentLine)
+            }
+            Some('$') => {
+                source = &source[1..];
+                Some(AddressBase::LastLine)
+            }
+            Some('\'') => {
+                let mark = chars.next()?;
+                source = chars.as_str();
+                Some(AddressBase::Mark(mark))
+            }
+            Some(delimiter @ ('/' | '?')) => {
+                let (pattern, rest) = split_at_delimiter(&source[1..], delimiter);
+                source = rest.unwrap_or("");
+                if delimiter == '/' {
+                    Some(AddressBase::SearchForward(pattern))
+                } else {
+                    Some(AddressBase::SearchBackward(pattern))
+                }
+            }
+            _ => None,
+        };
+
+        let mut offset = None;
+        loop {
+            let sign = match source.chars().next() {
+                Some('+') => 1,
+                Some('-') => -1,
+                _ => break,
+            };
+            source = &source[1..];
+            let len = digits_len(source);
+            let amount = if len == 0 {
+                1
+            } else {
+                source[..len].parse::<i64>().ok()?
+            };
+            source = &source[len..];
+            offset = Some(offset.unwrap_or(0) + sign * amount);
+        }
+
+        if base.is_none() && offset.is_none() {
+            return Some((None, source));
+        }
+        let address = Address {
+            base: base.unwrap_or(AddressBase::CurrentLine),
+            offset: offset.unwrap_or(0),
+        };
+        Some((Some(address), source))
+    }
+
+    fn resolve(&self, current_row: u32, context: &RangeContext) -> Result<u32> {
+        let max_row = context.snapshot.max_point().row;
+        let row = match &self.base {
+            AddressBase::Line(line) => line.saturating_sub(1),
+            AddressBase::CurrentLine => current_row,
+            AddressBase::LastLine => max_row,
+            AddressBase::Mark(mark) => match (*mark, &context.visual_rows) {
+                ('<', Some(rows)) => rows.start,
+                ('>', Some(rows)) => rows.end - 1,
+                _ => context
+                    .marks
+                    .get(&mark.to_string())
+                    .and_then(|anchors| anchors.first())
+                    .map(|anchor| anchor.to_point(context.snapshot).row)
+                    .ok_or_else(|| anyhow!("mark not set: {mark}"))?,
+            },
+            AddressBase::SearchForward(pattern) => {
+                let regex = vim_regex(pattern, None)?;
+                (1..=max_row + 1)
+                    .map(|delta| (current_row + delta) % (max_row + 1))
+                    .find(|row| regex.is_match(&line_text(context.snapshot, *row)))
+                    .ok_or_else(|| anyhow!("pattern not found: {pattern}"))?
+            }
+            AddressBase::SearchBackward(pattern) => {
+                let regex = vim_regex(pattern, None)?;
+                (1..=max_row + 1)
+                    .map(|delta| (current_row + max_row + 1 - delta) % (max_row + 1))
+                    .find(|row| regex.is_match(&line_text(context.snapshot, *row)))
+                    .ok_or_else(|| anyhow!("pattern not found: {pattern}"))?
+            }
+        };
+        let row = row as i64 + self.offset;
+        if row < 0 || row > max_row as i64 {
+            return Err(anyhow!("invalid range"));
+        }
+        Ok(row as u32)
+    }
+}
+
+/// Runs the given keys in normal mode at the start of each line, grouping all of
+/// the resulting edits into a single undo step.
+fn run_normal(line_starts: Vec<Anchor>, keys: &str, vim: &mut Vim, cx: &mut WindowContext) {
+    let Some(editor) = vim
+        .active_editor
+        .clone()
+        .and_then(|editor| editor.upgrade())
+    else {
+        return;
+    };
+    let keystrokes = keys
+        .chars()
+        .filter_map(|c| match c {
+            ' ' => Keystroke::parse("space").log_err(),
+            c => Keystroke::parse(&c.to_string()).log_err(),
+        })
+        .collect::<Vec<_>>();
+    let Some(escape) = Keystroke::parse("escape").log_err() else {
+        return;
+    };
+
+    editor.update(cx, |editor, cx| {
+        editor
+            .buffer()
+            .update(cx, |buffer, cx| buffer.start_transaction(cx))
+    });
+    cx.spawn(|mut cx| async move {
+        let result = async {
+            for line_start in line_starts {
+                cx.update(|cx| {
+                    Vim::update(cx, |vim, cx| {
+                        vim.switch_mode(Mode::Normal, false, cx);
+                        vim.update_active_editor(cx, |_, editor, cx| {
+                            editor.change_selections(None, cx, |s| {
+                                s.select_anchor_ranges([line_start..line_start])
+                            })
+                        });
+                    })
+                })?;
+                for keystroke in &keystrokes {
+                    cx.update(|cx| {
+                        let focused = cx.focused();
+                        cx.dispatch_keystroke(keystroke.clone());
+                        if cx.focused() != focused {
+                            cx.draw();
+                        }
+                    })?;
+                }
+                cx.update(|cx| {
+                    if Vim::read(cx).state().mode != Mode::Normal {
+                        cx.dispatch_keystroke(escape.clone());
+                    }
+                })?;
+            }
+            anyhow::Ok(())
+        }
+        .await;
+        editor.update(&mut cx, |editor, cx| {
+            editor
+                .buffer()
+                .update(cx, |buffer, cx| buffer.end_transaction(cx))
+        })?;
+        result
+    })
+    .detach_and_log_err(cx);
+}
+
+/// Runs `edit` as a single undoable transaction, returning the row the cursor
+/// should end up on.
+fn edit_in_transaction(
+    editor: &mut Editor,
+    cx: &mut ViewContext<Editor>,
+    edit: impl FnOnce(&mut Editor, &mut ViewContext<Editor>) -> Result<Option<u32>>,
+) -> Result<Option<u32>> {
+    let mut result = Ok(None);
+    editor.transact(cx, |editor, cx| result = edit(editor, cx));
+    result
+}
+
+fn move_to_row(vim: &mut Vim, row: u32, cx: &mut WindowContext) {
+    vim.update_active_editor(cx, |_, editor, cx| {
+        editor.change_selections(Some(Autoscroll::fit()), cx, |s| {
+            s.select_ranges([Point::new(row, 0)..Point::new(row, 0)])
+        })
+    });
+    move_cursor(
+        vim,
+        Motion::FirstNonWhitespace {
+            display_lines: false,
+        },
+        None,
+        cx,
+    );
+}
+
+/// Deletes the given rows, returning the row the cursor should end up on.
+fn delete_rows(rows: Range<u32>, editor: &mut Editor, cx: &mut ViewContext<Editor>) -> u32 {
+    let snapshot = editor.buffer().read(cx).snapshot(cx);
+    let max_point = snapshot.max_point();
+    let range = if rows.end <= max_point.row {
+        Point::new(rows.start, 0)..Point::new(rows.end, 0)
+    } else if rows.start > 0 {
+        let previous_row = rows.start - 1;
+        Point::new(
+            previous_row,
+            snapshot.line_len(MultiBufferRow(previous_row)),
+        )..max_point
+    } else {
+        Point::zero()..max_point
+    };
+    editor
+        .buffer()
+        .update(cx, |buffer, cx| buffer.edit([(range, "")], None, cx));
+    let max_row = editor.buffer().read(cx).snapshot(cx).max_point().row;
+    rows.start.min(max_row)
+}
+
+fn line_text(snapshot: &MultiBufferSnapshot, row: u32) -> String {
+    let end = Point::new(row, snapshot.line_len(MultiBufferRow(row)));
+    snapshot.text_for_range(Point::new(row, 0)..end).collect()
+}
+
+fn digits_len(source: &str) -> usize {
+    source
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(source.len())
+}
+
+fn is_delimiter(c: char) -> bool {
+    !c.is_alphanumeric() && !c.is_whitespace() && !matches!(c, '\\' | '"' | '|')
+}
+
+/// Splits off the text up to the next unescaped delimiter, unescaping any escaped
+/// delimiters. Returns `None` for the remainder if the delimiter is missing.
+fn split_at_delimiter(source: &str, delimiter: char) -> (String, Option<&str>) {
+    let mut text = String::new();
+    let mut chars = source.char_indices();
+    while let Some((ix, c)) = chars.next() {
+        if c == delimiter {
+            return (text, Some(&source[ix + c.len_utf8()..]));
+        }
+        if c == '\\' {
+            match chars.next() {
+                Some((_, c)) if c == delimiter => text.push(c),
+                Some((_, c)) => {
+                    text.push('\\');
+                    text.push(c);
+                }
+                None => text.push('\\'),
+            }
+        } else {
+            text.push(c);
+        }
+    }
+    (text, None)
+}
+
+/// Converts a vim pattern (with the default `magic` option) to a regex.
+fn vim_regex(pattern: &str, case_sensitive: Option<bool>) -> Result<Regex> {
+    let mut regex = String::new();
+    let mut ignore_case = case_sensitive == Some(false);
+    let mut chars = pattern.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => match chars.next() {
+                Some(c @ ('(' | ')' | '|' | '+' | '?')) => regex.push(c),
+                Some('=') => regex.push('?'),
+                Some('<' | '>') => regex.push_str("\\b"),
+                Some('c') => ignore_case = true,
+                Some('C') => ignore_case = false,
+                Some('{') => {
+                    let mut bounds = String::new();
+                    for c in chars.by_ref() {
+                        if c == '}' {
+                            break;
+                        }
+                        bounds.push(c);
+                    }
+                    let (lazy, bounds) = match bounds.strip_prefix('-') {
+                        Some(bounds) => (true, bounds),
+                        None => (false, bounds.as_str()),
+                    };
+                    if bounds.is_empty() {
+                        regex.push('*');
+                    } else {
+                        regex.push('{');
+                        if bounds.starts_with(',') {
+                            regex.push('0');
+                        }
+                        regex.push_str(bounds);
+                        regex.push('}');
+                    }
+                    if lazy {
+                        regex.push('?');
+                    }
+                }
+                Some(c) => {
+                    regex.push('\\');
+                    regex.push(c);
+                }
+                None => regex.push_str("\\\\"),
+            },
+            '(' | ')' | '|' | '+' | '?' | '{' | '}' => {
+                regex.push('\\');
+                regex.push(c);
+            }
+            c => regex.push(c),
+        }
+    }
+    Ok(RegexBuilder::new(&regex)
+        .case_insensitive(ignore_case)
+        .build()?)
+}
+
+/// Converts a vim substitution string to the replacement syntax of the regex crate.
+fn vim_replacement(replacement: &str) -> String {
+    let mut result = String::new();
+    let mut chars = replacement.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => match chars.next() {
+                Some(c @ '0'..='9') => {
+                    result.push_str("${");
+                    result.push(c);
+                    result.push('}');
+                }
+                Some('r' | 'n') => result.push('\n'),
+                Some('t') => result.push('\t'),
+                Some(c) => result.push(c),
+                None => result.push('\\'),
+            },
+            '&' => result.push_str("${0}"),
+            '$' => result.push_str("$$"),
+            c => result.push(c),
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod test {
+    use indoc::indoc;
+
+    use super::*;
+    use crate::test::NeovimBackedTestContext;
+
+    #[test]
+    fn test_parse_ranges() {
+        let command = ParsedCommand::parse("'<,'>s/a/b/g").unwrap();
+        assert_eq!(command.range.unwrap().start.base, AddressBase::Mark('<'));
+
+        let command = ParsedCommand::parse(".-2;/foo/+1").unwrap();
+        let range = command.range.unwrap();
+        assert_eq!(
+            range.start,
+            Address {
+                base: AddressBase::CurrentLine,
+                offset: -2
+            }
+        );
+        assert_eq!(
+            range.end,
+            Some(Address {
+                base: AddressBase::SearchForward("foo".into()),
+                offset: 1
+            })
+        );
+        assert!(range.end_relative_to_start);
+        assert!(command.is_range_only());
+
+        let command = ParsedCommand::parse("5,$norm @q").unwrap();
+        assert_eq!(command.command, Command::Normal { keys: "@q".into() });
+
+        let command = ParsedCommand::parse("g!/a\\/b/s#x#y#").unwrap();
+        let Command::Global {
+            pattern,
+            invert,
+            command,
+        } = command.command
+        else {
+            panic!("expected :global");
+        };
+        assert_eq!(pattern, "a/b");
+        assert!(invert);
+        assert!(matches!(command.unwrap().command, Command::Substitute(_)));
+
+        assert_eq!(ParsedCommand::parse("5,"), ParsedCommand::parse("5,."));
+        assert!(ParsedCommand::parse("'").is_none());
+        assert!(ParsedCommand::parse("normalx").is_none());
+        assert!(ParsedCommand::parse("g/a/g/b/d").is_none());
+    }
+
+    #[test]
+    fn test_vim_regex() {
+        let regex = vim_regex("\\<\\(foo\\|bar\\)\\+(x)\\{-1,}", None).unwrap();
+        assert_eq!(regex.as_str(), "\\b(foo|bar)+\\(x\\){1,}?");
+        assert!(vim_regex("\\cFOO", None).unwrap().is_match("foo"));
+        assert_eq!(vim_replacement("\\1-&-\\&$\\r"), "${1}-${0}-&$$\n");
+    }
+
+    #[gpui::test]
+    async fn test_ex_substitute_ranges(cx: &mut gpui::TestAppContext) {
+        let mut cx = NeovimBackedTestContext::new(cx).await;
+
+        cx.set_shared_state(indoc! {"
+            ˇa a
+            a a
+            a a
+            a a"})
+            .await;
+        cx.simulate_shared_keystrokes(": 2 , 3 s / a / b enter")
+            .await;
+        cx.shared_state().await.assert_eq(indoc! {"
+            a a
+            b a
+            ˇb a
+            a a"});
+
+        cx.simulate_shared_keystrokes(": % s / a / c / g enter")
+            .await;
+        cx.shared_state().await.assert_eq(indoc! {"
+            c c
+            b c
+            b c
+            ˇc c"});
+
+        cx.simulate_shared_keystrokes(": . - 2 , . s / c / d enter")
+            .await;
+        cx.shared_state().await.assert_eq(indoc! {"
+            c c
+            b d
+            b d
+            ˇd c"});
+    }
+
+    #[gpui::test]
+    async fn test_ex_global(cx: &mut gpui::TestAppContext) {
+        let mut cx = NeovimBackedTestContext::new(cx).await;
+
+        cx.set_shared_state(indoc! {"
+            ˇfoo 1
+            bar 2
+            foo 3
+            bar 4"})
+            .await;
+        cx.simulate_shared_keystrokes(": g / f o o / d enter").await;
+        cx.shared_state().await.assert_eq(indoc! {"
+            bar 2
+            ˇbar 4"});
+
+        cx.simulate_shared_keystrokes(": v / 2 / s / b a r / b a z enter")
+            .await;
+        cx.shared_state().await.assert_eq(indoc! {"
+            bar 2
+            ˇbaz 4"});
+
+        cx.simulate_shared_keystrokes("u").await;
+        cx.shared_state().await.assert_eq(indoc! {"
+            bar 2
+            ˇbar 4"});
+    }
+
+    #[gpui::test]
+    async fn test_ex_normal(cx: &mut gpui::TestAppContext) {
+        let mut cx = NeovimBackedTestContext::new(cx).await;
+
+        cx.set_shared_state(indoc! {"
+            ˇa
+            b
+            c"})
+            .await;
+        cx.simulate_shared_keystrokes(": % n o r m a l space A ; enter")
+            .await;
+        cx.shared_state().await.assert_eq(indoc! {"
+            a;
+            b;
+            cˇ;"});
+
+        cx.simulate_shared_keystrokes(": 1 , 2 n o r m space x enter")
+            .await;
+        cx.shared_state().await.assert_eq(indoc! {"
+            ;
+            ˇ;
+            c;"});
+    }
+}
@@ -1,35 +1,95 @@
-use std::{ops::Range, sync::Arc};
+use std::{ops::Range, path::PathBuf, sync::Arc};
 
 use editor::{
     display_map::{DisplaySnapshot, ToDisplayPoint},
     movement,
     scroll::Autoscroll,
-    Anchor, Bias, DisplayPoint,
+    Anchor, Bias, DisplayPoint, Editor, ToPoint,
 };
-use gpui::WindowContext;
+use gpui::{AppContext, WindowContext};
 use language::SelectionGoal;
+use multi_buffer::MultiBufferRow;
+use settings::Settings;
 
 use crate::{
     motion::{self, Motion},
+    persistence,
     state::Mode,
-    Vim,
+    Vim, VimSettings,
 };
 
 pub fn create_mark(vim: &mut Vim, text: Arc<str>, tail: bool, cx: &mut WindowContext) {
-    let Some(anchors) = vim.update_active_editor(cx, |_, editor, _| {
-        editor
+    let Some((anchors, location)) = vim.update_active_editor(cx, |_, editor, cx| {
+        let anchors = editor
             .selections
             .disjoint_anchors()
             .iter()
             .map(|s| if tail { s.tail() } else { s.head() })
-            .collect::<Vec<_>>()
+            .collect::<Vec<_>>();
+        let location = anchors.first().and_then(|anchor| {
+            let snapshot = editor.buffer().read(cx).snapshot(cx);
+            Some((editor_path(editor, cx)?, anchor.to_point(&snapshot)))
+        });
+        (anchors, location)
     }) else {
         return;
     };
+
+    if is_global_mark(&text) {
+        for state in vim.editor_states.values_mut() {
+            state.marks.remove(&*text);
+        }
+        if let Some(location) = location.clone() {
+            vim.workspace_state
+                .global_marks
+                .insert(text.to_string(), location);
+        }
+    }
+    if let Some((path, point)) = location.filter(|_| text.chars().all(|c| c.is_ascii_alphabetic()))
+    {
+        persistence::save_mark(text.to_string(), path, point, cx);
+    }
     vim.update_state(|state| state.marks.insert(text.to_string(), anchors));
     vim.clear_operator(cx);
 }
 
+/// Loads the marks saved by previous sessions for the active editor's file.
+pub fn restore_marks(vim: &mut Vim, cx: &mut WindowContext) {
+    if vim.state().marks_restored || !VimSettings::get_global(cx).persistence.enabled {
+        return;
+    }
+    let marks = vim
+        .update_active_editor(cx, |vim, editor, cx| {
+            let path = editor_path(editor, cx)?;
+            let snapshot = editor.buffer().read(cx).snapshot(cx);
+            let mut marks = persistence::marks(&path);
+            marks.extend(
+                vim.workspace_state
+                    .global_marks
+                    .iter()
+                    .filter(|(_, (mark_path, _))| *mark_path == path)
+                    .map(|(name, (_, point))| (name.clone(), *point)),
+            );
+            Some(
+                marks
+                    .into_iter()
+                    .map(|(name, point)| {
+                        let point = snapshot.clip_point(point, Bias::Left);
+                        (name, vec![snapshot.anchor_before(point)])
+                    })
+                    .collect::<Vec<_>>(),
+            )
+        })
+        .flatten()
+        .unwrap_or_default();
+    vim.update_state(|state| {
+        state.marks_restored = true;
+        for (name, anchors) in marks {
+            state.marks.entry(name).or_insert(anchors);
+        }
+    });
+}
+
 pub fn create_visual_marks(vim: &mut Vim, mode: Mode, cx: &mut WindowContext) {
     let mut starts = vec![];
     let mut ends = vec![];
@@ -84,7 +144,12 @@ pub fn jump(text: Arc<str>, line: bool, cx: &mut WindowContext) {
         }
     });
 
-    let Some(anchors) = anchors else { return };
+    let Some(anchors) = anchors else {
+        if is_global_mark(&text) {
+            jump_to_global_mark(text, line, cx);
+        }
+        return;
+    };
 
     let is_active_operator = Vim::read(cx).state().active_operator().is_some();
     if is_active_operator {
@@ -124,6 +189,60 @@ pub fn jump(text: Arc<str>, line: bool, cx: &mut WindowContext) {
     }
 }
 
+/// Opens the file containing an uppercase mark that was set in another editor.
+fn jump_to_global_mark(text: Arc<str>, line: bool, cx: &mut WindowContext) {
+    let vim = Vim::read(cx);
+    let global_mark = vim.workspace_state.global_marks.get(&*text).cloned();
+    let active_editor = vim.active_editor.clone();
+    let Some((path, point)) = global_mark else {
+        return;
+    };
+    let Some(workspace) = active_editor
+        .and_then(|editor| editor.upgrade())
+        .and_then(|editor| editor.read(cx).workspace())
+    else {
+        return;
+    };
+
+    let open_task = workspace.update(cx, |workspace, cx| workspace.open_abs_path(path, true, cx));
+    cx.spawn(|mut cx| async move {
+        let Some(editor) = open_task.await?.downcast::<Editor>() else {
+            return Ok(());
+        };
+        let anchor = cx.update(|cx| {
+            Vim::read(cx)
+                .editor_states
+                .get(&editor.entity_id())
+                .and_then(|state| state.marks.get(&*text)?.first().copied())
+        })?;
+        editor.update(&mut cx, |editor, cx| {
+            let snapshot = editor.buffer().read(cx).snapshot(cx);
+            let mut point = match anchor {
+                Some(anchor) => anchor.to_point(&snapshot),
+                None => snapshot.clip_point(point, Bias::Left),
+            };
+            if line {
+                point.column = snapshot.indent_size_for_line(MultiBufferRow(point.row)).len;
+            }
+            editor.change_selections(Some(Autoscroll::center()), cx, |s| {
+                s.select_ranges([point..point])
+            });
+        })
+    })
+    .detach_and_log_err(cx);
+}
+
+fn is_global_mark(text: &str) -> bool {
+    text.chars().all(|c| c.is_ascii_uppercase())
+}
+
+/// The absolute path of the file in the editor, if it is a single local file.
+fn editor_path(editor: &Editor, cx: &AppContext) -> Option<PathBuf> {
+    let buffer = editor.buffer().read(cx).as_singleton()?;
+    let file = buffer.read(cx).file()?;
+    Some(file.as_local()?.abs_path(cx))
+}
+
 pub fn jump_motion(
     map: &DisplaySnapshot,
     anchor: Anchor,
@@ -3,11 +3,12 @@ use std::{cell::RefCell, ops::Range, rc::Rc, sync::Arc};
 use crate::{
     insert::NormalBefore,
     motion::Motion,
+    persistence,
     state::{Mode, Operator, RecordedSelection, ReplayableAction},
     visual::visual_motion,
     Vim,
 };
-use gpui::{actions, Action, ViewContext, WindowContext};
+use gpui::{actions, Action, Keystroke, ViewContext, WindowContext};
 use util::ResultExt;
 use workspace::Workspace;
 
@@ -40,7 +41,7 @@ fn repeatable_insert(action: &ReplayableAction) -> Option<Box<dyn Action>> {
                 None
             }
         }
-        ReplayableAction::Insertion { .. } => None,
+        ReplayableAction::Insertion { .. } | ReplayableAction::Keystroke(_) => None,
     }
 }
 
@@ -56,7 +57,10 @@ pub(crate) fn register(workspace: &mut Workspace, _: &mut ViewContext<Workspace>
     workspace.register_action(|_: &mut Workspace, _: &ToggleRecord, cx| {
         Vim::update(cx, |vim, cx| {
             if let Some(char) = vim.workspace_state.recording_register.take() {
-                vim.workspace_state.last_recorded_register = Some(char)
+                vim.workspace_state.last_recorded_register = Some(char);
+                if let Some(keystrokes) = vim.workspace_state.recorded_keystrokes.get(&char) {
+                    persistence::save_recording(char, keystrokes, cx);
+                }
             } else {
                 vim.push_operator(Operator::RecordRegister, cx);
             }
@@ -138,6 +142,9 @@ impl Replayer {
                         .log_err();
                 }
             }
+            ReplayableAction::Keystroke(keystroke) => {
+                cx.dispatch_keystroke(keystroke);
+            }
         }
         cx.defer(move |cx| self.next(cx));
     }
@@ -147,6 +154,7 @@ pub(crate) fn record_register(register: char, cx: &mut WindowContext) {
     Vim::update(cx, |vim, cx| {
         vim.workspace_state.recording_register = Some(register);
         vim.workspace_state.recordings.remove(&register);
+        vim.workspace_state.recorded_keystrokes.remove(&register);
         vim.workspace_state.ignore_current_insertion = true;
         vim.clear_operator(cx)
     })
@@ -340,6 +348,21 @@ pub(crate) fn observe_action(action: Box<dyn Action>, cx: &mut WindowContext) {
     })
 }
 
+pub(crate) fn observe_keystroke(keystroke: &Keystroke, cx: &mut WindowContext) {
+    Vim::update(cx, |vim, _| {
+        if vim.workspace_state.replayer.is_some() {
+            return;
+        }
+        if let Some(recording_register) = vim.workspace_state.recording_register {
+            vim.workspace_state
+                .recorded_keystrokes
+                .entry(recording_register)
+                .or_default()
+                .push(keystroke.clone());
+        }
+    })
+}
+
 pub(crate) fn observe_insertion(
     text: &Arc<str>,
     range_to_replace: Option<Range<isize>>,
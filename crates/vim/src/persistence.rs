@@ -0,0 +1,437 @@
+use std::{
+    path::{Path, PathBuf},
+    sync::atomic::{self, AtomicBool},
+};
+
+use anyhow::Result;
+use collections::HashMap;
+use db::sqlez_macros::sql;
+use db::{define_connection, query};
+use gpui::{AppContext, Keystroke};
+use language::Point;
+use settings::Settings;
+use util::ResultExt;
+
+use crate::{
+    state::{Register, ReplayableAction, WorkspaceState},
+    VimSettings,
+};
+
+define_connection!(
+    // Current schema shape using pseudo-rust syntax:
+    // vim_registers(
+    //   name: char,
+    //   text: String,
+    //   clipboard_selections: Option<String>, // JSON
+    // )
+    // vim_recordings(
+    //   name: char,
+    //   keystrokes: String, // space separated, in keymap syntax
+    // )
+    // vim_marks(
+    //   path: PathBuf,
+    //   name: char, // uppercase marks are only stored for a single path
+    //   mark_row: u32,
+    //   mark_column: u32,
+    // )
+    pub static ref DB: VimDb<()> =
+        &[sql!(
+            CREATE TABLE vim_registers(
+                name TEXT PRIMARY KEY,
+                text TEXT NOT NULL,
+                clipboard_selections TEXT
+            ) STRICT;
+
+            CREATE TABLE vim_recordings(
+                name TEXT PRIMARY KEY,
+                keystrokes TEXT NOT NULL
+            ) STRICT;
+
+            CREATE TABLE vim_marks(
+                path BLOB NOT NULL,
+                name TEXT NOT NULL,
+                mark_row INTEGER NOT NULL,
+                mark_column INTEGER NOT NULL,
+                PRIMARY KEY(path, name)
+            ) STRICT;
+        )];
+);
+
+impl VimDb {
+    query! {
+        pub fn registers() -> Result<Vec<(String, String, Option<String>)>> {
+            SELECT name, text, clipboard_selections FROM vim_registers
+        }
+    }
+
+    /// Saves the given registers, and deletes the saved contents of the ones without any,
+    /// leaving the other saved registers as they are.
+    pub async fn save_registers(
+        &self,
+        registers: Vec<(String, Option<(String, Option<String>)>)>,
+    ) -> Result<()> {
+        self.write(move |conn| {
+            conn.with_savepoint("save_registers", || {
+                for (name, register) in registers {
+                    match register {
+                        Some((text, clipboard_selections)) => {
+                            conn.exec_bound(sql!(
+                                INSERT OR REPLACE INTO vim_registers(name, text, clipboard_selections)
+                                VALUES (?1, ?2, ?3)
+                            ))?((name, text, clipboard_selections))?;
+                        }
+                        None => {
+                            conn.exec_bound(sql!(DELETE FROM vim_registers WHERE name = ?))?(
+                                name.as_str(),
+                            )?;
+                        }
+                    }
+                }
+                Ok(())
+            })
+        })
+        .await
+    }
+
+    query! {
+        pub fn recordings() -> Result<Vec<(String, String)>> {
+            SELECT name, keystrokes FROM vim_recordings
+        }
+    }
+
+    query! {
+        pub async fn save_recording(name: String, keystrokes: String) -> Result<()> {
+            INSERT OR REPLACE INTO vim_recordings(name, keystrokes)
+            VALUES (?1, ?2)
+        }
+    }
+
+    query! {
+        pub fn marks(path: &Path) -> Result<Vec<(String, u32, u32)>> {
+            SELECT name, mark_row, mark_column FROM vim_marks
+            WHERE path = ?
+        }
+    }
+
+    query! {
+        pub fn global_marks() -> Result<Vec<(String, PathBuf, u32, u32)>> {
+            SELECT name, path, mark_row, mark_column FROM vim_marks
+            WHERE unicode(name) BETWEEN 65 AND 90 // Uppercase ASCII letters
+        }
+    }
+
+    /// Saves a mark, keeping only the `max_marks` most recently saved ones.
+    pub async fn save_mark(
+        &self,
+        path: PathBuf,
+        name: String,
+        row: u32,
+        column: u32,
+        max_marks: usize,
+    ) -> Result<()> {
+        self.write(move |conn| {
+            conn.with_savepoint("save_mark", || {
+                if name.chars().all(|c| c.is_ascii_uppercase()) {
+                    conn.exec_bound(sql!(DELETE FROM vim_marks WHERE name = ?))?(name.as_str())?;
+                }
+                conn.exec_bound(sql!(
+                    INSERT OR REPLACE INTO vim_marks(path, name, mark_row, mark_column)
+                    VALUES (?1, ?2, ?3, ?4)
+                ))?((path, name, row, column))?;
+                conn.exec_bound(sql!(
+                    DELETE FROM vim_marks WHERE rowid NOT IN (
+                        SELECT rowid FROM vim_marks ORDER BY rowid DESC LIMIT ?
+                    )
+                ))?(max_marks)?;
+                Ok(())
+            })
+        })
+        .await
+    }
+}
+
+/// Whether the saved state was loaded already. It is only loaded once per process, as it is not
+/// updated for the registers that are not saved, and would overwrite them when Vim mode is
+/// toggled.
+static RESTORED: AtomicBool = AtomicBool::new(false);
+
+/// Loads the registers, macro recordings and global marks saved by previous sessions, unless
+/// they were loaded already.
+pub(crate) fn restore(state: &mut WorkspaceState) -> Result<()> {
+    if RESTORED.swap(true, atomic::Ordering::SeqCst) {
+        return Ok(());
+    }
+    for (name, text, clipboard_selections) in DB.registers()? {
+        let Some(name) = name.chars().next() else {
+            continue;
+        };
+        let register = Register {
+            text: text.into(),
+            clipboard_selections: clipboard_selections
+                .and_then(|selections| serde_json::from_str(&selections).log_err()),
+        };
+        state.registers.insert(name, register);
+    }
+
+    for (name, keystrokes) in DB.recordings()? {
+        let Some(name) = name.chars().next() else {
+            continue;
+        };
+        let keystrokes = deserialize_keystrokes(&keystrokes);
+        state.recordings.insert(
+            name,
+            keystrokes
+                .iter()
+                .cloned()
+                .map(ReplayableAction::Keystroke)
+                .collect(),
+        );
+        state.recorded_keystrokes.insert(name, keystrokes);
+    }
+
+    for (name, path, row, column) in DB.global_marks()? {
+        state
+            .global_marks
+            .insert(name, (path, Point::new(row, column)));
+    }
+    Ok(())
+}
+
+/// Returns the marks saved for the file at the given path.
+pub(crate) fn marks(path: &Path) -> Vec<(String, Point)> {
+    DB.marks(path)
+        .log_err()
+        .unwrap_or_default()
+        .into_iter()
+        .map(|(name, row, column)| (name, Point::new(row, column)))
+        .collect()
+}
+
+/// Saves the registers with the given names, which were just written to.
+pub(crate) fn save_registers(names: &[char], registers: &HashMap<char, Register>, cx: &AppContext) {
+    let settings = VimSettings::get_global(cx).persistence;
+    if !settings.enabled {
+        return;
+    }
+    let registers = names
+        .iter()
+        .filter(|name| name.is_ascii_alphanumeric() || matches!(name, '"' | '-'))
+        .map(|name| {
+            // Registers that are too large to save are deleted, so that their previous
+            // contents are not restored instead.
+            let register = registers
+                .get(name)
+                .filter(|register| register.text.lines().count() <= settings.max_register_lines)
+                .map(|register| {
+                    let clipboard_selections = register
+                        .clipboard_selections
+                        .as_ref()
+                        .and_then(|selections| serde_json::to_string(selections).log_err());
+                    (register.text.to_string(), clipboard_selections)
+                });
+            (name.to_string(), register)
+        })
+        .collect::<Vec<_>>();
+    cx.background_executor()
+        .spawn(async move { DB.save_registers(registers).await.log_err() })
+        .detach();
+}
+
+pub(crate) fn save_recording(name: char, keystrokes: &[Keystroke], cx: &AppContext) {
+    if !VimSettings::get_global(cx).persistence.enabled {
+        return;
+    }
+    let keystrokes = serialize_keystrokes(keystrokes);
+    cx.background_executor()
+        .spawn(async move {
+            DB.save_recording(name.to_string(), keystrokes)
+                .await
+                .log_err()
+        })
+        .detach();
+}
+
+pub(crate) fn save_mark(name: String, path: PathBuf, position: Point, cx: &AppContext) {
+    let settings = VimSettings::get_global(cx).persistence;
+    if !settings.enabled {
+        return;
+    }
+    cx.background_executor()
+        .spawn(async move {
+            DB.save_mark(
+                path,
+                name,
+                position.row,
+                position.column,
+                settings.max_marks,
+            )
+            .await
+            .log_err()
+        })
+        .detach();
+}
+
+fn serialize_keystrokes(keystrokes: &[Keystroke]) -> String {
+    let mut text = String::new();
+    for keystroke in keystrokes {
+        if !text.is_empty() {
+            text.push(' ');
+        }
+        let modifiers = &keystroke.modifiers;
+        for (pressed, name) in [
+            (modifiers.control, "ctrl-"),
+            (modifiers.alt, "alt-"),
+            (modifiers.shift, "shift-"),
+            (modifiers.platform, "cmd-"),
+            (modifiers.function, "fn-"),
+        ] {
+            if pressed {
+                text.push_str(name);
+            }
+        }
+        text.push_str(&keystroke.key);
+
+        // The IME key only needs to be stored when it differs from what typing
+        // the key produces, such as for option-s on macOS.
+        let simulated_ime_key = Keystroke {
+            ime_key: None,
+            ..keystroke.clone()
+        }
+        .with_simulated_ime()
+        .ime_key;
+        if let Some(ime_key) = keystroke.ime_key.as_ref().filter(|ime_key| {
+            Some(*ime_key) != simulated_ime_key.as_ref()
+                && !ime_key.contains(|c: char| c == '-' || c.is_whitespace())
+        }) {
+            text.push_str("->");
+            text.push_str(ime_key);
+        }
+    }
+    text
+}
+
+fn deserialize_keystrokes(text: &str) -> Vec<Keystroke> {
+    text.split(' ')
+        .filter(|keystroke| !keystroke.is_empty())
+        .filter_map(|keystroke| Keystroke::parse(keystroke).log_err())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::Mode;
+    use crate::test::VimTestContext;
+    use crate::Vim;
+
+    #[gpui::test]
+    async fn test_vim_db() {
+        let db = VimDb(db::open_test_db("test_vim_db").await);
+
+        db.save_registers(vec![
+            ("a".into(), Some(("one".into(), None))),
+            ("\"".into(), Some(("two\n".into(), Some("[]".into())))),
+        ])
+        .await
+        .unwrap();
+        // Saving some registers replaces or deletes only those.
+        db.save_registers(vec![
+            ("b".into(), Some(("three".into(), None))),
+            ("\"".into(), Some(("four".into(), None))),
+            ("a".into(), None),
+        ])
+        .await
+        .unwrap();
+        let mut registers = db.registers().unwrap();
+        registers.sort();
+        assert_eq!(
+            registers,
+            vec![
+                ("\"".to_string(), "four".to_string(), None),
+                ("b".to_string(), "three".to_string(), None)
+            ]
+        );
+
+        db.save_recording("q".into(), "d d j".into()).await.unwrap();
+        assert_eq!(
+            db.recordings().unwrap(),
+            vec![("q".to_string(), "d d j".to_string())]
+        );
+
+        db.save_mark("/a.rs".into(), "a".into(), 1, 2, 3)
+            .await
+            .unwrap();
+        db.save_mark("/a.rs".into(), "A".into(), 3, 4, 3)
+            .await
+            .unwrap();
+        db.save_mark("/b.rs".into(), "A".into(), 5, 6, 3)
+            .await
+            .unwrap();
+        db.save_mark("/b.rs".into(), "b".into(), 7, 8, 3)
+            .await
+            .unwrap();
+        db.save_mark("/b.rs".into(), "c".into(), 9, 10, 3)
+            .await
+            .unwrap();
+        assert_eq!(db.marks(Path::new("/a.rs")).unwrap(), vec![]);
+        assert_eq!(
+            db.global_marks().unwrap(),
+            vec![("A".to_string(), PathBuf::from("/b.rs"), 5, 6)]
+        );
+        let mut marks = db.marks(Path::new("/b.rs")).unwrap();
+        marks.sort();
+        assert_eq!(
+            marks,
+            vec![
+                ("A".to_string(), 5, 6),
+                ("b".to_string(), 7, 8),
+                ("c".to_string(), 9, 10)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_serialize_keystrokes() {
+        let keystrokes = deserialize_keystrokes("shift-a ctrl-w space escape alt-s->ß -");
+        assert_eq!(keystrokes.len(), 6);
+        assert_eq!(keystrokes[4].ime_key.as_deref(), Some("ß"));
+        assert_eq!(
+            serialize_keystrokes(&keystrokes),
+            "shift-a ctrl-w space escape alt-s->ß -"
+        );
+    }
+
+    #[gpui::test]
+    async fn test_replay_restored_recording(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        cx.set_state("ˇone\ntwo", Mode::Normal);
+        cx.update(|cx| {
+            Vim::update(cx, |vim, _| {
+                let keystrokes = deserialize_keystrokes("shift-a ; escape j");
+                vim.workspace_state.recordings.insert(
+                    'q',
+                    keystrokes
+                        .into_iter()
+                        .map(ReplayableAction::Keystroke)
+                        .collect(),
+                );
+            })
+        });
+        cx.simulate_keystrokes("2 @ q");
+        cx.assert_state("one;\ntwoˇ;", Mode::Normal);
+    }
+
+    #[gpui::test]
+    async fn test_recording_keystrokes(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        cx.set_state("ˇone", Mode::Normal);
+        cx.simulate_keystrokes("q a shift-a ! escape q");
+        cx.assert_state("oneˇ!", Mode::Normal);
+        let keystrokes = cx.update(|cx| {
+            serialize_keystrokes(&Vim::read(cx).workspace_state.recorded_keystrokes[&'a'])
+        });
+        assert_eq!(keystrokes, "shift-a ! escape");
+    }
+}
@@ -1,12 +1,12 @@
-use std::{fmt::Display, ops::Range, sync::Arc};
+use std::{fmt::Display, ops::Range, path::PathBuf, sync::Arc};
 
 use crate::normal::repeat::Replayer;
 use crate::surrounds::SurroundsType;
 use crate::{motion::Motion, object::Object};
 use collections::HashMap;
 use editor::{Anchor, ClipboardSelection};
-use gpui::{Action, ClipboardItem, KeyContext};
-use language::{CursorShape, Selection, TransactionId};
+use gpui::{Action, ClipboardItem, KeyContext, Keystroke};
+use language::{CursorShape, Point, Selection, TransactionId};
 use serde::{Deserialize, Serialize};
 use ui::SharedString;
 use workspace::searchable::Direction;
@@ -87,6 +87,8 @@ pub struct EditorState {
     pub replacements: Vec<(Range<editor::Anchor>, String)>,
 
     pub marks: HashMap<String, Vec<Anchor>>,
+    /// Whether the marks saved by previous sessions have been loaded for this editor.
+    pub marks_restored: bool,
     pub stored_visual_mode: Option<(Mode, Vec<bool>)>,
     pub change_list: Vec<Vec<Anchor>>,
     pub change_list_position: Option<usize>,
@@ -175,6 +177,11 @@ pub struct WorkspaceState {
     pub last_yank: Option<SharedString>,
     pub registers: HashMap<char, Register>,
     pub recordings: HashMap<char, Vec<ReplayableAction>>,
+    /// The keystrokes typed while recording each register, which is what gets
+    /// saved across restarts.
+    pub recorded_keystrokes: HashMap<char, Vec<Keystroke>>,
+    /// Uppercase marks, which can jump across files.
+    pub global_marks: HashMap<String, (PathBuf, Point)>,
 }
 
 #[derive(Debug)]
@@ -184,6 +191,8 @@ pub enum ReplayableAction {
         text: Arc<str>,
         utf16_range_to_replace: Option<Range<isize>>,
     },
+    /// A keystroke from a recording restored from a previous session.
+    Keystroke(Keystroke),
 }
 
 impl Clone for ReplayableAction {
@@ -197,6 +206,7 @@ impl Clone for ReplayableAction {
                 text: text.clone(),
                 utf16_range_to_replace: utf16_range_to_replace.clone(),
             },
+            Self::Keystroke(keystroke) => Self::Keystroke(keystroke.clone()),
         }
     }
 }
@@ -58,6 +58,13 @@ impl VimTestContext {
         cx.update(|cx| {
             SettingsStore::update_global(cx, |store, cx| {
                 store.update_user_settings::<VimModeSetting>(cx, |s| *s = Some(enabled));
+                // Keep registers and marks from leaking between tests through the database.
+                store.update_user_settings::<VimSettings>(cx, |s| {
+                    s.persistence = Some(PersistenceSettingsContent {
+                        enabled: Some(false),
+                        ..Default::default()
+                    })
+                });
             });
             settings::KeymapFile::load_asset("keymaps/default-macos.json", cx).unwrap();
             if enabled {
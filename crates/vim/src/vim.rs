@@ -6,11 +6,13 @@ mod test;
 mod change_list;
 mod command;
 mod editor_events;
+mod ex_command;
 mod insert;
 mod mode_indicator;
 mod motion;
 mod normal;
 mod object;
+mod persistence;
 mod replace;
 mod state;
 mod surrounds;
@@ -33,8 +35,11 @@ pub use mode_indicator::ModeIndicator;
 use motion::Motion;
 use normal::{
     mark::create_visual_marks,
+    mark::restore_marks,
     normal_replace,
-    repeat::{observe_action, observe_insertion, record_register, replay_register},
+    repeat::{
+        observe_action, observe_insertion, observe_keystroke, record_register, replay_register,
+    },
 };
 use replace::multi_replace;
 use schemars::JsonSchema;
@@ -45,6 +50,7 @@ use state::{EditorState, Mode, Operator, RecordedSelection, Register, WorkspaceS
 use std::{ops::Range, sync::Arc};
 use surrounds::{add_surrounds, change_surrounds, delete_surrounds, SurroundsType};
 use ui::BorrowAppContext;
+use util::ResultExt;
 use visual::{visual_block_motion, visual_replace};
 use workspace::{self, Workspace};
 
@@ -164,6 +170,7 @@ fn register(workspace: &mut Workspace, cx: &mut ViewContext<Workspace>) {
     insert::register(workspace, cx);
     motion::register(workspace, cx);
     command::register(workspace, cx);
+    ex_command::register(workspace, cx);
     replace::register(workspace, cx);
     object::register(workspace, cx);
     visual::register(workspace, cx);
@@ -173,6 +180,8 @@ fn register(workspace: &mut Workspace, cx: &mut ViewContext<Workspace>) {
 /// Called whenever an keystroke is typed so vim can observe all actions
 /// and keystrokes accordingly.
 fn observe_keystrokes(keystroke_event: &KeystrokeEvent, cx: &mut WindowContext) {
+    observe_keystroke(&keystroke_event.keystroke, cx);
+
     if let Some(action) = keystroke_event
         .action
         .as_ref()
@@ -243,6 +252,7 @@ impl Vim {
         }
 
         self.active_editor = Some(editor.clone().downgrade());
+        restore_marks(self, cx);
         self.editor_subscription = Some(cx.subscribe(&editor, |editor, event, cx| match event {
             EditorEvent::SelectionsChanged { local: true } => {
                 if editor.read(cx).leader_peer_id().is_none() {
@@ -530,6 +540,8 @@ impl Vim {
         linewise: bool,
         cx: &mut ViewContext<Editor>,
     ) {
+        // The registers written to, which are saved to the database afterwards
+        let mut changed_registers = vec!['"'];
         if let Some(register) = register {
             let lower = register.to_lowercase().next().unwrap_or(register);
             if lower != register {
@@ -539,6 +551,7 @@ impl Vim {
                 current.clipboard_selections.take();
                 let yanked = current.clone();
                 self.workspace_state.registers.insert('"', yanked);
+                changed_registers.push(lower);
             } else {
                 self.workspace_state.registers.insert('"', content.clone());
                 match lower {
@@ -555,9 +568,11 @@ impl Vim {
                     '"' => {
                         self.workspace_state.registers.insert('0', content.clone());
                         self.workspace_state.registers.insert('"', content);
+                        changed_registers.push('0');
                     }
                     _ => {
                         self.workspace_state.registers.insert(lower, content);
+                        changed_registers.push(lower);
                     }
                 }
             }
@@ -577,14 +592,17 @@ impl Vim {
             self.workspace_state.registers.insert('"', content.clone());
             if is_yank {
                 self.workspace_state.registers.insert('0', content);
+                changed_registers.push('0');
             } else {
                 let contains_newline = content.text.contains('\n');
                 if !contains_newline {
                     self.workspace_state.registers.insert('-', content.clone());
+                    changed_registers.push('-');
                 }
                 if linewise || contains_newline {
                     let mut content = content;
                     for i in '1'..'8' {
+                        changed_registers.push(i);
                         if let Some(moved) = self.workspace_state.registers.insert(i, content) {
                             content = moved;
                         } else {
@@ -594,6 +612,7 @@ impl Vim {
                 }
             }
         }
+        persistence::save_registers(&changed_registers, &self.workspace_state.registers, cx);
     }
 
     fn read_register(
@@ -925,6 +944,9 @@ impl Vim {
         }
 
         self.enabled = true;
+        if VimSettings::get_global(cx).persistence.enabled {
+            persistence::restore(&mut self.workspace_state).log_err();
+        }
         CommandPaletteFilter::update_global(cx, |filter, _| {
             filter.show_namespace(Self::NAMESPACE);
         });
@@ -1034,6 +1056,14 @@ struct VimSettings {
     pub use_system_clipboard: UseSystemClipboard,
     pub use_multiline_find: bool,
     pub use_smartcase_find: bool,
+    pub persistence: PersistenceSettings,
+}
+
+#[derive(Clone, Copy, Deserialize)]
+struct PersistenceSettings {
+    pub enabled: bool,
+    pub max_register_lines: usize,
+    pub max_marks: usize,
 }
 
 #[derive(Clone, Default, Serialize, Deserialize, JsonSchema)]
@@ -1041,6 +1071,24 @@ struct VimSettingsContent {
     pub use_system_clipboard: Option<UseSystemClipboard>,
     pub use_multiline_find: Option<bool>,
     pub use_smartcase_find: Option<bool>,
+    /// Which registers, marks and macro recordings are saved across restarts.
+    pub persistence: Option<PersistenceSettingsContent>,
+}
+
+#[derive(Clone, Default, Serialize, Deserialize, JsonSchema)]
+struct PersistenceSettingsContent {
+    /// Whether to save registers, marks and macro recordings across restarts.
+    ///
+    /// Default: true
+    pub enabled: Option<bool>,
+    /// Registers with more lines than this are not saved.
+    ///
+    /// Default: 1000
+    pub max_register_lines: Option<usize>,
+    /// The maximum number of marks to save, keeping the most recently set ones.
+    ///
+    /// Default: 1000
+    pub max_marks: Option<usize>,
 }
 
 impl Settings for VimSettings {
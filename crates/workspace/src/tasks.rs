@@ -32,6 +32,22 @@ pub fn schedule_resolved_task(
     omit_history: bool,
     cx: &mut ViewContext<'_, Workspace>,
 ) {
+    let has_unresolved_dependencies = resolved_task.resolved.as_ref().map_or(false, |resolved| {
+        resolved.depends_on.is_empty() && !resolved_task.original_task().depends_on.is_empty()
+    });
+    if has_unresolved_dependencies {
+        let resolved_dependencies = workspace
+            .project()
+            .read(cx)
+            .task_inventory()
+            .read(cx)
+            .resolve_task_dependencies(&task_source_kind, &mut resolved_task);
+        if let Err(error) = resolved_dependencies {
+            cx.defer(move |workspace, cx| workspace.show_error(&error, cx));
+            return;
+        }
+    }
+
     if let Some(spawn_in_terminal) = resolved_task.resolved.take() {
         if !omit_history {
             resolved_task.resolved = Some(spawn_in_terminal.clone());
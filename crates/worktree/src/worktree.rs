@@ -21,7 +21,8 @@ use futures::{
 };
 use fuzzy::CharBag;
 use git::{
-    repository::{GitFileStatus, GitRepository, RepoPath},
+    remote::AheadBehind,
+    repository::{DiffBase, GitFileStatus, GitRepository, RepoPath},
     status::GitStatus,
     DOT_GIT, GITIGNORE,
 };
@@ -120,6 +121,8 @@ pub struct LocalWorktree {
     next_entry_id: Arc<AtomicUsize>,
     settings: WorktreeSettings,
     share_private_files: bool,
+    /// The text that the buffers of the worktree's files are diffed against.
+    git_diff_base: DiffBase,
 }
 
 struct ScanRequest {
@@ -189,6 +192,9 @@ pub struct RepositoryEntry {
     ///     work_directory: pointing to "" entry
     ///     location_in_repo: Some("my_sub_folder_1/project_root")
     pub(crate) location_in_repo: Option<Arc<Path>>,
+    /// How many commits the branch is ahead and behind its upstream.
+    /// Only known for local repositories.
+    pub(crate) ahead_behind: Option<AheadBehind>,
 }
 
 impl RepositoryEntry {
@@ -196,6 +202,10 @@ impl RepositoryEntry {
         self.branch.clone()
     }
 
+    pub fn ahead_behind(&self) -> Option<AheadBehind> {
+        self.ahead_behind
+    }
+
     pub fn work_directory_id(&self) -> ProjectEntryId {
         *self.work_directory
     }
@@ -234,6 +244,17 @@ impl RepositoryEntry {
             relativize_path(path)
         }
     }
+
+    /// The inverse of [`Self::relativize`], returns the path of the given repository path
+    /// in the worktree or `None` if it's located outside of the worktree.
+    pub fn unrelativize(&self, worktree: &Snapshot, path: &RepoPath) -> Option<Arc<Path>> {
+        let entry = worktree.entry_for_id(self.work_directory.0)?;
+        let path = match &self.location_in_repo {
+            Some(location_in_repo) => path.strip_prefix(location_in_repo).ok()?,
+            None => path.0.as_path(),
+        };
+        Some(entry.path.join(path).into())
+    }
 }
 
 impl From<&RepositoryEntry> for proto::RepositoryEntry {
@@ -432,6 +453,7 @@ impl Worktree {
             let (path_prefixes_to_scan_tx, path_prefixes_to_scan_rx) = channel::unbounded();
             let mut worktree = LocalWorktree {
                 share_private_files: false,
+                git_diff_base: DiffBase::default(),
                 next_entry_id,
                 snapshot,
                 is_scanning: watch::channel_with(true),
@@ -1243,6 +1265,7 @@ impl LocalWorktree {
         let fs = self.fs.clone();
         let entry = self.refresh_entry(path.clone(), None, cx);
         let is_private = self.is_path_private(path.as_ref());
+        let git_diff_base = self.git_diff_base.clone();
 
         cx.spawn(|this, mut cx| async move {
             let abs_path = abs_path?;
@@ -1268,7 +1291,7 @@ impl LocalWorktree {
                                 if metadata.is_dir || metadata.is_symlink {
                                     None
                                 } else {
-                                    git_repo.load_index_text(&repo_path)
+                                    git_diff_base.load_text(git_repo.as_ref(), &repo_path)
                                 }
                             }
                         }));
@@ -1800,6 +1823,15 @@ impl LocalWorktree {
         self.share_private_files = true;
         self.restart_background_scanners(cx);
     }
+
+    pub fn git_diff_base(&self) -> &DiffBase {
+        &self.git_diff_base
+    }
+
+    /// Sets the diff base of the buffers loaded afterwards.
+    pub fn set_git_diff_base(&mut self, git_diff_base: DiffBase) {
+        self.git_diff_base = git_diff_base;
+    }
 }
 
 impl RemoteWorktree {
@@ -2043,6 +2075,7 @@ impl Snapshot {
                             // the location_in_repo field, since git operations don't happen locally
                             // anyway.
                             location_in_repo: None,
+                            ahead_behind: None,
                         },
                     )
                 }
@@ -2817,6 +2850,7 @@ impl BackgroundScannerState {
                 work_directory: work_dir_id.into(),
                 branch: repository.branch_name().map(Into::into),
                 location_in_repo,
+                ahead_behind: repository.ahead_behind(),
             },
         );
         self.snapshot.git_repositories.insert(
@@ -4325,6 +4359,7 @@ impl BackgroundScanner {
 
                         let repo = &repository.repo_ptr;
                         let branch = repo.branch_name();
+                        let ahead_behind = repo.ahead_behind();
                         repo.reload_index();
 
                         state
@@ -4335,7 +4370,10 @@ impl BackgroundScanner {
                             .snapshot
                             .snapshot
                             .repository_entries
-                            .update(&work_dir, |entry| entry.branch = branch.map(Into::into));
+                            .update(&work_dir, |entry| {
+                                entry.branch = branch.map(Into::into);
+                                entry.ahead_behind = ahead_behind;
+                            });
                         (work_dir, repository.repo_ptr.clone())
                     }
                 };
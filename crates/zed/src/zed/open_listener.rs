@@ -6,6 +6,7 @@ use collections::HashMap;
 use db::kvp::KEY_VALUE_STORE;
 use editor::scroll::Autoscroll;
 use editor::Editor;
+use extension::ExtensionStore;
 use futures::channel::mpsc::{UnboundedReceiver, UnboundedSender};
 use futures::channel::{mpsc, oneshot};
 use futures::{FutureExt, SinkExt, StreamExt};
@@ -413,6 +414,39 @@ pub async fn handle_cli_connection(
                     })
                     .log_err();
             }
+            CliRequest::ReloadDevExtension { path } => {
+                let result = async {
+                    cx.update(|cx| init_ui(app_state, cx))??;
+                    cx.update(|cx| {
+                        ExtensionStore::global(cx).update(cx, |store, cx| {
+                            store.reload_dev_extension(PathBuf::from(&path), cx)
+                        })
+                    })?
+                    .await
+                }
+                .await;
+
+                match result {
+                    Ok(()) => {
+                        responses
+                            .send(CliResponse::Stdout {
+                                message: format!("reloaded dev extension at {path}"),
+                            })
+                            .log_err();
+                        responses.send(CliResponse::Exit { status: 0 }).log_err();
+                    }
+                    Err(error) => {
+                        responses
+                            .send(CliResponse::Stderr {
+                                message: format!(
+                                    "error reloading dev extension at {path}: {error}"
+                                ),
+                            })
+                            .log_err();
+                        responses.send(CliResponse::Exit { status: 1 }).log_err();
+                    }
+                }
+            }
         }
     }
 }